@@ -0,0 +1,59 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Compare checking messages with `icu::validate` to parsing them with
+//! `icu::parse`.
+//!
+//! ```text
+//! cargo bench --bench validate
+//! ```
+
+extern crate message_format;
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use message_format::icu;
+
+const PATTERNS: &[&str] =
+    &["Settings",
+      "Welcome back, {name}!",
+      "You have {count, plural, =0 {no new messages} one {# new message} other {# new messages}}.",
+      "{host} shared {gender, select, female {her} male {his} other {their}} album with you.",
+      "{count, plural, offset:1 =0 {Nobody} =1 {{name}} one {{name} and # other} \
+       other {{name} and # others}} liked this.",
+      "You finished {place, selectordinal, one {#st} two {#nd} few {#rd} other {#th}}.",
+      "{distance, number, ::unit/kilometer unit-width-full-name} in {time, duration, units}",
+      "{count, choice, 0#No files|1#One file|1<{count, number} files}",
+      "{! Shown in the toolbar }Share {count, plural, one {# photo} other {# photos}}"];
+
+/// The time taken to run `f` over every message of `catalog`, as many
+/// times as fit in about a second.
+fn measure<T, F>(catalog: &[String], f: F) -> Duration
+    where F: Fn(&str) -> T
+{
+    let mut rounds = 0;
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(1) {
+        for message in catalog {
+            black_box(f(black_box(message)));
+        }
+        rounds += 1;
+    }
+    start.elapsed() / (rounds * catalog.len() as u32)
+}
+
+fn main() {
+    // A catalog of distinct messages, like that of a large application.
+    let catalog = (0..10_000)
+        .map(|i| format!("{} ({})", PATTERNS[i % PATTERNS.len()], i))
+        .collect::<Vec<_>>();
+    let parse = measure(&catalog, |message| icu::parse(message).is_ok());
+    let validate = measure(&catalog, |message| icu::validate(message).is_ok());
+    println!("parse:    {:>8.2?} per message", parse);
+    println!("validate: {:>8.2?} per message", validate);
+    println!("validate is {:.1}x as fast", parse.as_secs_f64() / validate.as_secs_f64());
+}
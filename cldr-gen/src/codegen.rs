@@ -0,0 +1,152 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Compiling plural rules into Rust.
+
+use message_format::PluralCategory;
+use message_format::plural_rules::syntax::{Condition, Operand, Relation, Rule};
+
+/// Compile the rules for a locale into the body of a function from
+/// `o: &PluralOperands` to a `PluralCategory`. The rules are given by
+/// their category, and must not include `other`.
+pub fn rule_body(rules: &[(PluralCategory, Rule)]) -> String {
+    if rules.is_empty() {
+        return "    Other\n".to_string();
+    }
+    let mut body = String::new();
+    for (index, &(category, ref rule)) in rules.iter().enumerate() {
+        let keyword = if index == 0 { "    if" } else { " else if" };
+        body.push_str(&format!("{} {} {{\n        {}\n    }}",
+                               keyword,
+                               condition(&rule.condition),
+                               category_name(category)));
+    }
+    body.push_str(" else {\n        Other\n    }\n");
+    body
+}
+
+/// The name of a `PluralCategory` variant.
+pub fn category_name(category: PluralCategory) -> String {
+    format!("{:?}", category)
+}
+
+/// Compile a condition into a boolean expression.
+pub fn condition(condition: &Condition) -> String {
+    let and_conditions = condition.0
+        .iter()
+        .map(|relations| {
+            let relations = relations.iter().map(relation).collect::<Vec<_>>();
+            let parenthesize = relations.len() > 1;
+            relations.into_iter()
+                .map(|(expression, is_or)| if is_or && parenthesize {
+                    format!("({})", expression)
+                } else {
+                    expression
+                })
+                .collect::<Vec<_>>()
+                .join(" && ")
+        })
+        .collect::<Vec<_>>();
+    let parenthesize = and_conditions.len() > 1;
+    and_conditions.into_iter()
+        .map(|expression| if parenthesize && expression.contains(" || ") {
+            format!("({})", expression)
+        } else {
+            expression
+        })
+        .collect::<Vec<_>>()
+        .join(" || ")
+}
+
+/// Compile a relation into a boolean expression, also returning whether
+/// it is a disjunction that may need parentheses.
+fn relation(relation: &Relation) -> (String, bool) {
+    let field = match relation.operand {
+        Operand::N => "o.n",
+        Operand::I => "o.i",
+        Operand::V => "o.v",
+        Operand::W => "o.w",
+        Operand::F => "o.f",
+        Operand::T => "o.t",
+        Operand::E => "o.e",
+    };
+    let is_n = relation.operand == Operand::N;
+    let value = |value: u64| if is_n { format!("{}.0", value) } else { value.to_string() };
+    let expression = match relation.modulus {
+        Some(modulus) => format!("{} % {}", field, value(modulus)),
+        None => field.to_string(),
+    };
+
+    let tests = relation.ranges
+        .iter()
+        .map(|&(start, end)| {
+            let (equal, not_equal) = if relation.negated { ("!=", "!") } else { ("==", "") };
+            if start == end {
+                return format!("{} {} {}", expression, equal, value(start));
+            }
+            if is_n {
+                format!("{}in_range({}, {}, {})", not_equal, expression, value(start), value(end))
+            } else if relation.modulus.is_some() {
+                format!("{}({}..={}).contains(&({}))", not_equal, start, end, expression)
+            } else {
+                format!("{}({}..={}).contains(&{})", not_equal, start, end, expression)
+            }
+        })
+        .collect::<Vec<_>>();
+    if relation.negated {
+        (tests.join(" && "), false)
+    } else {
+        let is_or = tests.len() > 1;
+        (tests.join(" || "), is_or)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{category_name, condition, rule_body};
+    use message_format::PluralCategory;
+    use message_format::plural_rules::syntax::parse_rule;
+
+    fn compile(source: &str) -> String {
+        condition(&parse_rule(source).unwrap().condition)
+    }
+
+    #[test]
+    fn relations() {
+        assert_eq!(compile("i = 1 and v = 0"), "o.i == 1 && o.v == 0");
+        assert_eq!(compile("n % 10 = 1 and n % 100 != 11"),
+                   "o.n % 10.0 == 1.0 && o.n % 100.0 != 11.0");
+        assert_eq!(compile("i = 0,1"), "o.i == 0 || o.i == 1");
+        assert_eq!(compile("i % 10 = 2..4"), "(2..=4).contains(&(o.i % 10))");
+        assert_eq!(compile("n = 3..10"), "in_range(o.n, 3.0, 10.0)");
+        assert_eq!(compile("i % 1000000 = 0"), "o.i % 1000000 == 0");
+    }
+
+    #[test]
+    fn negated_lists() {
+        assert_eq!(compile("i % 10 != 4,6"), "o.i % 10 != 4 && o.i % 10 != 6");
+        assert_eq!(compile("n % 100 != 11..19"), "!in_range(o.n % 100.0, 11.0, 19.0)");
+        assert_eq!(compile("e != 0..5"), "!(0..=5).contains(&o.e)");
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(compile("v = 0 and i = 1,2 or n = 3"),
+                   "(o.v == 0 && (o.i == 1 || o.i == 2)) || o.n == 3.0");
+        assert_eq!(compile("n = 1 or t != 0 and i = 0,1"),
+                   "o.n == 1.0 || (o.t != 0 && (o.i == 0 || o.i == 1))");
+        assert_eq!(compile("n = 0 or n = 1"), "o.n == 0.0 || o.n == 1.0");
+    }
+
+    #[test]
+    fn bodies() {
+        assert_eq!(rule_body(&[]), "    Other\n");
+        let one = parse_rule("n = 1").unwrap();
+        assert_eq!(rule_body(&[(PluralCategory::One, one)]),
+                   "    if o.n == 1.0 {\n        One\n    } else {\n        Other\n    }\n");
+        assert_eq!(category_name(PluralCategory::Few), "Few");
+    }
+}
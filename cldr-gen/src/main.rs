@@ -0,0 +1,262 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Generate the built-in plural rules of `message-format` from CLDR.
+//!
+//! This reads the `plurals.json` and `ordinals.json` files from the
+//! `cldr-core` package of [CLDR JSON], compiles each rule into Rust and
+//! writes the `plural_rules/cldr.rs` module of `message-format`. The
+//! conditions and samples for each rule are written as test data.
+//!
+//! Update the files in `data` and then run:
+//!
+//! ```text
+//! cargo run -p cldr-gen
+//! ```
+//!
+//! The input directory and output file can also be given as arguments.
+//!
+//! [CLDR JSON]: https://github.com/unicode-org/cldr-json
+
+extern crate message_format;
+extern crate serde_json;
+
+mod codegen;
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use message_format::PluralCategory;
+use message_format::plural_rules::syntax::{parse_rule, Rule};
+
+/// The license header of the generated module.
+const HEADER: &str = "\
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+";
+
+/// The compiled rules of one type, either cardinal or ordinal.
+struct RuleSet {
+    kind: &'static str,
+    /// The function names for each locale, in lowercase.
+    locales: Vec<(String, String)>,
+    /// The distinct functions.
+    functions: Vec<Function>,
+}
+
+/// A function for the rules of one or more locales.
+struct Function {
+    name: String,
+    body: String,
+    /// The samples for each category, from the first locale to use it.
+    samples: Vec<Samples>,
+}
+
+/// The samples for a category, with its condition so that they can
+/// also be checked against rules loaded at runtime.
+struct Samples {
+    category: PluralCategory,
+    condition: String,
+    values: Vec<String>,
+}
+
+impl Samples {
+    fn new(category: PluralCategory, source: &str, rule: &Rule) -> Self {
+        Samples {
+            category,
+            condition: source.split('@').next().unwrap_or("").trim().to_string(),
+            values: rule.samples().map(str::to_string).collect(),
+        }
+    }
+}
+
+impl RuleSet {
+    fn new(kind: &'static str, json: &serde_json::Value, key: &str) -> Result<Self, String> {
+        let locales = json["supplemental"][key]
+            .as_object()
+            .ok_or_else(|| format!("Missing {}", key))?;
+        let mut set = RuleSet {
+            kind,
+            locales: vec![],
+            functions: vec![],
+        };
+        let mut names = HashMap::new();
+        for (locale, rules) in locales {
+            let rules = rules.as_object().ok_or_else(|| format!("Invalid rules for {}", locale))?;
+            let rule = |category| -> Result<Option<(&str, Rule)>, String> {
+                match rules.get(&format!("pluralRule-count-{}", category)) {
+                    Some(source) => {
+                        let source = source.as_str().ok_or("Invalid rule")?;
+                        let rule = parse_rule(source).map_err(|e| format!("{}: {}", locale, e))?;
+                        Ok(Some((source, rule)))
+                    }
+                    None => Ok(None),
+                }
+            };
+            let mut compiled = vec![];
+            let mut samples = vec![];
+            // The categories are in the order that they're tested, and
+            // `other` isn't compiled as it's used when nothing matches.
+            for &category in &PluralCategory::ALL {
+                if let Some((source, rule)) = rule(category)? {
+                    samples.push(Samples::new(category, source, &rule));
+                    if category != PluralCategory::Other {
+                        compiled.push((category, rule));
+                    }
+                }
+            }
+
+            let body = codegen::rule_body(&compiled);
+            let locale = locale.to_ascii_lowercase();
+            let name = names.entry(body.clone())
+                .or_insert_with(|| {
+                    let name = format!("{}_{}", kind, locale.replace('-', "_"));
+                    set.functions.push(Function {
+                        name: name.clone(),
+                        body,
+                        samples,
+                    });
+                    name
+                })
+                .clone();
+            set.locales.push((locale, name));
+        }
+        set.locales.sort();
+        Ok(set)
+    }
+
+    fn write_table(&self, output: &mut String) {
+        output.push_str(&format!("/// The {} rules, by locale in lowercase.\n", self.kind));
+        output.push_str(&format!("pub(super) const {}_RULES: &[(&str, Rule, Conditions)] = &[\n",
+                                 self.kind.to_uppercase()));
+        for (locale, name) in &self.locales {
+            output.push_str(&format!("    (\"{}\", {}, {}_CONDITIONS),\n",
+                                     locale,
+                                     name,
+                                     name.to_uppercase()));
+        }
+        output.push_str("];\n\n");
+    }
+
+    fn write_functions(&self, output: &mut String) {
+        for function in &self.functions {
+            let operands = if function.body.contains("o.") { "o" } else { "_" };
+            output.push_str(&format!("fn {}({}: &PluralOperands) -> PluralCategory {{\n{}}}\n\n",
+                                     function.name,
+                                     operands,
+                                     function.body));
+            let conditions = function.samples
+                .iter()
+                .filter(|samples| samples.category != PluralCategory::Other)
+                .map(|samples| {
+                    format!("    ({}, \"{}\"),\n",
+                            codegen::category_name(samples.category),
+                            samples.condition)
+                })
+                .collect::<String>();
+            let conditions = if conditions.is_empty() {
+                "&[]".to_string()
+            } else {
+                format!("&[\n{}]", conditions)
+            };
+            output.push_str(&format!("const {}_CONDITIONS: Conditions = {};\n\n",
+                                     function.name.to_uppercase(),
+                                     conditions));
+        }
+    }
+
+    fn write_samples(&self, output: &mut String) {
+        output.push_str(&format!("/// The CLDR samples for each distinct {} rule.\n", self.kind));
+        output.push_str("#[cfg(test)]\n");
+        output.push_str(&format!("pub(super) const {}_SAMPLES: &Samples = &[\n",
+                                 self.kind.to_uppercase()));
+        for function in &self.functions {
+            let locale = function.name[self.kind.len() + 1..].replace('_', "-");
+            output.push_str(&format!("    (\"{}\",\n     &[", locale));
+            for (index, samples) in function.samples.iter().enumerate() {
+                if index > 0 {
+                    output.push_str(",\n       ");
+                }
+                output.push_str(&format!("({},\n        \"{}\",\n        &[",
+                                         codegen::category_name(samples.category),
+                                         samples.condition));
+                let mut line = 10;
+                for (index, value) in samples.values.iter().enumerate() {
+                    let value = format!("\"{}\"", value);
+                    if index > 0 {
+                        output.push(',');
+                        if line + value.len() > 90 {
+                            output.push_str("\n          ");
+                            line = 10;
+                        } else {
+                            output.push(' ');
+                        }
+                    }
+                    line += value.len() + 2;
+                    output.push_str(&value);
+                }
+                output.push_str("])");
+            }
+            output.push_str("]),\n");
+        }
+        output.push_str("];\n\n");
+    }
+}
+
+fn read_json(path: &Path) -> Result<serde_json::Value, String> {
+    let source = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    serde_json::from_str(&source).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn generate(data: &Path) -> Result<String, String> {
+    let plurals = read_json(&data.join("plurals.json"))?;
+    let ordinals = read_json(&data.join("ordinals.json"))?;
+    let version = plurals["supplemental"]["version"]["_cldrVersion"].as_str().unwrap_or("?");
+    let cardinal = RuleSet::new("cardinal", &plurals, "plurals-type-cardinal")?;
+    let ordinal = RuleSet::new("ordinal", &ordinals, "plurals-type-ordinal")?;
+
+    let mut output = String::new();
+    output.push_str(HEADER);
+    output.push_str(&format!("\n// Generated by cldr-gen from CLDR {}. Do not edit.\n\n", version));
+    // The conditions are kept as they are written in CLDR.
+    output.push_str("#![allow(clippy::nonminimal_bool)]\n\n");
+    output.push_str("#[cfg(test)]\n");
+    output.push_str("use super::Samples;\n");
+    output.push_str("use super::{in_range, Conditions, Rule};\n");
+    output.push_str("use {PluralCategory, PluralOperands};\n");
+    output.push_str("use PluralCategory::*;\n\n");
+    cardinal.write_table(&mut output);
+    ordinal.write_table(&mut output);
+    cardinal.write_functions(&mut output);
+    ordinal.write_functions(&mut output);
+    cardinal.write_samples(&mut output);
+    ordinal.write_samples(&mut output);
+    output.pop();
+    Ok(output)
+}
+
+fn main() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut args = env::args_os().skip(1);
+    let data = args.next().map_or_else(|| root.join("data"), PathBuf::from);
+    let output = args.next()
+        .map_or_else(|| root.join("../src/plural_rules/cldr.rs"), PathBuf::from);
+    match generate(&data).and_then(|code| {
+        fs::write(&output, code).map_err(|e| format!("{}: {}", output.display(), e))
+    }) {
+        Ok(()) => println!("Wrote {}", output.display()),
+        Err(error) => {
+            eprintln!("cldr-gen: {}", error);
+            process::exit(1);
+        }
+    }
+}
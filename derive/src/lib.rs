@@ -0,0 +1,131 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `#[derive(MessageArgs)]` for structs of message arguments.
+//!
+//! This is re-exported by `message-format` with its `derive` feature,
+//! so programs don't depend on this crate themselves.
+
+#![deny(missing_docs)]
+
+extern crate proc_macro;
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, LitStr};
+
+/// Implement `MessageArgs` for a struct, with an argument for each of
+/// its fields.
+///
+/// Each argument is named by its field, and its value is from the
+/// `IntoValue` of the field, so an `Option` field that is `None` is a
+/// missing argument. A field can be named differently with
+/// `#[msg(rename = "name")]`, or left out with `#[msg(skip)]`:
+///
+/// ```
+/// extern crate message_format;
+///
+/// use message_format::{icu, Context, MessageArgs};
+///
+/// #[derive(MessageArgs)]
+/// struct Inbox {
+///     name: String,
+///     #[msg(rename = "count")]
+///     unread: u32,
+///     #[msg(skip)]
+///     id: u64,
+/// }
+///
+/// let m = icu::parse("{name} has {count, plural, one {# message} other {# messages}}")
+///     .unwrap();
+/// let inbox = Inbox { name: "Ana".to_string(), unread: 3, id: 7 };
+/// assert_eq!(inbox.with_args(|args| Context::default().format(&m, args)),
+///            "Ana has 3 messages");
+/// ```
+#[proc_macro_derive(MessageArgs, attributes(msg))]
+pub fn derive_message_args(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(expanded) => expanded.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match input.data {
+        Data::Struct(ref data) => {
+            match data.fields {
+                Fields::Named(ref fields) => fields.named.iter().collect(),
+                Fields::Unit => vec![],
+                Fields::Unnamed(_) => return Err(unsupported(input)),
+            }
+        }
+        _ => return Err(unsupported(input)),
+    };
+    let mut links = vec![];
+    for (index, field) in fields.iter().enumerate() {
+        let ident = match field.ident {
+            Some(ref ident) => ident,
+            None => continue,
+        };
+        let mut name = ident.to_string();
+        let mut skip = false;
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("msg")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    name = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("Expected `rename = \"...\"` or `skip`."))
+                }
+            })?;
+        }
+        if skip {
+            continue;
+        }
+        let arg = format_ident!("__arg{}", index);
+        links.push(quote! {
+            let #arg;
+            let __args = match ::message_format::IntoValue::to_value(&self.#ident) {
+                ::message_format::__derive::Option::Some(value) => {
+                    #arg = ::message_format::Args { name: #name, value, prev: __args };
+                    ::message_format::__derive::Option::Some(&#arg)
+                }
+                ::message_format::__derive::Option::None => __args,
+            };
+        });
+    }
+    let ty = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::message_format::MessageArgs for #ty #ty_generics #where_clause {
+            fn with_args<__R, __F>(&self, f: __F) -> __R
+                where __F: ::message_format::__derive::FnOnce(
+                               ::message_format::__derive::Option<&::message_format::Args>
+                           ) -> __R
+            {
+                let __args: ::message_format::__derive::Option<&::message_format::Args> =
+                    ::message_format::__derive::Option::None;
+                #(#links)*
+                f(__args)
+            }
+        }
+    })
+}
+
+/// The error for deriving `MessageArgs` for anything other than a
+/// struct with named fields or a unit struct.
+fn unsupported(input: &DeriveInput) -> syn::Error {
+    syn::Error::new_spanned(&input.ident,
+                            "MessageArgs can only be derived for structs with named fields.")
+}
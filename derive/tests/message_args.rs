@@ -0,0 +1,73 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Derive `MessageArgs` for structs of message arguments.
+
+extern crate message_format;
+
+use message_format::{icu, Context, MessageArgs, Value};
+
+#[derive(MessageArgs)]
+struct Upload<'a> {
+    user: &'a str,
+    #[msg(rename = "count")]
+    files: u32,
+    folder: Option<String>,
+    size: Value<'a>,
+    #[msg(skip)]
+    #[allow(dead_code)]
+    id: u64,
+}
+
+#[derive(MessageArgs)]
+struct Empty;
+
+#[test]
+fn fields_are_arguments() {
+    let ctx = Context::default();
+    let m = icu::parse("{user} uploaded {count, plural, one {# file} other {# files}} \
+                        ({size, number} KB) to {folder}")
+        .unwrap();
+    let mut upload = Upload {
+        user: "Ana",
+        files: 2,
+        folder: Some("Photos".to_string()),
+        size: Value::Number(1536),
+        id: 7,
+    };
+    assert_eq!(upload.with_args(|args| ctx.format(&m, args)),
+               "Ana uploaded 2 files (1,536 KB) to Photos");
+    assert!(upload.with_args(|args| args.unwrap().get("id").is_none()));
+    // A field that is `None` is a missing argument.
+    upload.folder = None;
+    assert_eq!(upload.with_args(|args| ctx.format_with_unused(&m, args)),
+               ("Ana uploaded 2 files (1,536 KB) to ".to_string(), vec![]));
+    assert!(Empty.with_args(|args| args.is_none()));
+}
+
+#[test]
+fn fields_match_variables() {
+    let m = icu::parse("{user} uploaded {count, number} files ({size} KB) to {folder}").unwrap();
+    let upload = Upload {
+        user: "Ana",
+        files: 2,
+        folder: Some("Photos".to_string()),
+        size: Value::Number(0),
+        id: 7,
+    };
+    let mut names = upload.with_args(|mut args| {
+        let mut names = vec![];
+        while let Some(arg) = args {
+            names.push(arg.name.to_string());
+            args = arg.prev;
+        }
+        names
+    });
+    names.sort();
+    let mut variables = m.variables().into_iter().map(|v| v.name).collect::<Vec<_>>();
+    variables.sort();
+    assert_eq!(names, variables);
+}
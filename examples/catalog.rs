@@ -0,0 +1,72 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Format the messages in a JSON catalog with their sample arguments.
+//!
+//! ```text
+//! cargo run --example catalog [tests/fixtures/catalog.json]
+//! ```
+
+extern crate message_format;
+extern crate serde_json;
+
+use std::env;
+use std::fs;
+use std::process;
+
+use message_format::{icu, AsValue, Args, Context, Locale, Message, Value};
+use serde_json::Value as Json;
+
+fn value(json: &Json) -> Option<Value<'_>> {
+    match *json {
+        Json::String(ref value) => Some(Value::Str(value)),
+        Json::Number(ref value) => match value.as_i64() {
+            Some(value) => Some(Value::Number(value)),
+            None => value.as_f64().map(|value| value.as_formattable()),
+        },
+        _ => None,
+    }
+}
+
+fn format(ctx: &Context, message: &Message, args: &[(&str, Value)], prev: Option<&Args>) -> String {
+    match args.split_first() {
+        Some((&(name, value), rest)) => {
+            format(ctx, message, rest, Some(&Args { name, value, prev }))
+        }
+        None => ctx.format(message, prev),
+    }
+}
+
+fn run(path: &str) -> Result<(), String> {
+    let source = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let catalog: Json = serde_json::from_str(&source).map_err(|e| format!("{}: {}", path, e))?;
+    let locale = Locale::new(catalog["locale"].as_str().unwrap_or("en-US"))
+        .map_err(|e| e.to_string())?;
+    let ctx = Context::new(locale, None);
+    let messages = catalog["messages"].as_object().ok_or("Missing messages")?;
+    for (id, entry) in messages {
+        let pattern = entry["pattern"].as_str().ok_or_else(|| format!("{}: Missing pattern", id))?;
+        let message = icu::parse(pattern).map_err(|e| format!("{}: {}", id, e))?;
+        for case in entry["cases"].as_array().into_iter().flatten() {
+            let args = case["args"]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter_map(|(name, json)| value(json).map(|value| (name.as_str(), value)))
+                .collect::<Vec<_>>();
+            println!("{}: {}", id, format(&ctx, &message, &args, None));
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let path = env::args().nth(1).unwrap_or_else(|| "tests/fixtures/catalog.json".to_string());
+    if let Err(error) = run(&path) {
+        eprintln!("catalog: {}", error);
+        process::exit(1);
+    }
+}
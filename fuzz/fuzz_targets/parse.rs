@@ -0,0 +1,40 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Parse arbitrary input as an ICU message, and format the messages
+//! that parse, which must never panic.
+
+#![no_main]
+
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate message_format;
+
+use message_format::{arg, icu, Context, DecimalStr, Value};
+
+fuzz_target!(|data: &[u8]| {
+    let source = match ::std::str::from_utf8(data) {
+        Ok(source) => source,
+        Err(_) => return,
+    };
+    let message = match icu::parse(source) {
+        Ok(message) => message,
+        Err(_) => return,
+    };
+    let ctx = Context::default();
+    let values = [Value::Number(0),
+                  Value::Number(-7),
+                  Value::Number(i64::MIN),
+                  Value::BigInt(i128::MAX),
+                  Value::Decimal { value: 1.5, fraction_digits: 2 },
+                  Value::DecimalStr(DecimalStr::new("-0.001").unwrap()),
+                  Value::Str("other")];
+    for &value in &values {
+        let _ = ctx.format(&message, Some(&arg("n", value)));
+    }
+    let _ = ctx.format(&message, None);
+    let _ = message.variables();
+});
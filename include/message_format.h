@@ -0,0 +1,88 @@
+/*
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+/*
+ * The C API of message-format, from its `ffi` feature.
+ *
+ * Strings are UTF-8 and terminated by NUL. Strings that are returned,
+ * including the messages of errors, are freed with mf_string_free.
+ */
+
+#ifndef MESSAGE_FORMAT_H
+#define MESSAGE_FORMAT_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum MfErrorCode {
+    MF_OK = 0,
+    MF_NULL_ARGUMENT = 1,
+    MF_INVALID_UTF8 = 2,
+    MF_PARSE = 3,
+    MF_FORMAT = 4,
+    MF_PANIC = 5,
+    MF_INVALID_ARGUMENT = 6,
+} MfErrorCode;
+
+/* The message is NULL when the code is MF_OK. It is overwritten by each
+ * call that is given the error, so it must be freed first. */
+typedef struct MfError {
+    MfErrorCode code;
+    char *message;
+} MfError;
+
+typedef enum MfArgKind {
+    MF_ARG_STRING = 0,
+    MF_ARG_INT64 = 1,
+    MF_ARG_DOUBLE = 2,
+} MfArgKind;
+
+/* The kind is an MfArgKind. A kind that isn't one of them is an
+ * MF_INVALID_ARGUMENT. */
+typedef struct MfArg {
+    const char *name;
+    uint32_t kind;
+    union {
+        const char *string;
+        int64_t int64;
+        double dbl;
+    } value;
+} MfArg;
+
+/* A parsed message. */
+typedef struct MfMessage MfMessage;
+
+/* Parse an ICU message, to be formatted for en-US. This returns NULL if
+ * there is an error. */
+MfMessage *mf_parse(const char *source, MfError *error);
+
+/* Parse an ICU message, to be formatted for a locale like "fr-CA", or
+ * for en-US if it is NULL. This returns NULL if there is an error. */
+MfMessage *mf_parse_with_locale(const char *source, const char *locale, MfError *error);
+
+/* Format a message with len arguments, and set out to the result, or to
+ * NULL if there is an error. */
+MfErrorCode mf_format(const MfMessage *message,
+                      const MfArg *args,
+                      size_t len,
+                      char **out,
+                      MfError *error);
+
+void mf_message_free(MfMessage *message);
+
+void mf_string_free(char *string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
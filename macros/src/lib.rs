@@ -0,0 +1,119 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Message bundles that are checked when they are compiled.
+//!
+//! [`include_messages!`] embeds a JSON resource of ICU messages in a
+//! program, as for [`MessageBundle::from_json_str`], and parses each
+//! message when the program is compiled, so an invalid message is a
+//! build failure rather than an error at runtime.
+//!
+//! This is a separate crate because a procedural macro uses
+//! `message-format` to parse the messages, so `message-format` can't
+//! depend on it. Programs that use the macro depend on both crates.
+//!
+//! [`include_messages!`]: macro.include_messages.html
+//! [`MessageBundle::from_json_str`]:
+//! ../message_format/struct.MessageBundle.html#method.from_json_str
+
+#![deny(missing_docs)]
+
+extern crate message_format;
+extern crate proc_macro;
+extern crate quote;
+extern crate syn;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use message_format::{Context, MessageBundle};
+use proc_macro::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::{Expr, LitStr, Token};
+
+/// The arguments of `include_messages!`: a path and an optional
+/// `Context`.
+struct Input {
+    path: LitStr,
+    context: Option<Expr>,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let mut context = None;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            context = Some(input.parse()?);
+            input.parse::<Option<Token![,]>>()?;
+        }
+        Ok(Input { path, context })
+    }
+}
+
+/// Embed a JSON resource of messages as a `MessageBundle`, checking
+/// every message when the program is compiled.
+///
+/// The path is relative to the directory of the `Cargo.toml` of the
+/// crate being compiled. The resource is in the format read by
+/// `MessageBundle::from_json_str`, and each error in it is reported as
+/// a compile error, with the key of the message and the offset of the
+/// error within it. The resource is embedded with `include_str!`, so
+/// the crate is rebuilt when it changes.
+///
+/// The expression is a `MessageBundle`, which formats its messages with
+/// `Context::default()`, or with the `Context` given as a second
+/// argument:
+///
+/// ```ignore
+/// extern crate message_format;
+/// #[macro_use]
+/// extern crate message_format_macros;
+///
+/// use message_format::{arg, Context, Locale};
+///
+/// let en = include_messages!("locales/en.json");
+/// let de = include_messages!("locales/de.json", Context::new(Locale::new("de").unwrap(), None));
+/// assert_eq!(en.format("greeting", Some(&arg("name", "Jo"))).unwrap(), "Hello, Jo!");
+/// ```
+#[proc_macro]
+pub fn include_messages(input: TokenStream) -> TokenStream {
+    let Input { path, context } = syn::parse_macro_input!(input as Input);
+    let root = env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string());
+    let full_path = Path::new(&root).join(path.value());
+    let json = match fs::read_to_string(&full_path) {
+        Ok(json) => json,
+        Err(e) => {
+            let message = format!("Unable to read '{}': {}", full_path.display(), e);
+            return compile_errors(&path, &[message]);
+        }
+    };
+    if let Err(errors) = MessageBundle::from_json_str(Context::default(), &json) {
+        let messages = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+        return compile_errors(&path, &messages);
+    }
+    let full_path = full_path.to_string_lossy();
+    let context = match context {
+        Some(context) => quote!(#context),
+        None => quote!(::message_format::Context::default()),
+    };
+    let expanded = quote! {
+        ::message_format::MessageBundle::from_json_str(#context, include_str!(#full_path))
+            .unwrap_or_else(|_| unreachable!("The messages were checked when they were compiled."))
+    };
+    expanded.into()
+}
+
+/// An expression that fails to compile with each of the `messages`, at
+/// the `path` argument. `compile_error!` is used without a path so that
+/// it works in any edition.
+fn compile_errors(path: &LitStr, messages: &[String]) -> TokenStream {
+    let errors = messages.iter().map(|message| {
+        quote_spanned!(path.span()=> compile_error!(#message);)
+    });
+    quote!({ #(#errors)* }).into()
+}
@@ -0,0 +1,33 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Embed message bundles that are checked when they are compiled.
+
+extern crate message_format;
+#[macro_use]
+extern crate message_format_macros;
+
+use message_format::{arg, Context, Locale};
+
+#[test]
+fn it_works() {
+    let bundle = include_messages!("tests/fixtures/messages_en.json");
+    assert_eq!(bundle.keys().collect::<Vec<_>>(), ["app.title", "greeting", "library.photos"]);
+    assert_eq!(bundle.format("app.title", None).unwrap(), "Photo Library");
+    assert_eq!(bundle.format("greeting", Some(&arg("name", "Ana"))).unwrap(),
+               "Welcome back, Ana!");
+    assert_eq!(bundle.format("library.photos", Some(&arg("count", 1))).unwrap(), "1 photo");
+    assert_eq!(bundle.context().locale().to_string(), "en-US");
+}
+
+#[test]
+fn with_context() {
+    let bundle = include_messages!("tests/fixtures/messages_de.json",
+                                   Context::new(Locale::new("de").unwrap(), None));
+    assert_eq!(bundle.context().locale().language(), "de");
+    assert_eq!(bundle.format("library.photos", Some(&arg("count", 0))).unwrap(), "Keine Fotos");
+    assert_eq!(bundle.format("library.photos", Some(&arg("count", 3))).unwrap(), "3 Fotos");
+}
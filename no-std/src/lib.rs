@@ -0,0 +1,53 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Use `message-format` from a `no_std` crate, with only `alloc`.
+//!
+//! This depends on `message-format` without its default `std` feature,
+//! so building it on its own checks that the formatter doesn't need
+//! `std`:
+//!
+//! ```text
+//! cargo build -p message-format-no-std
+//! cargo test -p message-format-no-std
+//! ```
+//!
+//! Building the whole workspace enables `std` for every member, as the
+//! features of a dependency are unified.
+
+#![cfg_attr(not(test), no_std)]
+#![deny(missing_docs)]
+
+extern crate alloc;
+#[macro_use]
+extern crate message_format;
+
+use alloc::string::String;
+use message_format::{icu, Context, Locale};
+
+/// Format how many files there are in `locale`, as a plural message.
+pub fn files(locale: &str, count: i64) -> Result<String, &'static str> {
+    let message = icu::parse("{count, plural, one {# file} other {# files}}")
+        .map_err(|_| "The message is not valid.")?;
+    let locale = Locale::new(locale).map_err(|_| "The locale is not valid.")?;
+    let ctx = Context::new(locale, None);
+    Ok(format_message!(ctx, &message, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::files;
+
+    #[test]
+    fn plural() {
+        assert_eq!(files("en", 1), Ok("1 file".into()));
+        assert_eq!(files("en", 0), Ok("0 files".into()));
+        // In French, 0 and 1 are both one.
+        assert_eq!(files("fr", 0), Ok("0 file".into()));
+        assert_eq!(files("fr", 1500), Ok("1500 files".into()));
+        assert!(files("en_US", 1).is_err());
+    }
+}
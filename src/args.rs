@@ -4,6 +4,11 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[cfg(any(feature = "ffi", feature = "serde_json"))]
+use std::cell::OnceCell;
+#[cfg(any(feature = "ffi", feature = "serde_json"))]
+use std::vec::Vec;
+
 use super::{AsValue, Value};
 
 /// Holds the arguments being used to format a [`Message`].
@@ -38,7 +43,7 @@ pub struct Args<'a> {
 /// ```
 pub fn arg<'a, T: 'a + AsValue<'a>>(name: &'a str, value: T) -> Args<'a> {
     Args {
-        name: name,
+        name,
         value: value.as_formattable(),
         prev: None,
     }
@@ -64,7 +69,7 @@ impl<'a> Args<'a> {
         where Self: Sized
     {
         Args {
-            name: name,
+            name,
             value: value.as_formattable(),
             prev: Some(self),
         }
@@ -79,13 +84,14 @@ impl<'a> Args<'a> {
     /// let arg = args.get("count").unwrap();
     /// ```
     pub fn get(&'a self, name: &str) -> Option<&'a Args<'a>> {
-        if self.name == name {
-            Some(self)
-        } else if let Some(prev) = self.prev {
-            prev.get(name)
-        } else {
-            None
+        let mut args = Some(self);
+        while let Some(arg) = args {
+            if arg.name == name {
+                return Some(arg);
+            }
+            args = arg.prev;
         }
+        None
     }
 
     /// Retrieve the [`Value`] wrapper around the argument value.
@@ -108,6 +114,29 @@ impl<'a> Args<'a> {
     }
 }
 
+/// Link an argument for each of `entries` onto `prev`, and call `f`
+/// with the last of them. The arguments are linked within a vector
+/// rather than on the stack, so that there can be any number of them.
+#[cfg(any(feature = "ffi", feature = "serde_json"))]
+pub(crate) fn chain<'b, N, R, F>(entries: &'b [(N, Value<'b>)],
+                                 prev: Option<&'b Args<'b>>,
+                                 f: F)
+                                 -> R
+    where N: AsRef<str>,
+          F: FnOnce(Option<&Args>) -> R
+{
+    let links: Vec<OnceCell<Args>> = entries.iter().map(|_| OnceCell::new()).collect();
+    for (index, (name, value)) in entries.iter().enumerate() {
+        let args = Args {
+            name: name.as_ref(),
+            value: *value,
+            prev: if index == 0 { prev } else { links[index - 1].get() },
+        };
+        let _ = links[index].set(args);
+    }
+    f(links.last().map_or(prev, |last| last.get()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -127,4 +156,22 @@ mod tests {
         assert_eq!(args.get("count").unwrap().value(), &Value::Number(3));
         assert_eq!(format!("{}", args.get("count").unwrap().value()), "3");
     }
+
+    #[cfg(any(feature = "ffi", feature = "serde_json"))]
+    #[test]
+    fn chain_many() {
+        let names = (0..100_000).map(|i| i.to_string()).collect::<Vec<_>>();
+        let entries = names.iter()
+            .enumerate()
+            .map(|(i, name)| (name, Value::Number(i as i64)))
+            .collect::<Vec<_>>();
+        let prev = arg("prev", "x");
+        let found = chain(&entries, Some(&prev), |args| {
+            let args = args.unwrap();
+            assert_eq!(args.name, "99999");
+            ["0", "prev"].map(|name| args.get(name).unwrap().value().to_string())
+        });
+        assert_eq!(found, ["0", "x"]);
+        assert!(chain(&entries[..0], None, |args| args.is_none()));
+    }
 }
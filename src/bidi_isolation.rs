@@ -0,0 +1,109 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use {Locale, Value};
+
+/// Whether argument values are wrapped in the Unicode bidi isolation
+/// characters, FIRST STRONG ISOLATE (`U+2068`) and POP DIRECTIONAL
+/// ISOLATE (`U+2069`), when they are inserted by a `{name}` format.
+///
+/// Without them, a value written in the other direction from the
+/// message, like a Hebrew name in an English message or a Latin file
+/// name in an Arabic one, can reorder the text around it when it is
+/// displayed.
+///
+/// ```
+/// use message_format::{arg, icu, BidiIsolation, Context};
+///
+/// let ctx = Context::builder().bidi_isolation(BidiIsolation::Auto).build().unwrap();
+/// let m = icu::parse("{name} shared a photo.").unwrap();
+/// assert_eq!(ctx.format(&m, Some(&arg("name", "דנה"))),
+///            "\u{2068}דנה\u{2069} shared a photo.");
+/// assert_eq!(ctx.format(&m, Some(&arg("name", "Dana"))), "Dana shared a photo.");
+/// ```
+#[derive(Clone,Copy,Debug,PartialEq,Default)]
+pub enum BidiIsolation {
+    /// Values are written as they are.
+    #[default]
+    Off,
+    /// Values are isolated if the locale is written right to left, or
+    /// if the value has right-to-left characters in it.
+    Auto,
+    /// Every value is isolated.
+    Always,
+}
+
+impl BidiIsolation {
+    /// Whether a `value` is isolated when a message is formatted for a
+    /// `locale`.
+    pub(crate) fn isolates(self, locale: &Locale, value: &Value) -> bool {
+        match self {
+            BidiIsolation::Off => false,
+            BidiIsolation::Auto => {
+                locale.is_right_to_left() ||
+                match *value {
+                    Value::Str(value) => value.chars().any(is_right_to_left),
+                    _ => false,
+                }
+            }
+            BidiIsolation::Always => true,
+        }
+    }
+}
+
+/// Whether a character is in a block of a right-to-left script, like
+/// Hebrew or Arabic.
+fn is_right_to_left(ch: char) -> bool {
+    matches!(ch,
+             '\u{0590}'..='\u{08ff}' |
+             '\u{fb1d}'..='\u{fdff}' |
+             '\u{fe70}'..='\u{feff}' |
+             '\u{10800}'..='\u{10fff}' |
+             '\u{1e800}'..='\u{1efff}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BidiIsolation;
+    use icu::parse;
+    use {arg, Context, Locale, Value};
+
+    #[test]
+    fn isolates_appear_when_enabled() {
+        let m = parse("Welcome, {name}! You have {count} files.").unwrap();
+        let format = |isolation| {
+            let ctx = Context::builder().bidi_isolation(isolation).build().unwrap();
+            ctx.format(&m, Some(&arg("name", "Jo").arg("count", 3)))
+        };
+        assert_eq!(format(BidiIsolation::Off), "Welcome, Jo! You have 3 files.");
+        assert_eq!(format(BidiIsolation::Auto), "Welcome, Jo! You have 3 files.");
+        assert_eq!(format(BidiIsolation::Always),
+                   "Welcome, \u{2068}Jo\u{2069}! You have \u{2068}3\u{2069} files.");
+    }
+
+    #[test]
+    fn auto_isolates_in_right_to_left_locales() {
+        let ctx = Context::builder()
+            .locale("ar-EG")
+            .bidi_isolation(BidiIsolation::Auto)
+            .build()
+            .unwrap();
+        let m = parse("{file} :تم حفظ").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("file", "report.pdf"))),
+                   "\u{2068}report.pdf\u{2069} :تم حفظ");
+    }
+
+    #[test]
+    fn right_to_left_values() {
+        let en = Locale::en_us();
+        let isolates = |value| BidiIsolation::Auto.isolates(&en, &value);
+        assert!(isolates(Value::Str("שלום")));
+        assert!(isolates(Value::Str("file_مرحبا.txt")));
+        assert!(!isolates(Value::Str("Grüße")));
+        assert!(!isolates(Value::Number(5)));
+        assert!(!BidiIsolation::Off.isolates(&Locale::new("he").unwrap(), &Value::Str("שלום")));
+    }
+}
@@ -0,0 +1,310 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Build Script Helpers
+//!
+//! This module checks the message resources of a crate from its
+//! `build.rs`, so that broken or inconsistent translations fail the
+//! build, or are reported as warnings, rather than being found at
+//! runtime.
+//!
+//! ```no_run
+//! extern crate message_format;
+//!
+//! use message_format::build::{validate_dir, ValidateOptions};
+//!
+//! fn main() {
+//!     match validate_dir("locales", &ValidateOptions::default()) {
+//!         Ok(report) => {
+//!             for file in &report.files {
+//!                 println!("cargo:rerun-if-changed={}", file.display());
+//!             }
+//!         }
+//!         Err(issues) => {
+//!             for issue in &issues {
+//!                 println!("cargo:warning={}", issue);
+//!             }
+//!             panic!("{} problems with the messages.", issues.len());
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Nothing is printed by this module, and the files and issues are
+//! always in the same order, so the output of a build is stable.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use {validate_against, BundleError, ConsistencyIssue, Context, Locale, MessageBundle,
+     PropertiesEncoding};
+
+/// Options for [`validate_dir`].
+///
+/// [`validate_dir`]: fn.validate_dir.html
+#[derive(Clone,Debug,PartialEq)]
+pub struct ValidateOptions {
+    /// The locale that the messages are written in, and that the other
+    /// locales are checked against. The default is `en`.
+    pub source_locale: String,
+    /// The encoding of `.properties` files. The default is ISO-8859-1.
+    pub properties_encoding: PropertiesEncoding,
+}
+
+impl Default for ValidateOptions {
+    fn default() -> Self {
+        ValidateOptions {
+            source_locale: "en".to_string(),
+            properties_encoding: PropertiesEncoding::default(),
+        }
+    }
+}
+
+/// What [`validate_dir`] found when there were no issues.
+///
+/// [`validate_dir`]: fn.validate_dir.html
+#[derive(Clone,Debug,PartialEq)]
+pub struct Report {
+    /// The resource files, in sorted order.
+    pub files: Vec<PathBuf>,
+    /// The locale of each file.
+    pub locales: Vec<Locale>,
+    /// The number of messages in all of the files.
+    pub messages: usize,
+}
+
+/// A problem with a resource file.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Issue {
+    /// The file, or the directory if the problem isn't with one file.
+    pub path: PathBuf,
+    /// What is wrong.
+    pub kind: IssueKind,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}: {}", self.path.display(), self.kind)
+    }
+}
+
+/// The kinds of [`Issue`].
+///
+/// [`Issue`]: struct.Issue.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum IssueKind {
+    /// The file or directory couldn't be read. Has a description of the
+    /// error.
+    Unreadable(String),
+    /// The name of the file doesn't have a locale in it.
+    UnknownLocale,
+    /// Another file has messages for the same locale. Has the other
+    /// file.
+    DuplicateLocale(PathBuf),
+    /// There is no file for the source locale. Has the source locale.
+    MissingSource(String),
+    /// The file couldn't be loaded as a bundle.
+    Bundle(BundleError),
+    /// A message isn't consistent with the one for the source locale.
+    Consistency(ConsistencyIssue),
+}
+
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            IssueKind::Unreadable(ref error) => write!(f, "Unable to read: {}", error),
+            IssueKind::UnknownLocale => "The file name has no locale.".fmt(f),
+            IssueKind::DuplicateLocale(ref other) => {
+                write!(f, "The locale is also that of '{}'.", other.display())
+            }
+            IssueKind::MissingSource(ref locale) => {
+                write!(f, "There are no messages for the source locale '{}'.", locale)
+            }
+            IssueKind::Bundle(ref error) => error.fmt(f),
+            IssueKind::Consistency(ref issue) => issue.fmt(f),
+        }
+    }
+}
+
+/// Load every resource file in a directory and its subdirectories,
+/// and check the messages of each locale against those of the source
+/// locale, as with [`validate_against`].
+///
+/// Files ending in `.json` are read as with
+/// [`MessageBundle::from_json_str`] when the `serde_json` feature is
+/// enabled, `.arb` files as with [`MessageBundle::from_arb_str`] and
+/// `.properties` files as with [`MessageBundle::from_properties`].
+/// Other files are ignored. The
+/// locale of a file is its name without the extension, like `pt_BR`
+/// or `de`, or the part of it after the first `_`, like `de` in
+/// `messages_de.properties`. The `@@locale` of an ARB file is used
+/// instead, if it has one.
+///
+/// Each locale must be in only one file. The issues are sorted by
+/// file, and then in the order that they were found.
+///
+/// [`validate_against`]: ../fn.validate_against.html
+/// [`MessageBundle::from_json_str`]: ../struct.MessageBundle.html#method.from_json_str
+/// [`MessageBundle::from_arb_str`]: ../struct.MessageBundle.html#method.from_arb_str
+/// [`MessageBundle::from_properties`]: ../struct.MessageBundle.html#method.from_properties
+pub fn validate_dir<P: AsRef<Path>>(dir: P,
+                                    options: &ValidateOptions)
+                                    -> Result<Report, Vec<Issue>> {
+    let dir = dir.as_ref();
+    let issue = |path: &Path, kind| Issue { path: path.to_path_buf(), kind };
+    let mut files = vec![];
+    if let Err(e) = resource_files(dir, &mut files) {
+        return Err(vec![issue(dir, IssueKind::Unreadable(e.to_string()))]);
+    }
+    let mut issues = vec![];
+    let mut bundles: Vec<(&PathBuf, MessageBundle)> = vec![];
+    for file in &files {
+        let bundle = match load(file, options) {
+            Ok(bundle) => bundle,
+            Err(kinds) => {
+                issues.extend(kinds.into_iter().map(|kind| issue(file, kind)));
+                continue;
+            }
+        };
+        let locale = bundle.context().locale();
+        if let Some(&(other, _)) = bundles.iter().find(|b| b.1.context().locale() == locale) {
+            issues.push(issue(file, IssueKind::DuplicateLocale(other.clone())));
+            continue;
+        }
+        bundles.push((file, bundle));
+    }
+    let source_locale = Locale::new(&options.source_locale.replace('_', "-")).ok();
+    match bundles.iter().find(|b| Some(b.1.context().locale()) == source_locale.as_ref()) {
+        Some((_, source)) => {
+            for &(file, ref bundle) in &bundles {
+                if bundle.context().locale() != source.context().locale() {
+                    issues.extend(validate_against(source, bundle)
+                        .into_iter()
+                        .map(|consistency| issue(file, IssueKind::Consistency(consistency))));
+                }
+            }
+        }
+        None => {
+            // A source that failed to load has already been reported.
+            let failed = issues.iter().any(|issue| {
+                matches!(issue.kind, IssueKind::Unreadable(_) | IssueKind::Bundle(_))
+            });
+            if !failed {
+                let kind = IssueKind::MissingSource(options.source_locale.clone());
+                issues.push(issue(dir, kind));
+            }
+        }
+    }
+    if !issues.is_empty() {
+        // Stable, so the issues of each file stay in the order found.
+        issues.sort_by(|a, b| a.path.cmp(&b.path));
+        return Err(issues);
+    }
+    Ok(Report {
+        locales: bundles.iter().map(|b| b.1.context().locale().clone()).collect(),
+        messages: bundles.iter().map(|b| b.1.len()).sum(),
+        files,
+    })
+}
+
+/// Collect the resource files in `dir` and its subdirectories, in
+/// sorted order.
+fn resource_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            resource_files(&path, files)?;
+        } else if format_of(&path).is_some() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// The resource formats that can be validated.
+#[derive(Clone,Copy,Debug,PartialEq)]
+enum Format {
+    #[cfg(feature = "serde_json")]
+    Json,
+    Arb,
+    Properties,
+}
+
+fn format_of(path: &Path) -> Option<Format> {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        #[cfg(feature = "serde_json")]
+        Some("json") => Some(Format::Json),
+        Some("arb") => Some(Format::Arb),
+        Some("properties") => Some(Format::Properties),
+        _ => None,
+    }
+}
+
+/// The locale in a file name without its extension, like `pt_BR` or
+/// `messages_de`.
+fn locale_of(stem: &str) -> Option<Locale> {
+    let parse = |name: &str| {
+        Locale::new(&name.replace('_', "-")).ok().filter(|locale| locale.language().len() <= 3)
+    };
+    parse(stem).or_else(|| stem.find('_').and_then(|i| parse(&stem[i + 1..])))
+}
+
+fn load(path: &Path, options: &ValidateOptions) -> Result<MessageBundle, Vec<IssueKind>> {
+    let unreadable = |e: io::Error| vec![IssueKind::Unreadable(e.to_string())];
+    let format = format_of(path).unwrap();
+    let locale = path.file_stem().and_then(|stem| stem.to_str()).and_then(locale_of);
+    // An ARB file may set its own locale.
+    let context = Context::new(locale.clone().unwrap_or_default(), None);
+    let bundle = match format {
+        #[cfg(feature = "serde_json")]
+        Format::Json => {
+            let json = fs::read_to_string(path).map_err(unreadable)?;
+            MessageBundle::from_json_str(context, &json)
+        }
+        Format::Arb => {
+            let arb = fs::read_to_string(path).map_err(unreadable)?;
+            MessageBundle::from_arb_str(context, &arb)
+        }
+        Format::Properties => {
+            let source = fs::read(path).map_err(unreadable)?;
+            MessageBundle::from_properties(context, &source, options.properties_encoding)
+        }
+    };
+    let bundle = bundle.map_err(|errors| {
+        errors.into_iter().map(IssueKind::Bundle).collect::<Vec<_>>()
+    })?;
+    if locale.is_none() && bundle.attribute("locale").is_none() {
+        return Err(vec![IssueKind::UnknownLocale]);
+    }
+    Ok(bundle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::locale_of;
+
+    #[test]
+    fn locales_of_file_names() {
+        let cases = [("de", Some("de")),
+                     ("pt_BR", Some("pt-BR")),
+                     ("zh-Hant", Some("zh-Hant")),
+                     ("messages_de", Some("de")),
+                     ("app_zh_Hant_TW", Some("zh-Hant-TW")),
+                     ("messages", None),
+                     ("strings_", None)];
+        for &(stem, expected) in &cases {
+            assert_eq!(locale_of(stem).map(|locale| locale.to_string()),
+                       expected.map(ToString::to_string),
+                       "{}",
+                       stem);
+        }
+    }
+}
@@ -0,0 +1,343 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use {negotiate_locales, Args, BundleError, FormatError, Locale, MessageBundle};
+
+/// A message formatted by a [`BundleStack`], with the locale of the
+/// bundle that it came from.
+///
+/// [`BundleStack`]: struct.BundleStack.html
+#[derive(Clone,Debug,PartialEq)]
+pub struct FormattedMessage {
+    /// The formatted message.
+    pub text: String,
+    /// The locale of the bundle that had the message.
+    pub locale: Locale,
+    /// How many bundles with higher priority were skipped, which is 0
+    /// if the first bundle had the message.
+    pub fallbacks: usize,
+}
+
+/// A chain of [`MessageBundle`]s in priority order, such as for `de-AT`,
+/// `de` and then `en`, so that messages missing from one bundle are
+/// found in the next.
+///
+/// ```
+/// use message_format::{arg, BundleStack, Context, Locale, MessageBundle};
+///
+/// let mut de = MessageBundle::new(Context::new(Locale::new("de").unwrap(), None));
+/// de.insert("greeting", "Hallo, {name}!").unwrap();
+/// let mut en = MessageBundle::new(Context::default());
+/// en.insert("greeting", "Hello, {name}!").unwrap();
+/// en.insert("farewell", "Goodbye, {name}!").unwrap();
+///
+/// let mut stack = BundleStack::new();
+/// stack.push(de);
+/// stack.push(en);
+/// let args = arg("name", "Jo");
+/// assert_eq!(stack.format("greeting", Some(&args)).unwrap().text, "Hallo, Jo!");
+/// let farewell = stack.format("farewell", Some(&args)).unwrap();
+/// assert_eq!((farewell.text.as_str(), farewell.fallbacks), ("Goodbye, Jo!", 1));
+/// assert_eq!(farewell.locale.to_string(), "en-US");
+/// ```
+#[derive(Debug,Default)]
+pub struct BundleStack {
+    bundles: Vec<MessageBundle>,
+    fall_back_on_error: bool,
+    source: Option<Locale>,
+}
+
+impl BundleStack {
+    /// Construct an empty `BundleStack`.
+    pub fn new() -> Self {
+        BundleStack::default()
+    }
+
+    /// Construct a `BundleStack` of the `bundles` for the `requested`
+    /// locales, as chosen by [`negotiate_locales`] from the locales of
+    /// the bundles. The bundle for the `default` locale is last, and the
+    /// bundles that aren't chosen are dropped.
+    ///
+    /// ```
+    /// use message_format::{BundleStack, Context, Locale, MessageBundle};
+    ///
+    /// let bundles = ["de", "de-CH", "en", "fr"]
+    ///     .iter()
+    ///     .map(|locale| MessageBundle::new(Context::new(Locale::new(locale).unwrap(), None)));
+    /// let stack = BundleStack::negotiated(&["de-AT, fr;q=0.1"], bundles, "en");
+    /// let locales = stack.bundles()
+    ///     .map(|bundle| bundle.context().locale().to_string())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(locales, ["de", "de-CH", "fr", "en"]);
+    /// ```
+    ///
+    /// [`negotiate_locales`]: fn.negotiate_locales.html
+    pub fn negotiated<I>(requested: &[&str], bundles: I, default: &str) -> Self
+        where I: IntoIterator<Item = MessageBundle>
+    {
+        let mut bundles = bundles.into_iter()
+            .map(|bundle| (bundle.context().locale().to_string(), Some(bundle)))
+            .collect::<Vec<_>>();
+        let available = bundles.iter().map(|(locale, _)| locale.as_str()).collect::<Vec<_>>();
+        let chosen = negotiate_locales(requested, &available, default);
+        let mut stack = BundleStack::new();
+        for locale in chosen {
+            // The default locale might not have a bundle.
+            let bundle = bundles.iter_mut().find(|(available, _)| *available == locale);
+            if let Some(bundle) = bundle.and_then(|(_, bundle)| bundle.take()) {
+                stack.push(bundle);
+            }
+        }
+        stack
+    }
+
+    /// Add a `bundle` with a lower priority than those already added.
+    pub fn push(&mut self, bundle: MessageBundle) {
+        self.bundles.push(bundle);
+    }
+
+    /// Set whether a message that fails to format, such as because an
+    /// argument is missing, falls back to the next bundle with the key.
+    ///
+    /// By default, the error is returned, as the message in the next
+    /// bundle most likely uses the same arguments. If every bundle with
+    /// the key fails, the error from the first is returned.
+    pub fn fall_back_on_error(&mut self, fall_back_on_error: bool) {
+        self.fall_back_on_error = fall_back_on_error;
+    }
+
+    /// Set the locale that the messages are written in, so that a
+    /// message that fails to format in another bundle, such as when a
+    /// translation uses an argument that isn't given, is formatted from
+    /// the bundle of the source locale instead.
+    ///
+    /// ```
+    /// use message_format::{arg, BundleStack, Context, Locale, MessageBundle};
+    ///
+    /// let mut de = MessageBundle::new(Context::new(Locale::new("de").unwrap(), None));
+    /// de.insert("greeting", "Hallo, {nmae}!").unwrap();
+    /// let mut en = MessageBundle::new(Context::default());
+    /// en.insert("greeting", "Hello, {name}!").unwrap();
+    ///
+    /// let mut stack = BundleStack::new();
+    /// stack.push(de);
+    /// stack.push(en);
+    /// let args = arg("name", "Jo");
+    /// assert!(stack.format("greeting", Some(&args)).is_err());
+    /// stack.fall_back_to_source(Some(Locale::en_us()));
+    /// assert_eq!(stack.format("greeting", Some(&args)).unwrap().text, "Hello, Jo!");
+    /// ```
+    ///
+    /// Each fallback is notified to the [`FormatObserver`] of the
+    /// bundle that failed, with [`on_source_fallback`]. If the source
+    /// message fails too, the original error is returned. There is no
+    /// fallback if there is no bundle for the source locale, which is
+    /// the default.
+    ///
+    /// [`FormatObserver`]: trait.FormatObserver.html
+    /// [`on_source_fallback`]: trait.FormatObserver.html#method.on_source_fallback
+    pub fn fall_back_to_source(&mut self, source: Option<Locale>) {
+        self.source = source;
+    }
+
+    /// The bundles, in priority order.
+    pub fn bundles(&self) -> impl Iterator<Item = &MessageBundle> {
+        self.bundles.iter()
+    }
+
+    /// Whether any bundle has a message with `key`.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.bundles.iter().any(|bundle| bundle.contains_key(key))
+    }
+
+    /// The first bundle with a message for `key`, if there is one.
+    pub fn bundle_for(&self, key: &str) -> Option<&MessageBundle> {
+        self.bundles.iter().find(|bundle| bundle.contains_key(key))
+    }
+
+    /// Format the message with `key` from the first bundle that has it.
+    pub fn format<'f>(&self,
+                      key: &str,
+                      args: Option<&Args<'f>>)
+                      -> Result<FormattedMessage, BundleError> {
+        let mut first_error = None;
+        for (fallbacks, bundle) in self.bundles.iter().enumerate() {
+            if !bundle.contains_key(key) {
+                continue;
+            }
+            match bundle.format(key, args) {
+                Ok(text) => return Ok(self.formatted(text, fallbacks)),
+                Err(error) => {
+                    first_error.get_or_insert((fallbacks, error));
+                    if !self.fall_back_on_error {
+                        break;
+                    }
+                }
+            }
+        }
+        match first_error {
+            Some((failed, BundleError::Format(key, error))) => {
+                self.format_source(failed, &key, &error, args)
+                    .ok_or(BundleError::Format(key, error))
+            }
+            Some((_, error)) => Err(error),
+            None => Err(BundleError::UnknownKey(key.to_string())),
+        }
+    }
+
+    /// Format the message with `key` from the bundle of the source
+    /// locale, after it failed with `error` in the bundle at `failed`.
+    fn format_source<'f>(&self,
+                         failed: usize,
+                         key: &str,
+                         error: &FormatError,
+                         args: Option<&Args<'f>>)
+                         -> Option<FormattedMessage> {
+        let source = self.source.as_ref()?;
+        let index = self.bundles.iter().position(|bundle| bundle.context().locale() == source)?;
+        if index == failed {
+            return None;
+        }
+        let text = self.bundles[index].format(key, args).ok()?;
+        self.bundles[failed].context().format_observer().on_source_fallback(key, error);
+        Some(self.formatted(text, index))
+    }
+
+    fn formatted(&self, text: String, fallbacks: usize) -> FormattedMessage {
+        FormattedMessage {
+            text,
+            locale: self.bundles[fallbacks].context().locale().clone(),
+            fallbacks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::BundleStack;
+    use {arg, BundleError, Context, FormatError, FormatObserver, Locale, MessageBundle};
+
+    fn bundle(locale: &str, messages: &[(&str, &str)]) -> MessageBundle {
+        let mut bundle = MessageBundle::new(Context::new(Locale::new(locale).unwrap(), None));
+        for &(key, source) in messages {
+            bundle.insert(key, source).unwrap();
+        }
+        bundle
+    }
+
+    fn stack() -> BundleStack {
+        let mut stack = BundleStack::new();
+        stack.push(bundle("de-AT", &[("month", "Jänner")]));
+        stack.push(bundle("de", &[("month", "Januar"), ("title", "Fotos {name}")]));
+        stack.push(bundle("en", &[("title", "Photos"), ("help", "Help")]));
+        stack
+    }
+
+    #[test]
+    fn it_works() {
+        let stack = stack();
+        let served = |key| {
+            let message = stack.format(key, Some(&arg("name", "x"))).unwrap();
+            (message.text, message.locale.to_string(), message.fallbacks)
+        };
+        assert_eq!(served("month"), ("Jänner".to_string(), "de-AT".to_string(), 0));
+        assert_eq!(served("title"), ("Fotos x".to_string(), "de".to_string(), 1));
+        assert_eq!(served("help"), ("Help".to_string(), "en".to_string(), 2));
+        assert!(stack.contains_key("help") && !stack.contains_key("missing"));
+        assert_eq!(stack.bundle_for("title").unwrap().context().locale().to_string(), "de");
+        assert_eq!(stack.bundles().count(), 3);
+        assert_eq!(stack.format("missing", None),
+                   Err(BundleError::UnknownKey("missing".to_string())));
+    }
+
+    #[test]
+    fn negotiated() {
+        let bundles = || {
+            vec![bundle("en", &[("title", "Photos"), ("help", "Help")]),
+                 bundle("de", &[("title", "Fotos")]),
+                 bundle("fr", &[("title", "Photos"), ("help", "Aide")])]
+        };
+        let locales = |stack: &BundleStack| {
+            stack.bundles().map(|bundle| bundle.context().locale().to_string()).collect::<Vec<_>>()
+        };
+        let stack = BundleStack::negotiated(&["de-CH;q=0.9, fr"], bundles(), "en");
+        assert_eq!(locales(&stack), ["fr", "de", "en"]);
+        assert_eq!(stack.format("help", None).unwrap().text, "Aide");
+        let stack = BundleStack::negotiated(&["DE-ch"], bundles(), "en");
+        assert_eq!(locales(&stack), ["de", "en"]);
+        assert_eq!(stack.format("help", None).unwrap().text, "Help");
+        // There is no bundle for the default locale.
+        let stack = BundleStack::negotiated(&["ja"], bundles(), "ja");
+        assert_eq!(stack.bundles().count(), 0);
+    }
+
+    #[test]
+    fn errors() {
+        let mut stack = stack();
+        let missing = Err(BundleError::Format("title".to_string(),
+                                              FormatError::MissingArgument("name".to_string())));
+        assert_eq!(stack.format("title", None), missing);
+
+        stack.fall_back_on_error(true);
+        assert_eq!(stack.format("title", None).unwrap().text, "Photos");
+
+        // If every bundle fails, the first error is returned.
+        stack.push(bundle("fr", &[("count", "{n, number}")]));
+        stack.push(bundle("it", &[("count", "{m}")]));
+        assert_eq!(stack.format("count", None),
+                   Err(BundleError::Format("count".to_string(),
+                                           FormatError::MissingArgument("n".to_string()))));
+    }
+
+    #[derive(Default)]
+    struct Fallbacks(Mutex<Vec<String>>);
+
+    impl FormatObserver for Fallbacks {
+        fn on_source_fallback(&self, key: &str, error: &FormatError) {
+            self.0.lock().unwrap().push(format!("{}: {}", key, error));
+        }
+    }
+
+    #[test]
+    fn source_fallback() {
+        let fallbacks = Arc::new(Fallbacks::default());
+        let ctx = Context::builder().locale("de").observer(fallbacks.clone()).build().unwrap();
+        let mut de = MessageBundle::new(ctx);
+        de.insert("title", "Fotos von {nmae}").unwrap();
+        de.insert("count", "{n, number} Fotos").unwrap();
+        let mut stack = BundleStack::new();
+        stack.push(de);
+        stack.push(bundle("en", &[("title", "Photos by {name}"), ("count", "{n} photos")]));
+        let args = arg("name", "Jo");
+        let missing = |key: &str, name: &str| {
+            Err(BundleError::Format(key.to_string(),
+                                    FormatError::MissingArgument(name.to_string())))
+        };
+        assert_eq!(stack.format("title", Some(&args)), missing("title", "nmae"));
+
+        // The translation is broken, but the source is fine.
+        stack.fall_back_to_source(Some(Locale::new("en").unwrap()));
+        let message = stack.format("title", Some(&args)).unwrap();
+        assert_eq!((message.text.as_str(), message.fallbacks), ("Photos by Jo", 1));
+        assert_eq!(message.locale.to_string(), "en");
+        assert_eq!(*fallbacks.0.lock().unwrap(),
+                   ["title: Missing argument 'nmae'."]);
+
+        // Both are broken, so the error of the translation is returned.
+        assert_eq!(stack.format("count", Some(&args)), missing("count", "n"));
+        stack.fall_back_on_error(true);
+        assert_eq!(stack.format("count", Some(&args)), missing("count", "n"));
+        assert_eq!(fallbacks.0.lock().unwrap().len(), 1);
+
+        // The source itself failing isn't a fallback.
+        let mut stack = BundleStack::new();
+        stack.push(bundle("en", &[("title", "Photos by {name}")]));
+        stack.fall_back_to_source(Some(Locale::new("en").unwrap()));
+        assert_eq!(stack.format("title", None), missing("title", "name"));
+    }
+}
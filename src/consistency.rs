@@ -0,0 +1,387 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+
+use icu::ast::PluralFormat;
+use icu::ParseError;
+use {BundleError, Locale, Message, MessageBundle, MessageVisitor, PluralCategory, VariableInfo,
+     VariableKind};
+
+/// A way that a translated message doesn't match its source message,
+/// as found by [`validate_against`].
+///
+/// [`validate_against`]: fn.validate_against.html
+#[derive(Clone,Debug,PartialEq)]
+pub struct ConsistencyIssue {
+    /// The key of the message.
+    pub key: String,
+    /// The locale of the bundle with the problem, which is that of the
+    /// source if the source message is invalid or the translation
+    /// otherwise.
+    pub locale: Locale,
+    /// What is wrong.
+    pub kind: ConsistencyIssueKind,
+}
+
+impl fmt::Display for ConsistencyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{} '{}': {}", self.locale, self.key, self.kind)
+    }
+}
+
+/// The kinds of [`ConsistencyIssue`].
+///
+/// [`ConsistencyIssue`]: struct.ConsistencyIssue.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum ConsistencyIssueKind {
+    /// The translation has no message for a key of the source.
+    MissingMessage,
+    /// The translation has a message for a key that the source doesn't.
+    ExtraMessage,
+    /// The message couldn't be parsed.
+    InvalidMessage(ParseError),
+    /// A variable of the source isn't used by the translation.
+    MissingVariable(String),
+    /// The translation uses a variable that the source doesn't.
+    ExtraVariable(String),
+    /// A variable is used in different ways, such as a plural in the
+    /// source that is plain text in the translation.
+    KindMismatch {
+        /// The name of the variable.
+        variable: String,
+        /// How the source uses the variable, in sorted order.
+        source: Vec<VariableKind>,
+        /// How the translation uses the variable, in sorted order.
+        translation: Vec<VariableKind>,
+    },
+    /// A key of a `select` in the source isn't in the translation.
+    MissingSelectKey {
+        /// The variable of the select.
+        variable: String,
+        /// The missing key.
+        key: String,
+    },
+    /// A key of a `select` in the translation isn't in the source.
+    ExtraSelectKey {
+        /// The variable of the select.
+        variable: String,
+        /// The extra key.
+        key: String,
+    },
+    /// A plural category that the rules for the locale of the translation
+    /// use has no branch.
+    MissingPluralCategory {
+        /// The variable of the plural.
+        variable: String,
+        /// The missing category.
+        category: PluralCategory,
+    },
+}
+
+impl fmt::Display for ConsistencyIssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let kinds = |kinds: &[VariableKind]| {
+            kinds.iter().map(|kind| kind.to_string()).collect::<Vec<_>>().join(" and ")
+        };
+        match *self {
+            ConsistencyIssueKind::MissingMessage => "The message is missing.".fmt(f),
+            ConsistencyIssueKind::ExtraMessage => "The message isn't in the source.".fmt(f),
+            ConsistencyIssueKind::InvalidMessage(ref error) => {
+                write!(f, "Invalid message: {}", error)
+            }
+            ConsistencyIssueKind::MissingVariable(ref variable) => {
+                write!(f, "Variable '{}' is missing.", variable)
+            }
+            ConsistencyIssueKind::ExtraVariable(ref variable) => {
+                write!(f, "Variable '{}' isn't in the source.", variable)
+            }
+            ConsistencyIssueKind::KindMismatch { ref variable, ref source, ref translation } => {
+                write!(f,
+                       "Variable '{}' is {} but {} in the source.",
+                       variable,
+                       kinds(translation),
+                       kinds(source))
+            }
+            ConsistencyIssueKind::MissingSelectKey { ref variable, ref key } => {
+                write!(f, "Select '{}' is missing key '{}'.", variable, key)
+            }
+            ConsistencyIssueKind::ExtraSelectKey { ref variable, ref key } => {
+                write!(f, "Select '{}' has key '{}', which isn't in the source.", variable, key)
+            }
+            ConsistencyIssueKind::MissingPluralCategory { ref variable, category } => {
+                write!(f, "Plural '{}' is missing category '{}'.", variable, category)
+            }
+        }
+    }
+}
+
+/// Compare each message of a `translation` with the message with the
+/// same key in its `source`, finding where they don't match.
+///
+/// The variables of the messages, how each variable is used and the
+/// keys of their `select` formats must match. The categories of the
+/// plurals are compared with the built-in plural rules for the locale
+/// of the translation, rather than with the source, as languages use
+/// different categories. `=` literals aren't taken into account.
+///
+/// ```
+/// use message_format::{validate_against, Context, ConsistencyIssueKind, Locale,
+///                      MessageBundle, PluralCategory};
+///
+/// let mut source = MessageBundle::new(Context::default());
+/// source.insert("files", "{count, plural, one {# file} other {# files}} in {dir}").unwrap();
+/// let mut translation = MessageBundle::new(Context::new(Locale::new("ru").unwrap(), None));
+/// translation.insert("files", "{count, plural, one {# файл} other {# файлов}} в {folder}")
+///     .unwrap();
+///
+/// let issues = validate_against(&source, &translation)
+///     .into_iter()
+///     .map(|issue| issue.kind)
+///     .collect::<Vec<_>>();
+/// assert_eq!(issues,
+///            [ConsistencyIssueKind::MissingVariable("dir".to_string()),
+///             ConsistencyIssueKind::ExtraVariable("folder".to_string()),
+///             ConsistencyIssueKind::MissingPluralCategory {
+///                 variable: "count".to_string(),
+///                 category: PluralCategory::Few,
+///             },
+///             ConsistencyIssueKind::MissingPluralCategory {
+///                 variable: "count".to_string(),
+///                 category: PluralCategory::Many,
+///             }]);
+/// ```
+///
+/// The issues are in the order of the keys.
+pub fn validate_against(source: &MessageBundle,
+                        translation: &MessageBundle)
+                        -> Vec<ConsistencyIssue> {
+    let source_locale = source.context().locale();
+    let locale = translation.context().locale();
+    let mut issues = vec![];
+    let mut push = |key: &str, locale: &Locale, kind| {
+        issues.push(ConsistencyIssue {
+            key: key.to_string(),
+            locale: locale.clone(),
+            kind,
+        })
+    };
+    let mut keys = source.keys().chain(translation.keys()).collect::<Vec<_>>();
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        let (source_message, message) = match (source.try_get(key), translation.try_get(key)) {
+            (Err(BundleError::UnknownKey(_)), _) => {
+                push(key, locale, ConsistencyIssueKind::ExtraMessage);
+                continue;
+            }
+            (_, Err(BundleError::UnknownKey(_))) => {
+                push(key, locale, ConsistencyIssueKind::MissingMessage);
+                continue;
+            }
+            (Err(BundleError::Parse(_, error)), _) => {
+                push(key, source_locale, ConsistencyIssueKind::InvalidMessage(error));
+                continue;
+            }
+            (_, Err(BundleError::Parse(_, error))) => {
+                push(key, locale, ConsistencyIssueKind::InvalidMessage(error));
+                continue;
+            }
+            (Ok(source_message), Ok(message)) => (source_message, message),
+            _ => continue,
+        };
+        for kind in compare(source_message, message, locale) {
+            push(key, locale, kind);
+        }
+    }
+    issues
+}
+
+/// The issues with a translated `message` of a `source` message, for a
+/// `locale`.
+fn compare(source: &Message, message: &Message, locale: &Locale) -> Vec<ConsistencyIssueKind> {
+    let source = source.variables();
+    let variables = message.variables();
+    let mut issues = vec![];
+    for variable in &source {
+        match find(&variables, &variable.name) {
+            None => issues.push(ConsistencyIssueKind::MissingVariable(variable.name.clone())),
+            Some(translation) if shape(&translation.kinds) != shape(&variable.kinds) => {
+                issues.push(ConsistencyIssueKind::KindMismatch {
+                    variable: variable.name.clone(),
+                    source: variable.kinds.clone(),
+                    translation: translation.kinds.clone(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    for variable in &variables {
+        if find(&source, &variable.name).is_none() {
+            issues.push(ConsistencyIssueKind::ExtraVariable(variable.name.clone()));
+        }
+    }
+    for variable in &source {
+        if let Some(translation) = find(&variables, &variable.name) {
+            for key in difference(select_keys(variable), select_keys(translation)) {
+                issues.push(ConsistencyIssueKind::MissingSelectKey {
+                    variable: variable.name.clone(),
+                    key: key.clone(),
+                });
+            }
+        }
+    }
+    for variable in &variables {
+        if let Some(original) = find(&source, &variable.name) {
+            for key in difference(select_keys(variable), select_keys(original)) {
+                issues.push(ConsistencyIssueKind::ExtraSelectKey {
+                    variable: variable.name.clone(),
+                    key: key.clone(),
+                });
+            }
+        }
+    }
+    let mut plurals = Plurals(vec![]);
+    message.accept(&mut plurals);
+    for &(ref variable, ordinal, ref categories) in &plurals.0 {
+        let mut rules = PluralFormat::new(variable, Message::new(vec![]));
+        rules.ordinal(ordinal);
+        for category in rules.categories_used(locale) {
+            if !categories.contains(&category) {
+                let variable = variable.clone();
+                let issue = ConsistencyIssueKind::MissingPluralCategory { variable, category };
+                if !issues.contains(&issue) {
+                    issues.push(issue);
+                }
+            }
+        }
+    }
+    issues
+}
+
+fn find<'a>(variables: &'a [VariableInfo], name: &str) -> Option<&'a VariableInfo> {
+    variables.iter().find(|variable| variable.name == name)
+}
+
+/// The `kinds` of a variable without the keys of its selects, which are
+/// compared separately.
+fn shape(kinds: &[VariableKind]) -> Vec<VariableKind> {
+    kinds.iter()
+        .map(|kind| match *kind {
+            VariableKind::Select { .. } => VariableKind::Select { keys: vec![] },
+            ref kind => kind.clone(),
+        })
+        .collect()
+}
+
+fn select_keys(variable: &VariableInfo) -> &[String] {
+    variable.kinds
+        .iter()
+        .find_map(|kind| match *kind {
+            VariableKind::Select { ref keys } => Some(&keys[..]),
+            _ => None,
+        })
+        .unwrap_or(&[])
+}
+
+/// The items of `a` that aren't in `b`.
+fn difference<'a>(a: &'a [String], b: &[String]) -> Vec<&'a String> {
+    a.iter().filter(|item| !b.contains(item)).collect()
+}
+
+/// Collects each plural of a message, with whether it is ordinal and
+/// its categories.
+struct Plurals(Vec<(String, bool, Vec<PluralCategory>)>);
+
+impl MessageVisitor for Plurals {
+    fn visit_plural(&mut self, variable: &str, categories: &[PluralCategory], _literals: &[i64]) {
+        self.0.push((variable.to_string(), false, categories.to_vec()));
+    }
+
+    fn visit_selectordinal(&mut self,
+                           variable: &str,
+                           categories: &[PluralCategory],
+                           _literals: &[i64]) {
+        self.0.push((variable.to_string(), true, categories.to_vec()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_against, ConsistencyIssueKind, VariableKind};
+    use {Context, Locale, MessageBundle, PluralCategory};
+
+    fn bundle(locale: &str, messages: &[(&str, &str)]) -> MessageBundle {
+        let mut bundle = MessageBundle::new(Context::new(Locale::new(locale).unwrap(), None));
+        for &(key, source) in messages {
+            bundle.insert_raw(key, source);
+        }
+        bundle
+    }
+
+    #[test]
+    fn matching_bundles() {
+        let source = bundle("en",
+                            &[("a", "{n, plural, one {# file} other {# files}} in {dir}"),
+                              ("b", "{g, select, f {She} m {He} other {They}} {rank, \
+                                     selectordinal, one {#st} two {#nd} few {#rd} other {#th}}")]);
+        let translation = bundle("fr",
+                                 &[("a", "{dir} : {n, plural, one {# fichier} many {# de \
+                                          fichiers} other {# fichiers}}"),
+                                   ("b", "{rank, selectordinal, one {#er} other {#e}} {g, \
+                                          select, m {Il} f {Elle} other {Iel}}")]);
+        assert_eq!(validate_against(&source, &translation), []);
+    }
+
+    #[test]
+    fn issues() {
+        let source = bundle("en",
+                            &[("missing", "x"),
+                              ("invalid", "{n"),
+                              ("kinds", "{n, plural, one {#} other {#}} {n} {g}"),
+                              ("keys", "{g, select, f {x} m {y} other {z}}")]);
+        let translation = bundle("pl",
+                                 &[("extra", "x"),
+                                   ("invalid", "x"),
+                                   ("kinds", "{n} {g, select, other {}}"),
+                                   ("keys", "{g, select, f {x} n {z} other {z}}")]);
+        let issues = validate_against(&source, &translation);
+        let described = issues.iter().map(|issue| issue.to_string()).collect::<Vec<_>>();
+        assert_eq!(described[..4],
+                   ["pl 'extra': The message isn't in the source.",
+                    "en 'invalid': Invalid message: At offset 2: Unexpected end of message.",
+                    "pl 'keys': Select 'g' is missing key 'm'.",
+                    "pl 'keys': Select 'g' has key 'n', which isn't in the source."]);
+        assert_eq!(issues[4].kind,
+                   ConsistencyIssueKind::KindMismatch {
+                       variable: "n".to_string(),
+                       source: vec![VariableKind::Plain, VariableKind::Plural],
+                       translation: vec![VariableKind::Plain],
+                   });
+        assert_eq!(described[4..],
+                   ["pl 'kinds': Variable 'n' is plain but plain and plural in the source.",
+                    "pl 'kinds': Variable 'g' is select but plain in the source.",
+                    "pl 'missing': The message is missing."]);
+    }
+
+    #[test]
+    fn plural_categories() {
+        let source = bundle("en", &[("a", "{n, plural, one {#} other {#}} {n, plural, other {}}")]);
+        let translation = bundle("cy",
+                                 &[("a", "{n, plural, zero {} one {} two {} few {} other {}} \
+                                          {n, plural, =0 {} many {} other {}}")]);
+        let missing = validate_against(&source, &translation)
+            .into_iter()
+            .map(|issue| match issue.kind {
+                ConsistencyIssueKind::MissingPluralCategory { category, .. } => category,
+                kind => panic!("unexpected {:?}", kind),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(missing,
+                   [PluralCategory::Many, PluralCategory::Zero, PluralCategory::One,
+                    PluralCategory::Two, PluralCategory::Few]);
+    }
+}
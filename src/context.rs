@@ -4,51 +4,289 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use language_tags::LanguageTag;
 use std::fmt;
+use std::iter;
+use std::string::{String, ToString};
+use std::sync::Arc;
+use std::vec::Vec;
 
-use {Args, Message};
+use format_observer::{NoObserver, UsedArguments};
+use icu::DEFAULT_MAX_DEPTH;
+use plural_rules::cardinal_rules;
+use {Args, BidiIsolation, ContextBuilder, FormatError, FormatObserver, Locale, LocaleError,
+     Message, NumberSymbols, PluralClassifier, Pseudolocalization, Sanitization, Value};
 
 /// Contextual configuration data.
+///
+/// Use [`Context::builder`] to create a `Context` with anything other
+/// than the defaults.
+///
+/// [`Context::builder`]: struct.Context.html#method.builder
 #[derive(Clone)]
 pub struct Context {
-    /// The language being localized for.
-    pub language_tag: LanguageTag,
+    /// The locale being localized for. This defaults to `en-US`.
+    pub(crate) locale: Locale,
+    /// The locales to use data from when there is none for `locale`, in
+    /// order.
+    pub(crate) fallback_locales: Vec<Locale>,
     /// The value to use in a `PlaceholderFormat`.
-    pub placeholder_value: Option<i64>,
+    pub(crate) placeholder: Option<Value<'static>>,
+    /// The symbols used when writing numbers.
+    pub(crate) number_symbols: NumberSymbols,
+    /// How control characters within argument values are handled.
+    pub(crate) sanitization: Sanitization,
+    /// Whether messages are pseudolocalized.
+    pub(crate) pseudolocalization: Pseudolocalization,
+    /// Whether argument values are wrapped in bidi isolation characters.
+    pub(crate) bidi_isolation: BidiIsolation,
+    /// Whether a `plural` or `select` falling back to `other` is an error.
+    pub(crate) strict_fallbacks: bool,
+    /// How deeply formats can be nested within each other.
+    pub(crate) max_depth: usize,
+    /// Notified of fallbacks and failures while formatting.
+    pub(crate) observer: Option<Arc<dyn FormatObserver>>,
+    /// Used for `plural` formats instead of their own classifier or
+    /// the rules for the locale.
+    pub(crate) cardinal_classifier: Option<Arc<dyn PluralClassifier>>,
+    /// Used for `selectordinal` formats instead of their own classifier
+    /// or the rules for the locale.
+    pub(crate) ordinal_classifier: Option<Arc<dyn PluralClassifier>>,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Context::new(Locale::default(), None)
+    }
 }
 
 impl Context {
     /// Create a new instance of `Context`.
-    pub fn new(language: LanguageTag, placeholder_value: Option<i64>) -> Self {
+    pub fn new(locale: Locale, placeholder_value: Option<i64>) -> Self {
         Context {
-            language_tag: language,
-            placeholder_value: placeholder_value,
+            locale,
+            fallback_locales: vec![],
+            placeholder: placeholder_value.map(Value::Number),
+            number_symbols: NumberSymbols::default(),
+            sanitization: Sanitization::default(),
+            pseudolocalization: Pseudolocalization::default(),
+            bidi_isolation: BidiIsolation::default(),
+            strict_fallbacks: false,
+            max_depth: DEFAULT_MAX_DEPTH,
+            observer: None,
+            cardinal_classifier: None,
+            ordinal_classifier: None,
+        }
+    }
+
+    /// Create a [`ContextBuilder`] to configure a `Context`.
+    ///
+    /// [`ContextBuilder`]: struct.ContextBuilder.html
+    pub fn builder() -> ContextBuilder {
+        ContextBuilder::new()
+    }
+
+    /// Create a `Context` for the first of `locales`, which uses the
+    /// data of the rest of them, in order, when there is none for it.
+    /// This is the same as [`ContextBuilder::locale_chain`].
+    ///
+    /// ```
+    /// use message_format::{arg, icu, Context};
+    ///
+    /// // There are no plural rules for Klingon, so French is used.
+    /// let ctx = Context::locale_chain(&["tlh", "fr", "en"]).unwrap();
+    /// let m = icu::parse("{n, plural, one {# fichier} other {# fichiers}}").unwrap();
+    /// assert_eq!(ctx.format(&m, Some(&arg("n", 0))), "0 fichier");
+    /// ```
+    ///
+    /// [`ContextBuilder::locale_chain`]: struct.ContextBuilder.html#method.locale_chain
+    pub fn locale_chain(locales: &[&str]) -> Result<Self, LocaleError> {
+        ContextBuilder::new().locale_chain(locales).build()
+    }
+
+    /// The locale being localized for. This defaults to `en-US`.
+    pub fn locale(&self) -> &Locale {
+        &self.locale
+    }
+
+    /// The locale being localized for, followed by the locales whose
+    /// data is used when there is none for it.
+    pub fn locales(&self) -> impl Iterator<Item = &Locale> {
+        Some(&self.locale).into_iter().chain(&self.fallback_locales)
+    }
+
+    /// The first of the [`locales`] that has built-in plural rules, or
+    /// the locale being localized for if none of them do.
+    ///
+    /// [`locales`]: #method.locales
+    pub(crate) fn plural_locale(&self) -> &Locale {
+        self.locales().find(|locale| cardinal_rules(locale).is_some()).unwrap_or(&self.locale)
+    }
+
+    /// The classifier set for ordinal or cardinal plurals, if there is
+    /// one.
+    pub(crate) fn plural_classifier(&self, ordinal: bool) -> Option<&dyn PluralClassifier> {
+        if ordinal {
+            self.ordinal_classifier.as_deref()
+        } else {
+            self.cardinal_classifier.as_deref()
         }
     }
 
+    /// The value to use in a `PlaceholderFormat`, if it is an integer.
+    pub fn placeholder_value(&self) -> Option<i64> {
+        match self.placeholder {
+            Some(Value::Number(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The symbols used when writing numbers.
+    pub fn number_symbols(&self) -> &NumberSymbols {
+        &self.number_symbols
+    }
+
+    /// How control characters within argument values are handled.
+    pub fn sanitization(&self) -> Sanitization {
+        self.sanitization
+    }
+
+    /// Whether messages are pseudolocalized.
+    pub fn pseudolocalization(&self) -> Pseudolocalization {
+        self.pseudolocalization
+    }
+
+    /// Whether argument values are wrapped in bidi isolation characters.
+    pub fn bidi_isolation(&self) -> BidiIsolation {
+        self.bidi_isolation
+    }
+
+    /// Whether formatting fails with `FormatError::UnexpectedFallback`
+    /// when a `plural` or `select` has no branch for a value and would
+    /// fall back to `other`. This defaults to `false`.
+    pub fn strict_fallbacks(&self) -> bool {
+        self.strict_fallbacks
+    }
+
+    /// How deeply formats can be nested within each other before
+    /// formatting fails with `FormatError::TooDeep`. This defaults to
+    /// [`icu::DEFAULT_MAX_DEPTH`].
+    ///
+    /// [`icu::DEFAULT_MAX_DEPTH`]: icu/constant.DEFAULT_MAX_DEPTH.html
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
     /// Format a message, returning a string.
     pub fn format<'f>(&self, message: &Message, args: Option<&Args<'f>>) -> String {
         let mut output = String::new();
-        let _ = message.write_message(self, &mut output, args);
+        let _ = self.write(message, &mut output, args);
         output
     }
 
+    /// Format a message, returning a string and the names of the
+    /// arguments in `args` that it didn't use, in the order that they
+    /// were added, which are likely to be mistakes. An argument that is
+    /// only used by branches that weren't formatted is unused.
+    ///
+    /// ```
+    /// use message_format::{arg, icu, Context};
+    ///
+    /// let ctx = Context::default();
+    /// let m = icu::parse("Hello, {name}!").unwrap();
+    /// let args = arg("name", "Ana");
+    /// let (output, unused) = ctx.format_with_unused(&m, Some(&args.arg("nmae", "Ana")));
+    /// assert_eq!(output, "Hello, Ana!");
+    /// assert_eq!(unused, ["nmae"]);
+    /// ```
+    pub fn format_with_unused<'f>(&self,
+                                  message: &Message,
+                                  args: Option<&Args<'f>>)
+                                  -> (String, Vec<String>) {
+        let names = iter::successors(args, |arg| arg.prev).map(|arg| arg.name);
+        let used = Arc::new(UsedArguments::new(self.observer.clone(), names.clone()));
+        let ctx = Context { observer: Some(used.clone()), ..self.clone() };
+        let output = ctx.format(message, args);
+        let mut unused: Vec<String> = vec![];
+        for name in names {
+            if !used.contains(name) && !unused.iter().any(|n| n == name) {
+                unused.push(name.to_string());
+            }
+        }
+        unused.reverse();
+        (output, unused)
+    }
+
     /// Write a message to a stream.
     pub fn write<'f>(&self,
                      message: &Message,
-                     stream: &mut fmt::Write,
+                     stream: &mut dyn fmt::Write,
                      args: Option<&Args<'f>>)
-                     -> fmt::Result {
-        message.write_message(self, stream, args)
+                     -> Result<(), FormatError> {
+        let result = message.write_message(self, stream, args);
+        if let Err(ref error) = result {
+            self.format_observer().on_error(error);
+        }
+        result
     }
-}
 
-impl Default for Context {
-    fn default() -> Self {
-        Context {
-            language_tag: Default::default(),
-            placeholder_value: None,
+    /// The observer to notify of fallbacks and failures. If there isn't
+    /// one, notifications are ignored.
+    pub fn format_observer(&self) -> &dyn FormatObserver {
+        match self.observer {
+            Some(ref observer) => &**observer,
+            None => &NoObserver,
+        }
+    }
+
+    /// Look up the argument with the given `name`, for use by a
+    /// [`MessagePart`]. If it is missing, the observer is notified.
+    ///
+    /// [`MessagePart`]: trait.MessagePart.html
+    pub fn lookup_arg<'a>(&self,
+                          args: Option<&'a Args<'a>>,
+                          name: &str)
+                          -> Result<&'a Args<'a>, FormatError> {
+        match self.find_arg(args, name) {
+            Some(arg) => Ok(arg),
+            None => {
+                self.format_observer().on_missing_argument(name);
+                Err(FormatError::MissingArgument(name.to_string()))
+            }
+        }
+    }
+
+    /// Look up the argument with the given `name` without notifying the
+    /// observer if it is missing.
+    pub(crate) fn find_arg<'a>(&self,
+                               args: Option<&'a Args<'a>>,
+                               name: &str)
+                               -> Option<&'a Args<'a>> {
+        let arg = args.and_then(|args| args.get(name));
+        if arg.is_some() {
+            self.format_observer().on_argument(name);
         }
+        arg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use icu::parse;
+    use {arg, Context};
+
+    #[test]
+    fn unused_arguments() {
+        let ctx = Context::default();
+        let m = parse("{count, plural, =0 {No files} other {# files in {dir}}}").unwrap();
+        let args = arg("dir", "Documents");
+        let args = args.arg("extra", 1);
+        let args = args.arg("count", 3);
+        assert_eq!(ctx.format_with_unused(&m, Some(&args)),
+                   ("3 files in Documents".to_string(), vec!["extra".to_string()]));
+        // The directory is only used by the other branch.
+        let args = args.arg("count", 0);
+        assert_eq!(ctx.format_with_unused(&m, Some(&args)),
+                   ("No files".to_string(), vec!["dir".to_string(), "extra".to_string()]));
+        assert_eq!(ctx.format_with_unused(&m, None), (String::new(), vec![]));
     }
 }
@@ -0,0 +1,255 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::sync::Arc;
+
+use {BidiIsolation, Context, FormatObserver, Locale, LocaleError, NumberSymbols, PluralClassifier,
+     Pseudolocalization, Sanitization, Value};
+
+/// Build a [`Context`].
+///
+/// This is created by [`Context::builder`]. Anything that isn't set
+/// has the same value as in `Context::default()`.
+///
+/// ```
+/// use message_format::{arg, icu, Context};
+///
+/// let ctx = Context::builder().locale("fr").build().unwrap();
+/// assert_eq!(ctx.locale().language(), "fr");
+///
+/// // French uses the singular for 0.
+/// let m = icu::parse("{n, plural, one {# fichier} other {# fichiers}}").unwrap();
+/// assert_eq!(ctx.format(&m, Some(&arg("n", 0))), "0 fichier");
+/// ```
+///
+/// [`Context`]: struct.Context.html
+/// [`Context::builder`]: struct.Context.html#method.builder
+#[derive(Clone)]
+pub struct ContextBuilder {
+    locale: Result<Locale, LocaleError>,
+    context: Context,
+}
+
+impl ContextBuilder {
+    pub(crate) fn new() -> Self {
+        ContextBuilder {
+            locale: Ok(Locale::default()),
+            context: Context::default(),
+        }
+    }
+
+    /// Set the locale being localized for, such as `"fr-CA"`. If it is
+    /// invalid, `build` returns the error.
+    pub fn locale(mut self, locale: &str) -> Self {
+        self.locale = Locale::new(locale);
+        self.context.fallback_locales.clear();
+        self
+    }
+
+    /// Set the locale being localized for to the first of `locales`,
+    /// such as `&["fr-CA", "fr", "en"]`, with the rest of them used, in
+    /// order, for data that there is none of for it. If any of them are
+    /// invalid, `build` returns the first error. If `locales` is empty,
+    /// the locale is `en-US`.
+    ///
+    /// Plural rules and the names of units in durations are taken from
+    /// the first locale that has them. Data for a locale already falls
+    /// back to its less specific locales, so `fr-CA` uses the rules for
+    /// `fr` without a chain. [`NumberSymbols`] are never looked up by
+    /// locale, so they are unaffected.
+    ///
+    /// [`NumberSymbols`]: struct.NumberSymbols.html
+    pub fn locale_chain(mut self, locales: &[&str]) -> Self {
+        let mut locales = locales.iter().map(|locale| Locale::new(locale));
+        let locale = locales.next().unwrap_or_else(|| Ok(Locale::default()));
+        match (locale, locales.collect()) {
+            (Ok(locale), Ok(fallbacks)) => {
+                self.locale = Ok(locale);
+                self.context.fallback_locales = fallbacks;
+            }
+            (Err(error), _) | (_, Err(error)) => self.locale = Err(error),
+        }
+        self
+    }
+
+    /// Set the value to use in a `PlaceholderFormat`.
+    pub fn placeholder_value(mut self, value: i64) -> Self {
+        self.context.placeholder = Some(Value::Number(value));
+        self
+    }
+
+    /// Set the symbols used when writing numbers.
+    pub fn number_symbols(mut self, number_symbols: NumberSymbols) -> Self {
+        self.context.number_symbols = number_symbols;
+        self
+    }
+
+    /// Set how control characters within argument values are handled.
+    pub fn sanitization(mut self, sanitization: Sanitization) -> Self {
+        self.context.sanitization = sanitization;
+        self
+    }
+
+    /// Set whether messages are pseudolocalized.
+    pub fn pseudolocalization(mut self, pseudolocalization: Pseudolocalization) -> Self {
+        self.context.pseudolocalization = pseudolocalization;
+        self
+    }
+
+    /// Set whether argument values are wrapped in bidi isolation
+    /// characters, so that they can't reorder the text around them.
+    pub fn bidi_isolation(mut self, bidi_isolation: BidiIsolation) -> Self {
+        self.context.bidi_isolation = bidi_isolation;
+        self
+    }
+
+    /// Set whether formatting fails when a `plural` or `select` has no
+    /// branch for a value and would fall back to `other`, rather than
+    /// only notifying the observer. This is useful when testing that
+    /// every translation is complete.
+    ///
+    /// ```
+    /// use message_format::{arg, icu, Context, FormatError};
+    ///
+    /// let ctx = Context::builder().strict_fallbacks(true).build().unwrap();
+    /// let m = icu::parse("{n, plural, other {# files}}").unwrap();
+    /// assert_eq!(m.write_message(&ctx, &mut String::new(), Some(&arg("n", 1))),
+    ///            Err(FormatError::UnexpectedFallback {
+    ///                variable: "n".to_string(),
+    ///                value: "1".to_string(),
+    ///            }));
+    /// ```
+    pub fn strict_fallbacks(mut self, strict: bool) -> Self {
+        self.context.strict_fallbacks = strict;
+        self
+    }
+
+    /// Set how deeply formats can be nested within each other before
+    /// formatting fails with `FormatError::TooDeep`, which guards
+    /// against messages that weren't limited by the parser. This
+    /// defaults to [`icu::DEFAULT_MAX_DEPTH`].
+    ///
+    /// ```
+    /// use message_format::{arg, icu, Context, FormatError};
+    ///
+    /// let ctx = Context::builder().max_depth(1).build().unwrap();
+    /// let m = icu::parse("{a, select, other {{b, select, other {deep}}}}").unwrap();
+    /// assert_eq!(m.write_message(&ctx, &mut String::new(), Some(&arg("a", "x").arg("b", "y"))),
+    ///            Err(FormatError::TooDeep));
+    /// ```
+    ///
+    /// [`icu::DEFAULT_MAX_DEPTH`]: icu/constant.DEFAULT_MAX_DEPTH.html
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.context.max_depth = max_depth;
+        self
+    }
+
+    /// Set the observer to notify of fallbacks and failures while
+    /// formatting.
+    pub fn observer(mut self, observer: Arc<dyn FormatObserver>) -> Self {
+        self.context.observer = Some(observer);
+        self
+    }
+
+    /// Set the classifier for `plural` formats, which is used rather than
+    /// the rules for the locale or a classifier set on the format. This
+    /// chooses the rules when formatting, such as for the locale of a
+    /// request, rather than when messages are parsed.
+    ///
+    /// ```
+    /// use message_format::{arg, icu, Context, PluralCategory, PluralOperands};
+    ///
+    /// let m = icu::parse("{n, plural, one {# file} other {# files}}").unwrap();
+    /// let ctx = Context::builder()
+    ///     .plural_classifier(|operands: &PluralOperands| match operands.i {
+    ///         0 | 1 => PluralCategory::One,
+    ///         _ => PluralCategory::Other,
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(ctx.format(&m, Some(&arg("n", 0))), "0 file");
+    /// assert_eq!(Context::default().format(&m, Some(&arg("n", 0))), "0 files");
+    /// ```
+    pub fn plural_classifier<C: PluralClassifier + 'static>(mut self, classifier: C) -> Self {
+        self.context.cardinal_classifier = Some(Arc::new(classifier));
+        self
+    }
+
+    /// Set the classifier for `selectordinal` formats, as
+    /// [`plural_classifier`] does for `plural` formats.
+    ///
+    /// [`plural_classifier`]: #method.plural_classifier
+    pub fn ordinal_classifier<C: PluralClassifier + 'static>(mut self, classifier: C) -> Self {
+        self.context.ordinal_classifier = Some(Arc::new(classifier));
+        self
+    }
+
+    /// Build the `Context`.
+    pub fn build(self) -> Result<Context, LocaleError> {
+        Ok(Context { locale: self.locale?, ..self.context })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use icu::DEFAULT_MAX_DEPTH;
+    use {BidiIsolation, Context, LocaleError, NumberSymbols, Pseudolocalization, Sanitization};
+
+    #[test]
+    fn defaults() {
+        let ctx = Context::builder().build().unwrap();
+        assert_eq!(ctx.locale().to_string(), "en-US");
+        assert_eq!(ctx.placeholder_value(), None);
+        assert_eq!(*ctx.number_symbols(), NumberSymbols::default());
+        assert_eq!(ctx.sanitization(), Sanitization::Off);
+        assert_eq!(ctx.pseudolocalization(), Pseudolocalization::Off);
+        assert_eq!(ctx.bidi_isolation(), BidiIsolation::Off);
+        assert!(!ctx.strict_fallbacks());
+        assert_eq!(ctx.max_depth(), DEFAULT_MAX_DEPTH);
+        assert_eq!(ctx.locales().count(), 1);
+    }
+
+    #[test]
+    fn setters() {
+        let ctx = Context::builder()
+            .locale("pt-BR")
+            .placeholder_value(3)
+            .sanitization(Sanitization::Strip)
+            .pseudolocalization(Pseudolocalization::Arguments)
+            .bidi_isolation(BidiIsolation::Always)
+            .strict_fallbacks(true)
+            .max_depth(8)
+            .build()
+            .unwrap();
+        assert_eq!(ctx.locale().to_string(), "pt-BR");
+        assert_eq!(ctx.placeholder_value(), Some(3));
+        assert_eq!(ctx.sanitization(), Sanitization::Strip);
+        assert_eq!(ctx.pseudolocalization(), Pseudolocalization::Arguments);
+        assert_eq!(ctx.bidi_isolation(), BidiIsolation::Always);
+        assert!(ctx.strict_fallbacks());
+        assert_eq!(ctx.max_depth(), 8);
+    }
+
+    #[test]
+    fn locale_chain() {
+        let ctx = Context::builder().locale_chain(&["fr-CA", "fr", "en"]).build().unwrap();
+        assert_eq!(ctx.locale().to_string(), "fr-CA");
+        assert_eq!(ctx.locales().map(|locale| locale.to_string()).collect::<Vec<_>>(),
+                   ["fr-CA", "fr", "en"]);
+        let ctx = Context::builder().locale_chain(&["fr-CA", "fr"]).locale("de").build().unwrap();
+        assert_eq!(ctx.locales().count(), 1);
+        let ctx = Context::builder().locale_chain(&[]).build().unwrap();
+        assert_eq!(ctx.locale().to_string(), "en-US");
+        assert_eq!(Context::locale_chain(&["fr", "en_US", "x-"]).err(),
+                   Some(LocaleError::Malformed("en_US".to_string())));
+    }
+
+    #[test]
+    fn invalid_locale() {
+        assert_eq!(Context::builder().locale("en_US").build().err(),
+                   Some(LocaleError::Malformed("en_US".to_string())));
+    }
+}
@@ -0,0 +1,477 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Android
+//!
+//! This converts [Android string resources], `strings.xml` files, into
+//! ICU messages:
+//!
+//! ```
+//! use message_format::{arg, Context};
+//! use message_format::convert::android::{self, AndroidOptions};
+//!
+//! let conversion = android::convert(Context::default(), r#"<?xml version="1.0"?>
+//! <resources>
+//!     <string name="welcome">Welcome, %1$s! You\'re in.</string>
+//!     <plurals name="songs">
+//!         <item quantity="one">%d song</item>
+//!         <item quantity="other">%d songs</item>
+//!     </plurals>
+//! </resources>"#, &AndroidOptions::default()).unwrap();
+//! assert!(conversion.diagnostics.is_empty());
+//! let bundle = conversion.bundle;
+//! assert_eq!(bundle.format("welcome", Some(&arg("0", "Jo"))).unwrap(),
+//!            "Welcome, Jo! You're in.");
+//! assert_eq!(bundle.format("songs", Some(&arg("count", 3).arg("0", 3))).unwrap(),
+//!            "3 songs");
+//! ```
+//!
+//! The text of a resource is unescaped as Android does: `\'`, `\"`,
+//! `\@`, `\?`, `\\`, `\n`, `\t` and `\u` escapes are replaced, the
+//! quotes around quoted text are removed, and whitespace outside of
+//! quotes is collapsed into a single space and trimmed.
+//!
+//! Format specifiers become simple formats of the argument's position,
+//! counting from 0, so `%1$s` and the first `%s` or `%d` become `{0}`.
+//! `%%` is `%`, and `%n` is a new line. A `<plurals>` becomes a
+//! `plural` format on a variable, `count` by default, and as with
+//! Android, the number is usually also passed as the first argument.
+//! Styling tags, like `<b>`, are kept as text, and `<xliff:g>` elements
+//! are replaced by their text.
+//!
+//! Other resources, like `<string-array>`, and references to other
+//! resources have no ICU equivalent, and are reported as
+//! [`Diagnostic`]s.
+//!
+//! [Android string resources]: https://developer.android.com/guide/topics/resources/string-resource
+//! [`Diagnostic`]: type.Diagnostic.html
+
+use std::fmt;
+
+use super::printf::{self, specifier};
+use super::Lines;
+use icu::ast::{PlainText, PluralFormat, SimpleFormat};
+use xml::{self, Element, Node};
+use {BundleError, Context, Message, MessageBundle, MessagePart, PluralCategory};
+
+/// Options for [`convert`].
+///
+/// [`convert`]: fn.convert.html
+#[derive(Clone,Debug,PartialEq)]
+pub struct AndroidOptions {
+    /// The variable of the `plural` formats that `<plurals>` become.
+    /// The default is `count`.
+    pub count_variable: String,
+}
+
+impl Default for AndroidOptions {
+    fn default() -> Self {
+        AndroidOptions { count_variable: "count".to_string() }
+    }
+}
+
+/// The result of converting Android string resources with
+/// [`convert`].
+///
+/// [`convert`]: fn.convert.html
+pub type Conversion = super::Conversion<DiagnosticKind>;
+
+/// A problem with a resource of a `strings.xml` file. Its `id` is the
+/// name of the resource, which is empty if it has no name, and its
+/// `line` is the line that the resource starts on.
+pub type Diagnostic = super::Diagnostic<DiagnosticKind>;
+
+/// The kinds of [`Diagnostic`]. The resource is still converted for
+/// `InvalidQuantity` and `FormatSpecifier`, and isn't otherwise.
+///
+/// [`Diagnostic`]: type.Diagnostic.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum DiagnosticKind {
+    /// The resource has no `name`.
+    MissingName,
+    /// The resource, or an element of a `<plurals>`, isn't a string.
+    /// Has the name of the element, like `string-array`.
+    Unsupported(String),
+    /// The resource refers to another resource, like `@string/app_name`.
+    /// Has the reference.
+    Reference(String),
+    /// An `<item>` of a `<plurals>` has a quantity that isn't a plural
+    /// category, and was left out. Has the quantity.
+    InvalidQuantity(String),
+    /// A `<plurals>` has no item for the `other` quantity.
+    MissingOther,
+    /// A format specifier has flags, a width or a precision, like
+    /// `%.2f`, which were left out. Has the specifier.
+    FormatSpecifier(String),
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            DiagnosticKind::MissingName => "The resource has no name.".fmt(f),
+            DiagnosticKind::Unsupported(ref element) => {
+                write!(f, "'<{}>' has no ICU equivalent and was left out.", element)
+            }
+            DiagnosticKind::Reference(ref reference) => {
+                write!(f, "The reference to '{}' has no ICU equivalent.", reference)
+            }
+            DiagnosticKind::InvalidQuantity(ref quantity) => {
+                write!(f, "Quantity '{}' isn't a plural category and was left out.", quantity)
+            }
+            DiagnosticKind::MissingOther => "The plurals have no 'other' item.".fmt(f),
+            DiagnosticKind::FormatSpecifier(ref specifier) => {
+                write!(f,
+                       "The flags, width and precision of '{}' have no ICU equivalent.",
+                       specifier)
+            }
+        }
+    }
+}
+
+/// Convert the `<string>` and `<plurals>` resources of a `strings.xml`
+/// file into ICU messages in a bundle with `context`. The problems with
+/// each resource are reported, and the rest of the resources are
+/// converted. An error is only returned if the file isn't well-formed
+/// XML or has no `<resources>`.
+pub fn convert(context: Context,
+               resources: &str,
+               options: &AndroidOptions)
+               -> Result<Conversion, BundleError> {
+    let lines = Lines::new(resources);
+    let invalid = |offset, message: String| {
+        BundleError::Line(lines.line(offset), Box::new(BundleError::InvalidResource(message)))
+    };
+    let root = xml::parse(resources).map_err(|e| invalid(e.offset, format!("{}.", e.message)))?;
+    if root.name != "resources" {
+        return Err(invalid(root.offset, "Expected Android resources.".to_string()));
+    }
+    let mut conversion = Conversion {
+        bundle: MessageBundle::new(context),
+        diagnostics: vec![],
+    };
+    for element in root.elements() {
+        let name = element.attribute("name").unwrap_or("");
+        let mut kinds = vec![];
+        let message = match &*element.name {
+            "eat-comment" | "skip" => continue,
+            "string" | "plurals" if name.is_empty() => {
+                kinds.push(DiagnosticKind::MissingName);
+                None
+            }
+            "string" => lower_string(element, &mut kinds),
+            "plurals" => lower_plurals(element, &options.count_variable, &mut kinds),
+            other => {
+                kinds.push(DiagnosticKind::Unsupported(other.to_string()));
+                None
+            }
+        };
+        if let Some(message) = message {
+            conversion.bundle.insert_message(name, message);
+        }
+        conversion.diagnostics.extend(kinds.into_iter().map(|kind| {
+            Diagnostic { id: name.to_string(), line: Some(lines.line(element.offset)), kind }
+        }));
+    }
+    Ok(conversion)
+}
+
+/// Build the message for a `<string>` or an `<item>` of a `<plurals>`.
+fn lower_string(element: &Element, kinds: &mut Vec<DiagnosticKind>) -> Option<Message> {
+    let raw = element.text();
+    if raw.trim_start().starts_with(['@', '?']) {
+        kinds.push(DiagnosticKind::Reference(raw.trim().to_string()));
+        return None;
+    }
+    let mut unescaper = Unescaper { text: String::new(), quoted: false, space: false };
+    unescaper.element(element);
+    if element.attribute("formatted") == Some("false") {
+        return Some(Message::new(vec![Box::new(PlainText::new(&unescaper.text))]));
+    }
+    Some(lower_format(&unescaper.text, kinds))
+}
+
+fn lower_plurals(element: &Element,
+                 variable: &str,
+                 kinds: &mut Vec<DiagnosticKind>)
+                 -> Option<Message> {
+    let mut other = None;
+    let mut branches = vec![];
+    for item in element.elements() {
+        if item.name != "item" {
+            kinds.push(DiagnosticKind::Unsupported(item.name.clone()));
+            continue;
+        }
+        let quantity = item.attribute("quantity").unwrap_or("");
+        let category = match quantity {
+            "zero" | "one" | "two" | "few" | "many" | "other" => quantity.parse().ok(),
+            _ => None,
+        };
+        match category {
+            Some(PluralCategory::Other) => other = Some(lower_string(item, kinds)?),
+            Some(category) => branches.push((category, lower_string(item, kinds)?)),
+            None => kinds.push(DiagnosticKind::InvalidQuantity(quantity.to_string())),
+        }
+    }
+    let other = match other {
+        Some(other) => other,
+        None => {
+            kinds.push(DiagnosticKind::MissingOther);
+            return None;
+        }
+    };
+    let mut fmt = PluralFormat::new(variable, other);
+    for (category, message) in branches {
+        match category {
+            PluralCategory::Zero => fmt.zero(message),
+            PluralCategory::One => fmt.one(message),
+            PluralCategory::Two => fmt.two(message),
+            PluralCategory::Few => fmt.few(message),
+            PluralCategory::Many => fmt.many(message),
+            PluralCategory::Other => {}
+        }
+    }
+    Some(Message::new(vec![Box::new(fmt)]))
+}
+
+/// Build a message from unescaped text, with its format specifiers as
+/// simple formats.
+fn lower_format(text: &str, kinds: &mut Vec<DiagnosticKind>) -> Message {
+    let mut parts: Vec<Box<dyn MessagePart>> = vec![];
+    let mut plain = String::new();
+    // The index of the next argument without an explicit index.
+    let mut next = 0;
+    let mut rest = text;
+    while let Some(i) = rest.find('%') {
+        plain.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if rest.starts_with("%%") || rest.starts_with("%n") {
+            plain.push(if rest.starts_with("%%") { '%' } else { '\n' });
+            rest = &rest[2..];
+            continue;
+        }
+        let specifier = match specifier(rest, &printf::JAVA) {
+            Some(specifier) => specifier,
+            None => {
+                plain.push('%');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        if specifier.styled {
+            kinds.push(DiagnosticKind::FormatSpecifier(rest[..specifier.len].to_string()));
+        }
+        let index = specifier.position.unwrap_or_else(|| {
+            next += 1;
+            next
+        });
+        if !plain.is_empty() {
+            parts.push(Box::new(PlainText::new(&plain)));
+            plain.clear();
+        }
+        parts.push(Box::new(SimpleFormat::new(&(index - 1).to_string())));
+        rest = &rest[specifier.len..];
+    }
+    plain.push_str(rest);
+    if !plain.is_empty() {
+        parts.push(Box::new(PlainText::new(&plain)));
+    }
+    Message::new(parts)
+}
+
+/// Unescapes the text of a resource as Android does, keeping the
+/// state of quoting and whitespace across the elements within it.
+struct Unescaper {
+    text: String,
+    /// Whether the text is within double quotes.
+    quoted: bool,
+    /// Whether there is whitespace to collapse before the next
+    /// character.
+    space: bool,
+}
+
+impl Unescaper {
+    fn push(&mut self, ch: char) {
+        if self.space && !self.text.is_empty() {
+            self.text.push(' ');
+        }
+        self.space = false;
+        self.text.push(ch);
+    }
+
+    fn push_str(&mut self, text: &str) {
+        text.chars().for_each(|ch| self.push(ch));
+    }
+
+    /// Add the content of an element, keeping styling tags and
+    /// replacing `<xliff:g>` elements with their content.
+    fn element(&mut self, element: &Element) {
+        for node in &element.children {
+            match *node {
+                Node::Text(ref text) => self.text(text),
+                Node::Element(ref inline) if inline.name == "g" => self.element(inline),
+                Node::Element(ref inline) => {
+                    let mut tag = format!("<{}", inline.name);
+                    for (name, value) in &inline.attributes {
+                        tag.push_str(&format!(" {}=\"{}\"", name, value));
+                    }
+                    tag.push('>');
+                    self.push_str(&tag);
+                    self.element(inline);
+                    self.push_str(&format!("</{}>", inline.name));
+                }
+            }
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        let mut chars = text.chars();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\\' => {
+                    match chars.next() {
+                        Some('n') => self.push('\n'),
+                        Some('t') => self.push('\t'),
+                        Some('u') => {
+                            let hex = chars.as_str().get(..4).unwrap_or("");
+                            match u32::from_str_radix(hex, 16).ok().and_then(char::from_u32) {
+                                Some(ch) => {
+                                    self.push(ch);
+                                    chars.nth(3);
+                                }
+                                None => self.push('u'),
+                            }
+                        }
+                        Some(ch) => self.push(ch),
+                        None => {}
+                    }
+                }
+                '"' => self.quoted = !self.quoted,
+                ' ' | '\t' | '\n' | '\r' if !self.quoted => self.space = true,
+                ch => self.push(ch),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert, AndroidOptions, Diagnostic, DiagnosticKind};
+    use {arg, Context, MessageBundle};
+
+    fn bundle(strings: &str) -> MessageBundle {
+        let resources = format!("<resources>\n{}\n</resources>", strings);
+        let conversion = convert(Context::default(), &resources, &AndroidOptions::default())
+            .unwrap();
+        assert_eq!(conversion.diagnostics, []);
+        conversion.bundle
+    }
+
+    #[test]
+    fn escapes_and_whitespace() {
+        let bundle = bundle(r#"<string name="apostrophe">Don\'t \"panic\"</string>
+            <string name="quoted">"Don't   panic"  now </string>
+            <string name="whitespace">
+                Two
+                lines\nand\ta \\ \@ \? é
+            </string>
+            <string name="entities">Tom &amp; Jerry &lt;3</string>
+            <string name="empty"></string>"#);
+        let format = |key| bundle.format(key, None).unwrap();
+        assert_eq!(format("apostrophe"), "Don't \"panic\"");
+        assert_eq!(format("quoted"), "Don't   panic now");
+        assert_eq!(format("whitespace"), "Two lines\nand\ta \\ @ ? é");
+        assert_eq!(format("entities"), "Tom & Jerry <3");
+        assert_eq!(format("empty"), "");
+    }
+
+    #[test]
+    fn only_xml_whitespace_is_collapsed() {
+        let bundle = bundle("<string name=\"nbsp\">50\u{a0}\u{a0}km</string>");
+        assert_eq!(bundle.format("nbsp", None).unwrap(), "50\u{a0}\u{a0}km");
+    }
+
+    #[test]
+    fn format_specifiers() {
+        let bundle = bundle(r#"<string name="positional">%2$s sent %1$d files to %2$s.</string>
+            <string name="sequential">%s has %d%% left%n</string>
+            <string name="unformatted" formatted="false">%s of %d</string>
+            <string name="unknown">100% sure, 50%</string>"#);
+        let format = |key| bundle.format(key, Some(&arg("0", 3).arg("1", "Jo"))).unwrap();
+        assert_eq!(format("positional"), "Jo sent 3 files to Jo.");
+        assert_eq!(format("sequential"), "3 has Jo% left\n");
+        assert_eq!(format("unformatted"), "%s of %d");
+        assert_eq!(format("unknown"), "100% sure, 50%");
+    }
+
+    #[test]
+    fn plurals() {
+        let bundle = bundle(r#"<plurals name="files">
+                <item quantity="zero">No files</item>
+                <item quantity="one">One file</item>
+                <item quantity="other"><xliff:g id="count">%d</xliff:g> files</item>
+            </plurals>"#);
+        let format = |count| bundle.format("files", Some(&arg("count", count).arg("0", count)));
+        assert_eq!(format(1).unwrap(), "One file");
+        assert_eq!(format(0).unwrap(), "0 files");
+        assert_eq!(format(5).unwrap(), "5 files");
+
+        let resources = "<resources><plurals name='n'><item quantity='one'>%d</item></plurals>\
+                         </resources>";
+        let options = AndroidOptions { count_variable: "n".to_string() };
+        let conversion = convert(Context::default(), resources, &options).unwrap();
+        assert_eq!(conversion.diagnostics[0].kind, DiagnosticKind::MissingOther);
+        assert!(conversion.bundle.is_empty());
+    }
+
+    #[test]
+    fn styling() {
+        let bundle = bundle(r#"<string name="styled">Hello, <b>%1$s</b>!
+            <a href="https://example.com">More</a></string>"#);
+        assert_eq!(bundle.format("styled", Some(&arg("0", "Jo"))).unwrap(),
+                   "Hello, <b>Jo</b>! <a href=\"https://example.com\">More</a>");
+    }
+
+    #[test]
+    fn diagnostics() {
+        let resources = r#"<?xml version="1.0" encoding="utf-8"?>
+<resources>
+    <string name="title">Title</string>
+    <string-array name="planets">
+        <item>Mercury</item>
+    </string-array>
+    <string name="app_name">@string/title</string>
+    <string>No name</string>
+    <eat-comment/>
+    <plurals name="days">
+        <item quantity="couple">A couple of days</item>
+        <item quantity="other">%1$.1f days</item>
+    </plurals>
+</resources>"#;
+        let conversion = convert(Context::default(), resources, &AndroidOptions::default())
+            .unwrap();
+        let diagnostic = |id: &str, line, kind| {
+            Diagnostic { id: id.to_string(), line: Some(line), kind }
+        };
+        assert_eq!(conversion.diagnostics,
+                   [diagnostic("planets", 4, DiagnosticKind::Unsupported("string-array".into())),
+                    diagnostic("app_name", 7, DiagnosticKind::Reference("@string/title".into())),
+                    diagnostic("", 8, DiagnosticKind::MissingName),
+                    diagnostic("days", 10, DiagnosticKind::InvalidQuantity("couple".into())),
+                    diagnostic("days", 10, DiagnosticKind::FormatSpecifier("%1$.1f".into()))]);
+        assert_eq!(conversion.diagnostics[1].to_string(),
+                   "Line 7: 'app_name': The reference to '@string/title' has no ICU equivalent.");
+        assert_eq!(conversion.bundle.keys().collect::<Vec<_>>(), ["days", "title"]);
+        assert_eq!(conversion.bundle.format("days", Some(&arg("count", 2).arg("0", 2))).unwrap(),
+                   "2 days");
+    }
+
+    #[test]
+    fn invalid_resources() {
+        let options = AndroidOptions::default();
+        assert!(convert(Context::default(), "<resources>", &options).is_err());
+        assert!(convert(Context::default(), "<xliff/>", &options).is_err());
+    }
+}
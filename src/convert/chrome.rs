@@ -0,0 +1,556 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! chrome.i18n
+//!
+//! This converts the [`messages.json`] files of browser extensions into
+//! ICU messages, and writes bundles back as `messages.json`:
+//!
+//! ```
+//! use message_format::{arg, Context};
+//! use message_format::convert::chrome;
+//!
+//! let conversion = chrome::convert(Context::default(), r#"{
+//!     "greeting": {
+//!         "message": "Hello, $USER$! You have $1 new messages.",
+//!         "description": "Greets the user.",
+//!         "placeholders": {
+//!             "user": {"content": "$2", "example": "Jo"}
+//!         }
+//!     }
+//! }"#).unwrap();
+//! assert!(conversion.diagnostics.is_empty());
+//! let bundle = conversion.bundle;
+//! let args = arg("user", "Jo");
+//! assert_eq!(bundle.format("greeting", Some(&args.arg("0", 3))).unwrap(),
+//!            "Hello, Jo! You have 3 new messages.");
+//! assert_eq!(bundle.metadata("greeting").unwrap().description.as_ref().unwrap(),
+//!            "Greets the user.");
+//! assert!(chrome::export(&bundle).unwrap().contains(r#""content": "$2""#));
+//! ```
+//!
+//! A placeholder, like `$USER$`, whose content is a substitution, like
+//! `$2`, becomes a simple format of the placeholder's name as it is
+//! written in the `placeholders`, `{user}`, since the names of
+//! placeholders aren't case-sensitive. The content of other placeholders is written in its
+//! place, with its substitutions. A substitution outside of a
+//! placeholder, like `$1`, becomes a simple format of its position,
+//! counting from 0, so `$1` is `{0}`, and `$$` is `$`.
+//!
+//! The description of a message and the examples of its placeholders
+//! are kept in its [`MessageMetadata`], so that [`export`] can write
+//! them back.
+//!
+//! [`messages.json`]: https://developer.chrome.com/docs/extensions/reference/api/i18n
+//! [`MessageMetadata`]: ../../struct.MessageMetadata.html
+//! [`export`]: fn.export.html
+
+use std::error::Error;
+use std::fmt::{self, Write};
+
+use icu::ast::{PlainText, SimpleFormat};
+use json::{self, JsonValue};
+use writers::JsonEscapingWriter;
+use {BundleError, Context, Message, MessageBundle, MessageMetadata, MessagePart, MessageVisitor,
+     Placeholder, PluralCategory};
+
+/// A message can have this many substitutions, `$1` to `$9`.
+const MAX_SUBSTITUTIONS: usize = 9;
+
+/// The result of converting a `messages.json` file with [`convert`].
+///
+/// [`convert`]: fn.convert.html
+pub type Conversion = super::Conversion<DiagnosticKind>;
+
+/// A problem with a message of a `messages.json` file. Its `id` is the
+/// name of the message, and it has no `line`.
+pub type Diagnostic = super::Diagnostic<DiagnosticKind>;
+
+/// The kinds of [`Diagnostic`]. The message isn't converted for any of
+/// them.
+///
+/// [`Diagnostic`]: type.Diagnostic.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum DiagnosticKind {
+    /// The message isn't an object with a `message` string, or its
+    /// `description` or `placeholders` are malformed.
+    Malformed,
+    /// The message uses a placeholder that isn't defined. Has the name
+    /// of the placeholder.
+    UnknownPlaceholder(String),
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            DiagnosticKind::Malformed => "The message is malformed.".fmt(f),
+            DiagnosticKind::UnknownPlaceholder(ref name) => {
+                write!(f, "Unknown placeholder '{}'.", name)
+            }
+        }
+    }
+}
+
+/// Convert the messages of a `messages.json` file into ICU messages in
+/// a bundle with `context`. The problems with each message are
+/// reported, and the rest of the messages are converted. An error is
+/// only returned if the file isn't a JSON object.
+pub fn convert(context: Context, json: &str) -> Result<Conversion, BundleError> {
+    let value = json::parse(json).map_err(|e| BundleError::InvalidResource(e.to_string()))?;
+    let members = value.as_object()
+        .ok_or_else(|| BundleError::InvalidResource("Expected an object.".to_string()))?;
+    let mut conversion = Conversion {
+        bundle: MessageBundle::new(context),
+        diagnostics: vec![],
+    };
+    for (id, entry) in members {
+        match lower_entry(entry) {
+            Ok((message, metadata)) => {
+                conversion.bundle.insert_message(id, message);
+                if metadata != MessageMetadata::default() {
+                    conversion.bundle.insert_metadata(id, metadata);
+                }
+            }
+            Err(kind) => {
+                conversion.diagnostics.push(Diagnostic { id: id.clone(), line: None, kind })
+            }
+        }
+    }
+    Ok(conversion)
+}
+
+fn lower_entry(entry: &JsonValue) -> Result<(Message, MessageMetadata), DiagnosticKind> {
+    let text = |value: &JsonValue, field| match value.get(field) {
+        Some(JsonValue::String(text)) => Ok(Some(text.clone())),
+        Some(_) => Err(DiagnosticKind::Malformed),
+        None => Ok(None),
+    };
+    let message = text(entry, "message")?.ok_or(DiagnosticKind::Malformed)?;
+    let mut metadata = MessageMetadata {
+        description: text(entry, "description")?,
+        placeholders: vec![],
+    };
+    // The names and contents of the placeholders.
+    let mut placeholders = vec![];
+    if let Some(members) = entry.get("placeholders") {
+        for (name, placeholder) in members.as_object().ok_or(DiagnosticKind::Malformed)? {
+            let content = text(placeholder, "content")?.ok_or(DiagnosticKind::Malformed)?;
+            metadata.placeholders.push(Placeholder {
+                name: name.clone(),
+                example: text(placeholder, "example")?,
+                ..Placeholder::default()
+            });
+            placeholders.push((name.clone(), content));
+        }
+    }
+    let mut parts: Vec<Box<dyn MessagePart>> = vec![];
+    let mut plain = String::new();
+    lower(&message, Some(&placeholders), &mut plain, &mut parts)?;
+    if !plain.is_empty() {
+        parts.push(Box::new(PlainText::new(&plain)));
+    }
+    Ok((Message::new(parts), metadata))
+}
+
+/// Add the parts of the text of a message, or of the content of a
+/// placeholder if there are no `placeholders`, to `parts`, collecting
+/// plain text in `plain`.
+fn lower(text: &str,
+         placeholders: Option<&[(String, String)]>,
+         plain: &mut String,
+         parts: &mut Vec<Box<dyn MessagePart>>)
+         -> Result<(), DiagnosticKind> {
+    let mut rest = text;
+    while let Some(i) = rest.find('$') {
+        plain.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if rest.starts_with("$$") {
+            plain.push('$');
+            rest = &rest[2..];
+            continue;
+        }
+        if let Some(position) = substitution(rest) {
+            push_variable(&(position - 1).to_string(), plain, parts);
+            rest = &rest[2..];
+            continue;
+        }
+        let name = rest[1..].find('$')
+            .map(|end| &rest[1..end + 1])
+            .filter(|name| !name.is_empty() && name.chars().all(is_name_char));
+        match (name, placeholders) {
+            (Some(name), Some(placeholders)) => {
+                rest = &rest[name.len() + 2..];
+                let (name, content) = placeholders.iter()
+                    .find(|&(n, _)| n.eq_ignore_ascii_case(name))
+                    .ok_or_else(|| DiagnosticKind::UnknownPlaceholder(name.to_string()))?;
+                if content.len() == 2 && substitution(content).is_some() {
+                    push_variable(name, plain, parts);
+                } else {
+                    lower(content, None, plain, parts)?;
+                }
+            }
+            _ => {
+                plain.push('$');
+                rest = &rest[1..];
+            }
+        }
+    }
+    plain.push_str(rest);
+    Ok(())
+}
+
+/// The position of the substitution at the start of `text`, like `$1`.
+fn substitution(text: &str) -> Option<usize> {
+    match *text.as_bytes() {
+        [b'$', digit @ b'1'..=b'9', ..] => Some((digit - b'0') as usize),
+        _ => None,
+    }
+}
+
+/// Whether a character may be in the name of a message or placeholder.
+fn is_name_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '_' || ch == '@'
+}
+
+fn push_variable(name: &str, plain: &mut String, parts: &mut Vec<Box<dyn MessagePart>>) {
+    if !plain.is_empty() {
+        parts.push(Box::new(PlainText::new(plain)));
+        plain.clear();
+    }
+    parts.push(Box::new(SimpleFormat::new(name)));
+}
+
+/// An error exporting messages with [`export`].
+///
+/// [`export`]: fn.export.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum ExportError {
+    /// The key of a message has characters other than ASCII letters,
+    /// digits, `_` and `@`, which the name of a message can't have. Has
+    /// the key.
+    InvalidKey(String),
+    /// The message isn't valid. Has its key, then the error.
+    InvalidMessage(String, BundleError),
+    /// The message uses a variable other than as a simple format, like
+    /// in a `plural`, or the name of the variable can't be the name of
+    /// a placeholder. Has the key, then the variable.
+    Unsupported(String, String),
+    /// The message has more than 9 arguments. Has its key.
+    TooManyArguments(String),
+}
+
+impl Error for ExportError {}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            ExportError::InvalidKey(ref key) => write!(f, "Invalid message name '{}'.", key),
+            ExportError::InvalidMessage(ref key, ref error) => {
+                write!(f, "Message '{}' is invalid: {}", key, error)
+            }
+            ExportError::Unsupported(ref key, ref variable) => {
+                write!(f,
+                       "Message '{}' uses '{}' in a way that messages.json can't express.",
+                       key,
+                       variable)
+            }
+            ExportError::TooManyArguments(ref key) => {
+                write!(f, "Message '{}' has more than 9 arguments.", key)
+            }
+        }
+    }
+}
+
+/// Write the messages of `bundle` as a `messages.json` file, in the
+/// order of their keys.
+///
+/// The messages may only have text and simple formats. A simple format
+/// of a position, like `{0}`, is written as a substitution, `$1`, and
+/// one of a name, like `{user}`, as a placeholder, `$USER$`, whose
+/// content is the next substitution after the positions that the
+/// message uses. The description of a message and the examples of its
+/// placeholders are written from its [`MessageMetadata`]. The errors
+/// for all of the messages that can't be written are returned
+/// together.
+///
+/// [`MessageMetadata`]: ../../struct.MessageMetadata.html
+pub fn export(bundle: &MessageBundle) -> Result<String, Vec<ExportError>> {
+    let mut json = String::from("{");
+    let mut errors = vec![];
+    for key in bundle.keys() {
+        match export_message(bundle, key) {
+            Ok(message) => {
+                if json.len() > 1 {
+                    json.push(',');
+                }
+                json.push_str("\n  ");
+                write_string(key, &mut json);
+                json.push_str(": ");
+                json.push_str(&message);
+            }
+            Err(error) => errors.push(error),
+        }
+    }
+    json.push_str("\n}\n");
+    if errors.is_empty() { Ok(json) } else { Err(errors) }
+}
+
+/// Write the object for the message with `key`.
+fn export_message(bundle: &MessageBundle, key: &str) -> Result<String, ExportError> {
+    if key.is_empty() || !key.chars().all(is_name_char) {
+        return Err(ExportError::InvalidKey(key.to_string()));
+    }
+    let message = bundle.try_get(key)
+        .map_err(|error| ExportError::InvalidMessage(key.to_string(), error))?;
+    let mut pieces = Pieces::default();
+    message.accept(&mut pieces);
+    if let Some(variable) = pieces.unsupported {
+        return Err(ExportError::Unsupported(key.to_string(), variable));
+    }
+    let position = |name: &str| name.parse::<usize>().ok().filter(|&p| p < MAX_SUBSTITUTIONS);
+    let mut last = pieces.pieces
+        .iter()
+        .filter_map(|piece| match *piece {
+            Piece::Variable(ref name) => position(name).map(|p| p + 1),
+            Piece::Text(_) => None,
+        })
+        .max()
+        .unwrap_or(0);
+    let mut text = String::new();
+    // The names of the placeholders, with their substitutions.
+    let mut placeholders: Vec<(&str, usize)> = vec![];
+    for piece in &pieces.pieces {
+        match *piece {
+            Piece::Text(ref plain) => text.push_str(&plain.replace('$', "$$")),
+            Piece::Variable(ref name) => {
+                if let Some(position) = position(name) {
+                    text.push_str(&format!("${}", position + 1));
+                    continue;
+                }
+                if name.is_empty() || !name.chars().all(is_name_char) {
+                    return Err(ExportError::Unsupported(key.to_string(), name.clone()));
+                }
+                if placeholders.iter().all(|&(n, _)| n != name) {
+                    last += 1;
+                    if last > MAX_SUBSTITUTIONS {
+                        return Err(ExportError::TooManyArguments(key.to_string()));
+                    }
+                    placeholders.push((name, last));
+                }
+                text.push_str(&format!("${}$", name.to_uppercase()));
+            }
+        }
+    }
+    let metadata = bundle.metadata(key);
+    let mut json = String::from("{\n    \"message\": ");
+    write_string(&text, &mut json);
+    if let Some(description) = metadata.and_then(|m| m.description.as_ref()) {
+        json.push_str(",\n    \"description\": ");
+        write_string(description, &mut json);
+    }
+    if !placeholders.is_empty() {
+        json.push_str(",\n    \"placeholders\": {");
+        for (index, &(name, position)) in placeholders.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            json.push_str("\n      ");
+            write_string(name, &mut json);
+            json.push_str(&format!(": {{\n        \"content\": \"${}\"", position));
+            let example = metadata.and_then(|m| m.placeholders.iter().find(|p| p.name == name))
+                .and_then(|placeholder| placeholder.example.as_ref());
+            if let Some(example) = example {
+                json.push_str(",\n        \"example\": ");
+                write_string(example, &mut json);
+            }
+            json.push_str("\n      }");
+        }
+        json.push_str("\n    }");
+    }
+    json.push_str("\n  }");
+    Ok(json)
+}
+
+/// Write a JSON string.
+fn write_string(text: &str, json: &mut String) {
+    json.push('"');
+    let mut writer = JsonEscapingWriter::new(&mut *json);
+    let _ = writer.write_str(text);
+    json.push('"');
+}
+
+/// A piece of a message that can be written to `messages.json`.
+#[derive(Debug)]
+enum Piece {
+    Text(String),
+    Variable(String),
+}
+
+/// Collects the pieces of a message, and the first variable that is
+/// used other than as a simple format.
+#[derive(Debug,Default)]
+struct Pieces {
+    pieces: Vec<Piece>,
+    unsupported: Option<String>,
+}
+
+impl Pieces {
+    fn unsupported(&mut self, variable: &str) {
+        self.unsupported.get_or_insert_with(|| variable.to_string());
+    }
+}
+
+impl MessageVisitor for Pieces {
+    fn visit_text(&mut self, text: &str) {
+        self.pieces.push(Piece::Text(text.to_string()));
+    }
+
+    fn visit_placeholder(&mut self) {
+        self.unsupported("#");
+    }
+
+    fn visit_variable(&mut self, name: &str) {
+        self.pieces.push(Piece::Variable(name.to_string()));
+    }
+
+    fn visit_number(&mut self, name: &str) {
+        self.unsupported(name);
+    }
+
+    fn visit_duration(&mut self, name: &str) {
+        self.unsupported(name);
+    }
+
+    fn visit_relative_time(&mut self, name: &str) {
+        self.unsupported(name);
+    }
+
+    fn visit_spellout(&mut self, name: &str) {
+        self.unsupported(name);
+    }
+
+    fn visit_choice(&mut self, name: &str) {
+        self.unsupported(name);
+    }
+
+    fn visit_plural(&mut self, variable: &str, _: &[PluralCategory], _: &[i64]) {
+        self.unsupported(variable);
+    }
+
+    fn visit_select(&mut self, variable: &str, _: &[&str]) {
+        self.unsupported(variable);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert, export, Diagnostic, DiagnosticKind, ExportError};
+    use {arg, Context, MessageBundle, MessageMetadata, Placeholder};
+
+    #[test]
+    fn placeholders() {
+        let conversion = convert(Context::default(), r#"{
+            "total": {
+                "message": "$Count$ of $count$ in $Place$, $$ and $ and $A-B$ and $2",
+                "placeholders": {
+                    "count": {"content": "$1"},
+                    "place": {"content": "the \"$2\" folder"}
+                }
+            },
+            "plain": {"message": "{literal} 'text'"}
+        }"#).unwrap();
+        assert_eq!(conversion.diagnostics, []);
+        let bundle = conversion.bundle;
+        let args = arg("count", 3);
+        let args = args.arg("1", "Docs");
+        assert_eq!(bundle.format("total", Some(&args)).unwrap(),
+                   "3 of 3 in the \"Docs\" folder, $ and $ and $A-B$ and Docs");
+        assert_eq!(bundle.format("plain", None).unwrap(), "{literal} 'text'");
+        assert_eq!(bundle.metadata("total").unwrap().placeholders.len(), 2);
+        assert!(bundle.metadata("plain").is_none());
+    }
+
+    #[test]
+    fn diagnostics() {
+        let conversion = convert(Context::default(), r#"{
+            "unknown": {"message": "Hi $NAME$"},
+            "not_an_object": "Hi",
+            "no_message": {"description": "Nothing"},
+            "bad_placeholder": {"message": "$A$", "placeholders": {"a": {"example": "x"}}},
+            "ok": {"message": "OK"}
+        }"#).unwrap();
+        let diagnostic = |id: &str, kind| Diagnostic { id: id.to_string(), line: None, kind };
+        assert_eq!(conversion.diagnostics,
+                   [diagnostic("unknown", DiagnosticKind::UnknownPlaceholder("NAME".into())),
+                    diagnostic("not_an_object", DiagnosticKind::Malformed),
+                    diagnostic("no_message", DiagnosticKind::Malformed),
+                    diagnostic("bad_placeholder", DiagnosticKind::Malformed)]);
+        assert_eq!(conversion.diagnostics[0].to_string(), "'unknown': Unknown placeholder 'NAME'.");
+        assert_eq!(conversion.bundle.keys().collect::<Vec<_>>(), ["ok"]);
+        assert!(convert(Context::default(), "[]").is_err());
+    }
+
+    #[test]
+    fn export_round_trip() {
+        let mut bundle = MessageBundle::new(Context::default());
+        bundle.insert("greeting", "{userName} paid $5 to {1}, {userName}!").unwrap();
+        bundle.insert("title", "Say \"hi\"").unwrap();
+        bundle.insert_metadata("greeting", MessageMetadata {
+            description: Some("Shown after paying.".to_string()),
+            placeholders: vec![Placeholder {
+                name: "userName".to_string(),
+                example: Some("Jo".to_string()),
+                ..Placeholder::default()
+            }],
+        });
+        let json = export(&bundle).unwrap();
+        assert_eq!(json,
+                   r#"{
+  "greeting": {
+    "message": "$USERNAME$ paid $$5 to $2, $USERNAME$!",
+    "description": "Shown after paying.",
+    "placeholders": {
+      "userName": {
+        "content": "$3",
+        "example": "Jo"
+      }
+    }
+  },
+  "title": {
+    "message": "Say \"hi\""
+  }
+}
+"#);
+        let bundle = convert(Context::default(), &json).unwrap().bundle;
+        let args = arg("userName", "Jo");
+        let args = args.arg("1", "Al");
+        assert_eq!(bundle.format("greeting", Some(&args)).unwrap(), "Jo paid $5 to Al, Jo!");
+        assert_eq!(export(&bundle).unwrap(), json);
+    }
+
+    #[test]
+    fn export_errors() {
+        let mut bundle = MessageBundle::new(Context::default());
+        bundle.insert("plural", "{n, plural, one {# file} other {# files}}").unwrap();
+        bundle.insert("number", "{n, number}").unwrap();
+        bundle.insert("dotted.key", "Text").unwrap();
+        bundle.insert("dotted_name", "{user.name}").unwrap();
+        bundle.insert("many", "{a}{b}{c}{d}{e}{f}{g}{h}{i}{j}").unwrap();
+        bundle.insert_raw("raw", "{");
+        bundle.insert("ok", "OK").unwrap();
+        let errors = export(&bundle).unwrap_err();
+        let unsupported = |key: &str, variable: &str| {
+            ExportError::Unsupported(key.to_string(), variable.to_string())
+        };
+        assert_eq!(errors.len(), 6);
+        assert_eq!(errors[0], ExportError::InvalidKey("dotted.key".to_string()));
+        assert_eq!(errors[1], unsupported("dotted_name", "user.name"));
+        assert_eq!(errors[2], ExportError::TooManyArguments("many".to_string()));
+        assert_eq!(errors[3], unsupported("number", "n"));
+        assert_eq!(errors[4], unsupported("plural", "n"));
+        assert!(matches!(errors[5], ExportError::InvalidMessage(ref key, _) if key == "raw"));
+    }
+}
@@ -0,0 +1,869 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Fluent
+//!
+//! This converts [Fluent] resources, `.ftl` files, into ICU messages:
+//!
+//! ```
+//! use message_format::{arg, Context};
+//! use message_format::convert::fluent;
+//!
+//! let conversion = fluent::convert(Context::default(), r#"
+//! -brand = Photos
+//! welcome = Welcome to { -brand }, { $name }!
+//! photos = { $count ->
+//!     [one] One photo
+//!    *[other] { $count } photos
+//! }
+//! "#);
+//! assert!(conversion.diagnostics.is_empty());
+//! let bundle = conversion.bundle;
+//! assert_eq!(bundle.format("welcome", Some(&arg("name", "Jo"))).unwrap(),
+//!            "Welcome to Photos, Jo!");
+//! assert_eq!(bundle.format("photos", Some(&arg("count", 3))).unwrap(), "3 photos");
+//! ```
+//!
+//! Text and string literals become plain text, and variables become
+//! simple formats. A select expression becomes a `plural` format if its
+//! variant keys are numbers and plural categories, and a `select`
+//! format otherwise, with the default variant as the `other` branch.
+//! Terms without arguments are inlined into the messages that use them.
+//! The indentation of the lines of a multiline pattern is removed.
+//!
+//! Attributes, functions like `NUMBER`, references to other messages
+//! and terms with arguments have no ICU equivalent, and are reported
+//! as [`Diagnostic`]s.
+//!
+//! [Fluent]: https://projectfluent.org/
+//! [`Diagnostic`]: type.Diagnostic.html
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::Lines;
+use icu::ast::{PlainText, PluralFormat, SelectFormat, SimpleFormat};
+use nesting::Nesting;
+use {Context, Message, MessageBundle, MessagePart, PluralCategory};
+
+/// The result of converting a Fluent resource with [`convert`].
+///
+/// [`convert`]: fn.convert.html
+pub type Conversion = super::Conversion<DiagnosticKind>;
+
+/// A problem with a message or term of a Fluent resource. Its `id` is
+/// the identifier of the message, or of the term, like `-brand`, which
+/// is empty for text that isn't in a message or term. Its `line` is the
+/// line that the message or term starts on.
+pub type Diagnostic = super::Diagnostic<DiagnosticKind>;
+
+/// The kinds of [`Diagnostic`]. Except for `Attribute`, the message
+/// isn't converted.
+///
+/// [`Diagnostic`]: type.Diagnostic.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum DiagnosticKind {
+    /// The message or term is malformed. Has a description of the
+    /// problem.
+    Syntax(String),
+    /// The message has an attribute, like `.placeholder`, which was
+    /// left out. Has the name of the attribute.
+    Attribute(String),
+    /// The message calls a function, like `NUMBER`.
+    Function(String),
+    /// The message refers to another message.
+    MessageReference(String),
+    /// The message passes arguments to a term, like
+    /// `-brand(case: "genitive")`, uses an attribute of a term, or uses
+    /// a term whose value depends on its arguments.
+    ParameterizedTerm(String),
+    /// The message refers to a term that isn't defined.
+    UnknownTerm(String),
+    /// A select over a number has a variant key that isn't an integer.
+    InvalidVariantKey(String),
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            DiagnosticKind::Syntax(ref message) => message.fmt(f),
+            DiagnosticKind::Attribute(ref name) => {
+                write!(f, "Attribute '{}' has no ICU equivalent and was left out.", name)
+            }
+            DiagnosticKind::Function(ref name) => {
+                write!(f, "Function '{}' has no ICU equivalent.", name)
+            }
+            DiagnosticKind::MessageReference(ref id) => {
+                write!(f, "The reference to message '{}' has no ICU equivalent.", id)
+            }
+            DiagnosticKind::ParameterizedTerm(ref id) => {
+                write!(f, "Term '{}' with arguments has no ICU equivalent.", id)
+            }
+            DiagnosticKind::UnknownTerm(ref id) => write!(f, "Unknown term '{}'.", id),
+            DiagnosticKind::InvalidVariantKey(ref key) => {
+                write!(f, "Variant key '{}' isn't an integer or plural category.", key)
+            }
+        }
+    }
+}
+
+/// Convert the messages of a Fluent resource into ICU messages in a
+/// bundle with `context`. The problems with each message are reported,
+/// and the rest of the resource is converted.
+pub fn convert(context: Context, ftl: &str) -> Conversion {
+    let mut parser = FluentParser { source: ftl, pos: 0, lines: Lines::new(ftl), depth: 0 };
+    let mut entries = vec![];
+    let mut diagnostics = vec![];
+    while let Some(at_entry) = parser.next_line() {
+        let line = parser.line();
+        let start = parser.pos;
+        let result = if at_entry {
+            parser.get_entry()
+        } else {
+            Err(DiagnosticKind::Syntax("Expected a message, term or comment.".to_string()))
+        };
+        match result {
+            Ok(entry) => entries.push(entry),
+            Err(kind) => {
+                let id = ftl[start..]
+                    .split(|ch: char| !is_identifier_char(ch))
+                    .next()
+                    .unwrap_or("");
+                diagnostics.push(Diagnostic { id: id.to_string(), line: Some(line), kind });
+                parser.pos = start;
+                parser.skip_entry();
+            }
+        }
+    }
+
+    let (terms, messages): (Vec<_>, Vec<_>) =
+        entries.iter().partition(|entry| entry.id.starts_with('-'));
+    let terms = terms.into_iter()
+        .filter_map(|term| term.value.as_ref().map(|value| (&term.id[..], &value[..])))
+        .collect::<HashMap<_, _>>();
+    let mut bundle = MessageBundle::new(context);
+    for entry in messages {
+        let diagnostic = |kind| {
+            Diagnostic { id: entry.id.clone(), line: Some(entry.line), kind }
+        };
+        for attribute in &entry.attributes {
+            diagnostics.push(diagnostic(DiagnosticKind::Attribute(attribute.clone())));
+        }
+        if let Some(ref value) = entry.value {
+            match inline(value, &terms, &mut vec![]).and_then(|pattern| lower(&pattern)) {
+                Ok(message) => bundle.insert_message(&entry.id, message),
+                Err(kind) => diagnostics.push(diagnostic(kind)),
+            }
+        }
+    }
+    diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+    Conversion { bundle, diagnostics }
+}
+
+/// A part of the pattern of a message or term.
+#[derive(Clone,Debug,PartialEq)]
+enum Element {
+    Text(String),
+    Variable(String),
+    /// A reference to a term, with its identifier, like `-brand`.
+    Term(String),
+    Select {
+        variable: String,
+        variants: Vec<Variant>,
+    },
+}
+
+#[derive(Clone,Debug,PartialEq)]
+struct Variant {
+    key: String,
+    default: bool,
+    pattern: Vec<Element>,
+}
+
+/// A message or term.
+#[derive(Debug)]
+struct Entry {
+    id: String,
+    line: usize,
+    value: Option<Vec<Element>>,
+    /// The names of the attributes.
+    attributes: Vec<String>,
+}
+
+fn is_identifier_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '_' || ch == '-'
+}
+
+fn is_number(key: &str) -> bool {
+    key.parse::<f64>().is_ok()
+}
+
+fn syntax(message: &str) -> DiagnosticKind {
+    DiagnosticKind::Syntax(message.to_string())
+}
+
+struct FluentParser<'a> {
+    source: &'a str,
+    pos: usize,
+    lines: Lines<'a>,
+    /// The number of placeables around the position.
+    depth: usize,
+}
+
+impl<'a> Nesting for FluentParser<'a> {
+    type Error = DiagnosticKind;
+
+    /// A placeable is several calls deep, so fewer of them fit on the
+    /// stack.
+    const MAX_DEPTH: usize = 64;
+
+    fn depth(&mut self) -> &mut usize {
+        &mut self.depth
+    }
+
+    fn too_deep(&self) -> DiagnosticKind {
+        syntax("Too deeply nested.")
+    }
+}
+
+impl<'a> FluentParser<'a> {
+    fn ch(&self) -> Option<char> {
+        self.source[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) {
+        if let Some(ch) = self.ch() {
+            self.pos += ch.len_utf8();
+        }
+    }
+
+    fn ch_is(&self, ch: char) -> bool {
+        self.ch() == Some(ch)
+    }
+
+    fn at_newline(&self) -> bool {
+        self.ch_is('\n') || self.source[self.pos..].starts_with("\r\n")
+    }
+
+    /// The line of the current position, counting from 1.
+    fn line(&self) -> usize {
+        self.lines.line(self.pos)
+    }
+
+    fn expect(&mut self, ch: char) -> Result<(), DiagnosticKind> {
+        if self.ch_is(ch) {
+            self.bump();
+            Ok(())
+        } else {
+            Err(DiagnosticKind::Syntax(format!("Expected '{}'.", ch)))
+        }
+    }
+
+    fn skip_line(&mut self) {
+        match self.source[self.pos..].find('\n') {
+            Some(end) => self.pos += end + 1,
+            None => self.pos = self.source.len(),
+        }
+    }
+
+    /// Skip blank lines and comments up to the start of the next line
+    /// with content. Returns whether it starts a message or term, or
+    /// `None` at the end of the resource.
+    fn next_line(&mut self) -> Option<bool> {
+        loop {
+            let start = self.pos;
+            self.get_blank_inline();
+            match self.ch() {
+                None => return None,
+                _ if self.at_newline() => self.skip_line(),
+                Some('#') if self.pos == start => self.skip_line(),
+                Some(ch) => {
+                    let indented = self.pos != start;
+                    self.pos = start;
+                    return Some(!indented && (ch == '-' || ch.is_ascii_alphabetic()));
+                }
+            }
+        }
+    }
+
+    /// Skip the rest of a malformed entry, up to the next line that
+    /// isn't indented.
+    fn skip_entry(&mut self) {
+        self.skip_line();
+        while self.ch().is_some_and(|ch| !(ch == '-' || ch == '#' || ch.is_ascii_alphabetic())) {
+            self.skip_line();
+        }
+    }
+
+    fn get_blank_inline(&mut self) {
+        while self.ch_is(' ') {
+            self.bump();
+        }
+    }
+
+    /// Skip whitespace, including line breaks.
+    fn get_blank(&mut self) {
+        while self.ch().is_some_and(|ch| ch == ' ' || ch == '\n' || ch == '\r') {
+            self.bump();
+        }
+    }
+
+    fn get_identifier(&mut self) -> Result<&'a str, DiagnosticKind> {
+        let start = self.pos;
+        if self.ch().is_some_and(|ch| ch.is_ascii_alphabetic()) {
+            while self.ch().is_some_and(is_identifier_char) {
+                self.bump();
+            }
+        }
+        if start == self.pos {
+            return Err(syntax("Expected an identifier."));
+        }
+        Ok(&self.source[start..self.pos])
+    }
+
+    fn get_number(&mut self) -> &'a str {
+        let start = self.pos;
+        if self.ch_is('-') {
+            self.bump();
+        }
+        while self.ch().is_some_and(|ch| ch.is_ascii_digit() || ch == '.') {
+            self.bump();
+        }
+        &self.source[start..self.pos]
+    }
+
+    /// A message is an identifier, `=` and a pattern, followed by
+    /// attributes. A term is the same, with an identifier starting with
+    /// `-`.
+    fn get_entry(&mut self) -> Result<Entry, DiagnosticKind> {
+        let line = self.line();
+        let term = self.ch_is('-');
+        if term {
+            self.bump();
+        }
+        let name = self.get_identifier()?;
+        let id = if term { format!("-{}", name) } else { name.to_string() };
+        self.get_blank_inline();
+        self.expect('=')?;
+        let value = self.get_pattern(false)?;
+        let mut attributes = vec![];
+        let source = self.source;
+        let attribute = |&(_, pos): &(usize, usize)| source[pos..].starts_with('.');
+        while let Some((_, pos)) = self.continuation().filter(attribute) {
+            self.pos = pos + 1;
+            let name = self.get_identifier()?;
+            self.get_blank_inline();
+            self.expect('=')?;
+            if self.get_pattern(false)?.is_empty() {
+                return Err(DiagnosticKind::Syntax(format!("Attribute '{}' has no value.", name)));
+            }
+            attributes.push(name.to_string());
+        }
+        if self.ch().is_some() && self.continuation().is_some() {
+            return Err(syntax("Expected the end of the entry."));
+        }
+        if value.is_empty() && (term || attributes.is_empty()) {
+            return Err(syntax("The entry has no value."));
+        }
+        Ok(Entry {
+            id,
+            line,
+            value: if value.is_empty() { None } else { Some(value) },
+            attributes,
+        })
+    }
+
+    /// At a line break, the number of line breaks up to the next line
+    /// with content, and the position of its content, if that line is
+    /// indented and so continues the current entry.
+    fn continuation(&self) -> Option<(usize, usize)> {
+        let mut pos = self.pos;
+        let mut lines = 0;
+        loop {
+            let rest = &self.source[pos..];
+            if rest.starts_with("\r\n") {
+                pos += 2;
+            } else if rest.starts_with('\n') {
+                pos += 1;
+            } else {
+                return None;
+            }
+            lines += 1;
+            let line_start = pos;
+            while self.source[pos..].starts_with(' ') {
+                pos += 1;
+            }
+            let rest = &self.source[pos..];
+            if rest.is_empty() {
+                return None;
+            }
+            if !rest.starts_with('\n') && !rest.starts_with("\r\n") {
+                return if pos > line_start { Some((lines, pos)) } else { None };
+            }
+        }
+    }
+
+    /// A pattern is text and placeables, up through the end of the
+    /// line and any indented lines that follow it. Lines starting with
+    /// `.`, `[`, `*` or `}` aren't text, and end the pattern.
+    fn get_pattern(&mut self, in_variant: bool) -> Result<Vec<Element>, DiagnosticKind> {
+        let mut elements = vec![];
+        let mut text = String::new();
+        self.get_blank_inline();
+        loop {
+            match self.ch() {
+                None => break,
+                _ if self.at_newline() => {
+                    let continuation = self.continuation().filter(|&(_, pos)| {
+                        !self.source[pos..].starts_with(|c| ".[*}".contains(c))
+                    });
+                    let (lines, pos) = match continuation {
+                        Some(continuation) => continuation,
+                        None => break,
+                    };
+                    // A pattern may start on the line after the `=`.
+                    if !elements.is_empty() || !text.is_empty() {
+                        text.extend((0..lines).map(|_| '\n'));
+                    }
+                    self.pos = pos;
+                }
+                Some('{') => {
+                    if !text.is_empty() {
+                        elements.push(Element::Text(text.split_off(0)));
+                    }
+                    elements.push(self.get_placeable()?);
+                }
+                Some('}') if in_variant => break,
+                Some('}') => return Err(syntax("Unexpected '}'.")),
+                Some(ch) => {
+                    text.push(ch);
+                    self.bump();
+                }
+            }
+        }
+        let text = text.trim_end();
+        if !text.is_empty() {
+            elements.push(Element::Text(text.to_string()));
+        }
+        Ok(elements)
+    }
+
+    fn get_placeable(&mut self) -> Result<Element, DiagnosticKind> {
+        self.nested(Self::get_placeable_contents)
+    }
+
+    fn get_placeable_contents(&mut self) -> Result<Element, DiagnosticKind> {
+        self.expect('{')?;
+        self.get_blank();
+        let element = match self.ch() {
+            Some('"') => Element::Text(self.get_string()?),
+            Some('$') => {
+                self.bump();
+                let name = self.get_identifier()?.to_string();
+                self.get_blank();
+                if self.source[self.pos..].starts_with("->") {
+                    self.pos += "->".len();
+                    return self.get_select(name);
+                }
+                Element::Variable(name)
+            }
+            Some('-') if !self.source[self.pos + 1..].starts_with(char::is_numeric) => {
+                self.bump();
+                let id = format!("-{}", self.get_identifier()?);
+                self.get_blank_inline();
+                if self.ch_is('(') || self.ch_is('.') {
+                    return Err(DiagnosticKind::ParameterizedTerm(id));
+                }
+                Element::Term(id)
+            }
+            Some(ch) if ch == '-' || ch.is_ascii_digit() => {
+                Element::Text(self.get_number().to_string())
+            }
+            Some('{') => self.get_placeable()?,
+            Some(ch) if ch.is_ascii_alphabetic() => {
+                let name = self.get_identifier()?.to_string();
+                self.get_blank_inline();
+                if self.ch_is('(') {
+                    return Err(DiagnosticKind::Function(name));
+                }
+                return Err(DiagnosticKind::MessageReference(name));
+            }
+            _ => return Err(syntax("Expected an expression.")),
+        };
+        self.get_blank();
+        if self.source[self.pos..].starts_with("->") {
+            return Err(syntax("Only variables can be selected on."));
+        }
+        self.expect('}')?;
+        Ok(element)
+    }
+
+    /// A string literal, with its escapes replaced.
+    fn get_string(&mut self) -> Result<String, DiagnosticKind> {
+        self.expect('"')?;
+        let mut value = String::new();
+        loop {
+            match self.ch() {
+                Some('"') => {
+                    self.bump();
+                    return Ok(value);
+                }
+                Some('\\') => {
+                    self.bump();
+                    let digits = match self.ch() {
+                        Some('u') => 4,
+                        Some('U') => 6,
+                        Some(ch) if ch == '"' || ch == '\\' => {
+                            value.push(ch);
+                            self.bump();
+                            continue;
+                        }
+                        _ => return Err(syntax("Unknown escape sequence.")),
+                    };
+                    self.bump();
+                    let hex = self.source[self.pos..].get(..digits).unwrap_or("");
+                    let ch = u32::from_str_radix(hex, 16).ok().and_then(char::from_u32);
+                    value.push(ch.ok_or_else(|| syntax("Invalid escape sequence."))?);
+                    self.pos += digits;
+                }
+                Some(ch) if ch != '\n' && ch != '\r' => {
+                    value.push(ch);
+                    self.bump();
+                }
+                _ => return Err(syntax("Unterminated string.")),
+            }
+        }
+    }
+
+    /// The variants of a select expression, through its closing `}`.
+    /// Each variant is on a line of its own.
+    fn get_select(&mut self, variable: String) -> Result<Element, DiagnosticKind> {
+        let mut variants = vec![];
+        loop {
+            self.get_blank();
+            let default = self.ch_is('*');
+            if default {
+                self.bump();
+            } else if self.ch_is('}') {
+                self.bump();
+                break;
+            }
+            self.expect('[')?;
+            self.get_blank_inline();
+            let key = match self.ch() {
+                Some(ch) if ch == '-' || ch.is_ascii_digit() => self.get_number(),
+                _ => self.get_identifier()?,
+            };
+            self.get_blank_inline();
+            self.expect(']')?;
+            let pattern = self.get_pattern(true)?;
+            variants.push(Variant { key: key.to_string(), default, pattern });
+        }
+        if variants.iter().filter(|variant| variant.default).count() != 1 {
+            return Err(syntax("A select needs exactly one default variant."));
+        }
+        Ok(Element::Select { variable, variants })
+    }
+}
+
+/// Replace the references to terms in a `pattern` with their values.
+/// `inlining` has the terms that are being inlined, to find cycles.
+fn inline<'a>(pattern: &[Element],
+              terms: &HashMap<&'a str, &'a [Element]>,
+              inlining: &mut Vec<&'a str>)
+              -> Result<Vec<Element>, DiagnosticKind> {
+    let mut elements = vec![];
+    for element in pattern {
+        let inlined = match *element {
+            Element::Term(ref id) => {
+                let (&id, &value) = terms.get_key_value(&id[..])
+                    .ok_or_else(|| DiagnosticKind::UnknownTerm(id.clone()))?;
+                if inlining.contains(&id) {
+                    let message = format!("Term '{}' refers to itself.", id);
+                    return Err(DiagnosticKind::Syntax(message));
+                }
+                // Terms are inlined as deeply as placeables can be nested.
+                if inlining.len() == FluentParser::MAX_DEPTH {
+                    return Err(syntax("Too deeply nested."));
+                }
+                inlining.push(id);
+                let value = inline(value, terms, inlining)?;
+                inlining.pop();
+                if value.iter().any(|element| !matches!(*element, Element::Text(_))) {
+                    return Err(DiagnosticKind::ParameterizedTerm(id.to_string()));
+                }
+                value
+            }
+            Element::Select { ref variable, ref variants } => {
+                let variants = variants.iter()
+                    .map(|variant| {
+                        Ok(Variant {
+                            pattern: inline(&variant.pattern, terms, inlining)?,
+                            ..variant.clone()
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+                vec![Element::Select { variable: variable.clone(), variants }]
+            }
+            ref element => vec![element.clone()],
+        };
+        for element in inlined {
+            // Join text that was next to an inlined term.
+            match (elements.last_mut(), element) {
+                (Some(&mut Element::Text(ref mut text)), Element::Text(ref more)) => {
+                    text.push_str(more)
+                }
+                (_, element) => elements.push(element),
+            }
+        }
+    }
+    Ok(elements)
+}
+
+/// Build the ICU message for a `pattern` that has no references to
+/// terms.
+fn lower(pattern: &[Element]) -> Result<Message, DiagnosticKind> {
+    let mut parts: Vec<Box<dyn MessagePart>> = vec![];
+    for element in pattern {
+        match *element {
+            Element::Text(ref text) => parts.push(Box::new(PlainText::new(text))),
+            Element::Variable(ref name) => parts.push(Box::new(SimpleFormat::new(name))),
+            Element::Select { ref variable, ref variants } => {
+                parts.push(lower_select(variable, variants)?)
+            }
+            Element::Term(ref id) => return Err(DiagnosticKind::UnknownTerm(id.clone())),
+        }
+    }
+    Ok(Message::new(parts))
+}
+
+/// Build a `plural` or `select` format for a select expression. The
+/// `other` branch is the `[other]` variant, or the default variant if
+/// there isn't one.
+fn lower_select(variable: &str,
+                variants: &[Variant])
+                -> Result<Box<dyn MessagePart>, DiagnosticKind> {
+    let category = |key: &str| match key {
+        "zero" | "one" | "two" | "few" | "many" | "other" => key.parse::<PluralCategory>().ok(),
+        _ => None,
+    };
+    let numeric = |variant: &Variant| is_number(&variant.key) || category(&variant.key).is_some();
+    let plural = variants.iter().all(numeric) && variants.iter().any(|v| v.key != "other");
+    let other = variants.iter()
+        .position(|variant| variant.key == "other")
+        .or_else(|| variants.iter().position(|variant| variant.default))
+        .unwrap();
+    let others = variants.iter()
+        .enumerate()
+        .filter(|&(i, variant)| i != other || variant.key != "other")
+        .map(|(_, variant)| variant);
+    if !plural {
+        let mut fmt = SelectFormat::new(variable, lower(&variants[other].pattern)?);
+        for variant in others {
+            fmt.map(&variant.key, lower(&variant.pattern)?);
+        }
+        return Ok(Box::new(fmt));
+    }
+    let mut fmt = PluralFormat::new(variable, lower(&variants[other].pattern)?);
+    for variant in others {
+        let message = lower(&variant.pattern)?;
+        match category(&variant.key) {
+            Some(PluralCategory::Zero) => fmt.zero(message),
+            Some(PluralCategory::One) => fmt.one(message),
+            Some(PluralCategory::Two) => fmt.two(message),
+            Some(PluralCategory::Few) => fmt.few(message),
+            Some(PluralCategory::Many) => fmt.many(message),
+            Some(PluralCategory::Other) => {}
+            None => {
+                let literal = variant.key
+                    .parse()
+                    .map_err(|_| DiagnosticKind::InvalidVariantKey(variant.key.clone()))?;
+                fmt.literal(literal, message);
+            }
+        }
+    }
+    Ok(Box::new(fmt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert, syntax, Diagnostic, DiagnosticKind};
+    use {arg, Context, MessageBundle};
+
+    fn bundle(ftl: &str) -> MessageBundle {
+        let conversion = convert(Context::default(), ftl);
+        assert_eq!(conversion.diagnostics, []);
+        conversion.bundle
+    }
+
+    #[test]
+    fn simple_messages() {
+        let bundle = bundle("# A comment\n\
+                             hello = Hello, world!\n\
+                             \n\
+                             greeting = Hello, { $name }.\n\
+                             literal = { \"{\" }braces{ \"}\" } { 42 } { \"\\u00e9\" }\n\
+                             multiline =\n    First line,\n    second line.\n\
+                             \n\
+                             blank-lines = One\n\n    Two   \n");
+        let format = |key, name| bundle.format(key, Some(&arg("name", name))).unwrap();
+        assert_eq!(format("hello", ""), "Hello, world!");
+        assert_eq!(format("greeting", "Jo"), "Hello, Jo.");
+        assert_eq!(format("literal", ""), "{braces} 42 é");
+        assert_eq!(format("multiline", ""), "First line,\nsecond line.");
+        assert_eq!(format("blank-lines", ""), "One\n\nTwo");
+        assert_eq!(bundle.len(), 5);
+    }
+
+    #[test]
+    fn selects() {
+        let bundle = bundle("emails = { $count ->\n\
+                             \x20   [0] No emails\n\
+                             \x20   [one] One email\n\
+                             \x20  *[other] { $count } emails\n\
+                             \x20   }\n\
+                             shared = { $gender ->\n\
+                             \x20   [female] She\n\
+                             \x20   [male] He\n\
+                             \x20  *[other] They\n\
+                             } shared { $count ->\n\
+                             \x20  *[one] a photo\n\
+                             \x20   [other] photos\n\
+                             }.\n");
+        let emails = |count| bundle.format("emails", Some(&arg("count", count))).unwrap();
+        assert_eq!(emails(0), "No emails");
+        assert_eq!(emails(1), "One email");
+        assert_eq!(emails(5), "5 emails");
+        let shared = |gender, count| {
+            bundle.format("shared", Some(&arg("gender", gender).arg("count", count))).unwrap()
+        };
+        assert_eq!(shared("female", 1), "She shared a photo.");
+        assert_eq!(shared("robot", 3), "They shared photos.");
+    }
+
+    #[test]
+    fn selects_over_strings_with_other_defaults() {
+        let bundle = bundle("role = { $role ->\n\
+                             \x20  *[admin] Administrator\n\
+                             \x20   [guest] Guest\n\
+                             }\n");
+        let role = |role| bundle.format("role", Some(&arg("role", role))).unwrap();
+        assert_eq!(role("guest"), "Guest");
+        assert_eq!(role("admin"), "Administrator");
+        assert_eq!(role("editor"), "Administrator");
+    }
+
+    #[test]
+    fn terms() {
+        let bundle = bundle("welcome = Welcome to { -brand-name }!\n\
+                             -brand-name = { -brand } Cloud\n\
+                             -brand = Acme\n");
+        assert_eq!(bundle.format("welcome", None).unwrap(), "Welcome to Acme Cloud!");
+        assert!(!bundle.contains_key("-brand"));
+    }
+
+    #[test]
+    fn unconvertible() {
+        let ftl = "-brand = { $case ->\n\
+                   \x20  *[nominative] Firefox\n\
+                   \x20   [genitive] Firefoxu\n\
+                   }\n\
+                   -short = Fx\n\
+                   login = Log in\n\
+                   \x20   .title = Log in to your account\n\
+                   \x20   .accesskey = L\n\
+                   about = About { -brand(case: \"genitive\") }\n\
+                   about-short = About { -short.gender }\n\
+                   named = About { -brand }\n\
+                   price = { NUMBER($amount, minimumFractionDigits: 2) }\n\
+                   help = See { login }.\n\
+                   missing = { -missing }\n\
+                   half = { $n ->\n\
+                   \x20   [0.5] Half\n\
+                   \x20  *[other] Some\n\
+                   }\n\
+                   = stray text\n\
+                   broken = { $n\n\
+                   after = Still converted\n";
+        let conversion = convert(Context::default(), ftl);
+        let diagnostic = |id: &str, line, kind| {
+            Diagnostic { id: id.to_string(), line: Some(line), kind }
+        };
+        assert_eq!(conversion.diagnostics,
+                   [diagnostic("login", 6, DiagnosticKind::Attribute("title".to_string())),
+                    diagnostic("login", 6, DiagnosticKind::Attribute("accesskey".to_string())),
+                    diagnostic("about",
+                               9,
+                               DiagnosticKind::ParameterizedTerm("-brand".to_string())),
+                    diagnostic("about-short",
+                               10,
+                               DiagnosticKind::ParameterizedTerm("-short".to_string())),
+                    diagnostic("named",
+                               11,
+                               DiagnosticKind::ParameterizedTerm("-brand".to_string())),
+                    diagnostic("price", 12, DiagnosticKind::Function("NUMBER".to_string())),
+                    diagnostic("help",
+                               13,
+                               DiagnosticKind::MessageReference("login".to_string())),
+                    diagnostic("missing",
+                               14,
+                               DiagnosticKind::UnknownTerm("-missing".to_string())),
+                    diagnostic("half", 15, DiagnosticKind::InvalidVariantKey("0.5".to_string())),
+                    diagnostic("",
+                               19,
+                               DiagnosticKind::Syntax("Expected a message, term or comment."
+                                   .to_string())),
+                    diagnostic("broken", 20, DiagnosticKind::Syntax("Expected '}'.".to_string()))]);
+        assert_eq!(conversion.diagnostics[0].to_string(),
+                   "Line 6: 'login': Attribute 'title' has no ICU equivalent and was left out.");
+        let bundle = conversion.bundle;
+        assert_eq!(bundle.keys().collect::<Vec<_>>(), ["after", "login"]);
+        assert_eq!(bundle.format("login", None).unwrap(), "Log in");
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let cases = ["key",
+                     "key = {",
+                     "key = }",
+                     "-term =\n",
+                     "key = { $n -> [one] x }",
+                     "key =",
+                     "key = { \"open }",
+                     "key = { $n ->\n [one] x\n}\n"];
+        for &ftl in &cases {
+            let conversion = convert(Context::default(), ftl);
+            assert_eq!(conversion.diagnostics.len(), 1, "{}", ftl);
+            assert!(conversion.bundle.is_empty());
+        }
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let nested = |count| {
+            let ftl = format!("key = {}\"x\"{}\n", "{ ".repeat(count), " }".repeat(count));
+            convert(Context::default(), &ftl).diagnostics
+        };
+        assert!(nested(50).is_empty());
+        let diagnostics = nested(100_000);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, syntax("Too deeply nested."));
+
+        let terms = (0..100_000)
+            .map(|i| format!("-t{} = {{ -t{} }}\n", i, i + 1))
+            .collect::<String>();
+        let ftl = format!("{}-t100000 = x\nkey = {{ -t0 }}\n", terms);
+        let diagnostics = convert(Context::default(), &ftl).diagnostics;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, syntax("Too deeply nested."));
+    }
+
+    #[test]
+    fn text_after_a_line_break() {
+        let conversion = convert(Context::default(), "key = a\n  é\n");
+        assert_eq!(conversion.bundle.format("key", None).unwrap(), "a\né");
+    }
+}
@@ -0,0 +1,649 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! gettext
+//!
+//! This converts [gettext] catalogs, `.po` and `.pot` files, into ICU
+//! messages:
+//!
+//! ```
+//! use message_format::{arg, Context};
+//! use message_format::convert::gettext::{self, GettextOptions};
+//!
+//! let conversion = gettext::convert(Context::default(), r#"
+//! msgid ""
+//! msgstr ""
+//! "Plural-Forms: nplurals=2; plural=(n != 1);\n"
+//!
+//! msgid "Welcome, %s!"
+//! msgstr "Willkommen, %s!"
+//!
+//! msgid "%d song"
+//! msgid_plural "%d songs"
+//! msgstr[0] "%d Lied"
+//! msgstr[1] "%d Lieder"
+//! "#, &GettextOptions::default());
+//! assert!(conversion.diagnostics.is_empty());
+//! let bundle = conversion.bundle;
+//! assert_eq!(bundle.format("Welcome, %s!", Some(&arg("0", "Jo"))).unwrap(),
+//!            "Willkommen, Jo!");
+//! assert_eq!(bundle.format("%d song", Some(&arg("count", 3).arg("0", 3))).unwrap(),
+//!            "3 Lieder");
+//! ```
+//!
+//! Each entry is keyed by its `msgid`, or by its `msgctxt` and `msgid`
+//! separated by `|`, like `menu|Open`. The strings of an entry may be
+//! split across lines, and their C escapes, like `\n`, `\"` and `\t`,
+//! are replaced. The extracted comments of an entry, the `#.` lines,
+//! become the description of its metadata.
+//!
+//! Format specifiers become simple formats of the argument's position,
+//! counting from 0, so `%1$s` and the first `%s` or `%d` become `{0}`,
+//! and `%%` is `%`. An entry with a `msgid_plural` becomes a `plural`
+//! format on a variable, `count` by default, and as with `ngettext`,
+//! the number is usually also passed as the first argument.
+//!
+//! The forms of a plural entry are chosen by the `Plural-Forms` header
+//! of the catalog, using [`GettextPlurals`], so the entry selects the
+//! same form that gettext would. Each form is a branch for the category
+//! of its index. Without a header, the forms are chosen as for English.
+//!
+//! Untranslated and fuzzy entries use their `msgid`, as gettext does,
+//! or can be left out with [`GettextOptions::skip_untranslated`].
+//! Obsolete entries are left out.
+//!
+//! [gettext]: https://www.gnu.org/software/gettext/
+//! [`GettextPlurals`]: ../../plural_rules/struct.GettextPlurals.html
+//! [`GettextOptions::skip_untranslated`]: struct.GettextOptions.html#structfield.skip_untranslated
+
+use std::fmt;
+
+use super::printf::{self, specifier};
+use icu::ast::{PlainText, PluralFormat, SimpleFormat};
+use plural_rules::{GettextPlurals, RuleParseError};
+use {Context, Message, MessageBundle, MessageMetadata, MessagePart, PluralCategory};
+
+/// The plural forms of a catalog without a `Plural-Forms` header, and
+/// of untranslated entries.
+const ENGLISH_PLURALS: &str = "nplurals=2; plural=(n != 1);";
+
+/// Options for [`convert`].
+///
+/// [`convert`]: fn.convert.html
+#[derive(Clone,Debug,PartialEq)]
+pub struct GettextOptions {
+    /// The variable of the `plural` formats that plural entries become.
+    /// The default is `count`.
+    pub count_variable: String,
+    /// Whether untranslated and fuzzy entries are left out, rather than
+    /// using their `msgid`. The default is `false`.
+    pub skip_untranslated: bool,
+}
+
+impl Default for GettextOptions {
+    fn default() -> Self {
+        GettextOptions {
+            count_variable: "count".to_string(),
+            skip_untranslated: false,
+        }
+    }
+}
+
+/// The result of converting a gettext catalog with [`convert`].
+///
+/// [`convert`]: fn.convert.html
+pub type Conversion = super::Conversion<DiagnosticKind>;
+
+/// A problem with an entry of a gettext catalog. Its `id` is the key of
+/// the entry, which is empty for the header, and for an entry without a
+/// `msgid`. Its `line` is the line that the problem is on.
+pub type Diagnostic = super::Diagnostic<DiagnosticKind>;
+
+/// The kinds of [`Diagnostic`]. The entry is still converted for
+/// `FormCount` and `FormatSpecifier`, and isn't otherwise.
+///
+/// [`Diagnostic`]: type.Diagnostic.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum DiagnosticKind {
+    /// The entry is malformed. Has a description of the problem.
+    Syntax(String),
+    /// The `Plural-Forms` header is invalid, so the forms are chosen as
+    /// for English.
+    PluralForms(RuleParseError),
+    /// A plural entry doesn't have as many forms as the `Plural-Forms`
+    /// header, so its `msgid` was used. Has the number of forms of the
+    /// entry and of the header.
+    FormCount(usize, usize),
+    /// An earlier entry has the same key, so this one was left out.
+    Duplicate,
+    /// A format specifier has flags, a width or a precision, like
+    /// `%.2f`, which were left out. Has the specifier.
+    FormatSpecifier(String),
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            DiagnosticKind::Syntax(ref message) => message.fmt(f),
+            DiagnosticKind::PluralForms(ref error) => write!(f, "Invalid Plural-Forms: {}", error),
+            DiagnosticKind::FormCount(found, expected) => {
+                write!(f, "The entry has {} forms rather than {}.", found, expected)
+            }
+            DiagnosticKind::Duplicate => "The entry is a duplicate and was left out.".fmt(f),
+            DiagnosticKind::FormatSpecifier(ref specifier) => {
+                write!(f,
+                       "The flags, width and precision of '{}' have no ICU equivalent.",
+                       specifier)
+            }
+        }
+    }
+}
+
+/// Convert the entries of a gettext catalog into ICU messages in a
+/// bundle with `context`. The problems with each entry are reported,
+/// and the rest of the entries are converted.
+pub fn convert(context: Context, po: &str, options: &GettextOptions) -> Conversion {
+    let mut conversion = Conversion {
+        bundle: MessageBundle::new(context),
+        diagnostics: vec![],
+    };
+    let entries = parse(po, &mut conversion.diagnostics);
+    let english = GettextPlurals::new(ENGLISH_PLURALS).expect("valid plural forms");
+    let mut plurals = english.clone();
+    for entry in &entries {
+        if entry.is_header() {
+            if let Some(header) = entry.header("Plural-Forms") {
+                match GettextPlurals::new(header) {
+                    Ok(header) => plurals = header,
+                    Err(error) => {
+                        conversion.diagnostics.push(Diagnostic {
+                            id: String::new(),
+                            line: Some(entry.line),
+                            kind: DiagnosticKind::PluralForms(error),
+                        })
+                    }
+                }
+            }
+        }
+    }
+    for entry in entries {
+        if entry.is_header() {
+            continue;
+        }
+        let key = match entry.context {
+            Some(ref context) => format!("{}|{}", context, entry.id),
+            None => entry.id.clone(),
+        };
+        let mut kinds = vec![];
+        let translated = !entry.fuzzy && entry.strs.iter().any(|s| !s.is_empty());
+        let mut forms = if translated { Some(&entry.strs[..]) } else { None };
+        if forms.is_some() && entry.id_plural.is_some() && entry.strs.len() != plurals.len() {
+            kinds.push(DiagnosticKind::FormCount(entry.strs.len(), plurals.len()));
+            forms = None;
+        }
+        if forms.is_none() && options.skip_untranslated {
+            continue;
+        }
+        if conversion.bundle.contains_key(&key) {
+            kinds = vec![DiagnosticKind::Duplicate];
+        } else {
+            let message = match (entry.id_plural.as_ref(), forms) {
+                (None, Some(strs)) => lower(&strs[0], &mut kinds),
+                (None, None) => lower(&entry.id, &mut kinds),
+                (Some(_), Some(strs)) => {
+                    lower_plural(&options.count_variable, strs, &plurals, &mut kinds)
+                }
+                (Some(id_plural), None) => {
+                    let strs = [entry.id.clone(), id_plural.clone()];
+                    lower_plural(&options.count_variable, &strs, &english, &mut kinds)
+                }
+            };
+            conversion.bundle.insert_message(&key, message);
+            if !entry.comments.is_empty() {
+                let metadata = MessageMetadata {
+                    description: Some(entry.comments.join("\n")),
+                    ..MessageMetadata::default()
+                };
+                conversion.bundle.insert_metadata(&key, metadata);
+            }
+        }
+        conversion.diagnostics.extend(kinds.into_iter().map(|kind| {
+            Diagnostic { id: key.clone(), line: Some(entry.line), kind }
+        }));
+    }
+    conversion.diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+    conversion
+}
+
+/// An entry of a catalog.
+#[derive(Debug,Default)]
+struct Entry {
+    /// The line of its first keyword.
+    line: usize,
+    context: Option<String>,
+    id: String,
+    id_plural: Option<String>,
+    /// The `msgstr`, or each `msgstr[n]` in order.
+    strs: Vec<String>,
+    fuzzy: bool,
+    /// The extracted comments.
+    comments: Vec<String>,
+}
+
+impl Entry {
+    fn is_header(&self) -> bool {
+        self.id.is_empty() && self.context.is_none()
+    }
+
+    /// The value of a field of the header, like `Plural-Forms`.
+    fn header(&self, name: &str) -> Option<&str> {
+        self.strs.first()?.lines().find_map(|line| {
+            let (field, value) = line.split_at(line.find(':')?);
+            if field.trim().eq_ignore_ascii_case(name) { Some(value[1..].trim()) } else { None }
+        })
+    }
+}
+
+/// A keyword of an entry, whose string may continue on the lines after
+/// it.
+#[derive(Clone,Copy,Debug,PartialEq)]
+enum Field {
+    Context,
+    Id,
+    IdPlural,
+    /// A `msgstr`, or a `msgstr[n]` with its index.
+    Str(usize),
+}
+
+/// Split a catalog into entries, reporting the malformed ones.
+fn parse(po: &str, diagnostics: &mut Vec<Diagnostic>) -> Vec<Entry> {
+    let mut entries = vec![];
+    let mut entry = Entry::default();
+    // The field being read, or `None` if a new entry hasn't started.
+    let mut field = None;
+    let mut error = None;
+    let mut finish = |entry: &mut Entry, field: &mut Option<Field>, error: &mut Option<String>| {
+        let entry = ::std::mem::take(entry);
+        if field.take().is_none() {
+            return;
+        }
+        let error = match error.take() {
+            Some(error) => Some(error),
+            None if entry.strs.is_empty() => Some("The entry has no msgstr.".to_string()),
+            None => None,
+        };
+        match error {
+            Some(error) => {
+                diagnostics.push(Diagnostic {
+                    id: entry.id.clone(),
+                    line: Some(entry.line),
+                    kind: DiagnosticKind::Syntax(error),
+                })
+            }
+            None => entries.push(entry),
+        }
+    };
+    for (index, line) in po.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            if field.is_some() {
+                finish(&mut entry, &mut field, &mut error);
+            }
+            if let Some(flags) = line.strip_prefix("#,") {
+                entry.fuzzy |= flags.split(',').any(|flag| flag.trim() == "fuzzy");
+            } else if let Some(comment) = line.strip_prefix("#.") {
+                entry.comments.push(comment.trim().to_string());
+            }
+            continue;
+        }
+        if error.is_some() {
+            continue;
+        }
+        let (keyword, string) = line.split_at(line.find([' ', '\t', '"']).unwrap_or(line.len()));
+        // An entry can start right after the last one.
+        if matches!(field, Some(Field::Str(_))) && (keyword == "msgctxt" || keyword == "msgid") {
+            finish(&mut entry, &mut field, &mut error);
+        }
+        let next = match keyword {
+            "" => field.ok_or("A string doesn't follow a keyword."),
+            "msgctxt" if field.is_none() => Ok(Field::Context),
+            "msgid" if field.is_none() || field == Some(Field::Context) => Ok(Field::Id),
+            "msgid_plural" if field == Some(Field::Id) => Ok(Field::IdPlural),
+            "msgstr" if field == Some(Field::Id) => Ok(Field::Str(0)),
+            _ if keyword.starts_with("msgstr[") && keyword.ends_with(']') => {
+                let index = keyword["msgstr[".len()..keyword.len() - 1].parse().ok();
+                match (index, field) {
+                    (Some(index), Some(Field::IdPlural)) if index == 0 => Ok(Field::Str(index)),
+                    (Some(index), Some(Field::Str(last))) if index == last + 1 => {
+                        Ok(Field::Str(index))
+                    }
+                    _ => Err("The msgstr indices aren't in order."),
+                }
+            }
+            "msgctxt" | "msgid" | "msgid_plural" | "msgstr" => {
+                Err("The keywords of the entry aren't in order.")
+            }
+            _ => Err("Unknown keyword."),
+        };
+        if field.is_none() {
+            entry.line = index + 1;
+        }
+        let next = match next {
+            Ok(next) => next,
+            Err(message) => {
+                error = Some(message.to_string());
+                // Keep the entry going until it is finished.
+                field = field.or(Some(Field::Id));
+                continue;
+            }
+        };
+        let string = match unquote(string) {
+            Ok(string) => string,
+            Err(message) => {
+                error = Some(message.to_string());
+                field = Some(next);
+                continue;
+            }
+        };
+        let target = match next {
+            Field::Context => entry.context.get_or_insert_with(String::new),
+            Field::Id => &mut entry.id,
+            Field::IdPlural => entry.id_plural.get_or_insert_with(String::new),
+            Field::Str(index) => {
+                if entry.strs.len() == index {
+                    entry.strs.push(String::new());
+                }
+                &mut entry.strs[index]
+            }
+        };
+        target.push_str(&string);
+        field = Some(next);
+    }
+    finish(&mut entry, &mut field, &mut error);
+    entries
+}
+
+/// Remove the quotes around a string and replace its escapes.
+fn unquote(string: &str) -> Result<String, &'static str> {
+    let string = string.trim();
+    let inner = match string.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        Some(inner) => inner,
+        _ => return Err("Expected a quoted string."),
+    };
+    let mut output = String::new();
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => return Err("Unescaped quote in a string."),
+            '\\' => {}
+            ch => {
+                output.push(ch);
+                continue;
+            }
+        }
+        let escaped = match chars.next() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('a') => '\u{7}',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some('v') => '\u{b}',
+            Some(ch @ '\\') | Some(ch @ '"') | Some(ch @ '\'') | Some(ch @ '?') => ch,
+            Some(ch @ '0'..='7') => {
+                let rest = chars.as_str();
+                let len = rest.bytes().take(2).take_while(|b| matches!(b, b'0'..=b'7')).count();
+                let digits = format!("{}{}", ch, &rest[..len]);
+                chars = rest[len..].chars();
+                u32::from_str_radix(&digits, 8).ok().and_then(char::from_u32).unwrap_or(ch)
+            }
+            Some('x') => {
+                let rest = chars.as_str();
+                let len = rest.bytes().take_while(u8::is_ascii_hexdigit).count();
+                let code = u32::from_str_radix(&rest[..len], 16).ok().and_then(char::from_u32);
+                chars = rest[len..].chars();
+                code.ok_or("Invalid escape in a string.")?
+            }
+            _ => return Err("Invalid escape in a string."),
+        };
+        output.push(escaped);
+    }
+    Ok(output)
+}
+
+/// Build a `plural` format on `variable` with a branch for each of the
+/// `strs` of a plural entry, chosen by `plurals`.
+fn lower_plural(variable: &str,
+                strs: &[String],
+                plurals: &GettextPlurals,
+                kinds: &mut Vec<DiagnosticKind>)
+                -> Message {
+    let mut other = None;
+    let mut branches = vec![];
+    for (index, string) in strs.iter().enumerate() {
+        let category = plurals.category(index).unwrap_or(PluralCategory::Other);
+        if category == PluralCategory::Other {
+            // Later forms with the same category are never chosen.
+            if other.is_none() {
+                other = Some(lower(string, kinds));
+            }
+        } else if branches.iter().all(|&(c, _)| c != category) {
+            branches.push((category, lower(string, kinds)));
+        }
+    }
+    let mut fmt = PluralFormat::new(variable, other.unwrap_or_else(|| Message::new(vec![])));
+    for (category, message) in branches {
+        match category {
+            PluralCategory::Zero => fmt.zero(message),
+            PluralCategory::One => fmt.one(message),
+            PluralCategory::Two => fmt.two(message),
+            PluralCategory::Few => fmt.few(message),
+            PluralCategory::Many => fmt.many(message),
+            PluralCategory::Other => {}
+        }
+    }
+    fmt.classifier(plurals.clone());
+    Message::new(vec![Box::new(fmt)])
+}
+
+/// Build a message from a string, with its format specifiers as simple
+/// formats.
+fn lower(text: &str, kinds: &mut Vec<DiagnosticKind>) -> Message {
+    let mut parts: Vec<Box<dyn MessagePart>> = vec![];
+    let mut plain = String::new();
+    // The position of the next argument without an explicit position.
+    let mut next = 0;
+    let mut rest = text;
+    while let Some(i) = rest.find('%') {
+        plain.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if rest.starts_with("%%") {
+            plain.push('%');
+            rest = &rest[2..];
+            continue;
+        }
+        let specifier = match specifier(rest, &printf::C) {
+            Some(specifier) if specifier.variable.is_none() => specifier,
+            _ => {
+                plain.push('%');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        if specifier.styled {
+            kinds.push(DiagnosticKind::FormatSpecifier(rest[..specifier.len].to_string()));
+        }
+        let position = specifier.position.unwrap_or_else(|| {
+            next += 1;
+            next
+        });
+        if !plain.is_empty() {
+            parts.push(Box::new(PlainText::new(&plain)));
+            plain.clear();
+        }
+        parts.push(Box::new(SimpleFormat::new(&(position - 1).to_string())));
+        rest = &rest[specifier.len..];
+    }
+    plain.push_str(rest);
+    if !plain.is_empty() {
+        parts.push(Box::new(PlainText::new(&plain)));
+    }
+    Message::new(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert, unquote, Diagnostic, DiagnosticKind, GettextOptions};
+    use plural_rules::RuleParseError;
+    use {arg, Context, Locale, MessageBundle};
+
+    fn bundle(po: &str) -> MessageBundle {
+        let conversion = convert(Context::default(), po, &GettextOptions::default());
+        assert_eq!(conversion.diagnostics, []);
+        conversion.bundle
+    }
+
+    #[test]
+    fn strings_and_escapes() {
+        let bundle = bundle(r#"
+# A translator comment.
+#. The title of the window.
+#: src/main.c:12
+msgid "Title"
+msgstr "Titel"
+
+msgctxt "menu"
+msgid "Open"
+msgstr ""
+"Öff"
+"nen"
+
+msgid "Escapes"
+msgstr "\"Tab\"\there\\ \101\x42\n"
+"#);
+        assert_eq!(bundle.format("Title", None).unwrap(), "Titel");
+        assert_eq!(bundle.metadata("Title").unwrap().description.as_ref().unwrap(),
+                   "The title of the window.");
+        assert_eq!(bundle.format("menu|Open", None).unwrap(), "Öffnen");
+        assert!(!bundle.contains_key("Open"));
+        assert_eq!(bundle.format("Escapes", None).unwrap(), "\"Tab\"\there\\ AB\n");
+        assert_eq!(unquote(r#""\q""#), Err("Invalid escape in a string."));
+        assert_eq!(unquote(r#""a"b""#), Err("Unescaped quote in a string."));
+        assert_eq!(unquote("\""), Err("Expected a quoted string."));
+    }
+
+    #[test]
+    fn format_specifiers() {
+        let bundle = bundle(r#"
+msgid "%s sent %d files"
+msgstr "%2$d Dateien von %1$s, 100% sicher, %% %ld"
+"#);
+        let args = arg("0", "Jo");
+        let args = args.arg("1", 3);
+        assert_eq!(bundle.format("%s sent %d files", Some(&args)).unwrap(),
+                   "3 Dateien von Jo, 100% sicher, % Jo");
+    }
+
+    #[test]
+    fn plural_forms() {
+        let po = r#"
+msgid ""
+msgstr ""
+"Language: ru\n"
+"Plural-Forms: nplurals=3; plural=(n%10==1 && n%100!=11 ? 0 : "
+"n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2);\n"
+
+msgid "%d file"
+msgid_plural "%d files"
+msgstr[0] "%d файл"
+msgstr[1] "%d файла"
+msgstr[2] "%d файлов"
+"#;
+        // The header chooses the forms, whatever the locale is.
+        let bundle = convert(Context::default(), po, &GettextOptions::default()).bundle;
+        let format = |n| bundle.format("%d file", Some(&arg("count", n).arg("0", n))).unwrap();
+        assert_eq!(format(1), "1 файл");
+        assert_eq!(format(21), "21 файл");
+        assert_eq!(format(3), "3 файла");
+        assert_eq!(format(11), "11 файлов");
+        assert_eq!(format(100), "100 файлов");
+    }
+
+    #[test]
+    fn untranslated_entries() {
+        let po = r#"
+msgid "Save"
+msgstr ""
+
+#, fuzzy, c-format
+msgid "Quit"
+msgstr "Beenden"
+
+msgid "%d day"
+msgid_plural "%d days"
+msgstr[0] ""
+msgstr[1] ""
+
+#~ msgid "Old"
+#~ msgstr "Alt"
+"#;
+        let ctx = Context::new(Locale::new("de").unwrap(), None);
+        let bundle = convert(ctx.clone(), po, &GettextOptions::default()).bundle;
+        assert_eq!(bundle.format("Save", None).unwrap(), "Save");
+        assert_eq!(bundle.format("Quit", None).unwrap(), "Quit");
+        let format = |n| bundle.format("%d day", Some(&arg("count", n).arg("0", n))).unwrap();
+        assert_eq!((format(1), format(2)), ("1 day".to_string(), "2 days".to_string()));
+        assert_eq!(bundle.keys().count(), 3);
+
+        let options = GettextOptions {
+            count_variable: "n".to_string(),
+            skip_untranslated: true,
+        };
+        assert!(convert(ctx, po, &options).bundle.is_empty());
+    }
+
+    #[test]
+    fn diagnostics() {
+        let po = r#"msgid ""
+msgstr "Plural-Forms: nplurals=2; plural=n >;\n"
+
+msgid "Tab"
+msgstr "%-5s"
+
+msgid "Tab"
+msgstr "Again"
+
+msgid "Unquoted"
+msgstr Unquoted
+
+msgid "%d item"
+msgid_plural "%d items"
+msgstr[0] "%d Element"
+
+msgstr "Orphan"
+
+msgid "No msgstr"
+"#;
+        let conversion = convert(Context::default(), po, &GettextOptions::default());
+        let diagnostic = |id: &str, line, kind| {
+            Diagnostic { id: id.to_string(), line: Some(line), kind }
+        };
+        let syntax = |message: &str| DiagnosticKind::Syntax(message.to_string());
+        let header = RuleParseError::InvalidRule("nplurals=2; plural=n >;".to_string(), 22);
+        assert_eq!(conversion.diagnostics,
+                   [diagnostic("", 1, DiagnosticKind::PluralForms(header)),
+                    diagnostic("Tab", 4, DiagnosticKind::FormatSpecifier("%-5s".into())),
+                    diagnostic("Tab", 7, DiagnosticKind::Duplicate),
+                    diagnostic("Unquoted", 10, syntax("Expected a quoted string.")),
+                    diagnostic("%d item", 13, DiagnosticKind::FormCount(1, 2)),
+                    diagnostic("", 17, syntax("The keywords of the entry aren't in order.")),
+                    diagnostic("No msgstr", 19, syntax("The entry has no msgstr."))]);
+        assert_eq!(conversion.diagnostics[4].to_string(),
+                   "Line 13: '%d item': The entry has 1 forms rather than 2.");
+        assert_eq!(conversion.bundle.keys().collect::<Vec<_>>(), ["%d item", "Tab"]);
+        assert_eq!(conversion.bundle.format("Tab", Some(&arg("0", "x"))).unwrap(), "x");
+    }
+}
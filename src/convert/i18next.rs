@@ -0,0 +1,396 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! i18next
+//!
+//! This converts [i18next] JSON resources into ICU messages:
+//!
+//! ```
+//! use message_format::{arg, Context};
+//! use message_format::convert::i18next::{self, I18nextOptions};
+//!
+//! let conversion = i18next::convert(Context::default(), r#"{
+//!     "welcome": "Welcome, {{name}}!",
+//!     "cart": {
+//!         "item_one": "{{count}} item",
+//!         "item_other": "{{count, number}} items"
+//!     }
+//! }"#, &I18nextOptions::default()).unwrap();
+//! assert!(conversion.diagnostics.is_empty());
+//! let bundle = conversion.bundle;
+//! assert_eq!(bundle.format("welcome", Some(&arg("name", "Jo"))).unwrap(), "Welcome, Jo!");
+//! assert_eq!(bundle.format("cart.item", Some(&arg("count", 1200))).unwrap(), "1,200 items");
+//! ```
+//!
+//! The keys of nested objects are joined with `.`. Keys with a plural
+//! suffix, like `item_one` and `item_other`, are grouped into a single
+//! `plural` format on `count` with the key before the suffix, `item`,
+//! and a branch for each suffix. As with i18next, `_zero` is used for
+//! `0` as well as for the `zero` category. Ordinal forms, like
+//! `place_ordinal_one`, become a `selectordinal` format with the key
+//! `place_ordinal`. Other keys are converted as they are.
+//!
+//! Interpolations, like `{{name}}` and the unescaped `{{- name}}`,
+//! become simple formats of their variable, and `{{count, number}}`
+//! becomes a `number` format. Other format hints have no ICU
+//! equivalent and are left out. Nesting, like `$t(otherKey)`, has no
+//! ICU equivalent either, so a message that uses it isn't converted.
+//! Both are reported as [`Diagnostic`]s.
+//!
+//! [i18next]: https://www.i18next.com/
+//! [`Diagnostic`]: type.Diagnostic.html
+
+use std::fmt;
+
+use icu::ast::{NumberFormat, PlainText, PluralFormat, SimpleFormat};
+use json::{self, JsonValue};
+use {BundleError, Context, Message, MessageBundle, MessagePart, PluralCategory};
+
+/// The variable that i18next chooses plural forms with.
+const COUNT: &str = "count";
+
+/// Options for [`convert`].
+///
+/// [`convert`]: fn.convert.html
+#[derive(Clone,Debug,PartialEq)]
+pub struct I18nextOptions {
+    /// The separator that the keys of nested objects are joined with.
+    /// The default is `.`, as for i18next's `keySeparator`.
+    pub key_separator: String,
+    /// The separator before a plural suffix, like `_one`. The default
+    /// is `_`, as for i18next's `pluralSeparator`.
+    pub plural_separator: String,
+}
+
+impl Default for I18nextOptions {
+    fn default() -> Self {
+        I18nextOptions {
+            key_separator: ".".to_string(),
+            plural_separator: "_".to_string(),
+        }
+    }
+}
+
+/// The result of converting an i18next resource with [`convert`].
+///
+/// [`convert`]: fn.convert.html
+pub type Conversion = super::Conversion<DiagnosticKind>;
+
+/// A problem with a message of an i18next resource. Its `id` is the key
+/// of the message without a plural suffix, and it has no `line`.
+pub type Diagnostic = super::Diagnostic<DiagnosticKind>;
+
+/// The kinds of [`Diagnostic`]. The message is still converted for
+/// `FormatHint`, and isn't otherwise.
+///
+/// [`Diagnostic`]: type.Diagnostic.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum DiagnosticKind {
+    /// The value of a key isn't a string or an object. Has the key,
+    /// with its plural suffix.
+    NotAString(String),
+    /// The keys with plural suffixes don't include one for `other`.
+    MissingOther,
+    /// The message uses nesting, like `$t(otherKey)`. Has the key that
+    /// it refers to.
+    Nesting(String),
+    /// An interpolation has a format hint other than `number`, like
+    /// `{{date, datetime}}`, which was left out. Has the format hint.
+    FormatHint(String),
+    /// Another message has the same key, so this one was left out.
+    Duplicate,
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            DiagnosticKind::NotAString(ref key) => {
+                write!(f, "The value of '{}' isn't a string.", key)
+            }
+            DiagnosticKind::MissingOther => "There is no '_other' form.".fmt(f),
+            DiagnosticKind::Nesting(ref key) => {
+                write!(f, "The nesting of '{}' has no ICU equivalent.", key)
+            }
+            DiagnosticKind::FormatHint(ref hint) => {
+                write!(f, "The format hint '{}' has no ICU equivalent.", hint)
+            }
+            DiagnosticKind::Duplicate => "The message is a duplicate and was left out.".fmt(f),
+        }
+    }
+}
+
+/// Convert the messages of an i18next resource into ICU messages in a
+/// bundle with `context`. The problems with each message are reported,
+/// and the rest of the messages are converted. An error is only
+/// returned if the resource isn't a JSON object.
+pub fn convert(context: Context,
+               json: &str,
+               options: &I18nextOptions)
+               -> Result<Conversion, BundleError> {
+    let value = json::parse(json).map_err(|e| BundleError::InvalidResource(e.to_string()))?;
+    let members = value.as_object()
+        .ok_or_else(|| BundleError::InvalidResource("Expected an object.".to_string()))?;
+    let mut conversion = Conversion {
+        bundle: MessageBundle::new(context),
+        diagnostics: vec![],
+    };
+    let mut entries = vec![];
+    flatten(members, "", options, &mut entries);
+    // The messages, each with its forms, in the order of their first
+    // keys. A key without a suffix is a message of its own.
+    let mut groups: Vec<(&str, Vec<Form>)> = vec![];
+    for &(ref key, value) in &entries {
+        let (id, category) = split_suffix(key, &options.plural_separator);
+        let group = match category {
+            Some(_) => groups.iter().position(|(i, forms)| *i == id && forms[0].1.is_some()),
+            None => None,
+        };
+        match group {
+            Some(group) => groups[group].1.push((key, category, value)),
+            None => groups.push((id, vec![(key, category, value)])),
+        }
+    }
+    for (id, forms) in groups {
+        let id = id.to_string();
+        let mut kinds = forms.iter()
+            .filter(|&&(_, _, value)| value.as_str().is_none())
+            .map(|&(key, _, _)| DiagnosticKind::NotAString(key.clone()))
+            .collect::<Vec<_>>();
+        if !kinds.is_empty() {
+            // A message with a value that isn't a string isn't converted.
+        } else if conversion.bundle.contains_key(&id) {
+            kinds.push(DiagnosticKind::Duplicate);
+        } else {
+            let message = match forms[0] {
+                (_, None, value) => lower(value.as_str().unwrap_or_default(), &mut kinds),
+                (_, Some(_), _) => lower_plural(&id, &forms, &mut kinds),
+            };
+            if let Some(message) = message {
+                conversion.bundle.insert_message(&id, message);
+            }
+        }
+        conversion.diagnostics.extend(kinds.into_iter().map(|kind| {
+            Diagnostic { id: id.clone(), line: None, kind }
+        }));
+    }
+    Ok(conversion)
+}
+
+/// A form of a message: its key, the category of its plural suffix and
+/// its value.
+type Form<'a> = (&'a String, Option<PluralCategory>, &'a JsonValue);
+
+/// The keys of the values in the members of a JSON object, after
+/// `prefix`, with the keys of nested objects joined.
+fn flatten<'a>(members: &'a [(String, JsonValue)],
+               prefix: &str,
+               options: &I18nextOptions,
+               entries: &mut Vec<(String, &'a JsonValue)>) {
+    for (name, value) in members {
+        let key = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}{}{}", prefix, options.key_separator, name)
+        };
+        match *value {
+            JsonValue::Object(ref members) => flatten(members, &key, options, entries),
+            _ => entries.push((key, value)),
+        }
+    }
+}
+
+/// Split a key into the key of its message and its plural category, if
+/// it has a plural suffix.
+fn split_suffix<'a>(key: &'a str, separator: &str) -> (&'a str, Option<PluralCategory>) {
+    let split = key.rfind(separator).filter(|_| !separator.is_empty()).and_then(|i| {
+        let category = key[i + separator.len()..].parse().ok()?;
+        Some((&key[..i], Some(category)))
+    });
+    split.unwrap_or((key, None))
+}
+
+/// Build a `plural` format on `count` with a branch for each of the
+/// `forms` of the message `id`.
+fn lower_plural(id: &str,
+                forms: &[Form],
+                kinds: &mut Vec<DiagnosticKind>)
+                -> Option<Message> {
+    let mut other = None;
+    let mut branches = vec![];
+    for &(_, category, value) in forms {
+        let message = lower(value.as_str().unwrap_or_default(), kinds)?;
+        match category {
+            Some(PluralCategory::Other) => other = Some(message),
+            Some(category) => branches.push((category, message)),
+            None => {}
+        }
+    }
+    let mut fmt = match other {
+        Some(other) => PluralFormat::new(COUNT, other),
+        None => {
+            kinds.push(DiagnosticKind::MissingOther);
+            return None;
+        }
+    };
+    for (category, message) in branches {
+        match category {
+            PluralCategory::Zero => {
+                fmt.literal(0, message.clone());
+                fmt.zero(message);
+            }
+            PluralCategory::One => fmt.one(message),
+            PluralCategory::Two => fmt.two(message),
+            PluralCategory::Few => fmt.few(message),
+            PluralCategory::Many => fmt.many(message),
+            PluralCategory::Other => {}
+        }
+    }
+    fmt.ordinal(id.ends_with("_ordinal"));
+    Some(Message::new(vec![Box::new(fmt)]))
+}
+
+/// Build a message from a string, with its interpolations as formats,
+/// or `None` if it uses nesting.
+fn lower(text: &str, kinds: &mut Vec<DiagnosticKind>) -> Option<Message> {
+    if let Some(i) = text.find("$t(") {
+        let rest = &text[i + "$t(".len()..];
+        let key = &rest[..rest.find([',', ')']).unwrap_or(rest.len())];
+        kinds.push(DiagnosticKind::Nesting(key.trim().to_string()));
+        return None;
+    }
+    let mut parts: Vec<Box<dyn MessagePart>> = vec![];
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let end = match rest[start..].find("}}") {
+            Some(end) => start + end,
+            None => break,
+        };
+        let interpolation = rest[start + "{{".len()..end].trim();
+        let interpolation = interpolation.strip_prefix('-').unwrap_or(interpolation);
+        let (variable, hint) = match interpolation.find(',') {
+            Some(i) => (interpolation[..i].trim(), Some(interpolation[i + 1..].trim())),
+            None => (interpolation.trim(), None),
+        };
+        plain.push_str(&rest[..start]);
+        rest = &rest[end + "}}".len()..];
+        if variable.is_empty() {
+            plain.push_str("{{}}");
+            continue;
+        }
+        if !plain.is_empty() {
+            parts.push(Box::new(PlainText::new(&plain)));
+            plain.clear();
+        }
+        match hint {
+            Some("number") => parts.push(Box::new(NumberFormat::new(variable))),
+            Some(hint) => {
+                kinds.push(DiagnosticKind::FormatHint(hint.to_string()));
+                parts.push(Box::new(SimpleFormat::new(variable)));
+            }
+            None => parts.push(Box::new(SimpleFormat::new(variable))),
+        }
+    }
+    plain.push_str(rest);
+    if !plain.is_empty() {
+        parts.push(Box::new(PlainText::new(&plain)));
+    }
+    Some(Message::new(parts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert, Diagnostic, DiagnosticKind, I18nextOptions};
+    use {arg, BundleError, Context, Locale, MessageBundle};
+
+    fn bundle(ctx: Context, json: &str) -> MessageBundle {
+        let conversion = convert(ctx, json, &I18nextOptions::default()).unwrap();
+        assert_eq!(conversion.diagnostics, []);
+        conversion.bundle
+    }
+
+    #[test]
+    fn interpolation() {
+        let bundle = bundle(Context::default(), r#"{
+            "a": {"b": {"c": "{{- user.name}} has {{ n, number }} {{}} {{points"}},
+            "braces": "{literal} 'text'"
+        }"#);
+        let args = arg("user.name", "<Jo>");
+        let args = args.arg("n", 1500);
+        assert_eq!(bundle.format("a.b.c", Some(&args)).unwrap(), "<Jo> has 1,500 {{}} {{points");
+        assert_eq!(bundle.format("braces", None).unwrap(), "{literal} 'text'");
+    }
+
+    #[test]
+    fn plurals() {
+        let ctx = Context::new(Locale::new("pl").unwrap(), None);
+        let bundle = bundle(ctx, r#"{
+            "file_zero": "No files",
+            "file_one": "{{count}} plik",
+            "file_few": "{{count}} pliki",
+            "file_many": "{{count}} plików",
+            "file_other": "{{count}} pliku",
+            "title_case": "Not a plural"
+        }"#);
+        let format = |n| bundle.format("file", Some(&arg("count", n))).unwrap();
+        assert_eq!(format(0), "No files");
+        assert_eq!(format(1), "1 plik");
+        assert_eq!(format(3), "3 pliki");
+        assert_eq!(format(5), "5 plików");
+        assert_eq!(bundle.format("title_case", None).unwrap(), "Not a plural");
+        assert_eq!(bundle.keys().collect::<Vec<_>>(), ["file", "title_case"]);
+    }
+
+    #[test]
+    fn ordinals_and_separators() {
+        let options = I18nextOptions {
+            key_separator: "/".to_string(),
+            plural_separator: "__".to_string(),
+        };
+        let json = r#"{"a": {
+            "place_ordinal__one": "{{count}}st",
+            "place_ordinal__two": "{{count}}nd",
+            "place_ordinal__few": "{{count}}rd",
+            "place_ordinal__other": "{{count}}th",
+            "cat_one": "Kept"
+        }}"#;
+        let bundle = convert(Context::default(), json, &options).unwrap().bundle;
+        let format = |n| bundle.format("a/place_ordinal", Some(&arg("count", n))).unwrap();
+        assert_eq!([format(1), format(22), format(13)], ["1st", "22nd", "13th"]);
+        assert_eq!(bundle.format("a/cat_one", None).unwrap(), "Kept");
+    }
+
+    #[test]
+    fn diagnostics() {
+        let json = r#"{
+            "item_one": "{{count}} item",
+            "date": "On {{date, datetime}}",
+            "nested": "See $t(help.title, {\"count\": 2})",
+            "list": ["a", "b"],
+            "dup": "Plain",
+            "dup_other": "Plural",
+            "dup_one": "Plural",
+            "n": 5
+        }"#;
+        let conversion = convert(Context::default(), json, &I18nextOptions::default()).unwrap();
+        let diagnostic = |id: &str, kind| Diagnostic { id: id.to_string(), line: None, kind };
+        assert_eq!(conversion.diagnostics,
+                   [diagnostic("item", DiagnosticKind::MissingOther),
+                    diagnostic("date", DiagnosticKind::FormatHint("datetime".into())),
+                    diagnostic("nested", DiagnosticKind::Nesting("help.title".into())),
+                    diagnostic("list", DiagnosticKind::NotAString("list".into())),
+                    diagnostic("dup", DiagnosticKind::Duplicate),
+                    diagnostic("n", DiagnosticKind::NotAString("n".into()))]);
+        assert_eq!(conversion.diagnostics[0].to_string(), "'item': There is no '_other' form.");
+        assert_eq!(conversion.bundle.keys().collect::<Vec<_>>(), ["date", "dup"]);
+        assert_eq!(conversion.bundle.format("dup", None).unwrap(), "Plain");
+        assert_eq!(conversion.bundle.format("date", Some(&arg("date", "today"))).unwrap(),
+                   "On today");
+
+        let error = convert(Context::default(), "[]", &I18nextOptions::default()).unwrap_err();
+        assert_eq!(error, BundleError::InvalidResource("Expected an object.".to_string()));
+    }
+}
@@ -0,0 +1,129 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Converters
+//!
+//! This module converts the resources of other localization systems
+//! into [`MessageBundle`]s of ICU messages, so that a project can move
+//! onto this crate without rewriting its translations by hand.
+//!
+//! Each converter reports the parts of a resource that have no ICU
+//! equivalent as diagnostics for the messages they are in, and converts
+//! the rest of the resource.
+//!
+//! The [`chrome`] module also writes bundles back as the resources of
+//! browser extensions, and with the `xliff` feature, the [`xliff`]
+//! module exports bundles for translation tools. The [`rails`] module,
+//! which reads YAML, is only available with the `yaml` feature.
+//!
+//! [`MessageBundle`]: ../struct.MessageBundle.html
+//! [`chrome`]: chrome/index.html
+//! [`rails`]: rails/index.html
+//! [`xliff`]: xliff/index.html
+
+pub mod android;
+pub mod chrome;
+pub mod fluent;
+pub mod gettext;
+pub mod i18next;
+mod printf;
+#[cfg(feature = "yaml")]
+pub mod rails;
+pub mod stringsdict;
+#[cfg(feature = "xliff")]
+pub mod xliff;
+
+use std::cell::Cell;
+use std::fmt;
+use std::string::String;
+use std::vec::Vec;
+
+use MessageBundle;
+
+/// The result of converting a resource, with the kinds of diagnostic
+/// `K` of its converter.
+#[derive(Debug)]
+pub struct Conversion<K> {
+    /// The messages that could be converted, keyed by their names in
+    /// the resource.
+    pub bundle: MessageBundle,
+    /// The problems with the messages, in the order that they are in
+    /// the resource.
+    pub diagnostics: Vec<Diagnostic<K>>,
+}
+
+/// A problem with a message of a resource, of the kind `K` of its
+/// converter.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Diagnostic<K> {
+    /// The name of the message in the resource. This is empty if the
+    /// message has no name, or the problem isn't with a message.
+    pub id: String,
+    /// The line that the problem is on, counting from 1. This is
+    /// `None` for resources that are read without their lines, like
+    /// those of JSON.
+    pub line: Option<usize>,
+    /// What the problem is.
+    pub kind: K,
+}
+
+impl<K: fmt::Display> fmt::Display for Diagnostic<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        if let Some(line) = self.line {
+            write!(f, "Line {}: ", line)?;
+        }
+        if !self.id.is_empty() {
+            write!(f, "'{}': ", self.id)?;
+        }
+        self.kind.fmt(f)
+    }
+}
+
+/// Finds the lines of offsets within a resource. The newlines before
+/// an offset are only counted once as long as the offsets don't
+/// decrease, which is the order that a resource is read in.
+pub(crate) struct Lines<'a> {
+    source: &'a str,
+    /// The offset that the newlines have been counted up to.
+    offset: Cell<usize>,
+    /// The line of `offset`, counting from 1.
+    line: Cell<usize>,
+}
+
+impl<'a> Lines<'a> {
+    pub(crate) fn new(source: &'a str) -> Self {
+        Lines { source, offset: Cell::new(0), line: Cell::new(1) }
+    }
+
+    /// The line of `offset`, counting from 1.
+    pub(crate) fn line(&self, offset: usize) -> usize {
+        if offset < self.offset.get() {
+            self.offset.set(0);
+            self.line.set(1);
+        }
+        let newlines = self.source[self.offset.get()..offset].matches('\n').count();
+        self.offset.set(offset);
+        self.line.set(self.line.get() + newlines);
+        self.line.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Lines;
+
+    #[test]
+    fn lines() {
+        let lines = Lines::new("a\nb\n\nc");
+        assert_eq!(lines.line(0), 1);
+        assert_eq!(lines.line(2), 2);
+        assert_eq!(lines.line(5), 4);
+        assert_eq!(lines.line(5), 4);
+        // Offsets before the last one are counted again.
+        assert_eq!(lines.line(1), 1);
+        assert_eq!(lines.line(3), 2);
+    }
+}
@@ -0,0 +1,97 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Format specifiers of C, as used by gettext and on Apple platforms,
+//! which add `%@` for objects, and of Java, as used on Android.
+
+/// What a format specifier can have in a language.
+pub struct Syntax {
+    /// The flags, except for ` `.
+    flags: &'static [u8],
+    /// The length modifiers, with longer ones before their prefixes.
+    modifiers: &'static [&'static str],
+    /// The conversions.
+    conversions: &'static [u8],
+    /// Whether there are variables like `%#@files@`.
+    variables: bool,
+}
+
+/// C, with the additions of Apple platforms.
+pub const C: Syntax = Syntax {
+    flags: b"-+#0'",
+    modifiers: &["hh", "h", "ll", "l", "q", "z", "t", "j", "L"],
+    conversions: b"@dDiuUxXoOfFeEgGaAcCsSp",
+    variables: true,
+};
+
+/// Java, as used by `String.format` on Android.
+pub const JAVA: Syntax = Syntax {
+    flags: b"-#+0,(",
+    modifiers: &[],
+    conversions: b"bBhHsScCdoxXeEfgGaAtT",
+    variables: false,
+};
+
+/// A format specifier, like `%1$@`, `%ld` or `%#@files@`.
+pub struct Specifier<'a> {
+    /// The length of the specifier in bytes.
+    pub len: usize,
+    /// The position of its argument, counting from 1, if it has one.
+    pub position: Option<usize>,
+    /// Whether it has flags, a width or a precision.
+    pub styled: bool,
+    /// The name of the variable, if it is a variable of Apple
+    /// platforms, like `%#@files@`.
+    pub variable: Option<&'a str>,
+}
+
+/// The format specifier with `syntax` at the start of `text`, if there
+/// is one. `%%` isn't a specifier.
+pub fn specifier<'a>(text: &'a str, syntax: &Syntax) -> Option<Specifier<'a>> {
+    let bytes = text.as_bytes();
+    let digits = |i: usize| bytes[i..].iter().take_while(|b| b.is_ascii_digit()).count();
+    let mut i = 1;
+    let mut position = None;
+    let n = digits(i);
+    if n > 0 && bytes.get(i + n) == Some(&b'$') {
+        position = Some(text[i..i + n].parse().ok().filter(|&position| position > 0)?);
+        i += n + 1;
+    }
+    if syntax.variables && text[i..].starts_with("#@") {
+        let name = &text[i + 2..];
+        let end = name.find('@').filter(|&end| end > 0)?;
+        return Some(Specifier {
+            len: i + 2 + end + 1,
+            position,
+            styled: false,
+            variable: Some(&name[..end]),
+        });
+    }
+    let start = i;
+    // The ` ` flag is left out, so that text like `100% sure` isn't a
+    // specifier.
+    while bytes.get(i).is_some_and(|b| syntax.flags.contains(b)) {
+        i += 1;
+    }
+    i += digits(i);
+    if bytes.get(i) == Some(&b'.') {
+        i += 1;
+        i += digits(i);
+    }
+    let styled = i > start;
+    for modifier in syntax.modifiers {
+        if text[i..].starts_with(modifier) {
+            i += modifier.len();
+            break;
+        }
+    }
+    match bytes.get(i) {
+        Some(b) if syntax.conversions.contains(b) => {
+            Some(Specifier { len: i + 1, position, styled, variable: None })
+        }
+        _ => None,
+    }
+}
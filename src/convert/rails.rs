@@ -0,0 +1,408 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Rails
+//!
+//! This converts the YAML locale files of [Rails], like
+//! `config/locales/de.yml`, into ICU messages:
+//!
+//! ```
+//! use message_format::{arg, Context};
+//! use message_format::convert::rails;
+//!
+//! let conversion = rails::convert(Context::default(), "
+//! de:
+//!   greeting: Hallo, %{name}!
+//!   inbox:
+//!     messages:
+//!       zero: Keine Nachrichten
+//!       one: Eine Nachricht
+//!       other: '%{count} Nachrichten'
+//! ").unwrap();
+//! assert!(conversion.diagnostics.is_empty());
+//! let bundle = conversion.bundle;
+//! assert_eq!(bundle.context().locale().language(), "de");
+//! assert_eq!(bundle.format("greeting", Some(&arg("name", "Jo"))).unwrap(), "Hallo, Jo!");
+//! assert_eq!(bundle.format("inbox.messages", Some(&arg("count", 0))).unwrap(),
+//!            "Keine Nachrichten");
+//! assert_eq!(bundle.format("inbox.messages", Some(&arg("count", 3))).unwrap(),
+//!            "3 Nachrichten");
+//! ```
+//!
+//! The top-level key of the file is its locale, which replaces the
+//! locale of the context, and the keys of the mappings within it are
+//! joined with `.`. As with Rails' pluralization, a mapping whose keys
+//! are all plural categories, like `one` and `other`, becomes a
+//! `plural` format on `count`, and its `zero` key is the branch for
+//! `=0`.
+//!
+//! Interpolations, like `%{name}`, become simple formats, as do Ruby's
+//! formatted interpolations, like `%<name>s`, and `%%` is `%`. Arrays,
+//! like the day names that are often in these files or a list of
+//! messages to choose from at random, and tagged values, like Ruby
+//! objects, have no ICU equivalent, and are reported as [`Diagnostic`]s.
+//! So are aliases, like `<<: *defaults`, which aren't resolved. The
+//! values with anchors are converted like any others.
+//! Lambdas can only be in Ruby locale files, so they aren't converted.
+//!
+//! This is only available with the `yaml` feature.
+//!
+//! [Rails]: https://guides.rubyonrails.org/i18n.html
+//! [`Diagnostic`]: type.Diagnostic.html
+
+use std::fmt;
+
+use icu::ast::{PlainText, PluralFormat, SimpleFormat};
+use yaml::{self, Node, Value};
+use {BundleError, Context, Locale, Message, MessageBundle, MessagePart, PluralCategory};
+
+/// The variable that Rails chooses plural forms with.
+const COUNT: &str = "count";
+
+/// The result of converting a Rails locale file with [`convert`].
+///
+/// [`convert`]: fn.convert.html
+pub type Conversion = super::Conversion<DiagnosticKind>;
+
+/// A problem with a message of a Rails locale file. Its `id` is the key
+/// of the message within the locale, and its `line` is the line that
+/// the value of the message starts on.
+pub type Diagnostic = super::Diagnostic<DiagnosticKind>;
+
+/// The kinds of [`Diagnostic`]. The message is still converted for
+/// `FormatSpecifier`, and isn't otherwise.
+///
+/// [`Diagnostic`]: type.Diagnostic.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum DiagnosticKind {
+    /// The value is an array.
+    Array,
+    /// The value has a tag, like `!ruby/object`. Has the tag.
+    Tag(String),
+    /// The value is an alias, like `*defaults`. Has the name of its
+    /// anchor.
+    Alias(String),
+    /// The key has no value.
+    Empty,
+    /// The plural forms don't include `other`.
+    MissingOther,
+    /// A formatted interpolation has flags, a width or a precision,
+    /// like `%<price>.2f`, which were left out. Has the interpolation.
+    FormatSpecifier(String),
+    /// An earlier message has the same key, so this one was left out.
+    Duplicate,
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            DiagnosticKind::Array => "Arrays have no ICU equivalent.".fmt(f),
+            DiagnosticKind::Tag(ref tag) => write!(f, "The tag '{}' has no ICU equivalent.", tag),
+            DiagnosticKind::Alias(ref anchor) => {
+                write!(f, "The alias '*{}' isn't supported.", anchor)
+            }
+            DiagnosticKind::Empty => "The key has no value.".fmt(f),
+            DiagnosticKind::MissingOther => "There is no 'other' form.".fmt(f),
+            DiagnosticKind::FormatSpecifier(ref specifier) => {
+                write!(f,
+                       "The flags, width and precision of '{}' have no ICU equivalent.",
+                       specifier)
+            }
+            DiagnosticKind::Duplicate => "The message is a duplicate and was left out.".fmt(f),
+        }
+    }
+}
+
+/// Convert the messages of a Rails locale file into ICU messages in a
+/// bundle with `context`, with the locale of the file. The problems
+/// with each message are reported, and the rest of the messages are
+/// converted. An error is only returned if the file isn't valid YAML
+/// or isn't a mapping with a single locale.
+pub fn convert(mut context: Context, yaml: &str) -> Result<Conversion, BundleError> {
+    let invalid = |line, message: String| {
+        BundleError::Line(line, Box::new(BundleError::InvalidResource(message)))
+    };
+    let root = yaml::parse(yaml).map_err(|e| invalid(e.line, format!("{}.", e.message)))?;
+    let (locale, messages) = match root.value {
+        Value::Mapping(ref entries) if entries.len() == 1 => (&entries[0].0, &entries[0].1),
+        _ => return Err(invalid(root.line, "Expected a single locale.".to_string())),
+    };
+    context.locale = Locale::new(locale).map_err(|e| invalid(root.line, e.to_string()))?;
+    let mut conversion = Conversion {
+        bundle: MessageBundle::new(context),
+        diagnostics: vec![],
+    };
+    match messages.value {
+        Value::Mapping(ref entries) => lower_mapping(entries, "", &mut conversion),
+        _ => return Err(invalid(messages.line, "Expected a mapping of messages.".to_string())),
+    }
+    conversion.diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+    Ok(conversion)
+}
+
+/// Convert the messages in a mapping, with their keys after `prefix`.
+fn lower_mapping(entries: &[(String, Node)], prefix: &str, conversion: &mut Conversion) {
+    for (name, node) in entries {
+        let key = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+        if let Value::Mapping(ref entries) = node.value {
+            if node.tag.is_none() && !is_plural(entries) {
+                lower_mapping(entries, &key, conversion);
+                continue;
+            }
+        }
+        let mut kinds = vec![];
+        if let Some(message) = lower_node(node, &mut kinds) {
+            if conversion.bundle.contains_key(&key) {
+                kinds = vec![DiagnosticKind::Duplicate];
+            } else {
+                conversion.bundle.insert_message(&key, message);
+            }
+        }
+        conversion.diagnostics.extend(kinds.into_iter().map(|kind| {
+            Diagnostic { id: key.clone(), line: Some(node.line), kind }
+        }));
+    }
+}
+
+/// The plural category of a key of a mapping of plural forms.
+fn category(key: &str) -> Option<PluralCategory> {
+    key.parse().ok()
+}
+
+/// Whether a mapping is the plural forms of a message.
+fn is_plural(entries: &[(String, Node)]) -> bool {
+    !entries.is_empty() &&
+    entries.iter().all(|(key, node)| {
+        category(key).is_some() && node.tag.is_none() && matches!(node.value, Value::String(_))
+    })
+}
+
+fn lower_node(node: &Node, kinds: &mut Vec<DiagnosticKind>) -> Option<Message> {
+    if let Some(ref tag) = node.tag {
+        kinds.push(DiagnosticKind::Tag(tag.clone()));
+        return None;
+    }
+    match node.value {
+        Value::String(ref text) => Some(lower(text, kinds)),
+        Value::Mapping(ref entries) => lower_plural(entries, kinds),
+        Value::Sequence(_) => {
+            kinds.push(DiagnosticKind::Array);
+            None
+        }
+        Value::Alias(ref anchor) => {
+            kinds.push(DiagnosticKind::Alias(anchor.clone()));
+            None
+        }
+        Value::Null => {
+            kinds.push(DiagnosticKind::Empty);
+            None
+        }
+    }
+}
+
+/// Build a `plural` format on `count` from the plural forms of a
+/// message.
+fn lower_plural(entries: &[(String, Node)], kinds: &mut Vec<DiagnosticKind>) -> Option<Message> {
+    let mut other = None;
+    let mut branches = vec![];
+    for (key, node) in entries {
+        let message = match node.value {
+            Value::String(ref text) => lower(text, kinds),
+            _ => continue,
+        };
+        match category(key) {
+            Some(PluralCategory::Other) => other = Some(message),
+            Some(category) => branches.push((category, message)),
+            None => {}
+        }
+    }
+    let mut fmt = match other {
+        Some(other) => PluralFormat::new(COUNT, other),
+        None => {
+            kinds.push(DiagnosticKind::MissingOther);
+            return None;
+        }
+    };
+    for (category, message) in branches {
+        match category {
+            PluralCategory::Zero => fmt.literal(0, message),
+            PluralCategory::One => fmt.one(message),
+            PluralCategory::Two => fmt.two(message),
+            PluralCategory::Few => fmt.few(message),
+            PluralCategory::Many => fmt.many(message),
+            PluralCategory::Other => {}
+        }
+    }
+    Some(Message::new(vec![Box::new(fmt)]))
+}
+
+/// Build a message from a string, with its interpolations as simple
+/// formats.
+fn lower(text: &str, kinds: &mut Vec<DiagnosticKind>) -> Message {
+    let mut parts: Vec<Box<dyn MessagePart>> = vec![];
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(i) = rest.find('%') {
+        plain.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if rest.starts_with("%%") {
+            plain.push('%');
+            rest = &rest[2..];
+            continue;
+        }
+        let interpolation = match interpolation(rest) {
+            Some(interpolation) => interpolation,
+            None => {
+                plain.push('%');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        let (len, name, styled) = interpolation;
+        if styled {
+            kinds.push(DiagnosticKind::FormatSpecifier(rest[..len].to_string()));
+        }
+        if !plain.is_empty() {
+            parts.push(Box::new(PlainText::new(&plain)));
+            plain.clear();
+        }
+        parts.push(Box::new(SimpleFormat::new(name)));
+        rest = &rest[len..];
+    }
+    plain.push_str(rest);
+    if !plain.is_empty() {
+        parts.push(Box::new(PlainText::new(&plain)));
+    }
+    Message::new(parts)
+}
+
+/// The interpolation at the start of `text`, like `%{name}` or
+/// `%<price>.2f`, with its length, its name and whether it has flags, a
+/// width or a precision.
+fn interpolation(text: &str) -> Option<(usize, &str, bool)> {
+    let is_name = |name: &str| {
+        !name.is_empty() && name.chars().all(|ch| ch.is_alphanumeric() || ch == '_')
+    };
+    if let Some(rest) = text.strip_prefix("%{") {
+        let end = rest.find('}')?;
+        let name = &rest[..end];
+        return if is_name(name) { Some((end + 3, name, false)) } else { None };
+    }
+    let rest = text.strip_prefix("%<")?;
+    let end = rest.find('>')?;
+    let name = &rest[..end];
+    let style = &rest[end + 1..];
+    let conversion = style.find(|ch| "bBdiouxXeEfgGcps".contains(ch))?;
+    let flags = &style[..conversion];
+    if !is_name(name) || !flags.chars().all(|ch| "-+ 0#*.".contains(ch) || ch.is_ascii_digit()) {
+        return None;
+    }
+    Some((end + conversion + 4, name, !flags.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert, Diagnostic, DiagnosticKind};
+    use {arg, BundleError, Context, Locale};
+
+    #[test]
+    fn interpolation() {
+        let conversion = convert(Context::default(), r#"
+en:
+  price: "%<name>s costs %<price>.2f, 100%% sure, 50% off, %{ bad } and %{a}%{b}"
+"#).unwrap();
+        let diagnostic = |kind| {
+            Diagnostic { id: "price".to_string(), line: Some(3), kind }
+        };
+        assert_eq!(conversion.diagnostics,
+                   [diagnostic(DiagnosticKind::FormatSpecifier("%<price>.2f".into()))]);
+        let args = arg("name", "Tea");
+        let args = args.arg("price", 2);
+        let args = args.arg("a", "x");
+        assert_eq!(conversion.bundle.format("price", Some(&args.arg("b", "y"))).unwrap(),
+                   "Tea costs 2, 100% sure, 50% off, %{ bad } and xy");
+    }
+
+    #[test]
+    fn plurals() {
+        let conversion = convert(Context::default(), "
+pl:
+  files:
+    zero: Brak plików
+    one: '%{count} plik'
+    few: '%{count} pliki'
+    many: '%{count} plików'
+    other: '%{count} pliku'
+  activerecord:
+    errors:
+      one: Not a plural
+      title: Errors
+").unwrap();
+        assert_eq!(conversion.diagnostics, []);
+        let bundle = conversion.bundle;
+        assert_eq!(*bundle.context().locale(), Locale::new("pl").unwrap());
+        let format = |n| bundle.format("files", Some(&arg("count", n))).unwrap();
+        assert_eq!([format(0), format(1), format(3), format(5)],
+                   ["Brak plików", "1 plik", "3 pliki", "5 plików"]);
+        assert_eq!(bundle.keys().collect::<Vec<_>>(),
+                   ["activerecord.errors.one", "activerecord.errors.title", "files"]);
+    }
+
+    #[test]
+    fn diagnostics() {
+        let conversion = convert(Context::default(), "
+en:
+  date:
+    day_names: [Sunday, Monday]
+  greetings:
+    - Hi
+    - Hello
+  object: !ruby/object:Foo {}
+  empty:
+  items:
+    one: An item
+  a.b: First
+  a:
+    b: Second
+  defaults: &defaults
+    title: Title
+  page:
+    <<: *defaults
+    body: Body
+").unwrap();
+        let diagnostic = |id: &str, line, kind| {
+            Diagnostic { id: id.to_string(), line: Some(line), kind }
+        };
+        assert_eq!(conversion.diagnostics,
+                   [diagnostic("date.day_names", 4, DiagnosticKind::Array),
+                    diagnostic("greetings", 6, DiagnosticKind::Array),
+                    diagnostic("object", 8, DiagnosticKind::Tag("!ruby/object:Foo".into())),
+                    diagnostic("empty", 9, DiagnosticKind::Empty),
+                    diagnostic("items", 11, DiagnosticKind::MissingOther),
+                    diagnostic("a.b", 14, DiagnosticKind::Duplicate),
+                    diagnostic("page.<<", 18, DiagnosticKind::Alias("defaults".into()))]);
+        assert_eq!(conversion.diagnostics[3].to_string(), "Line 9: 'empty': The key has no value.");
+        assert_eq!(conversion.diagnostics[6].to_string(),
+                   "Line 18: 'page.<<': The alias '*defaults' isn't supported.");
+        let bundle = conversion.bundle;
+        assert_eq!(bundle.format("a.b", None).unwrap(), "First");
+        assert_eq!(bundle.format("defaults.title", None).unwrap(), "Title");
+        assert_eq!(bundle.format("page.body", None).unwrap(), "Body");
+    }
+
+    #[test]
+    fn errors() {
+        let error = |yaml| convert(Context::default(), yaml).unwrap_err();
+        let invalid = |line, message: &str| {
+            BundleError::Line(line,
+                              Box::new(BundleError::InvalidResource(message.to_string())))
+        };
+        assert_eq!(error("en:\n  a: 'b"), invalid(2, "Unterminated string."));
+        assert_eq!(error("en:\n  a: b\nde:\n  a: c"), invalid(1, "Expected a single locale."));
+        assert_eq!(error("en: text"), invalid(1, "Expected a mapping of messages."));
+        assert!(matches!(error("not a locale!:\n  a: b"), BundleError::Line(1, _)));
+    }
+}
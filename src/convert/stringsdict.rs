@@ -0,0 +1,592 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Stringsdict
+//!
+//! This converts the plural rules of iOS and macOS, `.stringsdict`
+//! property lists, into ICU messages:
+//!
+//! ```
+//! use message_format::{arg, Context};
+//! use message_format::convert::stringsdict;
+//!
+//! let conversion = stringsdict::convert(Context::default(), r#"<?xml version="1.0"?>
+//! <plist version="1.0">
+//! <dict>
+//!     <key>songs</key>
+//!     <dict>
+//!         <key>NSStringLocalizedFormatKey</key>
+//!         <string>%@ has %#@songs@</string>
+//!         <key>songs</key>
+//!         <dict>
+//!             <key>NSStringFormatSpecTypeKey</key>
+//!             <string>NSStringPluralRuleType</string>
+//!             <key>NSStringFormatValueTypeKey</key>
+//!             <string>d</string>
+//!             <key>one</key>
+//!             <string>one song</string>
+//!             <key>other</key>
+//!             <string>%d songs</string>
+//!         </dict>
+//!     </dict>
+//! </dict>
+//! </plist>"#).unwrap();
+//! assert!(conversion.diagnostics.is_empty());
+//! let bundle = conversion.bundle;
+//! assert_eq!(bundle.format("songs", Some(&arg("0", "Jo").arg("songs", 3))).unwrap(),
+//!            "Jo has 3 songs");
+//! ```
+//!
+//! Each variable of a format key, like `%#@songs@`, becomes a `plural`
+//! format on a variable of the same name, and the format specifiers of
+//! its strings, like `%d` or `%ld`, become `#`. A `zero` string is used
+//! for the number 0 in every language, as it is on Apple platforms, as
+//! well as for the `zero` category of languages that have one.
+//!
+//! Other format specifiers become simple formats of the argument's
+//! position, counting from 0, so `%@` and `%1$@` become `{0}`, and
+//! each variable takes up a position as well. `%%` is `%`.
+//!
+//! A format key with several variables becomes nested `plural` formats,
+//! one for each variable in order, with a branch for every combination
+//! of their strings. The numbers of the outer variables are then
+//! written by simple formats. Strings may use variables of their own.
+//!
+//! Rules other than plural rules, such as the width variations of
+//! `NSStringVariableWidthRuleType`, have no ICU equivalent, and are
+//! reported as [`Diagnostic`]s along with the other problems that keep
+//! an entry from being converted.
+//!
+//! [`Diagnostic`]: type.Diagnostic.html
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::printf::{self, specifier};
+use super::Lines;
+use icu::ast::{PlaceholderFormat, PlainText, PluralFormat, SimpleFormat};
+use xml::{self, Element};
+use {BundleError, Context, Message, MessageBundle, MessagePart, PluralCategory};
+
+/// Variables can be nested this deeply, since each one multiplies the
+/// number of branches of an entry.
+const MAX_VARIABLES: usize = 4;
+
+/// The result of converting a `.stringsdict` file with [`convert`].
+///
+/// [`convert`]: fn.convert.html
+pub type Conversion = super::Conversion<DiagnosticKind>;
+
+/// A problem with an entry of a `.stringsdict` file. Its `id` is the
+/// key of the entry, and its `line` is the line that the key is on.
+pub type Diagnostic = super::Diagnostic<DiagnosticKind>;
+
+/// The kinds of [`Diagnostic`]. The entry is still converted for
+/// `FormatSpecifier`, and isn't otherwise.
+///
+/// [`Diagnostic`]: type.Diagnostic.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum DiagnosticKind {
+    /// The entry isn't a `<dict>`. Has the name of its element, like
+    /// `string`.
+    Unsupported(String),
+    /// The entry has no `NSStringLocalizedFormatKey` string.
+    MissingFormatKey,
+    /// A variable is used, but the entry doesn't define it. Has the
+    /// variable.
+    UnknownVariable(String),
+    /// A variable has a rule type other than `NSStringPluralRuleType`.
+    /// Has the variable and the rule type.
+    UnsupportedRuleType(String, String),
+    /// A variable has no string for the `other` category. Has the
+    /// variable.
+    MissingOther(String),
+    /// A variable is used within its own strings. Has the variable.
+    RecursiveVariable(String),
+    /// Variables are nested more deeply than they can be converted.
+    TooManyVariables,
+    /// A format specifier has flags, a width or a precision, like
+    /// `%.2f`, which were left out. Has the specifier.
+    FormatSpecifier(String),
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            DiagnosticKind::Unsupported(ref element) => {
+                write!(f, "'<{}>' has no ICU equivalent and was left out.", element)
+            }
+            DiagnosticKind::MissingFormatKey => "The entry has no format key.".fmt(f),
+            DiagnosticKind::UnknownVariable(ref variable) => {
+                write!(f, "Variable '{}' isn't defined.", variable)
+            }
+            DiagnosticKind::UnsupportedRuleType(ref variable, ref rule_type) => {
+                write!(f,
+                       "Variable '{}' has rule type '{}', which has no ICU equivalent.",
+                       variable,
+                       rule_type)
+            }
+            DiagnosticKind::MissingOther(ref variable) => {
+                write!(f, "Variable '{}' has no 'other' string.", variable)
+            }
+            DiagnosticKind::RecursiveVariable(ref variable) => {
+                write!(f, "Variable '{}' is used within its own strings.", variable)
+            }
+            DiagnosticKind::TooManyVariables => "Variables are nested too deeply.".fmt(f),
+            DiagnosticKind::FormatSpecifier(ref specifier) => {
+                write!(f,
+                       "The flags, width and precision of '{}' have no ICU equivalent.",
+                       specifier)
+            }
+        }
+    }
+}
+
+/// Convert the entries of a `.stringsdict` file, an XML property list,
+/// into ICU messages in a bundle with `context`. The problems with each
+/// entry are reported, and the rest of the entries are converted. An
+/// error is only returned if the file isn't well-formed XML or isn't a
+/// property list of a `<dict>`.
+pub fn convert(context: Context, plist: &str) -> Result<Conversion, BundleError> {
+    let lines = Lines::new(plist);
+    let invalid = |offset, message: String| {
+        BundleError::Line(lines.line(offset), Box::new(BundleError::InvalidResource(message)))
+    };
+    let root = xml::parse(plist).map_err(|e| invalid(e.offset, format!("{}.", e.message)))?;
+    let dict = match root.elements().next() {
+        Some(dict) if root.name == "plist" && dict.name == "dict" => dict,
+        _ => return Err(invalid(root.offset, "Expected a property list.".to_string())),
+    };
+    let mut conversion = Conversion {
+        bundle: MessageBundle::new(context),
+        diagnostics: vec![],
+    };
+    for (key, entry) in entries(dict) {
+        let id = key.text();
+        let mut kinds = vec![];
+        if let Some(message) = lower_entry(entry, &mut kinds) {
+            conversion.bundle.insert_message(&id, message);
+        }
+        conversion.diagnostics.extend(kinds.into_iter().map(|kind| {
+            Diagnostic { id: id.clone(), line: Some(lines.line(key.offset)), kind }
+        }));
+    }
+    Ok(conversion)
+}
+
+/// The keys of a `<dict>` with their values, which are the elements
+/// after them.
+fn entries(dict: &Element) -> Vec<(&Element, &Element)> {
+    let elements = dict.elements().collect::<Vec<_>>();
+    elements.chunks(2)
+        .filter_map(|pair| match *pair {
+            [key, value] if key.name == "key" => Some((key, value)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A piece of a format key or of a variable's string.
+#[derive(Clone,Debug,PartialEq)]
+enum Token {
+    Text(String),
+    /// A format specifier of an argument, with its position counting
+    /// from 1.
+    Arg(usize),
+    /// The number of a variable.
+    Value(String),
+    /// A variable, like `%#@files@`.
+    Variable(String),
+}
+
+/// The strings of a variable, tokenized.
+type Rule = Vec<(PluralCategory, Vec<Token>)>;
+
+fn lower_entry(entry: &Element, kinds: &mut Vec<DiagnosticKind>) -> Option<Message> {
+    if entry.name != "dict" {
+        kinds.push(DiagnosticKind::Unsupported(entry.name.clone()));
+        return None;
+    }
+    let mut format_key = None;
+    let mut variables = vec![];
+    for (key, value) in entries(entry) {
+        match &*key.text() {
+            "NSStringLocalizedFormatKey" if value.name == "string" => format_key = Some(value),
+            variable if value.name == "dict" => variables.push((variable.to_string(), value)),
+            _ => {}
+        }
+    }
+    let format_key = match format_key {
+        Some(format_key) => format_key,
+        None => {
+            kinds.push(DiagnosticKind::MissingFormatKey);
+            return None;
+        }
+    };
+    let mut positions = HashMap::new();
+    let tokens = tokenize(&format_key.text(), None, &mut positions, kinds);
+    let mut rules = HashMap::new();
+    for (variable, dict) in variables {
+        let position = positions.get(&variable).cloned();
+        let rule = lower_rule(&variable, dict, position, kinds);
+        rules.insert(variable, rule);
+    }
+    expand(&tokens, &rules, &mut vec![], kinds)
+}
+
+/// Tokenize the strings of a variable whose argument is at `position`.
+/// The rule is `None` if it isn't a plural rule.
+fn lower_rule(variable: &str,
+              dict: &Element,
+              position: Option<usize>,
+              kinds: &mut Vec<DiagnosticKind>)
+              -> Option<Rule> {
+    let mut rule = vec![];
+    for (key, value) in entries(dict) {
+        let key = key.text();
+        match &*key {
+            "NSStringFormatSpecTypeKey" => {
+                let rule_type = value.text();
+                if rule_type != "NSStringPluralRuleType" {
+                    kinds.push(DiagnosticKind::UnsupportedRuleType(variable.to_string(),
+                                                                   rule_type));
+                    return None;
+                }
+            }
+            "zero" | "one" | "two" | "few" | "many" | "other" => {
+                let value_of = Some((variable, position));
+                let tokens = tokenize(&value.text(), value_of, &mut HashMap::new(), kinds);
+                rule.push((key.parse().ok()?, tokens));
+            }
+            _ => {}
+        }
+    }
+    Some(rule)
+}
+
+/// Build the message for `tokens`, with a `plural` format for the first
+/// of their variables, within the `plural` formats of the `outer`
+/// variables.
+fn expand(tokens: &[Token],
+          rules: &HashMap<String, Option<Rule>>,
+          outer: &mut Vec<String>,
+          kinds: &mut Vec<DiagnosticKind>)
+          -> Option<Message> {
+    let variable = match tokens.iter().find_map(|token| match *token {
+        Token::Variable(ref variable) => Some(variable),
+        _ => None,
+    }) {
+        Some(variable) => variable,
+        None => return Some(lower(tokens, outer.last())),
+    };
+    if outer.contains(variable) {
+        kinds.push(DiagnosticKind::RecursiveVariable(variable.clone()));
+        return None;
+    }
+    if outer.len() == MAX_VARIABLES {
+        kinds.push(DiagnosticKind::TooManyVariables);
+        return None;
+    }
+    let rule = match rules.get(variable) {
+        Some(Some(rule)) => rule,
+        // The rule type was reported.
+        Some(None) => return None,
+        None => {
+            kinds.push(DiagnosticKind::UnknownVariable(variable.clone()));
+            return None;
+        }
+    };
+    outer.push(variable.clone());
+    let mut other = None;
+    let mut branches = vec![];
+    for (category, strings) in rule {
+        let mut branch = vec![];
+        for token in tokens {
+            match *token {
+                Token::Variable(ref v) if v == variable => branch.extend_from_slice(strings),
+                ref token => branch.push(token.clone()),
+            }
+        }
+        let message = expand(&branch, rules, outer, kinds)?;
+        match *category {
+            PluralCategory::Other => other = Some(message),
+            category => branches.push((category, message)),
+        }
+    }
+    outer.pop();
+    let mut fmt = match other {
+        Some(other) => PluralFormat::new(variable, other),
+        None => {
+            kinds.push(DiagnosticKind::MissingOther(variable.clone()));
+            return None;
+        }
+    };
+    for (category, message) in branches {
+        match category {
+            PluralCategory::Zero => {
+                fmt.literal(0, message.clone());
+                fmt.zero(message);
+            }
+            PluralCategory::One => fmt.one(message),
+            PluralCategory::Two => fmt.two(message),
+            PluralCategory::Few => fmt.few(message),
+            PluralCategory::Many => fmt.many(message),
+            PluralCategory::Other => {}
+        }
+    }
+    Some(Message::new(vec![Box::new(fmt)]))
+}
+
+/// Build the message for tokens without variables, within the `plural`
+/// format of the `innermost` variable, whose number is `#`.
+fn lower(tokens: &[Token], innermost: Option<&String>) -> Message {
+    let mut parts: Vec<Box<dyn MessagePart>> = vec![];
+    let mut plain = String::new();
+    for token in tokens {
+        let part: Box<dyn MessagePart> = match *token {
+            Token::Text(ref text) => {
+                plain.push_str(text);
+                continue;
+            }
+            Token::Arg(position) => Box::new(SimpleFormat::new(&(position - 1).to_string())),
+            Token::Value(ref variable) if Some(variable) == innermost => {
+                Box::new(PlaceholderFormat::new())
+            }
+            Token::Value(ref variable) | Token::Variable(ref variable) => {
+                Box::new(SimpleFormat::new(variable))
+            }
+        };
+        if !plain.is_empty() {
+            parts.push(Box::new(PlainText::new(&plain)));
+            plain.clear();
+        }
+        parts.push(part);
+    }
+    if !plain.is_empty() {
+        parts.push(Box::new(PlainText::new(&plain)));
+    }
+    Message::new(parts)
+}
+
+/// Split a format key, or the string of the variable in `value_of` with
+/// its position, into tokens. The positions of the variables of a
+/// format key are added to `positions`.
+fn tokenize(text: &str,
+            value_of: Option<(&str, Option<usize>)>,
+            positions: &mut HashMap<String, usize>,
+            kinds: &mut Vec<DiagnosticKind>)
+            -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut plain = String::new();
+    // The position of the next argument without an explicit position.
+    let mut next = 0;
+    let mut rest = text;
+    while let Some(i) = rest.find('%') {
+        plain.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if rest.starts_with("%%") {
+            plain.push('%');
+            rest = &rest[2..];
+            continue;
+        }
+        let specifier = match specifier(rest, &printf::C) {
+            Some(specifier) => specifier,
+            None => {
+                plain.push('%');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        if specifier.styled {
+            kinds.push(DiagnosticKind::FormatSpecifier(rest[..specifier.len].to_string()));
+        }
+        if !plain.is_empty() {
+            tokens.push(Token::Text(plain.split_off(0)));
+        }
+        let position = specifier.position.unwrap_or_else(|| {
+            next += 1;
+            next
+        });
+        tokens.push(match (specifier.variable, value_of) {
+            (Some(variable), _) => {
+                positions.entry(variable.to_string()).or_insert(position);
+                Token::Variable(variable.to_string())
+            }
+            // The variable's own number, whether or not it has a position.
+            (None, Some((variable, own))) if specifier.position.is_none() ||
+                                             specifier.position == own => {
+                Token::Value(variable.to_string())
+            }
+            (None, _) => Token::Arg(position),
+        });
+        rest = &rest[specifier.len..];
+    }
+    plain.push_str(rest);
+    if !plain.is_empty() {
+        tokens.push(Token::Text(plain));
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert, Diagnostic, DiagnosticKind};
+    use {arg, Context, Locale, MessageBundle};
+
+    /// The key, the format key and the keys and strings of each variable
+    /// of an entry.
+    type Entry<'a> = (&'a str, &'a str, &'a [(&'a str, &'a [(&'a str, &'a str)])]);
+
+    /// A `.stringsdict` file with an entry for each of `entries`.
+    fn plist(entries: &[Entry]) -> String {
+        let mut plist = "<plist version=\"1.0\">\n<dict>\n".to_string();
+        for &(key, format_key, variables) in entries {
+            plist.push_str(&format!("<key>{}</key>\n<dict>\n\
+                                     <key>NSStringLocalizedFormatKey</key>\n\
+                                     <string>{}</string>\n",
+                                    key,
+                                    format_key));
+            for &(variable, strings) in variables {
+                plist.push_str(&format!("<key>{}</key>\n<dict>\n", variable));
+                for &(category, string) in strings {
+                    plist.push_str(&format!("<key>{}</key><string>{}</string>\n",
+                                            category,
+                                            string));
+                }
+                plist.push_str("</dict>\n");
+            }
+            plist.push_str("</dict>\n");
+        }
+        plist.push_str("</dict>\n</plist>\n");
+        plist
+    }
+
+    const PLURAL: (&str, &str) = ("NSStringFormatSpecTypeKey", "NSStringPluralRuleType");
+
+    fn bundle(entries: &[Entry]) -> MessageBundle {
+        let conversion = convert(Context::default(), &plist(entries)).unwrap();
+        assert_eq!(conversion.diagnostics, []);
+        conversion.bundle
+    }
+
+    #[test]
+    fn plurals() {
+        let bundle = bundle(&[("files",
+                               "%1$@ has %2$#@files@.",
+                               &[("files",
+                                  &[PLURAL,
+                                    ("NSStringFormatValueTypeKey", "ld"),
+                                    ("zero", "no files"),
+                                    ("one", "one file"),
+                                    ("other", "%ld files in %1$@")])])]);
+        let format = |count| bundle.format("files", Some(&arg("0", "Jo").arg("files", count)));
+        assert_eq!(format(0).unwrap(), "Jo has no files.");
+        assert_eq!(format(1).unwrap(), "Jo has one file.");
+        assert_eq!(format(1234).unwrap(), "Jo has 1234 files in Jo.");
+    }
+
+    #[test]
+    fn zero_strings() {
+        let plist = plist(&[("days",
+                             "%#@days@",
+                             &[("days",
+                                &[PLURAL,
+                                  ("zero", "%d (zero)"),
+                                  ("one", "%d (one)"),
+                                  ("other", "%d (other)")])])]);
+        let conversion = convert(Context::new(Locale::new("lv").unwrap(), None), &plist)
+            .unwrap();
+        let format = |count| conversion.bundle.format("days", Some(&arg("days", count))).unwrap();
+        assert_eq!(format(0), "0 (zero)");
+        assert_eq!(format(10), "10 (zero)");
+        assert_eq!(format(21), "21 (one)");
+        assert_eq!(format(2), "2 (other)");
+    }
+
+    #[test]
+    fn several_variables() {
+        let files = [PLURAL, ("one", "%d file"), ("other", "%d files")];
+        let folders = [PLURAL, ("one", "one folder"), ("other", "%d folders")];
+        let bundle = bundle(&[("summary",
+                               "%#@files@ in %#@folders@ by %@",
+                               &[("files", &files), ("folders", &folders)]),
+                              ("nested",
+                               "%#@total@",
+                               &[("total", &[PLURAL, ("other", "%d of %#@done@")]),
+                                 ("done", &[PLURAL, ("one", "one done"), ("other", "%d")])])]);
+        let format = |files, folders| {
+            let args = arg("files", files);
+            let args = args.arg("folders", folders);
+            bundle.format("summary", Some(&args.arg("2", "Jo"))).unwrap()
+        };
+        assert_eq!(format(1, 1), "1 file in one folder by Jo");
+        assert_eq!(format(5, 1), "5 files in one folder by Jo");
+        assert_eq!(format(1, 3), "1 file in 3 folders by Jo");
+        assert_eq!(format(5, 3), "5 files in 3 folders by Jo");
+        let format = |total, done| {
+            bundle.format("nested", Some(&arg("total", total).arg("done", done))).unwrap()
+        };
+        assert_eq!(format(10, 1), "10 of one done");
+        assert_eq!(format(10, 4), "10 of 4");
+    }
+
+    #[test]
+    fn format_specifiers() {
+        let bundle = bundle(&[("escapes", "100% sure, %% %s and %2$@", &[]),
+                              ("lengths", "%lld %lu %hhd %zd %qd", &[])]);
+        let args = arg("0", 1);
+        let args = args.arg("1", "b");
+        assert_eq!(bundle.format("escapes", Some(&args)).unwrap(), "100% sure, % 1 and b");
+        let args = args.arg("2", 3);
+        let args = args.arg("3", 4);
+        assert_eq!(bundle.format("lengths", Some(&args.arg("4", 5))).unwrap(), "1 b 3 4 5");
+    }
+
+    #[test]
+    fn diagnostics() {
+        let other = [PLURAL, ("other", "%d")];
+        let plist = plist(&[("unknown", "%#@a@ %#@b@", &[("a", &other)]),
+                            ("missing_other", "%#@a@", &[("a", &[PLURAL, ("one", "one")])]),
+                            ("width",
+                             "%#@a@",
+                             &[("a",
+                                &[("NSStringFormatSpecTypeKey",
+                                   "NSStringVariableWidthRuleType"),
+                                  ("other", "Wide")])]),
+                            ("recursive", "%#@a@", &[("a", &[PLURAL, ("other", "%#@a@")])]),
+                            ("styled", "%05.1f", &[])])
+            .replace("</dict>\n</plist>",
+                     "<key>no_format_key</key>\n<dict/>\n\
+                      <key>text</key>\n<string>Hi</string>\n</dict>\n</plist>");
+        let conversion = convert(Context::default(), &plist).unwrap();
+        let line = |id: &str| {
+            plist.lines().position(|line| line == format!("<key>{}</key>", id)).unwrap() + 1
+        };
+        let diagnostic = |id: &str, kind| {
+            Diagnostic { id: id.to_string(), line: Some(line(id)), kind }
+        };
+        assert_eq!(conversion.diagnostics,
+                   [diagnostic("unknown", DiagnosticKind::UnknownVariable("b".into())),
+                    diagnostic("missing_other", DiagnosticKind::MissingOther("a".into())),
+                    diagnostic("width",
+                               DiagnosticKind::UnsupportedRuleType(
+                                   "a".into(), "NSStringVariableWidthRuleType".into())),
+                    diagnostic("recursive", DiagnosticKind::RecursiveVariable("a".into())),
+                    diagnostic("styled", DiagnosticKind::FormatSpecifier("%05.1f".into())),
+                    diagnostic("no_format_key", DiagnosticKind::MissingFormatKey),
+                    diagnostic("text", DiagnosticKind::Unsupported("string".into()))]);
+        assert_eq!(conversion.diagnostics[0].to_string(),
+                   format!("Line {}: 'unknown': Variable 'b' isn't defined.", line("unknown")));
+        assert_eq!(conversion.bundle.keys().collect::<Vec<_>>(), ["styled"]);
+    }
+
+    #[test]
+    fn invalid_plists() {
+        assert!(convert(Context::default(), "<plist>").is_err());
+        assert!(convert(Context::default(), "<plist><array/></plist>").is_err());
+        assert!(convert(Context::default(), "<resources/>").is_err());
+    }
+}
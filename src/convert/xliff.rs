@@ -0,0 +1,859 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! XLIFF
+//!
+//! This writes the messages of a bundle as an [XLIFF 1.2] document, the
+//! format that most translation tools and vendors exchange, and reads
+//! the translations back from one:
+//!
+//! ```
+//! use message_format::{Context, MessageBundle};
+//! use message_format::convert::xliff::{self, XliffOptions};
+//!
+//! let mut en = MessageBundle::new(Context::default());
+//! en.insert("greeting", "Hello, {name}!").unwrap();
+//! let options = XliffOptions { placeholders: true, ..XliffOptions::default() };
+//! let xliff = xliff::export(&en, None, &options).unwrap();
+//! assert!(xliff.contains("<source>Hello, <ph id=\"1\">{name}</ph>!</source>"));
+//! ```
+//!
+//! Each message is a `<trans-unit>` with its key as its `id`, its ICU
+//! source as the `<source>`, and the translation, if there is one, as
+//! the `<target>`. The description in the [`MessageMetadata`] of a
+//! message is written as a `<note>`.
+//!
+//! The translated document is read with [`import`], which puts the
+//! native code of inline elements like `<ph>` and `<x/>` back into the
+//! messages, and reports the translation units that need attention as
+//! [`Diagnostic`]s.
+//!
+//! This is only available with the `xliff` feature.
+//!
+//! [XLIFF 1.2]: http://docs.oasis-open.org/xliff/v1.2/os/xliff-core.html
+//! [`MessageMetadata`]: ../../struct.MessageMetadata.html
+//! [`import`]: fn.import.html
+//! [`Diagnostic`]: type.Diagnostic.html
+
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+use super::Lines;
+use icu::{self, ParseError};
+use xml::{self, Element, Node};
+use {BundleError, Context, Locale, Message, MessageBundle, MessageMetadata, MessageVisitor,
+     PluralCategory};
+
+/// Options for [`export`].
+///
+/// [`export`]: fn.export.html
+#[derive(Clone,Debug,PartialEq)]
+pub struct XliffOptions {
+    /// The `original` attribute of the `<file>`, naming the resource
+    /// that the messages are from. The default is `messages`.
+    pub original: String,
+    /// Whether the ICU syntax of a message, like `{name}`, `#` and the
+    /// selectors of a `plural`, is written as inline `<ph>` elements, so
+    /// that translation tools protect it and only the text around it is
+    /// translated. The default is `false`.
+    pub placeholders: bool,
+}
+
+impl Default for XliffOptions {
+    fn default() -> Self {
+        XliffOptions {
+            original: "messages".to_string(),
+            placeholders: false,
+        }
+    }
+}
+
+/// An error exporting messages with [`export`].
+///
+/// [`export`]: fn.export.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum ExportError {
+    /// The message was added as a `Message` rather than from its ICU
+    /// source, and its source can't be rebuilt because it has a format,
+    /// like `number` or `choice`, whose style isn't part of its
+    /// structure. Has the locale of its bundle, then its key.
+    UnknownSource(String, String),
+}
+
+impl Error for ExportError {}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            ExportError::UnknownSource(ref locale, ref key) => {
+                write!(f, "The source of message '{}' for '{}' isn't known.", key, locale)
+            }
+        }
+    }
+}
+
+/// Write the messages of `source` as an XLIFF 1.2 document, with their
+/// translations from `target`, if there is one.
+///
+/// There is a `<trans-unit>` for each message in `source`, in the order
+/// of their keys. Messages in `target` that aren't in `source` are left
+/// out. A message that was loaded from a resource or added with
+/// [`MessageBundle::insert`] is written with its source. The source of
+/// a message that was added as a `Message`, like those from the other
+/// converters or [`MessageBundle::pseudo_localize`], is rebuilt from its
+/// text, variables, `plural`s and `select`s, and the errors for all of
+/// the messages that it can't be rebuilt for are returned together.
+/// The offset of a `plural` isn't part of the structure it is rebuilt
+/// from, so it is left out.
+///
+/// A message that isn't valid ICU is written as text, even if
+/// `options.placeholders` is set.
+///
+/// [`MessageBundle::insert`]: ../../struct.MessageBundle.html#method.insert
+/// [`MessageBundle::pseudo_localize`]: ../../struct.MessageBundle.html#method.pseudo_localize
+pub fn export(source: &MessageBundle,
+              target: Option<&MessageBundle>,
+              options: &XliffOptions)
+              -> Result<String, Vec<ExportError>> {
+    let mut xliff = String::new();
+    xliff.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xliff.push_str("<xliff version=\"1.2\" xmlns=\"urn:oasis:names:tc:xliff:document:1.2\">\n");
+    xliff.push_str("  <file original=\"");
+    escape(&options.original, &mut xliff);
+    xliff.push_str("\" source-language=\"");
+    escape(&source.context().locale().to_string(), &mut xliff);
+    if let Some(target) = target {
+        xliff.push_str("\" target-language=\"");
+        escape(&target.context().locale().to_string(), &mut xliff);
+    }
+    xliff.push_str("\" datatype=\"x-icu-messageformat\">\n");
+    xliff.push_str("    <body>\n");
+    let mut errors = vec![];
+    for key in source.keys() {
+        let text = match source_of(source, key) {
+            Ok(text) => text,
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+        let translation = match target.filter(|target| target.contains_key(key)) {
+            Some(target) => {
+                match source_of(target, key) {
+                    Ok(translation) => Some(translation),
+                    Err(error) => {
+                        errors.push(error);
+                        continue;
+                    }
+                }
+            }
+            None => None,
+        };
+        xliff.push_str("      <trans-unit id=\"");
+        escape(key, &mut xliff);
+        xliff.push_str("\" resname=\"");
+        escape(key, &mut xliff);
+        xliff.push_str("\" xml:space=\"preserve\">\n");
+        xliff.push_str("        <source>");
+        write_segment(&text, options.placeholders, &mut xliff);
+        xliff.push_str("</source>\n");
+        if let Some(translation) = translation {
+            xliff.push_str("        <target>");
+            write_segment(&translation, options.placeholders, &mut xliff);
+            xliff.push_str("</target>\n");
+        }
+        let description = source.metadata(key).and_then(|m| m.description.as_ref());
+        if let Some(description) = description {
+            xliff.push_str("        <note>");
+            escape(description, &mut xliff);
+            xliff.push_str("</note>\n");
+        }
+        xliff.push_str("      </trans-unit>\n");
+    }
+    xliff.push_str("    </body>\n");
+    xliff.push_str("  </file>\n");
+    xliff.push_str("</xliff>\n");
+    if errors.is_empty() { Ok(xliff) } else { Err(errors) }
+}
+
+/// The ICU source of the message with `key`, rebuilt from the message
+/// if it was added without one.
+fn source_of<'a>(bundle: &'a MessageBundle, key: &str) -> Result<Cow<'a, str>, ExportError> {
+    if let Some(source) = bundle.source(key) {
+        return Ok(Cow::Borrowed(source));
+    }
+    let mut writer = IcuWriter::default();
+    if let Ok(message) = bundle.try_get(key) {
+        message.accept(&mut writer);
+    }
+    if writer.unsupported {
+        let locale = bundle.context().locale().to_string();
+        return Err(ExportError::UnknownSource(locale, key.to_string()));
+    }
+    Ok(Cow::Owned(writer.finish()))
+}
+
+/// Writes the ICU source of a message from its structure.
+#[derive(Debug,Default)]
+struct IcuWriter {
+    source: String,
+    /// For each `plural` or `select` that is being written, innermost
+    /// last, whether it is a `plural` and the selectors of the branches
+    /// that are left to write, in reverse.
+    formats: Vec<(bool, Vec<String>)>,
+    /// Whether the end of the source is quoted text, which is kept open
+    /// so that the text of the next part can continue it.
+    quoted: bool,
+    /// Whether there is a part whose source can't be written.
+    unsupported: bool,
+}
+
+impl IcuWriter {
+    /// Write `syntax`, ending the quoted text before it.
+    fn syntax(&mut self, syntax: &str) {
+        if self.quoted {
+            self.source.push('\'');
+            self.quoted = false;
+        }
+        self.source.push_str(syntax);
+    }
+
+    fn finish(mut self) -> String {
+        self.syntax("");
+        self.source
+    }
+
+    fn format(&mut self, variable: &str, format_type: &str, mut selectors: Vec<String>) {
+        self.syntax(&format!("{{{}, {},", variable, format_type));
+        selectors.reverse();
+        self.formats.push((format_type != "select", selectors));
+    }
+
+    fn plural(&mut self,
+              variable: &str,
+              format_type: &str,
+              categories: &[PluralCategory],
+              literals: &[i64]) {
+        let literals = literals.iter().map(|literal| format!("={}", literal));
+        let categories = categories.iter().map(|category| category.to_string());
+        self.format(variable, format_type, literals.chain(categories).collect());
+    }
+}
+
+impl MessageVisitor for IcuWriter {
+    /// Write `text`, quoting the characters that are special where it
+    /// is and doubling apostrophes.
+    fn visit_text(&mut self, text: &str) {
+        let in_plural = self.formats.last().is_some_and(|&(plural, _)| plural);
+        for ch in text.chars() {
+            let special = ch == '{' || ch == '}' || in_plural && ch == '#';
+            if special != self.quoted {
+                self.source.push('\'');
+                self.quoted = special;
+            }
+            match ch {
+                '\'' => self.source.push_str("''"),
+                _ => self.source.push(ch),
+            }
+        }
+    }
+
+    fn visit_comment(&mut self, text: &str) {
+        self.syntax(&format!("{{!{}}}", text));
+    }
+
+    fn visit_placeholder(&mut self) {
+        self.syntax("#");
+    }
+
+    fn visit_variable(&mut self, name: &str) {
+        self.syntax(&format!("{{{}}}", name));
+    }
+
+    fn visit_number(&mut self, _: &str) {
+        self.unsupported = true;
+    }
+
+    fn visit_duration(&mut self, _: &str) {
+        self.unsupported = true;
+    }
+
+    fn visit_relative_time(&mut self, _: &str) {
+        self.unsupported = true;
+    }
+
+    fn visit_spellout(&mut self, _: &str) {
+        self.unsupported = true;
+    }
+
+    fn visit_choice(&mut self, _: &str) {
+        self.unsupported = true;
+    }
+
+    fn visit_plural(&mut self, variable: &str, categories: &[PluralCategory], literals: &[i64]) {
+        self.plural(variable, "plural", categories, literals);
+    }
+
+    fn visit_selectordinal(&mut self,
+                           variable: &str,
+                           categories: &[PluralCategory],
+                           literals: &[i64]) {
+        self.plural(variable, "selectordinal", categories, literals);
+    }
+
+    fn visit_select(&mut self, variable: &str, keys: &[&str]) {
+        let mut selectors = keys.iter().map(|key| key.to_string()).collect::<Vec<_>>();
+        selectors.push("other".to_string());
+        self.format(variable, "select", selectors);
+    }
+
+    fn enter_branch(&mut self) {
+        // A `choice` visits its branches too, but is already unsupported.
+        let selector = self.formats.last_mut().and_then(|(_, s)| s.pop());
+        self.syntax(&format!(" {} {{", selector.unwrap_or_default()));
+    }
+
+    fn leave_branch(&mut self) {
+        self.syntax("}");
+        if self.formats.last().is_some_and(|(_, selectors)| selectors.is_empty()) {
+            self.formats.pop();
+            self.syntax("}");
+        }
+    }
+}
+
+/// Options for [`import`].
+///
+/// [`import`]: fn.import.html
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct ImportOptions {
+    /// Whether translation units whose `<target>` has the state `new` or
+    /// `needs-translation` are left out. The default is `false`, so that
+    /// they are imported like any other.
+    pub skip_untranslated: bool,
+}
+
+/// The result of reading an XLIFF document with [`import`].
+///
+/// [`import`]: fn.import.html
+#[derive(Debug)]
+pub struct Import {
+    /// A bundle of the translations for each `target-language` of the
+    /// `<file>`s in the document, in the order that they first appear.
+    /// The translations of files with the same target language are in
+    /// the same bundle.
+    pub bundles: Vec<MessageBundle>,
+    /// The problems with the translation units, in the order of their
+    /// lines.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// A problem with a translation unit of an XLIFF document. Its `id` is
+/// the key of the message, from the `resname` or `id` of the unit,
+/// which is empty if the unit has neither. Its `line` is the line that
+/// the unit starts on.
+pub type Diagnostic = super::Diagnostic<DiagnosticKind>;
+
+/// The kinds of [`Diagnostic`]. Except for `PlaceholderMismatch`, the
+/// translation isn't imported.
+///
+/// [`Diagnostic`]: type.Diagnostic.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum DiagnosticKind {
+    /// The unit has no `resname` or `id` to use as the key.
+    MissingId,
+    /// The unit has no `<target>`.
+    MissingTarget,
+    /// An `<x/>`, `<bx/>` or `<ex/>` in the target refers to an inline
+    /// element that isn't in the source. Has its `id`.
+    UnknownPlaceholder(String),
+    /// The target isn't a valid ICU message.
+    Parse(ParseError),
+    /// The target couldn't be added to the bundle, as its key conflicts
+    /// with that of another message.
+    Bundle(BundleError),
+    /// The target uses different variables than the source. Has the
+    /// variables of the source that the target doesn't use, then those
+    /// that the target uses and the source doesn't, in sorted order.
+    PlaceholderMismatch(Vec<String>, Vec<String>),
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            DiagnosticKind::MissingId => "The translation unit has no id.".fmt(f),
+            DiagnosticKind::MissingTarget => "The translation unit has no target.".fmt(f),
+            DiagnosticKind::UnknownPlaceholder(ref id) => {
+                write!(f, "Placeholder '{}' isn't in the source.", id)
+            }
+            DiagnosticKind::Parse(ref error) => write!(f, "Invalid target: {}", error),
+            DiagnosticKind::Bundle(ref error) => error.fmt(f),
+            DiagnosticKind::PlaceholderMismatch(ref missing, ref extra) => {
+                write!(f,
+                       "The target is missing [{}] and adds [{}].",
+                       missing.join(", "),
+                       extra.join(", "))
+            }
+        }
+    }
+}
+
+/// Read the translations from an XLIFF 1.2 document into bundles with
+/// `context`, and the locale of each `target-language`.
+///
+/// The translation of each `<trans-unit>`, including those within a
+/// `<group>`, is its `<target>`, with the native code of its `<ph>`,
+/// `<bpt>`, `<ept>` and `<it>` elements put back and the text of `<g>`
+/// and `<mrk>` elements kept. An `<x/>`, `<bx/>` or `<ex/>` is replaced
+/// by the code of the inline element with the same `id` in the
+/// `<source>`. The `<note>` of a unit becomes the description in the
+/// [`MessageMetadata`] of its message.
+///
+/// Elements in any namespace are read, and the problems with each unit
+/// are reported as [`Diagnostic`]s. An error is only returned if the
+/// document isn't well-formed XML, isn't XLIFF, or has a target
+/// language that isn't a valid locale.
+///
+/// ```
+/// use message_format::{arg, Context};
+/// use message_format::convert::xliff::{self, ImportOptions};
+///
+/// let import = xliff::import(Context::default(), r#"<?xml version="1.0"?>
+/// <xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+///   <file original="messages" source-language="en" target-language="de"
+///         datatype="x-icu-messageformat">
+///     <body>
+///       <trans-unit id="greeting">
+///         <source>Hello, <ph id="1">{name}</ph>!</source>
+///         <target>Hallo, <x id="1"/>!</target>
+///       </trans-unit>
+///     </body>
+///   </file>
+/// </xliff>"#, &ImportOptions::default()).unwrap();
+/// assert!(import.diagnostics.is_empty());
+/// let de = &import.bundles[0];
+/// assert_eq!(de.context().locale().to_string(), "de");
+/// assert_eq!(de.format("greeting", Some(&arg("name", "Jo"))).unwrap(), "Hallo, Jo!");
+/// ```
+///
+/// [`MessageMetadata`]: ../../struct.MessageMetadata.html
+/// [`Diagnostic`]: type.Diagnostic.html
+pub fn import(context: Context,
+              xliff: &str,
+              options: &ImportOptions)
+              -> Result<Import, BundleError> {
+    let lines = Lines::new(xliff);
+    let invalid = |offset, message: String| {
+        BundleError::Line(lines.line(offset), Box::new(BundleError::InvalidResource(message)))
+    };
+    let root = xml::parse(xliff).map_err(|e| invalid(e.offset, format!("{}.", e.message)))?;
+    if root.name != "xliff" {
+        return Err(invalid(root.offset, "Expected an XLIFF document.".to_string()));
+    }
+    let mut import = Import { bundles: vec![], diagnostics: vec![] };
+    for file in root.elements().filter(|element| element.name == "file") {
+        let mut context = context.clone();
+        if let Some(language) = file.attribute("target-language") {
+            context.locale = Locale::new(&language.replace('_', "-"))
+                .map_err(|e| invalid(file.offset, e.to_string()))?;
+        }
+        let bundles = &mut import.bundles;
+        let index = match bundles.iter().position(|b| *b.context().locale() == context.locale) {
+            Some(index) => index,
+            None => {
+                bundles.push(MessageBundle::new(context));
+                bundles.len() - 1
+            }
+        };
+        let mut units = vec![];
+        trans_units(file, &mut units);
+        for unit in units {
+            let id = unit.attribute("resname").or_else(|| unit.attribute("id")).unwrap_or("");
+            let kind = if id.is_empty() {
+                Some(DiagnosticKind::MissingId)
+            } else {
+                import_unit(id, unit, &mut bundles[index], options)
+            };
+            if let Some(kind) = kind {
+                let line = Some(lines.line(unit.offset));
+                import.diagnostics.push(Diagnostic { id: id.to_string(), line, kind });
+            }
+        }
+    }
+    Ok(import)
+}
+
+/// Collect the `<trans-unit>`s in the `<body>` of a `<file>`, and in the
+/// `<group>`s within it.
+fn trans_units<'a>(element: &'a Element, units: &mut Vec<&'a Element>) {
+    for child in element.elements() {
+        match &*child.name {
+            "trans-unit" => units.push(child),
+            "body" | "group" => trans_units(child, units),
+            _ => {}
+        }
+    }
+}
+
+/// Add the translation of a `unit` to `bundle` with `key`, returning the
+/// problem with it, if there is one.
+fn import_unit(key: &str,
+               unit: &Element,
+               bundle: &mut MessageBundle,
+               options: &ImportOptions)
+               -> Option<DiagnosticKind> {
+    let target = match unit.child("target") {
+        Some(target) => target,
+        None => return Some(DiagnosticKind::MissingTarget),
+    };
+    let state = target.attribute("state");
+    if options.skip_untranslated && matches!(state, Some("new") | Some("needs-translation")) {
+        return None;
+    }
+    let source = unit.child("source");
+    let mut codes = HashMap::new();
+    if let Some(source) = source {
+        inline_codes(source, &mut codes);
+    }
+    let translation = match read_segment(target, &codes) {
+        Ok(translation) => translation,
+        Err(id) => return Some(DiagnosticKind::UnknownPlaceholder(id)),
+    };
+    match bundle.insert(key, &translation) {
+        Ok(()) => {}
+        Err(BundleError::Parse(_, error)) => return Some(DiagnosticKind::Parse(error)),
+        Err(error) => return Some(DiagnosticKind::Bundle(error)),
+    }
+    if let Some(note) = unit.child("note") {
+        let metadata = MessageMetadata { description: Some(note.text()), ..Default::default() };
+        bundle.insert_metadata(key, metadata);
+    }
+    // A source that isn't valid ICU can't be compared.
+    let source = source.and_then(|source| read_segment(source, &codes).ok())
+        .and_then(|source| icu::parse(&source).ok())?;
+    let variables = |message: &Message| {
+        message.variables().into_iter().map(|variable| variable.name).collect::<BTreeSet<_>>()
+    };
+    let expected = variables(&source);
+    let actual = variables(bundle.get(key)?);
+    if expected == actual {
+        return None;
+    }
+    Some(DiagnosticKind::PlaceholderMismatch(expected.difference(&actual).cloned().collect(),
+                                             actual.difference(&expected).cloned().collect()))
+}
+
+/// Collect the native code of the inline elements in a `<source>`, by
+/// their `id`.
+fn inline_codes<'a>(element: &'a Element, codes: &mut HashMap<&'a str, String>) {
+    for inline in element.elements() {
+        match &*inline.name {
+            "ph" | "bpt" | "ept" | "it" => {
+                if let Some(id) = inline.attribute("id") {
+                    codes.insert(id, inline.text());
+                }
+            }
+            _ => inline_codes(inline, codes),
+        }
+    }
+}
+
+/// Read the ICU message in a `<source>` or `<target>`, with `codes` for
+/// the elements that refer to those of the source. The `id` of a
+/// reference to an unknown element is returned as an error.
+fn read_segment(element: &Element, codes: &HashMap<&str, String>) -> Result<String, String> {
+    let mut message = String::new();
+    for node in &element.children {
+        let inline = match *node {
+            Node::Text(ref text) => {
+                message.push_str(text);
+                continue;
+            }
+            Node::Element(ref inline) => inline,
+        };
+        match &*inline.name {
+            "ph" | "bpt" | "ept" | "it" => message.push_str(&inline.text()),
+            "x" | "bx" | "ex" => {
+                let id = inline.attribute("id").unwrap_or("");
+                message.push_str(codes.get(id).ok_or_else(|| id.to_string())?);
+            }
+            _ => message.push_str(&read_segment(inline, codes)?),
+        }
+    }
+    Ok(message)
+}
+
+/// Write an ICU message as the content of a `<source>` or `<target>`,
+/// with its syntax in `<ph>` elements if `placeholders` is set.
+fn write_segment(message: &str, placeholders: bool, xliff: &mut String) {
+    if !placeholders || icu::validate(message).is_err() {
+        return escape(message, xliff);
+    }
+    let mut splitter = Splitter { source: message, pos: 0, runs: vec![] };
+    splitter.message(false);
+    let mut id = 0;
+    for &(syntax, ref range) in &splitter.runs {
+        if syntax {
+            id += 1;
+            xliff.push_str(&format!("<ph id=\"{}\">", id));
+            escape(&message[range.clone()], xliff);
+            xliff.push_str("</ph>");
+        } else {
+            escape(&message[range.clone()], xliff);
+        }
+    }
+}
+
+/// Splits a valid ICU message into runs of translatable text and of the
+/// syntax around it, with the same rules as the parser. The messages in
+/// the branches of a `plural` or `select` are text, and the rest of the
+/// format is syntax. Other formats are syntax as a whole.
+struct Splitter<'a> {
+    source: &'a str,
+    pos: usize,
+    /// Whether each run is syntax, and where it is in the source.
+    /// Adjacent runs of syntax are joined.
+    runs: Vec<(bool, Range<usize>)>,
+}
+
+impl<'a> Splitter<'a> {
+    /// Add the source from the current position up to `end` as a run.
+    fn push(&mut self, syntax: bool, end: usize) {
+        match self.runs.last_mut() {
+            Some(&mut (last, ref mut range)) if last == syntax => range.end = end,
+            _ => self.runs.push((syntax, self.pos..end)),
+        }
+        self.pos = end;
+    }
+
+    /// Split the parts of a message up through the end of the source
+    /// or the `}` that closes its branch.
+    fn message(&mut self, in_plural: bool) {
+        loop {
+            let rest = &self.source[self.pos..];
+            match rest.chars().next() {
+                None | Some('}') => return,
+                Some('{') => self.format(),
+                Some('#') if in_plural => self.push(true, self.pos + 1),
+                Some(_) => {
+                    let len = text_len(rest, |ch| ch == '{' || ch == '}' || in_plural && ch == '#');
+                    self.push(false, self.pos + len);
+                }
+            }
+        }
+    }
+
+    fn format(&mut self) {
+        let rest = &self.source[self.pos..];
+        // Up to the `{` of the first branch or the `}` of the format.
+        let end = rest[1..].find(['{', '}']).map_or(rest.len() - 1, |i| i + 1);
+        let header = &rest[..end];
+        let format_type = header.split(',').nth(1).map(str::trim);
+        let in_plural = match format_type {
+            _ if rest.starts_with("{!") => return self.push(true, self.pos + header.len() + 1),
+            Some("plural") | Some("selectordinal") => true,
+            Some("select") => false,
+            Some("choice") => return self.push(true, self.pos + matching_brace(rest) + 1),
+            _ => return self.push(true, self.pos + header.len() + 1),
+        };
+        // Through the `{` of the first branch.
+        self.push(true, self.pos + header.len() + 1);
+        loop {
+            self.message(in_plural);
+            // The `}` of the branch, then either the selector and `{` of
+            // the next branch or the `}` of the format.
+            let rest = &self.source[self.pos + 1..];
+            let next = rest.find(['{', '}']).unwrap_or(rest.len() - 1);
+            let closed = rest[next..].starts_with('}');
+            self.push(true, self.pos + 1 + next + 1);
+            if closed {
+                return;
+            }
+        }
+    }
+}
+
+/// The length of the text at the start of `source`, up to the first
+/// character that is `special` and isn't quoted with apostrophes.
+fn text_len<F: Fn(char) -> bool>(source: &str, special: F) -> usize {
+    let mut chars = source.char_indices().peekable();
+    while let Some((i, ch)) = chars.next() {
+        if special(ch) {
+            return i;
+        }
+        if ch != '\'' {
+            continue;
+        }
+        match chars.peek() {
+            Some(&(_, '\'')) => {
+                chars.next();
+            }
+            Some(&(_, next)) if special(next) => {
+                // Quoted through the next apostrophe that isn't doubled.
+                while let Some((_, ch)) = chars.next() {
+                    if ch == '\'' && chars.next_if(|&(_, next)| next == '\'').is_none() {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    source.len()
+}
+
+/// The offset of the `}` matching the `{` at the start of `source`,
+/// which is a `choice`.
+fn matching_brace(source: &str) -> usize {
+    let mut depth = 0;
+    let mut pos = 0;
+    while pos < source.len() {
+        pos += text_len(&source[pos..], |ch| ch == '{' || ch == '}' || ch == '|');
+        match source[pos..].chars().next() {
+            Some('{') => depth += 1,
+            Some('}') if depth == 1 => return pos,
+            Some('}') => depth -= 1,
+            _ => {}
+        }
+        pos += 1;
+    }
+    source.len() - 1
+}
+
+/// Write `text` with the characters that are special in XML content
+/// and attributes escaped. A carriage return is escaped so that it isn't
+/// normalized to a line feed when the document is read.
+fn escape(text: &str, xml: &mut String) {
+    for ch in text.chars() {
+        match ch {
+            '&' => xml.push_str("&amp;"),
+            '<' => xml.push_str("&lt;"),
+            '>' => xml.push_str("&gt;"),
+            '"' => xml.push_str("&quot;"),
+            '\r' => xml.push_str("&#13;"),
+            _ => xml.push(ch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export, import, write_segment, DiagnosticKind, ExportError, ImportOptions,
+                XliffOptions};
+    use convert::fluent;
+    use icu::ast::PlainText;
+    use icu::parse;
+    use {arg, BundleError, Context, Locale, Message, MessageBundle, MessagePart,
+         PseudoLocalizeOptions};
+
+    fn segment(message: &str) -> String {
+        let mut xliff = String::new();
+        write_segment(message, true, &mut xliff);
+        xliff
+    }
+
+    #[test]
+    fn placeholders() {
+        assert_eq!(segment("Hello, {name}!"), "Hello, <ph id=\"1\">{name}</ph>!");
+        assert_eq!(segment("{ n , number }{!note}% done"),
+                   "<ph id=\"1\">{ n , number }{!note}</ph>% done");
+        assert_eq!(segment("{n, plural, offset:1 =0 {none} other {# & {x}}}"),
+                   "<ph id=\"1\">{n, plural, offset:1 =0 {</ph>none<ph id=\"2\">} other {#</ph> \
+                    &amp; <ph id=\"3\">{x}}}</ph>");
+        assert_eq!(segment("{g, select, other {# <b>}}"),
+                   "<ph id=\"1\">{g, select, other {</ph># &lt;b&gt;<ph id=\"2\">}}</ph>");
+        assert_eq!(segment("{n, choice, 0#none|1#{n} {n, number}}!"),
+                   "<ph id=\"1\">{n, choice, 0#none|1#{n} {n, number}}</ph>!");
+        assert_eq!(segment("'{'a'' {n, plural, other {'#{}' '' #}}"),
+                   "'{'a'' <ph id=\"1\">{n, plural, other {</ph>'#{}' '' <ph id=\"2\">#}}</ph>");
+        assert_eq!(segment("{n, choice, 0#'}'|1#'{'}!"),
+                   "<ph id=\"1\">{n, choice, 0#'}'|1#'{'}</ph>!");
+        assert_eq!(segment("Invalid {"), "Invalid {");
+        assert_eq!(segment(""), "");
+    }
+
+    #[test]
+    fn escaping() {
+        let mut en = MessageBundle::new(Context::default());
+        en.insert("a\"&<b>", "Tom & \"Jerry\"\r\n<i>").unwrap();
+        let options = XliffOptions { original: "a&b".to_string(), ..XliffOptions::default() };
+        let xliff = export(&en, None, &options).unwrap();
+        assert!(xliff.contains("<file original=\"a&amp;b\" source-language=\"en-US\""));
+        assert!(xliff.contains("<trans-unit id=\"a&quot;&amp;&lt;b&gt;\""));
+        assert!(xliff.contains("<source>Tom &amp; &quot;Jerry&quot;&#13;\n&lt;i&gt;</source>"));
+    }
+
+    #[test]
+    fn rebuilt_sources() {
+        let ftl = "files = { $count ->\n\
+                   \x20   [one] { $count } file's\n\
+                   \x20  *[other] { $count } files #{ \"{\" }x{ \"}\" }\n\
+                   }\n";
+        let mut en = fluent::convert(Context::default(), ftl).bundle;
+        en.insert_message("a", parse("{g, select, a {'{'It''s'}'} other {#}}").unwrap());
+        en.insert_message("b", parse("{n, selectordinal, =1 {#'#'} one {{x}} other {}}").unwrap());
+        let parts: Vec<Box<dyn MessagePart>> = vec![Box::new(PlainText::new("{")),
+                                                    Box::new(PlainText::new("}'"))];
+        en.insert_message("c", Message::new(parts));
+        let xliff = export(&en, None, &XliffOptions::default()).unwrap();
+        assert!(xliff.contains("<source>{g, select, a {'{'It''s'}'} other {#}}</source>"));
+        let source = "{n, selectordinal, =1 {#'#'} one {{x}} other {}}";
+        assert!(xliff.contains(&format!("<source>{}</source>", source)));
+        assert!(xliff.contains("<source>'{}'''</source>"));
+        let source = "{count, plural, one {{count} file''s} other {{count} files '#{'x'}'}}";
+        assert!(xliff.contains(&format!("<source>{}</source>", source)));
+        let pseudo = en.pseudo_localize(&PseudoLocalizeOptions::default());
+        let xliff = export(&en, Some(&pseudo), &XliffOptions::default()).unwrap();
+        let import = import(Context::default(), &xliff, &ImportOptions::default()).unwrap();
+        let translated = &import.bundles[0];
+        for key in en.keys() {
+            let args = arg("g", "a");
+            let args = args.arg("n", 1);
+            let args = args.arg("count", 2);
+            assert_eq!(translated.format(key, Some(&args)), pseudo.format(key, Some(&args)));
+        }
+    }
+
+    #[test]
+    fn unknown_sources() {
+        let mut en = MessageBundle::new(Context::default());
+        en.insert_message("a", parse("{n, number, percent}").unwrap());
+        en.insert("b", "B").unwrap();
+        let mut de = MessageBundle::new(Context::new(Locale::new("de").unwrap(), None));
+        de.insert_message("b", parse("{n, choice, 0#B}").unwrap());
+        assert_eq!(export(&en, Some(&de), &XliffOptions::default()),
+                   Err(vec![ExportError::UnknownSource("en-US".to_string(), "a".to_string()),
+                            ExportError::UnknownSource("de".to_string(), "b".to_string())]));
+    }
+
+    #[test]
+    fn invalid_documents() {
+        let invalid = |line, message: &str| {
+            BundleError::Line(line, Box::new(BundleError::InvalidResource(message.to_string())))
+        };
+        let options = ImportOptions::default();
+        let import = |xliff| import(Context::default(), xliff, &options).map(|_| ());
+        assert_eq!(import("<xliff>\n<file>"), Err(invalid(2, "Unclosed element.")));
+        assert_eq!(import("<html/>"), Err(invalid(1, "Expected an XLIFF document.")));
+        assert_eq!(import("<xliff>\n<file target-language='?'/></xliff>"),
+                   Err(invalid(2, "Malformed locale '?'.")));
+    }
+
+    #[test]
+    fn units_without_ids() {
+        let xliff = "<xliff><file><body>\n\
+                     <trans-unit><source>A</source><target>B</target></trans-unit>\n\
+                     </body></file></xliff>";
+        let import = import(Context::default(), xliff, &ImportOptions::default()).unwrap();
+        assert_eq!(import.diagnostics[0].kind, DiagnosticKind::MissingId);
+        assert_eq!(import.diagnostics[0].to_string(), "Line 2: The translation unit has no id.");
+        assert_eq!(import.bundles[0].context().locale(), &Locale::en_us());
+        assert!(import.bundles[0].is_empty());
+    }
+}
@@ -0,0 +1,514 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A C API for parsing and formatting ICU messages.
+//!
+//! This is enabled by the `ffi` feature. Its declarations for C, C++
+//! and Swift are in `include/message_format.h`, and a shared library
+//! with them is built with:
+//!
+//! ```text
+//! cargo rustc --release --lib --features ffi --crate-type cdylib
+//! ```
+//!
+//! A message is parsed by [`mf_parse`], or for a locale other than
+//! `en-US` by [`mf_parse_with_locale`], into an opaque handle, formatted
+//! with an array of [`MfArg`] by [`mf_format`], and freed with
+//! [`mf_message_free`]. Strings are UTF-8 and terminated by `NUL`, and
+//! those that are returned are freed with [`mf_string_free`]:
+//!
+//! ```c
+//! MfError error;
+//! MfMessage *m = mf_parse("{count, plural, one {# file} other {# files}}", &error);
+//! MfArg args[] = {{ "count", MF_ARG_INT64, { .int64 = 3 } }};
+//! char *out;
+//! if (mf_format(m, args, 1, &out, &error) == MF_OK) {
+//!     puts(out); // "3 files"
+//!     mf_string_free(out);
+//! }
+//! mf_message_free(m);
+//! ```
+//!
+//! Failures are reported with an [`MfErrorCode`] and, if there is an
+//! [`MfError`], a message. A panic is caught before it reaches the
+//! caller, and reported as [`MfErrorCode::Panic`]. The tests in
+//! `tests/ffi/plural.c` are run with the library by:
+//!
+//! ```text
+//! cc -Iinclude tests/ffi/plural.c -Ltarget/release -lmessage_format -o target/plural
+//! LD_LIBRARY_PATH=target/release target/plural
+//! ```
+//!
+//! [`mf_parse`]: fn.mf_parse.html
+//! [`mf_parse_with_locale`]: fn.mf_parse_with_locale.html
+//! [`mf_format`]: fn.mf_format.html
+//! [`mf_message_free`]: fn.mf_message_free.html
+//! [`mf_string_free`]: fn.mf_string_free.html
+//! [`MfArg`]: struct.MfArg.html
+//! [`MfError`]: struct.MfError.html
+//! [`MfErrorCode`]: enum.MfErrorCode.html
+//! [`MfErrorCode::Panic`]: enum.MfErrorCode.html#variant.Panic
+
+use std::any::Any;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+use args::chain;
+use icu;
+use {AsValue, Context, Locale, Message, Value};
+
+/// The outcome of a call.
+#[repr(C)]
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum MfErrorCode {
+    /// There was no error. This is `MF_OK` in C.
+    Ok = 0,
+    /// A pointer that is required is null.
+    NullArgument = 1,
+    /// A string isn't UTF-8.
+    InvalidUtf8 = 2,
+    /// The message couldn't be parsed.
+    Parse = 3,
+    /// The message couldn't be formatted with the arguments.
+    Format = 4,
+    /// There was a panic, which was caught.
+    Panic = 5,
+    /// An argument isn't valid, like an [`MfArg`] whose `kind` isn't one
+    /// of [`MfArgKind`], or a malformed locale.
+    ///
+    /// [`MfArg`]: struct.MfArg.html
+    /// [`MfArgKind`]: enum.MfArgKind.html
+    InvalidArgument = 6,
+}
+
+/// An error from a call, as an [`MfErrorCode`] and a description.
+///
+/// The `message` is `NULL` when the `code` is `MF_OK`, and is otherwise
+/// a string that is freed with [`mf_string_free`]. It is overwritten by
+/// each call that is given this error, so it must be freed first.
+///
+/// [`MfErrorCode`]: enum.MfErrorCode.html
+/// [`mf_string_free`]: fn.mf_string_free.html
+#[repr(C)]
+#[derive(Debug)]
+pub struct MfError {
+    /// What went wrong.
+    pub code: MfErrorCode,
+    /// A description of what went wrong, or `NULL`.
+    pub message: *mut c_char,
+}
+
+/// The type of the value of an [`MfArg`], which is its `kind`.
+///
+/// [`MfArg`]: struct.MfArg.html
+#[repr(C)]
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum MfArgKind {
+    /// The value is `string`, which is [`Value::Str`].
+    ///
+    /// [`Value::Str`]: ../enum.Value.html#variant.Str
+    String = 0,
+    /// The value is `int64`, which is [`Value::Number`].
+    ///
+    /// [`Value::Number`]: ../enum.Value.html#variant.Number
+    Int64 = 1,
+    /// The value is `dbl`, which is a [`Value::Decimal`], as for an
+    /// `f64`.
+    ///
+    /// [`Value::Decimal`]: ../enum.Value.html#variant.Decimal
+    Double = 2,
+}
+
+/// The value of an [`MfArg`], of the type given by its `kind`.
+///
+/// [`MfArg`]: struct.MfArg.html
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub union MfArgValue {
+    /// A UTF-8 string.
+    pub string: *const c_char,
+    /// An integer.
+    pub int64: i64,
+    /// A floating point number.
+    pub dbl: f64,
+}
+
+/// A named argument for [`mf_format`].
+///
+/// [`mf_format`]: fn.mf_format.html
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct MfArg {
+    /// The UTF-8 name of the argument.
+    pub name: *const c_char,
+    /// Which of the members of `value` is set, as an [`MfArgKind`]. This
+    /// is an integer so that a value from C that isn't one of them is
+    /// an error rather than undefined behavior.
+    ///
+    /// [`MfArgKind`]: enum.MfArgKind.html
+    pub kind: u32,
+    /// The value of the argument.
+    pub value: MfArgValue,
+}
+
+/// A parsed message, with the [`Context`] that it is formatted in.
+///
+/// This is opaque to C, and is created by [`mf_parse`].
+///
+/// [`Context`]: ../struct.Context.html
+/// [`mf_parse`]: fn.mf_parse.html
+pub struct MfMessage {
+    message: Message,
+    context: Context,
+}
+
+type Failure = (MfErrorCode, String);
+
+/// Parse an ICU message from `source`, to be formatted for `en-US`.
+///
+/// This is [`mf_parse_with_locale`] without a locale.
+///
+/// # Safety
+///
+/// As for [`mf_parse_with_locale`].
+///
+/// [`mf_parse_with_locale`]: fn.mf_parse_with_locale.html
+#[no_mangle]
+pub unsafe extern "C" fn mf_parse(source: *const c_char, error: *mut MfError) -> *mut MfMessage {
+    mf_parse_with_locale(source, ptr::null(), error)
+}
+
+/// Parse an ICU message from `source`, to be formatted for `locale`, or
+/// `en-US` if it is `NULL`.
+///
+/// This returns a handle that is freed with [`mf_message_free`], or
+/// `NULL` if there is an error. A malformed locale is an
+/// [`MfErrorCode::InvalidArgument`].
+///
+/// # Safety
+///
+/// `source` and `locale` must be `NULL` or strings terminated by `NUL`,
+/// and `error` must be `NULL` or valid to write an [`MfError`] to.
+///
+/// [`mf_message_free`]: fn.mf_message_free.html
+/// [`MfError`]: struct.MfError.html
+/// [`MfErrorCode::InvalidArgument`]: enum.MfErrorCode.html#variant.InvalidArgument
+#[no_mangle]
+pub unsafe extern "C" fn mf_parse_with_locale(source: *const c_char,
+                                              locale: *const c_char,
+                                              error: *mut MfError)
+                                              -> *mut MfMessage {
+    guard(error, || {
+        let source = to_str(source, "source")?;
+        let context = if locale.is_null() {
+            Context::default()
+        } else {
+            let locale = Locale::new(to_str(locale, "locale")?)
+                .map_err(|e| (MfErrorCode::InvalidArgument, e.to_string()))?;
+            Context::new(locale, None)
+        };
+        let message = icu::parse(source).map_err(|e| (MfErrorCode::Parse, e.to_string()))?;
+        Ok(Box::into_raw(Box::new(MfMessage { message, context })))
+    })
+        .unwrap_or(ptr::null_mut())
+}
+
+/// Format `message` with the `len` arguments of `args`, and set `out`
+/// to the result, which is freed with [`mf_string_free`].
+///
+/// When there is an error, `out` is set to `NULL`. A message that needs
+/// an argument that isn't given is an [`MfErrorCode::Format`], and an
+/// argument whose `kind` isn't one of [`MfArgKind`] is an
+/// [`MfErrorCode::InvalidArgument`].
+///
+/// # Safety
+///
+/// `message` must be `NULL` or from [`mf_parse`], and not yet freed.
+/// `args` must be `NULL` or point to `len` arguments, whose strings
+/// are terminated by `NUL`. `out` must be `NULL` or valid to write a pointer to, and `error`
+/// must be `NULL` or valid to write an [`MfError`] to.
+///
+/// [`mf_string_free`]: fn.mf_string_free.html
+/// [`mf_parse`]: fn.mf_parse.html
+/// [`MfArgKind`]: enum.MfArgKind.html
+/// [`MfError`]: struct.MfError.html
+/// [`MfErrorCode::Format`]: enum.MfErrorCode.html#variant.Format
+/// [`MfErrorCode::InvalidArgument`]: enum.MfErrorCode.html#variant.InvalidArgument
+#[no_mangle]
+pub unsafe extern "C" fn mf_format(message: *const MfMessage,
+                                   args: *const MfArg,
+                                   len: usize,
+                                   out: *mut *mut c_char,
+                                   error: *mut MfError)
+                                   -> MfErrorCode {
+    if !out.is_null() {
+        out.write(ptr::null_mut());
+    }
+    let result = guard(error, || {
+        let message = match message.as_ref() {
+            Some(message) => message,
+            None => return Err(null("message")),
+        };
+        if out.is_null() {
+            return Err(null("out"));
+        }
+        let args = match len {
+            0 => &[],
+            _ if args.is_null() => return Err(null("args")),
+            _ => slice::from_raw_parts(args, len),
+        };
+        const STRING: u32 = MfArgKind::String as u32;
+        const INT64: u32 = MfArgKind::Int64 as u32;
+        const DOUBLE: u32 = MfArgKind::Double as u32;
+        let mut entries = Vec::with_capacity(args.len());
+        for arg in args {
+            let name = to_str(arg.name, "name")?;
+            let value = match arg.kind {
+                STRING => Value::Str(to_str(arg.value.string, name)?),
+                INT64 => Value::Number(arg.value.int64),
+                DOUBLE => arg.value.dbl.as_formattable(),
+                kind => {
+                    let message = format!("'{}' has the unknown kind {}.", name, kind);
+                    return Err((MfErrorCode::InvalidArgument, message));
+                }
+            };
+            entries.push((name, value));
+        }
+        let mut output = String::new();
+        chain(&entries, None, |args| message.context.write(&message.message, &mut output, args))
+            .map_err(|e| (MfErrorCode::Format, e.to_string()))?;
+        out.write(to_c_string(output));
+        Ok(())
+    });
+    result.err().unwrap_or(MfErrorCode::Ok)
+}
+
+/// Free a message from [`mf_parse`]. It can be `NULL`.
+///
+/// # Safety
+///
+/// `message` must be `NULL` or from [`mf_parse`], and not yet freed.
+///
+/// [`mf_parse`]: fn.mf_parse.html
+#[no_mangle]
+pub unsafe extern "C" fn mf_message_free(message: *mut MfMessage) {
+    if !message.is_null() {
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(message))));
+    }
+}
+
+/// Free a string from [`mf_format`] or an [`MfError`]. It can be
+/// `NULL`.
+///
+/// # Safety
+///
+/// `string` must be `NULL` or from this module, and not yet freed.
+///
+/// [`mf_format`]: fn.mf_format.html
+/// [`MfError`]: struct.MfError.html
+#[no_mangle]
+pub unsafe extern "C" fn mf_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(CString::from_raw(string))));
+    }
+}
+
+/// Call `f`, catching a panic, and report how it went to `error`.
+unsafe fn guard<T, F>(error: *mut MfError, f: F) -> Result<T, MfErrorCode>
+    where F: FnOnce() -> Result<T, Failure>
+{
+    let result = match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => result,
+        Err(payload) => Err((MfErrorCode::Panic, panic_message(&*payload))),
+    };
+    let (code, message) = match result {
+        Ok(_) => (MfErrorCode::Ok, ptr::null_mut()),
+        Err((code, ref message)) if !error.is_null() => (code, to_c_string(message.clone())),
+        Err((code, _)) => (code, ptr::null_mut()),
+    };
+    if !error.is_null() {
+        error.write(MfError { code, message });
+    }
+    result.map_err(|(code, _)| code)
+}
+
+/// The `str` of a C string, which is the argument `name`.
+unsafe fn to_str<'a>(string: *const c_char, name: &str) -> Result<&'a str, Failure> {
+    if string.is_null() {
+        return Err(null(name));
+    }
+    CStr::from_ptr(string)
+        .to_str()
+        .map_err(|_| (MfErrorCode::InvalidUtf8, format!("'{}' isn't UTF-8.", name)))
+}
+
+fn null(name: &str) -> Failure {
+    (MfErrorCode::NullArgument, format!("'{}' is null.", name))
+}
+
+/// Move `string` to C. A `NUL` within it is replaced, as C would take
+/// it to be the end.
+fn to_c_string(string: String) -> *mut c_char {
+    let string = if string.contains('\0') {
+        string.replace('\0', "\u{FFFD}")
+    } else {
+        string
+    };
+    CString::new(string).unwrap_or_default().into_raw()
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let message = match payload.downcast_ref::<&str>() {
+        Some(message) => message,
+        None => payload.downcast_ref::<String>().map_or("unknown", String::as_str),
+    };
+    format!("Panicked: {}", message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::{CStr, CString};
+    use std::os::raw::c_char;
+    use std::ptr;
+
+    use super::*;
+
+    unsafe fn take(string: *mut c_char) -> String {
+        let result = CStr::from_ptr(string).to_str().unwrap().to_string();
+        mf_string_free(string);
+        result
+    }
+
+    fn new_error() -> MfError {
+        MfError {
+            code: MfErrorCode::Ok,
+            message: ptr::null_mut(),
+        }
+    }
+
+    #[test]
+    fn parse_and_format() {
+        let source = CString::new("{name} has {count, plural, one {# file} other {# files}} \
+                                   of {size} MB")
+            .unwrap();
+        let name = CString::new("name").unwrap();
+        let ana = CString::new("Ana").unwrap();
+        let count = CString::new("count").unwrap();
+        let size = CString::new("size").unwrap();
+        let mut error = new_error();
+        unsafe {
+            let m = mf_parse(source.as_ptr(), &mut error);
+            assert!(!m.is_null());
+            assert_eq!(error.code, MfErrorCode::Ok);
+            assert!(error.message.is_null());
+            let mut args = [MfArg {
+                                name: name.as_ptr(),
+                                kind: MfArgKind::String as u32,
+                                value: MfArgValue { string: ana.as_ptr() },
+                            },
+                            MfArg {
+                                name: count.as_ptr(),
+                                kind: MfArgKind::Int64 as u32,
+                                value: MfArgValue { int64: 1 },
+                            },
+                            MfArg {
+                                name: size.as_ptr(),
+                                kind: MfArgKind::Double as u32,
+                                value: MfArgValue { dbl: 2.5 },
+                            }];
+            let mut out = ptr::null_mut();
+            assert_eq!(mf_format(m, args.as_ptr(), 3, &mut out, &mut error), MfErrorCode::Ok);
+            assert_eq!(take(out), "Ana has 1 file of 2.5 MB");
+            args[1].value.int64 = 1234;
+            assert_eq!(mf_format(m, args.as_ptr(), 3, &mut out, ptr::null_mut()),
+                       MfErrorCode::Ok);
+            assert_eq!(take(out), "Ana has 1234 files of 2.5 MB");
+            args[2].kind = 7;
+            assert_eq!(mf_format(m, args.as_ptr(), 3, &mut out, &mut error),
+                       MfErrorCode::InvalidArgument);
+            assert!(out.is_null());
+            assert_eq!(take(error.message), "'size' has the unknown kind 7.");
+            mf_message_free(m);
+        }
+    }
+
+    #[test]
+    fn locales() {
+        let source = CString::new("{count, plural, one {# fichier} other {# fichiers}}").unwrap();
+        let fr = CString::new("fr").unwrap();
+        let count = CString::new("count").unwrap();
+        let args = [MfArg {
+                        name: count.as_ptr(),
+                        kind: MfArgKind::Int64 as u32,
+                        value: MfArgValue { int64: 0 },
+                    }];
+        let mut error = new_error();
+        unsafe {
+            let m = mf_parse_with_locale(source.as_ptr(), fr.as_ptr(), &mut error);
+            let mut out = ptr::null_mut();
+            assert_eq!(mf_format(m, args.as_ptr(), 1, &mut out, &mut error), MfErrorCode::Ok);
+            assert_eq!(take(out), "0 fichier");
+            mf_message_free(m);
+
+            let m = mf_parse_with_locale(source.as_ptr(), ptr::null(), &mut error);
+            assert_eq!(mf_format(m, args.as_ptr(), 1, &mut out, &mut error), MfErrorCode::Ok);
+            assert_eq!(take(out), "0 fichiers");
+            mf_message_free(m);
+
+            let invalid = CString::new("?").unwrap();
+            assert!(mf_parse_with_locale(source.as_ptr(), invalid.as_ptr(), &mut error).is_null());
+            assert_eq!(error.code, MfErrorCode::InvalidArgument);
+            mf_string_free(error.message);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let source = CString::new("{count, plural, one {# file}").unwrap();
+        let invalid = [b'{', 0xff, b'}', 0];
+        let mut error = new_error();
+        unsafe {
+            assert!(mf_parse(source.as_ptr(), &mut error).is_null());
+            assert_eq!(error.code, MfErrorCode::Parse);
+            assert!(take(error.message).contains("Unexpected end"));
+            assert!(mf_parse(ptr::null(), &mut error).is_null());
+            assert_eq!((error.code, take(error.message)),
+                       (MfErrorCode::NullArgument, "'source' is null.".to_string()));
+            assert!(mf_parse(invalid.as_ptr() as *const c_char, &mut error).is_null());
+            assert_eq!(error.code, MfErrorCode::InvalidUtf8);
+            mf_string_free(error.message);
+
+            let source = CString::new("{count} files").unwrap();
+            let m = mf_parse(source.as_ptr(), &mut error);
+            let mut out = ptr::null_mut();
+            assert_eq!(mf_format(m, ptr::null(), 0, &mut out, &mut error), MfErrorCode::Format);
+            assert!(out.is_null());
+            assert_eq!(take(error.message), "Missing argument 'count'.");
+            assert_eq!(mf_format(m, ptr::null(), 1, &mut out, &mut error),
+                       MfErrorCode::NullArgument);
+            assert_eq!(take(error.message), "'args' is null.");
+            assert_eq!(mf_format(ptr::null(), ptr::null(), 0, &mut out, ptr::null_mut()),
+                       MfErrorCode::NullArgument);
+            mf_message_free(m);
+            mf_message_free(ptr::null_mut());
+            mf_string_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn panics_are_caught() {
+        let mut error = new_error();
+        unsafe {
+            let result = guard(&mut error, || -> Result<i32, Failure> { panic!("oops") });
+            assert_eq!(result, Err(MfErrorCode::Panic));
+            assert_eq!((error.code, take(error.message)),
+                       (MfErrorCode::Panic, "Panicked: oops".to_string()));
+        }
+    }
+}
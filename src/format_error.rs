@@ -0,0 +1,73 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::error::Error;
+use std::fmt;
+use std::string::String;
+
+/// An error resulting from formatting a [`Message`].
+///
+/// [`Message`]: struct.Message.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum FormatError {
+    /// An argument used by the message was not supplied.
+    MissingArgument(String),
+    /// An argument was supplied, but its value is not of a type that
+    /// can be used where it is referenced.
+    InvalidArgumentType(String),
+    /// A `PlaceholderFormat` was used outside of a `PluralFormat`, so
+    /// there is no value to output.
+    MissingPlaceholderValue,
+    /// An argument has a value of the right type, but it is outside of
+    /// the range that the format supports.
+    ValueOutOfRange(String),
+    /// A `plural` or `select` had no branch for the value of its
+    /// variable, so it would have used its `other` branch, while the
+    /// [`Context`] is set to fail on fallbacks.
+    ///
+    /// [`Context`]: struct.Context.html
+    UnexpectedFallback {
+        /// The variable of the `plural` or `select`.
+        variable: String,
+        /// The value that had no branch.
+        value: String,
+    },
+    /// Messages within `plural`, `select` and other formats were nested
+    /// more deeply than the [`Context`] allows.
+    ///
+    /// [`Context`]: struct.Context.html
+    TooDeep,
+    /// Writing to the output stream failed.
+    Write,
+}
+
+impl Error for FormatError {}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            FormatError::MissingArgument(ref name) => write!(f, "Missing argument '{}'.", name),
+            FormatError::InvalidArgumentType(ref name) => {
+                write!(f, "Invalid type for argument '{}'.", name)
+            }
+            FormatError::MissingPlaceholderValue => "No value for placeholder.".fmt(f),
+            FormatError::ValueOutOfRange(ref name) => {
+                write!(f, "Value of argument '{}' is out of range.", name)
+            }
+            FormatError::UnexpectedFallback { ref variable, ref value } => {
+                write!(f, "No branch for value '{}' of argument '{}'.", value, variable)
+            }
+            FormatError::TooDeep => "Formats are nested too deeply.".fmt(f),
+            FormatError::Write => "Unable to write to stream.".fmt(f),
+        }
+    }
+}
+
+impl From<fmt::Error> for FormatError {
+    fn from(_: fmt::Error) -> Self {
+        FormatError::Write
+    }
+}
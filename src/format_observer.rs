@@ -0,0 +1,265 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::string::{String, ToString};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::vec::Vec;
+
+use {FormatError, PluralCategory};
+
+/// Receive notifications about fallbacks and failures while formatting.
+///
+/// An observer is set on the [`Context`] and is notified by each
+/// message part as it is formatted, which is useful for telemetry
+/// without changing every call site. All of the methods do nothing
+/// by default.
+///
+/// Observers must be `Send` and `Sync` as a `Context` may be shared
+/// between threads.
+///
+/// With the `tracing` feature enabled, [`TracingObserver`] emits
+/// `tracing` events for each notification.
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use message_format::{icu, Context, FormatObserver};
+///
+/// #[derive(Default)]
+/// struct MissingArguments(Mutex<Vec<String>>);
+///
+/// impl FormatObserver for MissingArguments {
+///     fn on_missing_argument(&self, name: &str) {
+///         self.0.lock().unwrap().push(name.to_string());
+///     }
+/// }
+///
+/// let observer = Arc::new(MissingArguments::default());
+/// let ctx = Context::builder().observer(observer.clone()).build().unwrap();
+/// let m = icu::parse("Hello, {name}!").unwrap();
+/// ctx.format(&m, None);
+/// assert_eq!(*observer.0.lock().unwrap(), ["name"]);
+/// ```
+///
+/// [`Context`]: struct.Context.html
+/// [`TracingObserver`]: struct.TracingObserver.html
+pub trait FormatObserver: Send + Sync {
+    /// An argument used by the message was not supplied.
+    fn on_missing_argument(&self, _name: &str) {}
+
+    /// An argument used by the message was looked up.
+    fn on_argument(&self, _name: &str) {}
+
+    /// A `select` value didn't match any branch, so the default
+    /// branch was used.
+    fn on_select_fallback(&self, _variable: &str, _value: &str) {}
+
+    /// A `plural` value was classified as a category that doesn't
+    /// have a branch, so the `other` branch was used.
+    fn on_plural_fallback(&self, _variable: &str, _category: PluralCategory) {}
+
+    /// Formatting a message failed.
+    fn on_error(&self, _error: &FormatError) {}
+
+    /// The message with `key` failed to format with `error`, and the
+    /// message of the source locale was used instead. This is notified
+    /// by a [`BundleStack`] that falls back to its source.
+    ///
+    /// [`BundleStack`]: struct.BundleStack.html
+    fn on_source_fallback(&self, _key: &str, _error: &FormatError) {}
+}
+
+/// A `FormatObserver` that ignores all notifications.
+pub(crate) struct NoObserver;
+
+impl FormatObserver for NoObserver {}
+
+/// A `FormatObserver` that records the names of the arguments that are
+/// used, and passes every notification on to another observer.
+pub(crate) struct UsedArguments {
+    observer: Option<Arc<dyn FormatObserver>>,
+    /// The names of the arguments, and whether each has been used.
+    arguments: Vec<(String, AtomicBool)>,
+}
+
+impl UsedArguments {
+    pub(crate) fn new<'a, I>(observer: Option<Arc<dyn FormatObserver>>, names: I) -> Self
+        where I: IntoIterator<Item = &'a str>
+    {
+        UsedArguments {
+            observer,
+            arguments: names.into_iter()
+                .map(|name| (name.to_string(), AtomicBool::new(false)))
+                .collect(),
+        }
+    }
+
+    /// Whether the argument with `name` was used.
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        self.arguments.iter().any(|(n, used)| n == name && used.load(Ordering::Relaxed))
+    }
+
+    fn observer(&self) -> &dyn FormatObserver {
+        match self.observer {
+            Some(ref observer) => &**observer,
+            None => &NoObserver,
+        }
+    }
+}
+
+impl FormatObserver for UsedArguments {
+    fn on_missing_argument(&self, name: &str) {
+        self.observer().on_missing_argument(name);
+    }
+
+    fn on_argument(&self, name: &str) {
+        for (n, used) in &self.arguments {
+            if n == name {
+                used.store(true, Ordering::Relaxed);
+            }
+        }
+        self.observer().on_argument(name);
+    }
+
+    fn on_select_fallback(&self, variable: &str, value: &str) {
+        self.observer().on_select_fallback(variable, value);
+    }
+
+    fn on_plural_fallback(&self, variable: &str, category: PluralCategory) {
+        self.observer().on_plural_fallback(variable, category);
+    }
+
+    fn on_error(&self, error: &FormatError) {
+        self.observer().on_error(error);
+    }
+
+    fn on_source_fallback(&self, key: &str, error: &FormatError) {
+        self.observer().on_source_fallback(key, error);
+    }
+}
+
+/// A [`FormatObserver`] that emits [`tracing`] events.
+///
+/// Fallbacks are logged at the `debug` level, while missing arguments
+/// and errors are logged as warnings. All events use the
+/// `message_format` target.
+///
+/// This is only available with the `tracing` feature.
+///
+/// [`FormatObserver`]: trait.FormatObserver.html
+/// [`tracing`]: https://docs.rs/tracing
+#[cfg(feature = "tracing")]
+#[derive(Clone,Copy,Debug,Default)]
+pub struct TracingObserver;
+
+#[cfg(feature = "tracing")]
+impl FormatObserver for TracingObserver {
+    fn on_missing_argument(&self, name: &str) {
+        tracing::warn!(target: "message_format", argument = name, "missing argument");
+    }
+
+    fn on_select_fallback(&self, variable: &str, value: &str) {
+        tracing::debug!(target: "message_format", variable, value, "select fell back to default");
+    }
+
+    fn on_plural_fallback(&self, variable: &str, category: PluralCategory) {
+        tracing::debug!(target: "message_format",
+                        variable,
+                        category = ?category,
+                        "plural fell back to other");
+    }
+
+    fn on_error(&self, error: &FormatError) {
+        tracing::warn!(target: "message_format", %error, "formatting failed");
+    }
+
+    fn on_source_fallback(&self, key: &str, error: &FormatError) {
+        tracing::warn!(target: "message_format", key, %error, "fell back to source message");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use icu::parse;
+    use std::sync::{Arc, Mutex};
+    use super::FormatObserver;
+    use {arg, Context, FormatError, PluralCategory};
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl Recorder {
+        fn record(&self, event: String) {
+            self.0.lock().unwrap().push(event);
+        }
+
+        fn take(&self) -> Vec<String> {
+            self.0.lock().unwrap().drain(..).collect()
+        }
+    }
+
+    impl FormatObserver for Recorder {
+        fn on_missing_argument(&self, name: &str) {
+            self.record(format!("missing {}", name));
+        }
+
+        fn on_select_fallback(&self, variable: &str, value: &str) {
+            self.record(format!("select {} {}", variable, value));
+        }
+
+        fn on_plural_fallback(&self, variable: &str, category: PluralCategory) {
+            self.record(format!("plural {} {:?}", variable, category));
+        }
+
+        fn on_error(&self, error: &FormatError) {
+            self.record(format!("error {}", error));
+        }
+    }
+
+    fn setup() -> (Arc<Recorder>, Context) {
+        let recorder = Arc::new(Recorder::default());
+        let ctx = Context { observer: Some(recorder.clone()), ..Context::default() };
+        (recorder, ctx)
+    }
+
+    #[test]
+    fn missing_argument_is_observed() {
+        let (recorder, ctx) = setup();
+        let m = parse("Hello, {name}!").unwrap();
+        assert_eq!(ctx.format(&m, None), "Hello, ");
+        assert_eq!(recorder.take(),
+                   ["missing name", "error Missing argument 'name'."]);
+    }
+
+    #[test]
+    fn select_fallback_is_observed() {
+        let (recorder, ctx) = setup();
+        let m = parse("{g, select, female {She} other {They}}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("g", "female"))), "She");
+        assert!(recorder.take().is_empty());
+        assert_eq!(ctx.format(&m, Some(&arg("g", "robot"))), "They");
+        assert_eq!(recorder.take(), ["select g robot"]);
+    }
+
+    #[test]
+    fn plural_fallback_is_observed() {
+        let (recorder, ctx) = setup();
+        let m = parse("{n, plural, other {# items}}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", 2))), "2 items");
+        assert!(recorder.take().is_empty());
+        assert_eq!(ctx.format(&m, Some(&arg("n", 1))), "1 items");
+        assert_eq!(recorder.take(), ["plural n One"]);
+    }
+
+    #[test]
+    fn display_errors_are_observed() {
+        let (recorder, ctx) = setup();
+        let m = parse("{n, plural, other {# items}}").unwrap();
+        let args = arg("n", "many");
+        assert!(m.display(&ctx, Some(&args)).try_to_string().is_err());
+        assert_eq!(recorder.take(), ["error Invalid type for argument 'n'."]);
+    }
+}
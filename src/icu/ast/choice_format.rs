@@ -0,0 +1,219 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::boxed::Box;
+use std::fmt;
+use std::string::{String, ToString};
+use std::sync::Arc;
+use std::vec::Vec;
+
+use {Args, Context, FormatError, Message, MessagePart, MessageVisitor, Value};
+
+/// Select a message for the range that a numeric value is within.
+///
+/// This is the deprecated ICU `ChoiceFormat`, which is supported for
+/// older messages. Each choice has a lower limit, which is either
+/// inclusive, as with `1#` in a pattern, or exclusive, as with `1<`.
+/// The last choice whose limit is met is used. If no limit is met,
+/// the first choice is used.
+///
+/// ```
+/// use message_format::{arg, icu, Context};
+///
+/// let ctx = Context::default();
+/// let m = icu::parse("{n, choice, 0#no files|1#one file|1<{n} files}").unwrap();
+/// assert_eq!(ctx.format(&m, Some(&arg("n", 0))), "no files");
+/// assert_eq!(ctx.format(&m, Some(&arg("n", 1))), "one file");
+/// assert_eq!(ctx.format(&m, Some(&arg("n", 1.5))), "1.5 files");
+/// ```
+#[derive(Debug)]
+pub struct ChoiceFormat {
+    /// The name of the variable whose value selects a choice.
+    variable_name: String,
+    /// The limit of each choice, whether it is exclusive and its message,
+    /// in ascending order of the limits.
+    choices: Vec<(f64, bool, Arc<Message>)>,
+}
+
+impl ChoiceFormat {
+    /// Construct a `ChoiceFormat` without any choices.
+    pub fn new(variable_name: &str) -> Self {
+        ChoiceFormat {
+            variable_name: variable_name.to_string(),
+            choices: vec![],
+        }
+    }
+
+    /// Add a choice for values that are at least `limit`.
+    ///
+    /// The limits of the choices must be added in ascending order.
+    pub fn at_least(&mut self, limit: f64, message: Message) {
+        self.choices.push((limit, false, Arc::new(message)));
+    }
+
+    /// Add a choice for values that are greater than `limit`.
+    ///
+    /// The limits of the choices must be added in ascending order.
+    pub fn greater_than(&mut self, limit: f64, message: Message) {
+        self.choices.push((limit, true, Arc::new(message)));
+    }
+
+    /// Given a value, determine which `Message` to use. This is `None`
+    /// if there are no choices.
+    pub fn lookup_message(&self, value: f64) -> Option<&Message> {
+        let index = self.choices
+            .iter()
+            .rposition(|&(limit, exclusive, _)| {
+                if exclusive { value > limit } else { value >= limit }
+            })
+            .unwrap_or(0);
+        self.choices.get(index).map(|(_, _, message)| &**message)
+    }
+
+    /// The numeric value of an argument, if it has one.
+    fn numeric_value(value: &Value) -> Option<f64> {
+        match value.resolve() {
+            Value::Number(value) => Some(value as f64),
+            Value::BigInt(value) => Some(value as f64),
+            Value::Decimal { value, .. } => Some(value),
+            Value::DecimalStr(decimal) => Some(decimal.value()),
+            Value::Str(_) | Value::Lazy(_) => None,
+        }
+    }
+
+    /// Replace the message of each choice with the result of `map`.
+    fn map_choices(&self, map: &dyn Fn(&Message) -> Message) -> ChoiceFormat {
+        ChoiceFormat {
+            variable_name: self.variable_name.clone(),
+            choices: self.choices
+                .iter()
+                .map(|&(limit, exclusive, ref message)| (limit, exclusive, Arc::new(map(message))))
+                .collect(),
+        }
+    }
+}
+
+impl MessagePart for ChoiceFormat {
+    fn apply_format<'f>(&self,
+                        ctx: &Context,
+                        stream: &mut dyn fmt::Write,
+                        args: Option<&Args<'f>>)
+                        -> Result<(), FormatError> {
+        self.apply_format_nested(ctx, 0, stream, args)
+    }
+
+    fn apply_format_nested<'f>(&self,
+                               ctx: &Context,
+                               depth: usize,
+                               stream: &mut dyn fmt::Write,
+                               args: Option<&Args<'f>>)
+                               -> Result<(), FormatError> {
+        let arg = ctx.lookup_arg(args, &self.variable_name)?;
+        let value = match ChoiceFormat::numeric_value(arg.value()) {
+            Some(value) => value,
+            None => return Err(FormatError::InvalidArgumentType(self.variable_name.clone())),
+        };
+        match self.lookup_message(value) {
+            Some(message) => message.write_nested(ctx, depth + 1, stream, args),
+            None => Ok(()),
+        }
+    }
+
+    fn partial(&self, ctx: &Context, args: &Args) -> Option<Message> {
+        match args.get(&self.variable_name) {
+            Some(arg) => {
+                let value = ChoiceFormat::numeric_value(arg.value())?;
+                match self.lookup_message(value) {
+                    Some(message) => Some(message.partial_parts(ctx, args)),
+                    None => Some(Message::new(vec![])),
+                }
+            }
+            None => {
+                let fmt = self.map_choices(&|message| message.partial_parts(ctx, args));
+                Some(Message::new(vec![Box::new(fmt)]))
+            }
+        }
+    }
+
+    fn map_text(&self, transform: &dyn Fn(&str) -> String) -> Option<Message> {
+        let fmt = self.map_choices(&|message| message.map_text(transform));
+        Some(Message::new(vec![Box::new(fmt)]))
+    }
+
+    fn accept(&self, visitor: &mut dyn MessageVisitor) {
+        visitor.visit_choice(&self.variable_name);
+        for (_, _, message) in &self.choices {
+            visitor.enter_branch();
+            message.accept(visitor);
+            visitor.leave_branch();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChoiceFormat;
+    use icu::parse;
+    use {arg, Context, DecimalStr, MessagePart, Value};
+
+    fn format(fmt: &ChoiceFormat, value: Value) -> String {
+        let mut output = String::new();
+        fmt.apply_format(&Context::default(), &mut output, Some(&arg("n", value))).unwrap();
+        output
+    }
+
+    fn decimal(source: &str) -> Value<'_> {
+        Value::DecimalStr(DecimalStr::new(source).unwrap())
+    }
+
+    #[test]
+    fn boundaries() {
+        let mut fmt = ChoiceFormat::new("n");
+        fmt.at_least(0.0, parse("none").unwrap());
+        fmt.at_least(1.0, parse("one").unwrap());
+        fmt.greater_than(1.0, parse("many").unwrap());
+        fmt.at_least(10.0, parse("lots").unwrap());
+
+        let cases = [(-5.0, "none"), (0.0, "none"), (0.5, "none"), (1.0, "one"),
+                     (1.001, "many"), (9.99, "many"), (10.0, "lots"), (1e9, "lots")];
+        for &(value, expected) in &cases {
+            let value = Value::Decimal { value, fraction_digits: 3 };
+            assert_eq!(format(&fmt, value), expected, "{:?}", value);
+        }
+        assert_eq!(format(&fmt, Value::Number(1)), "one");
+        assert_eq!(format(&fmt, decimal("1.0")), "one");
+        assert_eq!(format(&fmt, decimal("-1")), "none");
+    }
+
+    #[test]
+    fn exclusive_limits() {
+        let mut fmt = ChoiceFormat::new("n");
+        fmt.greater_than(0.0, parse("positive").unwrap());
+        fmt.greater_than(100.0, parse("large").unwrap());
+        // Values that don't meet any limit use the first choice.
+        assert_eq!(format(&fmt, Value::Number(0)), "positive");
+        assert_eq!(format(&fmt, Value::Number(100)), "positive");
+        assert_eq!(format(&fmt, Value::Number(101)), "large");
+    }
+
+    #[test]
+    fn strings_are_rejected() {
+        let fmt = ChoiceFormat::new("n");
+        let mut output = String::new();
+        assert!(fmt.apply_format(&Context::default(), &mut output, Some(&arg("n", "1"))).is_err());
+        assert_eq!(format(&fmt, Value::Number(1)), "");
+    }
+
+    #[test]
+    fn partial() {
+        let ctx = Context::default();
+        let m = parse("{n, choice, 0#no files for {name}|1#one file for {name}}").unwrap();
+        let partial = m.partial(&ctx, &arg("name", "Jane"));
+        assert_eq!(ctx.format(&partial, Some(&arg("n", 1))), "one file for Jane");
+        let partial = m.partial(&ctx, &arg("n", 0));
+        assert_eq!(ctx.format(&partial, Some(&arg("name", "Jane"))), "no files for Jane");
+    }
+}
@@ -0,0 +1,61 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::string::{String, ToString};
+
+use {Args, Context, FormatError, Message, MessagePart, MessageVisitor};
+
+/// A note for translators, written as `{! note }`. It isn't output.
+///
+/// Comments are only kept in a parsed message if the [`Parser`] is set
+/// to keep them, and are found with [`Message::comments`].
+///
+/// [`Parser`]: ../struct.Parser.html
+/// [`Message::comments`]: ../../struct.Message.html#method.comments
+#[derive(Debug)]
+pub struct Comment {
+    /// The text of the comment.
+    text: String,
+}
+
+impl Comment {
+    /// Construct a `Comment`.
+    pub fn new(text: &str) -> Self {
+        Comment { text: text.to_string() }
+    }
+}
+
+impl MessagePart for Comment {
+    fn apply_format(&self,
+                    _ctx: &Context,
+                    _stream: &mut dyn fmt::Write,
+                    _args: Option<&Args>)
+                    -> Result<(), FormatError> {
+        Ok(())
+    }
+
+    fn accept(&self, visitor: &mut dyn MessageVisitor) {
+        visitor.visit_comment(&self.text);
+    }
+
+    fn partial(&self, _ctx: &Context, _args: &Args) -> Option<Message> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Comment;
+    use {Context, Message};
+
+    #[test]
+    fn it_works() {
+        let m = Message::new(vec![Box::new(Comment::new("A note."))]);
+        assert_eq!(Context::default().format(&m, None), "");
+        assert_eq!(m.comments(), ["A note."]);
+    }
+}
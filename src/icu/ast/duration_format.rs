@@ -0,0 +1,219 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::string::{String, ToString};
+
+use {Args, Context, FormatError, Locale, MessagePart, MessageVisitor, Value};
+
+/// How a [`DurationFormat`] writes a duration.
+///
+/// [`DurationFormat`]: struct.DurationFormat.html
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum DurationStyle {
+    /// Colon-separated, as in `1:23:45`. The hours are left out when
+    /// they are zero, as in `0:59`.
+    Numeric,
+    /// Abbreviated units of the locale, as in `1 hr 23 min 45 sec`.
+    /// Units that are zero are left out.
+    Units,
+}
+
+/// Format a number of seconds as a duration.
+///
+/// This is the `duration` format type, as in `{secs, duration}`. The
+/// `units` style, as in `{secs, duration, units}`, uses
+/// `DurationStyle::Units`.
+#[derive(Debug)]
+pub struct DurationFormat {
+    /// The name of the variable whose value should be formatted.
+    variable_name: String,
+    style: DurationStyle,
+}
+
+impl DurationFormat {
+    /// Construct a `DurationFormat` with `DurationStyle::Numeric`.
+    pub fn new(variable_name: &str) -> Self {
+        DurationFormat {
+            variable_name: variable_name.to_string(),
+            style: DurationStyle::Numeric,
+        }
+    }
+
+    /// Set how the duration is written.
+    pub fn style(&mut self, style: DurationStyle) {
+        self.style = style;
+    }
+
+    /// Write the hours, minutes and seconds of a duration, without any
+    /// sign. The numbers are written with the number symbols of the
+    /// `ctx`, and units are named in the first of its locales that has
+    /// names for them.
+    fn write_duration(&self,
+                      ctx: &Context,
+                      stream: &mut dyn fmt::Write,
+                      seconds: u64)
+                      -> fmt::Result {
+        let symbols = &ctx.number_symbols;
+        let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+        match self.style {
+            DurationStyle::Numeric if hours > 0 => {
+                symbols.write_ascii(stream, &format!("{}:{:02}:{:02}", hours, minutes, seconds))
+            }
+            DurationStyle::Numeric => {
+                symbols.write_ascii(stream, &format!("{}:{:02}", minutes, seconds))
+            }
+            DurationStyle::Units => {
+                let names = ctx.locales().find_map(unit_names).unwrap_or(["hr", "min", "sec"]);
+                let mut written = false;
+                for (&value, name) in [hours, minutes, seconds].iter().zip(&names) {
+                    if value > 0 {
+                        if written {
+                            stream.write_char(' ')?;
+                        }
+                        symbols.write_integer(stream, value)?;
+                        write!(stream, " {}", name)?;
+                        written = true;
+                    }
+                }
+                if !written {
+                    symbols.write_integer(stream, 0)?;
+                    write!(stream, " {}", names[2])?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The abbreviated names of hours, minutes and seconds in a locale, if
+/// there are any. English names are used otherwise.
+fn unit_names(locale: &Locale) -> Option<[&'static str; 3]> {
+    match locale.language() {
+        "de" => Some(["Std.", "Min.", "Sek."]),
+        "en" => Some(["hr", "min", "sec"]),
+        "es" | "fr" | "it" | "pt" => Some(["h", "min", "s"]),
+        "nl" => Some(["u", "min", "s"]),
+        "ru" => Some(["ч", "мин", "с"]),
+        _ => None,
+    }
+}
+
+impl MessagePart for DurationFormat {
+    fn apply_format<'f>(&self,
+                        ctx: &Context,
+                        stream: &mut dyn fmt::Write,
+                        args: Option<&Args<'f>>)
+                        -> Result<(), FormatError> {
+        if ctx.pseudolocalization.is_on() {
+            return Ok(write!(stream, "[{}]", self.variable_name)?);
+        }
+        let arg = ctx.lookup_arg(args, &self.variable_name)?;
+        let seconds = match arg.value().resolve() {
+            Value::Number(value) => value,
+            Value::BigInt(_) => {
+                return Err(FormatError::ValueOutOfRange(self.variable_name.clone()))
+            }
+            _ => return Err(FormatError::InvalidArgumentType(self.variable_name.clone())),
+        };
+        if seconds < 0 {
+            ctx.number_symbols.write_ascii(stream, "-")?;
+        }
+        Ok(self.write_duration(ctx, stream, seconds.unsigned_abs())?)
+    }
+
+    fn accept(&self, visitor: &mut dyn MessageVisitor) {
+        visitor.visit_duration(&self.variable_name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DurationFormat, DurationStyle};
+    use icu::parse;
+    use {arg, Context, FormatError, Locale, MessagePart, NumberSymbols};
+
+    fn format(fmt: &DurationFormat, ctx: &Context, seconds: i64) -> String {
+        let mut output = String::new();
+        fmt.apply_format(ctx, &mut output, Some(&arg("secs", seconds))).unwrap();
+        output
+    }
+
+    #[test]
+    fn numeric() {
+        let fmt = DurationFormat::new("secs");
+        let ctx = Context::default();
+        let cases = [(0, "0:00"), (59, "0:59"), (60, "1:00"), (3599, "59:59"),
+                     (3600, "1:00:00"), (3661, "1:01:01"), (5025, "1:23:45"),
+                     (360000, "100:00:00"), (-61, "-1:01")];
+        for &(seconds, expected) in &cases {
+            assert_eq!(format(&fmt, &ctx, seconds), expected, "{}", seconds);
+        }
+    }
+
+    #[test]
+    fn units() {
+        let mut fmt = DurationFormat::new("secs");
+        fmt.style(DurationStyle::Units);
+        let ctx = Context::default();
+        let cases = [(0, "0 sec"), (59, "59 sec"), (3600, "1 hr"), (3661, "1 hr 1 min 1 sec"),
+                     (5025, "1 hr 23 min 45 sec"), (3605, "1 hr 5 sec"), (-120, "-2 min")];
+        for &(seconds, expected) in &cases {
+            assert_eq!(format(&fmt, &ctx, seconds), expected, "{}", seconds);
+        }
+
+        let ctx = Context::new(Locale::new("de-AT").unwrap(), None);
+        assert_eq!(format(&fmt, &ctx, 5025), "1 Std. 23 Min. 45 Sek.");
+        let ctx = Context::new(Locale::new("fr").unwrap(), None);
+        assert_eq!(format(&fmt, &ctx, 5025), "1 h 23 min 45 s");
+        let ctx = Context::locale_chain(&["tlh", "de"]).unwrap();
+        assert_eq!(format(&fmt, &ctx, 5025), "1 Std. 23 Min. 45 Sek.");
+        let ctx = Context::locale_chain(&["tlh", "ja"]).unwrap();
+        assert_eq!(format(&fmt, &ctx, 5025), "1 hr 23 min 45 sec");
+    }
+
+    #[test]
+    fn uses_number_symbols() {
+        let fmt = DurationFormat::new("secs");
+        let ctx = Context {
+            number_symbols: NumberSymbols { zero_digit: '\u{660}', ..NumberSymbols::default() },
+            ..Context::default()
+        };
+        assert_eq!(format(&fmt, &ctx, 3661), "١:٠١:٠١");
+
+        // Only the numbers use the symbols, not the names of the units.
+        let mut fmt = DurationFormat::new("secs");
+        fmt.style(DurationStyle::Units);
+        let ctx = Context {
+            number_symbols: NumberSymbols {
+                zero_digit: '\u{660}',
+                decimal_separator: ',',
+                minus_sign: '\u{2212}',
+                ..NumberSymbols::default()
+            },
+            ..Context::new(Locale::new("de").unwrap(), None)
+        };
+        assert_eq!(format(&fmt, &ctx, -3661), "\u{2212}١ Std. ١ Min. ١ Sek.");
+        assert_eq!(format(&fmt, &ctx, 0), "٠ Sek.");
+    }
+
+    #[test]
+    fn requires_a_number() {
+        let fmt = DurationFormat::new("secs");
+        let ctx = Context::default();
+        let mut output = String::new();
+        assert_eq!(fmt.apply_format(&ctx, &mut output, Some(&arg("secs", "soon"))),
+                   Err(FormatError::InvalidArgumentType("secs".to_string())));
+    }
+
+    #[test]
+    fn parsed() {
+        let ctx = Context::default();
+        let m = parse("Took {secs, duration} ({secs, duration, units}).").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("secs", 3661))), "Took 1:01:01 (1 hr 1 min 1 sec).");
+        assert!(parse("{secs, duration, long}").is_err());
+    }
+}
@@ -11,14 +11,28 @@
 //!
 //! [`MessagePart`]: ../../trait.MessagePart.html
 
+mod choice_format;
+mod comment;
+mod duration_format;
+mod number_format;
 mod placeholder_format;
 mod plain_text;
 mod plural_format;
+mod relative_time_format;
 mod select_format;
 mod simple_format;
+mod spellout_format;
+mod unit_format;
 
+pub use self::choice_format::ChoiceFormat;
+pub use self::comment::Comment;
+pub use self::duration_format::{DurationFormat, DurationStyle};
+pub use self::number_format::{Notation, NumberFormat, PadPosition, Padding, SignDisplay};
 pub use self::placeholder_format::PlaceholderFormat;
 pub use self::plain_text::PlainText;
-pub use self::plural_format::PluralFormat;
+pub use self::plural_format::{ModuloError, PluralBranch, PluralExplanation, PluralFormat};
+pub use self::relative_time_format::{RelativeTimeFormat, RelativeTimeUnit};
 pub use self::select_format::SelectFormat;
 pub use self::simple_format::SimpleFormat;
+pub use self::spellout_format::SpelloutFormat;
+pub use self::unit_format::{Unit, UnitFormat, UnitWidth};
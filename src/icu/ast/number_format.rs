@@ -0,0 +1,721 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use {Args, Context, FormatError, MessagePart, MessageVisitor, Value};
+
+/// When a [`NumberFormat`] shows the sign of a number.
+///
+/// [`NumberFormat`]: struct.NumberFormat.html
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum SignDisplay {
+    /// Show a sign only for negative numbers. This is the default.
+    Auto,
+    /// Show a sign for every number, including `+0`.
+    Always,
+    /// Never show a sign.
+    Never,
+    /// Show a sign for every number other than zero.
+    ExceptZero,
+}
+
+/// How a [`NumberFormat`] writes the digits of a number.
+///
+/// [`NumberFormat`]: struct.NumberFormat.html
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum Notation {
+    /// Write every integer digit, grouped unless grouping is disabled.
+    /// This is the default.
+    Standard,
+    /// Write a mantissa with one integer digit and a power of ten, as
+    /// in `1.2345E4`. The exponent is padded with zeros to at least
+    /// `exponent_digits` digits. The mantissa is rounded half to even
+    /// to `fraction_digits` digits after the decimal point, or if that
+    /// is `None`, it has as many as the value has significant digits.
+    Scientific {
+        /// The minimum number of digits in the exponent.
+        exponent_digits: u8,
+        /// The number of digits after the decimal point in the mantissa.
+        fraction_digits: Option<u8>,
+    },
+}
+
+/// Where a [`NumberFormat`] adds the padding of a [`Padding`].
+///
+/// [`NumberFormat`]: struct.NumberFormat.html
+/// [`Padding`]: struct.Padding.html
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum PadPosition {
+    /// Before the sign, or the opening parenthesis of a negative number,
+    /// so that numbers are right-aligned, as in `  -5`.
+    BeforeSign,
+    /// After the sign, before the digits, as in `-005`.
+    AfterSign,
+}
+
+/// Pads the output of a [`NumberFormat`] to a fixed width.
+///
+/// The width counts every character of the number, including its sign,
+/// grouping separators and any `%` or parentheses. Numbers that are at
+/// least as wide aren't changed. The padding is added after the digits
+/// are grouped, so zeros used as padding aren't grouped, and are best
+/// used without grouping.
+///
+/// [`NumberFormat`]: struct.NumberFormat.html
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct Padding {
+    /// The number of characters to pad to.
+    pub width: u8,
+    /// The character to pad with. `0` is written as the zero digit of
+    /// the `NumberSymbols`.
+    pub character: char,
+    /// Where the padding is added.
+    pub position: PadPosition,
+}
+
+/// Format a numeric value using the `NumberSymbols` of the `Context`.
+///
+/// By default, the integer digits are grouped in threes, as in `1,234`,
+/// and only negative numbers have a sign, which is the minus sign of
+/// the `NumberSymbols`. Negative numbers can be put in parentheses
+/// instead, as in `(1,234)`, which is common in financial statements.
+/// Very large and very small numbers can be written in scientific
+/// notation, as in `1.23E-8`. Numbers can be padded to a fixed width
+/// for aligned output, as in `  5` or `005`.
+#[derive(Debug)]
+pub struct NumberFormat {
+    /// The name of the variable whose value should be formatted.
+    variable_name: String,
+    grouping: bool,
+    sign_display: SignDisplay,
+    negative_parens: bool,
+    notation: Notation,
+    maximum_fraction_digits: Option<u8>,
+    percent: bool,
+    padding: Option<Padding>,
+}
+
+impl NumberFormat {
+    /// Construct a `NumberFormat`.
+    pub fn new(variable_name: &str) -> Self {
+        NumberFormat {
+            variable_name: variable_name.to_string(),
+            grouping: true,
+            sign_display: SignDisplay::Auto,
+            negative_parens: false,
+            notation: Notation::Standard,
+            maximum_fraction_digits: None,
+            percent: false,
+            padding: None,
+        }
+    }
+
+    /// Set whether integer digits are grouped. This is disabled by the
+    /// `::group-off` skeleton, which is useful for years and identifiers.
+    pub fn grouping(&mut self, grouping: bool) {
+        self.grouping = grouping;
+    }
+
+    /// Set when the sign is shown. This is set by the `sign-auto`,
+    /// `sign-always`, `sign-never` and `sign-except-zero` skeleton
+    /// stems, which is useful for differences like `+5`.
+    pub fn sign_display(&mut self, sign_display: SignDisplay) {
+        self.sign_display = sign_display;
+    }
+
+    /// Set whether negative numbers are put in parentheses rather than
+    /// having a minus sign. This is set by the `negative-parens` stem,
+    /// or `sign-accounting` as ICU calls it.
+    ///
+    /// Numbers that are zero once they are rounded, like `-0.001` with
+    /// two fraction digits, aren't negative, so they are written as
+    /// `0.00`. Negative numbers are still written without parentheses
+    /// when the sign display is `SignDisplay::Never`.
+    pub fn negative_parens(&mut self, negative_parens: bool) {
+        self.negative_parens = negative_parens;
+    }
+
+    /// Set how the digits of a number are written. This is set to
+    /// scientific notation by the `scientific` style and skeleton stem,
+    /// and by the `E0` stem, where each `0` is a digit of the exponent.
+    /// A `.00` stem, with one `0` for each fraction digit, sets the
+    /// digits of the mantissa.
+    pub fn notation(&mut self, notation: Notation) {
+        self.notation = notation;
+    }
+
+    /// Round numbers half to even to at most this many digits after the
+    /// decimal point, or if this is `None`, write every digit of the
+    /// value. This is set to `0` by the `integer` and `percent` styles,
+    /// and only applies to the standard notation.
+    pub fn maximum_fraction_digits(&mut self, digits: Option<u8>) {
+        self.maximum_fraction_digits = digits;
+    }
+
+    /// Set whether numbers are written as percentages, multiplied by 100
+    /// and followed by `%`, so that `0.25` is `25%`. This is set by the
+    /// `percent` style.
+    pub fn percent(&mut self, percent: bool) {
+        self.percent = percent;
+    }
+
+    /// Pad numbers to a fixed width, or if this is `None`, don't pad
+    /// them. This is set by the `padding/` stem followed by the width,
+    /// like `padding/3`, which pads with spaces before the sign, or by a
+    /// `0` and the width, like `padding/03`, which pads with zeros after
+    /// the sign. Another character before the width, like `padding/*6`,
+    /// pads with that character before the sign.
+    pub fn padding(&mut self, padding: Option<Padding>) {
+        self.padding = padding;
+    }
+
+    /// Group the integer digits of a number written in ASCII, like
+    /// `-1234.5`, and add its sign. The result is localized by
+    /// `NumberSymbols`.
+    pub(crate) fn format_ascii(&self, source: &str) -> String {
+        let (negative, source) = match source.strip_prefix('-') {
+            Some(source) => (true, source),
+            None => (false, source),
+        };
+        let mut source = Cow::Borrowed(source);
+        if self.percent {
+            if let Some(shifted) = shift(&source, 2) {
+                source = Cow::Owned(shifted);
+            }
+        }
+        if let (Notation::Standard, Some(digits)) = (self.notation, self.maximum_fraction_digits) {
+            if let Some(rounded) = round(&source, digits) {
+                source = Cow::Owned(rounded);
+            }
+        }
+        let is_zero = source.chars()
+            .take_while(|&ch| ch.is_ascii_digit() || ch == '.')
+            .all(|ch| ch == '0' || ch == '.');
+        let sign = match self.sign_display {
+            SignDisplay::Never => "",
+            SignDisplay::ExceptZero if is_zero => "",
+            _ if negative => "-",
+            SignDisplay::Always | SignDisplay::ExceptZero => "+",
+            SignDisplay::Auto => "",
+        };
+        let parens = self.negative_parens && sign == "-";
+        let sign = if parens { "" } else { sign };
+        let parens = parens && !is_zero;
+        let mut output = if parens { "(" } else { sign }.to_string();
+        let prefix_len = output.len();
+        let scientific = match self.notation {
+            Notation::Standard => None,
+            Notation::Scientific { exponent_digits, fraction_digits } => {
+                scientific(&source, exponent_digits, fraction_digits)
+            }
+        };
+        match scientific {
+            Some(scientific) => output.push_str(&scientific),
+            None => {
+                let (digits, fraction) =
+                    source.split_at(source.find('.').unwrap_or(source.len()));
+                for (index, digit) in digits.chars().enumerate() {
+                    if self.grouping && index > 0 && (digits.len() - index) % 3 == 0 {
+                        output.push(',');
+                    }
+                    output.push(digit);
+                }
+                output.push_str(fraction);
+            }
+        }
+        if self.percent {
+            output.push('%');
+        }
+        if parens {
+            output.push(')');
+        }
+        if let Some(padding) = self.padding {
+            let len = output.chars().count();
+            if len < padding.width as usize {
+                let index = match padding.position {
+                    PadPosition::BeforeSign => 0,
+                    PadPosition::AfterSign => prefix_len,
+                };
+                let pad = padding.character.to_string().repeat(padding.width as usize - len);
+                output.insert_str(index, &pad);
+            }
+        }
+        output
+    }
+
+    /// Write the value of the variable in ASCII, like `-1234.5`, before
+    /// it is grouped.
+    pub(crate) fn ascii_value(&self,
+                              ctx: &Context,
+                              args: Option<&Args>)
+                              -> Result<String, FormatError> {
+        let arg = ctx.lookup_arg(args, &self.variable_name)?;
+        Ok(match arg.value().resolve() {
+            Value::Number(value) => value.to_string(),
+            Value::BigInt(value) => value.to_string(),
+            Value::Decimal { value, fraction_digits } => {
+                format!("{:.*}", fraction_digits as usize, value)
+            }
+            Value::DecimalStr(value) => value.to_string(),
+            Value::Str(_) | Value::Lazy(_) => {
+                return Err(FormatError::InvalidArgumentType(self.variable_name.clone()))
+            }
+        })
+    }
+
+    /// The name of the variable whose value is formatted.
+    pub(crate) fn variable_name(&self) -> &str {
+        &self.variable_name
+    }
+}
+
+/// Split an unsigned number in ASCII, like `1234.5`, into its integer
+/// and fraction digits, or `None` if it isn't made of digits and a
+/// decimal point.
+fn split_digits(source: &str) -> Option<(&str, &str)> {
+    let (integer, fraction) = source.split_at(source.find('.').unwrap_or(source.len()));
+    let fraction = fraction.strip_prefix('.').unwrap_or(fraction);
+    if integer.len() + fraction.len() == 0 ||
+       !integer.chars().chain(fraction.chars()).all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+    Some((integer, fraction))
+}
+
+/// Write an unsigned number in ASCII, like `1234.5`, in scientific
+/// notation, like `1.2345E3`. Sources that aren't made of digits and a
+/// decimal point are left to be written as they are.
+fn scientific(source: &str, exponent_digits: u8, fraction_digits: Option<u8>) -> Option<String> {
+    let (integer, fraction) = split_digits(source)?;
+    let digits = integer.bytes().chain(fraction.bytes()).map(|b| b - b'0').collect::<Vec<_>>();
+    let (mut mantissa, mut exponent) = match digits.iter().position(|&digit| digit != 0) {
+        Some(first) => (digits[first..].to_vec(), integer.len() as i64 - first as i64 - 1),
+        None => (vec![], 0),
+    };
+    match fraction_digits {
+        Some(fraction_digits) => {
+            let len = fraction_digits as usize + 1;
+            if mantissa.len() > len && rounds_up(&mantissa, len) {
+                mantissa.truncate(len);
+                match mantissa.iter().rposition(|&digit| digit != 9) {
+                    Some(index) => {
+                        mantissa[index] += 1;
+                        mantissa[index + 1..].iter_mut().for_each(|digit| *digit = 0);
+                    }
+                    // 9.99 rounds up to 1.00 with the next exponent.
+                    None => {
+                        mantissa = vec![1];
+                        exponent += 1;
+                    }
+                }
+            }
+            mantissa.resize(len, 0);
+        }
+        None => {
+            while mantissa.len() > 1 && mantissa.last() == Some(&0) {
+                mantissa.pop();
+            }
+            if mantissa.is_empty() {
+                mantissa.push(0);
+            }
+        }
+    }
+    let mut output = String::new();
+    for (index, &digit) in mantissa.iter().enumerate() {
+        if index == 1 {
+            output.push('.');
+        }
+        output.push((b'0' + digit) as char);
+    }
+    output.push('E');
+    if exponent < 0 {
+        output.push('-');
+    }
+    output.push_str(&format!("{:01$}", exponent.abs(), exponent_digits as usize));
+    Some(output)
+}
+
+/// Join integer and fraction digits, without extra leading zeros.
+fn join(integer: &str, fraction: &str) -> String {
+    let integer = integer.trim_start_matches('0');
+    let integer = if integer.is_empty() { "0" } else { integer };
+    if fraction.is_empty() {
+        integer.to_string()
+    } else {
+        format!("{}.{}", integer, fraction)
+    }
+}
+
+/// Multiply an unsigned number in ASCII by `10^places`, by moving its
+/// decimal point.
+fn shift(source: &str, places: usize) -> Option<String> {
+    let (integer, fraction) = split_digits(source)?;
+    let fraction = format!("{:0<1$}", fraction, places);
+    Some(join(&format!("{}{}", integer, &fraction[..places]), &fraction[places..]))
+}
+
+/// Round an unsigned number in ASCII half to even to at most
+/// `fraction_digits` digits after the decimal point.
+fn round(source: &str, fraction_digits: u8) -> Option<String> {
+    let (integer, fraction) = split_digits(source)?;
+    let len = fraction_digits as usize;
+    if fraction.len() <= len {
+        return None;
+    }
+    // A leading zero makes room for a carry, like 9.5 rounding to 10.
+    let mut digits = "0".bytes()
+        .chain(integer.bytes())
+        .chain(fraction.bytes())
+        .map(|b| b - b'0')
+        .collect::<Vec<_>>();
+    let kept = 1 + integer.len() + len;
+    let up = rounds_up(&digits, kept);
+    digits.truncate(kept);
+    if up {
+        let index = digits.iter().rposition(|&digit| digit != 9).unwrap_or(0);
+        digits[index] += 1;
+        digits[index + 1..].iter_mut().for_each(|digit| *digit = 0);
+    }
+    let digits = digits.into_iter().map(|digit| (b'0' + digit) as char).collect::<String>();
+    let (integer, fraction) = digits.split_at(kept - len);
+    Some(join(integer, fraction))
+}
+
+/// Whether digits rounded half to even to their first `len` digits
+/// round up.
+fn rounds_up(digits: &[u8], len: usize) -> bool {
+    match digits[len] {
+        5 => digits[len + 1..].iter().any(|&digit| digit != 0) || digits[len - 1] % 2 == 1,
+        digit => digit > 5,
+    }
+}
+
+impl MessagePart for NumberFormat {
+    fn apply_format<'f>(&self,
+                        ctx: &Context,
+                        stream: &mut dyn fmt::Write,
+                        args: Option<&Args<'f>>)
+                        -> Result<(), FormatError> {
+        if ctx.pseudolocalization.is_on() {
+            return Ok(write!(stream, "[{}]", self.variable_name)?);
+        }
+        let source = self.ascii_value(ctx, args)?;
+        ctx.number_symbols.write_ascii(stream, &self.format_ascii(&source))?;
+        Ok(())
+    }
+
+    fn accept(&self, visitor: &mut dyn MessageVisitor) {
+        visitor.visit_number(&self.variable_name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Notation, NumberFormat, PadPosition, Padding, SignDisplay};
+    use icu::{parse, ParseErrorKind};
+    use {arg, Context, DecimalStr, MessagePart, NumberSymbols, Value};
+
+    fn format(fmt: &NumberFormat, value: i64) -> String {
+        let ctx = Context::default();
+        let mut output = String::new();
+        fmt.apply_format(&ctx, &mut output, Some(&arg("n", value))).unwrap();
+        output
+    }
+
+    fn decimal(source: &str) -> Value<'_> {
+        Value::DecimalStr(DecimalStr::new(source).unwrap())
+    }
+
+    #[test]
+    fn it_works() {
+        let fmt = NumberFormat::new("n");
+        assert_eq!(format(&fmt, 0), "0");
+        assert_eq!(format(&fmt, 999), "999");
+        assert_eq!(format(&fmt, 1000), "1,000");
+        assert_eq!(format(&fmt, -1234567), "-1,234,567");
+        assert_eq!(format(&fmt, i64::MIN), "-9,223,372,036,854,775,808");
+    }
+
+    #[test]
+    fn decimals() {
+        let fmt = NumberFormat::new("n");
+        let ctx = Context::default();
+        let mut output = String::new();
+        let value = Value::Decimal { value: -1234.5, fraction_digits: 2 };
+        fmt.apply_format(&ctx, &mut output, Some(&arg("n", value))).unwrap();
+        assert_eq!(output, "-1,234.50");
+    }
+
+    #[test]
+    fn grouping_can_be_disabled() {
+        let mut fmt = NumberFormat::new("n");
+        fmt.grouping(false);
+        assert_eq!(format(&fmt, 2024), "2024");
+        assert_eq!(format(&fmt, -1234567), "-1234567");
+    }
+
+    #[test]
+    fn sign_display() {
+        let cases = [(SignDisplay::Auto, ["5", "-3", "0"]),
+                     (SignDisplay::Always, ["+5", "-3", "+0"]),
+                     (SignDisplay::Never, ["5", "3", "0"]),
+                     (SignDisplay::ExceptZero, ["+5", "-3", "0"])];
+        for &(sign_display, expected) in &cases {
+            let mut fmt = NumberFormat::new("n");
+            fmt.sign_display(sign_display);
+            assert_eq!([format(&fmt, 5), format(&fmt, -3), format(&fmt, 0)],
+                       expected,
+                       "{:?}",
+                       sign_display);
+        }
+    }
+
+    #[test]
+    fn sign_display_of_decimals() {
+        let ctx = Context::default();
+        let m = parse("{n, number, ::sign-except-zero}").unwrap();
+        let cases = [(Value::Decimal { value: 0.25, fraction_digits: 2 }, "+0.25"),
+                     (Value::Decimal { value: 0.0, fraction_digits: 1 }, "0.0"),
+                     (Value::Decimal { value: -1234.5, fraction_digits: 1 }, "-1,234.5"),
+                     (decimal("0.00"), "0.00"),
+                     (decimal("-0.00"), "0.00"),
+                     (decimal("0.01"), "+0.01"),
+                     (decimal("1c3"), "+1,000")];
+        for &(value, expected) in &cases {
+            assert_eq!(ctx.format(&m, Some(&arg("n", value))), expected);
+        }
+    }
+
+    #[test]
+    fn sign_skeletons() {
+        let ctx = Context::default();
+        let m = parse("{a, number, ::sign-always} {b, number, ::sign-never group-off} \
+                       {c, number, ::sign-auto}")
+            .unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("a", 1234).arg("b", -1234).arg("c", -1))),
+                   "+1,234 1234 -1");
+    }
+
+    #[test]
+    fn group_off_skeleton() {
+        let ctx = Context::default();
+        let m = parse("© {year, number, ::group-off}, {count, number} downloads").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("year", 2024).arg("count", 2024))),
+                   "© 2024, 2,024 downloads");
+    }
+
+    #[test]
+    fn uses_number_symbols() {
+        let ctx = Context {
+            number_symbols: NumberSymbols {
+                zero_digit: '\u{660}',
+                decimal_separator: '\u{66b}',
+                grouping_separator: '\u{66c}',
+                minus_sign: '\u{2212}',
+            },
+            ..Context::default()
+        };
+        let m = parse("{n, number}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", 1234567))), "١٬٢٣٤٬٥٦٧");
+        assert_eq!(ctx.format(&m, Some(&arg("n", -5))), "\u{2212}٥");
+    }
+
+    #[test]
+    fn negative_parens() {
+        let mut fmt = NumberFormat::new("n");
+        fmt.negative_parens(true);
+        assert_eq!(format(&fmt, -1234), "(1,234)");
+        assert_eq!(format(&fmt, 1234), "1,234");
+        assert_eq!(format(&fmt, 0), "0");
+        fmt.sign_display(SignDisplay::Always);
+        assert_eq!([format(&fmt, 5), format(&fmt, -5)], ["+5", "(5)"]);
+        fmt.sign_display(SignDisplay::Never);
+        assert_eq!(format(&fmt, -5), "5");
+    }
+
+    #[test]
+    fn negative_zero_has_no_parens() {
+        let ctx = Context::default();
+        let m = parse("{n, number, ::negative-parens} {n, number, ::sign-accounting}").unwrap();
+        let cases = [(Value::Decimal { value: -0.001, fraction_digits: 2 }, "0.00 0.00"),
+                     (Value::Decimal { value: -0.0, fraction_digits: 0 }, "0 0"),
+                     (decimal("-0.0"), "0.0 0.0"),
+                     (decimal("-1234.50"), "(1,234.50) (1,234.50)")];
+        for &(value, expected) in &cases {
+            assert_eq!(ctx.format(&m, Some(&arg("n", value))), expected);
+        }
+    }
+
+    #[test]
+    fn parens_use_number_symbols() {
+        let ctx = Context {
+            number_symbols: NumberSymbols { minus_sign: '\u{2212}', ..NumberSymbols::default() },
+            ..Context::default()
+        };
+        let m = parse("{n, number} {n, number, ::negative-parens}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", -1234))), "\u{2212}1,234 (1,234)");
+    }
+
+    #[test]
+    fn scientific() {
+        let ctx = Context::default();
+        let m = parse("{n, number, scientific}").unwrap();
+        let cases = [(Value::Number(12345), "1.2345E4"),
+                     (Value::Number(-12345), "-1.2345E4"),
+                     (Value::Number(1000), "1E3"),
+                     (Value::Number(7), "7E0"),
+                     (Value::Number(0), "0E0"),
+                     (decimal("0.000123"), "1.23E-4"),
+                     (decimal("-0.00"), "-0E0"),
+                     (Value::Decimal { value: 0.5, fraction_digits: 2 }, "5E-1"),
+                     (Value::BigInt(-(1 << 100)), "-1.267650600228229401496703205376E30"),
+                     (decimal("1c3"), "1E3")];
+        for &(value, expected) in &cases {
+            assert_eq!(ctx.format(&m, Some(&arg("n", value))), expected);
+        }
+    }
+
+    #[test]
+    fn scientific_digits() {
+        let mut fmt = NumberFormat::new("n");
+        fmt.notation(Notation::Scientific {
+            exponent_digits: 2,
+            fraction_digits: Some(2),
+        });
+        assert_eq!(format(&fmt, 12345), "1.23E04");
+        assert_eq!(format(&fmt, 12355), "1.24E04");
+        assert_eq!(format(&fmt, 12250), "1.22E04");
+        assert_eq!(format(&fmt, 12251), "1.23E04");
+        assert_eq!(format(&fmt, 99999), "1.00E05");
+        assert_eq!(format(&fmt, 5), "5.00E00");
+        assert_eq!(format(&fmt, 0), "0.00E00");
+        assert_eq!(format(&fmt, -1234567890123), "-1.23E12");
+        fmt.notation(Notation::Scientific {
+            exponent_digits: 1,
+            fraction_digits: Some(0),
+        });
+        assert_eq!(format(&fmt, 96), "1E2");
+        assert_eq!(format(&fmt, 25), "2E1");
+    }
+
+    #[test]
+    fn scientific_skeletons() {
+        let ctx = Context {
+            number_symbols: NumberSymbols {
+                decimal_separator: ',',
+                minus_sign: '\u{2212}',
+                ..NumberSymbols::default()
+            },
+            ..Context::default()
+        };
+        let m = parse("{a, number, ::scientific .00} {b, number, ::E000} \
+                       {c, number, ::E00 .0 sign-always}")
+            .unwrap();
+        let args = arg("a", decimal("-0.00012345"));
+        let args = args.arg("b", 1234);
+        assert_eq!(ctx.format(&m, Some(&args.arg("c", 5))),
+                   "\u{2212}1,23E\u{2212}4 1,234E003 +5,0E00");
+    }
+
+    #[test]
+    fn maximum_fraction_digits() {
+        let cases = [("1234.5", 0, "1,234"),
+                     ("1235.5", 0, "1,236"),
+                     ("1234.51", 0, "1,235"),
+                     ("999.96", 1, "1,000.0"),
+                     ("0.125", 2, "0.12"),
+                     ("-0.4", 0, "-0"),
+                     ("2.5", 3, "2.5"),
+                     ("7", 0, "7")];
+        for &(source, digits, expected) in &cases {
+            let mut fmt = NumberFormat::new("n");
+            fmt.maximum_fraction_digits(Some(digits));
+            assert_eq!(fmt.format_ascii(source), expected, "{} {}", source, digits);
+        }
+    }
+
+    #[test]
+    fn percent() {
+        let mut fmt = NumberFormat::new("n");
+        fmt.percent(true);
+        fmt.negative_parens(true);
+        assert_eq!(fmt.format_ascii("0.25"), "25%");
+        assert_eq!(fmt.format_ascii("12.3456"), "1,234.56%");
+        assert_eq!(fmt.format_ascii("-0.5"), "(50%)");
+        assert_eq!(fmt.format_ascii("3"), "300%");
+    }
+
+    #[test]
+    fn named_presets() {
+        let ctx = Context::default();
+        let m = parse("{a, number, integer} {b, number, percent} {c, number, scientific}").unwrap();
+        let args = arg("a", Value::Decimal { value: 12345.678, fraction_digits: 3 });
+        let args = args.arg("b", decimal("0.4567"));
+        assert_eq!(ctx.format(&m, Some(&args.arg("c", 1200))), "12,346 46% 1.2E3");
+        let m = parse("{a, number, integer} {b, number, percent}").unwrap();
+        let args = arg("a", -2);
+        assert_eq!(ctx.format(&m, Some(&args.arg("b", 1))), "-2 100%");
+        for style in &["currency", "date", "Integer"] {
+            let source = format!("{{n, number, {}}}", style);
+            assert_eq!(parse(&source).unwrap_err().kind, ParseErrorKind::NotImplemented);
+        }
+    }
+
+    #[test]
+    fn padding() {
+        let mut fmt = NumberFormat::new("n");
+        fmt.padding(Some(Padding {
+            width: 4,
+            character: '0',
+            position: PadPosition::AfterSign,
+        }));
+        assert_eq!([format(&fmt, 5), format(&fmt, -5), format(&fmt, 12345)],
+                   ["0005", "-005", "12,345"]);
+        // Padding is added after grouping, so it isn't grouped.
+        fmt.padding(Some(Padding {
+            width: 8,
+            character: '0',
+            position: PadPosition::AfterSign,
+        }));
+        assert_eq!(format(&fmt, -1234), "-001,234");
+        fmt.negative_parens(true);
+        assert_eq!(format(&fmt, -5), "(000005)");
+        fmt.padding(Some(Padding {
+            width: 5,
+            character: ' ',
+            position: PadPosition::BeforeSign,
+        }));
+        assert_eq!([format(&fmt, 5), format(&fmt, -5), format(&fmt, 0)],
+                   ["    5", "  (5)", "    0"]);
+        fmt.sign_display(SignDisplay::Always);
+        fmt.percent(true);
+        assert_eq!(fmt.format_ascii("0.5"), " +50%");
+    }
+
+    #[test]
+    fn padding_skeletons() {
+        let ctx = Context {
+            number_symbols: NumberSymbols { zero_digit: '\u{660}', ..NumberSymbols::default() },
+            ..Context::default()
+        };
+        let m = parse("[{a, number, ::padding/3}] [{b, number, ::padding/03 group-off}] \
+                       [{c, number, ::padding/*6 sign-always}] [{d, number, ::padding/12}]")
+            .unwrap();
+        let args = arg("a", 5);
+        let args = args.arg("b", -7);
+        let args = args.arg("c", 12);
+        assert_eq!(ctx.format(&m, Some(&args.arg("d", 1))),
+                   "[  \u{665}] [-\u{660}\u{667}] [***+\u{661}\u{662}] [           \u{661}]");
+        for style in &["padding/", "padding/0", "padding/x", "padding/256", "padding/3x"] {
+            let source = format!("{{n, number, ::{}}}", style);
+            assert_eq!(parse(&source).unwrap_err().kind, ParseErrorKind::NotImplemented);
+        }
+    }
+}
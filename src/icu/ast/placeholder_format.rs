@@ -6,10 +6,10 @@
 
 use std::fmt;
 
-use {Args, Context, MessagePart};
+use {Args, Context, FormatError, MessagePart, MessageVisitor, Value};
 
 /// A placeholder for a value. Used by `PluralFormat`.
-#[derive(Debug)]
+#[derive(Debug,Default)]
 pub struct PlaceholderFormat {
 }
 
@@ -23,30 +23,51 @@ impl PlaceholderFormat {
 impl MessagePart for PlaceholderFormat {
     fn apply_format(&self,
                     ctx: &Context,
-                    stream: &mut fmt::Write,
+                    stream: &mut dyn fmt::Write,
                     _args: Option<&Args>)
-                    -> fmt::Result {
-        if let Some(value) = ctx.placeholder_value {
-            try!(write!(stream, "{}", value));
-            Ok(())
-        } else {
-            Err(fmt::Error {})
+                    -> Result<(), FormatError> {
+        if ctx.pseudolocalization.is_on() {
+            return Ok(stream.write_str("[#]")?);
         }
+        match ctx.placeholder {
+            Some(Value::Decimal { value, fraction_digits }) => {
+                ctx.number_symbols.write_decimal(stream, value, fraction_digits)?
+            }
+            Some(Value::Number(value)) => ctx.number_symbols.write_integer(stream, value)?,
+            Some(Value::BigInt(value)) => ctx.number_symbols.write_integer(stream, value)?,
+            _ => return Err(FormatError::MissingPlaceholderValue),
+        }
+        Ok(())
+    }
+
+    fn accept(&self, visitor: &mut dyn MessageVisitor) {
+        visitor.visit_placeholder();
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::PlaceholderFormat;
-    use {Context, MessagePart};
+    use {Context, MessagePart, Value};
 
     #[test]
     fn it_works() {
-        let ctx = Context { placeholder_value: Some(3), ..Context::default() };
+        let ctx = Context { placeholder: Some(Value::Number(3)), ..Context::default() };
         let fmt = PlaceholderFormat::new();
 
         let mut output = String::new();
         fmt.apply_format(&ctx, &mut output, None).unwrap();
         assert_eq!("3", output);
     }
+
+    #[test]
+    fn decimals() {
+        let value = Value::Decimal { value: 1.5, fraction_digits: 2 };
+        let ctx = Context { placeholder: Some(value), ..Context::default() };
+        let fmt = PlaceholderFormat::new();
+
+        let mut output = String::new();
+        fmt.apply_format(&ctx, &mut output, None).unwrap();
+        assert_eq!("1.50", output);
+    }
 }
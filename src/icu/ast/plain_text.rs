@@ -4,9 +4,11 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::boxed::Box;
 use std::fmt;
+use std::string::{String, ToString};
 
-use {Args, Context, MessagePart};
+use {Args, Context, FormatError, Message, MessagePart, MessageVisitor};
 
 /// A string that should be output. Used for the text in between
 /// formats.
@@ -25,13 +27,29 @@ impl PlainText {
 
 impl MessagePart for PlainText {
     fn apply_format(&self,
-                    _ctx: &Context,
-                    stream: &mut fmt::Write,
+                    ctx: &Context,
+                    stream: &mut dyn fmt::Write,
                     _args: Option<&Args>)
-                    -> fmt::Result {
-        try!(stream.write_str(self.text.as_str()));
+                    -> Result<(), FormatError> {
+        ctx.pseudolocalization.write_text(stream, &self.text)?;
         Ok(())
     }
+
+    fn accept(&self, visitor: &mut dyn MessageVisitor) {
+        visitor.visit_text(&self.text);
+    }
+
+    fn static_text(&self) -> Option<&str> {
+        Some(&self.text)
+    }
+
+    fn partial(&self, _ctx: &Context, _args: &Args) -> Option<Message> {
+        None
+    }
+
+    fn map_text(&self, transform: &dyn Fn(&str) -> String) -> Option<Message> {
+        Some(Message::new(vec![Box::new(PlainText::new(&transform(&self.text)))]))
+    }
 }
 
 #[cfg(test)]
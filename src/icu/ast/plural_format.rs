@@ -4,47 +4,153 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use std::collections::HashMap;
+use std::boxed::Box;
+use std::collections::BTreeMap;
+use std::error::Error;
 use std::fmt;
+use std::string::{String, ToString};
+use std::sync::Arc;
+use std::vec::Vec;
 
-use english_cardinal_classifier;
-use {Args, Context, Message, MessagePart, PluralCategory, Value};
+#[cfg(feature = "intl-pluralrules")]
+use plural_rules::IntlPluralRules;
+use plural_rules::{cardinal_category, cardinal_rules, ordinal_category, ordinal_rules};
+use {Args, AsValue, Context, FormatError, Locale, Message, MessagePart, MessageVisitor,
+     PluralCategory, PluralClassifier, PluralOperands, Value};
+
+/// Which branch of a [`PluralFormat`] is used for a value.
+///
+/// [`PluralFormat`]: struct.PluralFormat.html
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum PluralBranch {
+    /// The branch for an `=` literal.
+    Literal(i64),
+    /// The branch for a plural category, including `other`.
+    Category(PluralCategory),
+    /// The `other` branch, as there is no branch for the category that
+    /// the value is in.
+    OtherFallback(PluralCategory),
+}
+
+/// Why a [`PluralFormat`] uses a branch for a value, as found by
+/// [`PluralFormat::explain`].
+///
+/// [`PluralFormat`]: struct.PluralFormat.html
+/// [`PluralFormat::explain`]: struct.PluralFormat.html#method.explain
+#[derive(Clone,Debug,PartialEq)]
+pub struct PluralExplanation {
+    /// The branch that is used.
+    pub branch: PluralBranch,
+    /// The value after the `offset` and `modulo` have been applied,
+    /// which is output for a `#`.
+    pub value: Value<'static>,
+    /// The plural operands of the adjusted value.
+    pub operands: PluralOperands,
+    /// The condition in the CLDR rule syntax for the category of the
+    /// value, if it was classified and the classifier knows it. There
+    /// is never a condition for `other`, as it is used when no other
+    /// condition matches.
+    pub condition: Option<String>,
+}
 
 /// Format a value taking pluralization rules into account.
-#[derive(Debug)]
+///
+/// The plural category is determined by the cardinal or ordinal
+/// [CLDR rules] for the locale in the [`Context`], unless a classifier
+/// has been set. A classifier set on the `Context` is used rather than
+/// either of them, so that one message can be classified differently
+/// for each request.
+///
+/// The messages for each branch are reference counted, so branches
+/// with the same message can share it by using the `_shared` setters.
+///
+/// [CLDR rules]: ../../plural_rules/index.html
+/// [`Context`]: ../../struct.Context.html
 pub struct PluralFormat {
     /// The name of the variable whose value should be formatted.
     variable_name: String,
-    classifier: fn(i64) -> PluralCategory,
-    literals: HashMap<i64, Message>,
+    classifier: Option<Arc<dyn PluralClassifier>>,
+    ordinal: bool,
+    literals: BTreeMap<i64, Arc<Message>>,
     offset: i64,
-    zero: Option<Message>,
-    one: Option<Message>,
-    two: Option<Message>,
-    few: Option<Message>,
-    many: Option<Message>,
-    other: Message,
+    modulo: Option<i64>,
+    zero: Option<Arc<Message>>,
+    one: Option<Arc<Message>>,
+    two: Option<Arc<Message>>,
+    few: Option<Arc<Message>>,
+    many: Option<Arc<Message>>,
+    other: Arc<Message>,
 }
 
 impl PluralFormat {
     /// Construct a `PluralFormat`.
     pub fn new(variable_name: &str, other: Message) -> Self {
+        PluralFormat::new_shared(variable_name, Arc::new(other))
+    }
+
+    /// Construct a `PluralFormat` with a shared `other` message.
+    pub fn new_shared(variable_name: &str, other: Arc<Message>) -> Self {
         PluralFormat {
             variable_name: variable_name.to_string(),
-            classifier: english_cardinal_classifier,
-            literals: HashMap::new(),
+            classifier: None,
+            ordinal: false,
+            literals: BTreeMap::new(),
             offset: 0,
+            modulo: None,
             zero: None,
             one: None,
             two: None,
             few: None,
             many: None,
-            other: other,
+            other,
         }
     }
 
+    /// Construct a `PluralFormat` using the [`IntlPluralRules`] for a
+    /// `locale`, rather than the locale in the `Context`. These are the
+    /// ordinal rules if `ordinal` is set, as for a `selectordinal`.
+    ///
+    /// `None` is returned if there are no rules for the locale. This is
+    /// only available with the `intl-pluralrules` feature.
+    ///
+    /// [`IntlPluralRules`]: ../../plural_rules/struct.IntlPluralRules.html
+    #[cfg(feature = "intl-pluralrules")]
+    pub fn with_locale(variable_name: &str,
+                       other: Message,
+                       locale: &str,
+                       ordinal: bool)
+                       -> Option<Self> {
+        let classifier = if ordinal {
+            IntlPluralRules::ordinal(locale)?
+        } else {
+            IntlPluralRules::cardinal(locale)?
+        };
+        let mut format = PluralFormat::new(variable_name, other);
+        format.classifier(classifier);
+        format.ordinal(ordinal);
+        Some(format)
+    }
+
+    /// Use `classifier` to determine plural categories, rather than
+    /// the rules for the locale in the `Context`.
+    pub fn classifier<C: PluralClassifier + 'static>(&mut self, classifier: C) {
+        self.classifier = Some(Arc::new(classifier));
+    }
+
+    /// Use the ordinal plural rules for the locale, as for a
+    /// `selectordinal`, rather than the cardinal rules. This has no
+    /// effect if a classifier has been set.
+    pub fn ordinal(&mut self, ordinal: bool) {
+        self.ordinal = ordinal;
+    }
+
     /// Set the `message` to be used for a literal value.
     pub fn literal(&mut self, literal: i64, message: Message) {
+        self.literal_shared(literal, Arc::new(message));
+    }
+
+    /// Set a shared `message` to be used for a literal value.
+    pub fn literal_shared(&mut self, literal: i64, message: Arc<Message>) {
         self.literals.insert(literal, message);
     }
 
@@ -53,73 +159,650 @@ impl PluralFormat {
         self.offset = offset;
     }
 
+    /// The offset that is subtracted from a value before a message is
+    /// selected, which is 0 if none has been set.
+    pub fn offset_value(&self) -> i64 {
+        self.offset
+    }
+
+    /// The messages for the `=` literals, in order of their values.
+    /// Literals are matched after the offset has been applied.
+    pub fn literals(&self) -> &BTreeMap<i64, Arc<Message>> {
+        &self.literals
+    }
+
+    /// Reduce the value modulo `base` before selecting a message.
+    ///
+    /// This is applied after the `offset`, and the reduced value is used
+    /// both for matching literals and by the plural classifier, as well as
+    /// being the value that is output for a `PlaceholderFormat`. The result
+    /// is always in the range `0..base`, even for negative values.
+    ///
+    /// A `base` that isn't positive is an error.
+    pub fn modulo(&mut self, base: i64) -> Result<(), ModuloError> {
+        if base <= 0 {
+            return Err(ModuloError(base));
+        }
+        self.modulo = Some(base);
+        Ok(())
+    }
+
     /// Set the `message` for `PluralCategory::Zero`.
     pub fn zero(&mut self, message: Message) {
+        self.zero_shared(Arc::new(message));
+    }
+
+    /// Set a shared `message` for `PluralCategory::Zero`.
+    pub fn zero_shared(&mut self, message: Arc<Message>) {
         self.zero = Some(message);
     }
 
     /// Set the `message` for `PluralCategory::One`.
     pub fn one(&mut self, message: Message) {
+        self.one_shared(Arc::new(message));
+    }
+
+    /// Set a shared `message` for `PluralCategory::One`.
+    pub fn one_shared(&mut self, message: Arc<Message>) {
         self.one = Some(message);
     }
 
     /// Set the `message` for `PluralCategory::Two`.
     pub fn two(&mut self, message: Message) {
+        self.two_shared(Arc::new(message));
+    }
+
+    /// Set a shared `message` for `PluralCategory::Two`.
+    pub fn two_shared(&mut self, message: Arc<Message>) {
         self.two = Some(message);
     }
 
     /// Set the `message` for `PluralCategory::Few`.
     pub fn few(&mut self, message: Message) {
+        self.few_shared(Arc::new(message));
+    }
+
+    /// Set a shared `message` for `PluralCategory::Few`.
+    pub fn few_shared(&mut self, message: Arc<Message>) {
         self.few = Some(message);
     }
 
     /// Set the `message` for `PluralCategory::Many`.
     pub fn many(&mut self, message: Message) {
+        self.many_shared(Arc::new(message));
+    }
+
+    /// Set a shared `message` for `PluralCategory::Many`.
+    pub fn many_shared(&mut self, message: Arc<Message>) {
         self.many = Some(message);
     }
 
-    /// Given a value adjusted by the `offset`, determine which `Message` to use.
-    fn lookup_message(&self, offset_value: i64) -> &Message {
-        if let Some(literal) = self.literals.get(&offset_value) {
-            literal
+    /// Format the message for a `category` that has already been
+    /// determined, such as by an external plural rules engine. The
+    /// classifier and literals are not used.
+    ///
+    /// If the variable has a numeric value, it is still adjusted by
+    /// the `offset` and output for a `PlaceholderFormat`. If there is
+    /// no branch for the category and the `ctx` is set to fail on
+    /// fallbacks, the error has the category as its value.
+    pub fn apply_category<'f>(&self,
+                              category: PluralCategory,
+                              ctx: &Context,
+                              stream: &mut dyn fmt::Write,
+                              args: Option<&Args<'f>>)
+                              -> Result<(), FormatError> {
+        let message = self.category_message(ctx, category)
+            .ok_or_else(|| self.fallback_error(category.to_string()))?;
+        let arg = ctx.find_arg(args, &self.variable_name);
+        if let Some((value, _)) = arg.and_then(|arg| self.adjust(arg.value()).ok()) {
+            let ctx = Context { placeholder: Some(value), ..ctx.clone() };
+            message.write_nested(&ctx, 1, stream, args)
+        } else {
+            message.write_nested(ctx, 1, stream, args)
+        }
+    }
+
+    /// Substitute the arguments that are known in `args` within each
+    /// branch, keeping the variable and how a branch is selected.
+    fn partial_branches(&self, ctx: &Context, args: &Args) -> PluralFormat {
+        self.map_branches(&|message| message.partial_parts(ctx, args))
+    }
+
+    /// Replace each branch with the result of `map`, keeping the
+    /// variable and how a branch is selected.
+    fn map_branches(&self, map: &dyn Fn(&Message) -> Message) -> PluralFormat {
+        let map = |message: &Arc<Message>| Arc::new(map(message));
+        PluralFormat {
+            variable_name: self.variable_name.clone(),
+            classifier: self.classifier.clone(),
+            ordinal: self.ordinal,
+            literals: self.literals
+                .iter()
+                .map(|(&literal, message)| (literal, map(message)))
+                .collect(),
+            offset: self.offset,
+            modulo: self.modulo,
+            zero: self.zero.as_ref().map(map),
+            one: self.one.as_ref().map(map),
+            two: self.two.as_ref().map(map),
+            few: self.few.as_ref().map(map),
+            many: self.many.as_ref().map(map),
+            other: map(&self.other),
+        }
+    }
+
+    /// Adjust a numeric value by the `offset` and `modulo`, returning it
+    /// with its plural operands.
+    ///
+    /// Integers are adjusted exactly, so an `i64` can become a
+    /// `Value::BigInt`, and a `Value::BigInt` that would overflow is a
+    /// `FormatError::ValueOutOfRange`.
+    fn adjust(&self, value: &Value) -> Result<(Value<'static>, PluralOperands), FormatError> {
+        let value = value.resolve();
+        let integer = match value {
+            Value::Number(value) => Some(i128::from(value)),
+            Value::BigInt(value) => Some(value),
+            _ => None,
+        };
+        if let Some(value) = integer {
+            let out_of_range = || FormatError::ValueOutOfRange(self.variable_name.clone());
+            let offset_value = value.checked_sub(i128::from(self.offset)).ok_or_else(out_of_range)?;
+            let value = match self.modulo {
+                Some(base) => offset_value.rem_euclid(i128::from(base)),
+                None => offset_value,
+            };
+            return Ok((value.as_formattable(), PluralOperands::from(value)));
+        }
+        let invalid_type = || FormatError::InvalidArgumentType(self.variable_name.clone());
+        let (value, fraction_digits) = match value {
+            Value::Decimal { value, fraction_digits } => (value, fraction_digits),
+            Value::DecimalStr(decimal) => {
+                let operands = decimal.operands();
+                let value = decimal.value();
+                let fraction_digits = operands.v.min(u8::MAX as usize) as u8;
+                if self.offset == 0 && self.modulo.is_none() {
+                    // Keep the operands, as they include the exponent.
+                    return Ok((Value::Decimal { value, fraction_digits }, operands));
+                }
+                (value, fraction_digits)
+            }
+            _ => return Err(invalid_type()),
+        };
+        let offset_value = value - self.offset as f64;
+        let value = match self.modulo {
+            // As `f64::rem_euclid`, which is only in `std`.
+            Some(base) => {
+                let rem = offset_value % base as f64;
+                if rem < 0.0 { rem + base as f64 } else { rem }
+            }
+            None => offset_value,
+        };
+        let operands = PluralOperands::from_decimal(value, fraction_digits as usize);
+        Ok((Value::Decimal { value, fraction_digits }, operands))
+    }
+
+    /// Explain which branch is used for a `value` and why. This is
+    /// `None` if the value isn't numeric or is out of range.
+    ///
+    /// The `ctx` is used for its locale, as when formatting.
+    ///
+    /// ```
+    /// use message_format::{icu, Context, PluralCategory, Value};
+    /// use message_format::icu::ast::{PluralBranch, PluralFormat};
+    ///
+    /// let mut fmt = PluralFormat::new("n", icu::parse("# files").unwrap());
+    /// fmt.one(icu::parse("# file").unwrap());
+    /// let explanation = fmt.explain(&Context::default(), &Value::Number(1)).unwrap();
+    /// assert_eq!(explanation.branch, PluralBranch::Category(PluralCategory::One));
+    /// assert_eq!(explanation.condition.as_deref(), Some("i = 1 and v = 0"));
+    /// ```
+    pub fn explain(&self, ctx: &Context, value: &Value) -> Option<PluralExplanation> {
+        let (value, operands) = self.adjust(value).ok()?;
+        if let Some(literal) = self.matching_literal(&value) {
+            return Some(PluralExplanation {
+                branch: PluralBranch::Literal(literal),
+                value,
+                operands,
+                condition: None,
+            });
+        }
+        let category = self.classify(ctx, &operands);
+        let condition = match self.active_classifier(ctx) {
+            Some(classifier) => classifier.condition(category).map(str::to_string),
+            None => {
+                let rules = if self.ordinal {
+                    ordinal_rules(ctx.plural_locale())
+                } else {
+                    cardinal_rules(ctx.plural_locale())
+                };
+                rules.and_then(|rules| rules.condition(category).map(str::to_string))
+            }
+        };
+        let branch = if self.category_branch(category).is_some() {
+            PluralBranch::Category(category)
+        } else {
+            PluralBranch::OtherFallback(category)
+        };
+        Some(PluralExplanation {
+            branch,
+            value,
+            operands,
+            condition,
+        })
+    }
+
+    /// The plural categories that have a branch, in the order of
+    /// `PluralCategory::ALL`. This always includes `Other`, but not the
+    /// `=` literals.
+    pub fn categories_defined(&self) -> Vec<PluralCategory> {
+        PluralCategory::ALL
+            .iter()
+            .cloned()
+            .filter(|&category| self.category_branch(category).is_some())
+            .collect()
+    }
+
+    /// The plural categories that the built-in cardinal or ordinal rules
+    /// for `locale` select, in the order of `PluralCategory::ALL`. This
+    /// always includes `Other`, and any classifier that has been set is
+    /// not used.
+    ///
+    /// Together with [`categories_defined`], this finds the categories
+    /// that a translation is missing branches for:
+    ///
+    /// ```
+    /// use message_format::{Locale, Message, PluralCategory};
+    /// use message_format::icu::ast::PluralFormat;
+    ///
+    /// let mut fmt = PluralFormat::new("n", Message::new(vec![]));
+    /// fmt.one(Message::new(vec![]));
+    /// let defined = fmt.categories_defined();
+    /// let missing = fmt.categories_used(&Locale::new("ru").unwrap())
+    ///     .into_iter()
+    ///     .filter(|category| !defined.contains(category))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(missing, [PluralCategory::Few, PluralCategory::Many]);
+    /// ```
+    ///
+    /// [`categories_defined`]: #method.categories_defined
+    pub fn categories_used(&self, locale: &Locale) -> Vec<PluralCategory> {
+        let rules = if self.ordinal {
+            ordinal_rules(locale)
         } else {
-            let category = (self.classifier)(offset_value);
-            match category {
-                PluralCategory::Zero => self.zero.as_ref().unwrap_or(&self.other),
-                PluralCategory::One => self.one.as_ref().unwrap_or(&self.other),
-                PluralCategory::Two => self.two.as_ref().unwrap_or(&self.other),
-                PluralCategory::Few => self.few.as_ref().unwrap_or(&self.other),
-                PluralCategory::Many => self.many.as_ref().unwrap_or(&self.other),
-                PluralCategory::Other => &self.other,
+            cardinal_rules(locale)
+        };
+        PluralCategory::ALL
+            .iter()
+            .cloned()
+            .filter(|&category| {
+                category == PluralCategory::Other ||
+                rules.is_some_and(|rules| rules.condition(category).is_some())
+            })
+            .collect()
+    }
+
+    /// The literal that matches a value adjusted by the `offset`, if
+    /// there is a branch for it. Literals match decimals with the same
+    /// numeric value.
+    fn matching_literal(&self, offset_value: &Value) -> Option<i64> {
+        let literal = match *offset_value {
+            Value::Number(value) => Some(value),
+            Value::Decimal { value, .. } if value % 1.0 == 0.0 => Some(value as i64),
+            _ => None,
+        };
+        literal.filter(|literal| self.literals.contains_key(literal))
+    }
+
+    /// The classifier of the `ctx` for this kind of plural, or else the
+    /// one that has been set, if there is one.
+    fn active_classifier<'c>(&'c self, ctx: &'c Context) -> Option<&'c dyn PluralClassifier> {
+        ctx.plural_classifier(self.ordinal).or(self.classifier.as_deref())
+    }
+
+    /// Determine the plural category of the operands of a value.
+    fn classify(&self, ctx: &Context, operands: &PluralOperands) -> PluralCategory {
+        match self.active_classifier(ctx) {
+            Some(classifier) => classifier.classify(operands),
+            None if self.ordinal => ordinal_category(ctx.plural_locale(), operands),
+            None => cardinal_category(ctx.plural_locale(), operands),
+        }
+    }
+
+    /// Given a value adjusted by the `offset` and its operands, determine
+    /// which `Message` to use. This is `None` if there is no branch for
+    /// the value and the `ctx` is set to fail on fallbacks.
+    fn lookup_message(&self,
+                      ctx: &Context,
+                      offset_value: &Value,
+                      operands: &PluralOperands)
+                      -> Option<&Message> {
+        match self.matching_literal(offset_value) {
+            Some(literal) => Some(&self.literals[&literal]),
+            None => self.category_message(ctx, self.classify(ctx, operands)),
+        }
+    }
+
+    /// The branch for a plural category, if there is one.
+    fn category_branch(&self, category: PluralCategory) -> Option<&Message> {
+        match category {
+            PluralCategory::Zero => self.zero.as_deref(),
+            PluralCategory::One => self.one.as_deref(),
+            PluralCategory::Two => self.two.as_deref(),
+            PluralCategory::Few => self.few.as_deref(),
+            PluralCategory::Many => self.many.as_deref(),
+            PluralCategory::Other => Some(&*self.other),
+        }
+    }
+
+    /// Determine which `Message` to use for a plural category, falling
+    /// back to `other` if there isn't one for the category, unless the
+    /// `ctx` is set to fail on fallbacks.
+    fn category_message(&self, ctx: &Context, category: PluralCategory) -> Option<&Message> {
+        match self.category_branch(category) {
+            Some(message) => Some(message),
+            None => {
+                ctx.format_observer().on_plural_fallback(&self.variable_name, category);
+                if ctx.strict_fallbacks { None } else { Some(&self.other) }
             }
         }
     }
+
+    fn fallback_error(&self, value: String) -> FormatError {
+        FormatError::UnexpectedFallback {
+            variable: self.variable_name.clone(),
+            value,
+        }
+    }
+}
+
+/// An error from [`PluralFormat::modulo`] with a base that isn't
+/// positive.
+///
+/// [`PluralFormat::modulo`]: struct.PluralFormat.html#method.modulo
+#[derive(Clone,Debug,PartialEq)]
+pub struct ModuloError(pub i64);
+
+impl Error for ModuloError {}
+
+impl fmt::Display for ModuloError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "The modulo base {} isn't positive.", self.0)
+    }
+}
+
+impl fmt::Debug for PluralFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PluralFormat")
+            .field("variable_name", &self.variable_name)
+            .field("classifier", &self.classifier.as_ref().map(|_| "PluralClassifier"))
+            .field("ordinal", &self.ordinal)
+            .field("literals", &self.literals)
+            .field("offset", &self.offset)
+            .field("modulo", &self.modulo)
+            .field("zero", &self.zero)
+            .field("one", &self.one)
+            .field("two", &self.two)
+            .field("few", &self.few)
+            .field("many", &self.many)
+            .field("other", &self.other)
+            .finish()
+    }
 }
 
 impl MessagePart for PluralFormat {
     fn apply_format<'f>(&self,
                         ctx: &Context,
-                        stream: &mut fmt::Write,
+                        stream: &mut dyn fmt::Write,
                         args: Option<&Args<'f>>)
-                        -> fmt::Result {
-        let arg = args.and_then(|args| args.get(&self.variable_name));
-        if let Some(&Value::Number(value)) = arg.map(|a| a.value()) {
-            let offset_value = value - self.offset;
-            let message = self.lookup_message(offset_value);
-            let ctx = Context { placeholder_value: Some(offset_value), ..ctx.clone() };
-            try!(message.write_message(&ctx, stream, args));
-            Ok(())
+                        -> Result<(), FormatError> {
+        self.apply_format_nested(ctx, 0, stream, args)
+    }
+
+    fn apply_format_nested<'f>(&self,
+                               ctx: &Context,
+                               depth: usize,
+                               stream: &mut dyn fmt::Write,
+                               args: Option<&Args<'f>>)
+                               -> Result<(), FormatError> {
+        let arg = ctx.lookup_arg(args, &self.variable_name)?;
+        let (offset_value, operands) = self.adjust(arg.value())?;
+        let message = self.lookup_message(ctx, &offset_value, &operands)
+            .ok_or_else(|| self.fallback_error(arg.value().to_string()))?;
+        let ctx = Context { placeholder: Some(offset_value), ..ctx.clone() };
+        message.write_nested(&ctx, depth + 1, stream, args)?;
+        Ok(())
+    }
+
+    fn partial(&self, ctx: &Context, args: &Args) -> Option<Message> {
+        match args.get(&self.variable_name) {
+            Some(arg) => {
+                let (offset_value, operands) = self.adjust(arg.value()).ok()?;
+                // A fallback that is an error is left to be reported
+                // when the result is formatted.
+                let message = self.lookup_message(ctx, &offset_value, &operands)?;
+                let ctx = Context { placeholder: Some(offset_value), ..ctx.clone() };
+                Some(message.partial_parts(&ctx, args))
+            }
+            None => {
+                // A `#` within the branches is for this plural, so it
+                // isn't known yet.
+                let ctx = Context { placeholder: None, ..ctx.clone() };
+                Some(Message::new(vec![Box::new(self.partial_branches(&ctx, args))]))
+            }
+        }
+    }
+
+    fn map_text(&self, transform: &dyn Fn(&str) -> String) -> Option<Message> {
+        let fmt = self.map_branches(&|message| message.map_text(transform));
+        Some(Message::new(vec![Box::new(fmt)]))
+    }
+
+    fn accept(&self, visitor: &mut dyn MessageVisitor) {
+        let literals = self.literals.keys().cloned().collect::<Vec<_>>();
+        let branches = [(PluralCategory::Zero, &self.zero),
+                        (PluralCategory::One, &self.one),
+                        (PluralCategory::Two, &self.two),
+                        (PluralCategory::Few, &self.few),
+                        (PluralCategory::Many, &self.many)];
+        let mut categories = branches.iter()
+            .filter(|&&(_, message)| message.is_some())
+            .map(|&(category, _)| category)
+            .collect::<Vec<_>>();
+        categories.push(PluralCategory::Other);
+        if self.ordinal {
+            visitor.visit_selectordinal(&self.variable_name, &categories, &literals);
         } else {
-            Err(fmt::Error {})
+            visitor.visit_plural(&self.variable_name, &categories, &literals);
+        }
+        let literals = literals.iter().map(|literal| &*self.literals[literal]);
+        let categories = branches.iter().filter_map(|&(_, message)| message.as_deref());
+        for message in literals.chain(categories).chain(Some(&*self.other)) {
+            visitor.enter_branch();
+            message.accept(visitor);
+            visitor.leave_branch();
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use icu::parse;
-    use super::PluralFormat;
-    use {arg, Context, MessagePart};
+    use super::{ModuloError, PluralBranch, PluralFormat};
+    use plural_rules::PluralRules;
+    use icu::ast::{PlaceholderFormat, PlainText};
+    use {arg, Context, DecimalStr, FormatError, Locale, Message, MessagePart, NumberSymbols,
+         PluralCategory, PluralOperands, Value};
+
+    fn decimal(source: &str) -> Value<'_> {
+        Value::DecimalStr(DecimalStr::new(source).unwrap())
+    }
+
+    #[test]
+    fn big_integers() {
+        let ctx = Context::default();
+        let m = parse("{n, plural, =0 {none} one {# item} other {# items}}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", u64::MAX))), "18446744073709551615 items");
+        assert_eq!(ctx.format(&m, Some(&arg("n", i128::MIN))),
+                   "-170141183460469231731687303715884105728 items");
+
+        // Plural rules use the last digits, as for `many` in French.
+        let fr = Context::new(Locale::new("fr").unwrap(), None);
+        let m = parse("{n, plural, one {# un} many {# beaucoup} other {# autre}}").unwrap();
+        assert_eq!(fr.format(&m, Some(&arg("n", 10u64.pow(19)))), "10000000000000000000 beaucoup");
+        assert_eq!(fr.format(&m, Some(&arg("n", 10u64.pow(19) + 1))),
+                   "10000000000000000001 autre");
+        let ru = Context::new(Locale::new("ru").unwrap(), None);
+        let m = parse("{n, plural, one {one} few {few} many {many} other {other}}").unwrap();
+        assert_eq!(ru.format(&m, Some(&arg("n", u64::MAX - 14))), "one");
+        assert_eq!(ru.format(&m, Some(&arg("n", u64::MAX - 13))), "few");
+        assert_eq!(ru.format(&m, Some(&arg("n", u64::MAX))), "many");
+    }
+
+    #[test]
+    fn big_integer_offsets() {
+        let ctx = Context::default();
+        let m = parse("{n, plural, offset:2 =0 {none} other {#}}").unwrap();
+        // An offset that takes a value into or out of an `i64` is exact.
+        assert_eq!(ctx.format(&m, Some(&arg("n", i64::MIN))), "-9223372036854775810");
+        let n = Value::BigInt(i64::MAX as i128 + 2);
+        assert_eq!(ctx.format(&m, Some(&arg("n", n))), i64::MAX.to_string());
+        // Literals match once the offset has been applied.
+        assert_eq!(ctx.format(&m, Some(&arg("n", Value::BigInt(2)))), "none");
+
+        let mut output = String::new();
+        assert_eq!(m.write_message(&ctx, &mut output, Some(&arg("n", i128::MIN))),
+                   Err(FormatError::ValueOutOfRange("n".to_string())));
+    }
+
+    #[test]
+    fn categories_defined() {
+        let mut fmt = PluralFormat::new("n", Message::new(vec![]));
+        fmt.one(Message::new(vec![]));
+        fmt.literal(0, Message::new(vec![]));
+        assert_eq!(fmt.categories_defined(), [PluralCategory::One, PluralCategory::Other]);
+
+        let en = Locale::new("en").unwrap();
+        assert_eq!(fmt.categories_used(&en), [PluralCategory::One, PluralCategory::Other]);
+        assert_eq!(fmt.categories_used(&Locale::new("ja").unwrap()), [PluralCategory::Other]);
+        fmt.ordinal(true);
+        assert_eq!(fmt.categories_used(&en),
+                   [PluralCategory::One, PluralCategory::Two, PluralCategory::Few,
+                    PluralCategory::Other]);
+    }
+
+    #[test]
+    fn debug_output_is_stable() {
+        let source = "{n, plural, =5 {five} =-1 {minus one} =0 {zero} =10 {ten} other {#}}";
+        let debug = format!("{:?}", parse(source).unwrap());
+        for _ in 0..10 {
+            assert_eq!(format!("{:?}", parse(source).unwrap()), debug);
+        }
+        let literals = debug.find("-1: ").unwrap();
+        assert!(literals < debug.find("0: ").unwrap());
+        assert!(debug.find("5: ").unwrap() < debug.find("10: ").unwrap());
+    }
+
+    #[test]
+    fn explain_with_offset() {
+        let mut fmt = PluralFormat::new("n", parse("others").unwrap());
+        fmt.offset(1);
+        fmt.literal(0, parse("nobody else").unwrap());
+        fmt.one(parse("one other").unwrap());
+        fmt.few(parse("a few others").unwrap());
+        let ctx = Context::default();
+        let explain = |value: i64| fmt.explain(&ctx, &Value::Number(value)).unwrap();
+
+        // The literal is matched after the offset.
+        let explanation = explain(1);
+        assert_eq!(explanation.branch, PluralBranch::Literal(0));
+        assert_eq!(explanation.value, Value::Number(0));
+        assert_eq!(explanation.operands, PluralOperands::from(0));
+        assert_eq!(explanation.condition, None);
+
+        let explanation = explain(2);
+        assert_eq!(explanation.branch, PluralBranch::Category(PluralCategory::One));
+        assert_eq!(explanation.value, Value::Number(1));
+        assert_eq!(explanation.condition.as_deref(), Some("i = 1 and v = 0"));
+
+        // English has no `few`, so these are `other`.
+        for &(value, adjusted) in &[(3, 2), (11, 10)] {
+            let explanation = explain(value);
+            assert_eq!(explanation.branch, PluralBranch::Category(PluralCategory::Other));
+            assert_eq!(explanation.value, Value::Number(adjusted));
+            assert_eq!(explanation.operands, PluralOperands::from(adjusted));
+            assert_eq!(explanation.condition, None);
+        }
+
+        // Russian has `few` but this has no `many` branch.
+        let ctx = Context::new(Locale::new("ru").unwrap(), None);
+        let explanation = fmt.explain(&ctx, &Value::Number(4)).unwrap();
+        assert_eq!(explanation.branch, PluralBranch::Category(PluralCategory::Few));
+        assert_eq!(explanation.condition.as_deref(),
+                   Some("v = 0 and i % 10 = 2..4 and i % 100 != 12..14"));
+        let explanation = fmt.explain(&ctx, &Value::Number(6)).unwrap();
+        assert_eq!(explanation.branch, PluralBranch::OtherFallback(PluralCategory::Many));
+        assert_eq!(explanation.value, Value::Number(5));
+        assert!(explanation.condition.unwrap().starts_with("v = 0 and i % 10 = 0"));
+
+        assert_eq!(fmt.explain(&ctx, &Value::Str("x")), None);
+    }
+
+    #[test]
+    fn explain_with_classifier() {
+        fn always_few(_: &PluralOperands) -> PluralCategory {
+            PluralCategory::Few
+        }
+        let ctx = Context::default();
+        let mut fmt = PluralFormat::new("n", parse("other").unwrap());
+        fmt.classifier(always_few as fn(&PluralOperands) -> PluralCategory);
+        let explanation = fmt.explain(&ctx, &Value::Number(1)).unwrap();
+        assert_eq!(explanation.branch, PluralBranch::OtherFallback(PluralCategory::Few));
+        assert_eq!(explanation.condition, None);
+
+        let rules = PluralRules::new(&[(PluralCategory::Few, "n = 1..3")]).unwrap();
+        fmt.classifier(rules);
+        let explanation = fmt.explain(&ctx, &Value::Number(2)).unwrap();
+        assert_eq!(explanation.condition.as_deref(), Some("n = 1..3"));
+    }
+
+    #[test]
+    fn context_classifier() {
+        fn always_few(_: &PluralOperands) -> PluralCategory {
+            PluralCategory::Few
+        }
+        fn format(fmt: &PluralFormat, ctx: &Context, value: i64) -> String {
+            let mut output = String::new();
+            fmt.apply_format(ctx, &mut output, Some(&arg("n", value))).unwrap();
+            output
+        }
+        let mut fmt = PluralFormat::new("n", parse("other").unwrap());
+        fmt.one(parse("one").unwrap());
+        fmt.few(parse("few").unwrap());
+        fmt.literal(7, parse("seven").unwrap());
+        let ru = PluralRules::new(&[(PluralCategory::One, "n = 1"),
+                                    (PluralCategory::Few, "n = 2..4")])
+            .unwrap();
+        let en = Context::default();
+        let ru = Context::builder().plural_classifier(ru).build().unwrap();
+        assert_eq!([1, 3, 7].map(|value| format(&fmt, &en, value)), ["one", "other", "seven"]);
+        assert_eq!([1, 3, 7].map(|value| format(&fmt, &ru, value)), ["one", "few", "seven"]);
+        let explanation = fmt.explain(&ru, &Value::Number(3)).unwrap();
+        assert_eq!(explanation.condition.as_deref(), Some("n = 2..4"));
+
+        // The classifier of the context is used rather than the one that
+        // has been set, but only for the same kind of plural.
+        fmt.classifier(always_few as fn(&PluralOperands) -> PluralCategory);
+        assert_eq!([format(&fmt, &en, 1), format(&fmt, &ru, 1)], ["few", "one"]);
+        fmt.ordinal(true);
+        assert_eq!(format(&fmt, &ru, 1), "few");
+        let ordinal = Context::builder()
+            .ordinal_classifier(|_: &PluralOperands| PluralCategory::One)
+            .build()
+            .unwrap();
+        assert_eq!(format(&fmt, &ordinal, 3), "one");
+    }
 
     #[test]
     fn it_works() {
@@ -139,4 +822,248 @@ mod tests {
         fmt.apply_format(&ctx, &mut output, Some(&arg("count", 3))).unwrap();
         assert_eq!("Other", output);
     }
+
+    #[test]
+    fn placeholder_uses_number_symbols() {
+        let ctx = Context {
+            number_symbols: NumberSymbols { zero_digit: '\u{660}', ..NumberSymbols::default() },
+            ..Context::default()
+        };
+        let m = parse("{n, plural, other {# ملف}}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", 5))), "٥ ملف");
+    }
+
+    /// A message like `# text`, as it would be within a plural branch.
+    fn placeholder_message(text: &str) -> Message {
+        Message::new(vec![Box::new(PlaceholderFormat::new()), Box::new(PlainText::new(text))])
+    }
+
+    #[test]
+    fn apply_category_works() {
+        let ctx = Context::default();
+        let mut fmt = PluralFormat::new("count", placeholder_message(" other"));
+        fmt.one(placeholder_message(" one"));
+        fmt.few(placeholder_message(" few"));
+        fmt.literal(3, parse("three").unwrap());
+
+        let mut output = String::new();
+        fmt.apply_category(PluralCategory::Few, &ctx, &mut output, Some(&arg("count", 3)))
+            .unwrap();
+        assert_eq!("3 few", output);
+
+        let mut output = String::new();
+        fmt.apply_category(PluralCategory::Many, &ctx, &mut output, Some(&arg("count", 3)))
+            .unwrap();
+        assert_eq!("3 other", output);
+
+        let fmt = PluralFormat::new("count", parse("Other").unwrap());
+        let mut output = String::new();
+        fmt.apply_category(PluralCategory::Few, &ctx, &mut output, None).unwrap();
+        assert_eq!("Other", output);
+    }
+
+    #[test]
+    fn uses_context_locale() {
+        let m = parse("{n, plural, one {one} few {few} many {many} other {other}}").unwrap();
+        let ctx = Context::new(Locale::new("ru").unwrap(), None);
+        let cases = [(1, "one"), (3, "few"), (5, "many"), (11, "many"), (21, "one")];
+        for &(n, expected) in &cases {
+            assert_eq!(ctx.format(&m, Some(&arg("n", n))), expected);
+        }
+
+        let ctx = Context::new(Locale::new("ja").unwrap(), None);
+        assert_eq!(ctx.format(&m, Some(&arg("n", 1))), "other");
+    }
+
+    #[test]
+    fn locale_chain() {
+        let m = parse("{n, plural, one {one} many {many} other {other}}").unwrap();
+        let ordinal = parse("{n, selectordinal, one {one} other {other}}").unwrap();
+        // `fr-CA` has no rules of its own, so those of `fr` are used,
+        // for which 0 is `one`, unlike in English.
+        let ctx = Context::locale_chain(&["fr-CA", "fr", "en"]).unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", 0))), "one");
+        assert_eq!(ctx.format(&m, Some(&arg("n", 1000000))), "many");
+        assert_eq!(ctx.format(&ordinal, Some(&arg("n", 1))), "one");
+        // The first locale with rules is used for both kinds of plural.
+        let ctx = Context::locale_chain(&["tlh", "fr", "en"]).unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", 0))), "one");
+        assert_eq!(ctx.format(&ordinal, Some(&arg("n", 21))), "other");
+        let ctx = Context::locale_chain(&["tlh", "en"]).unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", 0))), "other");
+        assert_eq!(ctx.format(&ordinal, Some(&arg("n", 21))), "one");
+        // A locale with rules is used even if they differ from the fallbacks.
+        let ctx = Context::locale_chain(&["ja", "fr"]).unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", 1))), "other");
+
+        let mut fmt = PluralFormat::new("n", parse("other").unwrap());
+        fmt.one(parse("one").unwrap());
+        let ctx = Context::locale_chain(&["tlh", "fr"]).unwrap();
+        let explanation = fmt.explain(&ctx, &Value::Number(0)).unwrap();
+        assert_eq!(explanation.branch, PluralBranch::Category(PluralCategory::One));
+        assert_eq!(explanation.condition.as_deref(), Some("i = 0,1"));
+    }
+
+    #[test]
+    fn decimals_use_visible_fraction_digits() {
+        let ctx = Context::default();
+        let m = parse("{n, plural, one {# day} other {# days}}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", 1))), "1 day");
+        assert_eq!(ctx.format(&m, Some(&arg("n", 1.0))), "1 day");
+        let decimal = |value, fraction_digits| Value::Decimal { value, fraction_digits };
+        assert_eq!(ctx.format(&m, Some(&arg("n", decimal(1.0, 0)))), "1 day");
+        assert_eq!(ctx.format(&m, Some(&arg("n", decimal(1.0, 1)))), "1.0 days");
+        assert_eq!(ctx.format(&m, Some(&arg("n", 1.5))), "1.5 days");
+
+        // In French, `one` also includes fractions below 2.
+        let ctx = Context::new(Locale::new("fr").unwrap(), None);
+        assert_eq!(ctx.format(&m, Some(&arg("n", decimal(1.5, 2)))), "1.50 day");
+    }
+
+    #[test]
+    fn decimal_strings_keep_fraction_digits() {
+        let ctx = Context::default();
+        let m = parse("{n, plural, one {# day} other {# days}}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", decimal("1")))), "1 day");
+        assert_eq!(ctx.format(&m, Some(&arg("n", decimal("1.00")))), "1.00 days");
+        assert_eq!(ctx.format(&m, Some(&arg("n", decimal("-1")))), "-1 day");
+
+        // The exponent is an operand of the French rules.
+        let ctx = Context::new(Locale::new("fr").unwrap(), None);
+        let m = parse("{n, plural, one {one} many {many} other {other}}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", decimal("1000000")))), "many");
+        assert_eq!(ctx.format(&m, Some(&arg("n", decimal("1c6")))), "many");
+        assert_eq!(ctx.format(&m, Some(&arg("n", decimal("1.5c3")))), "other");
+        assert_eq!(ctx.format(&m, Some(&arg("n", decimal("1.5c6")))), "many");
+        assert!(DecimalStr::new("x").is_err());
+    }
+
+    #[test]
+    fn decimals_with_offset_and_literals() {
+        let ctx = Context::default();
+        let m = parse("{n, plural, offset:1 =2 {two} one {# more} other {# others}}").unwrap();
+        let fixed = |value, fraction_digits| Value::Decimal { value, fraction_digits };
+        assert_eq!(ctx.format(&m, Some(&arg("n", fixed(2.0, 1)))), "1.0 others");
+        assert_eq!(ctx.format(&m, Some(&arg("n", fixed(3.0, 1)))), "two");
+        assert_eq!(ctx.format(&m, Some(&arg("n", fixed(2.0, 0)))), "1 more");
+        assert_eq!(ctx.format(&m, Some(&arg("n", decimal("2.50")))), "1.50 others");
+    }
+
+    #[test]
+    fn classifier_overrides_locale() {
+        fn always_few(_: &PluralOperands) -> PluralCategory {
+            PluralCategory::Few
+        }
+
+        let ctx = Context::new(Locale::new("ja").unwrap(), None);
+        let mut fmt = PluralFormat::new("count", parse("Other").unwrap());
+        fmt.few(parse("Few").unwrap());
+        fmt.classifier(always_few as fn(&PluralOperands) -> PluralCategory);
+
+        let mut output = String::new();
+        fmt.apply_format(&ctx, &mut output, Some(&arg("count", 1))).unwrap();
+        assert_eq!("Few", output);
+    }
+
+    #[test]
+    fn capturing_classifier() {
+        // As if loaded from CLDR data at runtime.
+        let loaded: HashMap<u64, _> = [(1, PluralCategory::One), (2, PluralCategory::Two)].into();
+        let mut fmt = PluralFormat::new("count", parse("Other").unwrap());
+        fmt.one(parse("One").unwrap());
+        fmt.two(parse("Two").unwrap());
+        fmt.classifier(move |operands: &PluralOperands| {
+            *loaded.get(&operands.i).unwrap_or(&PluralCategory::Other)
+        });
+        assert!(format!("{:?}", fmt).contains("classifier: Some(\"PluralClassifier\")"));
+
+        let ctx = Context::default();
+        let format = |count| {
+            let mut output = String::new();
+            fmt.apply_format(&ctx, &mut output, Some(&arg("count", count))).unwrap();
+            output
+        };
+        assert_eq!(format(1), "One");
+        assert_eq!(format(2), "Two");
+        assert_eq!(format(3), "Other");
+    }
+
+    #[cfg(feature = "intl-pluralrules")]
+    #[test]
+    fn with_locale() {
+        let ctx = Context::default();
+        let mut fmt = PluralFormat::with_locale("n", placeholder_message(" файлов"), "ru", false)
+            .unwrap();
+        fmt.one(placeholder_message(" файл"));
+        fmt.few(placeholder_message(" файла"));
+        let cases = [(1, "1 файл"), (3, "3 файла"), (5, "5 файлов"), (21, "21 файл")];
+        for &(n, expected) in &cases {
+            let mut output = String::new();
+            fmt.apply_format(&ctx, &mut output, Some(&arg("n", n))).unwrap();
+            assert_eq!(output, expected);
+        }
+
+        let mut fmt = PluralFormat::with_locale("n", placeholder_message("th"), "en", true)
+            .unwrap();
+        fmt.two(placeholder_message("nd"));
+        let mut output = String::new();
+        fmt.apply_format(&ctx, &mut output, Some(&arg("n", 22))).unwrap();
+        assert_eq!(output, "22nd");
+
+        assert!(PluralFormat::with_locale("n", parse("").unwrap(), "xx", false).is_none());
+    }
+
+    #[test]
+    fn modulo_works() {
+        let ctx = Context::default();
+        let mut fmt = PluralFormat::new("hour", parse("Other").unwrap());
+        fmt.one(parse("One").unwrap());
+        fmt.literal(0, parse("Zero").unwrap());
+        fmt.modulo(12).unwrap();
+
+        let cases = [(0, "Zero"), (1, "One"), (12, "Zero"), (13, "One"), (14, "Other"),
+                     (-11, "One")];
+        for &(hour, expected) in &cases {
+            let mut output = String::new();
+            fmt.apply_format(&ctx, &mut output, Some(&arg("hour", hour))).unwrap();
+            assert_eq!(expected, output);
+        }
+    }
+
+    #[test]
+    fn strict_fallbacks() {
+        let ctx = Context::builder().strict_fallbacks(true).build().unwrap();
+        let m = parse("{n, plural, offset:1 =0 {none} one {# more} other {# more}} \
+                       {n, plural, one {file} other {files}}")
+            .unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", 2))), "1 more files");
+        let m = parse("{n, plural, =0 {none} other {#}}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", 0))), "none");
+        assert_eq!(ctx.format(&m, Some(&arg("n", 3))), "3");
+
+        let mut output = String::new();
+        assert_eq!(m.write_message(&ctx, &mut output, Some(&arg("n", 1))),
+                   Err(FormatError::UnexpectedFallback {
+                       variable: "n".to_string(),
+                       value: "1".to_string(),
+                   }));
+        // The error is left until a partial message is formatted.
+        let partial = m.partial(&ctx, &arg("n", 1));
+        assert!(partial.write_message(&ctx, &mut output, None).is_err());
+
+        let fmt = PluralFormat::new("n", parse("Other").unwrap());
+        assert_eq!(fmt.apply_category(PluralCategory::Few, &ctx, &mut output, None),
+                   Err(FormatError::UnexpectedFallback {
+                       variable: "n".to_string(),
+                       value: "few".to_string(),
+                   }));
+    }
+
+    #[test]
+    fn modulo_rejects_zero() {
+        let mut fmt = PluralFormat::new("hour", parse("Other").unwrap());
+        assert_eq!(fmt.modulo(0), Err(ModuloError(0)));
+        assert_eq!(fmt.modulo(-12), Err(ModuloError(-12)));
+        assert_eq!(fmt.modulo(12), Ok(()));
+    }
 }
@@ -0,0 +1,329 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::string::ToString;
+
+use icu::ast::NumberFormat;
+use plural_rules::cardinal_category;
+use {Args, Context, FormatError, Locale, MessagePart, MessageVisitor, PluralCategory,
+     PluralOperands, Value};
+
+/// A unit of time that a [`RelativeTimeFormat`] counts in.
+///
+/// [`RelativeTimeFormat`]: struct.RelativeTimeFormat.html
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum RelativeTimeUnit {
+    /// `second`
+    Second,
+    /// `minute`
+    Minute,
+    /// `hour`
+    Hour,
+    /// `day`
+    Day,
+    /// `week`
+    Week,
+    /// `month`
+    Month,
+    /// `year`
+    Year,
+}
+
+impl RelativeTimeUnit {
+    /// The unit with a `name`, like `day`, as it is written in the style
+    /// of a `relativetime` format.
+    pub fn from_name(name: &str) -> Option<RelativeTimeUnit> {
+        match name {
+            "second" => Some(RelativeTimeUnit::Second),
+            "minute" => Some(RelativeTimeUnit::Minute),
+            "hour" => Some(RelativeTimeUnit::Hour),
+            "day" => Some(RelativeTimeUnit::Day),
+            "week" => Some(RelativeTimeUnit::Week),
+            "month" => Some(RelativeTimeUnit::Month),
+            "year" => Some(RelativeTimeUnit::Year),
+            _ => None,
+        }
+    }
+
+    /// The name of the unit.
+    pub fn name(self) -> &'static str {
+        match self {
+            RelativeTimeUnit::Second => "second",
+            RelativeTimeUnit::Minute => "minute",
+            RelativeTimeUnit::Hour => "hour",
+            RelativeTimeUnit::Day => "day",
+            RelativeTimeUnit::Week => "week",
+            RelativeTimeUnit::Month => "month",
+            RelativeTimeUnit::Year => "year",
+        }
+    }
+}
+
+/// Format a signed number of units of time from now, as in
+/// `{delta, relativetime, day}`.
+///
+/// Positive numbers are in the future, as in `in 3 days`, and negative
+/// numbers are in the past, as in `3 days ago`. The plural category of
+/// the number, in the locale that the phrases are in, chooses the form
+/// of the unit. Zero is a word for the current unit, like `today` or
+/// `now`, unless the format is numeric, which is set by the `numeric`
+/// style after the unit, as in `{delta, relativetime, day numeric}`.
+/// The phrases are those of the first of the [`Context::locales`] that
+/// has them, or English otherwise.
+///
+/// ```
+/// use message_format::{arg, icu, Context};
+///
+/// let ctx = Context::default();
+/// let m = icu::parse("Due {delta, relativetime, day}").unwrap();
+/// assert_eq!(ctx.format(&m, Some(&arg("delta", 3))), "Due in 3 days");
+/// assert_eq!(ctx.format(&m, Some(&arg("delta", -1))), "Due 1 day ago");
+/// assert_eq!(ctx.format(&m, Some(&arg("delta", 0))), "Due today");
+/// ```
+///
+/// [`Context::locales`]: ../../struct.Context.html#method.locales
+#[derive(Debug)]
+pub struct RelativeTimeFormat {
+    number: NumberFormat,
+    unit: RelativeTimeUnit,
+    numeric: bool,
+}
+
+impl RelativeTimeFormat {
+    /// Construct a `RelativeTimeFormat` for the value of a variable,
+    /// counted in `unit`.
+    pub fn new(variable_name: &str, unit: RelativeTimeUnit) -> Self {
+        RelativeTimeFormat {
+            number: NumberFormat::new(variable_name),
+            unit,
+            numeric: false,
+        }
+    }
+
+    /// Set whether zero is written as a number, as in `in 0 days`,
+    /// rather than as a word, like `today`.
+    pub fn numeric(&mut self, numeric: bool) {
+        self.numeric = numeric;
+    }
+}
+
+/// The phrases of a locale for a unit: the future and past patterns,
+/// where `{0}` is the number and the unit, the forms of the unit for
+/// the `one` and `other` plural categories, and the word for zero.
+struct Phrases {
+    future: &'static str,
+    past: &'static str,
+    one: &'static str,
+    other: &'static str,
+    zero: &'static str,
+}
+
+/// The phrases of a unit in a locale, if there are any.
+fn phrases(locale: &Locale, unit: RelativeTimeUnit) -> Option<Phrases> {
+    use self::RelativeTimeUnit::*;
+
+    let (future, past) = match locale.language() {
+        "de" => ("in {0}", "vor {0}"),
+        "en" => ("in {0}", "{0} ago"),
+        "es" => ("dentro de {0}", "hace {0}"),
+        "fr" => ("dans {0}", "il y a {0}"),
+        _ => return None,
+    };
+    let [one, other, zero] = match (locale.language(), unit) {
+        ("de", Second) => ["Sekunde", "Sekunden", "jetzt"],
+        ("de", Minute) => ["Minute", "Minuten", "in dieser Minute"],
+        ("de", Hour) => ["Stunde", "Stunden", "in dieser Stunde"],
+        ("de", Day) => ["Tag", "Tagen", "heute"],
+        ("de", Week) => ["Woche", "Wochen", "diese Woche"],
+        ("de", Month) => ["Monat", "Monaten", "diesen Monat"],
+        ("de", Year) => ["Jahr", "Jahren", "dieses Jahr"],
+        ("en", Second) => ["second", "seconds", "now"],
+        ("en", Minute) => ["minute", "minutes", "this minute"],
+        ("en", Hour) => ["hour", "hours", "this hour"],
+        ("en", Day) => ["day", "days", "today"],
+        ("en", Week) => ["week", "weeks", "this week"],
+        ("en", Month) => ["month", "months", "this month"],
+        ("en", Year) => ["year", "years", "this year"],
+        ("es", Second) => ["segundo", "segundos", "ahora"],
+        ("es", Minute) => ["minuto", "minutos", "este minuto"],
+        ("es", Hour) => ["hora", "horas", "esta hora"],
+        ("es", Day) => ["día", "días", "hoy"],
+        ("es", Week) => ["semana", "semanas", "esta semana"],
+        ("es", Month) => ["mes", "meses", "este mes"],
+        ("es", Year) => ["año", "años", "este año"],
+        ("fr", Second) => ["seconde", "secondes", "maintenant"],
+        ("fr", Minute) => ["minute", "minutes", "cette minute-ci"],
+        ("fr", Hour) => ["heure", "heures", "cette heure-ci"],
+        ("fr", Day) => ["jour", "jours", "aujourd’hui"],
+        ("fr", Week) => ["semaine", "semaines", "cette semaine"],
+        ("fr", Month) => ["mois", "mois", "ce mois-ci"],
+        ("fr", Year) => ["an", "ans", "cette année"],
+        _ => return None,
+    };
+    Some(Phrases {
+        future,
+        past,
+        one,
+        other,
+        zero,
+    })
+}
+
+impl MessagePart for RelativeTimeFormat {
+    fn apply_format<'f>(&self,
+                        ctx: &Context,
+                        stream: &mut dyn fmt::Write,
+                        args: Option<&Args<'f>>)
+                        -> Result<(), FormatError> {
+        let variable_name = self.number.variable_name();
+        if ctx.pseudolocalization.is_on() {
+            return Ok(write!(stream, "[{}]", variable_name)?);
+        }
+        let arg = ctx.lookup_arg(args, variable_name)?;
+        let value = match arg.value().resolve() {
+            Value::Number(value) => value,
+            Value::BigInt(_) => return Err(FormatError::ValueOutOfRange(variable_name.to_string())),
+            _ => return Err(FormatError::InvalidArgumentType(variable_name.to_string())),
+        };
+        let operands = PluralOperands::from(value.unsigned_abs());
+        // The noun is classified by the rules of the locale that the
+        // phrases are for, unless the context has a classifier, which is
+        // used as it is by `plural`. Without phrases for any of the
+        // locales, English is used.
+        let english = Locale::en_us();
+        let (locale, phrases) = ctx.locales()
+            .find_map(|locale| phrases(locale, self.unit).map(|phrases| (locale, phrases)))
+            .unwrap_or_else(|| (&english, phrases(&english, self.unit).unwrap()));
+        let category = match ctx.plural_classifier(false) {
+            Some(classifier) => classifier.classify(&operands),
+            None => cardinal_category(locale, &operands),
+        };
+        if value == 0 && !self.numeric {
+            return Ok(stream.write_str(phrases.zero)?);
+        }
+        let source = value.unsigned_abs().to_string();
+        let name = match category {
+            PluralCategory::One => phrases.one,
+            _ => phrases.other,
+        };
+        let pattern = if value < 0 { phrases.past } else { phrases.future };
+        let (before, after) = pattern.split_at(pattern.find("{0}").unwrap_or(0));
+        stream.write_str(before)?;
+        ctx.number_symbols.write_ascii(stream, &self.number.format_ascii(&source))?;
+        Ok(write!(stream, " {}{}", name, after.trim_start_matches("{0}"))?)
+    }
+
+    fn accept(&self, visitor: &mut dyn MessageVisitor) {
+        visitor.visit_relative_time(self.number.variable_name());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RelativeTimeFormat, RelativeTimeUnit};
+    use icu::parse;
+    use {arg, Context, FormatError, Locale, MessagePart, PluralCategory, PluralOperands, Value};
+
+    fn format(ctx: &Context, fmt: &RelativeTimeFormat, value: i64) -> String {
+        let mut output = String::new();
+        fmt.apply_format(ctx, &mut output, Some(&arg("n", value))).unwrap();
+        output
+    }
+
+    #[test]
+    fn days() {
+        let ctx = Context::default();
+        let fmt = RelativeTimeFormat::new("n", RelativeTimeUnit::Day);
+        let cases = [(-1000, "1,000 days ago"),
+                     (-2, "2 days ago"),
+                     (-1, "1 day ago"),
+                     (0, "today"),
+                     (1, "in 1 day"),
+                     (3, "in 3 days"),
+                     (i64::MIN, "9,223,372,036,854,775,808 days ago")];
+        for &(value, expected) in &cases {
+            assert_eq!(format(&ctx, &fmt, value), expected);
+        }
+        let mut fmt = RelativeTimeFormat::new("n", RelativeTimeUnit::Day);
+        fmt.numeric(true);
+        assert_eq!(format(&ctx, &fmt, 0), "in 0 days");
+    }
+
+    #[test]
+    fn units() {
+        let ctx = Context::default();
+        let cases = [(RelativeTimeUnit::Second, "now", "in 1 second"),
+                     (RelativeTimeUnit::Minute, "this minute", "in 1 minute"),
+                     (RelativeTimeUnit::Hour, "this hour", "in 1 hour"),
+                     (RelativeTimeUnit::Day, "today", "in 1 day"),
+                     (RelativeTimeUnit::Week, "this week", "in 1 week"),
+                     (RelativeTimeUnit::Month, "this month", "in 1 month"),
+                     (RelativeTimeUnit::Year, "this year", "in 1 year")];
+        for &(unit, zero, one) in &cases {
+            let fmt = RelativeTimeFormat::new("n", unit);
+            assert_eq!([format(&ctx, &fmt, 0), format(&ctx, &fmt, 1)], [zero, one]);
+            assert_eq!(RelativeTimeUnit::from_name(unit.name()), Some(unit));
+        }
+        assert_eq!(RelativeTimeUnit::from_name("fortnight"), None);
+    }
+
+    #[test]
+    fn locales() {
+        let fmt = RelativeTimeFormat::new("n", RelativeTimeUnit::Day);
+        let cases = [("de", ["vor 2 Tagen", "vor 1 Tag", "heute", "in 1 Tag", "in 5 Tagen"]),
+                     ("es", ["hace 2 días", "hace 1 día", "hoy", "dentro de 1 día",
+                             "dentro de 5 días"]),
+                     // In French, 0 and 1 are `one`.
+                     ("fr", ["il y a 2 jours", "il y a 1 jour", "aujourd’hui", "dans 1 jour",
+                             "dans 5 jours"]),
+                     // There are no Japanese phrases, so English is used.
+                     ("ja", ["2 days ago", "1 day ago", "today", "in 1 day", "in 5 days"])];
+        for &(locale, expected) in &cases {
+            let ctx = Context::new(Locale::new(locale).unwrap(), None);
+            let values = [-2, -1, 0, 1, 5];
+            assert_eq!(values.iter().map(|&value| format(&ctx, &fmt, value)).collect::<Vec<_>>(),
+                       expected,
+                       "{}",
+                       locale);
+        }
+
+        // The classifier of the context is used, as it is by `plural`.
+        let other = |_: &PluralOperands| PluralCategory::Other;
+        let ctx = Context::builder().plural_classifier(other).build().unwrap();
+        assert_eq!(format(&ctx, &fmt, 1), "in 1 days");
+
+        // The phrases and their plural rules are from the same locale.
+        let ctx = Context::locale_chain(&["ja", "fr"]).unwrap();
+        assert_eq!(format(&ctx, &fmt, 1), "dans 1 jour");
+        assert_eq!(format(&ctx, &fmt, 5), "dans 5 jours");
+    }
+
+    #[test]
+    fn skeletons() {
+        let ctx = Context::default();
+        let m = parse("{a, relativetime, hour}; {b, relativetime,  day numeric }").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("a", -5).arg("b", 0))), "5 hours ago; in 0 days");
+        for source in &["{a, relativetime}", "{a, relativetime, fortnight}",
+                        "{a, relativetime, day always}", "{a, relativetime, numeric}"] {
+            assert!(parse(source).is_err(), "{}", source);
+        }
+        assert_eq!(parse("{a, relativetime, day} {a}").unwrap().variables()[0].kinds.len(), 2);
+    }
+
+    #[test]
+    fn only_integers() {
+        let ctx = Context::default();
+        let fmt = RelativeTimeFormat::new("n", RelativeTimeUnit::Day);
+        let mut output = String::new();
+        let args = arg("n", Value::Decimal { value: 1.5, fraction_digits: 1 });
+        assert_eq!(fmt.apply_format(&ctx, &mut output, Some(&args)),
+                   Err(FormatError::InvalidArgumentType("n".to_string())));
+        let args = arg("n", Value::BigInt(i128::MAX));
+        assert_eq!(fmt.apply_format(&ctx, &mut output, Some(&args)),
+                   Err(FormatError::ValueOutOfRange("n".to_string())));
+    }
+}
@@ -4,66 +4,217 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::boxed::Box;
+use std::collections::BTreeMap;
 use std::fmt;
+use std::string::{String, ToString};
+use std::sync::Arc;
+use std::vec::Vec;
 
-use {Args, Context, MessagePart, Message, Value};
+use {Args, Context, FormatError, MessagePart, MessageVisitor, Message, Value};
 
 /// Using a value, select the appropriate message and format it.
+///
+/// The value is a string, or an integer, which is matched by its
+/// decimal form, like `"-2"`, to select on exact numbers.
+///
+/// The messages for each value are reference counted, so values with
+/// the same message can share it by using `map_shared`.
 #[derive(Debug)]
 pub struct SelectFormat {
     /// The name of the variable whose value should be formatted.
     variable_name: String,
     /// Given a value of a variable, this maps that to a message format.
-    mappings: HashMap<String, Message>,
+    mappings: BTreeMap<String, Arc<Message>>,
     /// The message format to use if no valid mapping is found for
     /// the variable value.
-    default: Message,
+    default: Arc<Message>,
+    /// Whether a value can match a mapping that is a prefix of it.
+    prefix_match: bool,
+    /// Whether the default is used when the variable is missing.
+    default_on_missing: bool,
 }
 
 impl SelectFormat {
     /// Construct a `SelectFormat`.
     pub fn new(variable_name: &str, default: Message) -> Self {
+        SelectFormat::new_shared(variable_name, Arc::new(default))
+    }
+
+    /// Construct a `SelectFormat` with a shared default message.
+    pub fn new_shared(variable_name: &str, default: Arc<Message>) -> Self {
         SelectFormat {
             variable_name: variable_name.to_string(),
-            mappings: HashMap::<String, Message>::new(),
-            default: default,
+            mappings: BTreeMap::new(),
+            default,
+            prefix_match: false,
+            default_on_missing: false,
         }
     }
 
     /// Map a value for a particular message.
     pub fn map(&mut self, value: &str, message: Message) {
+        self.map_shared(value, Arc::new(message));
+    }
+
+    /// Map a value for a particular shared message.
+    pub fn map_shared(&mut self, value: &str, message: Arc<Message>) {
         self.mappings.insert(value.to_string(), message);
     }
 
+    /// Allow a value to match the longest mapped value that is a prefix
+    /// of it, when there is no exact match.
+    ///
+    /// This is useful for hierarchical values, so that mappings for
+    /// `error` and `error.network` can handle a value of
+    /// `error.network.timeout`.
+    pub fn prefix_match(&mut self, prefix_match: bool) {
+        self.prefix_match = prefix_match;
+    }
+
+    /// Use the default message when the variable is missing from the
+    /// arguments, rather than failing with `FormatError::MissingArgument`.
+    ///
+    /// This is useful for optional arguments, such as a gender that
+    /// isn't always known.
+    pub fn default_on_missing(&mut self, default_on_missing: bool) {
+        self.default_on_missing = default_on_missing;
+    }
+
     /// Given a value, determine which `Message` to use.
     pub fn lookup_message(&self, value: &str) -> &Message {
-        self.mappings.get(value).unwrap_or(&self.default)
+        self.find_message(value).unwrap_or(&self.default)
+    }
+
+    /// The mapped values, in sorted order. These don't include the
+    /// default.
+    pub fn keys(&self) -> Vec<&str> {
+        self.mappings.keys().map(|key| key.as_str()).collect()
+    }
+
+    /// The `Message` used when a value isn't mapped, which is the
+    /// `other` branch in a message.
+    pub fn default_message(&self) -> &Message {
+        &self.default
+    }
+
+    /// Find the `Message` mapped for a value, if there is one.
+    fn find_message(&self, value: &str) -> Option<&Message> {
+        if let Some(message) = self.mappings.get(value) {
+            return Some(message);
+        }
+        if self.prefix_match {
+            return self.mappings
+                .iter()
+                .filter(|&(key, _)| value.starts_with(key.as_str()))
+                .max_by_key(|&(key, _)| key.len())
+                .map(|(_, message)| &**message);
+        }
+        None
     }
 }
 
 impl MessagePart for SelectFormat {
     fn apply_format<'f>(&self,
                         ctx: &Context,
-                        stream: &mut fmt::Write,
+                        stream: &mut dyn fmt::Write,
                         args: Option<&Args<'f>>)
-                        -> fmt::Result {
-        let arg = args.and_then(|args| args.get(&self.variable_name));
-        if let Some(&Value::Str(value)) = arg.map(|a| a.value()) {
-            let message = self.lookup_message(value);
-            try!(message.write_message(ctx, stream, args));
+                        -> Result<(), FormatError> {
+        self.apply_format_nested(ctx, 0, stream, args)
+    }
+
+    fn apply_format_nested<'f>(&self,
+                               ctx: &Context,
+                               depth: usize,
+                               stream: &mut dyn fmt::Write,
+                               args: Option<&Args<'f>>)
+                               -> Result<(), FormatError> {
+        let missing = ctx.find_arg(args, &self.variable_name).is_none();
+        if missing && self.default_on_missing {
+            return self.default.write_nested(ctx, depth + 1, stream, args);
+        }
+        let arg = ctx.lookup_arg(args, &self.variable_name)?;
+        if let Some(value) = key(arg.value().resolve()) {
+            let message = match self.find_message(&value) {
+                Some(message) => message,
+                None => {
+                    ctx.format_observer().on_select_fallback(&self.variable_name, &value);
+                    if ctx.strict_fallbacks {
+                        return Err(FormatError::UnexpectedFallback {
+                            variable: self.variable_name.clone(),
+                            value: value.to_string(),
+                        });
+                    }
+                    &self.default
+                }
+            };
+            message.write_nested(ctx, depth + 1, stream, args)?;
             Ok(())
         } else {
-            Err(fmt::Error {})
+            Err(FormatError::InvalidArgumentType(self.variable_name.clone()))
+        }
+    }
+
+    fn partial(&self, ctx: &Context, args: &Args) -> Option<Message> {
+        match args.get(&self.variable_name).map(|arg| key(arg.value().resolve())) {
+            Some(Some(value)) if ctx.strict_fallbacks => {
+                Some(self.find_message(&value)?.partial_parts(ctx, args))
+            }
+            Some(Some(value)) => Some(self.lookup_message(&value).partial_parts(ctx, args)),
+            Some(None) => None,
+            None => {
+                let mut fmt = SelectFormat::new(&self.variable_name,
+                                                self.default.partial_parts(ctx, args));
+                for (key, message) in &self.mappings {
+                    fmt.map(key, message.partial_parts(ctx, args));
+                }
+                fmt.prefix_match(self.prefix_match);
+                fmt.default_on_missing(self.default_on_missing);
+                Some(Message::new(vec![Box::new(fmt)]))
+            }
+        }
+    }
+
+    fn map_text(&self, transform: &dyn Fn(&str) -> String) -> Option<Message> {
+        let mut fmt = SelectFormat::new(&self.variable_name, self.default.map_text(transform));
+        for (key, message) in &self.mappings {
+            fmt.map(key, message.map_text(transform));
+        }
+        fmt.prefix_match(self.prefix_match);
+        fmt.default_on_missing(self.default_on_missing);
+        Some(Message::new(vec![Box::new(fmt)]))
+    }
+
+    fn accept(&self, visitor: &mut dyn MessageVisitor) {
+        let keys = self.keys();
+        visitor.visit_select(&self.variable_name, &keys);
+        let branches = keys.iter().map(|key| &self.mappings[*key]);
+        for message in branches.chain(Some(&self.default)) {
+            visitor.enter_branch();
+            message.accept(visitor);
+            visitor.leave_branch();
         }
     }
 }
 
+/// The string a value is selected by, if it can be selected on.
+fn key<'a>(value: Value<'a>) -> Option<Cow<'a, str>> {
+    match value {
+        Value::Str(value) => Some(Cow::Borrowed(value)),
+        Value::Number(value) => Some(Cow::Owned(value.to_string())),
+        Value::BigInt(value) => Some(Cow::Owned(value.to_string())),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use icu::parse;
     use super::SelectFormat;
-    use {arg, Context, MessagePart};
+    use std::ptr;
+    use std::sync::Arc;
+    use {arg, Context, FormatError, MessagePart, Value};
 
     #[test]
     fn it_works() {
@@ -79,4 +230,153 @@ mod tests {
         fmt.apply_format(&ctx, &mut output, Some(&arg("type", "span"))).unwrap();
         assert_eq!("Default", output);
     }
+
+    #[test]
+    fn map_shared_works() {
+        let ctx = Context::default();
+        let shared = Arc::new(parse("Inline").unwrap());
+        let mut fmt = SelectFormat::new("type", parse("Default").unwrap());
+        fmt.map_shared("span", shared.clone());
+        fmt.map_shared("em", shared.clone());
+        assert!(ptr::eq(fmt.lookup_message("span"), fmt.lookup_message("em")));
+        assert_eq!(Arc::strong_count(&shared), 3);
+
+        let mut output = String::new();
+        fmt.apply_format(&ctx, &mut output, Some(&arg("type", "em"))).unwrap();
+        assert_eq!("Inline", output);
+    }
+
+    #[test]
+    fn accessors() {
+        let default = Arc::new(parse("Default").unwrap());
+        let mut fmt = SelectFormat::new_shared("type", default.clone());
+        assert!(fmt.keys().is_empty());
+        fmt.map("span", parse("Span").unwrap());
+        fmt.map("block", parse("Block").unwrap());
+        assert_eq!(fmt.keys(), ["block", "span"]);
+        assert!(ptr::eq(fmt.default_message(), &*default));
+        assert!(ptr::eq(fmt.default_message(), fmt.lookup_message("other")));
+    }
+
+    #[test]
+    fn debug_output_is_stable() {
+        let select = |keys: &[&str]| {
+            let mut fmt = SelectFormat::new("type", parse("Default").unwrap());
+            for key in keys {
+                fmt.map(key, parse(key).unwrap());
+            }
+            format!("{:?}", fmt)
+        };
+        let debug = select(&["span", "block", "inline"]);
+        assert_eq!(debug, select(&["inline", "span", "block"]));
+        let position = |key| debug.find(key).unwrap();
+        assert!(position("\"block\"") < position("\"inline\""));
+        assert!(position("\"inline\"") < position("\"span\""));
+    }
+
+    #[test]
+    fn prefix_match_works() {
+        let ctx = Context::default();
+        let mut fmt = SelectFormat::new("type", parse("Default").unwrap());
+        fmt.map("error", parse("Error").unwrap());
+        fmt.map("error.network", parse("Network").unwrap());
+        fmt.map("error.network.timeout", parse("Timeout").unwrap());
+        fmt.prefix_match(true);
+
+        let cases = [("error.network.timeout", "Timeout"),
+                     ("error.network.refused", "Network"),
+                     ("error.disk", "Error"),
+                     ("warning.disk", "Default")];
+        for &(value, expected) in &cases {
+            let mut output = String::new();
+            fmt.apply_format(&ctx, &mut output, Some(&arg("type", value))).unwrap();
+            assert_eq!(expected, output);
+        }
+    }
+
+    #[test]
+    fn prefix_match_is_off_by_default() {
+        let ctx = Context::default();
+        let mut fmt = SelectFormat::new("type", parse("Default").unwrap());
+        fmt.map("error", parse("Error").unwrap());
+
+        let mut output = String::new();
+        fmt.apply_format(&ctx, &mut output, Some(&arg("type", "error.disk"))).unwrap();
+        assert_eq!("Default", output);
+    }
+
+    #[test]
+    fn strict_fallbacks() {
+        let ctx = Context::builder().strict_fallbacks(true).build().unwrap();
+        let m = parse("{type, select, block {Block} other {Default}}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("type", "block"))), "Block");
+
+        let mut output = String::new();
+        assert_eq!(m.write_message(&ctx, &mut output, Some(&arg("type", "span"))),
+                   Err(FormatError::UnexpectedFallback {
+                       variable: "type".to_string(),
+                       value: "span".to_string(),
+                   }));
+        assert_eq!(FormatError::UnexpectedFallback {
+                           variable: "type".to_string(),
+                           value: "span".to_string(),
+                       }
+                       .to_string(),
+                   "No branch for value 'span' of argument 'type'.");
+        let partial = m.partial(&ctx, &arg("type", "span"));
+        assert!(partial.write_message(&ctx, &mut output, None).is_err());
+    }
+
+    #[test]
+    fn missing_argument_fails_by_default() {
+        let ctx = Context::default();
+        let mut fmt = SelectFormat::new("gender", parse("They").unwrap());
+        fmt.map("female", parse("She").unwrap());
+
+        let mut output = String::new();
+        assert_eq!(fmt.apply_format(&ctx, &mut output, Some(&arg("name", "Jo"))),
+                   Err(FormatError::MissingArgument("gender".to_string())));
+        assert_eq!(fmt.apply_format(&ctx, &mut output, None),
+                   Err(FormatError::MissingArgument("gender".to_string())));
+    }
+
+    #[test]
+    fn default_on_missing_works() {
+        let ctx = Context::default();
+        let mut fmt = SelectFormat::new("gender", parse("They").unwrap());
+        fmt.map("female", parse("She").unwrap());
+        fmt.default_on_missing(true);
+
+        let mut output = String::new();
+        fmt.apply_format(&ctx, &mut output, Some(&arg("name", "Jo"))).unwrap();
+        fmt.apply_format(&ctx, &mut output, None).unwrap();
+        fmt.apply_format(&ctx, &mut output, Some(&arg("gender", "female"))).unwrap();
+        assert_eq!("TheyTheyShe", output);
+
+        // Other arguments are still required by the default.
+        let mut fmt = SelectFormat::new("gender", parse("{name}").unwrap());
+        fmt.default_on_missing(true);
+        assert_eq!(fmt.apply_format(&ctx, &mut output, None),
+                   Err(FormatError::MissingArgument("name".to_string())));
+    }
+
+    #[test]
+    fn integers_are_selected_by_their_decimal_form() {
+        let ctx = Context::default();
+        let m = parse("{n, select, 1 {One} 2 {Two} -3 {Minus three} other {Some}}").unwrap();
+        let format = |value: Value| ctx.format(&m, Some(&arg("n", value)));
+        assert_eq!(format(Value::Number(1)), "One");
+        assert_eq!(format(Value::Number(2)), "Two");
+        assert_eq!(format(Value::Number(-3)), "Minus three");
+        assert_eq!(format(Value::Number(12)), "Some");
+        assert_eq!(format(Value::Str("2")), "Two");
+        assert_eq!(format(Value::BigInt(1)), "One");
+
+        let mut output = String::new();
+        let value = Value::Decimal { value: 1.0, fraction_digits: 0 };
+        assert_eq!(m.write_message(&ctx, &mut output, Some(&arg("n", value))),
+                   Err(FormatError::InvalidArgumentType("n".to_string())));
+        let partial = m.partial(&ctx, &arg("n", 2));
+        assert_eq!(ctx.format(&partial, None), "Two");
+    }
 }
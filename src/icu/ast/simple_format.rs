@@ -5,8 +5,9 @@
 // except according to those terms.
 
 use std::fmt;
+use std::string::{String, ToString};
 
-use {Args, Context, MessagePart};
+use {Args, Context, FormatError, MessagePart, MessageVisitor, Value};
 
 /// A simple message consisting of a value to be formatted.
 #[derive(Debug)]
@@ -20,27 +21,56 @@ impl SimpleFormat {
     pub fn new(variable_name: &str) -> Self {
         SimpleFormat { variable_name: variable_name.to_string() }
     }
+
+    /// Write a value as it is, computing it if it is lazy.
+    fn write_value(ctx: &Context,
+                   stream: &mut dyn fmt::Write,
+                   value: Value)
+                   -> Result<(), FormatError> {
+        match value {
+            Value::Number(value) => ctx.number_symbols.write_integer(stream, value)?,
+            Value::BigInt(value) => ctx.number_symbols.write_integer(stream, value)?,
+            Value::Decimal { value, fraction_digits } => {
+                ctx.number_symbols.write_decimal(stream, value, fraction_digits)?
+            }
+            Value::DecimalStr(value) => {
+                ctx.number_symbols.write_ascii(stream, &value.to_string())?
+            }
+            Value::Str(value) => ctx.sanitization.write_str(stream, value)?,
+            Value::Lazy(compute) => return SimpleFormat::write_value(ctx, stream, compute()),
+        }
+        Ok(())
+    }
 }
 
 impl MessagePart for SimpleFormat {
     fn apply_format<'f>(&self,
-                        _ctx: &Context,
-                        stream: &mut fmt::Write,
+                        ctx: &Context,
+                        stream: &mut dyn fmt::Write,
                         args: Option<&Args<'f>>)
-                        -> fmt::Result {
-        if let Some(arg) = args.and_then(|args| args.get(&self.variable_name)) {
-            try!(write!(stream, "{}", arg.value()));
-            Ok(())
-        } else {
-            Err(fmt::Error {})
+                        -> Result<(), FormatError> {
+        if ctx.pseudolocalization.is_on() {
+            return Ok(write!(stream, "[{}]", self.variable_name)?);
         }
+        let value = ctx.lookup_arg(args, &self.variable_name)?.value().resolve();
+        if !ctx.bidi_isolation.isolates(&ctx.locale, &value) {
+            return SimpleFormat::write_value(ctx, stream, value);
+        }
+        stream.write_char('\u{2068}')?;
+        SimpleFormat::write_value(ctx, stream, value)?;
+        Ok(stream.write_char('\u{2069}')?)
+    }
+
+    fn accept(&self, visitor: &mut dyn MessageVisitor) {
+        visitor.visit_variable(&self.variable_name);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::SimpleFormat;
-    use {arg, Context, MessagePart};
+    use icu::parse;
+    use {arg, BidiIsolation, Context, MessagePart, Sanitization};
 
     #[test]
     fn it_works() {
@@ -50,4 +80,39 @@ mod tests {
         fmt.apply_format(&ctx, &mut output, Some(&arg("name", "John"))).unwrap();
         assert_eq!("John", output);
     }
+
+    #[test]
+    fn values_are_inserted_verbatim() {
+        let ctx = Context::default();
+        let inner = "{count, plural, one {#} other {# {x}}} '{'";
+        let m = parse("[{inner}]").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("inner", inner))), format!("[{}]", inner));
+
+        // Within a plural branch, only the message's own `#` is replaced.
+        let m = parse("{n, plural, other {# {inner}}}").unwrap();
+        let args = arg("n", 2);
+        let args = args.arg("inner", "# {n}");
+        assert_eq!(ctx.format(&m, Some(&args)), "2 # {n}");
+    }
+
+    #[test]
+    fn sanitization_only_affects_values() {
+        let ctx = Context { sanitization: Sanitization::Strip, ..Context::default() };
+        let m = parse("Line one\nFile: {name}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("name", "evil\u{202e}gnp.exe\n"))),
+                   "Line one\nFile: evilgnp.exe");
+    }
+
+    #[test]
+    fn sanitized_values_stay_isolated() {
+        let ctx = Context {
+            sanitization: Sanitization::Strip,
+            bidi_isolation: BidiIsolation::Always,
+            ..Context::default()
+        };
+        let m = parse("{name}: {count}").unwrap();
+        let args = arg("name", "\u{2069}\u{202e}evil");
+        let args = args.arg("count", 3);
+        assert_eq!(ctx.format(&m, Some(&args)), "\u{2068}evil\u{2069}: \u{2068}3\u{2069}");
+    }
 }
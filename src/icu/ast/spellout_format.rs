@@ -0,0 +1,179 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::string::{String, ToString};
+
+use {Args, Context, FormatError, MessagePart, MessageVisitor, Value};
+
+/// The largest number that can be spelled out.
+const MAX: i64 = 999;
+
+const ONES: [&str; 20] = ["zero", "one", "two", "three", "four", "five", "six", "seven",
+                          "eight", "nine", "ten", "eleven", "twelve", "thirteen", "fourteen",
+                          "fifteen", "sixteen", "seventeen", "eighteen", "nineteen"];
+
+const TENS: [&str; 10] = ["", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy",
+                          "eighty", "ninety"];
+
+/// Spell out a number in English words, as in `twenty-one`, or as an
+/// ordinal, as in `twenty-first`.
+///
+/// This is the `spellout` and `spellout-ordinal` styles of the `number`
+/// format, as in `{n, number, spellout-ordinal}`. Numbers from 0 to 999
+/// are supported, and others are a `FormatError::ValueOutOfRange`. The
+/// words are English whatever the locale of the `Context`.
+#[derive(Debug)]
+pub struct SpelloutFormat {
+    /// The name of the variable whose value should be formatted.
+    variable_name: String,
+    ordinal: bool,
+}
+
+impl SpelloutFormat {
+    /// Construct a `SpelloutFormat` for cardinal numbers.
+    pub fn new(variable_name: &str) -> Self {
+        SpelloutFormat {
+            variable_name: variable_name.to_string(),
+            ordinal: false,
+        }
+    }
+
+    /// Set whether this spells out ordinals, like `first`, rather than
+    /// cardinals, like `one`.
+    pub fn ordinal(&mut self, ordinal: bool) {
+        self.ordinal = ordinal;
+    }
+}
+
+/// Spell out a number from 0 to 999 as a cardinal, as in `one hundred
+/// twenty-one`.
+fn cardinal(value: i64) -> String {
+    let value = value as usize;
+    let below_hundred = |value: usize| match value {
+        0..=19 => ONES[value].to_string(),
+        _ if value % 10 == 0 => TENS[value / 10].to_string(),
+        _ => format!("{}-{}", TENS[value / 10], ONES[value % 10]),
+    };
+    match (value / 100, value % 100) {
+        (0, rest) => below_hundred(rest),
+        (hundreds, 0) => format!("{} hundred", ONES[hundreds]),
+        (hundreds, rest) => format!("{} hundred {}", ONES[hundreds], below_hundred(rest)),
+    }
+}
+
+/// Turn the last word of a cardinal into an ordinal, as in `twenty-one`
+/// to `twenty-first`.
+fn ordinal(cardinal: &str) -> String {
+    let start = cardinal.rfind(['-', ' ']).map_or(0, |pos| pos + 1);
+    let (head, last) = cardinal.split_at(start);
+    let last = match last {
+        "one" => "first".to_string(),
+        "two" => "second".to_string(),
+        "three" => "third".to_string(),
+        "five" => "fifth".to_string(),
+        "eight" => "eighth".to_string(),
+        "nine" => "ninth".to_string(),
+        "twelve" => "twelfth".to_string(),
+        _ => {
+            match last.strip_suffix('y') {
+                Some(stem) => format!("{}ieth", stem),
+                None => format!("{}th", last),
+            }
+        }
+    };
+    format!("{}{}", head, last)
+}
+
+impl MessagePart for SpelloutFormat {
+    fn apply_format<'f>(&self,
+                        ctx: &Context,
+                        stream: &mut dyn fmt::Write,
+                        args: Option<&Args<'f>>)
+                        -> Result<(), FormatError> {
+        if ctx.pseudolocalization.is_on() {
+            return Ok(write!(stream, "[{}]", self.variable_name)?);
+        }
+        let arg = ctx.lookup_arg(args, &self.variable_name)?;
+        let value = match arg.value().resolve() {
+            Value::Number(value) => value,
+            Value::BigInt(_) => {
+                return Err(FormatError::ValueOutOfRange(self.variable_name.clone()))
+            }
+            _ => return Err(FormatError::InvalidArgumentType(self.variable_name.clone())),
+        };
+        if !(0..=MAX).contains(&value) {
+            return Err(FormatError::ValueOutOfRange(self.variable_name.clone()));
+        }
+        let words = cardinal(value);
+        if self.ordinal {
+            stream.write_str(&ordinal(&words))?;
+        } else {
+            stream.write_str(&words)?;
+        }
+        Ok(())
+    }
+
+    fn accept(&self, visitor: &mut dyn MessageVisitor) {
+        visitor.visit_spellout(&self.variable_name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpelloutFormat;
+    use icu::parse;
+    use {arg, Context, FormatError, MessagePart};
+
+    fn format(fmt: &SpelloutFormat, value: i64) -> Result<String, FormatError> {
+        let mut output = String::new();
+        fmt.apply_format(&Context::default(), &mut output, Some(&arg("n", value)))?;
+        Ok(output)
+    }
+
+    #[test]
+    fn ordinals() {
+        let mut fmt = SpelloutFormat::new("n");
+        fmt.ordinal(true);
+        let cases = [(0, "zeroth"), (1, "first"), (2, "second"), (3, "third"), (4, "fourth"),
+                     (5, "fifth"), (8, "eighth"), (9, "ninth"), (11, "eleventh"),
+                     (12, "twelfth"), (20, "twentieth"), (21, "twenty-first"),
+                     (42, "forty-second"), (100, "one hundredth"), (101, "one hundred first"),
+                     (999, "nine hundred ninety-ninth")];
+        for &(value, expected) in &cases {
+            assert_eq!(format(&fmt, value).unwrap(), expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn cardinals() {
+        let fmt = SpelloutFormat::new("n");
+        let cases = [(0, "zero"), (7, "seven"), (15, "fifteen"), (30, "thirty"),
+                     (21, "twenty-one"), (200, "two hundred"), (313, "three hundred thirteen")];
+        for &(value, expected) in &cases {
+            assert_eq!(format(&fmt, value).unwrap(), expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn out_of_range() {
+        let mut fmt = SpelloutFormat::new("n");
+        fmt.ordinal(true);
+        for &value in &[-1, 1000] {
+            assert_eq!(format(&fmt, value), Err(FormatError::ValueOutOfRange("n".to_string())));
+        }
+        let mut output = String::new();
+        assert_eq!(fmt.apply_format(&Context::default(), &mut output, Some(&arg("n", "1"))),
+                   Err(FormatError::InvalidArgumentType("n".to_string())));
+    }
+
+    #[test]
+    fn parsed() {
+        let ctx = Context::default();
+        let m = parse("The {n, number, spellout-ordinal} of {n, number, spellout}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", 3))), "The third of three");
+    }
+}
@@ -0,0 +1,262 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+
+use icu::ast::NumberFormat;
+use plural_rules::cardinal_category;
+use {Args, Context, FormatError, Locale, MessagePart, MessageVisitor, PluralCategory,
+     PluralOperands};
+
+/// A unit of measurement that a [`UnitFormat`] can write.
+///
+/// [`UnitFormat`]: struct.UnitFormat.html
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum Unit {
+    /// `unit/meter`
+    Meter,
+    /// `unit/kilometer`
+    Kilometer,
+    /// `unit/hour`
+    Hour,
+    /// `unit/minute`
+    Minute,
+}
+
+impl Unit {
+    /// The unit with a CLDR `name`, like `kilometer`, as it is written
+    /// in a `unit/` skeleton stem.
+    pub fn from_name(name: &str) -> Option<Unit> {
+        match name {
+            "meter" => Some(Unit::Meter),
+            "kilometer" => Some(Unit::Kilometer),
+            "hour" => Some(Unit::Hour),
+            "minute" => Some(Unit::Minute),
+            _ => None,
+        }
+    }
+
+    /// The CLDR name of the unit.
+    pub fn name(self) -> &'static str {
+        match self {
+            Unit::Meter => "meter",
+            Unit::Kilometer => "kilometer",
+            Unit::Hour => "hour",
+            Unit::Minute => "minute",
+        }
+    }
+}
+
+/// How the unit of a [`UnitFormat`] is written.
+///
+/// [`UnitFormat`]: struct.UnitFormat.html
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum UnitWidth {
+    /// An abbreviation, as in `5 km`. This is the default, and is set by
+    /// the `unit-width-short` stem.
+    Short,
+    /// The name of the unit, agreeing with the number, as in
+    /// `1 kilometer` and `5 kilometers`. This is set by the
+    /// `unit-width-full-name` stem.
+    FullName,
+}
+
+/// Format a number with a unit, as in `{d, number, unit/kilometer}`.
+///
+/// The number is formatted by a [`NumberFormat`]. With
+/// `UnitWidth::FullName`, the plural category of the number, in the
+/// locale that the unit is named in, chooses the form of the name.
+/// Units are named in the first of the [`Context::locales`] that has
+/// names for them, or in English otherwise.
+///
+/// ```
+/// use message_format::{arg, icu, Context};
+///
+/// let ctx = Context::default();
+/// let m = icu::parse("{d, number, ::unit/kilometer unit-width-full-name}").unwrap();
+/// assert_eq!(ctx.format(&m, Some(&arg("d", 1))), "1 kilometer");
+/// assert_eq!(ctx.format(&m, Some(&arg("d", 1500))), "1,500 kilometers");
+/// ```
+///
+/// [`NumberFormat`]: struct.NumberFormat.html
+/// [`Context::locales`]: ../../struct.Context.html#method.locales
+#[derive(Debug)]
+pub struct UnitFormat {
+    number: NumberFormat,
+    unit: Unit,
+    width: UnitWidth,
+}
+
+impl UnitFormat {
+    /// Construct a `UnitFormat` for the value of a variable in a `unit`.
+    pub fn new(variable_name: &str, unit: Unit) -> Self {
+        UnitFormat::from_number(NumberFormat::new(variable_name), unit)
+    }
+
+    /// Construct a `UnitFormat` that formats the number with `number`,
+    /// such as to turn off grouping.
+    pub fn from_number(number: NumberFormat, unit: Unit) -> Self {
+        UnitFormat {
+            number,
+            unit,
+            width: UnitWidth::Short,
+        }
+    }
+
+    /// Set how the unit is written.
+    pub fn width(&mut self, width: UnitWidth) {
+        self.width = width;
+    }
+
+    /// The name of the unit for a number with `operands`.
+    fn unit_name(&self, ctx: &Context, operands: Option<PluralOperands>) -> &'static str {
+        let english = Locale::en_us();
+        let (locale, names) = ctx.locales()
+            .find_map(|locale| unit_names(locale, self.unit).map(|names| (locale, names)))
+            .unwrap_or_else(|| (&english, unit_names(&english, self.unit).unwrap()));
+        match self.width {
+            UnitWidth::Short => names[0],
+            UnitWidth::FullName => {
+                let category = operands.map_or(PluralCategory::Other,
+                                               |operands| cardinal_category(locale, &operands));
+                if category == PluralCategory::One { names[1] } else { names[2] }
+            }
+        }
+    }
+}
+
+/// The names of a unit in a locale, if there are any: the abbreviation,
+/// and the full name for the `one` and `other` plural categories.
+fn unit_names(locale: &Locale, unit: Unit) -> Option<[&'static str; 3]> {
+    let names = match (locale.language(), unit) {
+        ("de", Unit::Meter) => ["m", "Meter", "Meter"],
+        ("de", Unit::Kilometer) => ["km", "Kilometer", "Kilometer"],
+        ("de", Unit::Hour) => ["Std.", "Stunde", "Stunden"],
+        ("de", Unit::Minute) => ["Min.", "Minute", "Minuten"],
+        ("en", Unit::Meter) => ["m", "meter", "meters"],
+        ("en", Unit::Kilometer) => ["km", "kilometer", "kilometers"],
+        ("en", Unit::Hour) => ["hr", "hour", "hours"],
+        ("en", Unit::Minute) => ["min", "minute", "minutes"],
+        ("es", Unit::Meter) => ["m", "metro", "metros"],
+        ("es", Unit::Kilometer) => ["km", "kilómetro", "kilómetros"],
+        ("es", Unit::Hour) => ["h", "hora", "horas"],
+        ("es", Unit::Minute) => ["min", "minuto", "minutos"],
+        ("fr", Unit::Meter) => ["m", "mètre", "mètres"],
+        ("fr", Unit::Kilometer) => ["km", "kilomètre", "kilomètres"],
+        ("fr", Unit::Hour) => ["h", "heure", "heures"],
+        ("fr", Unit::Minute) => ["min", "minute", "minutes"],
+        _ => return None,
+    };
+    Some(names)
+}
+
+impl MessagePart for UnitFormat {
+    fn apply_format<'f>(&self,
+                        ctx: &Context,
+                        stream: &mut dyn fmt::Write,
+                        args: Option<&Args<'f>>)
+                        -> Result<(), FormatError> {
+        if ctx.pseudolocalization.is_on() {
+            return Ok(write!(stream, "[{}]", self.number.variable_name())?);
+        }
+        let source = self.number.ascii_value(ctx, args)?;
+        let name = self.unit_name(ctx, source.parse::<PluralOperands>().ok());
+        ctx.number_symbols.write_ascii(stream, &self.number.format_ascii(&source))?;
+        Ok(write!(stream, " {}", name)?)
+    }
+
+    fn accept(&self, visitor: &mut dyn MessageVisitor) {
+        visitor.visit_number(self.number.variable_name());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Unit, UnitFormat, UnitWidth};
+    use icu::parse;
+    use {arg, Context, DecimalStr, Locale, MessagePart, Value};
+
+    fn format(ctx: &Context, fmt: &UnitFormat, value: Value) -> String {
+        let mut output = String::new();
+        fmt.apply_format(ctx, &mut output, Some(&arg("n", value))).unwrap();
+        output
+    }
+
+    fn decimal(source: &str) -> Value<'_> {
+        Value::DecimalStr(DecimalStr::new(source).unwrap())
+    }
+
+    #[test]
+    fn it_works() {
+        let ctx = Context::default();
+        let cases = [(Unit::Meter, "5 m"),
+                     (Unit::Kilometer, "5 km"),
+                     (Unit::Hour, "5 hr"),
+                     (Unit::Minute, "5 min")];
+        for &(unit, expected) in &cases {
+            assert_eq!(format(&ctx, &UnitFormat::new("n", unit), Value::Number(5)), expected);
+            assert_eq!(Unit::from_name(unit.name()), Some(unit));
+        }
+        assert_eq!(Unit::from_name("furlong"), None);
+    }
+
+    #[test]
+    fn full_names_agree_with_the_number() {
+        let ctx = Context::default();
+        let mut fmt = UnitFormat::new("n", Unit::Hour);
+        fmt.width(UnitWidth::FullName);
+        assert_eq!(format(&ctx, &fmt, Value::Number(1)), "1 hour");
+        assert_eq!(format(&ctx, &fmt, Value::Number(0)), "0 hours");
+        assert_eq!(format(&ctx, &fmt, Value::Number(-1)), "-1 hour");
+        assert_eq!(format(&ctx, &fmt, Value::Number(3)), "3 hours");
+        // Visible fraction digits make English plural.
+        let value = Value::Decimal { value: 1.0, fraction_digits: 1 };
+        assert_eq!(format(&ctx, &fmt, value), "1.0 hours");
+        assert_eq!(format(&ctx, &fmt, decimal("2.5")), "2.5 hours");
+    }
+
+    #[test]
+    fn locales() {
+        let mut fmt = UnitFormat::new("n", Unit::Kilometer);
+        fmt.width(UnitWidth::FullName);
+        let fr = Context::new(Locale::new("fr").unwrap(), None);
+        // In French, 0 and 1.5 are `one`.
+        assert_eq!(format(&fr, &fmt, Value::Number(0)), "0 kilomètre");
+        assert_eq!(format(&fr, &fmt, decimal("1.5")), "1.5 kilomètre");
+        assert_eq!(format(&fr, &fmt, Value::Number(2)), "2 kilomètres");
+        let mut fmt = UnitFormat::new("n", Unit::Minute);
+        fmt.width(UnitWidth::FullName);
+        let de = Context::locale_chain(&["ja", "de"]).unwrap();
+        assert_eq!(format(&de, &fmt, Value::Number(1)), "1 Minute");
+        assert_eq!(format(&de, &fmt, Value::Number(10)), "10 Minuten");
+        // Units aren't named in Japanese, so English is used.
+        let ja = Context::new(Locale::new("ja").unwrap(), None);
+        assert_eq!(format(&ja, &fmt, Value::Number(1)), "1 minute");
+    }
+
+    #[test]
+    fn skeletons() {
+        let ctx = Context::default();
+        let m = parse("{d, number, unit/kilometer} {d, number, ::unit/meter \
+                       unit-width-full-name group-off} {t, number, ::unit-width-short \
+                       unit/minute}")
+            .unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("d", 1234).arg("t", 1))),
+                   "1,234 km 1234 meters 1 min");
+        let m = parse("{d, number, unit/meter unit-width-full-name}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("d", 1))), "1 meter");
+        assert!(parse("{d, number, ::unit/furlong}").is_err());
+        assert!(parse("{d, number, ::unit-width-full-name}").is_err());
+        assert!(parse("{d, number, ::unit/meter unit/hour}").is_err());
+    }
+
+    #[test]
+    fn strings_are_not_numbers() {
+        let ctx = Context::default();
+        let m = parse("{d, number, unit/meter}").unwrap();
+        assert!(ctx.write(&m, &mut String::new(), Some(&arg("d", "far"))).is_err());
+    }
+}
@@ -10,11 +10,12 @@
 //! The ICU Message Format is widely supported in many languages and
 //! environments.
 //!
-//! This library aims to support all of the ICU Message Format with the
-//! exception of the deprecated `ChoiceFormat`.
+//! This library aims to support all of the ICU Message Format, including
+//! the deprecated `ChoiceFormat` for older messages.
 //!
 //! The important functionality provided here is the [`icu::parse`]
-//! function which generates [`Message`] from a string.
+//! function which generates [`Message`] from a string. [`validate`]
+//! checks a string the same way without building the message.
 //!
 //! # Syntax
 //!
@@ -35,19 +36,212 @@
 //! "Connecting to {application} on {host}..."
 //! ```
 //!
+//! ## Number Messages
+//!
+//! A `number` format writes a numeric value using the number symbols of
+//! the context, grouping the integer digits:
+//!
+//! ```text
+//! "{count, number} downloads"
+//! ```
+//!
+//! The style may be a number skeleton, introduced by `::`. Grouping can
+//! be disabled with the `group-off` stem:
+//!
+//! ```text
+//! "© {year, number, ::group-off}"
+//! ```
+//!
+//! The sign is shown only for negative numbers unless one of the
+//! `sign-always`, `sign-never` or `sign-except-zero` stems is used:
+//!
+//! ```text
+//! "{delta, number, ::sign-except-zero} since yesterday"
+//! ```
+//!
+//! The `negative-parens` stem, or `sign-accounting` as in ICU, puts
+//! negative numbers in parentheses, like `(1,234)`:
+//!
+//! ```text
+//! "Balance: {balance, number, ::negative-parens}"
+//! ```
+//!
+//! As in ICU, the `integer` style rounds numbers half to even to an
+//! integer, and the `percent` style writes them as percentages, like
+//! `25%` for `0.25`, also rounded to an integer. The `currency` style
+//! isn't supported, since the context has no currency:
+//!
+//! ```text
+//! "{progress, number, percent} done, {files, number, integer} files"
+//! ```
+//!
+//! The `scientific` style, or stem, writes a number in scientific
+//! notation, like `1.2345E4`. The `E0` stem does the same with one
+//! `0` for each digit the exponent is padded to, and a stem like `.00`
+//! rounds the mantissa to that many fraction digits:
+//!
+//! ```text
+//! "Mass: {mass, number, ::E00 .000} kg"
+//! ```
+//!
+//! The `padding/` stem pads numbers to a fixed width for aligned
+//! output. A width alone, like `padding/3`, pads with spaces before the
+//! sign, as in `  5`, and a `0` before the width, like `padding/03`,
+//! pads with zeros after the sign, as in `-05`. Any other character
+//! before the width pads with that character before the sign:
+//!
+//! ```text
+//! "{id, number, ::padding/05 group-off} {size, number, ::padding/*8}"
+//! ```
+//!
+//! A `unit/` stem, such as `unit/kilometer`, writes the number with a
+//! unit of measurement, like `5 km`, and the `::` may be left out. The
+//! `unit-width-full-name` stem names the unit instead, in the plural
+//! form for the number, like `5 kilometers`. The `meter`, `kilometer`,
+//! `hour` and `minute` units are supported:
+//!
+//! ```text
+//! "{distance, number, ::unit/kilometer unit-width-full-name} to go"
+//! ```
+//!
+//! The `spellout` and `spellout-ordinal` styles write numbers from 0 to
+//! 999 in English words, like `twenty-one` and `twenty-first`:
+//!
+//! ```text
+//! "You are {place, number, spellout-ordinal} in line."
+//! ```
+//!
+//! ## Duration Messages
+//!
+//! A `duration` format writes a number of seconds as hours, minutes and
+//! seconds, like `1:23:45`. The `units` style uses abbreviated units of
+//! the locale instead, like `1 hr 23 min 45 sec`:
+//!
+//! ```text
+//! "Played for {secs, duration, units}"
+//! ```
+//!
+//! ## Relative Time Messages
+//!
+//! A `relativetime` format writes a signed number of a unit of time
+//! from now, like `in 3 days` or `3 days ago`, and `today` for zero.
+//! The units are `second`, `minute`, `hour`, `day`, `week`, `month`
+//! and `year`, and `numeric` after the unit writes zero as `in 0 days`:
+//!
+//! ```text
+//! "Due {delta, relativetime, day}"
+//! ```
+//!
 //! ## Pluralized Messages
 //!
-//! Parsing of `plural` messages is not yet supported.
+//! A `plural` message selects a branch based on the plural category
+//! of a numeric value. Exact values can be matched with `=` literals,
+//! and an `other` branch is required. Within a branch, `#` is replaced
+//! by the value:
+//!
+//! ```text
+//! "{count, plural, =0 {No results.} one {# result.} other {# results.}}"
+//! ```
+//!
+//! As in ICU, `#` is only a placeholder when it is directly within a
+//! plural branch. Elsewhere, including within a `select` nested inside
+//! a plural branch, it is plain text. Use [`parse_with_warnings`] to
+//! find these.
+//!
+//! A branch may be empty, like `one {}`, and formats as nothing, as
+//! does an empty message.
+//!
+//! An `offset:` is subtracted from the value before selecting a branch
+//! and before it is output for `#`:
+//!
+//! ```text
+//! "{count, plural, offset:1 =0 {Nobody} one {You and # other} other {You and # others}}"
+//! ```
+//!
+//! A `selectordinal` message is like a `plural` message, but uses the
+//! ordinal plural rules of the locale:
+//!
+//! ```text
+//! "{place, selectordinal, one {#st} two {#nd} few {#rd} other {#th}} place"
+//! ```
 //!
 //! ## Select Messages
 //!
-//! Parsing of `select` messages is not yet supported.
+//! A `select` message selects a branch based on a string value, with
+//! the required `other` branch used when no other branch matches:
+//!
+//! ```text
+//! "{gender, select, female {She} male {He} other {They}} replied."
+//! ```
+//!
+//! An integer value is selected by its decimal form, which selects on
+//! exact numbers rather than plural categories:
+//!
+//! ```text
+//! "{level, select, 1 {Beginner} 2 {Expert} other {Level {level}}}"
+//! ```
+//!
+//! Branches can contain other formats, so a chain of fallbacks can be
+//! written by nesting a `select` in the `other` branch of another:
+//!
+//! ```text
+//! "{os, select, mac {⌘C} other {{desktop, select, kde {Ctrl+Ins} other {Ctrl+C}}}}"
+//! ```
+//!
+//! Formats can be nested up to [`DEFAULT_MAX_DEPTH`] deep, which a
+//! [`Parser`] can change. Formatting is limited the same way, by
+//! `ContextBuilder::max_depth`, for messages that weren't parsed.
+//!
+//! ## Choice Messages
+//!
+//! A `choice` message is the deprecated ICU `ChoiceFormat`. It selects
+//! the last choice whose limit the value meets, where `#` means at
+//! least the limit and `<` means greater than it:
+//!
+//! ```text
+//! "{count, choice, 0#No files|1#One file|1<{count, number} files}"
+//! ```
+//!
+//! Within a choice, `#` is plain text.
+//!
+//! ## Comments
+//!
+//! Notes for translators can be written as `{! note }` anywhere that a
+//! format can be. They aren't output, and are removed from the parsed
+//! message unless a [`Parser`] is set to keep them:
+//!
+//! ```text
+//! "{! Shown in the toolbar }Share {count, plural, one {# photo} other {# photos}}"
+//! ```
+//!
+//! ## Quoting
+//!
+//! As in ICU, an apostrophe before a character that would otherwise be
+//! syntax, like `{` or `}`, or `#` within a plural, starts quoted text,
+//! which is plain text up through the next apostrophe. Two apostrophes
+//! are written as one, and any other apostrophe is plain text:
+//!
+//! ```text
+//! "Don't write '{'braces'}' in {name}''s messages."
+//! ```
+//!
+//! Within a choice, `|` is quoted the same way.
+//!
+//! ## Custom Formats
+//!
+//! Other format types, like `{size, filesize}`, can be added by
+//! registering a factory for them with a [`Parser`].
 //!
 //! [`icu::parse`]: fn.parse.html
+//! [`parse_with_warnings`]: fn.parse_with_warnings.html
+//! [`validate`]: fn.validate.html
+//! [`Parser`]: struct.Parser.html
+//! [`DEFAULT_MAX_DEPTH`]: constant.DEFAULT_MAX_DEPTH.html
 //! [`Message`]: ../struct.Message.html
 //! [ICU-style message formatting]: http://userguide.icu-project.org/formatparse/messages
 
 pub mod ast;
 mod parse;
 
-pub use self::parse::parse;
+pub use self::parse::{parse, parse_with_warnings, validate, FormatFactory, ParseError,
+                      ParseErrorKind, ParseWarning, Parser, DEFAULT_MAX_DEPTH};
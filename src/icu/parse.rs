@@ -4,111 +4,1153 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::boxed::Box;
+use std::collections::BTreeMap;
 use std::error::Error;
 use std::fmt;
-use std::str;
-
-use nom::{IResult, multispace};
+use std::string::{String, ToString};
+use std::sync::Arc;
+use std::vec::Vec;
 
 use super::ast;
-use {Message, MessagePart};
+use {Message, MessagePart, PluralCategory};
+
+/// An error resulting from `parse`, with where it was found.
+///
+/// The position is given both as a byte offset and in UTF-16 code
+/// units, which is how JavaScript and many editors count characters:
+///
+/// ```
+/// use message_format::icu::{parse, ParseErrorKind};
+///
+/// let error = parse("Größe: {size").unwrap_err();
+/// assert_eq!(error.kind, ParseErrorKind::UnexpectedEnd);
+/// assert_eq!((error.offset, error.utf16_offset), (14, 12));
+/// ```
+#[derive(Clone,Debug,PartialEq)]
+pub struct ParseError {
+    /// What is wrong with the message.
+    pub kind: ParseErrorKind,
+    /// The byte offset of the error in the message.
+    pub offset: usize,
+    /// The offset of the error in UTF-16 code units.
+    pub utf16_offset: usize,
+}
+
+impl Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "At offset {}: {}", self.offset, self.kind)
+    }
+}
 
-/// An error resulting from `parse`.
-#[derive(Clone,Debug)]
-pub enum ParseError {
-    /// The message could not be parsed.
+/// The kinds of [`ParseError`].
+///
+/// [`ParseError`]: struct.ParseError.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum ParseErrorKind {
+    /// The message uses a format type that is not implemented.
     NotImplemented,
+    /// The message ended before a format was closed.
+    UnexpectedEnd,
+    /// A character was found where it isn't allowed.
+    UnexpectedCharacter(char),
+    /// A `plural` or `select` branch has an invalid selector.
+    InvalidSelector(String),
+    /// A `plural` or `select` format has more than one branch with the
+    /// selector.
+    DuplicateSelector(String),
+    /// A number, like an `offset:` or an `=` literal, is invalid.
+    InvalidNumber(String),
+    /// A `plural`, `selectordinal` or `select` format has no `other`
+    /// branch, which is used when no other branch matches.
+    MissingOtherBranch,
+    /// Formats are nested more deeply than the parser allows. See
+    /// [`Parser::max_depth`].
+    ///
+    /// [`Parser::max_depth`]: struct.Parser.html#method.max_depth
+    TooDeep,
 }
 
-impl Error for ParseError {
-    fn description(&self) -> &str {
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match *self {
-            ParseError::NotImplemented => "Not implemented.",
+            ParseErrorKind::NotImplemented => "Not implemented.".fmt(f),
+            ParseErrorKind::UnexpectedEnd => "Unexpected end of message.".fmt(f),
+            ParseErrorKind::UnexpectedCharacter(ch) => {
+                write!(f, "Unexpected character '{}'.", ch)
+            }
+            ParseErrorKind::InvalidSelector(ref selector) => {
+                write!(f, "Invalid selector '{}'.", selector)
+            }
+            ParseErrorKind::DuplicateSelector(ref selector) => {
+                write!(f, "Duplicate selector '{}'.", selector)
+            }
+            ParseErrorKind::InvalidNumber(ref number) => {
+                write!(f, "Invalid number '{}'.", number)
+            }
+            ParseErrorKind::MissingOtherBranch => "Missing 'other' branch.".fmt(f),
+            ParseErrorKind::TooDeep => "Formats are nested too deeply.".fmt(f),
         }
     }
 }
 
-impl fmt::Display for ParseError {
+/// A possible problem found by [`parse_with_warnings`]. These don't
+/// prevent a message from being parsed.
+///
+/// [`parse_with_warnings`]: fn.parse_with_warnings.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum ParseWarning {
+    /// A `#` that is not directly within a plural branch, so it is
+    /// plain text rather than a placeholder. This holds the byte
+    /// offset of the `#`.
+    LiteralHash(usize),
+}
+
+impl fmt::Display for ParseWarning {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        self.description().fmt(f)
-    }
-}
-
-/// Given a name, create a `SimpleFormat`.
-fn mk_simple(name: &str) -> Box<MessagePart> {
-    Box::new(ast::SimpleFormat::new(name))
-}
-
-/// This grabs the variable name from a format, which is
-/// the first thing after the '{' and extends to the first
-/// ',' or '}'.
-///
-/// '{name}' has a variable name of 'name'.
-named!(variable_name <&str, &str>, is_not_s!(",}"));
-
-/// A simple format has only a name, delimited by braces.
-named!(simple_format <&str, Box<MessagePart> >,
-    map!(
-        delimited!(
-            tag_s!("{"),
-            variable_name,
-            tag_s!("}")),
-        mk_simple));
-
-named!(plural_format <&str, Box<MessagePart> >,
-    delimited!(
-        tag_s!("{"),
-        chain!(
-            name: variable_name ~
-            tag_s!(",") ~ opt!(multispace) ~
-            tag_s!("plural") ~ opt!(multispace),
-            || Box::new(ast::SimpleFormat::new(name))),
-        tag_s!("}")));
-
-named!(select_format <&str, Box<MessagePart> >,
-    delimited!(
-        tag_s!("{"),
-        chain!(
-            name: variable_name ~
-            tag_s!(",") ~ opt!(multispace) ~
-            tag_s!("select") ~ opt!(multispace),
-            || Box::new(ast::SimpleFormat::new(name))),
-        tag_s!("}")));
-
-/// Plain text extends up through to the start of the next format
-/// block.
-named!(plain_text <&str, Box<MessagePart> >,
-    map!(is_not_s!("{"), |text| Box::new(ast::PlainText::new(text))));
-
-/// Message parts must be 1 of the various part types. And there must
-/// be at least one of them for now.
-named!(message_parts <&str, Vec<Box<MessagePart> > >,
-    many1!(
-        alt!(call!(simple_format) |
-             call!(plural_format) |
-             call!(select_format) |
-             call!(plain_text))));
-
-/// Given a set of `MessagePart`s, create a `Message`.
-named!(pub message_parser <&str, Message>,
-    map!(message_parts, Message::new));
+        match *self {
+            ParseWarning::LiteralHash(offset) => {
+                write!(f, "'#' at offset {} is outside of a plural and is plain text.", offset)
+            }
+        }
+    }
+}
 
 /// Parse some text and hopefully return a [`Message`].
 ///
+/// This only supports the built-in format types. Use a [`Parser`] to
+/// add others.
+///
 /// [`Message`]: ../struct.Message.html
+/// [`Parser`]: struct.Parser.html
 pub fn parse(message: &str) -> Result<Message, ParseError> {
-    match message_parser(message) {
-        IResult::Error(_) |
-        IResult::Incomplete(_) => Err(ParseError::NotImplemented),
-        IResult::Done(_, m) => Ok(m),
+    Parser::new().parse(message)
+}
+
+/// Parse some text like [`parse`], also returning any warnings about
+/// text that is probably not what was intended.
+///
+/// ```
+/// use message_format::icu::{parse_with_warnings, ParseWarning};
+///
+/// let (_, warnings) = parse_with_warnings("{n, select, other {# items}}").unwrap();
+/// assert_eq!(warnings, [ParseWarning::LiteralHash(19)]);
+/// ```
+///
+/// [`parse`]: fn.parse.html
+pub fn parse_with_warnings(message: &str) -> Result<(Message, Vec<ParseWarning>), ParseError> {
+    Parser::new().parse_with_warnings(message)
+}
+
+/// Check that some text would [`parse`], without building the
+/// message. This reports the same error as `parse`, and is much faster
+/// for checking many messages, as in a lint of a catalog.
+///
+/// ```
+/// use message_format::icu::{validate, ParseErrorKind};
+///
+/// assert!(validate("{count, plural, one {# file} other {# files}}").is_ok());
+/// let error = validate("{count, plural, one {# file}}").unwrap_err();
+/// assert_eq!(error.kind, ParseErrorKind::MissingOtherBranch);
+/// ```
+///
+/// [`parse`]: fn.parse.html
+pub fn validate(message: &str) -> Result<(), ParseError> {
+    Parser::new().validate(message)
+}
+
+/// Create a [`MessagePart`] for a custom format type from the tokens of
+/// the format: its variable name, type and style, if any.
+///
+/// [`MessagePart`]: ../trait.MessagePart.html
+pub type FormatFactory = dyn Fn(&[&str]) -> Box<dyn MessagePart>;
+
+type Formats = BTreeMap<String, Box<FormatFactory>>;
+
+/// The default for [`Parser::max_depth`].
+///
+/// [`Parser::max_depth`]: struct.Parser.html#method.max_depth
+pub const DEFAULT_MAX_DEPTH: usize = 64;
+
+/// A parser that can be extended with custom format types.
+///
+/// ```
+/// use std::fmt;
+/// use message_format::{arg, Args, Context, FormatError, MessagePart, Value};
+/// use message_format::icu::Parser;
+///
+/// /// Format a number of bytes as kilobytes.
+/// #[derive(Debug)]
+/// struct FileSize(String);
+///
+/// impl MessagePart for FileSize {
+///     fn apply_format(&self,
+///                     ctx: &Context,
+///                     stream: &mut dyn fmt::Write,
+///                     args: Option<&Args>)
+///                     -> Result<(), FormatError> {
+///         match *ctx.lookup_arg(args, &self.0)?.value() {
+///             Value::Number(n) => Ok(write!(stream, "{} KB", n / 1024)?),
+///             _ => Err(FormatError::InvalidArgumentType(self.0.clone())),
+///         }
+///     }
+/// }
+///
+/// let mut parser = Parser::new();
+/// parser.register("filesize", |tokens| Box::new(FileSize(tokens[0].to_string())));
+/// let m = parser.parse("Saved {size, filesize}.").unwrap();
+/// let ctx = Context::default();
+/// assert_eq!(ctx.format(&m, Some(&arg("size", 2048))), "Saved 2 KB.");
+/// ```
+pub struct Parser {
+    formats: Formats,
+    max_depth: usize,
+    keep_comments: bool,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Parser {
+            formats: BTreeMap::new(),
+            max_depth: DEFAULT_MAX_DEPTH,
+            keep_comments: false,
+        }
+    }
+}
+
+impl Parser {
+    /// Construct a `Parser` for the built-in format types.
+    pub fn new() -> Self {
+        Parser::default()
+    }
+
+    /// Set how deeply formats may be nested, as when a `select` is in
+    /// a branch of another `select`. Deeper messages fail to parse with
+    /// `ParseErrorKind::TooDeep`, which guards against untrusted input
+    /// that would otherwise exhaust the stack. The default is
+    /// [`DEFAULT_MAX_DEPTH`].
+    ///
+    /// ```
+    /// use message_format::icu::{ParseErrorKind, Parser};
+    ///
+    /// let mut parser = Parser::new();
+    /// parser.max_depth(2);
+    /// assert!(parser.parse("{a, select, other {{b}}}").is_ok());
+    /// let error = parser.parse("{a, select, other {{b, select, other {{c}}}}}").unwrap_err();
+    /// assert_eq!((error.kind, error.offset), (ParseErrorKind::TooDeep, 38));
+    /// ```
+    ///
+    /// [`DEFAULT_MAX_DEPTH`]: constant.DEFAULT_MAX_DEPTH.html
+    pub fn max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
+    /// Register a `factory` for a custom format type, like `filesize`
+    /// in `{size, filesize, short}`. It is given the tokens of each
+    /// format of the type, such as `["size", "filesize", "short"]`.
+    ///
+    /// A custom format type replaces a built-in type of the same name.
+    /// The style of a custom format can't contain braces.
+    pub fn register<F>(&mut self, format_type: &str, factory: F)
+        where F: Fn(&[&str]) -> Box<dyn MessagePart> + 'static
+    {
+        self.formats.insert(format_type.to_string(), Box::new(factory));
+    }
+
+    /// Set whether `{! comments }` are kept in parsed messages as
+    /// [`ast::Comment`] parts, so that they can be found with
+    /// [`Message::comments`]. Either way, comments aren't output. By
+    /// default, they are removed.
+    ///
+    /// [`ast::Comment`]: ast/struct.Comment.html
+    /// [`Message::comments`]: ../struct.Message.html#method.comments
+    pub fn keep_comments(&mut self, keep_comments: bool) {
+        self.keep_comments = keep_comments;
+    }
+
+    /// Parse some text and hopefully return a [`Message`].
+    ///
+    /// [`Message`]: ../struct.Message.html
+    pub fn parse(&self, message: &str) -> Result<Message, ParseError> {
+        MessageParser::new(message, self).parse()
+    }
+
+    /// Parse some text like [`parse`], also returning any warnings.
+    ///
+    /// [`parse`]: #method.parse
+    pub fn parse_with_warnings(&self,
+                               message: &str)
+                               -> Result<(Message, Vec<ParseWarning>), ParseError> {
+        let mut p = MessageParser::new(message, self);
+        let message = p.parse()?;
+        Ok((message, p.warnings))
+    }
+
+    /// Check that some text would [`parse`], without building the
+    /// message, as with [`icu::validate`]. Custom format types are
+    /// recognized, but their factories aren't called.
+    ///
+    /// [`parse`]: #method.parse
+    /// [`icu::validate`]: fn.validate.html
+    pub fn validate(&self, message: &str) -> Result<(), ParseError> {
+        MessageParser::new(message, self).validate()
+    }
+}
+
+impl fmt::Debug for Parser {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut formats = self.formats.keys().collect::<Vec<_>>();
+        formats.sort();
+        f.debug_struct("Parser")
+            .field("formats", &formats)
+            .field("max_depth", &self.max_depth)
+            .field("keep_comments", &self.keep_comments)
+            .finish()
+    }
+}
+
+/// Sets the options of a `NumberFormat` for a named style.
+type NumberPreset = fn(&mut ast::NumberFormat);
+
+/// The named styles of a `number` format, like `{n, number, integer}`,
+/// with the options that they set.
+const NUMBER_PRESETS: &[(&str, NumberPreset)] = &[
+    ("integer", |fmt| fmt.maximum_fraction_digits(Some(0))),
+    ("percent", |fmt| {
+        fmt.percent(true);
+        fmt.maximum_fraction_digits(Some(0));
+    }),
+    ("scientific", |fmt| {
+        fmt.notation(ast::Notation::Scientific {
+            exponent_digits: 1,
+            fraction_digits: None,
+        })
+    }),
+];
+
+/// The number of zeros in a skeleton stem like `E00` or `.000` that is
+/// a `prefix` followed by zeros.
+fn zero_digits(stem: &str, prefix: char) -> Option<u8> {
+    let zeros = stem.strip_prefix(prefix)?;
+    if zeros.is_empty() || zeros.len() > u8::MAX as usize || zeros.bytes().any(|b| b != b'0') {
+        return None;
+    }
+    Some(zeros.len() as u8)
+}
+
+/// The padding of a skeleton stem like `padding/3`, `padding/03` or
+/// `padding/*6`, which is a character other than a digit, or `0`, and
+/// the width to pad to.
+fn padding(stem: &str) -> Option<ast::Padding> {
+    let spec = stem.strip_prefix("padding/")?;
+    let first = spec.chars().next()?;
+    let (character, width) = match first {
+        '1'..='9' => (' ', spec),
+        _ => (first, &spec[first.len_utf8()..]),
+    };
+    if width.is_empty() || width.bytes().any(|b| !b.is_ascii_digit()) {
+        return None;
+    }
+    Some(ast::Padding {
+        width: width.parse().ok()?,
+        character,
+        position: if character == '0' {
+            ast::PadPosition::AfterSign
+        } else {
+            ast::PadPosition::BeforeSign
+        },
+    })
+}
+
+/// Characters that end an identifier, like a variable name, format
+/// type or selector.
+fn is_syntax(ch: char) -> bool {
+    ch.is_whitespace() || "{}#,:=".contains(ch)
+}
+
+struct MessageParser<'a> {
+    source: &'a str,
+    /// The custom format types that have been registered.
+    formats: &'a Formats,
+    max_depth: usize,
+    keep_comments: bool,
+    /// How many formats enclose the current position.
+    depth: usize,
+    pos: usize,
+    /// Branch messages that have been parsed, keyed by their source
+    /// text and whether they are within a plural, so that identical
+    /// branches can share a message.
+    branches: BTreeMap<(bool, &'a str), Arc<Message>>,
+    warnings: Vec<ParseWarning>,
+}
+
+impl<'a> MessageParser<'a> {
+    fn new(source: &'a str, parser: &'a Parser) -> MessageParser<'a> {
+        MessageParser {
+            source,
+            formats: &parser.formats,
+            max_depth: parser.max_depth,
+            keep_comments: parser.keep_comments,
+            depth: 0,
+            pos: 0,
+            branches: BTreeMap::new(),
+            warnings: vec![],
+        }
+    }
+
+    fn ch(&self) -> Option<char> {
+        self.source[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) {
+        if let Some(ch) = self.ch() {
+            self.pos += ch.len_utf8();
+        }
+    }
+
+    fn ch_is(&self, ch: char) -> bool {
+        self.ch() == Some(ch)
+    }
+
+    fn get_ws(&mut self) {
+        while self.ch().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    /// An error at a byte `offset` in the source.
+    fn error_at(&self, kind: ParseErrorKind, offset: usize) -> ParseError {
+        ParseError {
+            kind,
+            offset,
+            utf16_offset: self.source[..offset].encode_utf16().count(),
+        }
+    }
+
+    /// An error for `text`, which is a slice of the source.
+    fn error_in(&self, kind: ParseErrorKind, text: &str) -> ParseError {
+        self.error_at(kind, text.as_ptr() as usize - self.source.as_ptr() as usize)
+    }
+
+    /// The error to report for the current character.
+    fn unexpected(&self) -> ParseError {
+        let kind = match self.ch() {
+            Some(ch) => ParseErrorKind::UnexpectedCharacter(ch),
+            None => ParseErrorKind::UnexpectedEnd,
+        };
+        self.error_at(kind, self.pos)
+    }
+
+    fn expect(&mut self, ch: char) -> Result<(), ParseError> {
+        if self.ch_is(ch) {
+            self.bump();
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn parse(&mut self) -> Result<Message, ParseError> {
+        let message = self.get_message(false)?;
+        if self.ch().is_some() {
+            return Err(self.unexpected());
+        }
+        Ok(message)
+    }
+
+    fn validate(&mut self) -> Result<(), ParseError> {
+        self.skip_message(false)?;
+        if self.ch().is_some() {
+            return Err(self.unexpected());
+        }
+        Ok(())
+    }
+
+    /// Parse message parts up through the end of the input or a
+    /// closing `}`, which is left for the caller.
+    ///
+    /// Within a plural branch, `#` is a placeholder for the value.
+    fn get_message(&mut self, in_plural: bool) -> Result<Message, ParseError> {
+        let mut parts: Vec<Box<dyn MessagePart>> = vec![];
+        loop {
+            match self.ch() {
+                None | Some('}') => break,
+                Some('{') if self.at_comment() => parts.extend(self.get_comment()?),
+                Some('{') => parts.push(self.get_format()?),
+                Some('#') if in_plural => {
+                    self.bump();
+                    parts.push(Box::new(ast::PlaceholderFormat::new()));
+                }
+                Some(_) => parts.push(Box::new(ast::PlainText::new(&self.get_text(in_plural)))),
+            }
+        }
+        Ok(Message::new(parts))
+    }
+
+    /// Check the parts of a message like `get_message`, without
+    /// building them.
+    fn skip_message(&mut self, in_plural: bool) -> Result<(), ParseError> {
+        loop {
+            match self.ch() {
+                None | Some('}') => return Ok(()),
+                Some('{') if self.at_comment() => {
+                    self.get_comment_text()?;
+                }
+                Some('{') => self.nested(Self::skip_format_contents)?,
+                Some('#') if in_plural => self.bump(),
+                Some(_) => {
+                    self.get_text(in_plural);
+                }
+            }
+        }
+    }
+
+    /// Plain text extends up through to the start of the next format
+    /// block or the end of the enclosing one. Apostrophes quote syntax
+    /// characters, as described by `get_apostrophe`.
+    fn get_text(&mut self, in_plural: bool) -> String {
+        let special = |ch| ch == '{' || ch == '}' || in_plural && ch == '#';
+        let mut text = String::new();
+        while let Some(ch) = self.ch() {
+            match ch {
+                '\'' => {
+                    self.get_apostrophe(special, &mut text);
+                    continue;
+                }
+                _ if special(ch) => break,
+                '#' => self.warnings.push(ParseWarning::LiteralHash(self.pos)),
+                _ => {}
+            }
+            text.push(ch);
+            self.bump();
+        }
+        text
+    }
+
+    /// Add the text for the apostrophe at the current position to
+    /// `text`. As in ICU, two apostrophes are one, and an apostrophe
+    /// before a character that is `special` where it is starts quoted
+    /// text, which is plain text up through the next single apostrophe,
+    /// or the end of the message if there isn't one. Any other
+    /// apostrophe is itself.
+    fn get_apostrophe<F>(&mut self, special: F, text: &mut String)
+        where F: Fn(char) -> bool
+    {
+        self.bump();
+        match self.ch() {
+            Some('\'') => {
+                self.bump();
+                text.push('\'');
+            }
+            Some(ch) if special(ch) => {
+                while let Some(ch) = self.ch() {
+                    self.bump();
+                    if ch == '\'' {
+                        if !self.ch_is('\'') {
+                            return;
+                        }
+                        self.bump();
+                    }
+                    text.push(ch);
+                }
+            }
+            _ => text.push('\''),
+        }
+    }
+
+    fn at_comment(&self) -> bool {
+        self.source[self.pos..].starts_with("{!")
+    }
+
+    /// A comment is text between `{!` and the next `}`, with the
+    /// surrounding whitespace removed. It is only kept as a part if the
+    /// parser keeps comments.
+    fn get_comment(&mut self) -> Result<Option<Box<dyn MessagePart>>, ParseError> {
+        let text = self.get_comment_text()?;
+        if self.keep_comments {
+            Ok(Some(Box::new(ast::Comment::new(text))))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn get_comment_text(&mut self) -> Result<&'a str, ParseError> {
+        self.pos += "{!".len();
+        let start = self.pos;
+        while self.ch().is_some_and(|ch| ch != '}') {
+            self.bump();
+        }
+        let text = self.source[start..self.pos].trim();
+        self.expect('}')?;
+        Ok(text)
+    }
+
+    fn get_identifier(&mut self) -> Result<&'a str, ParseError> {
+        let start = self.pos;
+        while let Some(ch) = self.ch() {
+            if is_syntax(ch) {
+                break;
+            }
+            self.bump();
+        }
+        if start == self.pos {
+            return Err(self.unexpected());
+        }
+        Ok(&self.source[start..self.pos])
+    }
+
+    fn get_number(&mut self) -> Result<i64, ParseError> {
+        let start = self.pos;
+        if self.ch_is('-') {
+            self.bump();
+        }
+        while self.ch().is_some_and(|ch| ch.is_ascii_digit()) {
+            self.bump();
+        }
+        let number = &self.source[start..self.pos];
+        number.parse()
+            .map_err(|_| self.error_in(ParseErrorKind::InvalidNumber(number.to_string()), number))
+    }
+
+    /// A format is delimited by braces and has a variable name,
+    /// optionally followed by a format type and style.
+    ///
+    /// `{name}` is a simple format.
+    fn get_format(&mut self) -> Result<Box<dyn MessagePart>, ParseError> {
+        self.nested(Self::get_format_contents)
+    }
+
+    /// Parse a format with `f`, one level deeper.
+    fn nested<T, F>(&mut self, f: F) -> Result<T, ParseError>
+        where F: FnOnce(&mut Self) -> Result<T, ParseError>
+    {
+        if self.depth == self.max_depth {
+            return Err(self.error_at(ParseErrorKind::TooDeep, self.pos));
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn get_format_contents(&mut self) -> Result<Box<dyn MessagePart>, ParseError> {
+        self.expect('{')?;
+        self.get_ws();
+        let name = self.get_identifier()?;
+        self.get_ws();
+        if self.ch_is('}') {
+            self.bump();
+            return Ok(Box::new(ast::SimpleFormat::new(name)));
+        }
+        self.expect(',')?;
+        self.get_ws();
+        let format_type = self.get_identifier()?;
+        if let Some(factory) = self.formats.get(format_type) {
+            let mut tokens = vec![name, format_type];
+            if self.get_style_start()? {
+                tokens.push(self.get_style_text());
+            }
+            self.expect('}')?;
+            return Ok(factory(&tokens));
+        }
+        let part: Box<dyn MessagePart> = match format_type {
+            "number" => self.get_number_format(name)?,
+            "duration" => Box::new(self.get_duration_format(name)?),
+            "relativetime" => Box::new(self.get_relative_time_format(name)?),
+            "plural" => Box::new(self.get_plural_format(name)?),
+            "selectordinal" => {
+                let mut fmt = self.get_plural_format(name)?;
+                fmt.ordinal(true);
+                Box::new(fmt)
+            }
+            "select" => Box::new(self.get_select_format(name)?),
+            "choice" => Box::new(self.get_choice_format(name)?),
+            _ => return Err(self.error_in(ParseErrorKind::NotImplemented, format_type)),
+        };
+        self.get_ws();
+        self.expect('}')?;
+        Ok(part)
+    }
+
+    /// Check a format like `get_format_contents`. Only the formats
+    /// that have messages within them are skipped without being built.
+    fn skip_format_contents(&mut self) -> Result<(), ParseError> {
+        self.expect('{')?;
+        self.get_ws();
+        let name = self.get_identifier()?;
+        self.get_ws();
+        if self.ch_is('}') {
+            self.bump();
+            return Ok(());
+        }
+        self.expect(',')?;
+        self.get_ws();
+        let format_type = self.get_identifier()?;
+        if self.formats.contains_key(format_type) {
+            if self.get_style_start()? {
+                self.get_style_text();
+            }
+            return self.expect('}');
+        }
+        match format_type {
+            "number" => {
+                self.get_number_format(name)?;
+            }
+            "duration" => {
+                self.get_duration_format(name)?;
+            }
+            "relativetime" => {
+                self.get_relative_time_format(name)?;
+            }
+            "plural" | "selectordinal" => {
+                if self.get_style_start()? {
+                    self.get_plural_offset()?;
+                }
+                self.skip_branches(true)?;
+            }
+            "select" => {
+                self.get_style_start()?;
+                self.skip_branches(false)?;
+            }
+            "choice" => self.skip_choices()?,
+            _ => return Err(self.error_in(ParseErrorKind::NotImplemented, format_type)),
+        }
+        self.get_ws();
+        self.expect('}')
+    }
+
+    /// The style of a format is optional. If present, this consumes
+    /// the `,` that introduces it and returns `true`.
+    fn get_style_start(&mut self) -> Result<bool, ParseError> {
+        self.get_ws();
+        if self.ch_is('}') {
+            return Ok(false);
+        }
+        self.expect(',')?;
+        self.get_ws();
+        Ok(true)
+    }
+
+    /// Parse the message for a branch, delimited by braces. Branches
+    /// with identical text share the same message.
+    fn get_branch_message(&mut self, in_plural: bool) -> Result<Arc<Message>, ParseError> {
+        self.expect('{')?;
+        let start = self.pos;
+        let message = self.get_message(in_plural)?;
+        let text = &self.source[start..self.pos];
+        self.expect('}')?;
+        Ok(self.branches.entry((in_plural, text)).or_insert_with(|| Arc::new(message)).clone())
+    }
+
+    /// Check the branches of a `plural` or `select` format like
+    /// `get_branches`, followed by the checks of the selectors that are
+    /// made when the format is built.
+    fn skip_branches(&mut self, in_plural: bool) -> Result<(), ParseError> {
+        let mut selectors = vec![];
+        let mut invalid = None;
+        while !self.ch_is('}') {
+            let selector = self.get_selector(in_plural, &selectors)?;
+            if let Selector::Keyword(keyword) = selector {
+                if in_plural && invalid.is_none() && keyword != "other" &&
+                   plural_category(keyword).unwrap_or(PluralCategory::Other) ==
+                   PluralCategory::Other {
+                    invalid = Some(keyword);
+                }
+            }
+            selectors.push(selector);
+            self.get_ws();
+            self.expect('{')?;
+            self.skip_message(in_plural)?;
+            self.expect('}')?;
+            self.get_ws();
+        }
+        if !selectors.contains(&Selector::Keyword("other")) {
+            return Err(self.error_at(ParseErrorKind::MissingOtherBranch, self.pos));
+        }
+        match invalid {
+            Some(keyword) => {
+                let kind = ParseErrorKind::InvalidSelector(keyword.to_string());
+                Err(self.error_in(kind, keyword))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Parse the branches of a `plural` or `select` format, each
+    /// being a selector followed by a message in braces.
+    fn get_branches(&mut self,
+                    in_plural: bool)
+                    -> Result<Vec<(Selector<'a>, Arc<Message>)>, ParseError> {
+        let mut selectors = vec![];
+        let mut messages = vec![];
+        while !self.ch_is('}') {
+            selectors.push(self.get_selector(in_plural, &selectors)?);
+            self.get_ws();
+            messages.push(self.get_branch_message(in_plural)?);
+            self.get_ws();
+        }
+        Ok(selectors.into_iter().zip(messages).collect())
+    }
+
+    /// The selector of a branch, which is an `=` literal within a
+    /// plural or a keyword. It is an error for it to be one of the
+    /// `previous` selectors of the format.
+    fn get_selector(&mut self,
+                    in_plural: bool,
+                    previous: &[Selector<'a>])
+                    -> Result<Selector<'a>, ParseError> {
+        let start = self.pos;
+        let selector = if in_plural && self.ch_is('=') {
+            self.bump();
+            Selector::Literal(self.get_number()?)
+        } else {
+            Selector::Keyword(self.get_identifier()?)
+        };
+        if previous.contains(&selector) {
+            let text = &self.source[start..self.pos];
+            return Err(self.error_in(ParseErrorKind::DuplicateSelector(text.to_string()), text));
+        }
+        Ok(selector)
+    }
+
+    /// Remove the `other` branch, which is the fallback for both `plural`
+    /// and `select` formats and is required. A missing branch is
+    /// reported at the end of the branches.
+    fn take_other(&self,
+                  branches: &mut Vec<(Selector, Arc<Message>)>)
+                  -> Result<Arc<Message>, ParseError> {
+        match branches.iter().position(|(selector, _)| *selector == Selector::Keyword("other")) {
+            Some(index) => Ok(branches.remove(index).1),
+            None => Err(self.error_at(ParseErrorKind::MissingOtherBranch, self.pos)),
+        }
+    }
+
+    /// The style of a simple format, like a `number`, is text up through
+    /// the closing `}`, with surrounding whitespace removed.
+    fn get_style_text(&mut self) -> &'a str {
+        let start = self.pos;
+        while let Some(ch) = self.ch() {
+            if ch == '}' {
+                break;
+            }
+            self.bump();
+        }
+        self.source[start..self.pos].trim()
+    }
+
+    /// Parse the style of a `number` format, which is a number skeleton,
+    /// one of the `NUMBER_PRESETS` or one of the `spellout` styles. A skeleton with a
+    /// `unit/` stem makes a `UnitFormat`, and may leave out the `::`.
+    fn get_number_format(&mut self, name: &str) -> Result<Box<dyn MessagePart>, ParseError> {
+        let mut fmt = ast::NumberFormat::new(name);
+        let mut unit = None;
+        let mut width = None;
+        let mut exponent_digits = None;
+        let mut fraction_digits = None;
+        if self.get_style_start()? {
+            let style = self.get_style_text();
+            if style == "spellout" || style == "spellout-ordinal" {
+                let mut fmt = ast::SpelloutFormat::new(name);
+                fmt.ordinal(style == "spellout-ordinal");
+                return Ok(Box::new(fmt));
+            }
+            if let Some(&(_, preset)) = NUMBER_PRESETS.iter().find(|&&(n, _)| n == style) {
+                preset(&mut fmt);
+                return Ok(Box::new(fmt));
+            }
+            let skeleton = match style.strip_prefix("::") {
+                Some(skeleton) => skeleton,
+                None if style.starts_with("unit/") => style,
+                None => return Err(self.error_in(ParseErrorKind::NotImplemented, style)),
+            };
+            for stem in skeleton.split_whitespace() {
+                match stem {
+                    "group-off" => fmt.grouping(false),
+                    "sign-auto" => fmt.sign_display(ast::SignDisplay::Auto),
+                    "sign-always" => fmt.sign_display(ast::SignDisplay::Always),
+                    "sign-never" => fmt.sign_display(ast::SignDisplay::Never),
+                    "sign-except-zero" => fmt.sign_display(ast::SignDisplay::ExceptZero),
+                    "negative-parens" | "sign-accounting" => fmt.negative_parens(true),
+                    "unit-width-short" => width = Some((ast::UnitWidth::Short, stem)),
+                    "unit-width-full-name" => width = Some((ast::UnitWidth::FullName, stem)),
+                    "scientific" => exponent_digits = Some(1),
+                    _ => {
+                        if let Some(digits) = zero_digits(stem, 'E') {
+                            exponent_digits = Some(digits);
+                            continue;
+                        }
+                        if let Some(digits) = zero_digits(stem, '.') {
+                            fraction_digits = Some((digits, stem));
+                            continue;
+                        }
+                        if let Some(padding) = padding(stem) {
+                            fmt.padding(Some(padding));
+                            continue;
+                        }
+                        match stem.strip_prefix("unit/").and_then(ast::Unit::from_name) {
+                            Some(value) if unit.is_none() => unit = Some(value),
+                            _ => return Err(self.error_in(ParseErrorKind::NotImplemented, stem)),
+                        }
+                    }
+                }
+            }
+        }
+        match (exponent_digits, fraction_digits) {
+            (Some(exponent_digits), fraction_digits) => {
+                fmt.notation(ast::Notation::Scientific {
+                    exponent_digits,
+                    fraction_digits: fraction_digits.map(|(digits, _)| digits),
+                })
+            }
+            // The digits of a mantissa without scientific notation.
+            (None, Some((_, stem))) => {
+                return Err(self.error_in(ParseErrorKind::NotImplemented, stem))
+            }
+            (None, None) => {}
+        }
+        match (unit, width) {
+            (Some(unit), width) => {
+                let mut fmt = ast::UnitFormat::from_number(fmt, unit);
+                if let Some((width, _)) = width {
+                    fmt.width(width);
+                }
+                Ok(Box::new(fmt))
+            }
+            // A unit width without a unit.
+            (None, Some((_, stem))) => Err(self.error_in(ParseErrorKind::NotImplemented, stem)),
+            (None, None) => Ok(Box::new(fmt)),
+        }
+    }
+
+    fn get_duration_format(&mut self, name: &str) -> Result<ast::DurationFormat, ParseError> {
+        let mut fmt = ast::DurationFormat::new(name);
+        if self.get_style_start()? {
+            match self.get_style_text() {
+                "numeric" => fmt.style(ast::DurationStyle::Numeric),
+                "units" => fmt.style(ast::DurationStyle::Units),
+                style => return Err(self.error_in(ParseErrorKind::NotImplemented, style)),
+            }
+        }
+        Ok(fmt)
+    }
+
+    fn get_relative_time_format(&mut self,
+                                name: &str)
+                                -> Result<ast::RelativeTimeFormat, ParseError> {
+        if !self.get_style_start()? {
+            return Err(self.unexpected());
+        }
+        let style = self.get_style_text();
+        let mut tokens = style.split_whitespace();
+        let unit = match tokens.next() {
+            Some(token) => token,
+            None => return Err(self.error_in(ParseErrorKind::NotImplemented, style)),
+        };
+        let mut fmt = match ast::RelativeTimeUnit::from_name(unit) {
+            Some(unit) => ast::RelativeTimeFormat::new(name, unit),
+            None => return Err(self.error_in(ParseErrorKind::NotImplemented, unit)),
+        };
+        match tokens.next() {
+            Some("numeric") => fmt.numeric(true),
+            Some(token) => return Err(self.error_in(ParseErrorKind::NotImplemented, token)),
+            None => {}
+        }
+        if let Some(token) = tokens.next() {
+            return Err(self.error_in(ParseErrorKind::NotImplemented, token));
+        }
+        Ok(fmt)
+    }
+
+    fn get_plural_format(&mut self, name: &str) -> Result<ast::PluralFormat, ParseError> {
+        let mut offset = 0;
+        let mut branches = vec![];
+        if self.get_style_start()? {
+            offset = self.get_plural_offset()?;
+            branches = self.get_branches(true)?;
+        }
+        let other = self.take_other(&mut branches)?;
+        let mut fmt = ast::PluralFormat::new_shared(name, other);
+        fmt.offset(offset);
+        for (selector, message) in branches {
+            match selector {
+                Selector::Literal(value) => fmt.literal_shared(value, message),
+                Selector::Keyword(keyword) => {
+                    match plural_category(keyword) {
+                        Some(PluralCategory::Zero) => fmt.zero_shared(message),
+                        Some(PluralCategory::One) => fmt.one_shared(message),
+                        Some(PluralCategory::Two) => fmt.two_shared(message),
+                        Some(PluralCategory::Few) => fmt.few_shared(message),
+                        Some(PluralCategory::Many) => fmt.many_shared(message),
+                        Some(PluralCategory::Other) | None => {
+                            let kind = ParseErrorKind::InvalidSelector(keyword.to_string());
+                            return Err(self.error_in(kind, keyword));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(fmt)
+    }
+
+    /// The `offset:` of a plural, if it has one, or 0.
+    fn get_plural_offset(&mut self) -> Result<i64, ParseError> {
+        if !self.source[self.pos..].starts_with("offset:") {
+            return Ok(0);
+        }
+        self.pos += "offset:".len();
+        self.get_ws();
+        let offset = self.get_number()?;
+        self.get_ws();
+        Ok(offset)
+    }
+
+    fn get_select_format(&mut self, name: &str) -> Result<ast::SelectFormat, ParseError> {
+        let mut branches = vec![];
+        if self.get_style_start()? {
+            branches = self.get_branches(false)?;
+        }
+        let default = self.take_other(&mut branches)?;
+        let mut fmt = ast::SelectFormat::new_shared(name, default);
+        for (selector, message) in branches {
+            if let Selector::Keyword(keyword) = selector {
+                fmt.map_shared(keyword, message);
+            }
+        }
+        Ok(fmt)
+    }
+
+    /// The style of a `choice` format is a list of choices separated by
+    /// `|`. Each is a limit, followed by `#` or `≤` if it is inclusive or
+    /// `<` if it is exclusive, and then a message. The limits must be in
+    /// ascending order.
+    fn get_choice_format(&mut self, name: &str) -> Result<ast::ChoiceFormat, ParseError> {
+        let mut fmt = ast::ChoiceFormat::new(name);
+        if !self.get_style_start()? {
+            return Ok(fmt);
+        }
+        let mut previous = f64::NEG_INFINITY;
+        loop {
+            let (limit, exclusive) = self.get_choice_limit(previous)?;
+            previous = limit;
+            let message = self.get_choice_message()?;
+            if exclusive {
+                fmt.greater_than(limit, message);
+            } else {
+                fmt.at_least(limit, message);
+            }
+            if !self.ch_is('|') {
+                return Ok(fmt);
+            }
+            self.bump();
+        }
+    }
+
+    /// Check the choices of a `choice` format like `get_choice_format`.
+    fn skip_choices(&mut self) -> Result<(), ParseError> {
+        if !self.get_style_start()? {
+            return Ok(());
+        }
+        let mut previous = f64::NEG_INFINITY;
+        loop {
+            previous = self.get_choice_limit(previous)?.0;
+            loop {
+                match self.ch() {
+                    None | Some('}') | Some('|') => break,
+                    Some('{') if self.at_comment() => {
+                        self.get_comment_text()?;
+                    }
+                    Some('{') => self.nested(Self::skip_format_contents)?,
+                    Some(_) => {
+                        self.get_choice_text();
+                    }
+                }
+            }
+            if !self.ch_is('|') {
+                return Ok(());
+            }
+            self.bump();
+        }
+    }
+
+    /// The limit of a choice, which must be at least the `previous`
+    /// limit, and whether it is exclusive.
+    fn get_choice_limit(&mut self, previous: f64) -> Result<(f64, bool), ParseError> {
+        let start = self.pos;
+        while self.ch().is_some_and(|ch| !"#≤<|{}".contains(ch)) {
+            self.bump();
+        }
+        let text = self.source[start..self.pos].trim();
+        let limit = match text {
+            "∞" => f64::INFINITY,
+            "-∞" => f64::NEG_INFINITY,
+            _ => text.parse().ok().filter(|limit: &f64| limit.is_finite()).unwrap_or(f64::NAN),
+        };
+        if limit.is_nan() || limit < previous {
+            return Err(self.error_in(ParseErrorKind::InvalidNumber(text.to_string()), text));
+        }
+        let exclusive = match self.ch() {
+            Some('#') | Some('≤') => false,
+            Some('<') => true,
+            _ => return Err(self.unexpected()),
+        };
+        self.bump();
+        Ok((limit, exclusive))
+    }
+
+    /// Parse the message of a choice, which ends at a `|` or the end of
+    /// the format. `#` is plain text.
+    fn get_choice_message(&mut self) -> Result<Message, ParseError> {
+        let mut parts: Vec<Box<dyn MessagePart>> = vec![];
+        loop {
+            match self.ch() {
+                None | Some('}') | Some('|') => break,
+                Some('{') if self.at_comment() => parts.extend(self.get_comment()?),
+                Some('{') => parts.push(self.get_format()?),
+                Some(_) => parts.push(Box::new(ast::PlainText::new(&self.get_choice_text()))),
+            }
+        }
+        Ok(Message::new(parts))
+    }
+
+    fn get_choice_text(&mut self) -> String {
+        let special = |ch| "{}|".contains(ch);
+        let mut text = String::new();
+        while let Some(ch) = self.ch() {
+            if ch == '\'' {
+                self.get_apostrophe(special, &mut text);
+                continue;
+            }
+            if special(ch) {
+                break;
+            }
+            text.push(ch);
+            self.bump();
+        }
+        text
     }
 }
 
+/// What selects a branch within a `plural` or `select` format.
+#[derive(Debug,PartialEq)]
+enum Selector<'a> {
+    /// An `=` literal value for a plural.
+    Literal(i64),
+    /// A plural category or a select value.
+    Keyword(&'a str),
+}
+
+/// The plural category of a keyword selector, which must be written
+/// exactly, in lowercase.
+fn plural_category(keyword: &str) -> Option<PluralCategory> {
+    PluralCategory::ALL.iter().cloned().find(|category| category.to_string() == keyword)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use {arg, Context};
-    use nom::IResult;
+    use {arg, Args, Context, FormatError, Locale};
 
     #[test]
     fn it_works() {
@@ -124,34 +1166,398 @@ mod tests {
 
     #[test]
     fn incomplete_fails() {
-        match message_parser("{name") {
-            IResult::Incomplete(_) => {}
-            IResult::Error(e) => panic!("Expected incomplete failure: Got {}", e),
-            IResult::Done(_, _) => panic!("Expected incomplete failure, but succeeded."),
-        }
+        assert_eq!(parse("{name").unwrap_err().kind, ParseErrorKind::UnexpectedEnd);
     }
 
     #[test]
     fn all_text_works() {
-        match message_parser("Hello, world!") {
-            IResult::Done(_, _) => {}
-            _ => panic!("Expected successful parse."),
-        }
+        assert!(parse("Hello, world!").is_ok());
     }
 
     #[test]
     fn plural_format_works() {
-        match message_parser("{count,plural}") {
-            IResult::Done(_, _) => {}
-            _ => panic!("Expected successful parse."),
-        }
+        assert!(parse("{count,plural,other{}}").is_ok());
     }
 
     #[test]
     fn select_format_works() {
-        match message_parser("{type,select}") {
-            IResult::Done(_, _) => {}
-            _ => panic!("Expected successful parse."),
+        assert!(parse("{type,select,other{}}").is_ok());
+    }
+
+    #[test]
+    fn empty_pattern() {
+        let m = parse("").unwrap();
+        assert!(m.is_empty());
+        assert_eq!(Context::default().format(&m, None), "");
+        assert_eq!(validate(""), Ok(()));
+        assert!(parse_with_warnings("").unwrap().1.is_empty());
+    }
+
+    #[test]
+    fn empty_branches() {
+        let ctx = Context::default();
+        let m = parse("{n, plural, one {} other {items}}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", 1))), "");
+        assert_eq!(ctx.format(&m, Some(&arg("n", 2))), "items");
+        let m = parse("[{n, plural, =0 {} other {#}}] [{g, select, a {} other {}}] \
+                       [{n, selectordinal, one {} other {#th}}] [{n, choice, 0#|1#one}]")
+            .unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", 0).arg("g", "a"))), "[] [] [0th] []");
+        assert_eq!(ctx.format(&m, Some(&arg("n", 1).arg("g", "b"))), "[1] [] [] [one]");
+        // Whitespace within a branch is kept.
+        let m = parse("{n, plural, one { } other {x}}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", 1))), " ");
+        assert_eq!(parse("{}").unwrap_err().kind, ParseErrorKind::UnexpectedCharacter('}'));
+    }
+
+    #[test]
+    fn missing_other_fails() {
+        for source in &["{n, plural, one {x}}", "{n, selectordinal, one {#st} }", "{n,plural}",
+                        "{t, select, a {x}}", "{t, select}"] {
+            let error = parse(source).unwrap_err();
+            assert_eq!(error.kind, ParseErrorKind::MissingOtherBranch, "{}", source);
+            assert_eq!(error.offset, source.len() - 1, "{}", source);
+        }
+        let error = parse("{a, select, x {{n, plural, one {#}}} other {}}").unwrap_err();
+        assert_eq!(error.to_string(), "At offset 34: Missing 'other' branch.");
+    }
+
+    #[test]
+    fn plural_branches_work() {
+        let ctx = Context::default();
+        let m = parse("{count, plural, offset:1 =0 {Nobody} one {You and # other} \
+                       other {You and # others}}")
+            .unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("count", 1))), "Nobody");
+        assert_eq!(ctx.format(&m, Some(&arg("count", 2))), "You and 1 other");
+        assert_eq!(ctx.format(&m, Some(&arg("count", 5))), "You and 4 others");
+    }
+
+    #[test]
+    fn selectordinal_works() {
+        let m = parse("{place, selectordinal, one {#st} two {#nd} few {#rd} other {#th}}")
+            .unwrap();
+        let ctx = Context::default();
+        let cases = [(1, "1st"), (2, "2nd"), (3, "3rd"), (4, "4th"), (11, "11th"), (22, "22nd")];
+        for &(place, expected) in &cases {
+            assert_eq!(ctx.format(&m, Some(&arg("place", place))), expected);
+        }
+
+        let ctx = Context::new(Locale::new("de").unwrap(), None);
+        assert_eq!(ctx.format(&m, Some(&arg("place", 1))), "1th");
+    }
+
+    #[test]
+    fn choice_works() {
+        let ctx = Context::default();
+        let m = parse("{n, choice, 0#no files|1#one file|1<{n, number} files}").unwrap();
+        let cases = [(0, "no files"), (1, "one file"), (2, "2 files"), (1000, "1,000 files"),
+                     (-1, "no files")];
+        for &(n, expected) in &cases {
+            assert_eq!(ctx.format(&m, Some(&arg("n", n))), expected);
+        }
+        assert_eq!(ctx.format(&m, Some(&arg("n", 0.5))), "no files");
+
+        let m = parse("{n, choice, -∞<negative|0≤zero|0<#positive|1e3<large}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", -5))), "negative");
+        assert_eq!(ctx.format(&m, Some(&arg("n", 0))), "zero");
+        assert_eq!(ctx.format(&m, Some(&arg("n", 7))), "#positive");
+        assert_eq!(ctx.format(&m, Some(&arg("n", 1001))), "large");
+    }
+
+    #[test]
+    fn invalid_choices_fail() {
+        assert_eq!(parse("{n, choice, 1#a|0#b}").unwrap_err().kind,
+                   ParseErrorKind::InvalidNumber("0".to_string()));
+        assert_eq!(parse("{n, choice, x#a}").unwrap_err().kind,
+                   ParseErrorKind::InvalidNumber("x".to_string()));
+        assert_eq!(parse("{n, choice, 1 a}").unwrap_err().kind,
+                   ParseErrorKind::InvalidNumber("1 a".to_string()));
+        assert_eq!(parse("{n, choice, 1#a").unwrap_err().kind, ParseErrorKind::UnexpectedEnd);
+    }
+
+    #[test]
+    fn select_branches_work() {
+        let ctx = Context::default();
+        let m = parse("{gender, select, female {She} male {He} other {They}} replied.").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("gender", "female"))), "She replied.");
+        assert_eq!(ctx.format(&m, Some(&arg("gender", "unknown"))), "They replied.");
+    }
+
+    #[test]
+    fn identical_branches_are_shared() {
+        let ctx = Context::default();
+        let source = "{a, select, x {Same} y {Same} z {{b, select, x {Same} other {Same}}} \
+                      other {Other}} {n, plural, one {Same} other {#}}";
+        let parser = Parser::new();
+        let mut p = MessageParser::new(source, &parser);
+        let m = p.parse().unwrap();
+        // "Same", the nested select and "Other" outside of a plural,
+        // plus "Same" and "#" within one.
+        assert_eq!(p.branches.len(), 5);
+        assert_eq!(ctx.format(&m, Some(&arg("a", "y").arg("b", "x").arg("n", 2))),
+                   "Same 2");
+        assert_eq!(ctx.format(&m, Some(&arg("a", "z").arg("b", "q").arg("n", 1))),
+                   "Same Same");
+    }
+
+    #[test]
+    fn plural_offset_and_literals() {
+        let ctx = Context::default();
+        let parser = Parser::new();
+        let mut p = MessageParser::new(", offset:1 =0 {Nobody else} =1 {{name} and one \
+                                        other} other {# others}}",
+                                       &parser);
+        let fmt = p.get_plural_format("n").unwrap();
+        assert_eq!(fmt.offset_value(), 1);
+        let literals = fmt.literals();
+        assert_eq!(literals.len(), 2);
+        assert_eq!(ctx.format(&literals[&0], None), "Nobody else");
+        assert_eq!(ctx.format(&literals[&1], Some(&arg("name", "Jo"))), "Jo and one other");
+
+        let mut p = MessageParser::new(", other {#}}", &parser);
+        let fmt = p.get_plural_format("n").unwrap();
+        assert_eq!(fmt.offset_value(), 0);
+        assert!(fmt.literals().is_empty());
+    }
+
+    #[derive(Debug)]
+    struct Tokens(String);
+
+    impl MessagePart for Tokens {
+        fn apply_format(&self,
+                        _ctx: &Context,
+                        stream: &mut dyn fmt::Write,
+                        _args: Option<&Args>)
+                        -> Result<(), FormatError> {
+            Ok(stream.write_str(&self.0)?)
+        }
+    }
+
+    #[test]
+    fn custom_formats() {
+        let mut parser = Parser::new();
+        parser.register("tokens", |tokens| Box::new(Tokens(tokens.join("|"))));
+        parser.register("number", |_| Box::new(Tokens("custom".to_string())));
+        let ctx = Context::default();
+        let m = parser.parse("{a, tokens} {b , tokens , x y } {c, number}").unwrap();
+        assert_eq!(ctx.format(&m, None), "a|tokens b|tokens|x y custom");
+        let m = parser.parse("{n, plural, other {{n, tokens}}}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", 1))), "n|tokens");
+
+        assert_eq!(parse("{a, tokens}").unwrap_err().kind, ParseErrorKind::NotImplemented);
+        assert_eq!(parser.parse("{a, tokens").unwrap_err().kind, ParseErrorKind::UnexpectedEnd);
+        assert_eq!(format!("{:?}", parser),
+                   "Parser { formats: [\"number\", \"tokens\"], max_depth: 64, \
+                    keep_comments: false }");
+    }
+
+    #[test]
+    fn hash_outside_plural_is_text() {
+        let ctx = Context::default();
+        let m = parse("Item #{id}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("id", "3"))), "Item #3");
+
+        let m = parse("{n, plural, other {{g, select, other {#}} #}}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", 3).arg("g", "x"))), "# 3");
+    }
+
+    #[test]
+    fn hash_outside_plural_warns() {
+        let (_, warnings) = parse_with_warnings("#{n, plural, other {#}}").unwrap();
+        assert_eq!(warnings, [ParseWarning::LiteralHash(0)]);
+
+        let (_, warnings) = parse_with_warnings("{n, plural, other {{g, select, other {#}}}}")
+            .unwrap();
+        assert_eq!(warnings, [ParseWarning::LiteralHash(38)]);
+
+        let (_, warnings) = parse_with_warnings("{n, plural, one {# item} other {# items}}")
+            .unwrap();
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn invalid_plural_selector_fails() {
+        assert_eq!(parse("{count, plural, lots {x} other {y}}").unwrap_err().kind,
+                   ParseErrorKind::InvalidSelector("lots".to_string()));
+        assert_eq!(parse("{count, plural, OTHER {x} other {y}}").unwrap_err().kind,
+                   ParseErrorKind::InvalidSelector("OTHER".to_string()));
+    }
+
+    #[test]
+    fn duplicate_selectors_fail() {
+        let duplicate = |source| parse(source).map(|_| ()).unwrap_err();
+        let error = duplicate("{n, plural, one {a} one {b} other {c}}");
+        assert_eq!((error.kind, error.offset),
+                   (ParseErrorKind::DuplicateSelector("one".to_string()), 20));
+        assert_eq!(duplicate("{n, plural, =1 {a} =01 {b} other {c}}").kind,
+                   ParseErrorKind::DuplicateSelector("=01".to_string()));
+        let error = duplicate("{g, select, a {x} a {y} other {z}}");
+        assert_eq!(error.to_string(), "At offset 18: Duplicate selector 'a'.");
+        assert_eq!(duplicate("{g, select, other {x} other {y}}").kind,
+                   ParseErrorKind::DuplicateSelector("other".to_string()));
+    }
+
+    #[test]
+    fn apostrophes_quote_syntax() {
+        let ctx = Context::default();
+        let format = |source| ctx.format(&parse(source).unwrap(), Some(&arg("n", 2)));
+        assert_eq!(format("It''s '{'x'}' and '{n}'"), "It's {x} and {n}");
+        // An apostrophe that isn't before syntax is itself.
+        assert_eq!(format("It's {n}'s"), "It's 2's");
+        assert_eq!(format("'{It''s}'"), "{It's}");
+        assert_eq!(format("{n, plural, other {'#' is #, '''#'''}}"), "# is 2, '#'");
+        assert_eq!(format("{n, select, other {'#' is '#'.}}"), "'#' is '#'.");
+        assert_eq!(format("{n, choice, 0#a'|'b|5<c}"), "a|b");
+        // Quoted text runs to the end of the message if it isn't closed.
+        assert_eq!(format("'{n} {n}"), "{n} {n}");
+        assert_eq!(parse("{n, select, other {'}}}").unwrap_err().kind,
+                   ParseErrorKind::UnexpectedEnd);
+    }
+
+    #[test]
+    fn plural_selectors_are_case_sensitive() {
+        assert_eq!(parse("{count, plural, One {one} other {other}}").unwrap_err().kind,
+                   ParseErrorKind::InvalidSelector("One".to_string()));
+    }
+
+    #[test]
+    fn errors_have_offsets() {
+        let error = parse("{count, plural, one {x} lots {y} other {z}}").unwrap_err();
+        assert_eq!((error.offset, error.utf16_offset), (24, 24));
+        assert_eq!(error.to_string(), "At offset 24: Invalid selector 'lots'.");
+        let error = parse("{n, number, ::group-off percent}").unwrap_err();
+        assert_eq!((error.kind, error.offset), (ParseErrorKind::NotImplemented, 24));
+        let error = parse("{n, number, ::.00 E0x}").unwrap_err();
+        assert_eq!((error.kind, error.offset), (ParseErrorKind::NotImplemented, 18));
+        let error = parse("{n, number, ::group-off .00}").unwrap_err();
+        assert_eq!((error.kind, error.offset), (ParseErrorKind::NotImplemented, 24));
+        let error = parse("{n, choice, 1#a|0#b}").unwrap_err();
+        assert_eq!((error.kind, error.offset), (ParseErrorKind::InvalidNumber("0".into()), 16));
+        let error = parse("{n, plural, offset:x other {}}").unwrap_err();
+        assert_eq!(error.offset, 19);
+    }
+
+    #[test]
+    fn utf16_offsets_count_code_units() {
+        // "é" is two bytes and one UTF-16 code unit, and "😀" is four
+        // bytes and two UTF-16 code units.
+        let error = parse("é😀 {n, tokens}").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::NotImplemented);
+        assert_eq!(error.offset, 11);
+        assert_eq!(error.utf16_offset, 8);
+        assert_eq!(&"é😀 {n, tokens}"[error.offset..], "tokens}");
+
+        let error = parse("日本 }").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedCharacter('}'));
+        assert_eq!((error.offset, error.utf16_offset), (7, 3));
+    }
+
+    #[test]
+    fn unmatched_close_fails() {
+        assert_eq!(parse("Hello}").unwrap_err().kind,
+                   ParseErrorKind::UnexpectedCharacter('}'));
+    }
+
+    #[test]
+    fn nested_selects_in_default() {
+        let ctx = Context::default();
+        let m = parse("{a, select, x {A} other {{b, select, y {B} other {{c, select, \
+                       z {C} other {{a}, {b} and {c}}}}}}}")
+            .unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("a", "x").arg("b", "y").arg("c", "z"))), "A");
+        assert_eq!(ctx.format(&m, Some(&arg("a", "-").arg("b", "y").arg("c", "z"))), "B");
+        assert_eq!(ctx.format(&m, Some(&arg("a", "-").arg("b", "-").arg("c", "z"))), "C");
+        assert_eq!(ctx.format(&m, Some(&arg("a", "-").arg("b", "-").arg("c", "-"))),
+                   "-, - and -");
+    }
+
+    #[test]
+    fn max_depth() {
+        let nested = |depth| {
+            let mut message = "{x}".to_string();
+            for _ in 1..depth {
+                message = format!("{{x, select, other {{{}}}}}", message);
+            }
+            message
+        };
+        assert!(parse(&nested(DEFAULT_MAX_DEPTH)).is_ok());
+        let error = parse(&nested(DEFAULT_MAX_DEPTH + 1)).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::TooDeep);
+        assert_eq!(error.to_string(),
+                   format!("At offset {}: Formats are nested too deeply.", error.offset));
+
+        let mut parser = Parser::new();
+        parser.max_depth(3);
+        assert!(parser.parse(&nested(3)).is_ok());
+        assert_eq!(parser.parse(&nested(4)).unwrap_err().kind, ParseErrorKind::TooDeep);
+        // Choice messages are nested too.
+        parser.max_depth(1);
+        assert_eq!(parser.parse("{n, choice, 0#{x}}").unwrap_err().kind,
+                   ParseErrorKind::TooDeep);
+    }
+
+    #[test]
+    fn comments() {
+        let ctx = Context::default();
+        let source = "{! For the inbox } You have {! count is never 0 }{count, plural, \
+                      one {# message{! singular }} other {# messages}}{!}.";
+        let args = arg("count", 2);
+        let m = parse(source).unwrap();
+        assert_eq!(ctx.format(&m, Some(&args)), " You have 2 messages.");
+        assert_eq!(m.comments(), Vec::<String>::new());
+
+        let mut parser = Parser::new();
+        parser.keep_comments(true);
+        let m = parser.parse(source).unwrap();
+        assert_eq!(ctx.format(&m, Some(&args)), " You have 2 messages.");
+        assert_eq!(m.comments(), ["For the inbox", "count is never 0", "singular", ""]);
+        let m = parser.parse("{n, choice, 0#none{! zero }|1#some}").unwrap();
+        assert_eq!(ctx.format(&m, Some(&arg("n", 0))), "none");
+        assert_eq!(m.comments(), ["zero"]);
+
+        // A comment can't contain braces, and `#` is not a placeholder.
+        assert_eq!(parse("{! {x} }").unwrap_err().kind, ParseErrorKind::UnexpectedCharacter('}'));
+        assert_eq!(parse("{! note").unwrap_err().kind, ParseErrorKind::UnexpectedEnd);
+        let m = parser.parse("{n, plural, other {{! # is the count }#}}").unwrap();
+        assert_eq!(m.comments(), ["# is the count"]);
+    }
+
+    #[test]
+    fn validate_agrees_with_parse() {
+        let sources = ["Hello, {name}!",
+                       "{count, plural, offset:1 =0 {none} one {# file} other {# files}}",
+                       "{n, selectordinal, one {#st} two {#nd} few {#rd} other {#th}}",
+                       "{g, select, female {{n, plural, one {her #} other {her # items}}} \
+                        other {{n, number, ::sign-always}}}",
+                       "{n, choice, 0#none{! zero }|1#{n, number}|1<{n, duration, units}}",
+                       "{d, number, ::unit/meter unit-width-full-name} {t, spellout}",
+                       "{! note }{n, plural, one {a} ONE {b} other {c}}",
+                       "{n, plural, one {a} other {b} other {c}}",
+                       "{n, plural, seven {a} =x {b}}",
+                       "{g, select, other {a} other {b}}",
+                       "{n, plural, =1 {a} one {b} =1 {c}}",
+                       "{g, select, 'a' {b} other {'{c}'}} '{'{'}",
+                       "{n, choice, 1#a|0#b}",
+                       "{n, number, ::unit-width-short}",
+                       "{n, date} }"];
+        for source in &sources {
+            // Every prefix of each source, so that most are errors.
+            for (end, _) in source.char_indices().chain(Some((source.len(), ' '))) {
+                let source = &source[..end];
+                assert_eq!(validate(source), parse(source).map(|_| ()), "{}", source);
+            }
+        }
+
+        let mut parser = Parser::new();
+        parser.register("filesize", |tokens| Box::new(ast::SimpleFormat::new(tokens[0])));
+        parser.max_depth(2);
+        for source in &["{a, filesize}",
+                        "{a, filesize, short}",
+                        "{a, select, other {{b}}}",
+                        "{a, select, other {{b, select, other {{c}}}}}",
+                        "{n, choice, 0#{x, choice, 0#{y}}}"] {
+            assert_eq!(parser.validate(source), parser.parse(source).map(|_| ()), "{}", source);
         }
     }
 }
@@ -0,0 +1,343 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A small JSON reader for loading data files.
+
+use std::error::Error;
+use std::fmt;
+use std::string::String;
+use std::vec::Vec;
+
+use nesting::Nesting;
+
+/// A JSON value. The members of an object are kept in order.
+#[derive(Clone,Debug,PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Get the member of an object with the given `key`.
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match *self {
+            JsonValue::Object(ref members) => {
+                members.iter().find(|&(name, _)| name == key).map(|(_, value)| value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the value of a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            JsonValue::String(ref value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Get the members of an object.
+    pub fn as_object(&self) -> Option<&[(String, JsonValue)]> {
+        match *self {
+            JsonValue::Object(ref members) => Some(members),
+            _ => None,
+        }
+    }
+}
+
+/// An error in a JSON document, at a byte `offset`.
+#[derive(Clone,Debug,PartialEq)]
+pub struct JsonError {
+    pub offset: usize,
+    pub message: &'static str,
+}
+
+impl Error for JsonError {}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{} at offset {}.", self.message, self.offset)
+    }
+}
+
+/// Parse a JSON document.
+pub fn parse(source: &str) -> Result<JsonValue, JsonError> {
+    let mut p = Parser { source, pos: 0, depth: 0 };
+    let value = p.get_value()?;
+    p.get_ws();
+    if p.pos < source.len() {
+        return Err(p.error("Unexpected trailing characters"));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    pos: usize,
+    /// The number of arrays and objects that are being parsed.
+    depth: usize,
+}
+
+impl<'a> Nesting for Parser<'a> {
+    type Error = JsonError;
+
+    fn depth(&mut self) -> &mut usize {
+        &mut self.depth
+    }
+
+    fn too_deep(&self) -> JsonError {
+        self.error("Too deeply nested")
+    }
+}
+
+impl<'a> Parser<'a> {
+    fn ch(&self) -> Option<char> {
+        self.source[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) {
+        if let Some(ch) = self.ch() {
+            self.pos += ch.len_utf8();
+        }
+    }
+
+    fn error(&self, message: &'static str) -> JsonError {
+        JsonError {
+            offset: self.pos,
+            message,
+        }
+    }
+
+    fn get_ws(&mut self) {
+        while let Some(' ') | Some('\t') | Some('\n') | Some('\r') = self.ch() {
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, ch: char) -> Result<(), JsonError> {
+        self.get_ws();
+        if self.ch() == Some(ch) {
+            self.bump();
+            Ok(())
+        } else {
+            Err(self.error("Unexpected character"))
+        }
+    }
+
+    fn get_keyword(&mut self, keyword: &str, value: JsonValue) -> Result<JsonValue, JsonError> {
+        if self.source[self.pos..].starts_with(keyword) {
+            self.pos += keyword.len();
+            Ok(value)
+        } else {
+            Err(self.error("Unexpected character"))
+        }
+    }
+
+    fn get_value(&mut self) -> Result<JsonValue, JsonError> {
+        self.get_ws();
+        match self.ch() {
+            Some('{') => self.nested(Parser::get_object),
+            Some('[') => self.nested(Parser::get_array),
+            Some('"') => self.get_string().map(JsonValue::String),
+            Some('t') => self.get_keyword("true", JsonValue::Bool(true)),
+            Some('f') => self.get_keyword("false", JsonValue::Bool(false)),
+            Some('n') => self.get_keyword("null", JsonValue::Null),
+            Some('-') | Some('0'..='9') => self.get_number(),
+            Some(_) => Err(self.error("Unexpected character")),
+            None => Err(self.error("Unexpected end")),
+        }
+    }
+
+    fn get_object(&mut self) -> Result<JsonValue, JsonError> {
+        self.bump();
+        let mut members = vec![];
+        self.get_ws();
+        if self.ch() == Some('}') {
+            self.bump();
+            return Ok(JsonValue::Object(members));
+        }
+        loop {
+            self.get_ws();
+            if self.ch() != Some('"') {
+                return Err(self.error("Expected a string"));
+            }
+            let key = self.get_string()?;
+            self.expect(':')?;
+            let value = self.get_value()?;
+            members.push((key, value));
+            self.get_ws();
+            match self.ch() {
+                Some(',') => self.bump(),
+                Some('}') => {
+                    self.bump();
+                    return Ok(JsonValue::Object(members));
+                }
+                _ => return Err(self.error("Expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn get_array(&mut self) -> Result<JsonValue, JsonError> {
+        self.bump();
+        let mut values = vec![];
+        self.get_ws();
+        if self.ch() == Some(']') {
+            self.bump();
+            return Ok(JsonValue::Array(values));
+        }
+        loop {
+            values.push(self.get_value()?);
+            self.get_ws();
+            match self.ch() {
+                Some(',') => self.bump(),
+                Some(']') => {
+                    self.bump();
+                    return Ok(JsonValue::Array(values));
+                }
+                _ => return Err(self.error("Expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn get_string(&mut self) -> Result<String, JsonError> {
+        self.bump();
+        let mut value = String::new();
+        loop {
+            match self.ch() {
+                Some('"') => {
+                    self.bump();
+                    return Ok(value);
+                }
+                Some('\\') => {
+                    self.bump();
+                    let escaped = match self.ch() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            value.push(self.get_unicode_escape()?);
+                            continue;
+                        }
+                        _ => return Err(self.error("Invalid escape")),
+                    };
+                    self.bump();
+                    value.push(escaped);
+                }
+                Some(ch) if ch < ' ' => return Err(self.error("Control character in string")),
+                Some(ch) => {
+                    self.bump();
+                    value.push(ch);
+                }
+                None => return Err(self.error("Unterminated string")),
+            }
+        }
+    }
+
+    /// Parse the hex digits of a `\u` escape, which starts at the `u`,
+    /// combining surrogate pairs.
+    fn get_unicode_escape(&mut self) -> Result<char, JsonError> {
+        let high = self.get_hex()?;
+        if (0xd800..0xdc00).contains(&high) {
+            if !self.source[self.pos..].starts_with("\\u") {
+                return Err(self.error("Unpaired surrogate"));
+            }
+            self.bump();
+            let low = self.get_hex()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(self.error("Unpaired surrogate"));
+            }
+            let code = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+            return char::from_u32(code).ok_or_else(|| self.error("Invalid escape"));
+        }
+        char::from_u32(high).ok_or_else(|| self.error("Unpaired surrogate"))
+    }
+
+    /// Parse `u` followed by four hex digits.
+    fn get_hex(&mut self) -> Result<u32, JsonError> {
+        self.bump();
+        let digits = self.source
+            .get(self.pos..self.pos + 4)
+            .ok_or_else(|| self.error("Invalid escape"))?;
+        let value = u32::from_str_radix(digits, 16).map_err(|_| self.error("Invalid escape"))?;
+        self.pos += 4;
+        Ok(value)
+    }
+
+    fn get_number(&mut self) -> Result<JsonValue, JsonError> {
+        let start = self.pos;
+        while let Some('-') | Some('+') | Some('.') | Some('e') | Some('E') | Some('0'..='9') =
+            self.ch() {
+            self.bump();
+        }
+        self.source[start..self.pos]
+            .parse()
+            .map(JsonValue::Number)
+            .map_err(|_| JsonError {
+                offset: start,
+                message: "Invalid number",
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, JsonError, JsonValue};
+
+    #[test]
+    fn it_works() {
+        let value = parse(r#" {"a": [1, -2.5e1, true, false, null], "b": {"c": "d"}} "#).unwrap();
+        assert_eq!(value.get("a"),
+                   Some(&JsonValue::Array(vec![JsonValue::Number(1.0),
+                                               JsonValue::Number(-25.0),
+                                               JsonValue::Bool(true),
+                                               JsonValue::Bool(false),
+                                               JsonValue::Null])));
+        assert_eq!(value.get("b").and_then(|b| b.get("c")).and_then(|c| c.as_str()),
+                   Some("d"));
+        assert_eq!(value.get("c"), None);
+    }
+
+    #[test]
+    fn strings() {
+        let value = parse(r#""a\"\\\/\n\u00e9\ud83d\ude00…""#).unwrap();
+        assert_eq!(value.as_str(), Some("a\"\\/\né😀…"));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse("{\"a\" 1}"),
+                   Err(JsonError {
+                       offset: 5,
+                       message: "Unexpected character",
+                   }));
+        assert!(parse("[1,]").is_err());
+        assert!(parse("\"abc").is_err());
+        assert!(parse("\"\\ud83d\"").is_err());
+        assert!(parse("{} {}").is_err());
+        assert!(parse("").is_err());
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let nested = |depth| "[".repeat(depth) + &"]".repeat(depth);
+        assert!(parse(&nested(256)).is_ok());
+        assert_eq!(parse(&nested(257)),
+                   Err(JsonError {
+                       offset: 256,
+                       message: "Too deeply nested",
+                   }));
+        assert!(parse(&"{\"a\":".repeat(100_000)).is_err());
+    }
+}
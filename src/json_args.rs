@@ -0,0 +1,231 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::error::Error;
+use std::fmt;
+
+use serde_json::{self, Map};
+use args::chain;
+use {Args, AsValue, MessageArgs, Value};
+
+/// How [`json_args`] converts the members of a JSON object.
+///
+/// [`json_args`]: fn.json_args.html
+#[derive(Clone,Debug,PartialEq)]
+pub struct JsonArgsOptions {
+    /// Whether the members of nested objects are arguments named by the
+    /// path to them, like `user.name`, rather than an error. The default
+    /// is `true`.
+    pub flatten: bool,
+}
+
+impl Default for JsonArgsOptions {
+    fn default() -> Self {
+        JsonArgsOptions { flatten: true }
+    }
+}
+
+/// An error from converting JSON with [`json_args`].
+///
+/// [`json_args`]: fn.json_args.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum JsonArgsError {
+    /// The JSON isn't an object.
+    NotAnObject,
+    /// The member with this name is an object, and the options don't
+    /// flatten them.
+    Nested(String),
+    /// The member with this name is an array, which has no [`Value`].
+    ///
+    /// [`Value`]: enum.Value.html
+    Array(String),
+}
+
+impl Error for JsonArgsError {}
+
+impl fmt::Display for JsonArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            JsonArgsError::NotAnObject => "The arguments must be an object.".fmt(f),
+            JsonArgsError::Nested(ref name) => {
+                write!(f, "The argument '{}' is an object, which isn't flattened.", name)
+            }
+            JsonArgsError::Array(ref name) => write!(f, "The argument '{}' is an array.", name),
+        }
+    }
+}
+
+/// The members of a JSON object as arguments, from [`json_args`].
+///
+/// As [`Args`] refer to each other, they are only available within
+/// [`with_args`].
+///
+/// [`json_args`]: fn.json_args.html
+/// [`Args`]: struct.Args.html
+/// [`with_args`]: #method.with_args
+#[derive(Clone,Debug,PartialEq)]
+pub struct JsonArgs<'a> {
+    entries: Vec<(String, Value<'a>)>,
+}
+
+impl<'a> JsonArgs<'a> {
+    /// Convert the members of a JSON object, as with [`json_args`].
+    ///
+    /// [`json_args`]: fn.json_args.html
+    pub fn from_map(map: &'a Map<String, serde_json::Value>,
+                    options: &JsonArgsOptions)
+                    -> Result<Self, JsonArgsError> {
+        let mut args = JsonArgs { entries: vec![] };
+        args.add_members(map, "", options)?;
+        Ok(args)
+    }
+
+    fn add_members(&mut self,
+                   map: &'a Map<String, serde_json::Value>,
+                   prefix: &str,
+                   options: &JsonArgsOptions)
+                   -> Result<(), JsonArgsError> {
+        for (key, json) in map {
+            let name = format!("{}{}", prefix, key);
+            let value = match *json {
+                serde_json::Value::Null => continue,
+                serde_json::Value::Bool(true) => Value::Str("true"),
+                serde_json::Value::Bool(false) => Value::Str("false"),
+                serde_json::Value::Number(ref number) => {
+                    if let Some(n) = number.as_i64() {
+                        Value::Number(n)
+                    } else if let Some(n) = number.as_u64() {
+                        n.as_formattable()
+                    } else {
+                        number.as_f64().unwrap_or(f64::NAN).as_formattable()
+                    }
+                }
+                serde_json::Value::String(ref text) => Value::Str(text),
+                serde_json::Value::Array(_) => return Err(JsonArgsError::Array(name)),
+                serde_json::Value::Object(ref members) if options.flatten => {
+                    self.add_members(members, &format!("{}.", name), options)?;
+                    continue;
+                }
+                serde_json::Value::Object(_) => return Err(JsonArgsError::Nested(name)),
+            };
+            self.entries.push((name, value));
+        }
+        Ok(())
+    }
+
+    /// The value of the argument with the given `name`.
+    pub fn get(&self, name: &str) -> Option<&Value<'a>> {
+        self.entries.iter().find(|(n, _)| n == name).map(|(_, value)| value)
+    }
+
+    /// Call `f` with the arguments, which are `None` if there are none.
+    ///
+    /// ```
+    /// extern crate message_format;
+    /// extern crate serde_json;
+    ///
+    /// use message_format::{icu, json_args, Context, JsonArgsOptions};
+    ///
+    /// # fn main() {
+    /// let payload = serde_json::json!({ "user": { "name": "Ana" }, "unread": 3 });
+    /// let args = json_args(&payload, &JsonArgsOptions::default()).unwrap();
+    /// let m = icu::parse("{user.name} has {unread, plural, one {# message} other {# messages}}")
+    ///     .unwrap();
+    /// let ctx = Context::default();
+    /// assert_eq!(args.with_args(|args| ctx.format(&m, args)), "Ana has 3 messages");
+    /// # }
+    /// ```
+    pub fn with_args<R, F>(&self, f: F) -> R
+        where F: FnOnce(Option<&Args>) -> R
+    {
+        chain(&self.entries, None, f)
+    }
+}
+
+impl<'a> MessageArgs for JsonArgs<'a> {
+    fn with_args<R, F>(&self, f: F) -> R
+        where F: FnOnce(Option<&Args>) -> R
+    {
+        JsonArgs::with_args(self, f)
+    }
+}
+
+/// Convert the members of a JSON object into arguments.
+///
+/// Strings are [`Value::Str`], integers are [`Value::Number`], or
+/// [`Value::BigInt`] if they don't fit in an `i64`, and other numbers
+/// are [`Value::Decimal`], as for an `f64`. Booleans are the strings
+/// `true` and `false`, so that they can be selected on. Members that
+/// are `null` are left out, so they are missing, as are arguments that
+/// aren't given. The members of nested objects are named by the path
+/// to them, like `user.name`, unless the options don't flatten them.
+///
+/// [`Value::Str`]: enum.Value.html#variant.Str
+/// [`Value::Number`]: enum.Value.html#variant.Number
+/// [`Value::BigInt`]: enum.Value.html#variant.BigInt
+/// [`Value::Decimal`]: enum.Value.html#variant.Decimal
+pub fn json_args<'a>(json: &'a serde_json::Value,
+                     options: &JsonArgsOptions)
+                     -> Result<JsonArgs<'a>, JsonArgsError> {
+    match *json {
+        serde_json::Value::Object(ref map) => JsonArgs::from_map(map, options),
+        _ => Err(JsonArgsError::NotAnObject),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{json_args, JsonArgsError, JsonArgsOptions};
+    use {icu, Context, Value};
+
+    #[test]
+    fn values() {
+        let payload = json!({
+            "name": "Ana",
+            "count": 2,
+            "big": u64::MAX,
+            "ratio": 0.25,
+            "admin": true,
+            "missing": null,
+            "user": { "address": { "city": "Lima" } }
+        });
+        let args = json_args(&payload, &JsonArgsOptions::default()).unwrap();
+        assert_eq!(args.get("name"), Some(&Value::Str("Ana")));
+        assert_eq!(args.get("count"), Some(&Value::Number(2)));
+        assert_eq!(args.get("big"), Some(&Value::BigInt(u64::MAX as i128)));
+        assert_eq!(args.get("ratio"), Some(&Value::Decimal { value: 0.25, fraction_digits: 2 }));
+        assert_eq!(args.get("admin"), Some(&Value::Str("true")));
+        assert_eq!(args.get("missing"), None);
+        assert_eq!(args.get("user.address.city"), Some(&Value::Str("Lima")));
+    }
+
+    #[test]
+    fn formatting() {
+        let ctx = Context::default();
+        let m = icu::parse("{admin, select, true {Admin} other {User}} {name}: {missing}")
+            .unwrap();
+        let payload = json!({ "admin": true, "name": "Ana", "missing": null });
+        let args = json_args(&payload, &JsonArgsOptions::default()).unwrap();
+        assert_eq!(args.with_args(|args| ctx.format(&m, args)), "Admin Ana: ");
+        let empty = json!({});
+        let args = json_args(&empty, &JsonArgsOptions::default()).unwrap();
+        assert!(args.with_args(|args| args.is_none()));
+    }
+
+    #[test]
+    fn errors() {
+        let options = JsonArgsOptions { flatten: false };
+        assert_eq!(json_args(&json!({ "user": { "name": "Ana" } }), &options),
+                   Err(JsonArgsError::Nested("user".to_string())));
+        assert_eq!(json_args(&json!({ "a": { "tags": [] } }), &JsonArgsOptions::default()),
+                   Err(JsonArgsError::Array("a.tags".to_string())));
+        assert_eq!(json_args(&json!("text"), &options), Err(JsonArgsError::NotAnObject));
+        assert_eq!(JsonArgsError::Nested("user".to_string()).to_string(),
+                   "The argument 'user' is an object, which isn't flattened.");
+    }
+}
@@ -6,6 +6,9 @@
 
 #![allow(missing_docs)]
 
+use std::string::String;
+use std::vec::Vec;
+
 #[derive(Debug)]
 pub enum Expression {
     IdentifierExpression {
@@ -9,6 +9,8 @@
 use std::error::Error;
 use std::fmt;
 use std::str;
+use std::string::String;
+use std::vec::Vec;
 use super::ast::*;
 
 pub fn parse(source: &str) -> Result<Vec<Entry>, ParseError> {
@@ -27,15 +29,11 @@ impl ParseError {
     }
 }
 
-impl Error for ParseError {
-    fn description(&self) -> &str {
-        &self.error_message
-    }
-}
+impl Error for ParseError {}
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        self.description().fmt(f)
+        self.error_message.fmt(f)
     }
 }
 
@@ -85,7 +83,7 @@ impl<'a> Parser<'a> {
         self.bump();
 
         loop {
-            if self.ch == None {
+            if self.ch.is_none() {
                 break;
             }
 
@@ -104,7 +102,7 @@ impl<'a> Parser<'a> {
     }
 
     fn get_entity(&mut self, comment: Option<Comment>) -> Result<Entry, ParseError> {
-        let id = try!(self.get_identifier());
+        let id = self.get_identifier()?;
         self.get_line_ws();
 
         if !self.ch_is('=') {
@@ -117,9 +115,9 @@ impl<'a> Parser<'a> {
         match self.get_pattern() {
             Ok(value) => {
                 Ok(Entry::Entity {
-                    id: id,
-                    comment: comment,
-                    value: value,
+                    id,
+                    comment,
+                    value,
                 })
             }
             Err(err) => Err(err),
@@ -135,20 +133,20 @@ impl<'a> Parser<'a> {
         };
 
         match ch {
-            'a'...'z' | 'A'...'Z' | '_' => name.push(ch),
-            _ => return Ok(Identifier { name: name }),
+            'a'..='z' | 'A'..='Z' | '_' => name.push(ch),
+            _ => return Ok(Identifier { name }),
         }
         self.bump();
 
         while let Some(ch) = self.ch {
             match ch {
-                'a'...'z' | 'A'...'Z' | '0'...'9' | '_' | '-' => name.push(ch),
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' => name.push(ch),
                 _ => break,
             }
             self.bump();
         }
 
-        Ok(Identifier { name: name })
+        Ok(Identifier { name })
     }
 
     fn get_pattern(&mut self) -> Result<Value, ParseError> {
@@ -164,7 +162,7 @@ impl<'a> Parser<'a> {
 
         loop {
             match self.ch {
-                Some(c) if c == '\n' => {
+                Some('\n') => {
                     if quote_delimited {
                         return Err(ParseError::new("Unclosed string"));
                     }
@@ -188,7 +186,7 @@ impl<'a> Parser<'a> {
                     }
                     continue;
                 }
-                Some(c) if c == '"' => {
+                Some('"') => {
                     self.bump();
                     quote_delimited = false;
                     break;
@@ -219,7 +217,7 @@ impl<'a> Parser<'a> {
         content.push(PatternElement::TextElement { value: source.clone() });
 
         Ok(Value::Pattern {
-            source: source,
+            source,
             elements: content,
         })
     }
@@ -230,16 +228,14 @@ mod tests {
     use super::*;
 
     fn expected_parse(name: &str, source: &str) {
-        match parse(source) {
-            Err(e) => panic!("Parse failed: {}: {}", name, e),
-            _ => {}
+        if let Err(e) = parse(source) {
+            panic!("Parse failed: {}: {}", name, e);
         }
     }
 
     fn expected_failure(name: &str, source: &str) {
-        match parse(source) {
-            Ok(_) => panic!("Parse unexpectedly worked: {}", name),
-            _ => {}
+        if parse(source).is_ok() {
+            panic!("Parse unexpectedly worked: {}", name);
         }
     }
 
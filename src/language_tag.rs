@@ -0,0 +1,230 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! [BCP 47] language tags for a [`Locale`]. They are parsed here rather
+//! than by the `language_tags` crate, which needs `std`, so that the
+//! same parser is used with and without the `std` feature. The same
+//! tags are accepted, and their subtags are normalized the same way.
+//!
+//! [BCP 47]: https://tools.ietf.org/html/bcp47
+//! [`Locale`]: ../struct.Locale.html
+
+use std::fmt;
+use std::str::FromStr;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use LocaleError;
+
+/// The irregular and regular grandfathered tags, which are a language
+/// as a whole.
+const GRANDFATHERED: [&str; 26] = ["art-lojban", "cel-gaulish", "en-GB-oed", "i-ami", "i-bnn",
+                                   "i-default", "i-enochian", "i-hak", "i-klingon", "i-lux",
+                                   "i-mingo", "i-navajo", "i-pwn", "i-tao", "i-tay", "i-tsu",
+                                   "no-bok", "no-nyn", "sgn-BE-FR", "sgn-BE-NL", "sgn-CH-DE",
+                                   "zh-guoyu", "zh-hakka", "zh-min", "zh-min-nan", "zh-xiang"];
+
+/// A well-formed [BCP 47] language tag, which identifies a [`Locale`].
+/// The case of its subtags is normalized when it is parsed.
+///
+/// ```
+/// use message_format::LanguageTag;
+///
+/// let tag: LanguageTag = "sr-latn-rs-u-nu-latn".parse().unwrap();
+/// assert_eq!(tag.to_string(), "sr-Latn-RS-u-nu-latn");
+/// assert_eq!((tag.language(), tag.script(), tag.region()),
+///            (Some("sr"), Some("Latn"), Some("RS")));
+///
+/// // A private use tag has no language.
+/// assert_eq!("x-private".parse::<LanguageTag>().unwrap().language(), None);
+/// ```
+///
+/// [BCP 47]: https://tools.ietf.org/html/bcp47
+/// [`Locale`]: struct.Locale.html
+#[derive(Clone,Debug,PartialEq)]
+pub struct LanguageTag {
+    /// The tag, with the case of its subtags normalized.
+    tag: String,
+    language: Option<String>,
+    script: Option<String>,
+    region: Option<String>,
+}
+
+impl LanguageTag {
+    /// The language subtag, like `en`, or a grandfathered tag, like
+    /// `i-klingon`, as a whole. This is `None` for a private use tag.
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    /// The script subtag, like `Hant`, if there is one.
+    pub fn script(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+
+    /// The region subtag, like `US` or `419`, if there is one.
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+}
+
+/// Where a subtag is within a tag, which determines what it can be.
+#[derive(Clone,Copy,PartialEq,PartialOrd)]
+enum Position {
+    Language,
+    Extlang,
+    Script,
+    Region,
+    Variant,
+    Extension(u8),
+    PrivateUse,
+}
+
+impl FromStr for LanguageTag {
+    type Err = LocaleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s).ok_or_else(|| LocaleError::Malformed(s.to_string()))
+    }
+}
+
+/// Parse a language tag, or return `None` if it is malformed.
+fn parse(s: &str) -> Option<LanguageTag> {
+    let s = s.trim();
+    if !s.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-') {
+        return None;
+    }
+    if let Some(tag) = GRANDFATHERED.iter().find(|tag| tag.eq_ignore_ascii_case(s)) {
+        return Some(LanguageTag {
+            tag: tag.to_ascii_lowercase(),
+            language: Some(tag.to_string()),
+            script: None,
+            region: None,
+        });
+    }
+    let alphabetic = |subtag: &str| subtag.bytes().all(|b| b.is_ascii_alphabetic());
+    let numeric = |subtag: &str| subtag.bytes().all(|b| b.is_ascii_digit());
+    let mut tag = LanguageTag {
+        tag: String::new(),
+        language: None,
+        script: None,
+        region: None,
+    };
+    let mut extlangs = vec![];
+    let mut variants = vec![];
+    let mut extensions: Vec<(u8, Vec<&str>)> = vec![];
+    let mut private_use = vec![];
+    let mut position = Position::Language;
+    for subtag in s.split('-') {
+        let len = subtag.len();
+        if len > 8 {
+            return None;
+        }
+        if position == Position::PrivateUse {
+            private_use.push(subtag);
+        } else if subtag.eq_ignore_ascii_case("x") {
+            position = Position::PrivateUse;
+        } else if position == Position::Language {
+            if len < 2 || !alphabetic(subtag) {
+                return None;
+            }
+            tag.language = Some(subtag.to_ascii_lowercase());
+            // Only short languages can have extended languages.
+            position = if len < 4 { Position::Extlang } else { Position::Script };
+        } else if position == Position::Extlang && len == 3 && alphabetic(subtag) {
+            extlangs.push(subtag.to_ascii_lowercase());
+        } else if position <= Position::Script && len == 4 && alphabetic(subtag) {
+            let mut script = subtag.to_ascii_lowercase();
+            script[..1].make_ascii_uppercase();
+            tag.script = Some(script);
+            position = Position::Region;
+        } else if position <= Position::Region &&
+                  (len == 2 && alphabetic(subtag) || len == 3 && numeric(subtag)) {
+            tag.region = Some(subtag.to_ascii_uppercase());
+            position = Position::Variant;
+        } else if position <= Position::Variant &&
+                  (len >= 5 && alphabetic(&subtag[..1]) || len >= 4 && numeric(&subtag[..1])) {
+            variants.push(subtag.to_ascii_lowercase());
+            position = Position::Variant;
+        } else if len == 1 {
+            let singleton = subtag.as_bytes()[0];
+            if extensions.iter().any(|&(s, _)| s == singleton) {
+                return None;
+            }
+            extensions.push((singleton, vec![]));
+            position = Position::Extension(singleton);
+        } else if let Position::Extension(_) = position {
+            if let Some((_, values)) = extensions.last_mut() {
+                values.push(subtag);
+            }
+        } else {
+            return None;
+        }
+    }
+    if extlangs.len() > 2 || extensions.iter().any(|(_, values)| values.is_empty()) ||
+       position == Position::PrivateUse && private_use.is_empty() {
+        return None;
+    }
+
+    let mut subtags: Vec<&str> = tag.language.iter().map(String::as_str).collect();
+    subtags.extend(extlangs.iter().map(String::as_str));
+    subtags.extend(tag.script.as_deref());
+    subtags.extend(tag.region.as_deref());
+    subtags.extend(variants.iter().map(String::as_str));
+    extensions.sort_by_key(|&(singleton, _)| singleton.to_ascii_uppercase());
+    let mut singletons = String::new();
+    for &(singleton, _) in &extensions {
+        singletons.push(singleton as char);
+    }
+    for (index, (_, values)) in extensions.iter().enumerate() {
+        subtags.push(&singletons[index..index + 1]);
+        subtags.extend(values);
+    }
+    if !private_use.is_empty() {
+        subtags.push("x");
+        subtags.extend(private_use);
+    }
+    tag.tag = subtags.join("-");
+    Some(tag)
+}
+
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        self.tag.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LanguageTag;
+    use language_tags;
+
+    #[test]
+    fn same_as_language_tags() {
+        let tags = ["en", "EN-us", "zh-Hant-TW", "sr-latn-rs", "de-CH-1996", "sl-rozaj-biske",
+                    "en-US-u-ca-buddhist", "en-a-bbb-B-ccc-x-Private", "zh-yue-HK", "ar-aao",
+                    "zh-min-nan", "I-KLINGON", "x-private", "en-x-a-b", "es-419", "  fr  ", "",
+                    "en_US", "en--US", "123", "toolonglanguage", "en-", "en-x", "en-u",
+                    "en-u-ca-u-nu", "en-US-US", "aaa-bbb-ccc-ddd-eee", "en-1234", "en-a-b-c",
+                    "en-Latn-US-valencia-1994", "ja-Jpan-JP-u-nu-jpanfin", "e", "Ελληνικά"];
+        for &tag in &tags {
+            let ours = tag.parse::<LanguageTag>();
+            let theirs = tag.parse::<language_tags::LanguageTag>();
+            assert_eq!(ours.is_ok(), theirs.is_ok(), "{}", tag);
+            if let (Ok(ours), Ok(theirs)) = (ours, theirs) {
+                // The subtags of a parsed tag keep their case until it
+                // is written.
+                let normalized = theirs.to_string();
+                let theirs = normalized.parse::<language_tags::LanguageTag>().unwrap();
+                assert_eq!(ours.to_string(), normalized, "{}", tag);
+                assert_eq!((ours.language, ours.script, ours.region),
+                           (theirs.language, theirs.script, theirs.region),
+                           "{}",
+                           tag);
+            }
+        }
+    }
+}
@@ -104,6 +104,18 @@
 //!
 //! For details on the [ICU Message Format] syntax, see the [`icu` module].
 //!
+//! The types and functions that are used most often, like [`Context`],
+//! [`Message`] and [`parse`], can be imported together from the
+//! [`prelude`]:
+//!
+//! ```
+//! use message_format::prelude::*;
+//!
+//! let m = parse("Connecting to {host}...").unwrap();
+//! assert_eq!(Context::default().format(&m, Some(&arg("host", "localhost"))),
+//!            "Connecting to localhost...");
+//! ```
+//!
 //! ## L20n Formatted Messages
 //!
 //! Support for the [L20n] localization format is under development.
@@ -135,6 +147,20 @@
 //! }
 //! ```
 //!
+//! ## Without `std`
+//!
+//! Parsing and formatting messages only needs `alloc`, so this crate can
+//! be used by a `no_std` crate by turning off its default `std` feature:
+//!
+//! ```toml
+//! [dependencies]
+//! message-format = { version = "0.0.1", default-features = false }
+//! ```
+//!
+//! Loading bundles of messages, with [`MessageBundle`] and the `build`
+//! and `convert` modules, needs `std`, as do the features that depend
+//! upon other crates that need it.
+//!
 //! ## Future Directions
 //!
 //! In the future, we want to extend this library to support a number of
@@ -159,32 +185,158 @@
 //! [L20n]: http://l20n.org/
 //! [`parse`]: icu/fn.parse.html
 //! [`Message`]: struct.Message.html
+//! [`Context`]: struct.Context.html
+//! [`prelude`]: prelude/index.html
 //! [`Value`]: enum.Value.html
+//! [`MessageBundle`]: struct.MessageBundle.html
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![warn(missing_docs)]
 #![deny(trivial_numeric_casts,
         unsafe_code, unstable_features,
         unused_import_braces, unused_qualifications)]
 
-extern crate language_tags;
+#[cfg(not(any(feature = "std", test)))]
 #[macro_use]
-extern crate nom;
+extern crate alloc;
+
+#[cfg(feature = "intl-pluralrules")]
+extern crate intl_pluralrules;
+#[cfg(feature = "wasm")]
+extern crate js_sys;
+#[cfg(test)]
+extern crate language_tags;
+#[cfg(feature = "derive")]
+extern crate message_format_derive;
+#[cfg(feature = "serde_json")]
+extern crate serde_json;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(feature = "intl-pluralrules")]
+extern crate unic_langid;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
 
+/// Without the `std` feature, the parts of `std` that are in `core` and
+/// `alloc`, so that they are used by the same paths. The tests use
+/// `std` either way.
+#[cfg(not(any(feature = "std", test)))]
+mod std {
+    pub use core::{char, convert, fmt, iter, ptr, str};
+    #[cfg(feature = "derive")]
+    pub use core::{ops, option};
+    pub use alloc::{borrow, boxed, collections, string, vec};
+
+    pub mod error {
+        pub use core::error::Error;
+    }
+
+    pub mod sync {
+        pub use alloc::sync::Arc;
+        pub use core::sync::atomic;
+    }
+}
+
+#[cfg(feature = "std")]
+pub mod build;
+#[cfg(feature = "std")]
+pub mod convert;
+#[cfg(feature = "ffi")]
+#[allow(unsafe_code)]
+pub mod ffi;
 pub mod icu;
 pub mod l20n;
+pub mod plural_rules;
+pub mod prelude;
+#[cfg(feature = "wasm")]
+#[allow(unsafe_code)]
+pub mod wasm;
+pub mod writers;
 mod args;
+mod bidi_isolation;
+#[cfg(feature = "std")]
+mod bundle_stack;
+#[cfg(feature = "std")]
+mod consistency;
 mod context;
+mod context_builder;
+mod format_error;
+mod format_observer;
+mod json;
+mod language_tag;
+#[cfg(feature = "serde_json")]
+mod json_args;
+mod locale;
 mod macros;
 mod message;
+mod message_args;
+#[cfg(feature = "std")]
+mod message_bundle;
+mod message_diff;
 mod message_part;
+mod message_visitor;
+mod nesting;
+mod number_symbols;
 mod plural_category;
 mod plural_classifiers;
+mod plural_operands;
+#[cfg(feature = "std")]
+mod properties;
+mod pseudolocalization;
+mod sanitization;
+#[cfg(feature = "serde_json")]
+mod shared_bundle;
 mod value;
+mod variables;
+#[cfg(feature = "std")]
+mod xml;
+#[cfg(feature = "yaml")]
+mod yaml;
 
 pub use self::args::{arg, Args};
+pub use self::bidi_isolation::BidiIsolation;
+#[cfg(feature = "std")]
+pub use self::bundle_stack::{BundleStack, FormattedMessage};
+#[cfg(feature = "std")]
+pub use self::consistency::{validate_against, ConsistencyIssue, ConsistencyIssueKind};
 pub use self::context::Context;
-pub use self::message::Message;
+pub use self::context_builder::ContextBuilder;
+pub use self::format_error::FormatError;
+pub use self::format_observer::FormatObserver;
+#[cfg(feature = "tracing")]
+pub use self::format_observer::TracingObserver;
+#[cfg(feature = "serde_json")]
+pub use self::json_args::{json_args, JsonArgs, JsonArgsError, JsonArgsOptions};
+pub use self::language_tag::LanguageTag;
+pub use self::locale::{negotiate_locales, resolve_locale, Locale, LocaleError};
+pub use self::message::{BoundMessage, Message};
+pub use self::message_args::{IntoValue, MessageArgs};
+#[cfg(feature = "derive")]
+pub use message_format_derive::MessageArgs;
+#[cfg(feature = "std")]
+pub use self::message_bundle::{BundleError, KeyTree, MessageBundle, MessageMetadata,
+                                Placeholder, PropertiesEncoding};
+#[cfg(feature = "serde_json")]
+pub use self::message_bundle::ReloadReport;
+pub use self::message_diff::MessageDiff;
 pub use self::message_part::MessagePart;
-pub use self::plural_category::PluralCategory;
+pub use self::message_visitor::MessageVisitor;
+pub use self::number_symbols::NumberSymbols;
+pub use self::plural_category::{ParseCategoryError, PluralCategory};
 pub use self::plural_classifiers::*;
-pub use self::value::{AsValue, Value};
+pub use self::plural_operands::{ParseOperandsError, PluralOperands};
+pub use self::pseudolocalization::{PseudoLocalizeOptions, Pseudolocalization};
+pub use self::sanitization::Sanitization;
+#[cfg(feature = "serde_json")]
+pub use self::shared_bundle::SharedBundle;
+pub use self::value::{AsValue, DecimalStr, Value};
+pub use self::variables::{VariableInfo, VariableKind};
+
+/// The paths in the code that `#[derive(MessageArgs)]` generates, which
+/// are the same whether or not the deriving crate uses `std`.
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __derive {
+    pub use std::ops::FnOnce;
+    pub use std::option::Option;
+}
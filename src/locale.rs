@@ -0,0 +1,388 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use LanguageTag;
+
+/// An error resulting from parsing a [`Locale`].
+///
+/// [`Locale`]: struct.Locale.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum LocaleError {
+    /// The locale is not a well-formed [BCP 47] language tag.
+    ///
+    /// [BCP 47]: https://tools.ietf.org/html/bcp47
+    Malformed(String),
+    /// The locale does not specify a language, as with a private use
+    /// tag like `x-private`.
+    MissingLanguage(String),
+}
+
+impl Error for LocaleError {}
+
+impl fmt::Display for LocaleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            LocaleError::Malformed(ref locale) => write!(f, "Malformed locale '{}'.", locale),
+            LocaleError::MissingLanguage(ref locale) => {
+                write!(f, "Locale '{}' has no language.", locale)
+            }
+        }
+    }
+}
+
+/// The locale being localized for, identified by a [BCP 47] language
+/// tag such as `en-US` or `zh-Hant-TW`.
+///
+/// The case of the subtags is normalized, so `EN-us` is the same
+/// locale as `en-US`.
+///
+/// ```
+/// use message_format::Locale;
+///
+/// let locale: Locale = "sr-latn-rs".parse().unwrap();
+/// assert_eq!(locale.to_string(), "sr-Latn-RS");
+/// assert_eq!(locale.language(), "sr");
+/// assert_eq!(locale.script(), Some("Latn"));
+/// assert_eq!(locale.region(), Some("RS"));
+///
+/// assert!("en_US".parse::<Locale>().is_err());
+/// ```
+///
+/// [BCP 47]: https://tools.ietf.org/html/bcp47
+#[derive(Clone,Debug,PartialEq)]
+pub struct Locale {
+    tag: LanguageTag,
+}
+
+impl Locale {
+    /// The `en-US` locale, which is the default.
+    pub fn en_us() -> Self {
+        Locale::new("en-US").unwrap()
+    }
+
+    /// Parse and validate a locale.
+    pub fn new(locale: &str) -> Result<Self, LocaleError> {
+        let tag: LanguageTag = locale.parse()?;
+        if tag.language().is_none() {
+            return Err(LocaleError::MissingLanguage(locale.to_string()));
+        }
+        Ok(Locale { tag })
+    }
+
+    /// The language subtag, like `en`.
+    pub fn language(&self) -> &str {
+        self.tag.language().unwrap_or("")
+    }
+
+    /// The script subtag, like `Hant`, if there is one.
+    pub fn script(&self) -> Option<&str> {
+        self.tag.script()
+    }
+
+    /// The region subtag, like `US`, if there is one.
+    pub fn region(&self) -> Option<&str> {
+        self.tag.region()
+    }
+
+    /// Whether the locale is written right to left, like `ar`, `he` and
+    /// `ks-Arab`.
+    pub fn is_right_to_left(&self) -> bool {
+        let any = |codes: &[&str], code: &str| {
+            codes.iter().any(|c| c.eq_ignore_ascii_case(code))
+        };
+        match self.script() {
+            Some(script) => any(&["Adlm", "Arab", "Hebr", "Nkoo", "Rohg", "Syrc", "Thaa"], script),
+            None => {
+                any(&["ar", "ckb", "dv", "fa", "he", "iw", "ps", "sd", "ug", "ur", "yi"],
+                    self.language())
+            }
+        }
+    }
+
+    /// The underlying language tag.
+    pub fn language_tag(&self) -> &LanguageTag {
+        &self.tag
+    }
+
+    /// The locales to look up data for, from most to least specific.
+    /// Extensions and variants are removed, then the region and then
+    /// the script, so `sr-Latn-RS-u-nu-latn` gives `sr-Latn-RS`,
+    /// `sr-Latn`, `sr-RS` and `sr`.
+    pub fn fallbacks(&self) -> Vec<String> {
+        let language = self.language();
+        let mut fallbacks = vec![];
+        if let (Some(script), Some(region)) = (self.script(), self.region()) {
+            fallbacks.push(format!("{}-{}-{}", language, script, region));
+        }
+        if let Some(script) = self.script() {
+            fallbacks.push(format!("{}-{}", language, script));
+        }
+        if let Some(region) = self.region() {
+            fallbacks.push(format!("{}-{}", language, region));
+        }
+        fallbacks.push(language.to_string());
+        fallbacks
+    }
+}
+
+/// Find the best match for a `requested` locale among the `available`
+/// locales, using the [`Locale::fallbacks`] of the requested locale and
+/// then the root locale, as `root` or `und`, or `en`.
+///
+/// Locales are compared without regard to case, and `_` may be used
+/// rather than `-`. `None` is returned if the requested locale is
+/// malformed or there is no match.
+///
+/// ```
+/// use message_format::resolve_locale;
+///
+/// let available = ["en", "pt", "pt-PT", "zh-Hant"];
+/// assert_eq!(resolve_locale("pt-BR", &available), Some("pt"));
+/// assert_eq!(resolve_locale("pt_PT", &available), Some("pt-PT"));
+/// assert_eq!(resolve_locale("zh-Hant-TW", &available), Some("zh-Hant"));
+/// assert_eq!(resolve_locale("ja", &available), Some("en"));
+/// ```
+///
+/// [`Locale::fallbacks`]: struct.Locale.html#method.fallbacks
+pub fn resolve_locale<'a>(requested: &str, available: &[&'a str]) -> Option<&'a str> {
+    let locale = Locale::new(&requested.replace('_', "-")).ok()?;
+    let find = |candidate: &str| {
+        available.iter()
+            .find(|locale| locale.replace('_', "-").eq_ignore_ascii_case(candidate))
+            .cloned()
+    };
+    locale.fallbacks()
+        .iter()
+        .map(String::as_str)
+        .chain(["root", "und", "en"])
+        .find_map(find)
+}
+
+/// Choose the `available` locales to use, in order of preference, for
+/// the `requested` locales, which may have the quality weights of an
+/// HTTP `Accept-Language` header.
+///
+/// Each of the `requested` locales may be a whole header, like
+/// `de-CH, de;q=0.9, *;q=0.1`, and they are used in order of their
+/// weights, which are 1 if there is none. Locales with a weight of 0
+/// are not used. As in [RFC 4647] filtering, each is matched by the
+/// available locales that are equal to it or more specific, and then
+/// the same is done for its [`Locale::fallbacks`], so `de-CH` is
+/// matched by `de-CH`, then `de` and then `de-AT`. A `*` is matched by
+/// all of the available locales. The `default` locale is last, if it
+/// isn't already included.
+///
+/// As with [`resolve_locale`], locales are compared without regard to
+/// case, and `_` may be used rather than `-`. Locales are returned as
+/// they are written in `available`.
+///
+/// ```
+/// use message_format::negotiate_locales;
+///
+/// let available = ["de", "de-AT", "en", "fr"];
+/// assert_eq!(negotiate_locales(&["de-CH, fr;q=0.5"], &available, "en"),
+///            ["de", "de-AT", "fr", "en"]);
+/// assert_eq!(negotiate_locales(&["pt-BR", "pt"], &available, "en"), ["en"]);
+/// ```
+///
+/// [RFC 4647]: https://tools.ietf.org/html/rfc4647
+/// [`Locale::fallbacks`]: struct.Locale.html#method.fallbacks
+/// [`resolve_locale`]: fn.resolve_locale.html
+pub fn negotiate_locales(requested: &[&str], available: &[&str], default: &str) -> Vec<String> {
+    let normalize = |locale: &str| locale.replace('_', "-").to_ascii_lowercase();
+    let mut ranges = requested.iter()
+        .flat_map(|requested| requested.split(','))
+        .filter_map(|range| {
+            let mut params = range.split(';');
+            let range = params.next().unwrap_or("").trim();
+            let mut weight = 1.0;
+            for param in params {
+                if let Some(value) = param.trim().strip_prefix("q=") {
+                    weight = value.trim().parse::<f32>().ok()?;
+                }
+            }
+            if range.is_empty() || weight.is_nan() || weight <= 0.0 {
+                return None;
+            }
+            Some((range, weight))
+        })
+        .collect::<Vec<_>>();
+    // The sort is stable, so locales with the same weight stay in order.
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let available = available.iter().map(|&locale| (locale, normalize(locale))).collect::<Vec<_>>();
+    let mut chosen: Vec<String> = vec![];
+    let mut choose = |locale: &str| {
+        if !chosen.iter().any(|chosen| chosen == locale) {
+            chosen.push(locale.to_string());
+        }
+    };
+    for (range, _) in ranges {
+        if range == "*" {
+            available.iter().for_each(|&(locale, _)| choose(locale));
+            continue;
+        }
+        let locale = match Locale::new(&range.replace('_', "-")) {
+            Ok(locale) => locale,
+            Err(_) => continue,
+        };
+        for fallback in locale.fallbacks() {
+            let fallback = fallback.to_ascii_lowercase();
+            let prefix = format!("{}-", fallback);
+            for &(locale, ref normalized) in &available {
+                if *normalized == fallback {
+                    choose(locale);
+                }
+            }
+            for &(locale, ref normalized) in &available {
+                if normalized.starts_with(&prefix) {
+                    choose(locale);
+                }
+            }
+        }
+    }
+    if !default.is_empty() {
+        choose(default);
+    }
+    chosen
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::en_us()
+    }
+}
+
+impl FromStr for Locale {
+    type Err = LocaleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Locale::new(s)
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        self.tag.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_locales() {
+        let locales = ["en", "en-US", "pt-BR", "zh-Hant-TW", "de-CH-1996", "en-US-u-ca-buddhist"];
+        for &locale in &locales {
+            assert_eq!(Locale::new(locale).unwrap().to_string(), locale);
+        }
+    }
+
+    #[test]
+    fn case_is_normalized() {
+        let locale = Locale::new("EN-us").unwrap();
+        assert_eq!(locale.to_string(), "en-US");
+        assert_eq!(locale.language(), "en");
+        assert_eq!(locale.region(), Some("US"));
+        assert_eq!(locale, Locale::en_us());
+    }
+
+    #[test]
+    fn right_to_left() {
+        for &locale in &["ar", "ar-EG", "he-IL", "fa", "ks-Arab", "az-arab"] {
+            assert!(Locale::new(locale).unwrap().is_right_to_left(), "{}", locale);
+        }
+        for &locale in &["en", "de-CH", "sr-Cyrl", "ug-Latn", "hi"] {
+            assert!(!Locale::new(locale).unwrap().is_right_to_left(), "{}", locale);
+        }
+    }
+
+    #[test]
+    fn invalid_locales() {
+        for &locale in &["", "en_US", "en--US", "123", "toolonglanguage", "en-"] {
+            assert_eq!(Locale::new(locale), Err(LocaleError::Malformed(locale.to_string())));
+        }
+        assert_eq!(Locale::new("x-private"),
+                   Err(LocaleError::MissingLanguage("x-private".to_string())));
+    }
+
+    #[test]
+    fn fallbacks() {
+        let fallbacks = |locale: &str| Locale::new(locale).unwrap().fallbacks();
+        assert_eq!(fallbacks("sr-Latn-RS-u-nu-latn"), ["sr-Latn-RS", "sr-Latn", "sr-RS", "sr"]);
+        assert_eq!(fallbacks("de-CH-1996"), ["de-CH", "de"]);
+        assert_eq!(fallbacks("en"), ["en"]);
+    }
+
+    #[test]
+    fn resolution() {
+        let available = ["en", "pt", "pt-PT", "sr-Latn", "zh_Hant", "zh"];
+        assert_eq!(resolve_locale("pt-BR", &available), Some("pt"));
+        assert_eq!(resolve_locale("pt-PT", &available), Some("pt-PT"));
+        assert_eq!(resolve_locale("PT-pt", &available), Some("pt-PT"));
+        assert_eq!(resolve_locale("zh-Hant-TW", &available), Some("zh_Hant"));
+        assert_eq!(resolve_locale("zh-Hans-CN", &available), Some("zh"));
+        assert_eq!(resolve_locale("sr-Latn-RS", &available), Some("sr-Latn"));
+        assert_eq!(resolve_locale("en-US-x-private", &available), Some("en"));
+        assert_eq!(resolve_locale("fr", &available), Some("en"));
+        assert_eq!(resolve_locale("fr", &["de", "root"]), Some("root"));
+        assert_eq!(resolve_locale("fr", &["de"]), None);
+        for &garbage in &["", "!!!", "en--US", "x-private"] {
+            assert_eq!(resolve_locale(garbage, &available), None);
+        }
+    }
+
+    #[test]
+    fn negotiation() {
+        let available = ["de", "de-AT", "de-CH", "en", "en-GB", "fr", "pt_BR"];
+        let negotiate = |requested: &[&str]| negotiate_locales(requested, &available, "en");
+        assert_eq!(negotiate(&["de-CH"]), ["de-CH", "de", "de-AT", "en"]);
+        assert_eq!(negotiate(&["en-GB", "fr"]), ["en-GB", "en", "fr"]);
+        assert_eq!(negotiate(&["fr-CA", "ja"]), ["fr", "en"]);
+        assert_eq!(negotiate(&[]), ["en"]);
+        assert_eq!(negotiate_locales(&["ja"], &available, ""), Vec::<String>::new());
+    }
+
+    #[test]
+    fn negotiation_weights() {
+        let available = ["de", "en", "fr", "it"];
+        let negotiate = |requested: &[&str]| negotiate_locales(requested, &available, "en");
+        assert_eq!(negotiate(&["fr;q=0.5, it, de;q=0.8"]), ["it", "de", "fr", "en"]);
+        assert_eq!(negotiate(&["fr;q=0.5", "it;q=0.5", "de; q=0.7"]), ["de", "fr", "it", "en"]);
+        // A weight of 0 means the locale is not acceptable.
+        assert_eq!(negotiate(&["fr, de;q=0"]), ["fr", "en"]);
+        assert_eq!(negotiate(&["fr;q=abc, it"]), ["it", "en"]);
+        assert_eq!(negotiate(&["fr, , !!!, en--US, it"]), ["fr", "it", "en"]);
+    }
+
+    #[test]
+    fn negotiation_wildcard() {
+        let available = ["de", "en", "fr"];
+        assert_eq!(negotiate_locales(&["fr, *;q=0.1"], &available, "en"), ["fr", "de", "en"]);
+        assert_eq!(negotiate_locales(&["*"], &available, "en"), ["de", "en", "fr"]);
+    }
+
+    #[test]
+    fn negotiation_ignores_case() {
+        let available = ["DE-at", "en", "pt_BR", "zh-Hant"];
+        assert_eq!(negotiate_locales(&["de-AT"], &available, "en"), ["DE-at", "en"]);
+        assert_eq!(negotiate_locales(&["PT-br"], &available, "en"), ["pt_BR", "en"]);
+        assert_eq!(negotiate_locales(&["pt"], &available, "en"), ["pt_BR", "en"]);
+        assert_eq!(negotiate_locales(&["zh-hant-tw"], &available, "en"), ["zh-Hant", "en"]);
+    }
+
+    #[test]
+    fn default_is_en_us() {
+        assert_eq!(Locale::default().to_string(), "en-US");
+    }
+}
@@ -4,6 +4,21 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+/// Format a message, returning a `String`.
+///
+/// Arguments are given as `name => value` or, when a variable with the
+/// same name as the argument is in scope, as just `name`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate message_format;
+///
+/// fn main() {
+///     let ctx = message_format::Context::default();
+///     let m = message_format::icu::parse("Hello, {name}!").unwrap();
+///     assert_eq!(format_message!(ctx, &m, name => "Jane"), "Hello, Jane!");
+/// }
+/// ```
 #[macro_export]
 macro_rules! format_message {
     ($ctx:expr, $msg:expr) => {
@@ -15,6 +30,22 @@ macro_rules! format_message {
     });
 }
 
+/// Write a message to a `fmt::Write` stream.
+///
+/// Arguments are specified in the same way as with `format_message!`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate message_format;
+///
+/// fn main() {
+///     let ctx = message_format::Context::default();
+///     let m = message_format::icu::parse("Hello, {name}!").unwrap();
+///     let mut output = String::new();
+///     write_message!(ctx, &m, &mut output, name => "Jane").unwrap();
+///     assert_eq!(output, "Hello, Jane!");
+/// }
+/// ```
 #[macro_export]
 macro_rules! write_message {
     ($ctx:expr, $msg:expr, $stream:expr) => {
@@ -26,6 +57,8 @@ macro_rules! write_message {
     });
 }
 
+/// Helper for `message_args!`. Not intended to be used directly.
+#[doc(hidden)]
 #[macro_export]
 macro_rules! message_args_aux {
     ($prev:expr, $name:ident => $value:expr) => {
@@ -62,6 +95,12 @@ macro_rules! message_args_aux {
     };
 }
 
+/// Build an `Option<&Args>` from a list of arguments.
+///
+/// This is used by `format_message!` and `write_message!`. The values
+/// must implement [`AsValue`], so that trait must be in scope.
+///
+/// [`AsValue`]: trait.AsValue.html
 #[macro_export]
 macro_rules! message_args {
     () => { None };
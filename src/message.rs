@@ -4,9 +4,17 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::borrow::Cow;
+use std::boxed::Box;
 use std::fmt;
+use std::string::{String, ToString};
+use std::sync::Arc;
+use std::vec::Vec;
 
-use {Args, Context, MessagePart};
+use icu::ast::PlainText;
+use variables::Variables;
+use {Args, Context, FormatError, MessageDiff, MessagePart, MessageVisitor, PseudoLocalizeOptions,
+     Pseudolocalization, VariableInfo};
 
 /// A message that has been localized and can be formatted in a
 /// locale-aware manner.
@@ -14,17 +22,70 @@ use {Args, Context, MessagePart};
 /// While a `Message` can be created directly from [`MessagePart`]
 /// components, it is easiest to create it from [`icu::parse`].
 ///
+/// A `Message` is `Send` and `Sync` and formatting it doesn't modify
+/// it, so a message can be parsed once and then formatted from many
+/// threads at the same time, typically by sharing it within an `Arc`.
+/// Cloning a message shares its parts.
+///
 /// [`MessagePart`]: trait.MessagePart.html
 /// [`icu::parse`]: icu/fn.parse.html
-#[derive(Debug)]
+#[derive(Clone,Debug)]
 pub struct Message {
-    parts: Vec<Box<MessagePart>>,
+    /// The parts are shared so that the parts of a message that
+    /// `partial` can't resolve can be kept without copying them.
+    parts: Vec<Arc<dyn MessagePart>>,
 }
 
 impl Message {
     /// Construct a message from constituent parts.
-    pub fn new(parts: Vec<Box<MessagePart>>) -> Self {
-        Message { parts: parts }
+    pub fn new(parts: Vec<Box<dyn MessagePart>>) -> Self {
+        Message { parts: parts.into_iter().map(Arc::from).collect() }
+    }
+
+    /// Construct a message from parts that may be shared with other
+    /// messages, like those from [`into_parts`]. A new part is added as
+    /// `Arc::new(part)`, and a `Box<dyn MessagePart>` with `Arc::from`.
+    ///
+    /// [`into_parts`]: #method.into_parts
+    pub fn from_parts(parts: Vec<Arc<dyn MessagePart>>) -> Self {
+        Message { parts }
+    }
+
+    /// Take the parts of the message, so that it can be transformed
+    /// and put back together with [`from_parts`].
+    ///
+    /// The parts are `Arc<dyn MessagePart>` rather than the
+    /// `Box<dyn MessagePart>` that [`new`] takes, because they are shared
+    /// with any clones of the message and with messages built from it by
+    /// [`partial`]. A shared part can't be moved out into a `Box`, and
+    /// parts don't implement `Clone`, so they are given back as they are
+    /// kept.
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use message_format::{icu, Context, Message};
+    /// use message_format::icu::ast::PlainText;
+    ///
+    /// let ctx = Context::default();
+    /// let m = icu::parse("Hello, {name}!").unwrap();
+    /// let mut parts = m.into_parts();
+    /// parts.truncate(1);
+    /// parts.push(Arc::new(PlainText::new("World!")));
+    /// assert_eq!(ctx.format(&Message::from_parts(parts), None), "Hello, World!");
+    /// ```
+    ///
+    /// [`new`]: #method.new
+    /// [`from_parts`]: #method.from_parts
+    /// [`partial`]: #method.partial
+    pub fn into_parts(self) -> Vec<Arc<dyn MessagePart>> {
+        self.parts
+    }
+
+    /// Whether the message has no parts, like a message parsed from an
+    /// empty pattern or an empty branch, which formats as an empty
+    /// string.
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
     }
 
     /// Write a message to a stream.
@@ -33,12 +94,568 @@ impl Message {
     /// Use `Context::write` or `Context::format` instead.
     pub fn write_message<'f>(&self,
                              ctx: &Context,
-                             stream: &mut fmt::Write,
+                             stream: &mut dyn fmt::Write,
                              args: Option<&Args<'f>>)
-                             -> fmt::Result {
+                             -> Result<(), FormatError> {
+        self.write_nested(ctx, 0, stream, args)
+    }
+
+    /// Write a message like [`write_message`], as a branch within
+    /// `depth` formats. This fails with `FormatError::TooDeep` if
+    /// `depth` is more than the [`max_depth`] of the context.
+    ///
+    /// [`write_message`]: #method.write_message
+    /// [`max_depth`]: struct.Context.html#method.max_depth
+    pub fn write_nested<'f>(&self,
+                            ctx: &Context,
+                            depth: usize,
+                            stream: &mut dyn fmt::Write,
+                            args: Option<&Args<'f>>)
+                            -> Result<(), FormatError> {
+        if depth > ctx.max_depth {
+            return Err(FormatError::TooDeep);
+        }
         for part in &self.parts {
-            try!(part.apply_format(&ctx, stream, args));
+            part.apply_format_nested(ctx, depth, stream, args)?;
         }
         Ok(())
     }
+
+    /// Format a message one part at a time.
+    ///
+    /// Each part is only formatted when the iterator reaches it, so a
+    /// large message can be streamed without building the whole string.
+    /// Plain text is borrowed from the message, while other parts are
+    /// formatted into a new string. As with [`Context::write`], the
+    /// observer of `ctx` is notified of an error before it is returned.
+    ///
+    /// ```
+    /// use message_format::{arg, icu, Context};
+    ///
+    /// let ctx = Context::default();
+    /// let m = icu::parse("Hello, {name}!").unwrap();
+    /// let args = arg("name", "Jane");
+    /// let chunks = m.format_chunks(&ctx, Some(&args))
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(chunks, ["Hello, ", "Jane", "!"]);
+    /// ```
+    ///
+    /// [`Context::write`]: struct.Context.html#method.write
+    pub fn format_chunks<'a, 'f: 'a>
+        (&'a self,
+         ctx: &'a Context,
+         args: Option<&'a Args<'f>>)
+         -> impl Iterator<Item = Result<Cow<'a, str>, FormatError>> + 'a {
+        self.parts.iter().map(move |part| {
+            if let Some(text) = part.static_text() {
+                if ctx.pseudolocalization != Pseudolocalization::Full {
+                    return Ok(Cow::Borrowed(text));
+                }
+            }
+            let mut output = String::new();
+            if let Err(error) = part.apply_format(ctx, &mut output, args) {
+                ctx.format_observer().on_error(&error);
+                return Err(error);
+            }
+            Ok(Cow::Owned(output))
+        })
+    }
+
+    /// Report the structure of each part of the message to a `visitor`.
+    pub fn accept(&self, visitor: &mut dyn MessageVisitor) {
+        for part in &self.parts {
+            part.accept(visitor);
+        }
+    }
+
+    /// Compare the structure of this message with `other`, such as a
+    /// source message with its translation.
+    ///
+    /// ```
+    /// use message_format::icu;
+    ///
+    /// let source = icu::parse("{count, plural, one {# file} other {# files}} in {dir}").unwrap();
+    /// let translation = icu::parse("{count, plural, other {# fichiers}}").unwrap();
+    /// let diff = source.diff(&translation);
+    /// assert_eq!(diff.missing_variables, ["dir"]);
+    /// assert!(!diff.is_empty());
+    /// ```
+    pub fn diff(&self, other: &Message) -> MessageDiff {
+        MessageDiff::new(self, other)
+    }
+
+    /// Every run of plain text in the message, including within the
+    /// branches of `plural`, `select` and `choice` formats. This is
+    /// useful to review the prose of a message without its markup.
+    ///
+    /// The segments are in the order that parts are visited by
+    /// [`accept`], so the branches of a `select` are by key, followed
+    /// by `other`, and those of a `plural` are the `=` literals, then
+    /// the categories and then `other`.
+    ///
+    /// ```
+    /// use message_format::icu;
+    ///
+    /// let m = icu::parse("{count, plural, one {# file} other {# files}} in {dir}.").unwrap();
+    /// assert_eq!(m.static_segments(), [" file", " files", " in ", "."]);
+    /// ```
+    ///
+    /// [`accept`]: #method.accept
+    pub fn static_segments(&self) -> Vec<String> {
+        let mut segments = StaticSegments(vec![]);
+        self.accept(&mut segments);
+        segments.0
+    }
+
+    /// The text of each `{! comment }` in the message, including within
+    /// branches, in the same order as [`static_segments`]. Comments are
+    /// only kept by a [`Parser`] that is set to keep them.
+    ///
+    /// ```
+    /// use message_format::icu::Parser;
+    ///
+    /// let mut parser = Parser::new();
+    /// parser.keep_comments(true);
+    /// let m = parser.parse("{! The sender's name } {name} sent a photo.").unwrap();
+    /// assert_eq!(m.comments(), ["The sender's name"]);
+    /// ```
+    ///
+    /// [`static_segments`]: #method.static_segments
+    /// [`Parser`]: icu/struct.Parser.html
+    pub fn comments(&self) -> Vec<String> {
+        let mut comments = Comments(vec![]);
+        self.accept(&mut comments);
+        comments.0
+    }
+
+    /// The variables of the message, including those within branches, in
+    /// the order that they are first found. A variable that is used
+    /// several times is only included once, with each of the ways that
+    /// it is used.
+    ///
+    /// ```
+    /// use message_format::{icu, VariableKind};
+    ///
+    /// let m = icu::parse("{count, plural, one {# file} other {{count} files}} in {dir}")
+    ///     .unwrap();
+    /// let variables = m.variables();
+    /// assert_eq!(variables[0].name, "count");
+    /// assert_eq!(variables[0].kinds, [VariableKind::Plain, VariableKind::Plural]);
+    /// assert!(variables[0].nested);
+    /// assert_eq!(variables[1].name, "dir");
+    /// assert!(!variables[1].nested);
+    /// ```
+    pub fn variables(&self) -> Vec<VariableInfo> {
+        Variables::of(self)
+    }
+
+    /// Substitute the arguments that are known in `args`, returning a
+    /// message that only needs the rest of the arguments.
+    ///
+    /// This is useful when some arguments are known well before the
+    /// others. The `ctx` is used to format the known arguments, so it
+    /// should be the same as the context that the result is formatted
+    /// with. A `plural` or `select` can only be replaced by one of its
+    /// branches once the value of its variable is known, but the
+    /// arguments within its branches are still substituted.
+    ///
+    /// ```
+    /// use message_format::{arg, icu, Context};
+    ///
+    /// let ctx = Context::default();
+    /// let m = icu::parse("{name} has {count, plural, one {# file} other {# files}}.")
+    ///     .unwrap();
+    /// let partial = m.partial(&ctx, &arg("name", "Jane"));
+    /// assert_eq!(ctx.format(&partial, Some(&arg("count", 3))), "Jane has 3 files.");
+    /// ```
+    pub fn partial(&self, ctx: &Context, args: &Args) -> Message {
+        // Unknown arguments are expected, so they aren't reported, and
+        // a `#` at the top level is never a placeholder. Arguments are
+        // only pseudolocalized when the result is formatted.
+        let ctx = Context {
+            placeholder: None,
+            observer: None,
+            pseudolocalization: Pseudolocalization::Off,
+            ..ctx.clone()
+        };
+        self.partial_parts(&ctx, args)
+    }
+
+    /// Substitute the arguments that are known in `args` within each
+    /// part, for `MessagePart::partial`. Adjacent text is combined.
+    pub(crate) fn partial_parts(&self, ctx: &Context, args: &Args) -> Message {
+        let mut parts: Vec<Arc<dyn MessagePart>> = vec![];
+        let resolved = self.parts.iter().flat_map(|part| match part.partial(ctx, args) {
+            Some(message) => message.parts,
+            None => vec![part.clone()],
+        });
+        for part in resolved {
+            let last = parts.last().and_then(|last| last.static_text());
+            if let (Some(last), Some(text)) = (last, part.static_text()) {
+                let text = format!("{}{}", last, text);
+                *parts.last_mut().unwrap() = Arc::new(PlainText::new(&text));
+            } else {
+                parts.push(part);
+            }
+        }
+        parts.retain(|part| part.static_text() != Some(""));
+        Message { parts }
+    }
+
+    /// A pseudo-localized copy of the message, for testing that all of
+    /// the text of an application is localized and that the layout
+    /// leaves room for longer translations.
+    ///
+    /// Only the plain text of the message is transformed, including
+    /// within the branches of `plural`, `select` and `choice` formats, so
+    /// the result can be formatted with the same arguments. Unlike
+    /// [`Pseudolocalization`], the arguments are formatted as usual.
+    ///
+    /// ```
+    /// use message_format::{arg, icu, Context, PseudoLocalizeOptions};
+    ///
+    /// let ctx = Context::default();
+    /// let m = icu::parse("Hello, {name}!").unwrap();
+    /// let pseudo = m.pseudo_localize(&PseudoLocalizeOptions::default());
+    /// assert_eq!(ctx.format(&pseudo, Some(&arg("name", "Jo"))), "[Ĥéļļö,~~ Jo!]");
+    /// ```
+    ///
+    /// [`Pseudolocalization`]: enum.Pseudolocalization.html
+    pub fn pseudo_localize(&self, options: &PseudoLocalizeOptions) -> Message {
+        let mut parts = self.map_text(&|text| options.transform(text)).parts;
+        if let Some((ref start, ref end)) = options.markers {
+            parts.insert(0, Arc::new(PlainText::new(start)));
+            parts.push(Arc::new(PlainText::new(end)));
+        }
+        Message { parts }
+    }
+
+    /// Transform the plain text of each part, for
+    /// `MessagePart::map_text`.
+    pub(crate) fn map_text(&self, transform: &dyn Fn(&str) -> String) -> Message {
+        let parts = self.parts
+            .iter()
+            .flat_map(|part| match part.map_text(transform) {
+                Some(message) => message.parts,
+                None => vec![part.clone()],
+            })
+            .collect();
+        Message { parts }
+    }
+
+    /// Bind a message to a context and arguments so that it can be
+    /// used with `format!`, `write!`, `println!` and `to_string()`.
+    ///
+    /// ```
+    /// use message_format::{arg, icu, Context};
+    ///
+    /// let ctx = Context::default();
+    /// let m = icu::parse("Hello, {name}!").unwrap();
+    /// let args = arg("name", "Jane");
+    /// assert_eq!(format!("{}", m.display(&ctx, Some(&args))), "Hello, Jane!");
+    /// ```
+    pub fn display<'a>(&'a self,
+                       ctx: &'a Context,
+                       args: Option<&'a Args<'a>>)
+                       -> BoundMessage<'a> {
+        BoundMessage {
+            message: self,
+            ctx,
+            args,
+        }
+    }
+}
+
+/// Collects the text visited in a message.
+struct StaticSegments(Vec<String>);
+
+impl MessageVisitor for StaticSegments {
+    fn visit_text(&mut self, text: &str) {
+        if !text.is_empty() {
+            self.0.push(text.to_string());
+        }
+    }
+}
+
+/// Collects the comments visited in a message.
+struct Comments(Vec<String>);
+
+impl MessageVisitor for Comments {
+    fn visit_comment(&mut self, text: &str) {
+        self.0.push(text.to_string());
+    }
+}
+
+/// A [`Message`] bound to a [`Context`] and its arguments.
+///
+/// This implements `fmt::Display`. Since `fmt::Display` can only report
+/// a bare `fmt::Error`, use [`try_to_string`] when the underlying
+/// [`FormatError`] is needed.
+///
+/// This is created by [`Message::display`].
+///
+/// [`Message`]: struct.Message.html
+/// [`Context`]: struct.Context.html
+/// [`FormatError`]: enum.FormatError.html
+/// [`try_to_string`]: struct.BoundMessage.html#method.try_to_string
+/// [`Message::display`]: struct.Message.html#method.display
+#[derive(Clone,Copy)]
+pub struct BoundMessage<'a> {
+    message: &'a Message,
+    ctx: &'a Context,
+    args: Option<&'a Args<'a>>,
+}
+
+impl<'a> BoundMessage<'a> {
+    /// Format the message, returning a string or the error that
+    /// prevented formatting.
+    pub fn try_to_string(&self) -> Result<String, FormatError> {
+        let mut output = String::new();
+        self.ctx.write(self.message, &mut output, self.args)?;
+        Ok(output)
+    }
+}
+
+impl<'a> fmt::Display for BoundMessage<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.ctx.write(self.message, f, self.args).map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use icu::ast;
+    use icu::{parse, DEFAULT_MAX_DEPTH};
+    use std::borrow::Cow;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use {arg, Context, FormatError, FormatObserver, Message, PseudoLocalizeOptions,
+         Pseudolocalization};
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn types_are_send_and_sync() {
+        assert_send_sync::<Message>();
+        assert_send_sync::<Context>();
+        #[cfg(feature = "std")]
+        assert_send_sync::<::MessageBundle>();
+        assert_send_sync::<ast::NumberFormat>();
+        assert_send_sync::<ast::PlaceholderFormat>();
+        assert_send_sync::<ast::PlainText>();
+        assert_send_sync::<ast::PluralFormat>();
+        assert_send_sync::<ast::SelectFormat>();
+        assert_send_sync::<ast::SimpleFormat>();
+    }
+
+    #[test]
+    fn format_from_many_threads() {
+        let m = Arc::new(parse("{name} has {count, plural, one {# file} other {# files}}.")
+            .unwrap());
+        let ctx = Arc::new(Context::default());
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                let m = m.clone();
+                let ctx = ctx.clone();
+                thread::spawn(move || {
+                    for count in 0..100 {
+                        let output = ctx.format(&m, Some(&arg("name", "Jane").arg("count", count)));
+                        let expected = if count == 1 {
+                            "Jane has 1 file.".to_string()
+                        } else {
+                            format!("Jane has {} files.", count)
+                        };
+                        assert_eq!(output, expected, "thread {}", i);
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn format_chunks_matches_format() {
+        let ctx = Context::default();
+        let m = parse("{name} has {count, plural, one {# file} other {# files}}.").unwrap();
+        let args = arg("name", "Jane");
+        let args = args.arg("count", 3);
+
+        let chunks = m.format_chunks(&ctx, Some(&args)).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(chunks.concat(), ctx.format(&m, Some(&args)));
+        match chunks[1] {
+            Cow::Borrowed(text) => assert_eq!(text, " has "),
+            Cow::Owned(_) => panic!("Plain text should be borrowed."),
+        }
+    }
+
+    #[test]
+    fn format_chunks_is_lazy() {
+        let ctx = Context::default();
+        let m = parse("Hello, {name}!").unwrap();
+        let mut chunks = m.format_chunks(&ctx, None);
+        assert_eq!(chunks.next(), Some(Ok(Cow::Borrowed("Hello, "))));
+        assert_eq!(chunks.next(),
+                   Some(Err(FormatError::MissingArgument("name".to_string()))));
+    }
+
+    #[derive(Default)]
+    struct Errors(Mutex<Vec<String>>);
+
+    impl FormatObserver for Errors {
+        fn on_error(&self, error: &FormatError) {
+            self.0.lock().unwrap().push(error.to_string());
+        }
+    }
+
+    #[test]
+    fn format_chunks_reports_errors() {
+        let errors = Arc::new(Errors::default());
+        let ctx = Context::builder().observer(errors.clone()).build().unwrap();
+        let m = parse("Hello, {name}!").unwrap();
+        let chunks = m.format_chunks(&ctx, None).collect::<Vec<_>>();
+        assert_eq!(chunks[1], Err(FormatError::MissingArgument("name".to_string())));
+        assert_eq!(*errors.0.lock().unwrap(), ["Missing argument 'name'."]);
+    }
+
+    #[test]
+    fn partial_leaves_unknown_arguments() {
+        let ctx = Context::default();
+        let m = parse("{name} has {count, plural, =0 {no files} one {# file} other {# files}} \
+                       in {dir}.")
+            .unwrap();
+        let partial = m.partial(&ctx, &arg("name", "Jane").arg("dir", "/tmp"));
+        for &(count, expected) in &[(0, "Jane has no files in /tmp."),
+                                    (1, "Jane has 1 file in /tmp."),
+                                    (12, "Jane has 12 files in /tmp.")] {
+            assert_eq!(ctx.format(&partial, Some(&arg("count", count))), expected);
+        }
+        let partial = m.partial(&ctx, &arg("count", 1));
+        assert_eq!(ctx.format(&partial, Some(&arg("name", "Al").arg("dir", "/"))),
+                   "Al has 1 file in /.");
+    }
+
+    #[test]
+    fn partial_collapses_known_branches() {
+        let ctx = Context::default();
+        let m = parse("{count, plural, one {# file for {gender, select, female {her} other \
+                       {{name}}}} other {# files for {gender, select, female {her} other \
+                       {{name}}}}}.")
+            .unwrap();
+
+        // The plural is collapsed, so its `#` is known.
+        let partial = m.partial(&ctx, &arg("count", 3));
+        assert_eq!(ctx.format(&partial, Some(&arg("gender", "female"))), "3 files for her.");
+
+        // The select is kept, but the arguments within it are substituted.
+        let partial = partial.partial(&ctx, &arg("name", "Bob"));
+        assert_eq!(ctx.format(&partial, Some(&arg("gender", "male"))), "3 files for Bob.");
+
+        // The plural is kept, as is its `#`.
+        let partial = m.partial(&ctx, &arg("name", "Bob").arg("gender", "male"));
+        assert_eq!(ctx.format(&partial, Some(&arg("count", 1))), "1 file for Bob.");
+
+        // Everything is known, so no arguments are needed.
+        let partial = m.partial(&ctx, &arg("count", 1).arg("gender", "female"));
+        assert_eq!(partial.parts.len(), 2);
+        assert_eq!(ctx.format(&partial, None), "1 file for her.");
+    }
+
+    #[test]
+    fn partial_arguments_are_not_pseudolocalized() {
+        let m = parse("{name} has {count, plural, one {# file} other {# files}}.").unwrap();
+        let partial = m.partial(&Context::default(), &arg("name", "Jane"));
+        let pseudo = partial.pseudo_localize(&PseudoLocalizeOptions::default());
+        assert_eq!(Context::default().format(&pseudo, Some(&arg("count", 2))),
+                   "[Jane ĥåš~ 2 ƒîļéš~~.]");
+
+        // The arguments are written as they would be in the message.
+        let args = arg("name", "Jane");
+        let args = args.arg("count", 2);
+        let partial = m.partial(&Context::default(), &args);
+        let ctx = Context { pseudolocalization: Pseudolocalization::Full, ..Context::default() };
+        assert_eq!(ctx.format(&partial, None), "[name] ⓗⓐⓢ [#] ⓕⓘⓛⓔⓢ.");
+        assert_eq!(ctx.format(&partial, None), ctx.format(&m, Some(&args)));
+    }
+
+    #[test]
+    fn partial_keeps_invalid_arguments() {
+        let ctx = Context::default();
+        let m = parse("{count, plural, other {# files}}").unwrap();
+        let partial = m.partial(&ctx, &arg("count", "many"));
+        assert_eq!(ctx.format(&partial, Some(&arg("count", 5))), "5 files");
+    }
+
+    #[test]
+    fn static_segments() {
+        let m = parse("Hello {name}, {gender, select, \
+                           female {she has {count, plural, =0 {no files} one {a file} \
+                                                           other {# files}}} \
+                           other {they have {count, plural, other {# files}}}}!")
+            .unwrap();
+        assert_eq!(m.static_segments(),
+                   ["Hello ", ", ", "she has ", "no files", "a file", " files", "they have ",
+                    " files", "!"]);
+        assert!(parse("{name}").unwrap().static_segments().is_empty());
+    }
+
+    #[test]
+    fn display_works() {
+        let ctx = Context::default();
+        let m = parse("{name} is from {city}.").unwrap();
+        let args = arg("name", "Hendrik");
+        let args = args.arg("city", "Berlin");
+        assert_eq!(m.display(&ctx, Some(&args)).to_string(),
+                   "Hendrik is from Berlin.");
+    }
+
+    #[test]
+    fn try_to_string_reports_errors() {
+        let ctx = Context::default();
+        let m = parse("{name} is from {city}.").unwrap();
+        let args = arg("name", "Hendrik");
+        assert_eq!(m.display(&ctx, Some(&args)).try_to_string(),
+                   Err(FormatError::MissingArgument("city".to_string())));
+    }
+
+    #[test]
+    fn parts_can_be_taken_and_put_back() {
+        let ctx = Context::default();
+        let m = parse("{n, plural, one {# file} other {# files}} in {dir}, {n, number}").unwrap();
+        let parts = m.clone().into_parts();
+        assert_eq!(parts.len(), 5);
+        let rebuilt = Message::from_parts(parts);
+        for &n in &[1, 1234] {
+            let args = arg("n", n);
+            let args = args.arg("dir", "/tmp");
+            assert_eq!(ctx.format(&rebuilt, Some(&args)), ctx.format(&m, Some(&args)));
+        }
+        assert_eq!(rebuilt.variables(), m.variables());
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let nested = |depth| {
+            let mut message = Message::new(vec![Box::new(ast::PlainText::new("deep"))]);
+            for _ in 0..depth {
+                let select = ast::SelectFormat::new("a", message);
+                message = Message::new(vec![Box::new(select)]);
+            }
+            message
+        };
+        let ctx = Context::default();
+        let args = arg("a", "x");
+        let write = |m: &Message| m.write_message(&ctx, &mut String::new(), Some(&args));
+        assert_eq!(write(&nested(DEFAULT_MAX_DEPTH)), Ok(()));
+        assert_eq!(write(&nested(DEFAULT_MAX_DEPTH + 1)), Err(FormatError::TooDeep));
+        let chunks = nested(DEFAULT_MAX_DEPTH + 1);
+        let mut chunks = chunks.format_chunks(&ctx, Some(&args));
+        assert_eq!(chunks.next(), Some(Err(FormatError::TooDeep)));
+
+        let ctx = Context::builder().max_depth(200).build().unwrap();
+        let m = nested(200);
+        assert_eq!(ctx.format(&m, Some(&args)), "deep");
+    }
 }
@@ -0,0 +1,120 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::string::String;
+
+use {Args, AsValue, Value};
+
+/// A source of the arguments of a message, like a struct with a field
+/// for each argument.
+///
+/// As [`Args`] refer to each other, they are only available within
+/// [`with_args`]. With the `derive` feature, `#[derive(MessageArgs)]`
+/// implements this for a struct, with an argument named by each field,
+/// whose value is from [`IntoValue`]. A field can be given another
+/// name with `#[msg(rename = "n")]`, or left out with `#[msg(skip)]`,
+/// and an `Option` field that is `None` is a missing argument:
+///
+/// ```ignore
+/// #[derive(MessageArgs)]
+/// struct Greeting {
+///     name: String,
+///     #[msg(rename = "count")]
+///     unread: u32,
+/// }
+///
+/// let greeting = Greeting { name: "Ana".to_string(), unread: 3 };
+/// greeting.with_args(|args| ctx.format(&m, args))
+/// ```
+///
+/// It can also be implemented by hand:
+///
+/// ```
+/// use message_format::{icu, Args, Context, IntoValue, MessageArgs};
+///
+/// struct Greeting {
+///     name: String,
+/// }
+///
+/// impl MessageArgs for Greeting {
+///     fn with_args<R, F>(&self, f: F) -> R
+///         where F: FnOnce(Option<&Args>) -> R
+///     {
+///         let value = self.name.to_value();
+///         f(value.map(|value| Args { name: "name", value, prev: None }).as_ref())
+///     }
+/// }
+///
+/// let m = icu::parse("Hello, {name}!").unwrap();
+/// let greeting = Greeting { name: "Ana".to_string() };
+/// assert_eq!(greeting.with_args(|args| Context::default().format(&m, args)), "Hello, Ana!");
+/// ```
+///
+/// [`Args`]: struct.Args.html
+/// [`with_args`]: #tymethod.with_args
+/// [`IntoValue`]: trait.IntoValue.html
+pub trait MessageArgs {
+    /// Call `f` with the arguments, which are `None` if there are none.
+    fn with_args<R, F>(&self, f: F) -> R where F: FnOnce(Option<&Args>) -> R;
+}
+
+/// Convert a field of a [`MessageArgs`] into the value of an argument,
+/// borrowing from it.
+///
+/// This is implemented for the types that [`AsValue`] is, and for
+/// `String`, `&str` and `Value`. An `Option` is `None` when it is, so
+/// that the argument is missing.
+///
+/// [`MessageArgs`]: trait.MessageArgs.html
+/// [`AsValue`]: trait.AsValue.html
+pub trait IntoValue {
+    /// The value of the argument, or `None` if it is missing.
+    fn to_value(&self) -> Option<Value<'_>>;
+}
+
+macro_rules! into_value_as_value {
+    ($($t:ty),*) => {
+        $(
+            impl IntoValue for $t {
+                fn to_value(&self) -> Option<Value<'_>> {
+                    Some(self.as_formattable())
+                }
+            }
+        )*
+    }
+}
+
+into_value_as_value!(i32, u32, i64, u64, i128, usize, f64);
+
+impl IntoValue for String {
+    fn to_value(&self) -> Option<Value<'_>> {
+        Some(Value::Str(self))
+    }
+}
+
+impl IntoValue for str {
+    fn to_value(&self) -> Option<Value<'_>> {
+        Some(Value::Str(self))
+    }
+}
+
+impl<'a> IntoValue for Value<'a> {
+    fn to_value(&self) -> Option<Value<'_>> {
+        Some(*self)
+    }
+}
+
+impl<T: IntoValue> IntoValue for Option<T> {
+    fn to_value(&self) -> Option<Value<'_>> {
+        self.as_ref().and_then(IntoValue::to_value)
+    }
+}
+
+impl<T: IntoValue + ?Sized> IntoValue for &T {
+    fn to_value(&self) -> Option<Value<'_>> {
+        (**self).to_value()
+    }
+}
@@ -0,0 +1,1174 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt;
+use std::sync::OnceLock;
+
+use icu::{self, ParseError};
+use json::{self, JsonValue};
+use properties;
+use {Args, Context, FormatError, Locale, Message, PseudoLocalizeOptions};
+
+/// An error from a [`MessageBundle`].
+///
+/// [`MessageBundle`]: struct.MessageBundle.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum BundleError {
+    /// There is no message with the key.
+    UnknownKey(String),
+    /// The message for the key couldn't be parsed.
+    Parse(String, ParseError),
+    /// The message for the key couldn't be formatted.
+    Format(String, FormatError),
+    /// A resource that messages are loaded from is malformed. Has a
+    /// description of the problem.
+    InvalidResource(String),
+    /// An error in the resource entry that starts on the line, counting
+    /// from 1.
+    Line(usize, Box<BundleError>),
+    /// The key of a message is a namespace of the key of another
+    /// message, like `a` and `a.b`. Has the key of the message, then
+    /// the key of the message within its namespace.
+    KeyConflict(String, String),
+    /// Two messages have the key, as when `a.b` is both a key and the
+    /// key `b` of the object `a` of a JSON resource, or when a key is
+    /// repeated in a JSON or ARB object.
+    DuplicateKey(String),
+}
+
+impl Error for BundleError {}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            BundleError::UnknownKey(ref key) => write!(f, "Unknown message '{}'.", key),
+            BundleError::Parse(ref key, ref error) => {
+                write!(f, "Invalid message '{}': {}", key, error)
+            }
+            BundleError::Format(ref key, ref error) => {
+                write!(f, "Unable to format message '{}': {}", key, error)
+            }
+            BundleError::InvalidResource(ref message) => {
+                write!(f, "Invalid resource: {}", message)
+            }
+            BundleError::Line(line, ref error) => write!(f, "Line {}: {}", line, error),
+            BundleError::KeyConflict(ref key, ref nested) => {
+                write!(f, "Message '{}' conflicts with message '{}'.", key, nested)
+            }
+            BundleError::DuplicateKey(ref key) => {
+                write!(f, "Message '{}' is defined more than once.", key)
+            }
+        }
+    }
+}
+
+/// The character encoding of a `.properties` file.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub enum PropertiesEncoding {
+    /// ISO-8859-1, which Java uses by default, with other characters
+    /// written as `\u` escapes.
+    #[default]
+    Latin1,
+    /// UTF-8.
+    Utf8,
+}
+
+/// A message in a [`MessageBundle`], with its source if it is known.
+/// The source is parsed when the message is first used if it was
+/// inserted with `insert_raw`.
+///
+/// [`MessageBundle`]: struct.MessageBundle.html
+#[derive(Clone,Debug)]
+enum Entry {
+    Parsed(Message),
+    Source(String, OnceLock<Result<Message, ParseError>>),
+}
+
+impl Entry {
+    /// The message, parsing it if it hasn't been yet.
+    fn message(&self, key: &str) -> Result<&Message, BundleError> {
+        match *self {
+            Entry::Parsed(ref message) => Ok(message),
+            Entry::Source(ref source, ref parsed) => {
+                parsed.get_or_init(|| icu::parse(source))
+                    .as_ref()
+                    .map_err(|e| BundleError::Parse(key.to_string(), e.clone()))
+            }
+        }
+    }
+
+    fn source(&self) -> Option<&str> {
+        match *self {
+            Entry::Parsed(_) => None,
+            Entry::Source(ref source, _) => Some(source),
+        }
+    }
+}
+
+/// The changes made by [`MessageBundle::reload_from`]. Each list of keys
+/// is in sorted order.
+///
+/// [`MessageBundle::reload_from`]: struct.MessageBundle.html#method.reload_from
+#[cfg(feature = "serde_json")]
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct ReloadReport {
+    /// The keys of the messages that are new.
+    pub added: Vec<String>,
+    /// The keys of the messages that have a different source.
+    pub changed: Vec<String>,
+    /// The keys of the messages that are no longer in the resource.
+    pub removed: Vec<String>,
+    /// The keys of the messages that are invalid. Any previous version
+    /// of each of these messages is kept.
+    pub broken: Vec<String>,
+    /// The errors for the `broken` messages.
+    pub errors: Vec<BundleError>,
+}
+
+/// Notes on a message for translators, from its `@key` entry in an ARB
+/// file.
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct MessageMetadata {
+    /// What the message is for.
+    pub description: Option<String>,
+    /// The arguments of the message, in the order they are listed.
+    pub placeholders: Vec<Placeholder>,
+}
+
+/// The description of an argument of a message, from the
+/// `placeholders` of its [`MessageMetadata`].
+///
+/// [`MessageMetadata`]: struct.MessageMetadata.html
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct Placeholder {
+    /// The name of the argument.
+    pub name: String,
+    /// The type of the argument, like `int` or `String`.
+    pub type_name: Option<String>,
+    /// What the argument is.
+    pub description: Option<String>,
+    /// An example value of the argument.
+    pub example: Option<String>,
+}
+
+/// The keys of the messages in a [`MessageBundle`], as a tree of the
+/// namespaces that are separated by `.` in the keys.
+///
+/// [`MessageBundle`]: struct.MessageBundle.html
+#[derive(Clone,Debug,PartialEq)]
+pub enum KeyTree {
+    /// A message, with its full key.
+    Message(String),
+    /// A namespace, with the tree for each name within it.
+    Namespace(BTreeMap<String, KeyTree>),
+}
+
+/// The messages for a locale, by key, with the [`Context`] that they
+/// are formatted with.
+///
+/// Keys are usually hierarchical, with namespaces separated by `.`,
+/// like `settings.profile.title`. The messages in a namespace can be
+/// taken as a bundle of their own with [`subtree`], and the keys can
+/// be listed as a [`KeyTree`]. A key can't be both a message and a
+/// namespace.
+///
+/// ```
+/// use message_format::{arg, Context, MessageBundle};
+///
+/// let mut bundle = MessageBundle::new(Context::default());
+/// bundle.insert("greeting", "Hello, {name}!").unwrap();
+/// bundle.insert("files", "{count, plural, one {# file} other {# files}}").unwrap();
+/// assert_eq!(bundle.format("greeting", Some(&arg("name", "Jo"))).unwrap(), "Hello, Jo!");
+/// assert_eq!(bundle.format("files", Some(&arg("count", 2))).unwrap(), "2 files");
+/// ```
+///
+/// [`Context`]: struct.Context.html
+/// [`subtree`]: #method.subtree
+/// [`KeyTree`]: enum.KeyTree.html
+#[derive(Clone)]
+pub struct MessageBundle {
+    context: Context,
+    messages: BTreeMap<String, Entry>,
+    metadata: BTreeMap<String, MessageMetadata>,
+    /// Attributes of the resource, like the `@@` keys of an ARB file.
+    attributes: BTreeMap<String, String>,
+}
+
+impl MessageBundle {
+    /// Construct an empty `MessageBundle` that formats its messages
+    /// with `context`.
+    pub fn new(context: Context) -> Self {
+        MessageBundle {
+            context,
+            messages: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            attributes: BTreeMap::new(),
+        }
+    }
+
+    /// Load messages from JSON, which is an object with an ICU message
+    /// for each key. The keys of nested objects are joined with `.`,
+    /// and it is an error for two messages to have the same key this
+    /// way. This requires the `serde_json` feature.
+    ///
+    /// ```
+    /// use message_format::{arg, Context, MessageBundle};
+    ///
+    /// let bundle = MessageBundle::from_json_str(Context::default(), r#"{
+    ///     "title": "Settings",
+    ///     "profile": {"greeting": "Hello, {name}!"}
+    /// }"#).unwrap();
+    /// assert_eq!(bundle.format("profile.greeting", Some(&arg("name", "Jo"))).unwrap(),
+    ///            "Hello, Jo!");
+    /// ```
+    ///
+    /// Every message is parsed, so that all of the errors are
+    /// reported together. To have them reported when a program is
+    /// compiled instead, embed the resource with the `include_messages!`
+    /// macro of the `message-format-macros` crate.
+    #[cfg(feature = "serde_json")]
+    pub fn from_json_str(context: Context, json: &str) -> Result<Self, Vec<BundleError>> {
+        let members = json_object(json).map_err(|error| vec![error])?;
+        let mut bundle = MessageBundle::new(context);
+        let mut errors = vec![];
+        for (key, source) in json_sources(&members) {
+            if let Err(error) = source.and_then(|source| bundle.insert(&key, source)) {
+                errors.push(error);
+            }
+        }
+        if errors.is_empty() { Ok(bundle) } else { Err(errors) }
+    }
+
+    /// Load messages from a Java `.properties` file, in which each
+    /// value is an ICU message.
+    ///
+    /// ```
+    /// use message_format::{arg, Context, MessageBundle, PropertiesEncoding};
+    ///
+    /// let source = b"# Messages\n\
+    ///                title = R\\u00e9glages\n\
+    ///                files = {count, plural, one {# file} \\\n\
+    ///                        other {# files}}\n";
+    /// let bundle = MessageBundle::from_properties(Context::default(),
+    ///                                             source,
+    ///                                             PropertiesEncoding::Latin1).unwrap();
+    /// assert_eq!(bundle.format("title", None).unwrap(), "Réglages");
+    /// assert_eq!(bundle.format("files", Some(&arg("count", 2))).unwrap(), "2 files");
+    /// ```
+    ///
+    /// Every message is parsed, so that all of the errors are reported
+    /// together. An error in a message is a `BundleError::Line` with
+    /// the line that the message starts on. If a key is repeated, the
+    /// last message is kept.
+    pub fn from_properties(context: Context,
+                           source: &[u8],
+                           encoding: PropertiesEncoding)
+                           -> Result<Self, Vec<BundleError>> {
+        let source = match encoding {
+            PropertiesEncoding::Latin1 => properties::decode_latin1(source),
+            PropertiesEncoding::Utf8 => {
+                String::from_utf8(source.to_vec())
+                    .map_err(|e| vec![BundleError::InvalidResource(e.to_string())])?
+            }
+        };
+        let mut bundle = MessageBundle::new(context);
+        let mut errors = vec![];
+        for property in properties::parse(&source) {
+            let (line, error) = match property {
+                Ok(property) => {
+                    match bundle.insert(&property.key, &property.value) {
+                        Ok(()) => continue,
+                        Err(error) => (property.line, error),
+                    }
+                }
+                Err(e) => (e.line, BundleError::InvalidResource(e.message.to_string())),
+            };
+            errors.push(BundleError::Line(line, Box::new(error)));
+        }
+        if errors.is_empty() { Ok(bundle) } else { Err(errors) }
+    }
+
+    /// Load messages from a Flutter ARB file. This is JSON with an ICU
+    /// message for each key, [`MessageMetadata`] for the message with
+    /// key `name` in an `@name` object, and attributes of the file in
+    /// keys starting with `@@`.
+    ///
+    /// ```
+    /// use message_format::{arg, Context, MessageBundle};
+    ///
+    /// let bundle = MessageBundle::from_arb_str(Context::default(), r#"{
+    ///     "@@locale": "de",
+    ///     "files": "{count, plural, one {# Datei} other {# Dateien}}",
+    ///     "@files": {"description": "The number of files in a folder."}
+    /// }"#).unwrap();
+    /// assert_eq!(bundle.context().locale().language(), "de");
+    /// assert_eq!(bundle.format("files", Some(&arg("count", 2))).unwrap(), "2 Dateien");
+    /// assert_eq!(bundle.metadata("files").unwrap().description.as_ref().unwrap(),
+    ///            "The number of files in a folder.");
+    /// ```
+    ///
+    /// The `@@locale` attribute, if there is one, replaces the locale of
+    /// `context`. All of the attributes are kept, and are available from
+    /// [`attribute`]. Every message is parsed, so that all of the errors
+    /// are reported together.
+    ///
+    /// [`MessageMetadata`]: struct.MessageMetadata.html
+    /// [`attribute`]: #method.attribute
+    pub fn from_arb_str(context: Context, arb: &str) -> Result<Self, Vec<BundleError>> {
+        let value = json::parse(arb)
+            .map_err(|e| vec![BundleError::InvalidResource(e.to_string())])?;
+        let members = value.as_object()
+            .ok_or_else(|| vec![BundleError::InvalidResource("Expected an object.".to_string())])?;
+        let mut bundle = MessageBundle::new(context);
+        let mut errors = vec![];
+        let mut keys = BTreeSet::new();
+        for (key, value) in members {
+            if !keys.insert(key) {
+                errors.push(BundleError::DuplicateKey(key.clone()));
+                continue;
+            }
+            if let Some(name) = key.strip_prefix("@@") {
+                let attribute = match value.as_str() {
+                    Some(attribute) => attribute,
+                    None => {
+                        let message = format!("Expected a string for '{}'.", key);
+                        errors.push(BundleError::InvalidResource(message));
+                        continue;
+                    }
+                };
+                if name == "locale" {
+                    match Locale::new(attribute) {
+                        Ok(locale) => bundle.context.locale = locale,
+                        Err(e) => errors.push(BundleError::InvalidResource(e.to_string())),
+                    }
+                }
+                bundle.attributes.insert(name.to_string(), attribute.to_string());
+            } else if let Some(name) = key.strip_prefix('@') {
+                match arb_metadata(value) {
+                    Some(metadata) => {
+                        bundle.metadata.insert(name.to_string(), metadata);
+                    }
+                    None => {
+                        let message = format!("Invalid metadata for '{}'.", name);
+                        errors.push(BundleError::InvalidResource(message));
+                    }
+                }
+            } else {
+                match *value {
+                    JsonValue::String(ref source) => {
+                        if let Err(error) = bundle.insert(key, source) {
+                            errors.push(error);
+                        }
+                    }
+                    _ => {
+                        let message = format!("Expected a message for '{}'.", key);
+                        errors.push(BundleError::InvalidResource(message));
+                    }
+                }
+            }
+        }
+        if errors.is_empty() { Ok(bundle) } else { Err(errors) }
+    }
+
+    /// Reload the messages from JSON in the same form as
+    /// [`from_json_str`], replacing all of the messages, and report what
+    /// changed. If a message is now invalid, its previous version is
+    /// kept, so that an edit that breaks a message doesn't stop it from
+    /// being shown.
+    ///
+    /// ```
+    /// use message_format::{Context, MessageBundle};
+    ///
+    /// let mut bundle = MessageBundle::from_json_str(Context::default(), r#"{
+    ///     "title": "Settings",
+    ///     "greeting": "Hello, {name}!",
+    ///     "sign_out": "Sign out"
+    /// }"#).unwrap();
+    /// let report = bundle.reload_from(r#"{
+    ///     "title": "Preferences",
+    ///     "greeting": "Hello, {name",
+    ///     "help": "Help"
+    /// }"#).unwrap();
+    /// assert_eq!(report.added, ["help"]);
+    /// assert_eq!(report.changed, ["title"]);
+    /// assert_eq!(report.removed, ["sign_out"]);
+    /// assert_eq!(report.broken, ["greeting"]);
+    /// assert_eq!(bundle.keys().collect::<Vec<_>>(), ["greeting", "help", "title"]);
+    /// assert_eq!(bundle.format("title", None).unwrap(), "Preferences");
+    /// ```
+    ///
+    /// If `json` isn't an object, it is an error and the bundle is
+    /// unchanged. The bundle is changed in one step, so to reload it
+    /// while it is being used from other threads, share it in a
+    /// [`SharedBundle`].
+    ///
+    /// [`from_json_str`]: #method.from_json_str
+    /// [`SharedBundle`]: struct.SharedBundle.html
+    #[cfg(feature = "serde_json")]
+    pub fn reload_from(&mut self, json: &str) -> Result<ReloadReport, BundleError> {
+        let members = json_object(json)?;
+        let mut messages = BTreeMap::new();
+        let mut report = ReloadReport::default();
+        for (key, source) in json_sources(&members) {
+            let previous = match self.messages.remove(&key) {
+                Some(entry) if source.as_ref().is_ok_and(|&s| entry.source() == Some(s)) => {
+                    messages.insert(key, entry);
+                    continue;
+                }
+                previous => previous,
+            };
+            let parsed = source.and_then(|source| {
+                let message = icu::parse(source)
+                    .map_err(|e| BundleError::Parse(key.clone(), e))?;
+                match key_conflict(&messages, &key) {
+                    Some(error) => Err(error),
+                    None => Ok(Entry::Source(source.to_string(), OnceLock::from(Ok(message)))),
+                }
+            });
+            match parsed {
+                Ok(entry) => {
+                    if previous.is_some() {
+                        report.changed.push(key.clone());
+                    } else {
+                        report.added.push(key.clone());
+                    }
+                    messages.insert(key, entry);
+                }
+                Err(error) => {
+                    report.broken.push(key.clone());
+                    report.errors.push(error);
+                    if let Some(previous) = previous {
+                        messages.insert(key, previous);
+                    }
+                }
+            }
+        }
+        report.removed = self.messages.keys().cloned().collect();
+        self.messages = messages;
+        report.added.sort();
+        report.changed.sort();
+        report.broken.sort();
+        Ok(report)
+    }
+
+    /// The context that the messages are formatted with.
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Parse the ICU message `source` and add it with `key`, replacing
+    /// any message with the same key. If `source` is invalid, or `key`
+    /// conflicts with the namespace of another message, the bundle is
+    /// unchanged.
+    ///
+    /// ```
+    /// use message_format::{BundleError, Context, MessageBundle};
+    ///
+    /// let mut bundle = MessageBundle::new(Context::default());
+    /// bundle.insert("errors.network", "Unable to connect.").unwrap();
+    /// assert_eq!(bundle.insert("errors", "Something went wrong."),
+    ///            Err(BundleError::KeyConflict("errors".to_string(),
+    ///                                         "errors.network".to_string())));
+    /// ```
+    pub fn insert(&mut self, key: &str, source: &str) -> Result<(), BundleError> {
+        let message = icu::parse(source).map_err(|e| BundleError::Parse(key.to_string(), e))?;
+        if let Some(error) = key_conflict(&self.messages, key) {
+            return Err(error);
+        }
+        let entry = Entry::Source(source.to_string(), OnceLock::from(Ok(message)));
+        self.messages.insert(key.to_string(), entry);
+        Ok(())
+    }
+
+    /// Add a `message` with `key`, replacing any message with the same
+    /// key.
+    pub fn insert_message(&mut self, key: &str, message: Message) {
+        self.messages.insert(key.to_string(), Entry::Parsed(message));
+    }
+
+    /// Add the ICU message `source` with `key`, replacing any message
+    /// with the same key, without parsing it. It is parsed when it is
+    /// first used, and is then kept, so that a large bundle can be
+    /// loaded without parsing messages that are never used.
+    ///
+    /// ```
+    /// use message_format::{arg, BundleError, Context, MessageBundle};
+    ///
+    /// let mut bundle = MessageBundle::new(Context::default());
+    /// bundle.insert_raw("greeting", "Hello, {name}!");
+    /// bundle.insert_raw("broken", "Hello, {name");
+    /// assert_eq!(bundle.format("greeting", Some(&arg("name", "Jo"))).unwrap(), "Hello, Jo!");
+    /// assert!(matches!(bundle.format("broken", None), Err(BundleError::Parse(..))));
+    /// ```
+    ///
+    /// An invalid message is an error whenever it is used. Use
+    /// [`parse_all`] to find them up front.
+    ///
+    /// [`parse_all`]: #method.parse_all
+    pub fn insert_raw(&mut self, key: &str, source: &str) {
+        self.messages.insert(key.to_string(), Entry::Source(source.to_string(), OnceLock::new()));
+    }
+
+    /// Parse every message that was inserted with [`insert_raw`] and
+    /// hasn't been used yet, returning the errors for all of the
+    /// invalid messages, in the order of their keys.
+    ///
+    /// [`insert_raw`]: #method.insert_raw
+    pub fn parse_all(&self) -> Result<(), Vec<BundleError>> {
+        let errors = self.messages
+            .iter()
+            .filter_map(|(key, entry)| entry.message(key).err())
+            .collect::<Vec<_>>();
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    /// The metadata for the message with `key`, if there is any.
+    pub fn metadata(&self, key: &str) -> Option<&MessageMetadata> {
+        self.metadata.get(key)
+    }
+
+    /// Set the metadata for the message with `key`.
+    pub fn insert_metadata(&mut self, key: &str, metadata: MessageMetadata) {
+        self.metadata.insert(key.to_string(), metadata);
+    }
+
+    /// The attribute of the resource that the messages were loaded
+    /// from with `name`, if there is one. For an ARB file, this is the
+    /// value of the `@@name` key.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(String::as_str)
+    }
+
+    /// The names and values of the attributes of the resource that the
+    /// messages were loaded from, in sorted order.
+    pub fn attributes(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.attributes.iter().map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// The message with `key`, if there is one and it is valid.
+    pub fn get(&self, key: &str) -> Option<&Message> {
+        self.try_get(key).ok()
+    }
+
+    /// The message with `key`, parsing it if it was inserted with
+    /// [`insert_raw`] and hasn't been used yet.
+    ///
+    /// [`insert_raw`]: #method.insert_raw
+    pub fn try_get(&self, key: &str) -> Result<&Message, BundleError> {
+        self.messages
+            .get(key)
+            .ok_or_else(|| BundleError::UnknownKey(key.to_string()))?
+            .message(key)
+    }
+
+    /// The ICU source of the message with `key`, or `None` if there is
+    /// no message or it was added as a [`Message`] with
+    /// [`insert_message`].
+    ///
+    /// [`Message`]: struct.Message.html
+    /// [`insert_message`]: #method.insert_message
+    pub fn source(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).and_then(Entry::source)
+    }
+
+    /// Whether there is a message with `key`.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.messages.contains_key(key)
+    }
+
+    /// The keys of the messages, in sorted order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.messages.keys().map(String::as_str)
+    }
+
+    /// The keys of the messages as a tree of their namespaces. This is
+    /// a `BundleError::KeyConflict` if a key is both a message and a
+    /// namespace, which can only happen with [`insert_message`] and
+    /// [`insert_raw`].
+    ///
+    /// ```
+    /// use message_format::{Context, KeyTree, MessageBundle};
+    ///
+    /// let mut bundle = MessageBundle::new(Context::default());
+    /// bundle.insert("title", "Settings").unwrap();
+    /// bundle.insert("profile.name", "Name").unwrap();
+    /// let tree = match bundle.tree().unwrap() {
+    ///     KeyTree::Namespace(tree) => tree,
+    ///     KeyTree::Message(_) => unreachable!(),
+    /// };
+    /// assert_eq!(tree["title"], KeyTree::Message("title".to_string()));
+    /// match tree["profile"] {
+    ///     KeyTree::Namespace(ref profile) => {
+    ///         assert_eq!(profile["name"], KeyTree::Message("profile.name".to_string()));
+    ///     }
+    ///     KeyTree::Message(_) => unreachable!(),
+    /// }
+    /// ```
+    ///
+    /// [`insert_message`]: #method.insert_message
+    /// [`insert_raw`]: #method.insert_raw
+    pub fn tree(&self) -> Result<KeyTree, BundleError> {
+        let mut root = BTreeMap::new();
+        for key in self.messages.keys() {
+            let mut names = key.split('.');
+            let last = names.next_back().unwrap();
+            let mut namespace = &mut root;
+            let mut end = 0;
+            for name in names {
+                end += name.len();
+                let entry = namespace.entry(name.to_string())
+                    .or_insert_with(|| KeyTree::Namespace(BTreeMap::new()));
+                namespace = match *entry {
+                    KeyTree::Namespace(ref mut namespace) => namespace,
+                    KeyTree::Message(_) => {
+                        return Err(BundleError::KeyConflict(key[..end].to_string(), key.clone()));
+                    }
+                };
+                end += 1;
+            }
+            // The keys are sorted, so a message is always found before a
+            // namespace with the same key.
+            namespace.insert(last.to_string(), KeyTree::Message(key.clone()));
+        }
+        Ok(KeyTree::Namespace(root))
+    }
+
+    /// The messages in the namespace `prefix`, like `"checkout.errors"`,
+    /// as a bundle with the same context, in which their keys don't
+    /// have the prefix. Their metadata is kept, as are the attributes
+    /// of the bundle.
+    ///
+    /// ```
+    /// use message_format::{Context, MessageBundle};
+    ///
+    /// let mut bundle = MessageBundle::new(Context::default());
+    /// bundle.insert("checkout.errors.card_declined", "Your card was declined.").unwrap();
+    /// bundle.insert("checkout.title", "Checkout").unwrap();
+    /// let errors = bundle.subtree("checkout.errors");
+    /// assert_eq!(errors.keys().collect::<Vec<_>>(), ["card_declined"]);
+    /// assert_eq!(errors.format("card_declined", None).unwrap(), "Your card was declined.");
+    /// ```
+    ///
+    /// Messages are shared with this bundle, including the result of
+    /// parsing those inserted with [`insert_raw`] if they have been used.
+    ///
+    /// [`insert_raw`]: #method.insert_raw
+    pub fn subtree(&self, prefix: &str) -> MessageBundle {
+        let prefix = format!("{}.", prefix);
+        let within = |key: &String| key.strip_prefix(&prefix).map(str::to_string);
+        MessageBundle {
+            context: self.context.clone(),
+            messages: self.messages
+                .iter()
+                .filter_map(|(key, entry)| within(key).map(|key| (key, entry.clone())))
+                .collect(),
+            metadata: self.metadata
+                .iter()
+                .filter_map(|(key, metadata)| within(key).map(|key| (key, metadata.clone())))
+                .collect(),
+            attributes: self.attributes.clone(),
+        }
+    }
+
+    /// A copy of the bundle with each message pseudo-localized, as by
+    /// [`Message::pseudo_localize`].
+    ///
+    /// ```
+    /// use message_format::{Context, MessageBundle, PseudoLocalizeOptions};
+    ///
+    /// let mut bundle = MessageBundle::new(Context::default());
+    /// bundle.insert("title", "Settings").unwrap();
+    /// let pseudo = bundle.pseudo_localize(&PseudoLocalizeOptions::default());
+    /// assert_eq!(pseudo.format("title", None).unwrap(), "[Šéţţîñĝš~~]");
+    /// ```
+    ///
+    /// Messages that can't be parsed are kept as they are, so they are
+    /// still reported when they are used.
+    ///
+    /// [`Message::pseudo_localize`]: struct.Message.html#method.pseudo_localize
+    pub fn pseudo_localize(&self, options: &PseudoLocalizeOptions) -> MessageBundle {
+        let pseudo = |key: &str, entry: &Entry| match entry.message(key) {
+            Ok(message) => Entry::Parsed(message.pseudo_localize(options)),
+            Err(_) => entry.clone(),
+        };
+        MessageBundle {
+            messages: self.messages
+                .iter()
+                .map(|(key, entry)| (key.clone(), pseudo(key, entry)))
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// The number of messages.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Whether there are no messages.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Format the message with `key`, returning a string.
+    pub fn format<'f>(&self, key: &str, args: Option<&Args<'f>>) -> Result<String, BundleError> {
+        let message = self.try_get(key)?;
+        let mut output = String::new();
+        self.context
+            .write(message, &mut output, args)
+            .map_err(|e| BundleError::Format(key.to_string(), e))?;
+        Ok(output)
+    }
+}
+
+/// Parse a JSON resource, which must be an object.
+#[cfg(feature = "serde_json")]
+fn json_object(json: &str) -> Result<Vec<(String, JsonValue)>, BundleError> {
+    match json::parse(json) {
+        Ok(JsonValue::Object(members)) => Ok(members),
+        Ok(_) => Err(BundleError::InvalidResource("Expected an object.".to_string())),
+        Err(e) => Err(BundleError::InvalidResource(e.to_string())),
+    }
+}
+
+/// The source of the message for each key of a JSON resource, with
+/// the keys of nested objects joined with `.`. Each repeat of a key
+/// after the first is an error, whether it is repeated within an
+/// object or by joining the keys.
+#[cfg(feature = "serde_json")]
+fn json_sources(members: &[(String, JsonValue)]) -> Vec<(String, Result<&str, BundleError>)> {
+    let mut sources = vec![];
+    flatten_json(members, "", &mut sources);
+    let mut keys = BTreeSet::new();
+    for &mut (ref key, ref mut source) in &mut sources {
+        if !keys.insert(key.clone()) {
+            *source = Err(BundleError::DuplicateKey(key.clone()));
+        }
+    }
+    sources
+}
+
+/// The sources of the messages in the members of a JSON object, with
+/// their keys after `prefix`, or an error for a member that isn't a
+/// message or an object.
+#[cfg(feature = "serde_json")]
+fn flatten_json<'a>(members: &'a [(String, JsonValue)],
+                    prefix: &str,
+                    sources: &mut Vec<(String, Result<&'a str, BundleError>)>) {
+    for (name, value) in members {
+        let key = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+        match *value {
+            JsonValue::String(ref source) => sources.push((key, Ok(source))),
+            JsonValue::Object(ref members) => flatten_json(members, &key, sources),
+            _ => {
+                let message = format!("Expected a message for '{}'.", key);
+                sources.push((key, Err(BundleError::InvalidResource(message))));
+            }
+        }
+    }
+}
+
+/// The conflict between `key` and the key of a message in `messages`,
+/// if one is a namespace of the other.
+fn key_conflict(messages: &BTreeMap<String, Entry>, key: &str) -> Option<BundleError> {
+    let namespace = key.match_indices('.')
+        .map(|(i, _)| &key[..i])
+        .find(|namespace| messages.contains_key(*namespace));
+    if let Some(namespace) = namespace {
+        return Some(BundleError::KeyConflict(namespace.to_string(), key.to_string()));
+    }
+    let prefix = format!("{}.", key);
+    messages.range(prefix.clone()..)
+        .next()
+        .filter(|&(nested, _)| nested.starts_with(&prefix))
+        .map(|(nested, _)| BundleError::KeyConflict(key.to_string(), nested.clone()))
+}
+
+/// Read the `@key` metadata object for a message in an ARB file, with
+/// any fields other than the description and placeholders ignored.
+fn arb_metadata(value: &JsonValue) -> Option<MessageMetadata> {
+    let text = |value: &JsonValue, field| match value.get(field) {
+        Some(JsonValue::String(text)) => Some(Some(text.clone())),
+        Some(_) => None,
+        None => Some(None),
+    };
+    value.as_object()?;
+    let mut metadata = MessageMetadata {
+        description: text(value, "description")?,
+        placeholders: vec![],
+    };
+    if let Some(placeholders) = value.get("placeholders") {
+        for (name, placeholder) in placeholders.as_object()? {
+            placeholder.as_object()?;
+            metadata.placeholders.push(Placeholder {
+                name: name.clone(),
+                type_name: text(placeholder, "type")?,
+                description: text(placeholder, "description")?,
+                // An example may be written as a number.
+                example: match placeholder.get("example") {
+                    Some(&JsonValue::Number(number)) => Some(number.to_string()),
+                    _ => text(placeholder, "example")?,
+                },
+            });
+        }
+    }
+    Some(metadata)
+}
+
+impl fmt::Debug for MessageBundle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MessageBundle")
+            .field("locale", &self.context.locale)
+            .field("messages", &self.messages)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{BundleError, KeyTree, MessageBundle, MessageMetadata, PropertiesEncoding};
+    use icu::ParseErrorKind;
+    use {arg, Context, FormatError, Locale, Message, PseudoLocalizeOptions};
+
+    fn bundle() -> MessageBundle {
+        let mut bundle = MessageBundle::new(Context::default());
+        bundle.insert("greeting", "Hello, {name}!").unwrap();
+        bundle.insert("files", "{count, plural, one {# file} other {# files}}").unwrap();
+        bundle
+    }
+
+    #[test]
+    fn it_works() {
+        let bundle = bundle();
+        assert_eq!(bundle.len(), 2);
+        assert!(bundle.contains_key("files") && !bundle.contains_key("missing"));
+        assert_eq!(bundle.keys().collect::<Vec<_>>(), ["files", "greeting"]);
+        assert_eq!(bundle.format("files", Some(&arg("count", 1))), Ok("1 file".to_string()));
+        let greeting = bundle.get("greeting").unwrap();
+        assert_eq!(bundle.context().format(greeting, Some(&arg("name", "Jo"))), "Hello, Jo!");
+    }
+
+    #[test]
+    fn uses_context() {
+        let ctx = Context::new(Locale::new("ru").unwrap(), None);
+        let mut bundle = MessageBundle::new(ctx);
+        bundle.insert("files", "{n, plural, one {# файл} few {# файла} other {# файлов}}")
+            .unwrap();
+        assert_eq!(bundle.format("files", Some(&arg("n", 22))), Ok("22 файла".to_string()));
+    }
+
+    #[test]
+    fn errors() {
+        let mut bundle = bundle();
+        assert_eq!(bundle.format("missing", None),
+                   Err(BundleError::UnknownKey("missing".to_string())));
+        assert_eq!(bundle.format("greeting", None),
+                   Err(BundleError::Format("greeting".to_string(),
+                                           FormatError::MissingArgument("name".to_string()))));
+
+        // An invalid message doesn't replace the existing one.
+        match bundle.insert("greeting", "{name") {
+            Err(BundleError::Parse(ref key, ref error)) if key == "greeting" => {
+                assert_eq!(error.kind, ParseErrorKind::UnexpectedEnd);
+            }
+            result => panic!("unexpected {:?}", result),
+        }
+        assert_eq!(bundle.format("greeting", Some(&arg("name", "Jo"))),
+                   Ok("Hello, Jo!".to_string()));
+        assert_eq!(BundleError::UnknownKey("x".to_string()).to_string(), "Unknown message 'x'.");
+    }
+
+    #[test]
+    fn raw_messages() {
+        let mut bundle = bundle();
+        bundle.insert_raw("files", "{count, plural, one {# fichier} other {# fichiers}}");
+        bundle.insert_raw("a", "{n");
+        bundle.insert_raw("b", "{x, select, y {z}}");
+        assert_eq!(bundle.len(), 4);
+        assert_eq!(bundle.format("files", Some(&arg("count", 2))), Ok("2 fichiers".to_string()));
+        assert!(bundle.get("files").is_some());
+        assert!(bundle.get("a").is_none());
+
+        match bundle.format("a", None) {
+            Err(BundleError::Parse(ref key, ref error)) if key == "a" => {
+                assert_eq!(error.kind, ParseErrorKind::UnexpectedEnd);
+            }
+            result => panic!("unexpected {:?}", result),
+        }
+        let keys = bundle.parse_all()
+            .unwrap_err()
+            .into_iter()
+            .map(|error| match error {
+                BundleError::Parse(key, _) => key,
+                error => panic!("unexpected {:?}", error),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(keys, ["a", "b"]);
+
+        bundle.insert_message("a", Message::new(vec![]));
+        bundle.insert_raw("b", "ok");
+        assert_eq!(bundle.parse_all(), Ok(()));
+        assert_eq!(bundle.try_get("missing").unwrap_err(),
+                   BundleError::UnknownKey("missing".to_string()));
+    }
+
+    #[test]
+    fn raw_messages_are_parsed_once() {
+        let mut bundle = MessageBundle::new(Context::default());
+        bundle.insert_raw("k", "{n, number}");
+        let first = bundle.get("k").unwrap() as *const Message;
+        assert_eq!(bundle.get("k").unwrap() as *const Message, first);
+    }
+
+    #[test]
+    #[cfg(feature = "serde_json")]
+    fn json_errors_are_all_reported() {
+        let json = r#"{"a": "{n", "b": {"c": "ok", "d": "{x, bogus}"}, "e": 5}"#;
+        let errors = MessageBundle::from_json_str(Context::default(), json).unwrap_err();
+        let keys = errors.iter()
+            .map(|error| match *error {
+                BundleError::Parse(ref key, _) => key.as_str(),
+                BundleError::InvalidResource(ref message) => message.as_str(),
+                _ => panic!("unexpected {:?}", error),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(keys, ["a", "b.d", "Expected a message for 'e'."]);
+    }
+
+    #[test]
+    fn properties_errors() {
+        let source = "a={n\nb=ok\nc={x, plural, \\\n  one {#}\\\n  bogus}\nd=\\u00zz\n";
+        let errors = MessageBundle::from_properties(Context::default(),
+                                                    source.as_bytes(),
+                                                    PropertiesEncoding::Utf8)
+            .unwrap_err();
+        let lines = errors.iter()
+            .map(|error| match *error {
+                BundleError::Line(line, ref error) => (line, error.to_string()),
+                _ => panic!("unexpected {:?}", error),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!((lines[0].0, lines[1].0), (1, 3));
+        assert!(lines[0].1.starts_with("Invalid message 'a': "));
+        assert!(lines[1].1.starts_with("Invalid message 'c': "));
+        assert_eq!(lines[2], (6, "Invalid resource: Invalid \\u escape".to_string()));
+        assert_eq!(errors[2].to_string(), "Line 6: Invalid resource: Invalid \\u escape");
+    }
+
+    #[test]
+    fn properties_encoding() {
+        let source = "k=caf\u{e9}".as_bytes();
+        let utf8 = MessageBundle::from_properties(Context::default(),
+                                                  source,
+                                                  PropertiesEncoding::Utf8).unwrap();
+        assert_eq!(utf8.format("k", None), Ok("café".to_string()));
+        // The same bytes as ISO-8859-1 are two characters.
+        let latin1 = MessageBundle::from_properties(Context::default(),
+                                                    source,
+                                                    PropertiesEncoding::default()).unwrap();
+        assert_eq!(latin1.format("k", None), Ok("cafÃ©".to_string()));
+        assert!(MessageBundle::from_properties(Context::default(),
+                                               b"k=caf\xe9",
+                                               PropertiesEncoding::Utf8).is_err());
+    }
+
+    #[test]
+    fn arb_errors() {
+        let arb = r#"{"@@locale": "!", "@@x": 1, "a": "{n", "b": 2, "@b": [],
+                      "@c": {"placeholders": {"n": {"type": 3}}}, "@d": {"description": 4}}"#;
+        let errors = MessageBundle::from_arb_str(Context::default(), arb).unwrap_err();
+        let errors = errors.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        assert_eq!(errors.len(), 7);
+        assert_eq!(errors[0], "Invalid resource: Malformed locale '!'.");
+        assert_eq!(errors[1], "Invalid resource: Expected a string for '@@x'.");
+        assert!(errors[2].starts_with("Invalid message 'a': "));
+        assert_eq!(errors[3..],
+                   ["Invalid resource: Expected a message for 'b'.",
+                    "Invalid resource: Invalid metadata for 'b'.",
+                    "Invalid resource: Invalid metadata for 'c'.",
+                    "Invalid resource: Invalid metadata for 'd'."]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde_json")]
+    fn invalid_json() {
+        for json in &["{", "[]", r#"{"a": [1]}"#] {
+            match MessageBundle::from_json_str(Context::default(), json) {
+                Err(ref errors) if errors.len() == 1 => {
+                    assert!(matches!(errors[0], BundleError::InvalidResource(_)));
+                }
+                result => panic!("unexpected {:?}", result),
+            }
+        }
+    }
+
+    #[test]
+    fn key_conflicts() {
+        let conflict = |key: &str, nested: &str| {
+            BundleError::KeyConflict(key.to_string(), nested.to_string())
+        };
+        let mut bundle = MessageBundle::new(Context::default());
+        bundle.insert("a.b", "x").unwrap();
+        bundle.insert("a.bc", "x").unwrap();
+        bundle.insert("a-b", "x").unwrap();
+        assert_eq!(bundle.insert("a", "x"), Err(conflict("a", "a.b")));
+        assert_eq!(bundle.insert("a.b.c", "x"), Err(conflict("a.b", "a.b.c")));
+        assert_eq!(bundle.len(), 3);
+        assert_eq!(bundle.insert("a.b", "y"), Ok(()));
+        assert_eq!(conflict("a", "a.b").to_string(), "Message 'a' conflicts with message 'a.b'.");
+
+        // Messages inserted without checking are reported by `tree`.
+        bundle.insert_raw("a.b.c.d", "x");
+        assert_eq!(bundle.tree(), Err(conflict("a.b", "a.b.c.d")));
+    }
+
+    #[test]
+    #[cfg(feature = "serde_json")]
+    fn json_key_conflicts() {
+        let json = r#"{"a": {"b": "x"}, "a.b": {"c": "y"}}"#;
+        assert_eq!(MessageBundle::from_json_str(Context::default(), json).err(),
+                   Some(vec![BundleError::KeyConflict("a.b".to_string(), "a.b.c".to_string())]));
+
+        let json = r#"{"a.b": "x", "a": {"b": "y", "c": "z"}}"#;
+        let duplicate = BundleError::DuplicateKey("a.b".to_string());
+        assert_eq!(MessageBundle::from_json_str(Context::default(), json).err(),
+                   Some(vec![duplicate.clone()]));
+        assert_eq!(duplicate.to_string(), "Message 'a.b' is defined more than once.");
+
+        let mut bundle = MessageBundle::new(Context::default());
+        let report = bundle.reload_from(json).unwrap();
+        assert_eq!(report.broken, ["a.b"]);
+        assert_eq!(report.errors, [duplicate]);
+
+        // A key that is repeated within an object is an error too,
+        // rather than one of the messages being kept.
+        let json = r#"{"a": "x", "a": "y"}"#;
+        assert_eq!(MessageBundle::from_json_str(Context::default(), json).err(),
+                   Some(vec![BundleError::DuplicateKey("a".to_string())]));
+    }
+
+    #[test]
+    fn arb_duplicate_keys() {
+        let arb = r#"{"a": "x", "@a": {}, "a": "y", "@a": {}}"#;
+        assert_eq!(MessageBundle::from_arb_str(Context::default(), arb).err(),
+                   Some(vec![BundleError::DuplicateKey("a".to_string()),
+                             BundleError::DuplicateKey("@a".to_string())]));
+    }
+
+    #[test]
+    fn tree() {
+        let mut bundle = MessageBundle::new(Context::default());
+        for key in &["title", "settings.title", "settings.profile.name", "settings.profile.email"] {
+            bundle.insert(key, "x").unwrap();
+        }
+        let message = |key: &str| KeyTree::Message(key.to_string());
+        let namespace = |names: Vec<(&str, KeyTree)>| {
+            KeyTree::Namespace(names.into_iter()
+                .map(|(name, tree)| (name.to_string(), tree))
+                .collect::<BTreeMap<_, _>>())
+        };
+        let profile = namespace(vec![("email", message("settings.profile.email")),
+                                     ("name", message("settings.profile.name"))]);
+        let settings = namespace(vec![("profile", profile), ("title", message("settings.title"))]);
+        assert_eq!(bundle.tree(),
+                   Ok(namespace(vec![("settings", settings), ("title", message("title"))])));
+        assert_eq!(MessageBundle::new(Context::default()).tree(), Ok(namespace(vec![])));
+    }
+
+    #[test]
+    fn subtree() {
+        let mut bundle = MessageBundle::new(Context::new(Locale::new("fr").unwrap(), None));
+        bundle.insert("checkout.errors.card_declined", "Carte refusée.").unwrap();
+        bundle.insert_raw("checkout.errors.expired", "{n, plural, one {# jour} other {# jours}}");
+        bundle.insert("checkout.errorsx", "x").unwrap();
+        bundle.insert("checkout.title", "Paiement").unwrap();
+        let metadata = MessageMetadata {
+            description: Some("Shown when a payment fails.".to_string()),
+            ..MessageMetadata::default()
+        };
+        bundle.insert_metadata("checkout.errors.card_declined", metadata.clone());
+
+        let errors = bundle.subtree("checkout.errors");
+        assert_eq!(errors.keys().collect::<Vec<_>>(), ["card_declined", "expired"]);
+        assert_eq!(errors.context().locale().language(), "fr");
+        assert_eq!(errors.metadata("card_declined"), Some(&metadata));
+        assert_eq!(errors.format("expired", Some(&arg("n", 1))), Ok("1 jour".to_string()));
+
+        let checkout = bundle.subtree("checkout");
+        assert_eq!(checkout.subtree("errors").len(), 2);
+        assert_eq!(checkout.format("title", None), Ok("Paiement".to_string()));
+        assert!(bundle.subtree("checkout.title").is_empty());
+        assert!(bundle.subtree("missing").is_empty());
+    }
+
+    #[test]
+    fn pseudo_localize() {
+        let mut bundle = MessageBundle::new(Context::default());
+        bundle.insert("files", "{n, plural, one {# file} other {# files}} in {dir}").unwrap();
+        bundle.insert_raw("broken", "{n");
+        let options = PseudoLocalizeOptions { expansion: 1.0, ..PseudoLocalizeOptions::default() };
+        let pseudo = bundle.pseudo_localize(&options);
+        let args = arg("n", 2);
+        let args = args.arg("dir", "/tmp");
+        assert_eq!(pseudo.format("files", Some(&args)), Ok("[2 ƒîļéš îñ /tmp]".to_string()));
+        assert!(matches!(pseudo.format("broken", None), Err(BundleError::Parse(..))));
+        assert_eq!(bundle.format("files", Some(&args)), Ok("2 files in /tmp".to_string()));
+    }
+
+    #[test]
+    #[cfg(feature = "serde_json")]
+    fn reload() {
+        let mut bundle = MessageBundle::new(Context::default());
+        bundle.insert("same", "Same").unwrap();
+        bundle.insert("edited", "Before").unwrap();
+        bundle.insert_raw("fixed", "{n");
+        bundle.insert("removed", "Gone").unwrap();
+        bundle.insert("broken", "Hello, {name}!").unwrap();
+        // A message without a source is always changed.
+        bundle.insert_message("nested.a", Message::new(vec![]));
+        let report = bundle.reload_from(r#"{
+            "same": "Same",
+            "edited": "After",
+            "fixed": "{n}",
+            "broken": "Hello, {name",
+            "new": {"broken": 1, "ok": "New"},
+            "nested": {"a": "A"}
+        }"#)
+            .unwrap();
+        assert_eq!(report.added, ["new.ok"]);
+        assert_eq!(report.changed, ["edited", "fixed", "nested.a"]);
+        assert_eq!(report.removed, ["removed"]);
+        assert_eq!(report.broken, ["broken", "new.broken"]);
+        assert!(matches!(report.errors[0], BundleError::Parse(ref key, _) if key == "broken"));
+        let invalid = "Expected a message for 'new.broken'.".to_string();
+        assert_eq!(report.errors[1], BundleError::InvalidResource(invalid));
+
+        assert_eq!(bundle.keys().collect::<Vec<_>>(),
+                   ["broken", "edited", "fixed", "nested.a", "new.ok", "same"]);
+        assert_eq!(bundle.format("broken", Some(&arg("name", "Jo"))),
+                   Ok("Hello, Jo!".to_string()));
+        assert_eq!(bundle.format("edited", None), Ok("After".to_string()));
+        assert_eq!(bundle.format("fixed", Some(&arg("n", 1))), Ok("1".to_string()));
+
+        // Reloading the same messages changes nothing.
+        let report = bundle.reload_from(r#"{"same": "Same", "edited": "After"}"#).unwrap();
+        assert_eq!(report.changed, Vec::<String>::new());
+        assert_eq!(report.removed.len(), 4);
+
+        let conflicting = bundle.reload_from(r#"{"a": "x", "a.b": "y"}"#).unwrap();
+        assert_eq!(conflicting.added, ["a"]);
+        assert_eq!(conflicting.errors,
+                   [BundleError::KeyConflict("a".to_string(), "a.b".to_string())]);
+
+        assert!(bundle.reload_from("[]").is_err());
+        assert_eq!(bundle.len(), 1);
+    }
+}
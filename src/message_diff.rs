@@ -0,0 +1,150 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use {Message, MessageVisitor, PluralCategory};
+
+/// The structural differences between two messages, as found by
+/// [`Message::diff`].
+///
+/// Everything that is "missing" is in the first message but not the
+/// second, and everything that is "extra" is in the second message but
+/// not the first.
+///
+/// Plural categories are compared as written. A translation for a
+/// language with different plural rules will often legitimately have
+/// different categories.
+///
+/// [`Message::diff`]: struct.Message.html#method.diff
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct MessageDiff {
+    /// Variables that are missing.
+    pub missing_variables: Vec<String>,
+    /// Variables that are extra.
+    pub extra_variables: Vec<String>,
+    /// Select keys that are missing, with the variable of the select.
+    pub missing_select_keys: Vec<(String, String)>,
+    /// Select keys that are extra, with the variable of the select.
+    pub extra_select_keys: Vec<(String, String)>,
+    /// Plural categories that are missing, with the variable of the plural.
+    pub missing_plural_categories: Vec<(String, PluralCategory)>,
+    /// Plural categories that are extra, with the variable of the plural.
+    pub extra_plural_categories: Vec<(String, PluralCategory)>,
+}
+
+impl MessageDiff {
+    pub(crate) fn new(message: &Message, other: &Message) -> Self {
+        let message = Structure::of(message);
+        let other = Structure::of(other);
+        MessageDiff {
+            missing_variables: difference(&message.variables, &other.variables),
+            extra_variables: difference(&other.variables, &message.variables),
+            missing_select_keys: difference(&message.select_keys, &other.select_keys),
+            extra_select_keys: difference(&other.select_keys, &message.select_keys),
+            missing_plural_categories: difference(&message.plural_categories,
+                                                  &other.plural_categories),
+            extra_plural_categories: difference(&other.plural_categories,
+                                                &message.plural_categories),
+        }
+    }
+
+    /// Whether the messages have the same structure.
+    pub fn is_empty(&self) -> bool {
+        *self == MessageDiff::default()
+    }
+}
+
+/// The items of `a` that aren't in `b`.
+fn difference<T: Clone + PartialEq>(a: &[T], b: &[T]) -> Vec<T> {
+    a.iter().filter(|item| !b.contains(item)).cloned().collect()
+}
+
+/// The variables, select keys and plural categories of a message, in
+/// the order that they are first found.
+#[derive(Default)]
+struct Structure {
+    variables: Vec<String>,
+    select_keys: Vec<(String, String)>,
+    plural_categories: Vec<(String, PluralCategory)>,
+}
+
+impl Structure {
+    fn of(message: &Message) -> Self {
+        let mut structure = Structure::default();
+        message.accept(&mut structure);
+        structure
+    }
+}
+
+fn push_unique<T: PartialEq>(items: &mut Vec<T>, item: T) {
+    if !items.contains(&item) {
+        items.push(item);
+    }
+}
+
+impl MessageVisitor for Structure {
+    fn visit_variable(&mut self, name: &str) {
+        push_unique(&mut self.variables, name.to_string());
+    }
+
+    fn visit_plural(&mut self, variable: &str, categories: &[PluralCategory], _literals: &[i64]) {
+        self.visit_variable(variable);
+        for &category in categories {
+            push_unique(&mut self.plural_categories, (variable.to_string(), category));
+        }
+    }
+
+    fn visit_select(&mut self, variable: &str, keys: &[&str]) {
+        self.visit_variable(variable);
+        for key in keys {
+            push_unique(&mut self.select_keys, (variable.to_string(), key.to_string()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use icu::parse;
+    use {MessageDiff, PluralCategory};
+
+    #[test]
+    fn matching_messages() {
+        let source = parse("{gender, select, male {He} female {She} other {They}} sent \
+                            {count, plural, one {# file} other {# files}} to {name}.")
+            .unwrap();
+        let translation = parse("{name} a reçu {count, plural, one {# fichier} other {# \
+                                 fichiers}} {gender, select, female {d'elle} male {de lui} \
+                                 other {d'eux}}.")
+            .unwrap();
+        let diff = source.diff(&translation);
+        assert!(diff.is_empty(), "{:?}", diff);
+    }
+
+    #[test]
+    fn mismatched_messages() {
+        let source = parse("{gender, select, male {He} female {She} other {They}} sent \
+                            {count, plural, one {# file} other {# files}} to {name}.")
+            .unwrap();
+        let translation = parse("{gender, select, male {Il} other {{sender}}} a envoyé \
+                                 {count, plural, one {# fichier} many {# fichiers} other {# \
+                                 fichiers}}.")
+            .unwrap();
+        let diff = source.diff(&translation);
+        assert_eq!(diff,
+                   MessageDiff {
+                       missing_variables: vec!["name".to_string()],
+                       extra_variables: vec!["sender".to_string()],
+                       missing_select_keys: vec![("gender".to_string(), "female".to_string())],
+                       extra_select_keys: vec![],
+                       missing_plural_categories: vec![],
+                       extra_plural_categories: vec![("count".to_string(),
+                                                      PluralCategory::Many)],
+                   });
+        assert!(!diff.is_empty());
+    }
+}
@@ -4,17 +4,123 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::boxed::Box;
 use std::fmt;
+use std::string::String;
 
-use {Args, Context};
+use {Args, Context, FormatError, Message, MessageVisitor, Pseudolocalization};
 
 /// Part of a message. May be something that requires formatting a
 /// value or just plain text.
-pub trait MessagePart: fmt::Debug {
+///
+/// Parts must be `Send` and `Sync` so that a parsed [`Message`] can be
+/// shared between threads. Formatting takes `&self`, so a part must not
+/// rely on interior mutability that isn't thread-safe.
+///
+/// [`Message`]: struct.Message.html
+pub trait MessagePart: fmt::Debug + Send + Sync {
     /// Format this message part.
     fn apply_format<'f>(&self,
                         ctx: &Context,
-                        stream: &mut fmt::Write,
+                        stream: &mut dyn fmt::Write,
                         args: Option<&Args<'f>>)
-                        -> fmt::Result;
+                        -> Result<(), FormatError>;
+
+    /// Format this part like [`apply_format`], within `depth` other
+    /// formats. A part with messages of its own, like a `select`,
+    /// should write them with [`Message::write_nested`] at `depth + 1`,
+    /// so that a deeply nested message fails with
+    /// `FormatError::TooDeep` rather than overflowing the stack.
+    ///
+    /// By default, the depth is ignored and `apply_format` is used.
+    ///
+    /// [`apply_format`]: #tymethod.apply_format
+    /// [`Message::write_nested`]: struct.Message.html#method.write_nested
+    fn apply_format_nested<'f>(&self,
+                               ctx: &Context,
+                               _depth: usize,
+                               stream: &mut dyn fmt::Write,
+                               args: Option<&Args<'f>>)
+                               -> Result<(), FormatError> {
+        self.apply_format(ctx, stream, args)
+    }
+
+    /// If this part always formats to the same text, return it.
+    ///
+    /// This allows the text to be used without copying it.
+    fn static_text(&self) -> Option<&str> {
+        None
+    }
+
+    /// Report the structure of this part to a `visitor`.
+    ///
+    /// By default, nothing is reported.
+    fn accept(&self, _visitor: &mut dyn MessageVisitor) {}
+
+    /// Substitute the arguments that are known in `args`, for
+    /// [`Message::partial`]. This returns the message that replaces
+    /// this part, or `None` if the part should be kept as it is.
+    ///
+    /// By default, a part is replaced by its text if it can be formatted
+    /// with `args` and is kept otherwise. The text is still an argument,
+    /// so it isn't pseudolocalized like the text of the message. Parts
+    /// with messages of their own should substitute the arguments within
+    /// them as well.
+    ///
+    /// [`Message::partial`]: struct.Message.html#method.partial
+    fn partial(&self, ctx: &Context, args: &Args) -> Option<Message> {
+        let mut text = String::new();
+        self.apply_format(ctx, &mut text, Some(args)).ok()?;
+        let mut placeholder = String::new();
+        let pseudo = Context { pseudolocalization: Pseudolocalization::Arguments, ..ctx.clone() };
+        self.apply_format(&pseudo, &mut placeholder, Some(args)).ok()?;
+        Some(Message::new(vec![Box::new(Substituted { text, placeholder })]))
+    }
+
+    /// Transform the plain text of this part with `transform`, for
+    /// [`Message::pseudo_localize`]. This returns the message that
+    /// replaces this part, or `None` if the part has no text.
+    ///
+    /// By default, a part is kept as it is. Parts with messages of their
+    /// own should transform the text within them.
+    ///
+    /// [`Message::pseudo_localize`]: struct.Message.html#method.pseudo_localize
+    fn map_text(&self, _transform: &dyn Fn(&str) -> String) -> Option<Message> {
+        None
+    }
+}
+
+/// An argument that was substituted by [`Message::partial`].
+///
+/// [`Message::partial`]: struct.Message.html#method.partial
+#[derive(Debug)]
+struct Substituted {
+    /// The formatted argument.
+    text: String,
+    /// What is written instead when arguments are pseudolocalized,
+    /// which is usually the name of the argument, like `[name]`.
+    placeholder: String,
+}
+
+impl MessagePart for Substituted {
+    fn apply_format(&self,
+                    ctx: &Context,
+                    stream: &mut dyn fmt::Write,
+                    _args: Option<&Args>)
+                    -> Result<(), FormatError> {
+        if ctx.pseudolocalization.is_on() {
+            stream.write_str(&self.placeholder)?;
+        } else {
+            stream.write_str(&self.text)?;
+        }
+        Ok(())
+    }
+
+    fn accept(&self, visitor: &mut dyn MessageVisitor) {
+        visitor.visit_text(&self.text);
+    }
+
+    fn partial(&self, _ctx: &Context, _args: &Args) -> Option<Message> {
+        None
+    }
 }
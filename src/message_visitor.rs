@@ -0,0 +1,97 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use PluralCategory;
+
+/// Visit the structure of a [`Message`].
+///
+/// Each [`MessagePart`] reports itself to the visitor from
+/// [`MessagePart::accept`]. Parts with branches, such as a plural or a
+/// select, report themselves and then have each of their branch
+/// messages accept the visitor in turn, between calls to
+/// [`enter_branch`] and [`leave_branch`].
+///
+/// All of the methods do nothing by default, so a visitor only needs
+/// to implement the ones that it is interested in.
+///
+/// [`Message`]: struct.Message.html
+/// [`MessagePart`]: trait.MessagePart.html
+/// [`MessagePart::accept`]: trait.MessagePart.html#method.accept
+/// [`enter_branch`]: #method.enter_branch
+/// [`leave_branch`]: #method.leave_branch
+pub trait MessageVisitor {
+    /// Visit plain text.
+    fn visit_text(&mut self, _text: &str) {}
+
+    /// Visit a `{! comment }`.
+    fn visit_comment(&mut self, _text: &str) {}
+
+    /// Visit a `#` placeholder.
+    fn visit_placeholder(&mut self) {}
+
+    /// Visit a part that formats the variable `name`, like `{name}`.
+    fn visit_variable(&mut self, _name: &str) {}
+
+    /// Visit a `number` format of the variable `name`. By default, this
+    /// is visited as a variable.
+    fn visit_number(&mut self, name: &str) {
+        self.visit_variable(name);
+    }
+
+    /// Visit a `duration` format of the variable `name`. By default,
+    /// this is visited as a variable.
+    fn visit_duration(&mut self, name: &str) {
+        self.visit_variable(name);
+    }
+
+    /// Visit a `relativetime` format of the variable `name`. By
+    /// default, this is visited as a variable.
+    fn visit_relative_time(&mut self, name: &str) {
+        self.visit_variable(name);
+    }
+
+    /// Visit a `spellout` format of the variable `name`. By default,
+    /// this is visited as a variable.
+    fn visit_spellout(&mut self, name: &str) {
+        self.visit_variable(name);
+    }
+
+    /// Visit a `choice` on the variable `name`. By default, this is
+    /// visited as a variable.
+    fn visit_choice(&mut self, name: &str) {
+        self.visit_variable(name);
+    }
+
+    /// Visit a plural on `variable`, with messages for the given
+    /// `categories` and `literals`.
+    fn visit_plural(&mut self,
+                    _variable: &str,
+                    _categories: &[PluralCategory],
+                    _literals: &[i64]) {
+    }
+
+    /// Visit a `selectordinal` on `variable`, which is a plural that uses
+    /// the ordinal plural rules. By default, this is visited as a plural.
+    fn visit_selectordinal(&mut self,
+                           variable: &str,
+                           categories: &[PluralCategory],
+                           literals: &[i64]) {
+        self.visit_plural(variable, categories, literals);
+    }
+
+    /// Visit a select on `variable`, with messages for the given `keys`.
+    /// The default `other` message is not one of the `keys`.
+    fn visit_select(&mut self, _variable: &str, _keys: &[&str]) {}
+
+    /// Start visiting a branch of a `plural`, `select` or `choice`. The
+    /// parts of the branch are visited before [`leave_branch`].
+    ///
+    /// [`leave_branch`]: #method.leave_branch
+    fn enter_branch(&mut self) {}
+
+    /// Finish visiting a branch.
+    fn leave_branch(&mut self) {}
+}
@@ -0,0 +1,42 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A limit on how deeply the readers of resources nest.
+//!
+//! The readers of resources, like JSON documents, are recursive descent
+//! parsers, which call themselves for each level of nesting. Without a
+//! limit, a malicious resource could overflow the stack, so each one
+//! fails once it is nested more deeply than its `MAX_DEPTH`.
+
+/// How deeply a reader can nest, unless it has a limit of its own.
+const MAX_DEPTH: usize = 256;
+
+/// A recursive reader whose nesting is limited.
+pub(crate) trait Nesting: Sized {
+    type Error;
+
+    /// How deeply the reader can nest.
+    const MAX_DEPTH: usize = MAX_DEPTH;
+
+    /// The number of levels that the reader is within.
+    fn depth(&mut self) -> &mut usize;
+
+    /// The error for going more deeply than `MAX_DEPTH`.
+    fn too_deep(&self) -> Self::Error;
+
+    /// Read with `f`, one level more deeply.
+    fn nested<T, F>(&mut self, f: F) -> Result<T, Self::Error>
+        where F: FnOnce(&mut Self) -> Result<T, Self::Error>
+    {
+        if *self.depth() == Self::MAX_DEPTH {
+            return Err(self.too_deep());
+        }
+        *self.depth() += 1;
+        let result = f(self);
+        *self.depth() -= 1;
+        result
+    }
+}
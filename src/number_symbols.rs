@@ -0,0 +1,133 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::string::ToString;
+
+/// The symbols used when writing numbers.
+///
+/// By default, numbers are written with ASCII digits, `.` as the
+/// decimal separator, `,` as the grouping separator and `-` as the
+/// minus sign. Some locales use U+2212 MINUS SIGN, `−`, instead.
+///
+/// Locales that use another digit system, like the Arabic-Indic
+/// digits, supply the zero digit of that system. The digits one
+/// through nine follow it consecutively, as they do for every
+/// decimal digit block in Unicode.
+///
+/// ```
+/// use message_format::NumberSymbols;
+///
+/// let symbols = NumberSymbols { zero_digit: '\u{660}', ..NumberSymbols::default() };
+/// let mut output = String::new();
+/// symbols.write_integer(&mut output, 2016).unwrap();
+/// assert_eq!(output, "٢٠١٦");
+/// ```
+#[derive(Clone,Debug,PartialEq)]
+pub struct NumberSymbols {
+    /// The digit used for zero.
+    pub zero_digit: char,
+    /// The separator between the integer and fraction digits.
+    pub decimal_separator: char,
+    /// The separator between groups of integer digits.
+    pub grouping_separator: char,
+    /// The sign of negative numbers.
+    pub minus_sign: char,
+}
+
+impl NumberSymbols {
+    /// Write a number that has been formatted with ASCII digits, `.`,
+    /// `,` and `-`, replacing each of them with the corresponding symbol.
+    pub fn write_ascii(&self, stream: &mut dyn fmt::Write, number: &str) -> fmt::Result {
+        for ch in number.chars() {
+            let ch = match ch {
+                '0'..='9' => {
+                    let digit = ch as u32 - '0' as u32;
+                    ::std::char::from_u32(self.zero_digit as u32 + digit).unwrap_or(ch)
+                }
+                '.' => self.decimal_separator,
+                ',' => self.grouping_separator,
+                '-' => self.minus_sign,
+                _ => ch,
+            };
+            stream.write_char(ch)?;
+        }
+        Ok(())
+    }
+
+    /// Write an integer using these symbols.
+    pub fn write_integer<T: Into<i128>>(&self,
+                                        stream: &mut dyn fmt::Write,
+                                        value: T)
+                                        -> fmt::Result {
+        let value = value.into();
+        self.write_ascii(stream, &value.to_string())
+    }
+
+    /// Write a decimal with exactly `fraction_digits` digits after the
+    /// decimal point using these symbols.
+    pub fn write_decimal(&self,
+                         stream: &mut dyn fmt::Write,
+                         value: f64,
+                         fraction_digits: u8)
+                         -> fmt::Result {
+        self.write_ascii(stream, &format!("{:.*}", fraction_digits as usize, value))
+    }
+}
+
+impl Default for NumberSymbols {
+    fn default() -> Self {
+        NumberSymbols {
+            zero_digit: '0',
+            decimal_separator: '.',
+            grouping_separator: ',',
+            minus_sign: '-',
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NumberSymbols;
+
+    #[test]
+    fn ascii_is_unchanged() {
+        let symbols = NumberSymbols::default();
+        let mut output = String::new();
+        symbols.write_ascii(&mut output, "-1,234.5").unwrap();
+        assert_eq!(output, "-1,234.5");
+    }
+
+    #[test]
+    fn separators_interleave_with_digits() {
+        let symbols = NumberSymbols {
+            zero_digit: '\u{660}',
+            decimal_separator: '\u{66b}',
+            grouping_separator: '\u{66c}',
+            minus_sign: '-',
+        };
+        let mut output = String::new();
+        symbols.write_ascii(&mut output, "1,234.5").unwrap();
+        assert_eq!(output, "١٬٢٣٤٫٥");
+    }
+
+    #[test]
+    fn decimals_keep_fraction_digits() {
+        let symbols = NumberSymbols { decimal_separator: ',', ..NumberSymbols::default() };
+        let mut output = String::new();
+        symbols.write_decimal(&mut output, 1.0, 2).unwrap();
+        assert_eq!(output, "1,00");
+    }
+
+    #[test]
+    fn minus_sign() {
+        let symbols = NumberSymbols { minus_sign: '\u{2212}', ..NumberSymbols::default() };
+        let mut output = String::new();
+        symbols.write_integer(&mut output, -12).unwrap();
+        symbols.write_decimal(&mut output, -0.5, 1).unwrap();
+        assert_eq!(output, "\u{2212}12\u{2212}0.5");
+    }
+}
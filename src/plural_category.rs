@@ -4,14 +4,30 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::string::{String, ToString};
+
 /// The set of [grammatical numbers] that we support.
 ///
 /// These are used by the [ICU `PluralFormat`]. See also
-/// [`english_cardinal_classifier`].
+/// [`english_cardinal_classifier`] and [`english_ordinal_classifier`].
 ///
 /// [grammatical numbers]: https://en.wikipedia.org/wiki/Grammatical_number
 /// [ICU `PluralFormat`]: icu/ast/struct.PluralFormat.html
 /// [`english_cardinal_classifier`]: fn.english_cardinal_classifier.html
+/// [`english_ordinal_classifier`]: fn.english_ordinal_classifier.html
+///
+/// Categories are written as their keywords in ICU and CLDR, such as
+/// `few`, and can be parsed from them ignoring case:
+///
+/// ```
+/// use message_format::PluralCategory;
+///
+/// assert_eq!("Few".parse(), Ok(PluralCategory::Few));
+/// assert_eq!(PluralCategory::Few.to_string(), "few");
+/// ```
 #[derive(Clone,Copy,Debug,PartialEq)]
 pub enum PluralCategory {
     /// Value is `0`.
@@ -30,3 +46,83 @@ pub enum PluralCategory {
     /// form.
     Other,
 }
+
+impl PluralCategory {
+    /// Every category, in the order that plural rules test them.
+    pub const ALL: [PluralCategory; 6] = [PluralCategory::Zero,
+                                          PluralCategory::One,
+                                          PluralCategory::Two,
+                                          PluralCategory::Few,
+                                          PluralCategory::Many,
+                                          PluralCategory::Other];
+
+    /// The keyword for the category, such as `few`.
+    fn keyword(self) -> &'static str {
+        match self {
+            PluralCategory::Zero => "zero",
+            PluralCategory::One => "one",
+            PluralCategory::Two => "two",
+            PluralCategory::Few => "few",
+            PluralCategory::Many => "many",
+            PluralCategory::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for PluralCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.write_str(self.keyword())
+    }
+}
+
+impl FromStr for PluralCategory {
+    type Err = ParseCategoryError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        PluralCategory::ALL
+            .iter()
+            .find(|category| category.keyword().eq_ignore_ascii_case(source))
+            .cloned()
+            .ok_or_else(|| ParseCategoryError(source.to_string()))
+    }
+}
+
+/// An error from parsing a [`PluralCategory`] that isn't one of the
+/// keywords.
+///
+/// [`PluralCategory`]: enum.PluralCategory.html
+#[derive(Clone,Debug,PartialEq)]
+pub struct ParseCategoryError(pub String);
+
+impl Error for ParseCategoryError {}
+
+impl fmt::Display for ParseCategoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "Invalid plural category '{}'.", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParseCategoryError, PluralCategory};
+
+    #[test]
+    fn round_trips() {
+        for &category in &PluralCategory::ALL {
+            assert_eq!(category.to_string().parse(), Ok(category));
+            assert_eq!(category.to_string().to_uppercase().parse(), Ok(category));
+        }
+        assert_eq!(PluralCategory::ALL.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+                   ["zero", "one", "two", "few", "many", "other"]);
+    }
+
+    #[test]
+    fn rejects_unknown() {
+        assert_eq!("lots".parse::<PluralCategory>(),
+                   Err(ParseCategoryError("lots".to_string())));
+        assert!("".parse::<PluralCategory>().is_err());
+        assert!(" one".parse::<PluralCategory>().is_err());
+        assert_eq!(ParseCategoryError("lots".to_string()).to_string(),
+                   "Invalid plural category 'lots'.");
+    }
+}
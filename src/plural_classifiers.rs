@@ -4,12 +4,50 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use super::PluralCategory;
+use plural_rules::{cardinal_category, ordinal_category, parse_locale};
+use super::{PluralCategory, PluralOperands};
+
+/// Determine the plural category for a number.
+///
+/// The [`plural_rules`] module provides classifiers for many locales.
+/// Any function or closure from [`PluralOperands`] to [`PluralCategory`]
+/// is also a classifier, so rules loaded at runtime can be captured by
+/// a closure. The type of the closure's argument must be given, as in
+/// `move |operands: &PluralOperands| rules.category(operands)`.
+///
+/// Classifiers must be `Send` and `Sync` so that the messages using
+/// them can be shared between threads.
+///
+/// [`plural_rules`]: plural_rules/index.html
+/// [`PluralOperands`]: struct.PluralOperands.html
+/// [`PluralCategory`]: enum.PluralCategory.html
+pub trait PluralClassifier: Send + Sync {
+    /// Determine the plural category for a number.
+    fn classify(&self, operands: &PluralOperands) -> PluralCategory;
+
+    /// The condition for a category, in the CLDR rule syntax, such as
+    /// `i = 1 and v = 0`. This is used to explain why a category was
+    /// chosen.
+    ///
+    /// By default, this is `None`, as the conditions are unknown.
+    fn condition(&self, _category: PluralCategory) -> Option<&str> {
+        None
+    }
+}
+
+impl<F> PluralClassifier for F
+    where F: Fn(&PluralOperands) -> PluralCategory + Send + Sync
+{
+    fn classify(&self, operands: &PluralOperands) -> PluralCategory {
+        self(operands)
+    }
+}
 
 /// English cardinal plural classifier.
 ///
 /// In English, a single item is mapped to `PluralCategory::One`,
-/// with all other numbers mapped to `PluralCategory::Other`.
+/// with all other numbers mapped to `PluralCategory::Other`. Any
+/// integer type up to `i128` and `u64` can be classified.
 ///
 /// ```
 /// use message_format::{english_cardinal_classifier, PluralCategory};
@@ -17,10 +55,156 @@ use super::PluralCategory;
 /// assert_eq!(english_cardinal_classifier(0), PluralCategory::Other);
 /// assert_eq!(english_cardinal_classifier(1), PluralCategory::One);
 /// assert_eq!(english_cardinal_classifier(2), PluralCategory::Other);
+/// assert_eq!(english_cardinal_classifier(u64::MAX), PluralCategory::Other);
 /// ```
-pub fn english_cardinal_classifier(value: i64) -> PluralCategory {
-    match value {
+pub fn english_cardinal_classifier<T: Into<i128>>(value: T) -> PluralCategory {
+    match value.into() {
         1 => PluralCategory::One,
         _ => PluralCategory::Other,
     }
 }
+
+/// English ordinal plural classifier, as used for `selectordinal`.
+///
+/// Numbers ending in 1, 2 or 3 are mapped to `PluralCategory::One`,
+/// `PluralCategory::Two` and `PluralCategory::Few`, as in "1st", "22nd"
+/// and "103rd", except for those ending in 11, 12 or 13, which are
+/// mapped to `PluralCategory::Other` like the rest, as in "11th" and
+/// "112th". Negative numbers are classified by their absolute value.
+///
+/// ```
+/// use message_format::{english_ordinal_classifier, PluralCategory};
+///
+/// assert_eq!(english_ordinal_classifier(21), PluralCategory::One);
+/// assert_eq!(english_ordinal_classifier(22), PluralCategory::Two);
+/// assert_eq!(english_ordinal_classifier(23), PluralCategory::Few);
+/// assert_eq!(english_ordinal_classifier(13), PluralCategory::Other);
+/// ```
+pub fn english_ordinal_classifier<T: Into<i128>>(value: T) -> PluralCategory {
+    let value = value.into().unsigned_abs();
+    match (value % 10, value % 100) {
+        (_, 11..=13) => PluralCategory::Other,
+        (1, _) => PluralCategory::One,
+        (2, _) => PluralCategory::Two,
+        (3, _) => PluralCategory::Few,
+        _ => PluralCategory::Other,
+    }
+}
+
+/// Which plural rules of a locale to use.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum PluralKind {
+    /// The rules for counting things, as used by `plural`.
+    Cardinal,
+    /// The rules for ranking things, like "1st" and "2nd", as used by
+    /// `selectordinal`.
+    Ordinal,
+}
+
+/// Determine the plural category of a value in a `locale`, such as
+/// `"fr"` or `"pt_PT"`.
+///
+/// This uses the same rules as a `plural` or `selectordinal` message
+/// formatted in the locale. If there are no rules for the locale, or
+/// it is malformed, the category is `PluralCategory::Other`, as with
+/// the CLDR root rules. It doesn't fall back to English.
+///
+/// ```
+/// use message_format::{plural_category_for, PluralCategory, PluralKind};
+///
+/// assert_eq!(plural_category_for(1, "en", PluralKind::Cardinal), PluralCategory::One);
+/// assert_eq!(plural_category_for(22, "en", PluralKind::Ordinal), PluralCategory::Two);
+/// assert_eq!(plural_category_for(22, "ru", PluralKind::Cardinal), PluralCategory::Few);
+/// assert_eq!(plural_category_for(1, "tlh", PluralKind::Cardinal), PluralCategory::Other);
+/// ```
+pub fn plural_category_for<T: Into<PluralOperands>>(value: T,
+                                                    locale: &str,
+                                                    kind: PluralKind)
+                                                    -> PluralCategory {
+    let locale = match parse_locale(locale) {
+        Some(locale) => locale,
+        None => return PluralCategory::Other,
+    };
+    let operands = value.into();
+    match kind {
+        PluralKind::Cardinal => cardinal_category(&locale, &operands),
+        PluralKind::Ordinal => ordinal_category(&locale, &operands),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{english_cardinal_classifier, english_ordinal_classifier, plural_category_for,
+                PluralKind};
+    use icu::parse;
+    use {arg, Context, Locale, PluralOperands};
+    use PluralCategory::*;
+
+    #[test]
+    fn english_ordinals() {
+        let cases = [(0, Other), (1, One), (2, Two), (3, Few), (4, Other), (10, Other),
+                     (11, Other), (12, Other), (13, Other), (14, Other), (21, One), (22, Two),
+                     (23, Few), (31, One), (99, Other), (100, Other), (101, One), (102, Two),
+                     (103, Few), (104, Other), (110, Other), (111, Other), (112, Other),
+                     (113, Other), (121, One), (1011, Other), (1001, One), (-1, One),
+                     (-12, Other), (-23, Few), (i64::MIN, Other), (i64::MAX, Other)];
+        for &(value, category) in &cases {
+            assert_eq!(english_ordinal_classifier(value), category, "{}", value);
+        }
+    }
+
+    #[test]
+    fn wide_integers() {
+        let above = i128::from(i64::MAX) + 1;
+        assert_eq!(english_cardinal_classifier(above), Other);
+        assert_eq!(english_ordinal_classifier(above + 15), Few);
+        assert_eq!(english_ordinal_classifier(u64::MAX - 14), One);
+        assert_eq!(english_ordinal_classifier(i128::MIN + 7), One);
+        assert_eq!(english_ordinal_classifier(i128::MAX), Other);
+        assert_eq!(plural_category_for(above + 14, "ru", PluralKind::Cardinal), Few);
+        assert_eq!(plural_category_for(u64::MAX, "ru", PluralKind::Cardinal), Many);
+    }
+
+    #[test]
+    fn category_for() {
+        let cases = [("en", 1, PluralKind::Cardinal, One),
+                     ("en", 2, PluralKind::Cardinal, Other),
+                     ("en_US", 3, PluralKind::Ordinal, Few),
+                     ("fr", 0, PluralKind::Cardinal, One),
+                     ("ar", 0, PluralKind::Cardinal, Zero),
+                     ("cy", 2, PluralKind::Ordinal, Two),
+                     ("pl", 5, PluralKind::Cardinal, Many),
+                     // `de` only has cardinal rules.
+                     ("de", 1, PluralKind::Ordinal, Other)];
+        for &(locale, value, kind, category) in &cases {
+            assert_eq!(plural_category_for(value, locale, kind), category, "{} {}", locale, value);
+        }
+        let operands = "1.5".parse::<PluralOperands>().unwrap();
+        assert_eq!(plural_category_for(operands, "fr", PluralKind::Cardinal), One);
+    }
+
+    #[test]
+    fn unknown_locales_use_other() {
+        assert_eq!(plural_category_for(1, "tlh", PluralKind::Cardinal), Other);
+        assert_eq!(plural_category_for(1, "tlh", PluralKind::Ordinal), Other);
+        assert_eq!(plural_category_for(1, "not a locale", PluralKind::Cardinal), Other);
+        assert_eq!(plural_category_for(1, "", PluralKind::Cardinal), Other);
+    }
+
+    #[test]
+    fn matches_formatting() {
+        let m = parse("{n, plural, zero {zero} one {one} two {two} few {few} many {many} \
+                       other {other}} {n, selectordinal, zero {zero} one {one} two {two} \
+                       few {few} many {many} other {other}}")
+            .unwrap();
+        for locale in &["en", "fr", "ru", "ar", "cy", "tlh"] {
+            let ctx = Context::new(Locale::new(locale).unwrap(), None);
+            for n in 0..120 {
+                let expected = format!("{} {}",
+                                       plural_category_for(n, locale, PluralKind::Cardinal),
+                                       plural_category_for(n, locale, PluralKind::Ordinal));
+                assert_eq!(ctx.format(&m, Some(&arg("n", n))), expected, "{} {}", locale, n);
+            }
+        }
+    }
+}
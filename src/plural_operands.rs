@@ -0,0 +1,327 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::string::{String, ToString};
+
+/// The [plural operands] of a number, which are used by plural rules.
+///
+/// Plural categories depend on more than the numeric value. In many
+/// languages, `1` and `1.0` are in different categories, so the
+/// operands also describe the visible fraction digits.
+///
+/// ```
+/// use message_format::PluralOperands;
+///
+/// let operands = PluralOperands::from(-12);
+/// assert_eq!(operands.n, 12.0);
+/// assert_eq!(operands.i, 12);
+/// assert_eq!(operands.v, 0);
+/// ```
+///
+/// Integers with more than 15 digits, which can't all be held exactly
+/// by `n`, are reduced to their last 15 digits with a leading `1`.
+/// Plural rules only look at the last few digits of large numbers, so
+/// this keeps their category:
+///
+/// ```
+/// use message_format::PluralOperands;
+///
+/// let operands = PluralOperands::from(u64::MAX);
+/// assert_eq!(operands.i, 1_744_073_709_551_615);
+/// assert_eq!(operands.i % 1000, 615);
+/// ```
+///
+/// [plural operands]: http://unicode.org/reports/tr35/tr35-numbers.html#Operands
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct PluralOperands {
+    /// The absolute value of the number.
+    pub n: f64,
+    /// The integer digits of `n`.
+    pub i: u64,
+    /// The number of visible fraction digits, with trailing zeros.
+    pub v: usize,
+    /// The number of visible fraction digits, without trailing zeros.
+    pub w: usize,
+    /// The visible fraction digits, with trailing zeros, as an integer.
+    pub f: u64,
+    /// The visible fraction digits, without trailing zeros, as an integer.
+    pub t: u64,
+    /// The exponent of the power of ten in compact decimal notation,
+    /// like the `3` in `1.2c3`. This is `0` for other numbers.
+    pub e: usize,
+}
+
+/// The smallest integer that is reduced to its last digits.
+const LARGE_INTEGER: u128 = 1_000_000_000_000_000;
+
+impl From<i32> for PluralOperands {
+    fn from(value: i32) -> Self {
+        PluralOperands::from(i128::from(value))
+    }
+}
+
+impl From<u32> for PluralOperands {
+    fn from(value: u32) -> Self {
+        PluralOperands::from(i128::from(value))
+    }
+}
+
+impl From<i64> for PluralOperands {
+    fn from(value: i64) -> Self {
+        PluralOperands::from(i128::from(value))
+    }
+}
+
+impl From<u64> for PluralOperands {
+    fn from(value: u64) -> Self {
+        PluralOperands::from(i128::from(value))
+    }
+}
+
+impl From<i128> for PluralOperands {
+    fn from(value: i128) -> Self {
+        let mut value = value.unsigned_abs();
+        if value >= LARGE_INTEGER {
+            value = LARGE_INTEGER + value % LARGE_INTEGER;
+        }
+        let value = value as u64;
+        PluralOperands {
+            n: value as f64,
+            i: value,
+            v: 0,
+            w: 0,
+            f: 0,
+            t: 0,
+            e: 0,
+        }
+    }
+}
+
+impl PluralOperands {
+    /// The operands of a decimal `value` that is written with exactly
+    /// `fraction_digits` digits after the decimal point.
+    ///
+    /// ```
+    /// use message_format::PluralOperands;
+    ///
+    /// let operands = PluralOperands::from_decimal(1.50, 2);
+    /// assert_eq!((operands.i, operands.v, operands.w), (1, 2, 1));
+    /// assert_eq!((operands.f, operands.t), (50, 5));
+    /// ```
+    pub fn from_decimal(value: f64, fraction_digits: usize) -> Self {
+        let source = format!("{:.*}", fraction_digits, value.abs());
+        let (integer, fraction) = split_fraction(&source);
+        PluralOperands::from_digits(integer, fraction, 0).unwrap_or(PluralOperands {
+            n: value.abs(),
+            i: u64::MAX,
+            v: fraction.len(),
+            w: 0,
+            f: 0,
+            t: 0,
+            e: 0,
+        })
+    }
+
+    /// The operands of a number with the given integer and fraction
+    /// digits, or `None` if they don't fit.
+    fn from_digits(integer: &str, fraction: &str, e: usize) -> Option<Self> {
+        let trimmed = fraction.trim_end_matches('0');
+        let digits = |digits: &str| if digits.is_empty() { Some(0) } else { digits.parse().ok() };
+        Some(PluralOperands {
+            n: format!("{}.{}", integer, fraction).parse().ok()?,
+            i: integer.parse().ok()?,
+            v: fraction.len(),
+            w: trimmed.len(),
+            f: digits(fraction)?,
+            t: digits(trimmed)?,
+            e,
+        })
+    }
+}
+
+/// Split a number at the decimal point.
+fn split_fraction(source: &str) -> (&str, &str) {
+    match source.find('.') {
+        Some(pos) => (&source[..pos], &source[pos + 1..]),
+        None => (source, ""),
+    }
+}
+
+/// Parse the operands of a number written as a string, which keeps
+/// the visible fraction digits, like the `0` of `"1.50"`.
+///
+/// The number may be negative and may have a non-negative exponent in
+/// compact notation, like `"1.2c3"` for `1200`. An exponent can also be
+/// written with `e`.
+///
+/// ```
+/// use message_format::PluralOperands;
+///
+/// let operands = "1.50".parse::<PluralOperands>().unwrap();
+/// assert_eq!((operands.v, operands.f, operands.t), (2, 50, 5));
+///
+/// let operands = "-1.2c3".parse::<PluralOperands>().unwrap();
+/// assert_eq!((operands.n, operands.i, operands.e), (1200.0, 1200, 3));
+/// ```
+impl FromStr for PluralOperands {
+    type Err = ParseOperandsError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        DecimalDigits::parse(source)
+            .and_then(|digits| {
+                PluralOperands::from_digits(&digits.integer, digits.fraction, digits.e)
+            })
+            .ok_or_else(|| ParseOperandsError(source.to_string()))
+    }
+}
+
+/// The digits of a number written as a string, with the decimal point
+/// moved for its exponent, as parsed by `PluralOperands::from_str`.
+pub(crate) struct DecimalDigits<'a> {
+    pub(crate) negative: bool,
+    /// The integer digits, without leading zeros unless it is `0`.
+    pub(crate) integer: String,
+    pub(crate) fraction: &'a str,
+    pub(crate) e: usize,
+}
+
+impl<'a> DecimalDigits<'a> {
+    /// Parse a number, or `None` if it isn't one or its integer digits
+    /// don't fit in a `u64`.
+    pub(crate) fn parse(source: &'a str) -> Option<Self> {
+        let unsigned = source.strip_prefix('-');
+        let negative = unsigned.is_some();
+        let unsigned = unsigned.unwrap_or(source);
+        let (mantissa, e) = match unsigned.find(['c', 'e', 'E']) {
+            Some(pos) => {
+                let exponent = &unsigned[pos + 1..];
+                let exponent = exponent.strip_prefix('+').unwrap_or(exponent);
+                if exponent.is_empty() || !exponent.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                (&unsigned[..pos], exponent.parse::<usize>().ok()?)
+            }
+            None => (unsigned, 0),
+        };
+        let (integer, fraction) = split_fraction(mantissa);
+        let is_digits = |digits: &str| digits.bytes().all(|b| b.is_ascii_digit());
+        if integer.is_empty() || !is_digits(integer) || !is_digits(fraction) ||
+           mantissa.ends_with('.') {
+            return None;
+        }
+        // Move the decimal point for the exponent. A `u64` has at most
+        // 20 digits, so a larger exponent is only checked for a zero.
+        let shift = e.min(fraction.len());
+        let integer = format!("{}{}", integer, &fraction[..shift]);
+        let integer = integer.trim_start_matches('0');
+        let zeros = e - shift;
+        let integer = if integer.is_empty() {
+            "0".to_string()
+        } else if integer.len() + zeros > 20 {
+            return None;
+        } else {
+            format!("{}{}", integer, "0".repeat(zeros))
+        };
+        Some(DecimalDigits {
+            negative,
+            integer,
+            fraction: &fraction[shift..],
+            e,
+        })
+    }
+}
+
+/// The number, like `-1200.50` for `-1.20050c3`.
+impl<'a> fmt::Display for DecimalDigits<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        if self.negative {
+            f.write_str("-")?;
+        }
+        f.write_str(&self.integer)?;
+        if !self.fraction.is_empty() {
+            write!(f, ".{}", self.fraction)?;
+        }
+        Ok(())
+    }
+}
+
+/// An error parsing [`PluralOperands`] from a string that isn't a
+/// number, or is too large. Has the string.
+///
+/// [`PluralOperands`]: struct.PluralOperands.html
+#[derive(Clone,Debug,PartialEq)]
+pub struct ParseOperandsError(pub String);
+
+impl Error for ParseOperandsError {}
+
+impl fmt::Display for ParseOperandsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "Invalid number '{}'.", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParseOperandsError, PluralOperands};
+
+    fn operands(n: f64, i: u64, v: usize, w: usize, f: u64, t: u64, e: usize) -> PluralOperands {
+        PluralOperands { n, i, v, w, f, t, e }
+    }
+
+    #[test]
+    fn cldr_examples() {
+        // The examples from the table of plural operands in UTS #35.
+        let examples = [("1", operands(1.0, 1, 0, 0, 0, 0, 0)),
+                        ("1.0", operands(1.0, 1, 1, 0, 0, 0, 0)),
+                        ("1.00", operands(1.0, 1, 2, 0, 0, 0, 0)),
+                        ("1.3", operands(1.3, 1, 1, 1, 3, 3, 0)),
+                        ("1.30", operands(1.3, 1, 2, 1, 30, 3, 0)),
+                        ("1.03", operands(1.03, 1, 2, 2, 3, 3, 0)),
+                        ("1.230", operands(1.23, 1, 3, 2, 230, 23, 0)),
+                        ("1200000", operands(1200000.0, 1200000, 0, 0, 0, 0, 0)),
+                        ("1.2c6", operands(1200000.0, 1200000, 0, 0, 0, 0, 6)),
+                        ("123c6", operands(123000000.0, 123000000, 0, 0, 0, 0, 6)),
+                        ("123c5", operands(12300000.0, 12300000, 0, 0, 0, 0, 5)),
+                        ("1200.50", operands(1200.5, 1200, 2, 1, 50, 5, 0)),
+                        ("1.20050c3", operands(1200.5, 1200, 2, 1, 50, 5, 3))];
+        for &(source, expected) in &examples {
+            assert_eq!(source.parse::<PluralOperands>(), Ok(expected), "{}", source);
+        }
+    }
+
+    #[test]
+    fn signs_and_exponents() {
+        assert_eq!("-1.50".parse(), Ok(operands(1.5, 1, 2, 1, 50, 5, 0)));
+        assert_eq!("0.5".parse(), Ok(operands(0.5, 0, 1, 1, 5, 5, 0)));
+        assert_eq!("1.5e+2".parse(), Ok(operands(150.0, 150, 0, 0, 0, 0, 2)));
+        assert_eq!("00.10".parse(), Ok(operands(0.1, 0, 2, 1, 10, 1, 0)));
+        assert_eq!("0c999999999".parse(), Ok(operands(0.0, 0, 0, 0, 0, 0, 999999999)));
+        assert_eq!("1.5c19".parse(),
+                   Ok(operands(1.5e19, 15_000_000_000_000_000_000, 0, 0, 0, 0, 19)));
+    }
+
+    #[test]
+    fn invalid_numbers() {
+        for source in &["", "-", "abc", "1.", ".5", "1.2.3", "1c", "1c-3", "+1", "1 ",
+                        "99999999999999999999", "2c19", "1.5c20", "1e999999999",
+                        "1c99999999999999999999"] {
+            assert_eq!(source.parse::<PluralOperands>(),
+                       Err(ParseOperandsError(source.to_string())));
+        }
+    }
+
+    #[test]
+    fn decimals() {
+        assert_eq!(PluralOperands::from_decimal(1.0, 0), PluralOperands::from(1));
+        assert_eq!(PluralOperands::from_decimal(-1.0, 1),
+                   PluralOperands { n: 1.0, i: 1, v: 1, w: 0, f: 0, t: 0, e: 0 });
+        assert_eq!(PluralOperands::from_decimal(2.056, 2),
+                   PluralOperands { n: 2.06, i: 2, v: 2, w: 2, f: 6, t: 6, e: 0 });
+    }
+}
@@ -0,0 +1,2240 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Generated by cldr-gen from CLDR 42. Do not edit.
+
+#![allow(clippy::nonminimal_bool)]
+
+#[cfg(test)]
+use super::Samples;
+use super::{in_range, Conditions, Rule};
+use {PluralCategory, PluralOperands};
+use PluralCategory::*;
+
+/// The cardinal rules, by locale in lowercase.
+pub(super) const CARDINAL_RULES: &[(&str, Rule, Conditions)] = &[
+    ("af", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("ak", cardinal_ak, CARDINAL_AK_CONDITIONS),
+    ("am", cardinal_am, CARDINAL_AM_CONDITIONS),
+    ("an", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("ar", cardinal_ar, CARDINAL_AR_CONDITIONS),
+    ("ars", cardinal_ar, CARDINAL_AR_CONDITIONS),
+    ("as", cardinal_am, CARDINAL_AM_CONDITIONS),
+    ("asa", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("ast", cardinal_ast, CARDINAL_AST_CONDITIONS),
+    ("az", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("bal", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("be", cardinal_be, CARDINAL_BE_CONDITIONS),
+    ("bem", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("bez", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("bg", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("bho", cardinal_ak, CARDINAL_AK_CONDITIONS),
+    ("bm", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("bn", cardinal_am, CARDINAL_AM_CONDITIONS),
+    ("bo", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("br", cardinal_br, CARDINAL_BR_CONDITIONS),
+    ("brx", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("bs", cardinal_bs, CARDINAL_BS_CONDITIONS),
+    ("ca", cardinal_ca, CARDINAL_CA_CONDITIONS),
+    ("ce", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("ceb", cardinal_ceb, CARDINAL_CEB_CONDITIONS),
+    ("cgg", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("chr", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("ckb", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("cs", cardinal_cs, CARDINAL_CS_CONDITIONS),
+    ("cy", cardinal_cy, CARDINAL_CY_CONDITIONS),
+    ("da", cardinal_da, CARDINAL_DA_CONDITIONS),
+    ("de", cardinal_ast, CARDINAL_AST_CONDITIONS),
+    ("doi", cardinal_am, CARDINAL_AM_CONDITIONS),
+    ("dsb", cardinal_dsb, CARDINAL_DSB_CONDITIONS),
+    ("dv", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("dz", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("ee", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("el", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("en", cardinal_ast, CARDINAL_AST_CONDITIONS),
+    ("eo", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("es", cardinal_es, CARDINAL_ES_CONDITIONS),
+    ("et", cardinal_ast, CARDINAL_AST_CONDITIONS),
+    ("eu", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("fa", cardinal_am, CARDINAL_AM_CONDITIONS),
+    ("ff", cardinal_ff, CARDINAL_FF_CONDITIONS),
+    ("fi", cardinal_ast, CARDINAL_AST_CONDITIONS),
+    ("fil", cardinal_ceb, CARDINAL_CEB_CONDITIONS),
+    ("fo", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("fr", cardinal_fr, CARDINAL_FR_CONDITIONS),
+    ("fur", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("fy", cardinal_ast, CARDINAL_AST_CONDITIONS),
+    ("ga", cardinal_ga, CARDINAL_GA_CONDITIONS),
+    ("gd", cardinal_gd, CARDINAL_GD_CONDITIONS),
+    ("gl", cardinal_ast, CARDINAL_AST_CONDITIONS),
+    ("gsw", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("gu", cardinal_am, CARDINAL_AM_CONDITIONS),
+    ("guw", cardinal_ak, CARDINAL_AK_CONDITIONS),
+    ("gv", cardinal_gv, CARDINAL_GV_CONDITIONS),
+    ("ha", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("haw", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("he", cardinal_he, CARDINAL_HE_CONDITIONS),
+    ("hi", cardinal_am, CARDINAL_AM_CONDITIONS),
+    ("hnj", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("hr", cardinal_bs, CARDINAL_BS_CONDITIONS),
+    ("hsb", cardinal_dsb, CARDINAL_DSB_CONDITIONS),
+    ("hu", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("hy", cardinal_ff, CARDINAL_FF_CONDITIONS),
+    ("ia", cardinal_ast, CARDINAL_AST_CONDITIONS),
+    ("id", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("ig", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("ii", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("io", cardinal_ast, CARDINAL_AST_CONDITIONS),
+    ("is", cardinal_is, CARDINAL_IS_CONDITIONS),
+    ("it", cardinal_ca, CARDINAL_CA_CONDITIONS),
+    ("iu", cardinal_iu, CARDINAL_IU_CONDITIONS),
+    ("ja", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("jbo", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("jgo", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("jmc", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("jv", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("jw", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("ka", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("kab", cardinal_ff, CARDINAL_FF_CONDITIONS),
+    ("kaj", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("kcg", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("kde", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("kea", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("kk", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("kkj", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("kl", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("km", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("kn", cardinal_am, CARDINAL_AM_CONDITIONS),
+    ("ko", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("ks", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("ksb", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("ksh", cardinal_ksh, CARDINAL_KSH_CONDITIONS),
+    ("ku", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("kw", cardinal_kw, CARDINAL_KW_CONDITIONS),
+    ("ky", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("lag", cardinal_lag, CARDINAL_LAG_CONDITIONS),
+    ("lb", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("lg", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("lij", cardinal_ast, CARDINAL_AST_CONDITIONS),
+    ("lkt", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("ln", cardinal_ak, CARDINAL_AK_CONDITIONS),
+    ("lo", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("lt", cardinal_lt, CARDINAL_LT_CONDITIONS),
+    ("lv", cardinal_lv, CARDINAL_LV_CONDITIONS),
+    ("mas", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("mg", cardinal_ak, CARDINAL_AK_CONDITIONS),
+    ("mgo", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("mk", cardinal_mk, CARDINAL_MK_CONDITIONS),
+    ("ml", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("mn", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("mo", cardinal_mo, CARDINAL_MO_CONDITIONS),
+    ("mr", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("ms", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("mt", cardinal_mt, CARDINAL_MT_CONDITIONS),
+    ("my", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("nah", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("naq", cardinal_iu, CARDINAL_IU_CONDITIONS),
+    ("nb", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("nd", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("ne", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("nl", cardinal_ast, CARDINAL_AST_CONDITIONS),
+    ("nn", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("nnh", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("no", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("nqo", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("nr", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("nso", cardinal_ak, CARDINAL_AK_CONDITIONS),
+    ("ny", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("nyn", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("om", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("or", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("os", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("osa", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("pa", cardinal_ak, CARDINAL_AK_CONDITIONS),
+    ("pap", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("pcm", cardinal_am, CARDINAL_AM_CONDITIONS),
+    ("pl", cardinal_pl, CARDINAL_PL_CONDITIONS),
+    ("prg", cardinal_lv, CARDINAL_LV_CONDITIONS),
+    ("ps", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("pt", cardinal_pt, CARDINAL_PT_CONDITIONS),
+    ("pt-pt", cardinal_ca, CARDINAL_CA_CONDITIONS),
+    ("rm", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("ro", cardinal_mo, CARDINAL_MO_CONDITIONS),
+    ("rof", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("ru", cardinal_ru, CARDINAL_RU_CONDITIONS),
+    ("rwk", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("sah", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("saq", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("sat", cardinal_iu, CARDINAL_IU_CONDITIONS),
+    ("sc", cardinal_ast, CARDINAL_AST_CONDITIONS),
+    ("scn", cardinal_ast, CARDINAL_AST_CONDITIONS),
+    ("sd", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("sdh", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("se", cardinal_iu, CARDINAL_IU_CONDITIONS),
+    ("seh", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("ses", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("sg", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("sh", cardinal_bs, CARDINAL_BS_CONDITIONS),
+    ("shi", cardinal_shi, CARDINAL_SHI_CONDITIONS),
+    ("si", cardinal_si, CARDINAL_SI_CONDITIONS),
+    ("sk", cardinal_cs, CARDINAL_CS_CONDITIONS),
+    ("sl", cardinal_sl, CARDINAL_SL_CONDITIONS),
+    ("sma", cardinal_iu, CARDINAL_IU_CONDITIONS),
+    ("smi", cardinal_iu, CARDINAL_IU_CONDITIONS),
+    ("smj", cardinal_iu, CARDINAL_IU_CONDITIONS),
+    ("smn", cardinal_iu, CARDINAL_IU_CONDITIONS),
+    ("sms", cardinal_iu, CARDINAL_IU_CONDITIONS),
+    ("sn", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("so", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("sq", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("sr", cardinal_bs, CARDINAL_BS_CONDITIONS),
+    ("ss", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("ssy", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("st", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("su", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("sv", cardinal_ast, CARDINAL_AST_CONDITIONS),
+    ("sw", cardinal_ast, CARDINAL_AST_CONDITIONS),
+    ("syr", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("ta", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("te", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("teo", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("th", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("ti", cardinal_ak, CARDINAL_AK_CONDITIONS),
+    ("tig", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("tk", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("tl", cardinal_ceb, CARDINAL_CEB_CONDITIONS),
+    ("tn", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("to", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("tpi", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("tr", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("ts", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("tzm", cardinal_tzm, CARDINAL_TZM_CONDITIONS),
+    ("ug", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("uk", cardinal_ru, CARDINAL_RU_CONDITIONS),
+    ("und", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("ur", cardinal_ast, CARDINAL_AST_CONDITIONS),
+    ("uz", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("ve", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("vec", cardinal_ca, CARDINAL_CA_CONDITIONS),
+    ("vi", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("vo", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("vun", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("wa", cardinal_ak, CARDINAL_AK_CONDITIONS),
+    ("wae", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("wo", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("xh", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("xog", cardinal_af, CARDINAL_AF_CONDITIONS),
+    ("yi", cardinal_ast, CARDINAL_AST_CONDITIONS),
+    ("yo", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("yue", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("zh", cardinal_bm, CARDINAL_BM_CONDITIONS),
+    ("zu", cardinal_am, CARDINAL_AM_CONDITIONS),
+];
+
+/// The ordinal rules, by locale in lowercase.
+pub(super) const ORDINAL_RULES: &[(&str, Rule, Conditions)] = &[
+    ("af", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("am", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("an", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("ar", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("as", ordinal_as, ORDINAL_AS_CONDITIONS),
+    ("ast", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("az", ordinal_az, ORDINAL_AZ_CONDITIONS),
+    ("bal", ordinal_bal, ORDINAL_BAL_CONDITIONS),
+    ("be", ordinal_be, ORDINAL_BE_CONDITIONS),
+    ("bg", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("bn", ordinal_as, ORDINAL_AS_CONDITIONS),
+    ("bs", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("ca", ordinal_ca, ORDINAL_CA_CONDITIONS),
+    ("ce", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("cs", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("cy", ordinal_cy, ORDINAL_CY_CONDITIONS),
+    ("da", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("de", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("dsb", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("el", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("en", ordinal_en, ORDINAL_EN_CONDITIONS),
+    ("es", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("et", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("eu", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("fa", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("fi", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("fil", ordinal_bal, ORDINAL_BAL_CONDITIONS),
+    ("fr", ordinal_bal, ORDINAL_BAL_CONDITIONS),
+    ("fy", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("ga", ordinal_bal, ORDINAL_BAL_CONDITIONS),
+    ("gd", ordinal_gd, ORDINAL_GD_CONDITIONS),
+    ("gl", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("gsw", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("gu", ordinal_gu, ORDINAL_GU_CONDITIONS),
+    ("he", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("hi", ordinal_gu, ORDINAL_GU_CONDITIONS),
+    ("hr", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("hsb", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("hu", ordinal_hu, ORDINAL_HU_CONDITIONS),
+    ("hy", ordinal_bal, ORDINAL_BAL_CONDITIONS),
+    ("ia", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("id", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("is", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("it", ordinal_it, ORDINAL_IT_CONDITIONS),
+    ("ja", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("ka", ordinal_ka, ORDINAL_KA_CONDITIONS),
+    ("kk", ordinal_kk, ORDINAL_KK_CONDITIONS),
+    ("km", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("kn", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("ko", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("kw", ordinal_kw, ORDINAL_KW_CONDITIONS),
+    ("ky", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("lij", ordinal_lij, ORDINAL_LIJ_CONDITIONS),
+    ("lo", ordinal_bal, ORDINAL_BAL_CONDITIONS),
+    ("lt", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("lv", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("mk", ordinal_mk, ORDINAL_MK_CONDITIONS),
+    ("ml", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("mn", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("mo", ordinal_bal, ORDINAL_BAL_CONDITIONS),
+    ("mr", ordinal_mr, ORDINAL_MR_CONDITIONS),
+    ("ms", ordinal_bal, ORDINAL_BAL_CONDITIONS),
+    ("my", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("nb", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("ne", ordinal_ne, ORDINAL_NE_CONDITIONS),
+    ("nl", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("no", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("or", ordinal_or, ORDINAL_OR_CONDITIONS),
+    ("pa", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("pl", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("prg", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("ps", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("pt", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("ro", ordinal_bal, ORDINAL_BAL_CONDITIONS),
+    ("ru", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("sc", ordinal_it, ORDINAL_IT_CONDITIONS),
+    ("scn", ordinal_it, ORDINAL_IT_CONDITIONS),
+    ("sd", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("sh", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("si", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("sk", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("sl", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("sq", ordinal_sq, ORDINAL_SQ_CONDITIONS),
+    ("sr", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("sv", ordinal_sv, ORDINAL_SV_CONDITIONS),
+    ("sw", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("ta", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("te", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("th", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("tk", ordinal_tk, ORDINAL_TK_CONDITIONS),
+    ("tl", ordinal_bal, ORDINAL_BAL_CONDITIONS),
+    ("tpi", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("tr", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("uk", ordinal_uk, ORDINAL_UK_CONDITIONS),
+    ("und", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("ur", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("uz", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("vec", ordinal_it, ORDINAL_IT_CONDITIONS),
+    ("vi", ordinal_bal, ORDINAL_BAL_CONDITIONS),
+    ("yue", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("zh", ordinal_af, ORDINAL_AF_CONDITIONS),
+    ("zu", ordinal_af, ORDINAL_AF_CONDITIONS),
+];
+
+fn cardinal_af(o: &PluralOperands) -> PluralCategory {
+    if o.n == 1.0 {
+        One
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_AF_CONDITIONS: Conditions = &[
+    (One, "n = 1"),
+];
+
+fn cardinal_ak(o: &PluralOperands) -> PluralCategory {
+    if in_range(o.n, 0.0, 1.0) {
+        One
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_AK_CONDITIONS: Conditions = &[
+    (One, "n = 0..1"),
+];
+
+fn cardinal_am(o: &PluralOperands) -> PluralCategory {
+    if o.i == 0 || o.n == 1.0 {
+        One
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_AM_CONDITIONS: Conditions = &[
+    (One, "i = 0 or n = 1"),
+];
+
+fn cardinal_ar(o: &PluralOperands) -> PluralCategory {
+    if o.n == 0.0 {
+        Zero
+    } else if o.n == 1.0 {
+        One
+    } else if o.n == 2.0 {
+        Two
+    } else if in_range(o.n % 100.0, 3.0, 10.0) {
+        Few
+    } else if in_range(o.n % 100.0, 11.0, 99.0) {
+        Many
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_AR_CONDITIONS: Conditions = &[
+    (Zero, "n = 0"),
+    (One, "n = 1"),
+    (Two, "n = 2"),
+    (Few, "n % 100 = 3..10"),
+    (Many, "n % 100 = 11..99"),
+];
+
+fn cardinal_ast(o: &PluralOperands) -> PluralCategory {
+    if o.i == 1 && o.v == 0 {
+        One
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_AST_CONDITIONS: Conditions = &[
+    (One, "i = 1 and v = 0"),
+];
+
+fn cardinal_be(o: &PluralOperands) -> PluralCategory {
+    if o.n % 10.0 == 1.0 && o.n % 100.0 != 11.0 {
+        One
+    } else if in_range(o.n % 10.0, 2.0, 4.0) && !in_range(o.n % 100.0, 12.0, 14.0) {
+        Few
+    } else if o.n % 10.0 == 0.0 || in_range(o.n % 10.0, 5.0, 9.0) || in_range(o.n % 100.0, 11.0, 14.0) {
+        Many
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_BE_CONDITIONS: Conditions = &[
+    (One, "n % 10 = 1 and n % 100 != 11"),
+    (Few, "n % 10 = 2..4 and n % 100 != 12..14"),
+    (Many, "n % 10 = 0 or n % 10 = 5..9 or n % 100 = 11..14"),
+];
+
+fn cardinal_bm(_: &PluralOperands) -> PluralCategory {
+    Other
+}
+
+const CARDINAL_BM_CONDITIONS: Conditions = &[];
+
+fn cardinal_br(o: &PluralOperands) -> PluralCategory {
+    if o.n % 10.0 == 1.0 && o.n % 100.0 != 11.0 && o.n % 100.0 != 71.0 && o.n % 100.0 != 91.0 {
+        One
+    } else if o.n % 10.0 == 2.0 && o.n % 100.0 != 12.0 && o.n % 100.0 != 72.0 && o.n % 100.0 != 92.0 {
+        Two
+    } else if (in_range(o.n % 10.0, 3.0, 4.0) || o.n % 10.0 == 9.0) && !in_range(o.n % 100.0, 10.0, 19.0) && !in_range(o.n % 100.0, 70.0, 79.0) && !in_range(o.n % 100.0, 90.0, 99.0) {
+        Few
+    } else if o.n != 0.0 && o.n % 1000000.0 == 0.0 {
+        Many
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_BR_CONDITIONS: Conditions = &[
+    (One, "n % 10 = 1 and n % 100 != 11,71,91"),
+    (Two, "n % 10 = 2 and n % 100 != 12,72,92"),
+    (Few, "n % 10 = 3..4,9 and n % 100 != 10..19,70..79,90..99"),
+    (Many, "n != 0 and n % 1000000 = 0"),
+];
+
+fn cardinal_bs(o: &PluralOperands) -> PluralCategory {
+    if o.v == 0 && o.i % 10 == 1 && o.i % 100 != 11 || o.f % 10 == 1 && o.f % 100 != 11 {
+        One
+    } else if o.v == 0 && (2..=4).contains(&(o.i % 10)) && !(12..=14).contains(&(o.i % 100)) || (2..=4).contains(&(o.f % 10)) && !(12..=14).contains(&(o.f % 100)) {
+        Few
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_BS_CONDITIONS: Conditions = &[
+    (One, "v = 0 and i % 10 = 1 and i % 100 != 11 or f % 10 = 1 and f % 100 != 11"),
+    (Few, "v = 0 and i % 10 = 2..4 and i % 100 != 12..14 or f % 10 = 2..4 and f % 100 != 12..14"),
+];
+
+fn cardinal_ca(o: &PluralOperands) -> PluralCategory {
+    if o.i == 1 && o.v == 0 {
+        One
+    } else if o.e == 0 && o.i != 0 && o.i % 1000000 == 0 && o.v == 0 || !(0..=5).contains(&o.e) {
+        Many
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_CA_CONDITIONS: Conditions = &[
+    (One, "i = 1 and v = 0"),
+    (Many, "e = 0 and i != 0 and i % 1000000 = 0 and v = 0 or e != 0..5"),
+];
+
+fn cardinal_ceb(o: &PluralOperands) -> PluralCategory {
+    if (o.v == 0 && (o.i == 1 || o.i == 2 || o.i == 3)) || o.v == 0 && o.i % 10 != 4 && o.i % 10 != 6 && o.i % 10 != 9 || o.v != 0 && o.f % 10 != 4 && o.f % 10 != 6 && o.f % 10 != 9 {
+        One
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_CEB_CONDITIONS: Conditions = &[
+    (One, "v = 0 and i = 1,2,3 or v = 0 and i % 10 != 4,6,9 or v != 0 and f % 10 != 4,6,9"),
+];
+
+fn cardinal_cs(o: &PluralOperands) -> PluralCategory {
+    if o.i == 1 && o.v == 0 {
+        One
+    } else if (2..=4).contains(&o.i) && o.v == 0 {
+        Few
+    } else if o.v != 0 {
+        Many
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_CS_CONDITIONS: Conditions = &[
+    (One, "i = 1 and v = 0"),
+    (Few, "i = 2..4 and v = 0"),
+    (Many, "v != 0"),
+];
+
+fn cardinal_cy(o: &PluralOperands) -> PluralCategory {
+    if o.n == 0.0 {
+        Zero
+    } else if o.n == 1.0 {
+        One
+    } else if o.n == 2.0 {
+        Two
+    } else if o.n == 3.0 {
+        Few
+    } else if o.n == 6.0 {
+        Many
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_CY_CONDITIONS: Conditions = &[
+    (Zero, "n = 0"),
+    (One, "n = 1"),
+    (Two, "n = 2"),
+    (Few, "n = 3"),
+    (Many, "n = 6"),
+];
+
+fn cardinal_da(o: &PluralOperands) -> PluralCategory {
+    if o.n == 1.0 || (o.t != 0 && (o.i == 0 || o.i == 1)) {
+        One
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_DA_CONDITIONS: Conditions = &[
+    (One, "n = 1 or t != 0 and i = 0,1"),
+];
+
+fn cardinal_dsb(o: &PluralOperands) -> PluralCategory {
+    if o.v == 0 && o.i % 100 == 1 || o.f % 100 == 1 {
+        One
+    } else if o.v == 0 && o.i % 100 == 2 || o.f % 100 == 2 {
+        Two
+    } else if o.v == 0 && (3..=4).contains(&(o.i % 100)) || (3..=4).contains(&(o.f % 100)) {
+        Few
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_DSB_CONDITIONS: Conditions = &[
+    (One, "v = 0 and i % 100 = 1 or f % 100 = 1"),
+    (Two, "v = 0 and i % 100 = 2 or f % 100 = 2"),
+    (Few, "v = 0 and i % 100 = 3..4 or f % 100 = 3..4"),
+];
+
+fn cardinal_es(o: &PluralOperands) -> PluralCategory {
+    if o.n == 1.0 {
+        One
+    } else if o.e == 0 && o.i != 0 && o.i % 1000000 == 0 && o.v == 0 || !(0..=5).contains(&o.e) {
+        Many
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_ES_CONDITIONS: Conditions = &[
+    (One, "n = 1"),
+    (Many, "e = 0 and i != 0 and i % 1000000 = 0 and v = 0 or e != 0..5"),
+];
+
+fn cardinal_ff(o: &PluralOperands) -> PluralCategory {
+    if o.i == 0 || o.i == 1 {
+        One
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_FF_CONDITIONS: Conditions = &[
+    (One, "i = 0,1"),
+];
+
+fn cardinal_fr(o: &PluralOperands) -> PluralCategory {
+    if o.i == 0 || o.i == 1 {
+        One
+    } else if o.e == 0 && o.i != 0 && o.i % 1000000 == 0 && o.v == 0 || !(0..=5).contains(&o.e) {
+        Many
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_FR_CONDITIONS: Conditions = &[
+    (One, "i = 0,1"),
+    (Many, "e = 0 and i != 0 and i % 1000000 = 0 and v = 0 or e != 0..5"),
+];
+
+fn cardinal_ga(o: &PluralOperands) -> PluralCategory {
+    if o.n == 1.0 {
+        One
+    } else if o.n == 2.0 {
+        Two
+    } else if in_range(o.n, 3.0, 6.0) {
+        Few
+    } else if in_range(o.n, 7.0, 10.0) {
+        Many
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_GA_CONDITIONS: Conditions = &[
+    (One, "n = 1"),
+    (Two, "n = 2"),
+    (Few, "n = 3..6"),
+    (Many, "n = 7..10"),
+];
+
+fn cardinal_gd(o: &PluralOperands) -> PluralCategory {
+    if o.n == 1.0 || o.n == 11.0 {
+        One
+    } else if o.n == 2.0 || o.n == 12.0 {
+        Two
+    } else if in_range(o.n, 3.0, 10.0) || in_range(o.n, 13.0, 19.0) {
+        Few
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_GD_CONDITIONS: Conditions = &[
+    (One, "n = 1,11"),
+    (Two, "n = 2,12"),
+    (Few, "n = 3..10,13..19"),
+];
+
+fn cardinal_gv(o: &PluralOperands) -> PluralCategory {
+    if o.v == 0 && o.i % 10 == 1 {
+        One
+    } else if o.v == 0 && o.i % 10 == 2 {
+        Two
+    } else if o.v == 0 && (o.i % 100 == 0 || o.i % 100 == 20 || o.i % 100 == 40 || o.i % 100 == 60 || o.i % 100 == 80) {
+        Few
+    } else if o.v != 0 {
+        Many
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_GV_CONDITIONS: Conditions = &[
+    (One, "v = 0 and i % 10 = 1"),
+    (Two, "v = 0 and i % 10 = 2"),
+    (Few, "v = 0 and i % 100 = 0,20,40,60,80"),
+    (Many, "v != 0"),
+];
+
+fn cardinal_he(o: &PluralOperands) -> PluralCategory {
+    if o.i == 1 && o.v == 0 || o.i == 0 && o.v != 0 {
+        One
+    } else if o.i == 2 && o.v == 0 {
+        Two
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_HE_CONDITIONS: Conditions = &[
+    (One, "i = 1 and v = 0 or i = 0 and v != 0"),
+    (Two, "i = 2 and v = 0"),
+];
+
+fn cardinal_is(o: &PluralOperands) -> PluralCategory {
+    if o.t == 0 && o.i % 10 == 1 && o.i % 100 != 11 || o.t % 10 == 1 && o.t % 100 != 11 {
+        One
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_IS_CONDITIONS: Conditions = &[
+    (One, "t = 0 and i % 10 = 1 and i % 100 != 11 or t % 10 = 1 and t % 100 != 11"),
+];
+
+fn cardinal_iu(o: &PluralOperands) -> PluralCategory {
+    if o.n == 1.0 {
+        One
+    } else if o.n == 2.0 {
+        Two
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_IU_CONDITIONS: Conditions = &[
+    (One, "n = 1"),
+    (Two, "n = 2"),
+];
+
+fn cardinal_ksh(o: &PluralOperands) -> PluralCategory {
+    if o.n == 0.0 {
+        Zero
+    } else if o.n == 1.0 {
+        One
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_KSH_CONDITIONS: Conditions = &[
+    (Zero, "n = 0"),
+    (One, "n = 1"),
+];
+
+fn cardinal_kw(o: &PluralOperands) -> PluralCategory {
+    if o.n == 0.0 {
+        Zero
+    } else if o.n == 1.0 {
+        One
+    } else if (o.n % 100.0 == 2.0 || o.n % 100.0 == 22.0 || o.n % 100.0 == 42.0 || o.n % 100.0 == 62.0 || o.n % 100.0 == 82.0) || (o.n % 1000.0 == 0.0 && (in_range(o.n % 100000.0, 1000.0, 20000.0) || o.n % 100000.0 == 40000.0 || o.n % 100000.0 == 60000.0 || o.n % 100000.0 == 80000.0)) || o.n != 0.0 && o.n % 1000000.0 == 100000.0 {
+        Two
+    } else if o.n % 100.0 == 3.0 || o.n % 100.0 == 23.0 || o.n % 100.0 == 43.0 || o.n % 100.0 == 63.0 || o.n % 100.0 == 83.0 {
+        Few
+    } else if o.n != 1.0 && (o.n % 100.0 == 1.0 || o.n % 100.0 == 21.0 || o.n % 100.0 == 41.0 || o.n % 100.0 == 61.0 || o.n % 100.0 == 81.0) {
+        Many
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_KW_CONDITIONS: Conditions = &[
+    (Zero, "n = 0"),
+    (One, "n = 1"),
+    (Two, "n % 100 = 2,22,42,62,82 or n % 1000 = 0 and n % 100000 = 1000..20000,40000,60000,80000 or n != 0 and n % 1000000 = 100000"),
+    (Few, "n % 100 = 3,23,43,63,83"),
+    (Many, "n != 1 and n % 100 = 1,21,41,61,81"),
+];
+
+fn cardinal_lag(o: &PluralOperands) -> PluralCategory {
+    if o.n == 0.0 {
+        Zero
+    } else if (o.i == 0 || o.i == 1) && o.n != 0.0 {
+        One
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_LAG_CONDITIONS: Conditions = &[
+    (Zero, "n = 0"),
+    (One, "i = 0,1 and n != 0"),
+];
+
+fn cardinal_lt(o: &PluralOperands) -> PluralCategory {
+    if o.n % 10.0 == 1.0 && !in_range(o.n % 100.0, 11.0, 19.0) {
+        One
+    } else if in_range(o.n % 10.0, 2.0, 9.0) && !in_range(o.n % 100.0, 11.0, 19.0) {
+        Few
+    } else if o.f != 0 {
+        Many
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_LT_CONDITIONS: Conditions = &[
+    (One, "n % 10 = 1 and n % 100 != 11..19"),
+    (Few, "n % 10 = 2..9 and n % 100 != 11..19"),
+    (Many, "f != 0"),
+];
+
+fn cardinal_lv(o: &PluralOperands) -> PluralCategory {
+    if o.n % 10.0 == 0.0 || in_range(o.n % 100.0, 11.0, 19.0) || o.v == 2 && (11..=19).contains(&(o.f % 100)) {
+        Zero
+    } else if o.n % 10.0 == 1.0 && o.n % 100.0 != 11.0 || o.v == 2 && o.f % 10 == 1 && o.f % 100 != 11 || o.v != 2 && o.f % 10 == 1 {
+        One
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_LV_CONDITIONS: Conditions = &[
+    (Zero, "n % 10 = 0 or n % 100 = 11..19 or v = 2 and f % 100 = 11..19"),
+    (One, "n % 10 = 1 and n % 100 != 11 or v = 2 and f % 10 = 1 and f % 100 != 11 or v != 2 and f % 10 = 1"),
+];
+
+fn cardinal_mk(o: &PluralOperands) -> PluralCategory {
+    if o.v == 0 && o.i % 10 == 1 && o.i % 100 != 11 || o.f % 10 == 1 && o.f % 100 != 11 {
+        One
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_MK_CONDITIONS: Conditions = &[
+    (One, "v = 0 and i % 10 = 1 and i % 100 != 11 or f % 10 = 1 and f % 100 != 11"),
+];
+
+fn cardinal_mo(o: &PluralOperands) -> PluralCategory {
+    if o.i == 1 && o.v == 0 {
+        One
+    } else if o.v != 0 || o.n == 0.0 || o.n != 1.0 && in_range(o.n % 100.0, 1.0, 19.0) {
+        Few
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_MO_CONDITIONS: Conditions = &[
+    (One, "i = 1 and v = 0"),
+    (Few, "v != 0 or n = 0 or n != 1 and n % 100 = 1..19"),
+];
+
+fn cardinal_mt(o: &PluralOperands) -> PluralCategory {
+    if o.n == 1.0 {
+        One
+    } else if o.n == 2.0 {
+        Two
+    } else if o.n == 0.0 || in_range(o.n % 100.0, 3.0, 10.0) {
+        Few
+    } else if in_range(o.n % 100.0, 11.0, 19.0) {
+        Many
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_MT_CONDITIONS: Conditions = &[
+    (One, "n = 1"),
+    (Two, "n = 2"),
+    (Few, "n = 0 or n % 100 = 3..10"),
+    (Many, "n % 100 = 11..19"),
+];
+
+fn cardinal_pl(o: &PluralOperands) -> PluralCategory {
+    if o.i == 1 && o.v == 0 {
+        One
+    } else if o.v == 0 && (2..=4).contains(&(o.i % 10)) && !(12..=14).contains(&(o.i % 100)) {
+        Few
+    } else if o.v == 0 && o.i != 1 && (0..=1).contains(&(o.i % 10)) || o.v == 0 && (5..=9).contains(&(o.i % 10)) || o.v == 0 && (12..=14).contains(&(o.i % 100)) {
+        Many
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_PL_CONDITIONS: Conditions = &[
+    (One, "i = 1 and v = 0"),
+    (Few, "v = 0 and i % 10 = 2..4 and i % 100 != 12..14"),
+    (Many, "v = 0 and i != 1 and i % 10 = 0..1 or v = 0 and i % 10 = 5..9 or v = 0 and i % 100 = 12..14"),
+];
+
+fn cardinal_pt(o: &PluralOperands) -> PluralCategory {
+    if (0..=1).contains(&o.i) {
+        One
+    } else if o.e == 0 && o.i != 0 && o.i % 1000000 == 0 && o.v == 0 || !(0..=5).contains(&o.e) {
+        Many
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_PT_CONDITIONS: Conditions = &[
+    (One, "i = 0..1"),
+    (Many, "e = 0 and i != 0 and i % 1000000 = 0 and v = 0 or e != 0..5"),
+];
+
+fn cardinal_ru(o: &PluralOperands) -> PluralCategory {
+    if o.v == 0 && o.i % 10 == 1 && o.i % 100 != 11 {
+        One
+    } else if o.v == 0 && (2..=4).contains(&(o.i % 10)) && !(12..=14).contains(&(o.i % 100)) {
+        Few
+    } else if o.v == 0 && o.i % 10 == 0 || o.v == 0 && (5..=9).contains(&(o.i % 10)) || o.v == 0 && (11..=14).contains(&(o.i % 100)) {
+        Many
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_RU_CONDITIONS: Conditions = &[
+    (One, "v = 0 and i % 10 = 1 and i % 100 != 11"),
+    (Few, "v = 0 and i % 10 = 2..4 and i % 100 != 12..14"),
+    (Many, "v = 0 and i % 10 = 0 or v = 0 and i % 10 = 5..9 or v = 0 and i % 100 = 11..14"),
+];
+
+fn cardinal_shi(o: &PluralOperands) -> PluralCategory {
+    if o.i == 0 || o.n == 1.0 {
+        One
+    } else if in_range(o.n, 2.0, 10.0) {
+        Few
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_SHI_CONDITIONS: Conditions = &[
+    (One, "i = 0 or n = 1"),
+    (Few, "n = 2..10"),
+];
+
+fn cardinal_si(o: &PluralOperands) -> PluralCategory {
+    if (o.n == 0.0 || o.n == 1.0) || o.i == 0 && o.f == 1 {
+        One
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_SI_CONDITIONS: Conditions = &[
+    (One, "n = 0,1 or i = 0 and f = 1"),
+];
+
+fn cardinal_sl(o: &PluralOperands) -> PluralCategory {
+    if o.v == 0 && o.i % 100 == 1 {
+        One
+    } else if o.v == 0 && o.i % 100 == 2 {
+        Two
+    } else if o.v == 0 && (3..=4).contains(&(o.i % 100)) || o.v != 0 {
+        Few
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_SL_CONDITIONS: Conditions = &[
+    (One, "v = 0 and i % 100 = 1"),
+    (Two, "v = 0 and i % 100 = 2"),
+    (Few, "v = 0 and i % 100 = 3..4 or v != 0"),
+];
+
+fn cardinal_tzm(o: &PluralOperands) -> PluralCategory {
+    if in_range(o.n, 0.0, 1.0) || in_range(o.n, 11.0, 99.0) {
+        One
+    } else {
+        Other
+    }
+}
+
+const CARDINAL_TZM_CONDITIONS: Conditions = &[
+    (One, "n = 0..1 or n = 11..99"),
+];
+
+fn ordinal_af(_: &PluralOperands) -> PluralCategory {
+    Other
+}
+
+const ORDINAL_AF_CONDITIONS: Conditions = &[];
+
+fn ordinal_as(o: &PluralOperands) -> PluralCategory {
+    if o.n == 1.0 || o.n == 5.0 || o.n == 7.0 || o.n == 8.0 || o.n == 9.0 || o.n == 10.0 {
+        One
+    } else if o.n == 2.0 || o.n == 3.0 {
+        Two
+    } else if o.n == 4.0 {
+        Few
+    } else if o.n == 6.0 {
+        Many
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_AS_CONDITIONS: Conditions = &[
+    (One, "n = 1,5,7,8,9,10"),
+    (Two, "n = 2,3"),
+    (Few, "n = 4"),
+    (Many, "n = 6"),
+];
+
+fn ordinal_az(o: &PluralOperands) -> PluralCategory {
+    if (o.i % 10 == 1 || o.i % 10 == 2 || o.i % 10 == 5 || o.i % 10 == 7 || o.i % 10 == 8) || (o.i % 100 == 20 || o.i % 100 == 50 || o.i % 100 == 70 || o.i % 100 == 80) {
+        One
+    } else if (o.i % 10 == 3 || o.i % 10 == 4) || (o.i % 1000 == 100 || o.i % 1000 == 200 || o.i % 1000 == 300 || o.i % 1000 == 400 || o.i % 1000 == 500 || o.i % 1000 == 600 || o.i % 1000 == 700 || o.i % 1000 == 800 || o.i % 1000 == 900) {
+        Few
+    } else if o.i == 0 || o.i % 10 == 6 || (o.i % 100 == 40 || o.i % 100 == 60 || o.i % 100 == 90) {
+        Many
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_AZ_CONDITIONS: Conditions = &[
+    (One, "i % 10 = 1,2,5,7,8 or i % 100 = 20,50,70,80"),
+    (Few, "i % 10 = 3,4 or i % 1000 = 100,200,300,400,500,600,700,800,900"),
+    (Many, "i = 0 or i % 10 = 6 or i % 100 = 40,60,90"),
+];
+
+fn ordinal_bal(o: &PluralOperands) -> PluralCategory {
+    if o.n == 1.0 {
+        One
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_BAL_CONDITIONS: Conditions = &[
+    (One, "n = 1"),
+];
+
+fn ordinal_be(o: &PluralOperands) -> PluralCategory {
+    if (o.n % 10.0 == 2.0 || o.n % 10.0 == 3.0) && o.n % 100.0 != 12.0 && o.n % 100.0 != 13.0 {
+        Few
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_BE_CONDITIONS: Conditions = &[
+    (Few, "n % 10 = 2,3 and n % 100 != 12,13"),
+];
+
+fn ordinal_ca(o: &PluralOperands) -> PluralCategory {
+    if o.n == 1.0 || o.n == 3.0 {
+        One
+    } else if o.n == 2.0 {
+        Two
+    } else if o.n == 4.0 {
+        Few
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_CA_CONDITIONS: Conditions = &[
+    (One, "n = 1,3"),
+    (Two, "n = 2"),
+    (Few, "n = 4"),
+];
+
+fn ordinal_cy(o: &PluralOperands) -> PluralCategory {
+    if o.n == 0.0 || o.n == 7.0 || o.n == 8.0 || o.n == 9.0 {
+        Zero
+    } else if o.n == 1.0 {
+        One
+    } else if o.n == 2.0 {
+        Two
+    } else if o.n == 3.0 || o.n == 4.0 {
+        Few
+    } else if o.n == 5.0 || o.n == 6.0 {
+        Many
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_CY_CONDITIONS: Conditions = &[
+    (Zero, "n = 0,7,8,9"),
+    (One, "n = 1"),
+    (Two, "n = 2"),
+    (Few, "n = 3,4"),
+    (Many, "n = 5,6"),
+];
+
+fn ordinal_en(o: &PluralOperands) -> PluralCategory {
+    if o.n % 10.0 == 1.0 && o.n % 100.0 != 11.0 {
+        One
+    } else if o.n % 10.0 == 2.0 && o.n % 100.0 != 12.0 {
+        Two
+    } else if o.n % 10.0 == 3.0 && o.n % 100.0 != 13.0 {
+        Few
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_EN_CONDITIONS: Conditions = &[
+    (One, "n % 10 = 1 and n % 100 != 11"),
+    (Two, "n % 10 = 2 and n % 100 != 12"),
+    (Few, "n % 10 = 3 and n % 100 != 13"),
+];
+
+fn ordinal_gd(o: &PluralOperands) -> PluralCategory {
+    if o.n == 1.0 || o.n == 11.0 {
+        One
+    } else if o.n == 2.0 || o.n == 12.0 {
+        Two
+    } else if o.n == 3.0 || o.n == 13.0 {
+        Few
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_GD_CONDITIONS: Conditions = &[
+    (One, "n = 1,11"),
+    (Two, "n = 2,12"),
+    (Few, "n = 3,13"),
+];
+
+fn ordinal_gu(o: &PluralOperands) -> PluralCategory {
+    if o.n == 1.0 {
+        One
+    } else if o.n == 2.0 || o.n == 3.0 {
+        Two
+    } else if o.n == 4.0 {
+        Few
+    } else if o.n == 6.0 {
+        Many
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_GU_CONDITIONS: Conditions = &[
+    (One, "n = 1"),
+    (Two, "n = 2,3"),
+    (Few, "n = 4"),
+    (Many, "n = 6"),
+];
+
+fn ordinal_hu(o: &PluralOperands) -> PluralCategory {
+    if o.n == 1.0 || o.n == 5.0 {
+        One
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_HU_CONDITIONS: Conditions = &[
+    (One, "n = 1,5"),
+];
+
+fn ordinal_it(o: &PluralOperands) -> PluralCategory {
+    if o.n == 11.0 || o.n == 8.0 || o.n == 80.0 || o.n == 800.0 {
+        Many
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_IT_CONDITIONS: Conditions = &[
+    (Many, "n = 11,8,80,800"),
+];
+
+fn ordinal_ka(o: &PluralOperands) -> PluralCategory {
+    if o.i == 1 {
+        One
+    } else if o.i == 0 || ((2..=20).contains(&(o.i % 100)) || o.i % 100 == 40 || o.i % 100 == 60 || o.i % 100 == 80) {
+        Many
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_KA_CONDITIONS: Conditions = &[
+    (One, "i = 1"),
+    (Many, "i = 0 or i % 100 = 2..20,40,60,80"),
+];
+
+fn ordinal_kk(o: &PluralOperands) -> PluralCategory {
+    if o.n % 10.0 == 6.0 || o.n % 10.0 == 9.0 || o.n % 10.0 == 0.0 && o.n != 0.0 {
+        Many
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_KK_CONDITIONS: Conditions = &[
+    (Many, "n % 10 = 6 or n % 10 = 9 or n % 10 = 0 and n != 0"),
+];
+
+fn ordinal_kw(o: &PluralOperands) -> PluralCategory {
+    if in_range(o.n, 1.0, 4.0) || (in_range(o.n % 100.0, 1.0, 4.0) || in_range(o.n % 100.0, 21.0, 24.0) || in_range(o.n % 100.0, 41.0, 44.0) || in_range(o.n % 100.0, 61.0, 64.0) || in_range(o.n % 100.0, 81.0, 84.0)) {
+        One
+    } else if o.n == 5.0 || o.n % 100.0 == 5.0 {
+        Many
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_KW_CONDITIONS: Conditions = &[
+    (One, "n = 1..4 or n % 100 = 1..4,21..24,41..44,61..64,81..84"),
+    (Many, "n = 5 or n % 100 = 5"),
+];
+
+fn ordinal_lij(o: &PluralOperands) -> PluralCategory {
+    if o.n == 11.0 || o.n == 8.0 || in_range(o.n, 80.0, 89.0) || in_range(o.n, 800.0, 899.0) {
+        Many
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_LIJ_CONDITIONS: Conditions = &[
+    (Many, "n = 11,8,80..89,800..899"),
+];
+
+fn ordinal_mk(o: &PluralOperands) -> PluralCategory {
+    if o.i % 10 == 1 && o.i % 100 != 11 {
+        One
+    } else if o.i % 10 == 2 && o.i % 100 != 12 {
+        Two
+    } else if (o.i % 10 == 7 || o.i % 10 == 8) && o.i % 100 != 17 && o.i % 100 != 18 {
+        Many
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_MK_CONDITIONS: Conditions = &[
+    (One, "i % 10 = 1 and i % 100 != 11"),
+    (Two, "i % 10 = 2 and i % 100 != 12"),
+    (Many, "i % 10 = 7,8 and i % 100 != 17,18"),
+];
+
+fn ordinal_mr(o: &PluralOperands) -> PluralCategory {
+    if o.n == 1.0 {
+        One
+    } else if o.n == 2.0 || o.n == 3.0 {
+        Two
+    } else if o.n == 4.0 {
+        Few
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_MR_CONDITIONS: Conditions = &[
+    (One, "n = 1"),
+    (Two, "n = 2,3"),
+    (Few, "n = 4"),
+];
+
+fn ordinal_ne(o: &PluralOperands) -> PluralCategory {
+    if in_range(o.n, 1.0, 4.0) {
+        One
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_NE_CONDITIONS: Conditions = &[
+    (One, "n = 1..4"),
+];
+
+fn ordinal_or(o: &PluralOperands) -> PluralCategory {
+    if o.n == 1.0 || o.n == 5.0 || in_range(o.n, 7.0, 9.0) {
+        One
+    } else if o.n == 2.0 || o.n == 3.0 {
+        Two
+    } else if o.n == 4.0 {
+        Few
+    } else if o.n == 6.0 {
+        Many
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_OR_CONDITIONS: Conditions = &[
+    (One, "n = 1,5,7..9"),
+    (Two, "n = 2,3"),
+    (Few, "n = 4"),
+    (Many, "n = 6"),
+];
+
+fn ordinal_sq(o: &PluralOperands) -> PluralCategory {
+    if o.n == 1.0 {
+        One
+    } else if o.n % 10.0 == 4.0 && o.n % 100.0 != 14.0 {
+        Many
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_SQ_CONDITIONS: Conditions = &[
+    (One, "n = 1"),
+    (Many, "n % 10 = 4 and n % 100 != 14"),
+];
+
+fn ordinal_sv(o: &PluralOperands) -> PluralCategory {
+    if (o.n % 10.0 == 1.0 || o.n % 10.0 == 2.0) && o.n % 100.0 != 11.0 && o.n % 100.0 != 12.0 {
+        One
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_SV_CONDITIONS: Conditions = &[
+    (One, "n % 10 = 1,2 and n % 100 != 11,12"),
+];
+
+fn ordinal_tk(o: &PluralOperands) -> PluralCategory {
+    if (o.n % 10.0 == 6.0 || o.n % 10.0 == 9.0) || o.n == 10.0 {
+        Few
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_TK_CONDITIONS: Conditions = &[
+    (Few, "n % 10 = 6,9 or n = 10"),
+];
+
+fn ordinal_uk(o: &PluralOperands) -> PluralCategory {
+    if o.n % 10.0 == 3.0 && o.n % 100.0 != 13.0 {
+        Few
+    } else {
+        Other
+    }
+}
+
+const ORDINAL_UK_CONDITIONS: Conditions = &[
+    (Few, "n % 10 = 3 and n % 100 != 13"),
+];
+
+/// The CLDR samples for each distinct cardinal rule.
+#[cfg(test)]
+pub(super) const CARDINAL_SAMPLES: &Samples = &[
+    ("af",
+     &[(One,
+        "n = 1",
+        &["1", "1.0", "1.00", "1.000", "1.0000"]),
+       (Other,
+        "",
+        &["0", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15",
+          "16", "100", "1000", "10000", "100000", "1000000", "0.0", "0.1", "0.2", "0.3",
+          "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.1", "1.2", "1.3", "1.4", "1.5",
+          "1.6", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+    ("ak",
+     &[(One,
+        "n = 0..1",
+        &["0", "1", "0.0", "1.0", "0.00", "1.00", "0.000", "1.000", "0.0000", "1.0000"]),
+       (Other,
+        "",
+        &["2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+          "17", "100", "1000", "10000", "100000", "1000000", "0.1", "0.2", "0.3", "0.4",
+          "0.5", "0.6", "0.7", "0.8", "0.9", "1.1", "1.2", "1.3", "1.4", "1.5", "1.6",
+          "1.7", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+    ("am",
+     &[(One,
+        "i = 0 or n = 1",
+        &["0", "1", "0.0", "0.1", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9",
+          "1.0", "0.00", "0.01", "0.02", "0.03", "0.04"]),
+       (Other,
+        "",
+        &["2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+          "17", "100", "1000", "10000", "100000", "1000000", "1.1", "1.2", "1.3", "1.4",
+          "1.5", "1.6", "1.7", "1.8", "1.9", "2.0", "2.1", "2.2", "2.3", "2.4", "2.5",
+          "2.6", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+    ("ar",
+     &[(Zero,
+        "n = 0",
+        &["0", "0.0", "0.00", "0.000", "0.0000"]),
+       (One,
+        "n = 1",
+        &["1", "1.0", "1.00", "1.000", "1.0000"]),
+       (Two,
+        "n = 2",
+        &["2", "2.0", "2.00", "2.000", "2.0000"]),
+       (Few,
+        "n % 100 = 3..10",
+        &["3", "4", "5", "6", "7", "8", "9", "10", "103", "104", "105", "106", "107",
+          "108", "109", "110", "1003", "3.0", "4.0", "5.0", "6.0", "7.0", "8.0", "9.0",
+          "10.0", "103.0", "1003.0"]),
+       (Many,
+        "n % 100 = 11..99",
+        &["11", "12", "13", "14", "15", "16", "17", "18", "19", "20", "21", "22", "23",
+          "24", "25", "26", "111", "1011", "11.0", "12.0", "13.0", "14.0", "15.0", "16.0",
+          "17.0", "18.0", "111.0", "1011.0"]),
+       (Other,
+        "",
+        &["100", "101", "102", "200", "201", "202", "300", "301", "302", "400", "401",
+          "402", "500", "501", "502", "600", "1000", "10000", "100000", "1000000", "0.1",
+          "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.1", "1.2", "1.3",
+          "1.4", "1.5", "1.6", "1.7", "10.1", "100.0", "1000.0", "10000.0", "100000.0",
+          "1000000.0"])]),
+    ("ast",
+     &[(One,
+        "i = 1 and v = 0",
+        &["1"]),
+       (Other,
+        "",
+        &["0", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15",
+          "16", "100", "1000", "10000", "100000", "1000000", "0.0", "0.1", "0.2", "0.3",
+          "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.0", "1.1", "1.2", "1.3", "1.4",
+          "1.5", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+    ("be",
+     &[(One,
+        "n % 10 = 1 and n % 100 != 11",
+        &["1", "21", "31", "41", "51", "61", "71", "81", "101", "1001", "1.0", "21.0",
+          "31.0", "41.0", "51.0", "61.0", "71.0", "81.0", "101.0", "1001.0"]),
+       (Few,
+        "n % 10 = 2..4 and n % 100 != 12..14",
+        &["2", "3", "4", "22", "23", "24", "32", "33", "34", "42", "43", "44", "52", "53",
+          "54", "62", "102", "1002", "2.0", "3.0", "4.0", "22.0", "23.0", "24.0", "32.0",
+          "33.0", "102.0", "1002.0"]),
+       (Many,
+        "n % 10 = 0 or n % 10 = 5..9 or n % 100 = 11..14",
+        &["0", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16", "17",
+          "18", "19", "100", "1000", "10000", "100000", "1000000", "0.0", "5.0", "6.0",
+          "7.0", "8.0", "9.0", "10.0", "11.0", "100.0", "1000.0", "10000.0", "100000.0",
+          "1000000.0"]),
+       (Other,
+        "",
+        &["0.1", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.1", "1.2",
+          "1.3", "1.4", "1.5", "1.6", "1.7", "10.1", "100.1", "1000.1"])]),
+    ("bm",
+     &[(Other,
+        "",
+        &["0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14",
+          "15", "100", "1000", "10000", "100000", "1000000", "0.0", "0.1", "0.2", "0.3",
+          "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.0", "1.1", "1.2", "1.3", "1.4",
+          "1.5", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+    ("br",
+     &[(One,
+        "n % 10 = 1 and n % 100 != 11,71,91",
+        &["1", "21", "31", "41", "51", "61", "81", "101", "1001", "1.0", "21.0", "31.0",
+          "41.0", "51.0", "61.0", "81.0", "101.0", "1001.0"]),
+       (Two,
+        "n % 10 = 2 and n % 100 != 12,72,92",
+        &["2", "22", "32", "42", "52", "62", "82", "102", "1002", "2.0", "22.0", "32.0",
+          "42.0", "52.0", "62.0", "82.0", "102.0", "1002.0"]),
+       (Few,
+        "n % 10 = 3..4,9 and n % 100 != 10..19,70..79,90..99",
+        &["3", "4", "9", "23", "24", "29", "33", "34", "39", "43", "44", "49", "103",
+          "1003", "3.0", "4.0", "9.0", "23.0", "24.0", "29.0", "33.0", "34.0", "103.0",
+          "1003.0"]),
+       (Many,
+        "n != 0 and n % 1000000 = 0",
+        &["1000000", "1000000.0", "1000000.00", "1000000.000", "1000000.0000"]),
+       (Other,
+        "",
+        &["0", "5", "6", "7", "8", "10", "11", "12", "13", "14", "15", "16", "17", "18",
+          "19", "20", "100", "1000", "10000", "100000", "0.0", "0.1", "0.2", "0.3", "0.4",
+          "0.5", "0.6", "0.7", "0.8", "0.9", "1.1", "1.2", "1.3", "1.4", "1.5", "1.6",
+          "10.0", "100.0", "1000.0", "10000.0", "100000.0"])]),
+    ("bs",
+     &[(One,
+        "v = 0 and i % 10 = 1 and i % 100 != 11 or f % 10 = 1 and f % 100 != 11",
+        &["1", "21", "31", "41", "51", "61", "71", "81", "101", "1001", "0.1", "1.1",
+          "2.1", "3.1", "4.1", "5.1", "6.1", "7.1", "10.1", "100.1", "1000.1"]),
+       (Few,
+        "v = 0 and i % 10 = 2..4 and i % 100 != 12..14 or f % 10 = 2..4 and f % 100 != 12..14",
+        &["2", "3", "4", "22", "23", "24", "32", "33", "34", "42", "43", "44", "52", "53",
+          "54", "62", "102", "1002", "0.2", "0.3", "0.4", "1.2", "1.3", "1.4", "2.2",
+          "2.3", "2.4", "3.2", "3.3", "3.4", "4.2", "4.3", "4.4", "5.2", "10.2", "100.2",
+          "1000.2"]),
+       (Other,
+        "",
+        &["0", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16", "17",
+          "18", "19", "100", "1000", "10000", "100000", "1000000", "0.0", "0.5", "0.6",
+          "0.7", "0.8", "0.9", "1.0", "1.5", "1.6", "1.7", "1.8", "1.9", "2.0", "2.5",
+          "2.6", "2.7", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+    ("ca",
+     &[(One,
+        "i = 1 and v = 0",
+        &["1"]),
+       (Many,
+        "e = 0 and i != 0 and i % 1000000 = 0 and v = 0 or e != 0..5",
+        &["1000000", "1c6", "2c6", "3c6", "4c6", "5c6", "6c6", "1.0000001c6", "1.1c6",
+          "2.0000001c6", "2.1c6", "3.0000001c6", "3.1c6"]),
+       (Other,
+        "",
+        &["0", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15",
+          "16", "100", "1000", "10000", "100000", "1c3", "2c3", "3c3", "4c3", "5c3", "6c3",
+          "0.0", "0.1", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.0",
+          "1.1", "1.2", "1.3", "1.4", "1.5", "10.0", "100.0", "1000.0", "10000.0",
+          "100000.0", "1000000.0", "1.0001c3", "1.1c3", "2.0001c3", "2.1c3", "3.0001c3",
+          "3.1c3"])]),
+    ("ceb",
+     &[(One,
+        "v = 0 and i = 1,2,3 or v = 0 and i % 10 != 4,6,9 or v != 0 and f % 10 != 4,6,9",
+        &["0", "1", "2", "3", "5", "7", "8", "10", "11", "12", "13", "15", "17", "18",
+          "20", "21", "100", "1000", "10000", "100000", "1000000", "0.0", "0.1", "0.2",
+          "0.3", "0.5", "0.7", "0.8", "1.0", "1.1", "1.2", "1.3", "1.5", "1.7", "1.8",
+          "2.0", "2.1", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"]),
+       (Other,
+        "",
+        &["4", "6", "9", "14", "16", "19", "24", "26", "104", "1004", "0.4", "0.6", "0.9",
+          "1.4", "1.6", "1.9", "2.4", "2.6", "10.4", "100.4", "1000.4"])]),
+    ("cs",
+     &[(One,
+        "i = 1 and v = 0",
+        &["1"]),
+       (Few,
+        "i = 2..4 and v = 0",
+        &["2", "3", "4"]),
+       (Many,
+        "v != 0",
+        &["0.0", "0.1", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.0",
+          "1.1", "1.2", "1.3", "1.4", "1.5", "10.0", "100.0", "1000.0", "10000.0",
+          "100000.0", "1000000.0"]),
+       (Other,
+        "",
+        &["0", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16", "17",
+          "18", "19", "100", "1000", "10000", "100000", "1000000"])]),
+    ("cy",
+     &[(Zero,
+        "n = 0",
+        &["0", "0.0", "0.00", "0.000", "0.0000"]),
+       (One,
+        "n = 1",
+        &["1", "1.0", "1.00", "1.000", "1.0000"]),
+       (Two,
+        "n = 2",
+        &["2", "2.0", "2.00", "2.000", "2.0000"]),
+       (Few,
+        "n = 3",
+        &["3", "3.0", "3.00", "3.000", "3.0000"]),
+       (Many,
+        "n = 6",
+        &["6", "6.0", "6.00", "6.000", "6.0000"]),
+       (Other,
+        "",
+        &["4", "5", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16", "17", "18",
+          "19", "20", "100", "1000", "10000", "100000", "1000000", "0.1", "0.2", "0.3",
+          "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.1", "1.2", "1.3", "1.4", "1.5",
+          "1.6", "1.7", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+    ("da",
+     &[(One,
+        "n = 1 or t != 0 and i = 0,1",
+        &["1", "0.1", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.0", "1.1",
+          "1.2", "1.3", "1.4", "1.5", "1.6"]),
+       (Other,
+        "",
+        &["0", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15",
+          "16", "100", "1000", "10000", "100000", "1000000", "0.0", "2.0", "2.1", "2.2",
+          "2.3", "2.4", "2.5", "2.6", "2.7", "2.8", "2.9", "3.0", "3.1", "3.2", "3.3",
+          "3.4", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+    ("dsb",
+     &[(One,
+        "v = 0 and i % 100 = 1 or f % 100 = 1",
+        &["1", "101", "201", "301", "401", "501", "601", "701", "1001", "0.1", "1.1",
+          "2.1", "3.1", "4.1", "5.1", "6.1", "7.1", "10.1", "100.1", "1000.1"]),
+       (Two,
+        "v = 0 and i % 100 = 2 or f % 100 = 2",
+        &["2", "102", "202", "302", "402", "502", "602", "702", "1002", "0.2", "1.2",
+          "2.2", "3.2", "4.2", "5.2", "6.2", "7.2", "10.2", "100.2", "1000.2"]),
+       (Few,
+        "v = 0 and i % 100 = 3..4 or f % 100 = 3..4",
+        &["3", "4", "103", "104", "203", "204", "303", "304", "403", "404", "503", "504",
+          "603", "604", "703", "704", "1003", "0.3", "0.4", "1.3", "1.4", "2.3", "2.4",
+          "3.3", "3.4", "4.3", "4.4", "5.3", "5.4", "6.3", "6.4", "7.3", "7.4", "10.3",
+          "100.3", "1000.3"]),
+       (Other,
+        "",
+        &["0", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16", "17",
+          "18", "19", "100", "1000", "10000", "100000", "1000000", "0.0", "0.5", "0.6",
+          "0.7", "0.8", "0.9", "1.0", "1.5", "1.6", "1.7", "1.8", "1.9", "2.0", "2.5",
+          "2.6", "2.7", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+    ("es",
+     &[(One,
+        "n = 1",
+        &["1", "1.0", "1.00", "1.000", "1.0000"]),
+       (Many,
+        "e = 0 and i != 0 and i % 1000000 = 0 and v = 0 or e != 0..5",
+        &["1000000", "1c6", "2c6", "3c6", "4c6", "5c6", "6c6", "1.0000001c6", "1.1c6",
+          "2.0000001c6", "2.1c6", "3.0000001c6", "3.1c6"]),
+       (Other,
+        "",
+        &["0", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15",
+          "16", "100", "1000", "10000", "100000", "1c3", "2c3", "3c3", "4c3", "5c3", "6c3",
+          "0.0", "0.1", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.1",
+          "1.2", "1.3", "1.4", "1.5", "1.6", "10.0", "100.0", "1000.0", "10000.0",
+          "100000.0", "1000000.0", "1.0001c3", "1.1c3", "2.0001c3", "2.1c3", "3.0001c3",
+          "3.1c3"])]),
+    ("ff",
+     &[(One,
+        "i = 0,1",
+        &["0", "1", "0.0", "0.1", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9",
+          "1.0", "1.1", "1.2", "1.3", "1.4", "1.5"]),
+       (Other,
+        "",
+        &["2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+          "17", "100", "1000", "10000", "100000", "1000000", "2.0", "2.1", "2.2", "2.3",
+          "2.4", "2.5", "2.6", "2.7", "2.8", "2.9", "3.0", "3.1", "3.2", "3.3", "3.4",
+          "3.5", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+    ("fr",
+     &[(One,
+        "i = 0,1",
+        &["0", "1", "0.0", "0.1", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9",
+          "1.0", "1.1", "1.2", "1.3", "1.4", "1.5"]),
+       (Many,
+        "e = 0 and i != 0 and i % 1000000 = 0 and v = 0 or e != 0..5",
+        &["1000000", "1c6", "2c6", "3c6", "4c6", "5c6", "6c6", "1.0000001c6", "1.1c6",
+          "2.0000001c6", "2.1c6", "3.0000001c6", "3.1c6"]),
+       (Other,
+        "",
+        &["2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+          "17", "100", "1000", "10000", "100000", "1c3", "2c3", "3c3", "4c3", "5c3", "6c3",
+          "2.0", "2.1", "2.2", "2.3", "2.4", "2.5", "2.6", "2.7", "2.8", "2.9", "3.0",
+          "3.1", "3.2", "3.3", "3.4", "3.5", "10.0", "100.0", "1000.0", "10000.0",
+          "100000.0", "1000000.0", "1.0001c3", "1.1c3", "2.0001c3", "2.1c3", "3.0001c3",
+          "3.1c3"])]),
+    ("ga",
+     &[(One,
+        "n = 1",
+        &["1", "1.0", "1.00", "1.000", "1.0000"]),
+       (Two,
+        "n = 2",
+        &["2", "2.0", "2.00", "2.000", "2.0000"]),
+       (Few,
+        "n = 3..6",
+        &["3", "4", "5", "6", "3.0", "4.0", "5.0", "6.0", "3.00", "4.00", "5.00", "6.00",
+          "3.000", "4.000", "5.000", "6.000", "3.0000", "4.0000", "5.0000", "6.0000"]),
+       (Many,
+        "n = 7..10",
+        &["7", "8", "9", "10", "7.0", "8.0", "9.0", "10.0", "7.00", "8.00", "9.00",
+          "10.00", "7.000", "8.000", "9.000", "10.000", "7.0000", "8.0000", "9.0000",
+          "10.0000"]),
+       (Other,
+        "",
+        &["0", "11", "12", "13", "14", "15", "16", "17", "18", "19", "20", "21", "22",
+          "23", "24", "25", "100", "1000", "10000", "100000", "1000000", "0.0", "0.1",
+          "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.1", "1.2", "1.3",
+          "1.4", "1.5", "1.6", "10.1", "100.0", "1000.0", "10000.0", "100000.0",
+          "1000000.0"])]),
+    ("gd",
+     &[(One,
+        "n = 1,11",
+        &["1", "11", "1.0", "11.0", "1.00", "11.00", "1.000", "11.000", "1.0000"]),
+       (Two,
+        "n = 2,12",
+        &["2", "12", "2.0", "12.0", "2.00", "12.00", "2.000", "12.000", "2.0000"]),
+       (Few,
+        "n = 3..10,13..19",
+        &["3", "4", "5", "6", "7", "8", "9", "10", "13", "14", "15", "16", "17", "18",
+          "19", "3.0", "4.0", "5.0", "6.0", "7.0", "8.0", "9.0", "10.0", "13.0", "14.0",
+          "15.0", "16.0", "17.0", "18.0", "19.0", "3.00"]),
+       (Other,
+        "",
+        &["0", "20", "21", "22", "23", "24", "25", "26", "27", "28", "29", "30", "31",
+          "32", "33", "34", "100", "1000", "10000", "100000", "1000000", "0.0", "0.1",
+          "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.1", "1.2", "1.3",
+          "1.4", "1.5", "1.6", "10.1", "100.0", "1000.0", "10000.0", "100000.0",
+          "1000000.0"])]),
+    ("gv",
+     &[(One,
+        "v = 0 and i % 10 = 1",
+        &["1", "11", "21", "31", "41", "51", "61", "71", "101", "1001"]),
+       (Two,
+        "v = 0 and i % 10 = 2",
+        &["2", "12", "22", "32", "42", "52", "62", "72", "102", "1002"]),
+       (Few,
+        "v = 0 and i % 100 = 0,20,40,60,80",
+        &["0", "20", "40", "60", "80", "100", "120", "140", "1000", "10000", "100000",
+          "1000000"]),
+       (Many,
+        "v != 0",
+        &["0.0", "0.1", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.0",
+          "1.1", "1.2", "1.3", "1.4", "1.5", "10.0", "100.0", "1000.0", "10000.0",
+          "100000.0", "1000000.0"]),
+       (Other,
+        "",
+        &["3", "4", "5", "6", "7", "8", "9", "10", "13", "14", "15", "16", "17", "18",
+          "19", "23", "103", "1003"])]),
+    ("he",
+     &[(One,
+        "i = 1 and v = 0 or i = 0 and v != 0",
+        &["1", "0.0", "0.1", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9",
+          "0.00", "0.01", "0.02", "0.03", "0.04", "0.05"]),
+       (Two,
+        "i = 2 and v = 0",
+        &["2"]),
+       (Other,
+        "",
+        &["0", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+          "17", "100", "1000", "10000", "100000", "1000000", "1.0", "1.1", "1.2", "1.3",
+          "1.4", "1.5", "1.6", "1.7", "1.8", "1.9", "2.0", "2.1", "2.2", "2.3", "2.4",
+          "2.5", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+    ("is",
+     &[(One,
+        "t = 0 and i % 10 = 1 and i % 100 != 11 or t % 10 = 1 and t % 100 != 11",
+        &["1", "21", "31", "41", "51", "61", "71", "81", "101", "1001", "0.1", "1.0",
+          "1.1", "2.1", "3.1", "4.1", "5.1", "6.1", "7.1", "10.1", "100.1", "1000.1"]),
+       (Other,
+        "",
+        &["0", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15",
+          "16", "100", "1000", "10000", "100000", "1000000", "0.0", "0.2", "0.3", "0.4",
+          "0.5", "0.6", "0.7", "0.8", "0.9", "1.2", "1.3", "1.4", "1.5", "1.6", "1.7",
+          "1.8", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+    ("iu",
+     &[(One,
+        "n = 1",
+        &["1", "1.0", "1.00", "1.000", "1.0000"]),
+       (Two,
+        "n = 2",
+        &["2", "2.0", "2.00", "2.000", "2.0000"]),
+       (Other,
+        "",
+        &["0", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+          "17", "100", "1000", "10000", "100000", "1000000", "0.0", "0.1", "0.2", "0.3",
+          "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.1", "1.2", "1.3", "1.4", "1.5",
+          "1.6", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+    ("ksh",
+     &[(Zero,
+        "n = 0",
+        &["0", "0.0", "0.00", "0.000", "0.0000"]),
+       (One,
+        "n = 1",
+        &["1", "1.0", "1.00", "1.000", "1.0000"]),
+       (Other,
+        "",
+        &["2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+          "17", "100", "1000", "10000", "100000", "1000000", "0.1", "0.2", "0.3", "0.4",
+          "0.5", "0.6", "0.7", "0.8", "0.9", "1.1", "1.2", "1.3", "1.4", "1.5", "1.6",
+          "1.7", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+    ("kw",
+     &[(Zero,
+        "n = 0",
+        &["0", "0.0", "0.00", "0.000", "0.0000"]),
+       (One,
+        "n = 1",
+        &["1", "1.0", "1.00", "1.000", "1.0000"]),
+       (Two,
+        "n % 100 = 2,22,42,62,82 or n % 1000 = 0 and n % 100000 = 1000..20000,40000,60000,80000 or n != 0 and n % 1000000 = 100000",
+        &["2", "22", "42", "62", "82", "102", "122", "142", "1000", "10000", "100000",
+          "2.0", "22.0", "42.0", "62.0", "82.0", "102.0", "122.0", "142.0", "1000.0",
+          "10000.0", "100000.0"]),
+       (Few,
+        "n % 100 = 3,23,43,63,83",
+        &["3", "23", "43", "63", "83", "103", "123", "143", "1003", "3.0", "23.0", "43.0",
+          "63.0", "83.0", "103.0", "123.0", "143.0", "1003.0"]),
+       (Many,
+        "n != 1 and n % 100 = 1,21,41,61,81",
+        &["21", "41", "61", "81", "101", "121", "141", "161", "1001", "21.0", "41.0",
+          "61.0", "81.0", "101.0", "121.0", "141.0", "161.0", "1001.0"]),
+       (Other,
+        "",
+        &["4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16", "17",
+          "18", "19", "100", "1004", "1000000", "0.1", "0.2", "0.3", "0.4", "0.5", "0.6",
+          "0.7", "0.8", "0.9", "1.1", "1.2", "1.3", "1.4", "1.5", "1.6", "1.7", "10.0",
+          "100.0", "1000.1", "1000000.0"])]),
+    ("lag",
+     &[(Zero,
+        "n = 0",
+        &["0", "0.0", "0.00", "0.000", "0.0000"]),
+       (One,
+        "i = 0,1 and n != 0",
+        &["1", "0.1", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.0", "1.1",
+          "1.2", "1.3", "1.4", "1.5", "1.6"]),
+       (Other,
+        "",
+        &["2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+          "17", "100", "1000", "10000", "100000", "1000000", "2.0", "2.1", "2.2", "2.3",
+          "2.4", "2.5", "2.6", "2.7", "2.8", "2.9", "3.0", "3.1", "3.2", "3.3", "3.4",
+          "3.5", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+    ("lt",
+     &[(One,
+        "n % 10 = 1 and n % 100 != 11..19",
+        &["1", "21", "31", "41", "51", "61", "71", "81", "101", "1001", "1.0", "21.0",
+          "31.0", "41.0", "51.0", "61.0", "71.0", "81.0", "101.0", "1001.0"]),
+       (Few,
+        "n % 10 = 2..9 and n % 100 != 11..19",
+        &["2", "3", "4", "5", "6", "7", "8", "9", "22", "23", "24", "25", "26", "27", "28",
+          "29", "102", "1002", "2.0", "3.0", "4.0", "5.0", "6.0", "7.0", "8.0", "9.0",
+          "22.0", "102.0", "1002.0"]),
+       (Many,
+        "f != 0",
+        &["0.1", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.1", "1.2",
+          "1.3", "1.4", "1.5", "1.6", "1.7", "10.1", "100.1", "1000.1"]),
+       (Other,
+        "",
+        &["0", "10", "11", "12", "13", "14", "15", "16", "17", "18", "19", "20", "30",
+          "40", "50", "60", "100", "1000", "10000", "100000", "1000000", "0.0", "10.0",
+          "11.0", "12.0", "13.0", "14.0", "15.0", "16.0", "100.0", "1000.0", "10000.0",
+          "100000.0", "1000000.0"])]),
+    ("lv",
+     &[(Zero,
+        "n % 10 = 0 or n % 100 = 11..19 or v = 2 and f % 100 = 11..19",
+        &["0", "10", "11", "12", "13", "14", "15", "16", "17", "18", "19", "20", "30",
+          "40", "50", "60", "100", "1000", "10000", "100000", "1000000", "0.0", "10.0",
+          "11.0", "12.0", "13.0", "14.0", "15.0", "16.0", "100.0", "1000.0", "10000.0",
+          "100000.0", "1000000.0"]),
+       (One,
+        "n % 10 = 1 and n % 100 != 11 or v = 2 and f % 10 = 1 and f % 100 != 11 or v != 2 and f % 10 = 1",
+        &["1", "21", "31", "41", "51", "61", "71", "81", "101", "1001", "0.1", "1.0",
+          "1.1", "2.1", "3.1", "4.1", "5.1", "6.1", "7.1", "10.1", "100.1", "1000.1"]),
+       (Other,
+        "",
+        &["2", "3", "4", "5", "6", "7", "8", "9", "22", "23", "24", "25", "26", "27", "28",
+          "29", "102", "1002", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9",
+          "1.2", "1.3", "1.4", "1.5", "1.6", "1.7", "1.8", "1.9", "10.2", "100.2",
+          "1000.2"])]),
+    ("mk",
+     &[(One,
+        "v = 0 and i % 10 = 1 and i % 100 != 11 or f % 10 = 1 and f % 100 != 11",
+        &["1", "21", "31", "41", "51", "61", "71", "81", "101", "1001", "0.1", "1.1",
+          "2.1", "3.1", "4.1", "5.1", "6.1", "7.1", "10.1", "100.1", "1000.1"]),
+       (Other,
+        "",
+        &["0", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15",
+          "16", "100", "1000", "10000", "100000", "1000000", "0.0", "0.2", "0.3", "0.4",
+          "0.5", "0.6", "0.7", "0.8", "0.9", "1.0", "1.2", "1.3", "1.4", "1.5", "1.6",
+          "1.7", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+    ("mo",
+     &[(One,
+        "i = 1 and v = 0",
+        &["1"]),
+       (Few,
+        "v != 0 or n = 0 or n != 1 and n % 100 = 1..19",
+        &["0", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15",
+          "16", "101", "1001", "0.0", "0.1", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7",
+          "0.8", "0.9", "1.0", "1.1", "1.2", "1.3", "1.4", "1.5", "10.0", "100.0",
+          "1000.0", "10000.0", "100000.0", "1000000.0"]),
+       (Other,
+        "",
+        &["20", "21", "22", "23", "24", "25", "26", "27", "28", "29", "30", "31", "32",
+          "33", "34", "35", "100", "1000", "10000", "100000", "1000000"])]),
+    ("mt",
+     &[(One,
+        "n = 1",
+        &["1", "1.0", "1.00", "1.000", "1.0000"]),
+       (Two,
+        "n = 2",
+        &["2", "2.0", "2.00", "2.000", "2.0000"]),
+       (Few,
+        "n = 0 or n % 100 = 3..10",
+        &["0", "3", "4", "5", "6", "7", "8", "9", "10", "103", "104", "105", "106", "107",
+          "108", "109", "1003", "0.0", "3.0", "4.0", "5.0", "6.0", "7.0", "8.0", "9.0",
+          "10.0", "103.0", "1003.0"]),
+       (Many,
+        "n % 100 = 11..19",
+        &["11", "12", "13", "14", "15", "16", "17", "18", "19", "111", "112", "113", "114",
+          "115", "116", "117", "1011", "11.0", "12.0", "13.0", "14.0", "15.0", "16.0",
+          "17.0", "18.0", "111.0", "1011.0"]),
+       (Other,
+        "",
+        &["20", "21", "22", "23", "24", "25", "26", "27", "28", "29", "30", "31", "32",
+          "33", "34", "35", "100", "1000", "10000", "100000", "1000000", "0.1", "0.2",
+          "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.1", "1.2", "1.3", "1.4",
+          "1.5", "1.6", "1.7", "10.1", "100.0", "1000.0", "10000.0", "100000.0",
+          "1000000.0"])]),
+    ("pl",
+     &[(One,
+        "i = 1 and v = 0",
+        &["1"]),
+       (Few,
+        "v = 0 and i % 10 = 2..4 and i % 100 != 12..14",
+        &["2", "3", "4", "22", "23", "24", "32", "33", "34", "42", "43", "44", "52", "53",
+          "54", "62", "102", "1002"]),
+       (Many,
+        "v = 0 and i != 1 and i % 10 = 0..1 or v = 0 and i % 10 = 5..9 or v = 0 and i % 100 = 12..14",
+        &["0", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16", "17",
+          "18", "19", "100", "1000", "10000", "100000", "1000000"]),
+       (Other,
+        "",
+        &["0.0", "0.1", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.0",
+          "1.1", "1.2", "1.3", "1.4", "1.5", "10.0", "100.0", "1000.0", "10000.0",
+          "100000.0", "1000000.0"])]),
+    ("pt",
+     &[(One,
+        "i = 0..1",
+        &["0", "1", "0.0", "0.1", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9",
+          "1.0", "1.1", "1.2", "1.3", "1.4", "1.5"]),
+       (Many,
+        "e = 0 and i != 0 and i % 1000000 = 0 and v = 0 or e != 0..5",
+        &["1000000", "1c6", "2c6", "3c6", "4c6", "5c6", "6c6", "1.0000001c6", "1.1c6",
+          "2.0000001c6", "2.1c6", "3.0000001c6", "3.1c6"]),
+       (Other,
+        "",
+        &["2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+          "17", "100", "1000", "10000", "100000", "1c3", "2c3", "3c3", "4c3", "5c3", "6c3",
+          "2.0", "2.1", "2.2", "2.3", "2.4", "2.5", "2.6", "2.7", "2.8", "2.9", "3.0",
+          "3.1", "3.2", "3.3", "3.4", "3.5", "10.0", "100.0", "1000.0", "10000.0",
+          "100000.0", "1000000.0", "1.0001c3", "1.1c3", "2.0001c3", "2.1c3", "3.0001c3",
+          "3.1c3"])]),
+    ("ru",
+     &[(One,
+        "v = 0 and i % 10 = 1 and i % 100 != 11",
+        &["1", "21", "31", "41", "51", "61", "71", "81", "101", "1001"]),
+       (Few,
+        "v = 0 and i % 10 = 2..4 and i % 100 != 12..14",
+        &["2", "3", "4", "22", "23", "24", "32", "33", "34", "42", "43", "44", "52", "53",
+          "54", "62", "102", "1002"]),
+       (Many,
+        "v = 0 and i % 10 = 0 or v = 0 and i % 10 = 5..9 or v = 0 and i % 100 = 11..14",
+        &["0", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16", "17",
+          "18", "19", "100", "1000", "10000", "100000", "1000000"]),
+       (Other,
+        "",
+        &["0.0", "0.1", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9", "1.0",
+          "1.1", "1.2", "1.3", "1.4", "1.5", "10.0", "100.0", "1000.0", "10000.0",
+          "100000.0", "1000000.0"])]),
+    ("shi",
+     &[(One,
+        "i = 0 or n = 1",
+        &["0", "1", "0.0", "0.1", "0.2", "0.3", "0.4", "0.5", "0.6", "0.7", "0.8", "0.9",
+          "1.0", "0.00", "0.01", "0.02", "0.03", "0.04"]),
+       (Few,
+        "n = 2..10",
+        &["2", "3", "4", "5", "6", "7", "8", "9", "10", "2.0", "3.0", "4.0", "5.0", "6.0",
+          "7.0", "8.0", "9.0", "10.0", "2.00", "3.00", "4.00", "5.00", "6.00", "7.00",
+          "8.00"]),
+       (Other,
+        "",
+        &["11", "12", "13", "14", "15", "16", "17", "18", "19", "20", "21", "22", "23",
+          "24", "25", "26", "100", "1000", "10000", "100000", "1000000", "1.1", "1.2",
+          "1.3", "1.4", "1.5", "1.6", "1.7", "1.8", "1.9", "2.1", "2.2", "2.3", "2.4",
+          "2.5", "2.6", "2.7", "10.1", "100.0", "1000.0", "10000.0", "100000.0",
+          "1000000.0"])]),
+    ("si",
+     &[(One,
+        "n = 0,1 or i = 0 and f = 1",
+        &["0", "1", "0.0", "0.1", "1.0", "0.00", "0.01", "1.00", "0.000", "0.001", "1.000",
+          "0.0000", "0.0001", "1.0000"]),
+       (Other,
+        "",
+        &["2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+          "17", "100", "1000", "10000", "100000", "1000000", "0.2", "0.3", "0.4", "0.5",
+          "0.6", "0.7", "0.8", "0.9", "1.1", "1.2", "1.3", "1.4", "1.5", "1.6", "1.7",
+          "1.8", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+    ("sl",
+     &[(One,
+        "v = 0 and i % 100 = 1",
+        &["1", "101", "201", "301", "401", "501", "601", "701", "1001"]),
+       (Two,
+        "v = 0 and i % 100 = 2",
+        &["2", "102", "202", "302", "402", "502", "602", "702", "1002"]),
+       (Few,
+        "v = 0 and i % 100 = 3..4 or v != 0",
+        &["3", "4", "103", "104", "203", "204", "303", "304", "403", "404", "503", "504",
+          "603", "604", "703", "704", "1003", "0.0", "0.1", "0.2", "0.3", "0.4", "0.5",
+          "0.6", "0.7", "0.8", "0.9", "1.0", "1.1", "1.2", "1.3", "1.4", "1.5", "10.0",
+          "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"]),
+       (Other,
+        "",
+        &["0", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16", "17",
+          "18", "19", "100", "1000", "10000", "100000", "1000000"])]),
+    ("tzm",
+     &[(One,
+        "n = 0..1 or n = 11..99",
+        &["0", "1", "11", "12", "13", "14", "15", "16", "17", "18", "19", "20", "21", "22",
+          "23", "24", "0.0", "1.0", "11.0", "12.0", "13.0", "14.0", "15.0", "16.0", "17.0",
+          "18.0", "19.0", "20.0", "21.0", "22.0", "23.0", "24.0"]),
+       (Other,
+        "",
+        &["2", "3", "4", "5", "6", "7", "8", "9", "10", "100", "101", "102", "103", "104",
+          "105", "106", "1000", "10000", "100000", "1000000", "0.1", "0.2", "0.3", "0.4",
+          "0.5", "0.6", "0.7", "0.8", "0.9", "1.1", "1.2", "1.3", "1.4", "1.5", "1.6",
+          "1.7", "10.0", "100.0", "1000.0", "10000.0", "100000.0", "1000000.0"])]),
+];
+
+/// The CLDR samples for each distinct ordinal rule.
+#[cfg(test)]
+pub(super) const ORDINAL_SAMPLES: &Samples = &[
+    ("af",
+     &[(Other,
+        "",
+        &["0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14",
+          "15", "100", "1000", "10000", "100000", "1000000"])]),
+    ("as",
+     &[(One,
+        "n = 1,5,7,8,9,10",
+        &["1", "5", "7", "8", "9", "10"]),
+       (Two,
+        "n = 2,3",
+        &["2", "3"]),
+       (Few,
+        "n = 4",
+        &["4"]),
+       (Many,
+        "n = 6",
+        &["6"]),
+       (Other,
+        "",
+        &["0", "11", "12", "13", "14", "15", "16", "17", "18", "19", "20", "21", "22",
+          "23", "24", "25", "100", "1000", "10000", "100000", "1000000"])]),
+    ("az",
+     &[(One,
+        "i % 10 = 1,2,5,7,8 or i % 100 = 20,50,70,80",
+        &["1", "2", "5", "7", "8", "11", "12", "15", "17", "18", "20", "21", "22", "25",
+          "101", "1001"]),
+       (Few,
+        "i % 10 = 3,4 or i % 1000 = 100,200,300,400,500,600,700,800,900",
+        &["3", "4", "13", "14", "23", "24", "33", "34", "43", "44", "53", "54", "63", "64",
+          "73", "74", "100", "1003"]),
+       (Many,
+        "i = 0 or i % 10 = 6 or i % 100 = 40,60,90",
+        &["0", "6", "16", "26", "36", "40", "46", "56", "106", "1006"]),
+       (Other,
+        "",
+        &["9", "10", "19", "29", "30", "39", "49", "59", "69", "79", "109", "1000",
+          "10000", "100000", "1000000"])]),
+    ("bal",
+     &[(One,
+        "n = 1",
+        &["1"]),
+       (Other,
+        "",
+        &["0", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15",
+          "16", "100", "1000", "10000", "100000", "1000000"])]),
+    ("be",
+     &[(Few,
+        "n % 10 = 2,3 and n % 100 != 12,13",
+        &["2", "3", "22", "23", "32", "33", "42", "43", "52", "53", "62", "63", "72", "73",
+          "82", "83", "102", "1002"]),
+       (Other,
+        "",
+        &["0", "1", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+          "17", "100", "1000", "10000", "100000", "1000000"])]),
+    ("ca",
+     &[(One,
+        "n = 1,3",
+        &["1", "3"]),
+       (Two,
+        "n = 2",
+        &["2"]),
+       (Few,
+        "n = 4",
+        &["4"]),
+       (Other,
+        "",
+        &["0", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16", "17",
+          "18", "19", "100", "1000", "10000", "100000", "1000000"])]),
+    ("cy",
+     &[(Zero,
+        "n = 0,7,8,9",
+        &["0", "7", "8", "9"]),
+       (One,
+        "n = 1",
+        &["1"]),
+       (Two,
+        "n = 2",
+        &["2"]),
+       (Few,
+        "n = 3,4",
+        &["3", "4"]),
+       (Many,
+        "n = 5,6",
+        &["5", "6"]),
+       (Other,
+        "",
+        &["10", "11", "12", "13", "14", "15", "16", "17", "18", "19", "20", "21", "22",
+          "23", "24", "25", "100", "1000", "10000", "100000", "1000000"])]),
+    ("en",
+     &[(One,
+        "n % 10 = 1 and n % 100 != 11",
+        &["1", "21", "31", "41", "51", "61", "71", "81", "101", "1001"]),
+       (Two,
+        "n % 10 = 2 and n % 100 != 12",
+        &["2", "22", "32", "42", "52", "62", "72", "82", "102", "1002"]),
+       (Few,
+        "n % 10 = 3 and n % 100 != 13",
+        &["3", "23", "33", "43", "53", "63", "73", "83", "103", "1003"]),
+       (Other,
+        "",
+        &["0", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+          "17", "18", "100", "1000", "10000", "100000", "1000000"])]),
+    ("gd",
+     &[(One,
+        "n = 1,11",
+        &["1", "11"]),
+       (Two,
+        "n = 2,12",
+        &["2", "12"]),
+       (Few,
+        "n = 3,13",
+        &["3", "13"]),
+       (Other,
+        "",
+        &["0", "4", "5", "6", "7", "8", "9", "10", "14", "15", "16", "17", "18", "19",
+          "20", "21", "100", "1000", "10000", "100000", "1000000"])]),
+    ("gu",
+     &[(One,
+        "n = 1",
+        &["1"]),
+       (Two,
+        "n = 2,3",
+        &["2", "3"]),
+       (Few,
+        "n = 4",
+        &["4"]),
+       (Many,
+        "n = 6",
+        &["6"]),
+       (Other,
+        "",
+        &["0", "5", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16", "17", "18",
+          "19", "20", "100", "1000", "10000", "100000", "1000000"])]),
+    ("hu",
+     &[(One,
+        "n = 1,5",
+        &["1", "5"]),
+       (Other,
+        "",
+        &["0", "2", "3", "4", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+          "17", "100", "1000", "10000", "100000", "1000000"])]),
+    ("it",
+     &[(Many,
+        "n = 11,8,80,800",
+        &["8", "11", "80", "800"]),
+       (Other,
+        "",
+        &["0", "1", "2", "3", "4", "5", "6", "7", "9", "10", "12", "13", "14", "15", "16",
+          "17", "100", "1000", "10000", "100000", "1000000"])]),
+    ("ka",
+     &[(One,
+        "i = 1",
+        &["1"]),
+       (Many,
+        "i = 0 or i % 100 = 2..20,40,60,80",
+        &["0", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15",
+          "16", "102", "1002"]),
+       (Other,
+        "",
+        &["21", "22", "23", "24", "25", "26", "27", "28", "29", "30", "31", "32", "33",
+          "34", "35", "36", "100", "1000", "10000", "100000", "1000000"])]),
+    ("kk",
+     &[(Many,
+        "n % 10 = 6 or n % 10 = 9 or n % 10 = 0 and n != 0",
+        &["6", "9", "10", "16", "19", "20", "26", "29", "30", "36", "39", "40", "100",
+          "1000", "10000", "100000", "1000000"]),
+       (Other,
+        "",
+        &["0", "1", "2", "3", "4", "5", "7", "8", "11", "12", "13", "14", "15", "17", "18",
+          "21", "101", "1001"])]),
+    ("kw",
+     &[(One,
+        "n = 1..4 or n % 100 = 1..4,21..24,41..44,61..64,81..84",
+        &["1", "2", "3", "4", "21", "22", "23", "24", "41", "42", "43", "44", "61", "62",
+          "63", "64", "101", "1001"]),
+       (Many,
+        "n = 5 or n % 100 = 5",
+        &["5", "105", "205", "305", "405", "505", "605", "705", "1005"]),
+       (Other,
+        "",
+        &["0", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16", "17", "18",
+          "19", "20", "100", "1000", "10000", "100000", "1000000"])]),
+    ("lij",
+     &[(Many,
+        "n = 11,8,80..89,800..899",
+        &["8", "11", "80", "81", "82", "83", "84", "85", "86", "87", "88", "89", "800",
+          "801", "802", "803"]),
+       (Other,
+        "",
+        &["0", "1", "2", "3", "4", "5", "6", "7", "9", "10", "12", "13", "14", "15", "16",
+          "17", "100", "1000", "10000", "100000", "1000000"])]),
+    ("mk",
+     &[(One,
+        "i % 10 = 1 and i % 100 != 11",
+        &["1", "21", "31", "41", "51", "61", "71", "81", "101", "1001"]),
+       (Two,
+        "i % 10 = 2 and i % 100 != 12",
+        &["2", "22", "32", "42", "52", "62", "72", "82", "102", "1002"]),
+       (Many,
+        "i % 10 = 7,8 and i % 100 != 17,18",
+        &["7", "8", "27", "28", "37", "38", "47", "48", "57", "58", "67", "68", "77", "78",
+          "87", "88", "107", "1007"]),
+       (Other,
+        "",
+        &["0", "3", "4", "5", "6", "9", "10", "11", "12", "13", "14", "15", "16", "17",
+          "18", "19", "100", "1000", "10000", "100000", "1000000"])]),
+    ("mr",
+     &[(One,
+        "n = 1",
+        &["1"]),
+       (Two,
+        "n = 2,3",
+        &["2", "3"]),
+       (Few,
+        "n = 4",
+        &["4"]),
+       (Other,
+        "",
+        &["0", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16", "17",
+          "18", "19", "100", "1000", "10000", "100000", "1000000"])]),
+    ("ne",
+     &[(One,
+        "n = 1..4",
+        &["1", "2", "3", "4"]),
+       (Other,
+        "",
+        &["0", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16", "17",
+          "18", "19", "100", "1000", "10000", "100000", "1000000"])]),
+    ("or",
+     &[(One,
+        "n = 1,5,7..9",
+        &["1", "5", "7", "8", "9"]),
+       (Two,
+        "n = 2,3",
+        &["2", "3"]),
+       (Few,
+        "n = 4",
+        &["4"]),
+       (Many,
+        "n = 6",
+        &["6"]),
+       (Other,
+        "",
+        &["0", "10", "11", "12", "13", "14", "15", "16", "17", "18", "19", "20", "21",
+          "22", "23", "24", "100", "1000", "10000", "100000", "1000000"])]),
+    ("sq",
+     &[(One,
+        "n = 1",
+        &["1"]),
+       (Many,
+        "n % 10 = 4 and n % 100 != 14",
+        &["4", "24", "34", "44", "54", "64", "74", "84", "104", "1004"]),
+       (Other,
+        "",
+        &["0", "2", "3", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+          "17", "100", "1000", "10000", "100000", "1000000"])]),
+    ("sv",
+     &[(One,
+        "n % 10 = 1,2 and n % 100 != 11,12",
+        &["1", "2", "21", "22", "31", "32", "41", "42", "51", "52", "61", "62", "71", "72",
+          "81", "82", "101", "1001"]),
+       (Other,
+        "",
+        &["0", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+          "17", "100", "1000", "10000", "100000", "1000000"])]),
+    ("tk",
+     &[(Few,
+        "n % 10 = 6,9 or n = 10",
+        &["6", "9", "10", "16", "19", "26", "29", "36", "39", "106", "1006"]),
+       (Other,
+        "",
+        &["0", "1", "2", "3", "4", "5", "7", "8", "11", "12", "13", "14", "15", "17", "18",
+          "20", "100", "1000", "10000", "100000", "1000000"])]),
+    ("uk",
+     &[(Few,
+        "n % 10 = 3 and n % 100 != 13",
+        &["3", "23", "33", "43", "53", "63", "73", "83", "103", "1003"]),
+       (Other,
+        "",
+        &["0", "1", "2", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15",
+          "16", "100", "1000", "10000", "100000", "1000000"])]),
+];
@@ -0,0 +1,524 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::boxed::Box;
+use std::string::ToString;
+use std::vec::Vec;
+
+use super::syntax::RuleParseError;
+use nesting::Nesting;
+use {PluralCategory, PluralClassifier, PluralOperands};
+
+/// Plural rules from a gettext `Plural-Forms` header.
+///
+/// The header has the number of plural forms and a C expression that
+/// gives the index of the form to use for `n`:
+///
+/// ```text
+/// Plural-Forms: nplurals=2; plural=(n != 1);
+/// ```
+///
+/// gettext forms are positional, so each index is mapped onto a
+/// category. By default, the last index is `other`, and the others are
+/// `one` for 2 forms, `one, few` for 3 forms, `one, two, few` for 4
+/// forms, `one, two, few, many` for 5 forms and, as in
+/// [`PluralCategory::ALL`], `zero, one, two, few, many` for 6 forms.
+/// Use [`categories`] for languages where this doesn't fit, such as
+/// Russian, where the third form is `many`.
+///
+/// ```
+/// use message_format::{PluralCategory, PluralClassifier, PluralOperands};
+/// use message_format::plural_rules::GettextPlurals;
+///
+/// let mut ru = GettextPlurals::new("Plural-Forms: nplurals=3; \
+///     plural=(n%10==1 && n%100!=11 ? 0 : \
+///             n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2);").unwrap();
+/// ru.categories(&[PluralCategory::One, PluralCategory::Few, PluralCategory::Many]);
+/// assert_eq!(ru.classify(&PluralOperands::from(21)), PluralCategory::One);
+/// assert_eq!(ru.classify(&PluralOperands::from(22)), PluralCategory::Few);
+/// assert_eq!(ru.classify(&PluralOperands::from(25)), PluralCategory::Many);
+/// ```
+///
+/// gettext only has plural forms for integers, so numbers with
+/// fraction digits use their integer digits.
+///
+/// [`PluralCategory::ALL`]: ../enum.PluralCategory.html#associatedconstant.ALL
+/// [`categories`]: #method.categories
+#[derive(Clone,Debug,PartialEq)]
+pub struct GettextPlurals {
+    expression: Expression,
+    categories: Vec<PluralCategory>,
+}
+
+impl GettextPlurals {
+    /// Parse a `Plural-Forms` header. The `Plural-Forms:` name is
+    /// optional, so this also accepts `nplurals=2; plural=(n != 1);`.
+    ///
+    /// Errors have the offset within `header`.
+    pub fn new(header: &str) -> Result<Self, RuleParseError> {
+        let invalid = |offset| RuleParseError::InvalidRule(header.to_string(), offset);
+        let mut fields = header.trim_end();
+        if fields.get(..13).is_some_and(|field| field.eq_ignore_ascii_case("plural-forms:")) {
+            fields = &fields[13..];
+        }
+        let mut nplurals = None;
+        let mut expression = None;
+        for field in fields.split(';') {
+            let offset = field.as_ptr() as usize - header.as_ptr() as usize;
+            let pos = match field.find('=') {
+                Some(pos) => pos,
+                None if field.trim().is_empty() => continue,
+                None => return Err(invalid(offset)),
+            };
+            let value = &field[pos + 1..];
+            match field[..pos].trim() {
+                "nplurals" => {
+                    let count = value.trim().parse::<usize>().map_err(|_| invalid(offset))?;
+                    if count == 0 {
+                        return Err(invalid(offset));
+                    }
+                    nplurals = Some(count);
+                }
+                "plural" => {
+                    let mut parser = Parser {
+                        source: header,
+                        end: offset + field.len(),
+                        pos: offset + pos + 1,
+                        depth: 0,
+                    };
+                    expression = Some(parser.get_expression()?);
+                }
+                _ => return Err(invalid(offset)),
+            }
+        }
+        match (nplurals, expression) {
+            (Some(nplurals), Some(expression)) => {
+                Ok(GettextPlurals {
+                    expression,
+                    categories: default_categories(nplurals),
+                })
+            }
+            _ => Err(invalid(header.len())),
+        }
+    }
+
+    /// The number of plural forms, `nplurals`.
+    pub fn len(&self) -> usize {
+        self.categories.len()
+    }
+
+    /// Whether there are no plural forms. This is always `false`, as
+    /// `nplurals` must be at least 1.
+    pub fn is_empty(&self) -> bool {
+        self.categories.is_empty()
+    }
+
+    /// Set the category for each index. Indices beyond `categories`
+    /// use `other`, and extra categories are ignored.
+    pub fn categories(&mut self, categories: &[PluralCategory]) {
+        for (index, category) in self.categories.iter_mut().enumerate() {
+            *category = categories.get(index).cloned().unwrap_or(PluralCategory::Other);
+        }
+    }
+
+    /// The category of the plural form at `index`, or `None` if the
+    /// index isn't less than `nplurals`.
+    pub fn category(&self, index: usize) -> Option<PluralCategory> {
+        self.categories.get(index).cloned()
+    }
+
+    /// The index of the plural form for `n`, as gettext would choose
+    /// it. This is `None` if the expression divides by zero or gives
+    /// an index that isn't less than `nplurals`.
+    pub fn index(&self, n: u64) -> Option<usize> {
+        self.expression
+            .evaluate(n)
+            .and_then(|index| if index < self.len() as u64 { Some(index as usize) } else { None })
+    }
+}
+
+impl PluralClassifier for GettextPlurals {
+    fn classify(&self, operands: &PluralOperands) -> PluralCategory {
+        self.index(operands.i).map_or(PluralCategory::Other, |index| self.categories[index])
+    }
+}
+
+/// The categories for each of `nplurals` forms when none are given.
+fn default_categories(nplurals: usize) -> Vec<PluralCategory> {
+    use PluralCategory::*;
+    let categories: &[PluralCategory] = match nplurals {
+        1 => &[Other],
+        2 => &[One, Other],
+        3 => &[One, Few, Other],
+        4 => &[One, Two, Few, Other],
+        5 => &[One, Two, Few, Many, Other],
+        _ => &[Zero, One, Two, Few, Many, Other],
+    };
+    let mut categories = categories.to_vec();
+    categories.resize(nplurals, Other);
+    categories
+}
+
+/// A binary operator of a gettext plural expression.
+#[derive(Clone,Copy,Debug,PartialEq)]
+enum Operator {
+    Or,
+    And,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+}
+
+impl Operator {
+    /// The operators in order of precedence, from lowest to highest,
+    /// with their tokens. Longer tokens come before their prefixes.
+    const LEVELS: &'static [&'static [(&'static str, Operator)]] =
+        &[&[("||", Operator::Or)],
+          &[("&&", Operator::And)],
+          &[("==", Operator::Equal), ("!=", Operator::NotEqual)],
+          &[("<=", Operator::LessEqual),
+            (">=", Operator::GreaterEqual),
+            ("<", Operator::Less),
+            (">", Operator::Greater)],
+          &[("+", Operator::Add), ("-", Operator::Subtract)],
+          &[("*", Operator::Multiply), ("/", Operator::Divide), ("%", Operator::Remainder)]];
+
+    /// Apply the operator as C does to `unsigned long` values. This is
+    /// `None` for division by zero.
+    fn apply(self, left: u64, right: u64) -> Option<u64> {
+        Some(match self {
+            Operator::Or => (left != 0 || right != 0) as u64,
+            Operator::And => (left != 0 && right != 0) as u64,
+            Operator::Equal => (left == right) as u64,
+            Operator::NotEqual => (left != right) as u64,
+            Operator::Less => (left < right) as u64,
+            Operator::LessEqual => (left <= right) as u64,
+            Operator::Greater => (left > right) as u64,
+            Operator::GreaterEqual => (left >= right) as u64,
+            Operator::Add => left.wrapping_add(right),
+            Operator::Subtract => left.wrapping_sub(right),
+            Operator::Multiply => left.wrapping_mul(right),
+            Operator::Divide => left.checked_div(right)?,
+            Operator::Remainder => left.checked_rem(right)?,
+        })
+    }
+}
+
+/// A gettext plural expression.
+#[derive(Clone,Debug,PartialEq)]
+enum Expression {
+    N,
+    Number(u64),
+    Not(Box<Expression>),
+    Binary(Operator, Box<Expression>, Box<Expression>),
+    Conditional(Box<Expression>, Box<Expression>, Box<Expression>),
+}
+
+impl Expression {
+    /// The number of nested expressions, counting this one.
+    fn depth(&self) -> usize {
+        1 + match *self {
+            Expression::N | Expression::Number(_) => 0,
+            Expression::Not(ref operand) => operand.depth(),
+            Expression::Binary(_, ref left, ref right) => left.depth().max(right.depth()),
+            Expression::Conditional(ref condition, ref then, ref otherwise) => {
+                condition.depth().max(then.depth()).max(otherwise.depth())
+            }
+        }
+    }
+
+    fn evaluate(&self, n: u64) -> Option<u64> {
+        match *self {
+            Expression::N => Some(n),
+            Expression::Number(value) => Some(value),
+            Expression::Not(ref operand) => Some((operand.evaluate(n)? == 0) as u64),
+            // `||` and `&&` short-circuit, as in C.
+            Expression::Binary(Operator::Or, ref left, _) if left.evaluate(n)? != 0 => Some(1),
+            Expression::Binary(Operator::And, ref left, _) if left.evaluate(n)? == 0 => Some(0),
+            Expression::Binary(operator, ref left, ref right) => {
+                operator.apply(left.evaluate(n)?, right.evaluate(n)?)
+            }
+            Expression::Conditional(ref condition, ref then, ref otherwise) => {
+                if condition.evaluate(n)? != 0 {
+                    then.evaluate(n)
+                } else {
+                    otherwise.evaluate(n)
+                }
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    /// The end of the expression, at the `;` after it.
+    end: usize,
+    pos: usize,
+    /// The number of parentheses, `!` and `?` around the position.
+    depth: usize,
+}
+
+impl<'a> Nesting for Parser<'a> {
+    type Error = RuleParseError;
+
+    /// Each level is a call for each level of precedence, so fewer of
+    /// them fit on the stack.
+    const MAX_DEPTH: usize = 100;
+
+    fn depth(&mut self) -> &mut usize {
+        &mut self.depth
+    }
+
+    fn too_deep(&self) -> RuleParseError {
+        self.error()
+    }
+}
+
+impl<'a> Parser<'a> {
+    fn ch(&self) -> Option<char> {
+        self.source[self.pos..self.end].chars().next()
+    }
+
+    fn get_ws(&mut self) {
+        while let Some(ch) = self.ch().filter(|ch| ch.is_whitespace()) {
+            self.pos += ch.len_utf8();
+        }
+    }
+
+    /// Consume `token` if it is next, after any whitespace.
+    fn take(&mut self, token: &str) -> bool {
+        self.get_ws();
+        if self.source[self.pos..self.end].starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self) -> RuleParseError {
+        RuleParseError::InvalidRule(self.source.to_string(), self.pos)
+    }
+
+    /// Parse the whole expression, which must be followed by nothing
+    /// but whitespace.
+    fn get_expression(&mut self) -> Result<Expression, RuleParseError> {
+        let expression = self.get_conditional()?;
+        self.get_ws();
+        match self.ch() {
+            None => Ok(expression),
+            Some(_) => Err(self.error()),
+        }
+    }
+
+    fn get_conditional(&mut self) -> Result<Expression, RuleParseError> {
+        let condition = self.get_binary(0)?;
+        if !self.take("?") {
+            return Ok(condition);
+        }
+        let then = self.nested(Parser::get_conditional)?;
+        if !self.take(":") {
+            return Err(self.error());
+        }
+        let otherwise = self.nested(Parser::get_conditional)?;
+        Ok(Expression::Conditional(Box::new(condition), Box::new(then), Box::new(otherwise)))
+    }
+
+    /// Parse the operators of precedence `level` and higher, which are
+    /// all left associative. A long chain of them nests each in the
+    /// next, so its depth is limited too.
+    fn get_binary(&mut self, level: usize) -> Result<Expression, RuleParseError> {
+        let operators = match Operator::LEVELS.get(level) {
+            Some(operators) => operators,
+            None => return self.get_unary(),
+        };
+        let mut left = self.get_binary(level + 1)?;
+        let mut depth = left.depth();
+        while let Some(&(_, operator)) = operators.iter().find(|&&(token, _)| self.take(token)) {
+            let right = self.get_binary(level + 1)?;
+            depth = depth.max(right.depth()) + 1;
+            if depth > Self::MAX_DEPTH {
+                return Err(self.error());
+            }
+            left = Expression::Binary(operator, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn get_unary(&mut self) -> Result<Expression, RuleParseError> {
+        if self.take("!") {
+            return Ok(Expression::Not(Box::new(self.nested(Parser::get_unary)?)));
+        }
+        if self.take("(") {
+            let expression = self.nested(Parser::get_conditional)?;
+            return if self.take(")") { Ok(expression) } else { Err(self.error()) };
+        }
+        if self.take("n") {
+            return Ok(Expression::N);
+        }
+        let start = self.pos;
+        while self.ch().is_some_and(|ch| ch.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.source[start..self.pos].parse().map(Expression::Number).map_err(|_| self.error())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GettextPlurals;
+    use plural_rules::{cardinal_classifier, RuleParseError};
+    use {PluralClassifier, PluralOperands};
+    use PluralCategory::*;
+
+    const RU: &str = "Plural-Forms: nplurals=3; plural=(n%10==1 && n%100!=11 ? 0 : n%10>=2 && \
+                      n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2);";
+    const PL: &str = "Plural-Forms: nplurals=3; plural=(n==1 ? 0 : n%10>=2 && n%10<=4 && \
+                      (n%100<10 || n%100>=20) ? 1 : 2);";
+    const AR: &str = "Plural-Forms: nplurals=6; plural=(n==0 ? 0 : n==1 ? 1 : n==2 ? 2 : \
+                      n%100>=3 && n%100<=10 ? 3 : n%100>=11 ? 4 : 5);";
+
+    /// Check that `plurals` agrees with the built-in rules for integers.
+    fn check(plurals: &GettextPlurals, locale: &str) {
+        let builtin = cardinal_classifier(locale).unwrap();
+        for n in 0..1200 {
+            let operands = PluralOperands::from(n);
+            assert_eq!(plurals.classify(&operands),
+                       builtin.classify(&operands),
+                       "{} {}",
+                       locale,
+                       n);
+        }
+    }
+
+    #[test]
+    fn russian() {
+        let mut ru = GettextPlurals::new(RU).unwrap();
+        assert_eq!(ru.len(), 3);
+        assert_eq!((ru.index(1), ru.index(3), ru.index(11), ru.index(111)),
+                   (Some(0), Some(1), Some(2), Some(2)));
+        ru.categories(&[One, Few, Many]);
+        check(&ru, "ru");
+        check(&ru, "uk");
+    }
+
+    #[test]
+    fn polish() {
+        let mut pl = GettextPlurals::new(PL).unwrap();
+        assert_eq!((pl.index(1), pl.index(21), pl.index(22), pl.index(25)),
+                   (Some(0), Some(2), Some(1), Some(2)));
+        pl.categories(&[One, Few, Many]);
+        check(&pl, "pl");
+    }
+
+    #[test]
+    fn arabic() {
+        // Six forms use every category in order.
+        let ar = GettextPlurals::new(AR).unwrap();
+        check(&ar, "ar");
+    }
+
+    #[test]
+    fn default_categories() {
+        let en = GettextPlurals::new("nplurals=2; plural=n != 1;").unwrap();
+        assert_eq!(en.classify(&PluralOperands::from(1)), One);
+        assert_eq!(en.classify(&PluralOperands::from(2)), Other);
+        let ja = GettextPlurals::new("nplurals=1; plural=0;").unwrap();
+        assert_eq!(ja.classify(&PluralOperands::from(1)), Other);
+        let ga = GettextPlurals::new("nplurals=5; plural=n==1 ? 0 : n==2 ? 1 : \
+                                      (n>2 && n<7) ? 2 : (n>6 && n<11) ? 3 : 4;")
+            .unwrap();
+        check(&ga, "ga");
+    }
+
+    #[test]
+    fn fractions_use_integer_digits() {
+        let en = GettextPlurals::new("nplurals=2; plural=(n != 1);").unwrap();
+        let operands = PluralOperands::from_decimal(1.5, 1);
+        assert_eq!(en.classify(&operands), One);
+    }
+
+    #[test]
+    fn operators() {
+        let index = |expression: &str, n| {
+            let header = format!("nplurals=100; plural={};", expression);
+            GettextPlurals::new(&header).unwrap().index(n)
+        };
+        assert_eq!(index("1 + 2 * 3", 0), Some(7));
+        assert_eq!(index("(1 + 2) * 3", 0), Some(9));
+        assert_eq!(index("n / 10 - 1", 25), Some(1));
+        assert_eq!(index("10 - n % 4 % 3", 7), Some(10));
+        assert_eq!(index("!n + !!n", 5), Some(1));
+        assert_eq!(index("n < 5 == n <= 4", 4), Some(1));
+        assert_eq!(index("n > 1 || n >= 1 && 0", 1), Some(0));
+        assert_eq!(index("n ? n > 1 ? 2 : 1 : 0", 3), Some(2));
+        // `||` short-circuits, so this doesn't divide by zero.
+        assert_eq!(index("n || 1 / 0", 1), Some(1));
+        assert_eq!(index("1 / n", 0), None);
+        assert_eq!(index("n - 1", 0), None);
+        assert_eq!(index("n", 100), None);
+    }
+
+    #[test]
+    fn categories_beyond_nplurals() {
+        let mut plurals = GettextPlurals::new("nplurals=2; plural=n;").unwrap();
+        plurals.categories(&[Few]);
+        assert_eq!(plurals.classify(&PluralOperands::from(0)), Few);
+        assert_eq!(plurals.classify(&PluralOperands::from(1)), Other);
+        assert_eq!(plurals.classify(&PluralOperands::from(2)), Other);
+        assert_eq!([plurals.category(0), plurals.category(1), plurals.category(2)],
+                   [Some(Few), Some(Other), None]);
+    }
+
+    #[test]
+    fn errors() {
+        let invalid = |header: &str, offset| {
+            Err(RuleParseError::InvalidRule(header.to_string(), offset))
+        };
+        assert_eq!(GettextPlurals::new("nplurals=2; plural=n !! 1;"),
+                   invalid("nplurals=2; plural=n !! 1;", 21));
+        assert_eq!(GettextPlurals::new("nplurals=2; plural=(n;"),
+                   invalid("nplurals=2; plural=(n;", 21));
+        assert_eq!(GettextPlurals::new("nplurals=2; plural=n ? 1;"),
+                   invalid("nplurals=2; plural=n ? 1;", 24));
+        assert_eq!(GettextPlurals::new("nplurals=x; plural=n;"),
+                   invalid("nplurals=x; plural=n;", 0));
+        assert_eq!(GettextPlurals::new("nplurals=0; plural=n;"),
+                   invalid("nplurals=0; plural=n;", 0));
+        assert_eq!(GettextPlurals::new("plural=n;"), invalid("plural=n;", 9));
+        assert_eq!(GettextPlurals::new("nplurals=2; plurals=n;"),
+                   invalid("nplurals=2; plurals=n;", 11));
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let nested = |open: &str, close: &str, count| {
+            let expression = format!("{}n{}", open.repeat(count), close.repeat(count));
+            let header = format!("nplurals=2; plural={};", expression);
+            GettextPlurals::new(&header).is_ok()
+        };
+        assert!(nested("(", ")", 90));
+        assert!(!nested("(", ")", 100_000));
+        assert!(!nested("!", "", 100_000));
+        assert!(!nested("n ? 0 : ", "", 100_000));
+        assert!(!nested("", " + n", 100_000));
+        assert!(!nested("(n + ", ") + n", 1000));
+    }
+
+    #[test]
+    fn unicode_whitespace() {
+        let plurals = GettextPlurals::new("nplurals=2;\u{3000}plural=n\u{3000}!=\u{a0}1;").unwrap();
+        assert_eq!(plurals.index(1), Some(0));
+    }
+}
@@ -0,0 +1,122 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use intl_pluralrules::{self, operands, PluralRuleType};
+use unic_langid::LanguageIdentifier;
+
+use {Locale, PluralCategory, PluralClassifier, PluralOperands};
+
+/// A [`PluralClassifier`] using the rules of the [`intl_pluralrules`]
+/// crate.
+///
+/// This is only available with the `intl-pluralrules` feature.
+///
+/// ```
+/// use message_format::{PluralCategory, PluralClassifier, PluralOperands};
+/// use message_format::plural_rules::IntlPluralRules;
+///
+/// let ru = IntlPluralRules::cardinal("ru").unwrap();
+/// assert_eq!(ru.classify(&PluralOperands::from(22)), PluralCategory::Few);
+/// ```
+///
+/// [`PluralClassifier`]: ../trait.PluralClassifier.html
+/// [`intl_pluralrules`]: https://docs.rs/intl_pluralrules
+#[derive(Clone)]
+pub struct IntlPluralRules {
+    rules: intl_pluralrules::PluralRules,
+}
+
+impl IntlPluralRules {
+    /// Look up the cardinal plural rules for a `locale`, such as `"ru"`.
+    ///
+    /// The rules for the most specific of the [`Locale::fallbacks`] are
+    /// used. `None` is returned if the locale is malformed or there are
+    /// no rules for its language.
+    ///
+    /// [`Locale::fallbacks`]: ../struct.Locale.html#method.fallbacks
+    pub fn cardinal(locale: &str) -> Option<Self> {
+        IntlPluralRules::new(locale, PluralRuleType::CARDINAL)
+    }
+
+    /// Look up the ordinal plural rules for a `locale`, which are used
+    /// for `selectordinal`. This uses the same lookup as [`cardinal`].
+    ///
+    /// [`cardinal`]: #method.cardinal
+    pub fn ordinal(locale: &str) -> Option<Self> {
+        IntlPluralRules::new(locale, PluralRuleType::ORDINAL)
+    }
+
+    fn new(locale: &str, kind: PluralRuleType) -> Option<Self> {
+        let locale = Locale::new(&locale.replace('_', "-")).ok()?;
+        locale.fallbacks().iter().find_map(|fallback| {
+            let fallback = fallback.parse::<LanguageIdentifier>().ok()?;
+            let rules = intl_pluralrules::PluralRules::create(fallback, kind).ok()?;
+            Some(IntlPluralRules { rules })
+        })
+    }
+}
+
+impl PluralClassifier for IntlPluralRules {
+    fn classify(&self, operands: &PluralOperands) -> PluralCategory {
+        let operands = operands::PluralOperands {
+            n: operands.n,
+            i: operands.i,
+            v: operands.v,
+            w: operands.w,
+            f: operands.f,
+            t: operands.t,
+        };
+        match self.rules.select(operands) {
+            Ok(intl_pluralrules::PluralCategory::ZERO) => PluralCategory::Zero,
+            Ok(intl_pluralrules::PluralCategory::ONE) => PluralCategory::One,
+            Ok(intl_pluralrules::PluralCategory::TWO) => PluralCategory::Two,
+            Ok(intl_pluralrules::PluralCategory::FEW) => PluralCategory::Few,
+            Ok(intl_pluralrules::PluralCategory::MANY) => PluralCategory::Many,
+            Ok(intl_pluralrules::PluralCategory::OTHER) | Err(_) => PluralCategory::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IntlPluralRules;
+    use {PluralCategory, PluralClassifier, PluralOperands};
+    use PluralCategory::*;
+
+    fn classify(rules: &IntlPluralRules, value: &str) -> PluralCategory {
+        rules.classify(&value.parse::<PluralOperands>().unwrap())
+    }
+
+    #[test]
+    fn slavic_cardinals() {
+        let ru = IntlPluralRules::cardinal("ru-RU").unwrap();
+        let cases = [("1", One), ("21", One), ("2", Few), ("24", Few), ("5", Many),
+                     ("11", Many), ("12", Many), ("111", Many), ("1.5", Other)];
+        for &(value, category) in &cases {
+            assert_eq!(classify(&ru, value), category, "{}", value);
+        }
+        let pl = IntlPluralRules::cardinal("pl").unwrap();
+        assert_eq!(classify(&pl, "21"), Many);
+        assert_eq!(classify(&pl, "22"), Few);
+    }
+
+    #[test]
+    fn ordinals() {
+        let en = IntlPluralRules::ordinal("en").unwrap();
+        assert_eq!(classify(&en, "2"), Two);
+        assert_eq!(classify(&en, "12"), Other);
+        let ru = IntlPluralRules::ordinal("ru").unwrap();
+        assert_eq!(classify(&ru, "2"), Other);
+    }
+
+    #[test]
+    fn lookup() {
+        assert!(IntlPluralRules::cardinal("uk_UA").is_some());
+        assert!(IntlPluralRules::cardinal("sr-Latn-RS").is_some());
+        assert!(IntlPluralRules::cardinal("xx").is_none());
+        assert!(IntlPluralRules::cardinal("not a locale").is_none());
+    }
+}
@@ -0,0 +1,404 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Built-in [CLDR plural rules].
+//!
+//! These are the cardinal and ordinal plural rules for all of the
+//! locales in CLDR. They are used by [`PluralFormat`] to select a
+//! message for the locale in the [`Context`].
+//!
+//! The rules are generated from the CLDR JSON data by `cldr-gen`, which
+//! is in the repository alongside this crate. Rules can also be loaded
+//! from CLDR JSON at runtime with [`PluralRules`]. With the
+//! `intl-pluralrules` feature, [`IntlPluralRules`] uses the rules of the
+//! `intl_pluralrules` crate instead. Rules from the `Plural-Forms`
+//! header of gettext catalogs can be used with [`GettextPlurals`].
+//!
+//! ```
+//! use message_format::{PluralCategory, PluralClassifier, PluralOperands};
+//! use message_format::plural_rules::cardinal_classifier;
+//!
+//! let ru = cardinal_classifier("ru").unwrap();
+//! assert_eq!(ru.classify(&PluralOperands::from(21)), PluralCategory::One);
+//! assert_eq!(ru.classify(&PluralOperands::from(22)), PluralCategory::Few);
+//! assert_eq!(ru.classify(&PluralOperands::from(25)), PluralCategory::Many);
+//! ```
+//!
+//! [CLDR plural rules]: http://www.unicode.org/cldr/charts/latest/supplemental/language_plural_rules.html
+//! [`PluralFormat`]: ../icu/ast/struct.PluralFormat.html
+//! [`Context`]: ../struct.Context.html
+//! [`PluralRules`]: struct.PluralRules.html
+//! [`IntlPluralRules`]: struct.IntlPluralRules.html
+//! [`GettextPlurals`]: struct.GettextPlurals.html
+
+mod cldr;
+mod gettext;
+#[cfg(feature = "intl-pluralrules")]
+mod intl;
+mod rules;
+pub mod syntax;
+
+#[cfg(feature = "intl-pluralrules")]
+pub use self::intl::IntlPluralRules;
+pub use self::gettext::GettextPlurals;
+pub use self::rules::PluralRules;
+pub use self::syntax::RuleParseError;
+
+use self::cldr::{CARDINAL_RULES, ORDINAL_RULES};
+use {Locale, PluralCategory, PluralClassifier, PluralOperands};
+
+/// A plural rule, mapping operands to a category.
+type Rule = fn(&PluralOperands) -> PluralCategory;
+
+/// The CLDR condition of each category of a rule, other than `other`.
+type Conditions = &'static [(PluralCategory, &'static str)];
+
+/// The built-in rules for a locale, with their conditions.
+#[derive(Clone,Copy)]
+pub(crate) struct BuiltinRules {
+    rule: Rule,
+    conditions: Conditions,
+}
+
+impl PluralClassifier for BuiltinRules {
+    fn classify(&self, operands: &PluralOperands) -> PluralCategory {
+        (self.rule)(operands)
+    }
+
+    fn condition(&self, category: PluralCategory) -> Option<&str> {
+        self.conditions
+            .iter()
+            .find(|&&(c, _)| c == category)
+            .map(|&(_, condition)| condition)
+    }
+}
+
+/// The condition and samples of numbers for each category of the
+/// rules of a locale.
+#[cfg(test)]
+type Samples = [(&'static str, &'static [(PluralCategory, &'static str, &'static [&'static str])])];
+
+/// Look up the cardinal plural rules for a `locale`, such as `"fr"` or
+/// `"pt-PT"`.
+///
+/// The rules for the most specific of the [`Locale::fallbacks`] are
+/// used, so `"sr-Latn-RS"` uses the rules for `"sr"`. `None` is
+/// returned if the locale is malformed or there are no built-in rules
+/// for its language.
+///
+/// [`Locale::fallbacks`]: ../struct.Locale.html#method.fallbacks
+pub fn cardinal_classifier(locale: &str) -> Option<impl PluralClassifier> {
+    cardinal_rules(&parse_locale(locale)?)
+}
+
+/// Look up the ordinal plural rules for a `locale`, such as `"en"`,
+/// which are used for `selectordinal`.
+///
+/// ```
+/// use message_format::{PluralCategory, PluralClassifier, PluralOperands};
+/// use message_format::plural_rules::ordinal_classifier;
+///
+/// let en = ordinal_classifier("en").unwrap();
+/// assert_eq!(en.classify(&PluralOperands::from(22)), PluralCategory::Two);
+/// assert_eq!(en.classify(&PluralOperands::from(13)), PluralCategory::Other);
+/// ```
+///
+/// This uses the same lookup as [`cardinal_classifier`].
+///
+/// [`cardinal_classifier`]: fn.cardinal_classifier.html
+pub fn ordinal_classifier(locale: &str) -> Option<impl PluralClassifier> {
+    ordinal_rules(&parse_locale(locale)?)
+}
+
+/// Parse a locale like `"pt_PT"` or `"zh-Hant-TW"`.
+pub(crate) fn parse_locale(locale: &str) -> Option<Locale> {
+    Locale::new(&locale.replace('_', "-")).ok()
+}
+
+/// Determine the cardinal plural category of a number in a `locale`.
+/// Locales without built-in rules use the CLDR root rules, where every
+/// number is `PluralCategory::Other`.
+pub(crate) fn cardinal_category(locale: &Locale, operands: &PluralOperands) -> PluralCategory {
+    cardinal_rules(locale).map_or(PluralCategory::Other, |rules| rules.classify(operands))
+}
+
+/// Determine the ordinal plural category of a number in a `locale`.
+/// Locales without built-in rules use `PluralCategory::Other`.
+pub(crate) fn ordinal_category(locale: &Locale, operands: &PluralOperands) -> PluralCategory {
+    ordinal_rules(locale).map_or(PluralCategory::Other, |rules| rules.classify(operands))
+}
+
+/// The built-in cardinal rules for a `locale`, if there are any.
+pub(crate) fn cardinal_rules(locale: &Locale) -> Option<BuiltinRules> {
+    find_rules(CARDINAL_RULES, locale)
+}
+
+/// The built-in ordinal rules for a `locale`. Locales with cardinal
+/// rules but not ordinal rules use `PluralCategory::Other`.
+pub(crate) fn ordinal_rules(locale: &Locale) -> Option<BuiltinRules> {
+    find_rules(ORDINAL_RULES, locale).or_else(|| {
+        find_rules(CARDINAL_RULES, locale).map(|_| {
+            BuiltinRules {
+                rule: other,
+                conditions: &[],
+            }
+        })
+    })
+}
+
+fn other(_: &PluralOperands) -> PluralCategory {
+    PluralCategory::Other
+}
+
+/// Find the rules for the most specific fallback of `locale`. The
+/// rules are keyed by locale in lowercase.
+fn find_rules(rules: &[(&str, Rule, Conditions)], locale: &Locale) -> Option<BuiltinRules> {
+    locale.fallbacks().iter().find_map(|fallback| {
+        let fallback = fallback.to_ascii_lowercase();
+        rules.iter()
+            .find(|&&(key, _, _)| key == fallback)
+            .map(|&(_, rule, conditions)| BuiltinRules { rule, conditions })
+    })
+}
+
+/// Whether `value` is an integer within `low..=high`. Ranges in CLDR
+/// rules only match integers.
+fn in_range(value: f64, low: f64, high: f64) -> bool {
+    value % 1.0 == 0.0 && value >= low && value <= high
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cardinal_category, cardinal_classifier, ordinal_category, ordinal_classifier};
+    use super::{PluralRules, Samples};
+    use super::cldr::{CARDINAL_SAMPLES, ORDINAL_SAMPLES};
+    use {Locale, PluralCategory, PluralClassifier, PluralOperands};
+    use PluralCategory::*;
+
+    /// Check CLDR sample values for a locale.
+    fn check(locale: &str, samples: &[(PluralCategory, &[&str])]) {
+        check_classifier(cardinal_classifier(locale).unwrap(), locale, samples);
+    }
+
+    /// Check CLDR ordinal sample values for a locale.
+    fn check_ordinal(locale: &str, samples: &[(PluralCategory, &[&str])]) {
+        check_classifier(ordinal_classifier(locale).unwrap(), locale, samples);
+    }
+
+    fn check_classifier<C: PluralClassifier>(classifier: C,
+                                             locale: &str,
+                                             samples: &[(PluralCategory, &[&str])]) {
+        for &(category, values) in samples {
+            for value in values {
+                assert_eq!(classifier.classify(&value.parse().unwrap()),
+                           category,
+                           "{} {}",
+                           locale,
+                           value);
+            }
+        }
+    }
+
+    #[test]
+    fn germanic() {
+        for locale in &["en", "de", "nl", "sv"] {
+            check(locale,
+                  &[(One, &["1"]), (Other, &["0", "2", "16", "100", "0.0", "1.0", "1.5"])]);
+        }
+        check("da", &[(One, &["1", "0.1", "1.6"]), (Other, &["0", "2", "2.0", "2.5"])]);
+        check("is",
+              &[(One, &["1", "21", "31", "0.1", "2.1"]), (Other, &["0", "11", "2.0", "1.6"])]);
+        check("nb", &[(One, &["1", "1.0"]), (Other, &["0", "2", "0.5"])]);
+    }
+
+    #[test]
+    fn romance() {
+        check("fr",
+              &[(One, &["0", "1", "0.0", "1.5"]),
+                (Many, &["1000000", "2000000"]),
+                (Other, &["2", "17", "2.0", "100000"])]);
+        check("es", &[(One, &["1", "1.0"]), (Many, &["1000000"]), (Other, &["0", "2", "0.5"])]);
+        check("it", &[(One, &["1"]), (Many, &["1000000"]), (Other, &["0", "2", "1.0"])]);
+        check("pt", &[(One, &["0", "1", "1.5"]), (Many, &["1000000"]), (Other, &["2", "2.5"])]);
+        check("pt-PT", &[(One, &["1"]), (Many, &["1000000"]), (Other, &["0", "1.5"])]);
+        check("ro",
+              &[(One, &["1"]), (Few, &["0", "2", "19", "101", "1.5"]), (Other, &["20", "100"])]);
+    }
+
+    #[test]
+    fn slavic() {
+        for locale in &["ru", "uk"] {
+            check(locale,
+                  &[(One, &["1", "21", "101"]),
+                    (Few, &["2", "4", "22", "104"]),
+                    (Many, &["0", "5", "11", "12", "14", "20", "111"]),
+                    (Other, &["0.5", "1.0", "2.5"])]);
+        }
+        check("be",
+              &[(One, &["1", "21", "1.0"]),
+                (Few, &["2", "24", "2.0"]),
+                (Many, &["0", "5", "11", "12"]),
+                (Other, &["0.1", "1.5"])]);
+        check("pl",
+              &[(One, &["1"]),
+                (Few, &["2", "4", "22"]),
+                (Many, &["0", "5", "11", "12", "21"]),
+                (Other, &["0.5", "1.0"])]);
+        check("cs",
+              &[(One, &["1"]), (Few, &["2", "4"]), (Many, &["0.5", "1.0"]), (Other, &["0", "5"])]);
+        check("hr",
+              &[(One, &["1", "21", "0.1", "1.1"]),
+                (Few, &["2", "4", "22", "0.2", "1.4"]),
+                (Other, &["0", "5", "11", "12", "0.5", "1.0"])]);
+        check("sl",
+              &[(One, &["1", "101"]),
+                (Two, &["2", "102"]),
+                (Few, &["3", "4", "103", "0.5", "1.0"]),
+                (Other, &["0", "5", "100"])]);
+        check("mk", &[(One, &["1", "21", "0.1"]), (Other, &["0", "11", "2", "0.2"])]);
+    }
+
+    #[test]
+    fn baltic() {
+        check("lt",
+              &[(One, &["1", "21", "1.0"]),
+                (Few, &["2", "9", "22", "2.0"]),
+                (Many, &["0.1", "1.5"]),
+                (Other, &["0", "10", "11", "19", "20"])]);
+        check("lv",
+              &[(Zero, &["0", "10", "11", "19", "0.0", "0.11"]),
+                (One, &["1", "21", "0.1", "1.1", "0.01"]),
+                (Other, &["2", "22", "0.2", "0.02"])]);
+    }
+
+    #[test]
+    fn celtic() {
+        check("ga",
+              &[(One, &["1"]),
+                (Two, &["2"]),
+                (Few, &["3", "6"]),
+                (Many, &["7", "10"]),
+                (Other, &["0", "11", "1.5"])]);
+        check("cy",
+              &[(Zero, &["0"]),
+                (One, &["1"]),
+                (Two, &["2"]),
+                (Few, &["3"]),
+                (Many, &["6"]),
+                (Other, &["4", "5", "7", "0.5"])]);
+    }
+
+    #[test]
+    fn others() {
+        check("ar",
+              &[(Zero, &["0"]),
+                (One, &["1"]),
+                (Two, &["2"]),
+                (Few, &["3", "10", "103"]),
+                (Many, &["11", "99", "111"]),
+                (Other, &["100", "102", "0.5"])]);
+        check("he",
+              &[(One, &["1", "0.5"]), (Two, &["2"]), (Other, &["0", "3", "10", "1.5"])]);
+        check("hi", &[(One, &["0", "1", "0.5", "1.0"]), (Other, &["2", "1.5"])]);
+        check("fil", &[(One, &["0", "1", "3", "5", "0.1"]), (Other, &["4", "6", "9", "0.4"])]);
+        check("tr", &[(One, &["1"]), (Other, &["0", "2", "1.5"])]);
+        for locale in &["ja", "zh", "ko", "vi", "th", "id", "ms"] {
+            check(locale, &[(Other, &["0", "1", "2", "1.5"])]);
+        }
+    }
+
+    #[test]
+    fn ordinals() {
+        check_ordinal("en",
+                      &[(One, &["1", "21", "101"]),
+                        (Two, &["2", "22", "102"]),
+                        (Few, &["3", "23", "103"]),
+                        (Other, &["0", "4", "11", "12", "13", "111", "112", "113"])]);
+        check_ordinal("cy",
+                      &[(Zero, &["0", "7", "8", "9"]),
+                        (One, &["1"]),
+                        (Two, &["2"]),
+                        (Few, &["3", "4"]),
+                        (Many, &["5", "6"]),
+                        (Other, &["10", "11", "100"])]);
+        check_ordinal("it", &[(Many, &["8", "11", "80", "800"]), (Other, &["0", "1", "18"])]);
+        check_ordinal("hu", &[(One, &["1", "5"]), (Other, &["0", "2", "15"])]);
+        check_ordinal("uk", &[(Few, &["3", "23", "103"]), (Other, &["0", "1", "13", "113"])]);
+        check_ordinal("sv", &[(One, &["1", "2", "21", "22"]), (Other, &["0", "3", "11", "12"])]);
+        check_ordinal("ca",
+                      &[(One, &["1", "3"]), (Two, &["2"]), (Few, &["4"]), (Other, &["0", "5"])]);
+        check_ordinal("hi",
+                      &[(One, &["1"]),
+                        (Two, &["2", "3"]),
+                        (Few, &["4"]),
+                        (Many, &["6"]),
+                        (Other, &["0", "5", "7"])]);
+        check_ordinal("bn",
+                      &[(One, &["1", "5", "7", "10"]),
+                        (Two, &["2", "3"]),
+                        (Few, &["4"]),
+                        (Many, &["6"]),
+                        (Other, &["0", "11"])]);
+        check_ordinal("mk",
+                      &[(One, &["1", "21"]),
+                        (Two, &["2", "22"]),
+                        (Many, &["7", "8", "27"]),
+                        (Other, &["0", "11", "12", "17", "18"])]);
+        check_ordinal("be", &[(Few, &["2", "3", "22"]), (Other, &["0", "1", "12", "13"])]);
+        check_ordinal("fr", &[(One, &["1"]), (Other, &["0", "2"])]);
+        for locale in &["de", "ru", "ja"] {
+            check_ordinal(locale, &[(Other, &["0", "1", "2", "3"])]);
+        }
+        assert!(ordinal_classifier("xx").is_none());
+        assert_eq!(ordinal_category(&Locale::new("en-GB").unwrap(), &PluralOperands::from(3)),
+                   Few);
+    }
+
+    /// Check the CLDR samples against both the built-in rules and the
+    /// same rules loaded at runtime.
+    fn check_samples<C: PluralClassifier>(samples: &Samples, builtin: fn(&str) -> Option<C>) {
+        for &(locale, categories) in samples {
+            let conditions = categories.iter()
+                .map(|&(category, condition, _)| (category, condition))
+                .collect::<Vec<_>>();
+            let values = categories.iter()
+                .map(|&(category, _, values)| (category, values))
+                .collect::<Vec<_>>();
+            let builtin = builtin(locale).unwrap();
+            let rules = PluralRules::new(&conditions).unwrap();
+            for &(category, condition) in &conditions {
+                let condition = Some(condition).filter(|_| category != Other);
+                assert_eq!(builtin.condition(category), condition, "{} {:?}", locale, category);
+                assert_eq!(rules.condition(category), condition, "{} {:?}", locale, category);
+            }
+            check_classifier(builtin, locale, &values);
+            check_classifier(rules, locale, &values);
+        }
+    }
+
+    #[test]
+    fn cldr_samples() {
+        check_samples(CARDINAL_SAMPLES, cardinal_classifier);
+        check_samples(ORDINAL_SAMPLES, ordinal_classifier);
+    }
+
+    #[test]
+    fn lookup() {
+        assert!(cardinal_classifier("fr-CA").is_some());
+        assert!(cardinal_classifier("zh_Hant_TW").is_some());
+        assert!(cardinal_classifier("sr-Latn-RS-u-nu-latn").is_some());
+        assert!(cardinal_classifier("xx").is_none());
+        assert!(cardinal_classifier("not a locale").is_none());
+        // The region specific rules are only used for that region.
+        let one = PluralOperands::from(0);
+        assert_eq!(cardinal_classifier("pt-BR").unwrap().classify(&one), One);
+        assert_eq!(cardinal_classifier("pt_PT").unwrap().classify(&one), Other);
+        assert_eq!(cardinal_category(&Locale::new("pt-PT").unwrap(), &one), Other);
+        assert_eq!(cardinal_category(&Locale::new("pt-Latn-PT").unwrap(), &one), Other);
+        assert_eq!(cardinal_category(&Locale::new("xx").unwrap(), &PluralOperands::from(1)),
+                   Other);
+    }
+}
@@ -0,0 +1,156 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::collections::BTreeMap;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use json::{self, JsonValue};
+use super::syntax::{parse_rule, Condition, RuleParseError};
+use {PluralCategory, PluralClassifier, PluralOperands};
+
+/// Plural rules that are loaded at runtime.
+///
+/// This allows rules to be shipped separately from the binary, such
+/// as to add locales without recompiling. The rules are in the syntax
+/// used by CLDR and give the same results as the built-in rules.
+///
+/// ```
+/// use message_format::{PluralCategory, PluralClassifier, PluralOperands};
+/// use message_format::plural_rules::PluralRules;
+///
+/// let rules = PluralRules::from_cldr_json(r#"{
+///     "pluralRule-count-one": "i = 1 and v = 0 @integer 1",
+///     "pluralRule-count-other": " @integer 0, 2~16, 100, 1000, …"
+/// }"#).unwrap();
+/// assert_eq!(rules.classify(&PluralOperands::from(1)), PluralCategory::One);
+/// assert_eq!(rules.classify(&PluralOperands::from(2)), PluralCategory::Other);
+/// ```
+#[derive(Clone,Debug,PartialEq)]
+pub struct PluralRules {
+    /// The conditions for each category other than `other`, with
+    /// their source, in the order that they are tested.
+    conditions: Vec<(PluralCategory, String, Condition)>,
+}
+
+impl PluralRules {
+    /// Parse the rules for a category from each of `rules`. The rules
+    /// for `other` are ignored, as it is used when nothing else matches.
+    pub fn new(rules: &[(PluralCategory, &str)]) -> Result<Self, RuleParseError> {
+        let mut conditions = vec![];
+        for &(category, rule) in rules {
+            if category != PluralCategory::Other {
+                let source = rule.split('@').next().unwrap_or("").trim().to_string();
+                conditions.push((category, source, parse_rule(rule)?.condition));
+            }
+        }
+        // Test the categories in the same order as the built-in rules.
+        conditions.sort_by_key(|&(category, _, _)| category as u8);
+        Ok(PluralRules { conditions })
+    }
+
+    /// Load the rules for a locale from CLDR JSON, which is an object
+    /// with a `pluralRule-count-` member for each category.
+    pub fn from_cldr_json(json: &str) -> Result<Self, RuleParseError> {
+        let value = json::parse(json).map_err(|e| RuleParseError::InvalidJson(e.to_string()))?;
+        PluralRules::from_json_value(&value)
+    }
+
+    /// Load the rules for every locale from a CLDR `plurals.json` or
+    /// `ordinals.json` file, by locale.
+    pub fn all_from_cldr_json(json: &str) -> Result<BTreeMap<String, Self>, RuleParseError> {
+        let value = json::parse(json).map_err(|e| RuleParseError::InvalidJson(e.to_string()))?;
+        let supplemental = value.get("supplemental");
+        let locales = supplemental.and_then(|s| s.get("plurals-type-cardinal"))
+            .or_else(|| supplemental.and_then(|s| s.get("plurals-type-ordinal")))
+            .and_then(JsonValue::as_object)
+            .ok_or_else(|| RuleParseError::InvalidJson("Missing plural rules.".to_string()))?;
+        let mut rules = BTreeMap::new();
+        for (locale, value) in locales {
+            rules.insert(locale.clone(), PluralRules::from_json_value(value)?);
+        }
+        Ok(rules)
+    }
+
+    fn from_json_value(value: &JsonValue) -> Result<Self, RuleParseError> {
+        let members = value.as_object()
+            .ok_or_else(|| RuleParseError::InvalidJson("Expected an object.".to_string()))?;
+        let mut rules = vec![];
+        for (key, rule) in members {
+            let category = key.strip_prefix("pluralRule-count-")
+                .and_then(|name| name.parse().ok())
+                .ok_or_else(|| RuleParseError::InvalidCategory(key.clone()))?;
+            let rule = rule.as_str().ok_or_else(|| {
+                RuleParseError::InvalidJson(format!("Expected a string for {}.", key))
+            })?;
+            rules.push((category, rule));
+        }
+        PluralRules::new(&rules)
+    }
+}
+
+impl PluralClassifier for PluralRules {
+    fn classify(&self, operands: &PluralOperands) -> PluralCategory {
+        self.conditions
+            .iter()
+            .find(|&(_, _, condition)| condition.matches(operands))
+            .map_or(PluralCategory::Other, |&(category, _, _)| category)
+    }
+
+    fn condition(&self, category: PluralCategory) -> Option<&str> {
+        self.conditions
+            .iter()
+            .find(|&&(c, _, _)| c == category)
+            .map(|(_, source, _)| source.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PluralRules;
+    use plural_rules::RuleParseError;
+    use {PluralCategory, PluralClassifier, PluralOperands};
+
+    #[test]
+    fn categories_are_tested_in_order() {
+        // `other` is ignored, and `one` is tested before `many`.
+        let rules = PluralRules::new(&[(PluralCategory::Other, "n = 1"),
+                                       (PluralCategory::Many, "n = 1..5"),
+                                       (PluralCategory::One, "n = 1")])
+            .unwrap();
+        assert_eq!(rules.classify(&PluralOperands::from(1)), PluralCategory::One);
+        assert_eq!(rules.classify(&PluralOperands::from(2)), PluralCategory::Many);
+        assert_eq!(rules.classify(&PluralOperands::from(6)), PluralCategory::Other);
+        assert_eq!(rules.condition(PluralCategory::Many), Some("n = 1..5"));
+        assert_eq!(rules.condition(PluralCategory::Other), None);
+    }
+
+    #[test]
+    fn all_locales() {
+        let json = r#"{"supplemental": {"plurals-type-ordinal": {
+            "en": {"pluralRule-count-one": "n % 10 = 1 and n % 100 != 11",
+                   "pluralRule-count-other": ""},
+            "de": {"pluralRule-count-other": ""}
+        }}}"#;
+        let rules = PluralRules::all_from_cldr_json(json).unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules["en"].classify(&PluralOperands::from(21)), PluralCategory::One);
+        assert_eq!(rules["de"].classify(&PluralOperands::from(21)), PluralCategory::Other);
+    }
+
+    #[test]
+    fn errors() {
+        match PluralRules::from_cldr_json("{") {
+            Err(RuleParseError::InvalidJson(_)) => {}
+            result => panic!("unexpected {:?}", result),
+        }
+        assert_eq!(PluralRules::from_cldr_json(r#"{"pluralRule-count-lots": "n = 1"}"#),
+                   Err(RuleParseError::InvalidCategory("pluralRule-count-lots".to_string())));
+        assert_eq!(PluralRules::from_cldr_json(r#"{"pluralRule-count-one": "n == 1"}"#),
+                   Err(RuleParseError::InvalidRule("n == 1".to_string(), 3)));
+        assert!(PluralRules::all_from_cldr_json(r#"{"supplemental": {}}"#).is_err());
+    }
+}
@@ -0,0 +1,443 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The [plural rule syntax] used by CLDR.
+//!
+//! A rule is a condition followed by samples of the numbers that it
+//! matches:
+//!
+//! ```text
+//! n % 10 = 1 and n % 100 != 11 @integer 1, 21, 31, … @decimal 1.0, 21.0, …
+//! ```
+//!
+//! This is used by [`PluralRules`] to load rules at runtime and by
+//! `cldr-gen` to generate the built-in rules, so both of them agree.
+//!
+//! [plural rule syntax]: http://unicode.org/reports/tr35/tr35-numbers.html#Plural_rules_syntax
+//! [`PluralRules`]: ../struct.PluralRules.html
+
+use std::error::Error;
+use std::fmt;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use PluralOperands;
+
+/// A sample range can have this many values, so that a malicious rule
+/// can't exhaust memory.
+const MAX_SAMPLE_RANGE: u64 = 1000;
+
+/// An error parsing plural rules.
+#[derive(Clone,Debug,PartialEq)]
+pub enum RuleParseError {
+    /// The JSON is malformed, or isn't CLDR plural rules data. Has a
+    /// description of the problem.
+    InvalidJson(String),
+    /// A rule is malformed. Has the rule and the byte offset within it.
+    InvalidRule(String, usize),
+    /// A rule is for an unknown plural category.
+    InvalidCategory(String),
+}
+
+impl Error for RuleParseError {}
+
+impl fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            RuleParseError::InvalidJson(ref message) => write!(f, "Invalid JSON: {}", message),
+            RuleParseError::InvalidRule(ref rule, offset) => {
+                write!(f, "Invalid plural rule at offset {}: '{}'.", offset, rule)
+            }
+            RuleParseError::InvalidCategory(ref category) => {
+                write!(f, "Invalid plural category '{}'.", category)
+            }
+        }
+    }
+}
+
+/// An operand of a relation, as described by [`PluralOperands`].
+///
+/// [`PluralOperands`]: ../struct.PluralOperands.html
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum Operand {
+    /// `n`, the absolute value.
+    N,
+    /// `i`, the integer digits.
+    I,
+    /// `v`, the number of visible fraction digits, with trailing zeros.
+    V,
+    /// `w`, the number of visible fraction digits, without trailing zeros.
+    W,
+    /// `f`, the visible fraction digits, with trailing zeros.
+    F,
+    /// `t`, the visible fraction digits, without trailing zeros.
+    T,
+    /// Both `c` and `e`, which are synonyms for the compact exponent.
+    E,
+}
+
+/// A relation like `n % 10 != 2..4,6`.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Relation {
+    /// The operand being compared.
+    pub operand: Operand,
+    /// The modulus applied to the operand, if any.
+    pub modulus: Option<u64>,
+    /// Whether this is `!=` rather than `=`.
+    pub negated: bool,
+    /// The values and inclusive ranges to compare with. A single value
+    /// is a range with the same start and end.
+    pub ranges: Vec<(u64, u64)>,
+}
+
+impl Relation {
+    /// Whether the relation holds for `operands`.
+    ///
+    /// Values only match integers, so `n = 1` doesn't match `1.5`, even
+    /// though `i = 1` does.
+    pub fn matches(&self, operands: &PluralOperands) -> bool {
+        let found = if self.operand == Operand::N {
+            let value = match self.modulus {
+                Some(modulus) => operands.n % modulus as f64,
+                None => operands.n,
+            };
+            value % 1.0 == 0.0 &&
+            self.ranges.iter().any(|&(start, end)| value >= start as f64 && value <= end as f64)
+        } else {
+            let value = match self.operand {
+                Operand::I => operands.i,
+                Operand::V => operands.v as u64,
+                Operand::W => operands.w as u64,
+                Operand::F => operands.f,
+                Operand::T => operands.t,
+                _ => operands.e as u64,
+            };
+            let value = self.modulus.map_or(value, |modulus| value % modulus);
+            self.ranges.iter().any(|&(start, end)| (start..=end).contains(&value))
+        };
+        found != self.negated
+    }
+}
+
+/// A condition, which holds if all of the relations within any one of
+/// its `and` conditions hold. An empty condition, as for `other`,
+/// always holds.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Condition(pub Vec<Vec<Relation>>);
+
+impl Condition {
+    /// Whether the condition holds for `operands`.
+    pub fn matches(&self, operands: &PluralOperands) -> bool {
+        self.0.is_empty() ||
+        self.0.iter().any(|relations| relations.iter().all(|r| r.matches(operands)))
+    }
+}
+
+/// A parsed plural rule.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Rule {
+    /// The condition for the category of the rule.
+    pub condition: Condition,
+    /// The expanded `@integer` samples.
+    pub integer_samples: Vec<String>,
+    /// The expanded `@decimal` samples.
+    pub decimal_samples: Vec<String>,
+}
+
+impl Rule {
+    /// The expanded `@integer` samples followed by the `@decimal`
+    /// samples. Each can be parsed as `PluralOperands`.
+    pub fn samples(&self) -> impl Iterator<Item = &str> {
+        self.integer_samples.iter().chain(&self.decimal_samples).map(String::as_str)
+    }
+}
+
+/// Parse a rule, including its samples.
+///
+/// ```
+/// use message_format::PluralOperands;
+/// use message_format::plural_rules::syntax::parse_rule;
+///
+/// let rule = parse_rule("i = 1 and v = 0 @integer 1").unwrap();
+/// assert!(rule.condition.matches(&PluralOperands::from(1)));
+/// assert_eq!(rule.integer_samples, ["1"]);
+/// ```
+pub fn parse_rule(source: &str) -> Result<Rule, RuleParseError> {
+    let condition_end = source.find('@').unwrap_or(source.len());
+    let mut parser = Parser {
+        source,
+        end: condition_end,
+        pos: 0,
+    };
+    let condition = parser.get_condition()?;
+
+    let mut integer_samples = vec![];
+    let mut decimal_samples = vec![];
+    let mut offset = condition_end;
+    for section in source[condition_end..].split('@').skip(1) {
+        // Errors in the samples are reported at the `@`.
+        let invalid = || RuleParseError::InvalidRule(source.to_string(), offset);
+        let (samples, list) = if let Some(list) = section.strip_prefix("integer") {
+            (&mut integer_samples, list)
+        } else if let Some(list) = section.strip_prefix("decimal") {
+            (&mut decimal_samples, list)
+        } else {
+            return Err(invalid());
+        };
+        for sample in list.split(',').map(str::trim) {
+            if !sample.is_empty() && sample != "…" {
+                samples.extend(expand_sample(sample).ok_or_else(invalid)?);
+            }
+        }
+        offset += section.len() + 1;
+    }
+
+    Ok(Rule {
+        condition,
+        integer_samples,
+        decimal_samples,
+    })
+}
+
+/// Expand a sample range like `0.0~1.5` into each of its values. The
+/// step is the last digit of the start of the range, and a range with
+/// more than `MAX_SAMPLE_RANGE` values is invalid.
+fn expand_sample(sample: &str) -> Option<Vec<String>> {
+    let pos = match sample.find('~') {
+        Some(pos) => pos,
+        None => return Some(vec![sample.to_string()]),
+    };
+    let (start, end) = (&sample[..pos], &sample[pos + 1..]);
+    let digits = |value: &str| value.find('.').map_or(0, |pos| value.len() - pos - 1);
+    let v = digits(start);
+    if digits(end) != v {
+        return None;
+    }
+    let scaled = |value: &str| value.replace('.', "").parse::<u64>().ok();
+    let scale = 10u64.checked_pow(v as u32)?;
+    let (start, end) = (scaled(start)?, scaled(end)?);
+    if end.saturating_sub(start) >= MAX_SAMPLE_RANGE {
+        return None;
+    }
+    Some((start..=end)
+        .map(|value| if v == 0 {
+            value.to_string()
+        } else {
+            format!("{}.{:0width$}", value / scale, value % scale, width = v)
+        })
+        .collect())
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    /// The end of the condition, where the samples start.
+    end: usize,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn ch(&self) -> Option<char> {
+        self.source[self.pos..self.end].chars().next()
+    }
+
+    fn get_ws(&mut self) {
+        while let Some(ch) = self.ch().filter(|ch| ch.is_whitespace()) {
+            self.pos += ch.len_utf8();
+        }
+    }
+
+    /// Consume `token` if it is next, after any whitespace.
+    fn take(&mut self, token: &str) -> bool {
+        self.get_ws();
+        if self.source[self.pos..self.end].starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self) -> RuleParseError {
+        RuleParseError::InvalidRule(self.source.to_string(), self.pos)
+    }
+
+    fn get_condition(&mut self) -> Result<Condition, RuleParseError> {
+        let mut condition = vec![];
+        self.get_ws();
+        if self.ch().is_none() {
+            return Ok(Condition(condition));
+        }
+        loop {
+            let mut relations = vec![self.get_relation()?];
+            while self.take("and") {
+                relations.push(self.get_relation()?);
+            }
+            condition.push(relations);
+            if !self.take("or") {
+                break;
+            }
+        }
+        self.get_ws();
+        match self.ch() {
+            None => Ok(Condition(condition)),
+            Some(_) => Err(self.error()),
+        }
+    }
+
+    fn get_relation(&mut self) -> Result<Relation, RuleParseError> {
+        self.get_ws();
+        let operand = match self.ch() {
+            Some('n') => Operand::N,
+            Some('i') => Operand::I,
+            Some('v') => Operand::V,
+            Some('w') => Operand::W,
+            Some('f') => Operand::F,
+            Some('t') => Operand::T,
+            Some('c') | Some('e') => Operand::E,
+            _ => return Err(self.error()),
+        };
+        self.pos += 1;
+        let modulus = if self.take("%") { Some(self.get_modulus()?) } else { None };
+        let negated = if self.take("!=") {
+            true
+        } else if self.take("=") {
+            false
+        } else {
+            return Err(self.error());
+        };
+        let mut ranges = vec![];
+        loop {
+            let start = self.get_value()?;
+            let end = if self.take("..") { self.get_value()? } else { start };
+            ranges.push((start, end));
+            if !self.take(",") {
+                break;
+            }
+        }
+        Ok(Relation {
+            operand,
+            modulus,
+            negated,
+            ranges,
+        })
+    }
+
+    /// Parse the modulus after a `%`, which can't be zero.
+    fn get_modulus(&mut self) -> Result<u64, RuleParseError> {
+        self.get_ws();
+        let start = self.pos;
+        match self.get_value()? {
+            0 => Err(RuleParseError::InvalidRule(self.source.to_string(), start)),
+            modulus => Ok(modulus),
+        }
+    }
+
+    fn get_value(&mut self) -> Result<u64, RuleParseError> {
+        self.get_ws();
+        let start = self.pos;
+        while self.ch().is_some_and(|ch| ch.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.source[start..self.pos].parse().map_err(|_| self.error())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_sample, parse_rule, Condition, Operand, Relation, RuleParseError};
+    use PluralOperands;
+
+    fn relation(operand: Operand,
+                modulus: Option<u64>,
+                negated: bool,
+                ranges: &[(u64, u64)])
+                -> Relation {
+        Relation {
+            operand,
+            modulus,
+            negated,
+            ranges: ranges.to_vec(),
+        }
+    }
+
+    #[test]
+    fn relations_and_conditions() {
+        let rule = parse_rule("n % 10 = 1 and n % 100 != 11 or v = 0 and i = 2..4,7").unwrap();
+        assert_eq!(rule.condition,
+                   Condition(vec![vec![relation(Operand::N, Some(10), false, &[(1, 1)]),
+                                       relation(Operand::N, Some(100), true, &[(11, 11)])],
+                                  vec![relation(Operand::V, None, false, &[(0, 0)]),
+                                       relation(Operand::I, None, false, &[(2, 4), (7, 7)])]]));
+    }
+
+    #[test]
+    fn operands() {
+        let rule = parse_rule("e = 0 and c != 0..5 or w = 1 or f = 1 or t = 1").unwrap();
+        let operands = rule.condition.0.iter().flat_map(|and| and.iter().map(|r| r.operand));
+        assert_eq!(operands.collect::<Vec<_>>(),
+                   [Operand::E, Operand::E, Operand::W, Operand::F, Operand::T]);
+    }
+
+    #[test]
+    fn samples() {
+        let rule = parse_rule("i = 1 and v = 0 @integer 1, 21~23, … @decimal 0.0~0.2, 1.0c3, …")
+            .unwrap();
+        assert_eq!(rule.integer_samples, ["1", "21", "22", "23"]);
+        assert_eq!(rule.decimal_samples, ["0.0", "0.1", "0.2", "1.0c3"]);
+        assert_eq!(rule.samples().collect::<Vec<_>>(),
+                   ["1", "21", "22", "23", "0.0", "0.1", "0.2", "1.0c3"]);
+    }
+
+    #[test]
+    fn other_is_empty() {
+        let rule = parse_rule(" @integer 0, 2~4").unwrap();
+        assert_eq!(rule.condition, Condition(vec![]));
+        assert!(rule.condition.matches(&PluralOperands::from(5)));
+        assert_eq!(rule.integer_samples, ["0", "2", "3", "4"]);
+    }
+
+    #[test]
+    fn sample_ranges_use_last_digit() {
+        assert_eq!(expand_sample("0.00~0.03").unwrap(), ["0.00", "0.01", "0.02", "0.03"]);
+        assert_eq!(expand_sample("1.8~2.1").unwrap(), ["1.8", "1.9", "2.0", "2.1"]);
+        assert!(expand_sample("1.0~2").is_none());
+        assert_eq!(expand_sample("0~999").unwrap().len(), 1000);
+        assert!(expand_sample("0~1000").is_none());
+        assert!(expand_sample("0~999999999").is_none());
+    }
+
+    #[test]
+    fn unicode_whitespace() {
+        let rule = parse_rule("i\u{a0}=\u{2003}1").unwrap();
+        assert_eq!(rule.condition,
+                   Condition(vec![vec![relation(Operand::I, None, false, &[(1, 1)])]]));
+    }
+
+    #[test]
+    fn matching() {
+        let rule = parse_rule("n % 10 = 2..4 and n % 100 != 12..14").unwrap();
+        let matches = |n| rule.condition.matches(&PluralOperands::from(n));
+        assert!(matches(2) && matches(24) && matches(102));
+        assert!(!matches(12) && !matches(5) && !matches(0));
+        let fraction = PluralOperands { n: 2.5, i: 2, v: 1, w: 1, f: 5, t: 5, e: 0 };
+        assert!(!rule.condition.matches(&fraction));
+    }
+
+    #[test]
+    fn invalid_rules() {
+        let invalid = |rule: &str, offset| {
+            Err(RuleParseError::InvalidRule(rule.to_string(), offset))
+        };
+        assert_eq!(parse_rule("x = 1"), invalid("x = 1", 0));
+        assert_eq!(parse_rule("n = "), invalid("n = ", 4));
+        assert_eq!(parse_rule("n > 1"), invalid("n > 1", 2));
+        assert_eq!(parse_rule("n = 1 nor n = 2"), invalid("n = 1 nor n = 2", 6));
+        assert_eq!(parse_rule("n = 1 @float 1"), invalid("n = 1 @float 1", 6));
+        assert_eq!(parse_rule("n % 0 = 1"), invalid("n % 0 = 1", 4));
+        assert_eq!(parse_rule("i = 1 @integer 0~999999999"),
+                   invalid("i = 1 @integer 0~999999999", 6));
+    }
+}
@@ -0,0 +1,42 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The Prelude
+//!
+//! This re-exports the types, traits and functions that most code
+//! using the crate needs, so that they can be imported together:
+//!
+//! ```
+//! use message_format::prelude::*;
+//!
+//! let ctx = Context::default();
+//! let m = parse("{name} has {count, plural, one {# photo} other {# photos}}.").unwrap();
+//! assert_eq!(ctx.format(&m, Some(&arg("name", "Jo").arg("count", 3))), "Jo has 3 photos.");
+//!
+//! let mut photos = PluralFormat::new("count", parse("{count} photos").unwrap());
+//! photos.one(parse("{count} photo").unwrap());
+//! let m = Message::new(vec![Box::new(SimpleFormat::new("name")),
+//!                           Box::new(PlainText::new(" has ")),
+//!                           Box::new(photos),
+//!                           Box::new(PlainText::new("."))]);
+//! assert_eq!(ctx.format(&m, Some(&arg("name", "Jo").arg("count", 1))), "Jo has 1 photo.");
+//!
+//! let mut bundle = MessageBundle::new(Context::new(Locale::new("de").unwrap(), None));
+//! bundle.insert("greeting", "Hallo, {name}!").unwrap();
+//! assert_eq!(bundle.format("greeting", Some(&arg("name", Value::Str("Jo")))).unwrap(),
+//!            "Hallo, Jo!");
+//! ```
+//!
+//! The macros, like `format_message!`, are at the root of the crate,
+//! and are imported with `#[macro_use]`.
+
+pub use icu::ast::{NumberFormat, PlaceholderFormat, PlainText, PluralFormat, SelectFormat,
+                   SimpleFormat};
+pub use icu::{parse, ParseError};
+pub use {arg, Args, AsValue, Context, FormatError, Locale, Message, MessagePart, PluralCategory,
+         PluralClassifier, PluralOperands, Value};
+#[cfg(feature = "std")]
+pub use {BundleError, MessageBundle};
@@ -0,0 +1,202 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A small reader for Java `.properties` files.
+//!
+//! This follows `java.util.Properties::load`: lines ending in an odd
+//! number of `\` continue on the next line, `#` and `!` start comments,
+//! keys end at the first unescaped `=`, `:` or whitespace, and
+//! whitespace is trimmed from the start of lines and around the
+//! separator, but kept at the end of values.
+
+use std::error::Error;
+use std::fmt;
+
+/// A key and value, with the `line` that it starts on, counting
+/// from 1.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Property {
+    pub line: usize,
+    pub key: String,
+    pub value: String,
+}
+
+/// An error in the property starting on `line`.
+#[derive(Clone,Debug,PartialEq)]
+pub struct PropertiesError {
+    pub line: usize,
+    pub message: &'static str,
+}
+
+impl Error for PropertiesError {}
+
+impl fmt::Display for PropertiesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{} on line {}.", self.message, self.line)
+    }
+}
+
+/// Decode ISO-8859-1, where each byte is the code point of a character.
+pub fn decode_latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| char::from(b)).collect()
+}
+
+/// Parse the properties in `source`, in order. A malformed property
+/// is an error without stopping the others from being read.
+pub fn parse(source: &str) -> Vec<Result<Property, PropertiesError>> {
+    let mut properties = vec![];
+    let mut lines = source.lines().enumerate();
+    while let Some((index, line)) = lines.next() {
+        let line = trim_start(line);
+        if line.is_empty() || line.starts_with(['#', '!']) {
+            continue;
+        }
+        let mut logical = line.to_string();
+        while ends_with_escape(&logical) {
+            logical.pop();
+            match lines.next() {
+                Some((_, next)) => logical.push_str(trim_start(next)),
+                None => break,
+            }
+        }
+        properties.push(parse_property(index + 1, &logical));
+    }
+    properties
+}
+
+fn trim_start(line: &str) -> &str {
+    line.trim_start_matches([' ', '\t', '\x0c'])
+}
+
+/// Whether a line ends with an odd number of `\`, so it continues on the
+/// next line.
+fn ends_with_escape(line: &str) -> bool {
+    line.chars().rev().take_while(|&c| c == '\\').count() % 2 == 1
+}
+
+fn parse_property(line: usize, logical: &str) -> Result<Property, PropertiesError> {
+    let error = |message| PropertiesError { line, message };
+    let mut chars = logical.chars();
+    let mut key = String::new();
+    let mut separated = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescape(&mut chars, &mut key).map_err(error)?,
+            '=' | ':' => {
+                separated = true;
+                break;
+            }
+            ' ' | '\t' | '\x0c' => break,
+            _ => key.push(c),
+        }
+    }
+    let mut rest = trim_start(chars.as_str());
+    if !separated && rest.starts_with(['=', ':']) {
+        rest = trim_start(&rest[1..]);
+    }
+    let mut chars = rest.chars();
+    let mut value = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescape(&mut chars, &mut value).map_err(error)?,
+            _ => value.push(c),
+        }
+    }
+    Ok(Property { line, key, value })
+}
+
+/// Read the escape after a `\` into `output`.
+fn unescape(chars: &mut ::std::str::Chars, output: &mut String) -> Result<(), &'static str> {
+    match chars.next() {
+        Some('t') => output.push('\t'),
+        Some('n') => output.push('\n'),
+        Some('r') => output.push('\r'),
+        Some('f') => output.push('\x0c'),
+        Some('u') => {
+            let unit = code_unit(chars)?;
+            let c = match unit {
+                0xd800..=0xdbff => {
+                    // A surrogate pair is written as two escapes.
+                    if !chars.as_str().starts_with("\\u") {
+                        return Err("Unpaired surrogate in \\u escape");
+                    }
+                    chars.nth(1);
+                    let low = code_unit(chars)?;
+                    char::decode_utf16([unit, low]).next().and_then(Result::ok)
+                }
+                _ => char::from_u32(u32::from(unit)),
+            };
+            output.push(c.ok_or("Unpaired surrogate in \\u escape")?);
+        }
+        Some(c) => output.push(c),
+        None => {}
+    }
+    Ok(())
+}
+
+/// Read the four hex digits of a `\u` escape.
+fn code_unit(chars: &mut ::std::str::Chars) -> Result<u16, &'static str> {
+    let digits = chars.as_str().get(..4).ok_or("Invalid \\u escape")?;
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("Invalid \\u escape");
+    }
+    chars.nth(3);
+    Ok(u16::from_str_radix(digits, 16).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_latin1, parse, PropertiesError, Property};
+
+    fn property(line: usize, key: &str, value: &str) -> Result<Property, PropertiesError> {
+        Ok(Property { line, key: key.to_string(), value: value.to_string() })
+    }
+
+    #[test]
+    fn it_works() {
+        let source = "# A comment\n\
+                      ! Another\n\
+                      \n\
+                      a=1\n\
+                      \x20 b : two words  \n\
+                      c three\n\
+                      d\n\
+                      e\\=f\\:g=h\\\\\n\
+                      long = first, \\\n\
+                      \x20      second\n";
+        assert_eq!(parse(source),
+                   vec![property(4, "a", "1"),
+                        property(5, "b", "two words  "),
+                        property(6, "c", "three"),
+                        property(7, "d", ""),
+                        property(8, "e=f:g", "h\\"),
+                        property(9, "long", "first, second")]);
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(parse("k=\\u00e9\\t\\n\\q\\uD83D\\uDE00\r\nk\\ 2=x"),
+                   vec![property(1, "k", "é\t\nq😀"), property(2, "k 2", "x")]);
+        // A comment doesn't continue on the next line.
+        assert_eq!(parse("# \\\nk=v"), vec![property(2, "k", "v")]);
+        // A final continuation ends the value.
+        assert_eq!(parse("k=v\\"), vec![property(1, "k", "v")]);
+    }
+
+    #[test]
+    fn errors() {
+        let error = |line, message| Err(PropertiesError { line, message });
+        assert_eq!(parse("a=\\u12\nb=\\uD800x\nc=ok"),
+                   vec![error(1, "Invalid \\u escape"),
+                        error(2, "Unpaired surrogate in \\u escape"),
+                        property(3, "c", "ok")]);
+    }
+
+    #[test]
+    fn latin1() {
+        assert_eq!(decode_latin1(b"caf\xe9 \xa3"), "café £");
+    }
+}
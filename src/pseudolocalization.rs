@@ -0,0 +1,221 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::iter;
+use std::string::{String, ToString};
+
+/// Whether messages are pseudolocalized when they are formatted, which
+/// shows which text comes from messages and where each argument goes.
+///
+/// When pseudolocalizing, each argument is written as its name in
+/// brackets, like `[name]`, whatever its value, and a `#` in a plural
+/// is written as `[#]`. Arguments are still used to select the branch
+/// of a `plural` or `select`.
+///
+/// ```
+/// use message_format::{arg, icu, Context, Pseudolocalization};
+///
+/// let ctx = Context::builder().pseudolocalization(Pseudolocalization::Full).build().unwrap();
+/// let m = icu::parse("Hello {name}").unwrap();
+/// assert_eq!(ctx.format(&m, Some(&arg("name", "Jane"))), "Ⓗⓔⓛⓛⓞ [name]");
+/// ```
+#[derive(Clone,Copy,Debug,PartialEq,Default)]
+pub enum Pseudolocalization {
+    /// Messages are formatted as usual.
+    #[default]
+    Off,
+    /// Arguments are written as their names, and the text of messages
+    /// is unchanged.
+    Arguments,
+    /// Arguments are written as their names, and the letters and digits
+    /// in the text of messages are circled, as in `Ⓗⓔⓛⓛⓞ`.
+    Full,
+}
+
+impl Pseudolocalization {
+    /// Whether arguments are written as their names.
+    pub fn is_on(self) -> bool {
+        self != Pseudolocalization::Off
+    }
+
+    /// Write text from a message to a stream, transforming it as needed.
+    pub fn write_text(self, stream: &mut dyn fmt::Write, text: &str) -> fmt::Result {
+        if self != Pseudolocalization::Full {
+            return stream.write_str(text);
+        }
+        for ch in text.chars() {
+            stream.write_char(circled(ch))?;
+        }
+        Ok(())
+    }
+}
+
+/// How [`Message::pseudo_localize`] transforms the text of a message.
+///
+/// By default, letters are accented, text is made 30% longer and each
+/// message is wrapped in `[` and `]`.
+///
+/// [`Message::pseudo_localize`]: struct.Message.html#method.pseudo_localize
+#[derive(Clone,Debug,PartialEq)]
+pub struct PseudoLocalizeOptions {
+    /// Whether ASCII letters are replaced with accented forms, as in
+    /// `Ĥéļļö`, which shows text that isn't localized.
+    pub accents: bool,
+    /// The length of text relative to the original, which is padded
+    /// with `~` to reach it. Translations are often longer than English,
+    /// so a factor like `1.3` shows where text would be cut off.
+    pub expansion: f64,
+    /// The text put before and after each message, which shows where
+    /// messages are cut off or joined together.
+    pub markers: Option<(String, String)>,
+}
+
+impl Default for PseudoLocalizeOptions {
+    fn default() -> Self {
+        PseudoLocalizeOptions {
+            accents: true,
+            expansion: 1.3,
+            markers: Some(("[".to_string(), "]".to_string())),
+        }
+    }
+}
+
+impl PseudoLocalizeOptions {
+    /// Transform a run of text from a message. Only the characters
+    /// other than whitespace count towards the expansion, and the
+    /// padding goes before any trailing whitespace.
+    pub(crate) fn transform(&self, text: &str) -> String {
+        let trimmed = text.trim_end();
+        let mut output = if self.accents {
+            trimmed.chars().map(accented).collect()
+        } else {
+            trimmed.to_string()
+        };
+        let length = trimmed.chars().filter(|ch| !ch.is_whitespace()).count();
+        // Rounded to the nearest length, as `f64::round` is only in `std`.
+        let padding = length as f64 * (self.expansion - 1.0) + 0.5;
+        if padding >= 1.0 {
+            output.extend(iter::repeat_n('~', padding as usize));
+        }
+        output.push_str(&text[trimmed.len()..]);
+        output
+    }
+}
+
+/// The accented form of an ASCII letter.
+fn accented(ch: char) -> char {
+    const LOWER: [char; 26] = ['å', 'ƀ', 'ç', 'ð', 'é', 'ƒ', 'ĝ', 'ĥ', 'î', 'ĵ', 'ķ', 'ļ', 'ɱ', 'ñ',
+                               'ö', 'þ', 'ǫ', 'ŕ', 'š', 'ţ', 'û', 'ṽ', 'ŵ', 'ẋ', 'ý', 'ž'];
+    const UPPER: [char; 26] = ['Å', 'Ɓ', 'Ç', 'Ð', 'É', 'Ƒ', 'Ĝ', 'Ĥ', 'Î', 'Ĵ', 'Ķ', 'Ļ', 'Ṁ', 'Ñ',
+                               'Ö', 'Þ', 'Ǫ', 'Ŕ', 'Š', 'Ţ', 'Û', 'Ṽ', 'Ŵ', 'Ẋ', 'Ý', 'Ž'];
+    match ch {
+        'a'..='z' => LOWER[ch as usize - 'a' as usize],
+        'A'..='Z' => UPPER[ch as usize - 'A' as usize],
+        _ => ch,
+    }
+}
+
+/// The circled form of an ASCII letter or digit.
+fn circled(ch: char) -> char {
+    let circled = match ch {
+        'A'..='Z' => 0x24b6 + (ch as u32 - 'A' as u32),
+        'a'..='z' => 0x24d0 + (ch as u32 - 'a' as u32),
+        '1'..='9' => 0x2460 + (ch as u32 - '1' as u32),
+        '0' => 0x24ea,
+        _ => return ch,
+    };
+    char::from_u32(circled).unwrap_or(ch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PseudoLocalizeOptions, Pseudolocalization};
+    use icu::parse;
+    use {arg, Context};
+
+    fn transform(pseudolocalization: Pseudolocalization, text: &str) -> String {
+        let mut output = String::new();
+        pseudolocalization.write_text(&mut output, text).unwrap();
+        output
+    }
+
+    #[test]
+    fn it_works() {
+        assert_eq!(transform(Pseudolocalization::Full, "Az az 0-9, é!"), "Ⓐⓩ ⓐⓩ ⓪-⑨, é!");
+        assert_eq!(transform(Pseudolocalization::Arguments, "Az"), "Az");
+        assert_eq!(transform(Pseudolocalization::Off, "Az"), "Az");
+        assert!(!Pseudolocalization::default().is_on());
+    }
+
+    #[test]
+    fn formatting() {
+        let m = parse("{name} has {count, plural, one {# file} other {# files}} \
+                       ({size, number}, {time, duration}, {count, number, spellout})")
+            .unwrap();
+        let name = arg("name", "Jo");
+        let count = name.arg("count", 2);
+        let size = count.arg("size", 1024);
+        let args = size.arg("time", 60);
+        let ctx = |pseudolocalization| Context { pseudolocalization, ..Context::default() };
+        assert_eq!(ctx(Pseudolocalization::Off).format(&m, Some(&args)),
+                   "Jo has 2 files (1,024, 1:00, two)");
+        assert_eq!(ctx(Pseudolocalization::Arguments).format(&m, Some(&args)),
+                   "[name] has [#] files ([size], [time], [count])");
+        let full = ctx(Pseudolocalization::Full);
+        assert_eq!(full.format(&m, Some(&args)),
+                   "[name] ⓗⓐⓢ [#] ⓕⓘⓛⓔⓢ ([size], [time], [count])");
+        // Only the branch needs an argument.
+        assert_eq!(full.format(&m, Some(&arg("count", 1))),
+                   "[name] ⓗⓐⓢ [#] ⓕⓘⓛⓔ ([size], [time], [count])");
+        let chunks = m.format_chunks(&full, Some(&args)).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(chunks[1], " ⓗⓐⓢ ");
+
+        let partial = m.partial(&full, &arg("name", "Jo"));
+        assert_eq!(ctx(Pseudolocalization::Off).format(&partial, Some(&args)),
+                   "Jo has 2 files (1,024, 1:00, two)");
+    }
+
+    #[test]
+    fn pseudo_localize() {
+        let ctx = Context::default();
+        let m = parse("Hello {name}, you have {count, plural, =0 {no files} one {# file} other \
+                       {# files}} in {dir, select, home {Home} other {{dir}}}.")
+            .unwrap();
+        let pseudo = m.pseudo_localize(&PseudoLocalizeOptions::default());
+        let args = arg("name", "Jo");
+        let count = args.arg("count", 2);
+        let args = count.arg("dir", "home");
+        assert_eq!(ctx.format(&pseudo, Some(&args)),
+                   "[Ĥéļļö~~ Jo, ýöû ĥåṽé~~ 2 ƒîļéš~~ îñ~ Ĥöɱé~.]");
+        let count = args.arg("count", 0);
+        let args = count.arg("dir", "tmp");
+        assert_eq!(ctx.format(&pseudo, Some(&args)),
+                   "[Ĥéļļö~~ Jo, ýöû ĥåṽé~~ ñö ƒîļéš~~ îñ~ tmp.]");
+        // The structure of the message is unchanged.
+        assert_eq!(pseudo.variables(), m.variables());
+    }
+
+    #[test]
+    fn pseudo_localize_options() {
+        let ctx = Context::default();
+        let m = parse("Hi {n, number} {n, plural, other {#x}}").unwrap();
+        let options = PseudoLocalizeOptions {
+            accents: false,
+            expansion: 1.0,
+            markers: None,
+        };
+        let args = arg("n", 1234);
+        assert_eq!(ctx.format(&m.pseudo_localize(&options), Some(&args)), "Hi 1,234 1234x");
+        let options = PseudoLocalizeOptions {
+            expansion: 2.0,
+            markers: Some(("⟦".to_string(), "⟧".to_string())),
+            ..PseudoLocalizeOptions::default()
+        };
+        assert_eq!(ctx.format(&m.pseudo_localize(&options), Some(&args)),
+                   "⟦Ĥî~~ 1,234 1234ẋ~⟧");
+    }
+}
@@ -0,0 +1,98 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+
+/// How control characters within argument values are handled.
+///
+/// This applies to the C0 and C1 control characters, including
+/// newlines and tabs, to the bidi embedding and override characters
+/// (`U+202A` through `U+202E`), which can be used to spoof the
+/// displayed text, and to the bidi isolation characters (`U+2066`
+/// through `U+2069`), with which a value could end the isolation
+/// around it. The isolation characters that this crate inserts around
+/// a value are not part of it, and are kept.
+///
+/// Only argument values are affected. The text of the message
+/// itself is never changed.
+///
+/// ```
+/// use message_format::{arg, icu, Context, Sanitization};
+///
+/// let ctx = Context::builder().sanitization(Sanitization::Replace(' ')).build().unwrap();
+/// let m = icu::parse("Hello, {name}!").unwrap();
+/// assert_eq!(ctx.format(&m, Some(&arg("name", "Jane\nDoe"))), "Hello, Jane Doe!");
+/// ```
+#[derive(Clone,Copy,Debug,PartialEq,Default)]
+pub enum Sanitization {
+    /// Argument values are written as they are.
+    #[default]
+    Off,
+    /// Control characters are removed.
+    Strip,
+    /// Control characters are replaced with the given character.
+    Replace(char),
+}
+
+/// Whether a character is removed or replaced by sanitization.
+fn is_unsafe(ch: char) -> bool {
+    ch.is_control() || ('\u{202a}'..='\u{202e}').contains(&ch) ||
+    ('\u{2066}'..='\u{2069}').contains(&ch)
+}
+
+impl Sanitization {
+    /// Write an argument value to a stream, sanitizing it as needed.
+    pub fn write_str(&self, stream: &mut dyn fmt::Write, value: &str) -> fmt::Result {
+        match *self {
+            Sanitization::Off => stream.write_str(value),
+            Sanitization::Strip => {
+                for ch in value.chars().filter(|&ch| !is_unsafe(ch)) {
+                    stream.write_char(ch)?;
+                }
+                Ok(())
+            }
+            Sanitization::Replace(replacement) => {
+                for ch in value.chars() {
+                    stream.write_char(if is_unsafe(ch) { replacement } else { ch })?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sanitization;
+
+    fn sanitize(sanitization: Sanitization, value: &str) -> String {
+        let mut output = String::new();
+        sanitization.write_str(&mut output, value).unwrap();
+        output
+    }
+
+    #[test]
+    fn off_leaves_values_alone() {
+        assert_eq!(sanitize(Sanitization::Off, "a\u{202e}b\nc"), "a\u{202e}b\nc");
+    }
+
+    #[test]
+    fn strip_removes_controls() {
+        assert_eq!(sanitize(Sanitization::Strip, "evil\u{202e}gnp.exe"), "evilgnp.exe");
+        assert_eq!(sanitize(Sanitization::Strip, "line\none\u{85}\u{1b}"), "lineone");
+    }
+
+    #[test]
+    fn replace_substitutes_controls() {
+        assert_eq!(sanitize(Sanitization::Replace('\u{fffd}'), "a\u{202a}b\nc"),
+                   "a\u{fffd}b\u{fffd}c");
+    }
+
+    #[test]
+    fn isolates_are_stripped() {
+        assert_eq!(sanitize(Sanitization::Strip, "\u{2066}abc\u{2069}\u{2067}\u{2068}"), "abc");
+    }
+}
@@ -0,0 +1,127 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+use {BundleError, MessageBundle, ReloadReport};
+
+/// A [`MessageBundle`] that can be reloaded while it is being used from
+/// other threads, such as to show edits to resource files during
+/// development. This requires the `serde_json` feature.
+///
+/// Readers take the current version of the bundle with [`load`]. A
+/// reload makes a new version and swaps it in, so readers are never
+/// blocked by parsing, and a version that has been loaded is never
+/// changed.
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use message_format::{Context, MessageBundle, SharedBundle};
+///
+/// let bundle = MessageBundle::from_json_str(Context::default(), r#"{"title": "Settings"}"#)
+///     .unwrap();
+/// let shared = Arc::new(SharedBundle::new(bundle));
+/// let reader = shared.clone();
+/// let handle = thread::spawn(move || {
+///     let title = reader.load().format("title", None).unwrap();
+///     assert!(title == "Settings" || title == "Preferences");
+/// });
+/// shared.reload_from(r#"{"title": "Preferences"}"#).unwrap();
+/// handle.join().unwrap();
+/// assert_eq!(shared.load().format("title", None).unwrap(), "Preferences");
+/// ```
+///
+/// [`MessageBundle`]: struct.MessageBundle.html
+/// [`load`]: #method.load
+pub struct SharedBundle {
+    current: RwLock<Arc<MessageBundle>>,
+    /// Held while reloading, so that reloads don't lose each other's
+    /// changes.
+    reloading: Mutex<()>,
+}
+
+impl SharedBundle {
+    /// Share a `bundle`.
+    pub fn new(bundle: MessageBundle) -> Self {
+        SharedBundle {
+            current: RwLock::new(Arc::new(bundle)),
+            reloading: Mutex::new(()),
+        }
+    }
+
+    /// The current version of the bundle.
+    pub fn load(&self) -> Arc<MessageBundle> {
+        self.current.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// Replace the bundle with a new version that is reloaded from
+    /// `json`, as by [`MessageBundle::reload_from`]. The messages that
+    /// are unchanged are shared with the previous version.
+    ///
+    /// [`MessageBundle::reload_from`]: struct.MessageBundle.html#method.reload_from
+    pub fn reload_from(&self, json: &str) -> Result<ReloadReport, BundleError> {
+        let _reloading = self.reloading.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut bundle = MessageBundle::clone(&self.load());
+        let report = bundle.reload_from(json)?;
+        *self.current.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(bundle);
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::SharedBundle;
+    use {arg, Context, MessageBundle};
+
+    #[test]
+    fn it_works() {
+        let bundle = MessageBundle::from_json_str(Context::default(), r#"{"a": "1", "b": "2"}"#)
+            .unwrap();
+        let shared = SharedBundle::new(bundle);
+        let before = shared.load();
+        let report = shared.reload_from(r#"{"a": "1", "b": "two", "c": "{"}"#).unwrap();
+        assert_eq!(report.changed, ["b"]);
+        assert_eq!(report.broken, ["c"]);
+        assert_eq!(before.format("b", None), Ok("2".to_string()));
+        assert_eq!(shared.load().format("b", None), Ok("two".to_string()));
+        assert!(shared.reload_from("[]").is_err());
+        assert_eq!(shared.load().len(), 2);
+    }
+
+    #[test]
+    fn readers_see_whole_versions() {
+        let shared = Arc::new(SharedBundle::new(MessageBundle::new(Context::default())));
+        let readers = (0..4)
+            .map(|_| {
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    for _ in 0..200 {
+                        let bundle = shared.load();
+                        if bundle.is_empty() {
+                            continue;
+                        }
+                        // Both messages are always from the same version.
+                        let a = bundle.format("a", Some(&arg("n", 1))).unwrap();
+                        let b = bundle.format("b", None).unwrap();
+                        assert_eq!(a[2..], b[..]);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for version in 0..50 {
+            let json = format!(r#"{{"a": "{{n}} {0}", "b": "{0}"}}"#, version);
+            shared.reload_from(&json).unwrap();
+        }
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(shared.load().format("b", None), Ok("49".to_string()));
+    }
+}
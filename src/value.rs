@@ -4,7 +4,13 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::convert::TryFrom;
 use std::fmt;
+use std::ptr;
+use std::string::ToString;
+
+use plural_operands::DecimalDigits;
+use {ParseOperandsError, PluralOperands};
 
 /// A wrapper around a value, used with [`Args`] so that a [`MessagePart`]
 /// can access the original value when necessary.
@@ -15,12 +21,192 @@ use std::fmt;
 /// [`Args`]: struct.Args.html
 /// [`AsValue`]: trait.AsValue.html
 /// [`MessagePart`]: trait.MessagePart.html
-#[derive(Debug,PartialEq)]
+#[derive(Clone,Copy)]
 pub enum Value<'a> {
     /// Wrap an `i64`.
     Number(i64),
+    /// Wrap an integer that doesn't fit in an `i64`, such as a large
+    /// `u64`. Integers that fit are a `Value::Number`, which is what
+    /// [`AsValue`] creates for them.
+    ///
+    /// ```
+    /// use message_format::{arg, icu, Context};
+    ///
+    /// let ctx = Context::default();
+    /// let msg = icu::parse("{n, plural, one {# byte} other {# bytes}}").unwrap();
+    /// assert_eq!(ctx.format(&msg, Some(&arg("n", u64::MAX))), "18446744073709551615 bytes");
+    /// ```
+    ///
+    /// [`AsValue`]: trait.AsValue.html
+    BigInt(i128),
+    /// Wrap an `f64` that is written with exactly `fraction_digits`
+    /// digits after the decimal point.
+    ///
+    /// The visible fraction digits also determine the plural category,
+    /// so in English, `1` uses `one` but `1.0` uses `other`:
+    ///
+    /// ```
+    /// use message_format::{arg, icu, Context, Value};
+    ///
+    /// let ctx = Context::default();
+    /// let msg = icu::parse("{n, plural, one {# mile} other {# miles}}").unwrap();
+    /// let one = Value::Decimal { value: 1.0, fraction_digits: 0 };
+    /// assert_eq!(ctx.format(&msg, Some(&arg("n", one))), "1 mile");
+    /// let one = Value::Decimal { value: 1.0, fraction_digits: 1 };
+    /// assert_eq!(ctx.format(&msg, Some(&arg("n", one))), "1.0 miles");
+    /// ```
+    Decimal {
+        /// The value.
+        value: f64,
+        /// The number of visible fraction digits. The value is rounded
+        /// to this many digits.
+        fraction_digits: u8,
+    },
+    /// Wrap a number written as a string, like `"1.50"`, which keeps
+    /// its visible fraction digits when it is written. They determine
+    /// the plural category, as described by [`PluralOperands`].
+    ///
+    /// ```
+    /// use message_format::{arg, icu, Context, DecimalStr};
+    ///
+    /// let ctx = Context::default();
+    /// let msg = icu::parse("{n, plural, one {# mile} other {# miles}}").unwrap();
+    /// let one = DecimalStr::new("1.0").unwrap();
+    /// assert_eq!(ctx.format(&msg, Some(&arg("n", one))), "1.0 miles");
+    /// ```
+    ///
+    /// [`PluralOperands`]: struct.PluralOperands.html
+    DecimalStr(DecimalStr<'a>),
     /// Wrap an `&str`.
     Str(&'a str),
+    /// Wrap a closure that computes the value when it is used, so that
+    /// a value that is expensive to compute is only computed if the part
+    /// of the message that uses it is formatted. The closure is called
+    /// each time that the value is used, including by `Message::partial`
+    /// to substitute it within branches.
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use message_format::{arg, icu, Context, Value};
+    ///
+    /// let ctx = Context::default();
+    /// let m = icu::parse("{signed_in, select, yes {{unread} unread} other {Sign in}}").unwrap();
+    /// let calls = AtomicUsize::new(0);
+    /// let unread = || {
+    ///     calls.fetch_add(1, Ordering::Relaxed);
+    ///     Value::Number(12)
+    /// };
+    /// let args = arg("unread", Value::Lazy(&unread));
+    /// assert_eq!(ctx.format(&m, Some(&args.arg("signed_in", "no"))), "Sign in");
+    /// assert_eq!(calls.load(Ordering::Relaxed), 0);
+    /// assert_eq!(ctx.format(&m, Some(&args.arg("signed_in", "yes"))), "12 unread");
+    /// assert_eq!(calls.load(Ordering::Relaxed), 1);
+    /// ```
+    Lazy(&'a (dyn Fn() -> Value<'static> + Sync)),
+}
+
+impl<'a> Value<'a> {
+    /// The value, computing it if it is a `Value::Lazy`. The result is
+    /// never a `Value::Lazy`.
+    pub fn resolve(self) -> Value<'a> {
+        let mut value = self;
+        while let Value::Lazy(compute) = value {
+            value = compute();
+        }
+        value
+    }
+}
+
+impl<'a> fmt::Debug for Value<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Value::Number(i) => f.debug_tuple("Number").field(&i).finish(),
+            Value::BigInt(i) => f.debug_tuple("BigInt").field(&i).finish(),
+            Value::Decimal { value, fraction_digits } => {
+                f.debug_struct("Decimal")
+                    .field("value", &value)
+                    .field("fraction_digits", &fraction_digits)
+                    .finish()
+            }
+            Value::DecimalStr(s) => f.debug_tuple("DecimalStr").field(&s.as_str()).finish(),
+            Value::Str(s) => f.debug_tuple("Str").field(&s).finish(),
+            Value::Lazy(_) => f.write_str("Lazy(..)"),
+        }
+    }
+}
+
+/// Values are equal if they are the same kind of value and are equal.
+/// A `Value::Lazy` is only equal to itself, as the closure isn't
+/// called to compare it.
+impl<'a> PartialEq for Value<'a> {
+    fn eq(&self, other: &Value<'a>) -> bool {
+        match (*self, *other) {
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::BigInt(a), Value::BigInt(b)) => a == b,
+            (Value::Decimal { value: a, fraction_digits: a_digits },
+             Value::Decimal { value: b, fraction_digits: b_digits }) => {
+                a == b && a_digits == b_digits
+            }
+            (Value::DecimalStr(a), Value::DecimalStr(b)) => a == b,
+            (Value::Str(a), Value::Str(b)) => a == b,
+            (Value::Lazy(a), Value::Lazy(b)) => ptr::addr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+/// A number written as a string, for a [`Value::DecimalStr`].
+///
+/// It is checked to be a number when it is made, as it is parsed by
+/// [`PluralOperands`], so it can be negative and can have an exponent,
+/// like `1.2c3`. It is written with the exponent applied, like `1200`.
+///
+/// ```
+/// use message_format::DecimalStr;
+///
+/// assert_eq!(DecimalStr::new("-1.20050c3").unwrap().to_string(), "-1200.50");
+/// assert!(DecimalStr::new("1,5").is_err());
+/// ```
+///
+/// [`Value::DecimalStr`]: enum.Value.html#variant.DecimalStr
+/// [`PluralOperands`]: struct.PluralOperands.html
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct DecimalStr<'a> {
+    source: &'a str,
+}
+
+impl<'a> DecimalStr<'a> {
+    /// Check that `source` is a number.
+    pub fn new(source: &'a str) -> Result<Self, ParseOperandsError> {
+        source.parse::<PluralOperands>()?;
+        Ok(DecimalStr { source })
+    }
+
+    /// The number as it was written.
+    pub fn as_str(&self) -> &'a str {
+        self.source
+    }
+
+    /// The plural operands of the number.
+    pub fn operands(&self) -> PluralOperands {
+        self.source.parse().unwrap_or(PluralOperands::from(0))
+    }
+
+    /// The value of the number, which is negative if it is written
+    /// with a `-`.
+    pub fn value(&self) -> f64 {
+        let n = self.operands().n;
+        if self.source.starts_with('-') { -n } else { n }
+    }
+}
+
+impl<'a> fmt::Display for DecimalStr<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match DecimalDigits::parse(self.source) {
+            Some(digits) => digits.fmt(f),
+            None => self.source.fmt(f),
+        }
+    }
 }
 
 /// Convert a value to a `Value` wrapper.
@@ -49,7 +235,16 @@ impl<'a> AsValue<'a> for i64 {
 
 impl<'a> AsValue<'a> for u64 {
     fn as_formattable(&self) -> Value<'a> {
-        Value::Number(*self as i64)
+        i128::from(*self).as_formattable()
+    }
+}
+
+impl<'a> AsValue<'a> for i128 {
+    fn as_formattable(&self) -> Value<'a> {
+        match i64::try_from(*self) {
+            Ok(value) => Value::Number(value),
+            Err(_) => Value::BigInt(*self),
+        }
     }
 }
 
@@ -59,6 +254,33 @@ impl<'a> AsValue<'a> for usize {
     }
 }
 
+/// An `f64` is a [`Value::Decimal`] with as many fraction digits as
+/// are needed to write it, so `1.0` has none and `1.5` has one.
+///
+/// [`Value::Decimal`]: enum.Value.html#variant.Decimal
+impl<'a> AsValue<'a> for f64 {
+    fn as_formattable(&self) -> Value<'a> {
+        let source = self.to_string();
+        let fraction_digits = source.find('.').map_or(0, |pos| source.len() - pos - 1);
+        Value::Decimal {
+            value: *self,
+            fraction_digits: fraction_digits.min(u8::MAX as usize) as u8,
+        }
+    }
+}
+
+impl<'a> AsValue<'a> for Value<'a> {
+    fn as_formattable(&self) -> Value<'a> {
+        *self
+    }
+}
+
+impl<'a> AsValue<'a> for DecimalStr<'a> {
+    fn as_formattable(&self) -> Value<'a> {
+        Value::DecimalStr(*self)
+    }
+}
+
 impl<'a> AsValue<'a> for &'a str {
     fn as_formattable(&self) -> Value<'a> {
         Value::Str(self)
@@ -70,7 +292,78 @@ impl<'a> fmt::Display for Value<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             Value::Number(i) => i.fmt(f),
+            Value::BigInt(i) => i.fmt(f),
+            Value::Decimal { value, fraction_digits } => {
+                write!(f, "{:.*}", fraction_digits as usize, value)
+            }
+            Value::DecimalStr(s) => s.fmt(f),
             Value::Str(s) => s.fmt(f),
+            Value::Lazy(_) => self.resolve().fmt(f),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use icu::parse;
+    use {arg, AsValue, Context, Value};
+
+    #[test]
+    fn decimals() {
+        assert_eq!(1.0.as_formattable(), Value::Decimal { value: 1.0, fraction_digits: 0 });
+        assert_eq!(2.25.as_formattable(), Value::Decimal { value: 2.25, fraction_digits: 2 });
+        assert_eq!(Value::Decimal { value: 1.0, fraction_digits: 2 }.to_string(), "1.00");
+        assert_eq!(Value::Decimal { value: -2.26, fraction_digits: 1 }.to_string(), "-2.3");
+    }
+
+    #[test]
+    fn big_integers() {
+        assert_eq!(5u64.as_formattable(), Value::Number(5));
+        assert_eq!(u64::MAX.as_formattable(), Value::BigInt(u64::MAX as i128));
+        assert_eq!((-5i128).as_formattable(), Value::Number(-5));
+        assert_eq!(i128::MIN.as_formattable(), Value::BigInt(i128::MIN));
+        assert_eq!(Value::BigInt(i128::MAX).to_string(),
+                   "170141183460469231731687303715884105727");
+    }
+
+    #[test]
+    fn lazy_values_are_only_computed_when_used() {
+        let ctx = Context::default();
+        let m = parse("{count, plural, =0 {Nothing to sync} one {# file ({size, number} KB)} \
+                       other {# files ({size, number} KB) in {dir}}}")
+            .unwrap();
+        let calls = AtomicUsize::new(0);
+        let size = || {
+            calls.fetch_add(1, Ordering::Relaxed);
+            Value::Number(2048)
+        };
+        let dir = || Value::Str("Documents");
+        let args = arg("size", Value::Lazy(&size));
+        let args = args.arg("dir", Value::Lazy(&dir));
+        assert_eq!(ctx.format(&m, Some(&args.arg("count", 0))), "Nothing to sync");
+        assert_eq!(calls.load(Ordering::Relaxed), 0);
+        assert_eq!(ctx.format(&m, Some(&args.arg("count", 3))),
+                   "3 files (2,048 KB) in Documents");
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn lazy_values_select_branches() {
+        let ctx = Context::default();
+        let m = parse("{n, plural, one {one} other {{n}}} {g, select, f {her} other {their}}")
+            .unwrap();
+        let n = || Value::Number(1);
+        let g = || Value::Str("f");
+        let args = arg("n", Value::Lazy(&n));
+        assert_eq!(ctx.format(&m, Some(&args.arg("g", Value::Lazy(&g)))), "one her");
+        let nested = || Value::Lazy(&|| Value::Number(7));
+        assert_eq!(Value::Lazy(&nested).resolve(), Value::Number(7));
+        assert_eq!(Value::Lazy(&nested).to_string(), "7");
+        assert_eq!(format!("{:?}", Value::Lazy(&n)), "Lazy(..)");
+        let lazy = Value::Lazy(&n);
+        assert_eq!(lazy, lazy);
+        assert_ne!(lazy, Value::Number(1));
+    }
+}
@@ -0,0 +1,212 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use {Message, MessageVisitor, PluralCategory};
+
+/// How a variable is used by a message.
+#[derive(Clone,Debug,PartialEq,Eq,PartialOrd,Ord)]
+pub enum VariableKind {
+    /// The value is written as it is, as in `{name}`.
+    Plain,
+    /// The value is written by a `number` format.
+    Number,
+    /// The value is written by a `duration` format.
+    Duration,
+    /// The value is written by a `relativetime` format.
+    RelativeTime,
+    /// The value is spelled out, as in `{n, number, spellout}`.
+    Spellout,
+    /// The value selects a branch of a `choice`.
+    Choice,
+    /// The value selects a branch of a `plural`.
+    Plural,
+    /// The value selects a branch of a `selectordinal`.
+    SelectOrdinal,
+    /// The value selects a branch of a `select`.
+    Select {
+        /// The keys of the branches, in sorted order, not including
+        /// `other`. If the variable has several selects, these are the
+        /// keys of all of them.
+        keys: Vec<String>,
+    },
+}
+
+impl fmt::Display for VariableKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            VariableKind::Plain => "plain".fmt(f),
+            VariableKind::Number => "number".fmt(f),
+            VariableKind::Duration => "duration".fmt(f),
+            VariableKind::RelativeTime => "relativetime".fmt(f),
+            VariableKind::Spellout => "spellout".fmt(f),
+            VariableKind::Choice => "choice".fmt(f),
+            VariableKind::Plural => "plural".fmt(f),
+            VariableKind::SelectOrdinal => "selectordinal".fmt(f),
+            VariableKind::Select { .. } => "select".fmt(f),
+        }
+    }
+}
+
+/// A variable of a message, as found by [`Message::variables`].
+///
+/// [`Message::variables`]: struct.Message.html#method.variables
+#[derive(Clone,Debug,PartialEq)]
+pub struct VariableInfo {
+    /// The name of the variable.
+    pub name: String,
+    /// Each way that the variable is used, in sorted order.
+    pub kinds: Vec<VariableKind>,
+    /// Whether the variable is used within a branch of a `plural`,
+    /// `select` or `choice`.
+    pub nested: bool,
+}
+
+/// Collects the variables visited in a message, in the order that they
+/// are first found.
+#[derive(Default)]
+pub struct Variables {
+    variables: Vec<VariableInfo>,
+    depth: usize,
+}
+
+impl Variables {
+    pub fn of(message: &Message) -> Vec<VariableInfo> {
+        let mut variables = Variables::default();
+        message.accept(&mut variables);
+        variables.variables
+    }
+
+    fn add(&mut self, name: &str, kind: VariableKind) {
+        let nested = self.depth > 0;
+        let index = match self.variables.iter().position(|variable| variable.name == name) {
+            Some(index) => index,
+            None => {
+                self.variables.push(VariableInfo {
+                    name: name.to_string(),
+                    kinds: vec![],
+                    nested,
+                });
+                self.variables.len() - 1
+            }
+        };
+        let variable = &mut self.variables[index];
+        variable.nested |= nested;
+        if let VariableKind::Select { keys: ref new } = kind {
+            // The keys of all of the selects on a variable are merged.
+            let existing = variable.kinds.iter_mut().find_map(|kind| match *kind {
+                VariableKind::Select { ref mut keys } => Some(keys),
+                _ => None,
+            });
+            if let Some(keys) = existing {
+                keys.extend(new.iter().cloned());
+                keys.sort();
+                keys.dedup();
+                return;
+            }
+        }
+        if !variable.kinds.contains(&kind) {
+            variable.kinds.push(kind);
+            variable.kinds.sort();
+        }
+    }
+}
+
+impl MessageVisitor for Variables {
+    fn visit_variable(&mut self, name: &str) {
+        self.add(name, VariableKind::Plain);
+    }
+
+    fn visit_number(&mut self, name: &str) {
+        self.add(name, VariableKind::Number);
+    }
+
+    fn visit_duration(&mut self, name: &str) {
+        self.add(name, VariableKind::Duration);
+    }
+
+    fn visit_relative_time(&mut self, name: &str) {
+        self.add(name, VariableKind::RelativeTime);
+    }
+
+    fn visit_spellout(&mut self, name: &str) {
+        self.add(name, VariableKind::Spellout);
+    }
+
+    fn visit_choice(&mut self, name: &str) {
+        self.add(name, VariableKind::Choice);
+    }
+
+    fn visit_plural(&mut self, variable: &str, _categories: &[PluralCategory], _literals: &[i64]) {
+        self.add(variable, VariableKind::Plural);
+    }
+
+    fn visit_selectordinal(&mut self,
+                           variable: &str,
+                           _categories: &[PluralCategory],
+                           _literals: &[i64]) {
+        self.add(variable, VariableKind::SelectOrdinal);
+    }
+
+    fn visit_select(&mut self, variable: &str, keys: &[&str]) {
+        let keys = keys.iter().map(|key| key.to_string()).collect();
+        self.add(variable, VariableKind::Select { keys });
+    }
+
+    fn enter_branch(&mut self) {
+        self.depth += 1;
+    }
+
+    fn leave_branch(&mut self) {
+        self.depth -= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VariableInfo, VariableKind};
+    use icu::parse;
+
+    fn variable(name: &str, kinds: Vec<VariableKind>, nested: bool) -> VariableInfo {
+        VariableInfo { name: name.to_string(), kinds, nested }
+    }
+
+    fn select(keys: &[&str]) -> VariableKind {
+        VariableKind::Select { keys: keys.iter().map(|key| key.to_string()).collect() }
+    }
+
+    #[test]
+    fn it_works() {
+        let m = parse("{name} has {size, number} MB in {count, plural, one {one file} other \
+                       {{count} files in {dir}}}, {rank, selectordinal, one {#st} other \
+                       {#th}} for {time, duration}, {n, number, spellout} {c, choice, \
+                       0#none|1#{count}}")
+            .unwrap();
+        assert_eq!(m.variables(),
+                   [variable("name", vec![VariableKind::Plain], false),
+                    variable("size", vec![VariableKind::Number], false),
+                    variable("count", vec![VariableKind::Plain, VariableKind::Plural], true),
+                    variable("dir", vec![VariableKind::Plain], true),
+                    variable("rank", vec![VariableKind::SelectOrdinal], false),
+                    variable("time", vec![VariableKind::Duration], false),
+                    variable("n", vec![VariableKind::Spellout], false),
+                    variable("c", vec![VariableKind::Choice], false)]);
+    }
+
+    #[test]
+    fn selects_are_merged() {
+        let m = parse("{g, select, f {x} m {{g}} other {{h, select, a {} other {}}}} \
+                       {g, select, n {y} f {z} other {}}")
+            .unwrap();
+        assert_eq!(m.variables(),
+                   [variable("g", vec![VariableKind::Plain, select(&["f", "m", "n"])], true),
+                    variable("h", vec![select(&["a"])], true)]);
+        assert_eq!(select(&[]).to_string(), "select");
+    }
+}
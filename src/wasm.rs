@@ -0,0 +1,96 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! WebAssembly bindings, with the `wasm` feature.
+//!
+//! [`JsMessage`] parses and formats ICU messages in a browser with the
+//! same code as on a server, so that they are formatted the same way:
+//!
+//! ```js
+//! import { JsMessage } from "message-format";
+//!
+//! const m = JsMessage.parse("{count, plural, one {# file} other {# files}}");
+//! m.format({ count: 3 }); // "3 files"
+//! ```
+//!
+//! The arguments are converted as by [`json_args`], so they are the
+//! same as those of a server that has them as JSON.
+//!
+//! The bindings are exported by a `cdylib` that is built for
+//! `wasm32-unknown-unknown` and depends upon this crate with the `wasm`
+//! feature, for `wasm-bindgen` or `wasm-pack` to package. They are
+//! tested in node with `wasm-bindgen-test-runner`:
+//!
+//! ```text
+//! cargo test --target wasm32-unknown-unknown --features wasm --test wasm
+//! ```
+//!
+//! [`JsMessage`]: struct.JsMessage.html
+//! [`json_args`]: ../fn.json_args.html
+
+use js_sys::{Error, Reflect, JSON};
+use serde_json;
+use wasm_bindgen::prelude::*;
+
+use {icu, json_args, Context, JsonArgsOptions, Locale, Message};
+
+/// A parsed ICU message, with the locale that it is formatted for.
+#[wasm_bindgen]
+pub struct JsMessage {
+    message: Message,
+    context: Context,
+}
+
+#[wasm_bindgen]
+impl JsMessage {
+    /// Parse an ICU message, formatted for `locale`, or `en-US` if
+    /// there isn't one.
+    ///
+    /// An invalid message throws an `Error` whose `offset` is the
+    /// index in `source` of the problem, as for JavaScript strings, and
+    /// whose `byteOffset` is its offset in UTF-8.
+    pub fn parse(source: &str, locale: Option<String>) -> Result<JsMessage, JsValue> {
+        let context = match locale {
+            Some(locale) => {
+                let locale = Locale::new(&locale).map_err(|e| error(&e.to_string()))?;
+                Context::new(locale, None)
+            }
+            None => Context::default(),
+        };
+        match icu::parse(source) {
+            Ok(message) => Ok(JsMessage { message, context }),
+            Err(e) => {
+                let error = error(&e.to_string());
+                Reflect::set(&error, &"offset".into(), &(e.utf16_offset as f64).into())?;
+                Reflect::set(&error, &"byteOffset".into(), &(e.offset as f64).into())?;
+                Err(error)
+            }
+        }
+    }
+
+    /// Format the message with the properties of `args` as arguments.
+    ///
+    /// Strings and numbers are their values, and booleans are the
+    /// strings `true` and `false`. The properties of nested objects are
+    /// named by the path to them, like `user.name`. Properties that are
+    /// `null` or `undefined` are missing arguments, and `args` can be
+    /// left out if there are none. Arrays throw an `Error`.
+    pub fn format(&self, args: &JsValue) -> Result<String, JsValue> {
+        if args.is_undefined() || args.is_null() {
+            return Ok(self.context.format(&self.message, None));
+        }
+        let text = String::from(JSON::stringify(args)?);
+        let json = serde_json::from_str(&text).map_err(|e| error(&e.to_string()))?;
+        let args = json_args(&json, &JsonArgsOptions::default())
+            .map_err(|e| error(&e.to_string()))?;
+        Ok(args.with_args(|args| self.context.format(&self.message, args)))
+    }
+}
+
+/// A JavaScript `Error` with `message`.
+fn error(message: &str) -> JsValue {
+    Error::new(message).into()
+}
@@ -0,0 +1,139 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+
+/// Escape output so that it can be placed within the quotes of a
+/// JSON string.
+///
+/// Quotation marks, backslashes and control characters are escaped.
+/// Optionally, `/` can be escaped as well, which prevents `</script>`
+/// from appearing in JSON embedded within HTML.
+///
+/// Escaping is done per character, so the output is the same no matter
+/// how it is split between calls to `write_str`.
+///
+/// ```
+/// use message_format::{arg, icu, Context};
+/// use message_format::writers::JsonEscapingWriter;
+///
+/// let ctx = Context::default();
+/// let m = icu::parse("Could not open \"{path}\".").unwrap();
+/// let mut writer = JsonEscapingWriter::new(String::new());
+/// ctx.write(&m, &mut writer, Some(&arg("path", "C:\\temp"))).unwrap();
+/// assert_eq!(writer.into_inner(), r#"Could not open \"C:\\temp\"."#);
+/// ```
+#[derive(Debug)]
+pub struct JsonEscapingWriter<W: fmt::Write> {
+    inner: W,
+    escape_slash: bool,
+}
+
+impl<W: fmt::Write> JsonEscapingWriter<W> {
+    /// Construct a `JsonEscapingWriter` that writes to `inner`.
+    pub fn new(inner: W) -> Self {
+        JsonEscapingWriter {
+            inner,
+            escape_slash: false,
+        }
+    }
+
+    /// Set whether `/` should be escaped as `\/`.
+    pub fn escape_slash(&mut self, escape_slash: bool) {
+        self.escape_slash = escape_slash;
+    }
+
+    /// Get a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Unwrap this `JsonEscapingWriter`, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn write_escape(&mut self, ch: char) -> fmt::Result {
+        match ch {
+            '"' => self.inner.write_str("\\\""),
+            '\\' => self.inner.write_str("\\\\"),
+            '/' => self.inner.write_str("\\/"),
+            '\u{8}' => self.inner.write_str("\\b"),
+            '\u{c}' => self.inner.write_str("\\f"),
+            '\n' => self.inner.write_str("\\n"),
+            '\r' => self.inner.write_str("\\r"),
+            '\t' => self.inner.write_str("\\t"),
+            _ => write!(self.inner, "\\u{:04x}", ch as u32),
+        }
+    }
+
+    fn needs_escape(&self, ch: char) -> bool {
+        ch == '"' || ch == '\\' || ch < ' ' || (self.escape_slash && ch == '/')
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for JsonEscapingWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // Write runs of characters that don't need escaping at once.
+        let mut start = 0;
+        for (index, ch) in s.char_indices() {
+            if self.needs_escape(ch) {
+                self.inner.write_str(&s[start..index])?;
+                self.write_escape(ch)?;
+                start = index + ch.len_utf8();
+            }
+        }
+        self.inner.write_str(&s[start..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonEscapingWriter;
+    use icu::parse;
+    use std::fmt::Write;
+    use {arg, Context};
+
+    fn escape(s: &str) -> String {
+        let mut writer = JsonEscapingWriter::new(String::new());
+        writer.write_str(s).unwrap();
+        writer.into_inner()
+    }
+
+    #[test]
+    fn escapes_json_characters() {
+        assert_eq!(escape("plain"), "plain");
+        assert_eq!(escape("\"quoted\" \\ back"), "\\\"quoted\\\" \\\\ back");
+        assert_eq!(escape("a\nb\tc\r\u{8}\u{c}\u{1}"), "a\\nb\\tc\\r\\b\\f\\u0001");
+        assert_eq!(escape("</script>"), "</script>");
+    }
+
+    #[test]
+    fn escapes_slash_when_asked() {
+        let mut writer = JsonEscapingWriter::new(String::new());
+        writer.escape_slash(true);
+        writer.write_str("</script>").unwrap();
+        assert_eq!(writer.into_inner(), "<\\/script>");
+    }
+
+    #[test]
+    fn escapes_across_write_boundaries() {
+        let mut writer = JsonEscapingWriter::new(String::new());
+        for chunk in &["say ", "\"", "hi", "\\", "\"", "\n"] {
+            writer.write_str(chunk).unwrap();
+        }
+        assert_eq!(writer.into_inner(), escape("say \"hi\\\"\n"));
+    }
+
+    #[test]
+    fn escapes_message_parts() {
+        let ctx = Context::default();
+        let m = parse("\"{a}\"{b}\\").unwrap();
+        let mut writer = JsonEscapingWriter::new(String::new());
+        ctx.write(&m, &mut writer, Some(&arg("a", "\\").arg("b", "\""))).unwrap();
+        assert_eq!(writer.into_inner(), "\\\"\\\\\\\"\\\"\\\\");
+    }
+}
@@ -0,0 +1,15 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Output Adapters
+//!
+//! This module provides `fmt::Write` adapters that transform the output
+//! of a message as it is written, so that no second pass over the
+//! formatted text is needed.
+
+mod json_escaping_writer;
+
+pub use self::json_escaping_writer::JsonEscapingWriter;
@@ -0,0 +1,380 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A small XML reader for loading translation files.
+//!
+//! The names of elements and attributes are reduced to their local
+//! part, so that `<x:trans-unit>` and `<trans-unit>` are the same
+//! whatever namespace they are in. Comments, processing instructions
+//! and the document type declaration are skipped, and only the
+//! predefined and character entities are expanded.
+
+use std::error::Error;
+use std::fmt;
+
+use nesting::Nesting;
+
+/// A node within an element.
+#[derive(Clone,Debug,PartialEq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+/// An element, with the local names of it and its attributes.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+    /// The byte offset of the element's start tag.
+    pub offset: usize,
+}
+
+impl Element {
+    /// Get the value of the attribute with the local `name`.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|&(n, _)| n == name).map(|(_, value)| value.as_str())
+    }
+
+    /// The elements within this one.
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match *node {
+            Node::Element(ref element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// Get the first element within this one with the local `name`.
+    #[cfg(feature = "xliff")]
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.elements().find(|element| element.name == name)
+    }
+
+    /// The text within this element and the elements within it.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for node in &self.children {
+            match *node {
+                Node::Element(ref element) => text.push_str(&element.text()),
+                Node::Text(ref t) => text.push_str(t),
+            }
+        }
+        text
+    }
+}
+
+/// An error in an XML document, at a byte `offset`.
+#[derive(Clone,Debug,PartialEq)]
+pub struct XmlError {
+    pub offset: usize,
+    pub message: &'static str,
+}
+
+impl Error for XmlError {}
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{} at offset {}.", self.message, self.offset)
+    }
+}
+
+/// Parse an XML document, returning its root element.
+pub fn parse(source: &str) -> Result<Element, XmlError> {
+    let mut p = Parser { source, pos: 0, depth: 0 };
+    if p.source.starts_with('\u{feff}') {
+        p.bump();
+    }
+    p.get_misc()?;
+    if !p.rest().starts_with('<') {
+        return Err(p.error("Expected an element"));
+    }
+    let root = p.get_element()?;
+    p.get_misc()?;
+    if p.pos < source.len() {
+        return Err(p.error("Unexpected trailing characters"));
+    }
+    Ok(root)
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    pos: usize,
+    /// The number of elements around the position.
+    depth: usize,
+}
+
+impl<'a> Nesting for Parser<'a> {
+    type Error = XmlError;
+
+    fn depth(&mut self) -> &mut usize {
+        &mut self.depth
+    }
+
+    fn too_deep(&self) -> XmlError {
+        self.error("Too deeply nested")
+    }
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.source[self.pos..]
+    }
+
+    fn ch(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) {
+        if let Some(ch) = self.ch() {
+            self.pos += ch.len_utf8();
+        }
+    }
+
+    fn error(&self, message: &'static str) -> XmlError {
+        XmlError {
+            offset: self.pos,
+            message,
+        }
+    }
+
+    fn get_ws(&mut self) {
+        while let Some(' ') | Some('\t') | Some('\n') | Some('\r') = self.ch() {
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, ch: char) -> Result<(), XmlError> {
+        if self.ch() == Some(ch) {
+            self.bump();
+            Ok(())
+        } else {
+            Err(self.error("Unexpected character"))
+        }
+    }
+
+    /// Skip past the next `end`.
+    fn skip_past(&mut self, end: &str) -> Result<(), XmlError> {
+        match self.rest().find(end) {
+            Some(i) => {
+                self.pos += i + end.len();
+                Ok(())
+            }
+            None => Err(self.error("Unterminated markup")),
+        }
+    }
+
+    /// Skip whitespace, comments, processing instructions and the
+    /// document type declaration.
+    fn get_misc(&mut self) -> Result<(), XmlError> {
+        loop {
+            self.get_ws();
+            let rest = self.rest();
+            if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<!DOCTYPE") {
+                // The internal subset may have `>` within it.
+                if rest.find(['[', '>']).is_some_and(|i| rest[i..].starts_with('[')) {
+                    self.skip_past("]")?;
+                }
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn get_name(&mut self) -> Result<&'a str, XmlError> {
+        let start = self.pos;
+        while self.ch().is_some_and(|ch| {
+            !ch.is_whitespace() && !matches!(ch, '<' | '>' | '/' | '=' | '"' | '\'' | '&')
+        }) {
+            self.bump();
+        }
+        if start == self.pos {
+            return Err(self.error("Expected a name"));
+        }
+        Ok(&self.source[start..self.pos])
+    }
+
+    fn get_element(&mut self) -> Result<Element, XmlError> {
+        self.nested(Self::get_element_contents)
+    }
+
+    fn get_element_contents(&mut self) -> Result<Element, XmlError> {
+        let offset = self.pos;
+        self.expect('<')?;
+        let name = self.get_name()?;
+        let mut element = Element {
+            name: local(name).to_string(),
+            attributes: vec![],
+            children: vec![],
+            offset,
+        };
+        loop {
+            self.get_ws();
+            if self.rest().starts_with("/>") {
+                self.pos += "/>".len();
+                return Ok(element);
+            }
+            if self.ch() == Some('>') {
+                self.bump();
+                break;
+            }
+            let attribute = local(self.get_name()?).to_string();
+            self.get_ws();
+            self.expect('=')?;
+            self.get_ws();
+            let value = self.get_attribute_value()?;
+            element.attributes.push((attribute, value));
+        }
+        self.get_content(&mut element.children)?;
+        self.pos += "</".len();
+        if self.get_name()? != name {
+            return Err(self.error("Mismatched end tag"));
+        }
+        self.get_ws();
+        self.expect('>')?;
+        Ok(element)
+    }
+
+    fn get_attribute_value(&mut self) -> Result<String, XmlError> {
+        let quote = match self.ch() {
+            Some(quote @ '"') | Some(quote @ '\'') => quote,
+            _ => return Err(self.error("Expected a quoted value")),
+        };
+        self.bump();
+        let mut value = String::new();
+        loop {
+            match self.ch() {
+                None => return Err(self.error("Unterminated value")),
+                Some('<') => return Err(self.error("Unexpected '<' in a value")),
+                Some(ch) if ch == quote => {
+                    self.bump();
+                    return Ok(value);
+                }
+                Some(_) => self.get_char(&mut value)?,
+            }
+        }
+    }
+
+    /// Parse the content of an element up through the start of its end
+    /// tag.
+    fn get_content(&mut self, children: &mut Vec<Node>) -> Result<(), XmlError> {
+        let mut text = String::new();
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err(self.error("Unclosed element"));
+            } else if rest.starts_with("</") {
+                break;
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<![CDATA[") {
+                self.pos += "<![CDATA[".len();
+                let start = self.pos;
+                self.skip_past("]]>")?;
+                push_normalized(&self.source[start..self.pos - "]]>".len()], &mut text);
+            } else if rest.starts_with('<') {
+                if !text.is_empty() {
+                    children.push(Node::Text(text.split_off(0)));
+                }
+                children.push(Node::Element(self.get_element()?));
+            } else {
+                self.get_char(&mut text)?;
+            }
+        }
+        if !text.is_empty() {
+            children.push(Node::Text(text));
+        }
+        Ok(())
+    }
+
+    /// Add the next character of text or attribute value to `text`,
+    /// expanding an entity and normalizing line breaks to `\n`.
+    fn get_char(&mut self, text: &mut String) -> Result<(), XmlError> {
+        let rest = self.rest();
+        if rest.starts_with('&') {
+            let end = rest.find(';').ok_or_else(|| self.error("Unterminated entity"))?;
+            let ch = match &rest[1..end] {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                entity => {
+                    let code = match entity.strip_prefix("#x") {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                        None => entity.strip_prefix('#').and_then(|dec| dec.parse().ok()),
+                    };
+                    code.and_then(char::from_u32)
+                }
+            };
+            text.push(ch.ok_or_else(|| self.error("Unknown entity"))?);
+            self.pos += end + 1;
+        } else if rest.starts_with("\r\n") {
+            text.push('\n');
+            self.pos += "\r\n".len();
+        } else if rest.starts_with('\r') {
+            text.push('\n');
+            self.bump();
+        } else if let Some(ch) = self.ch() {
+            text.push(ch);
+            self.bump();
+        }
+        Ok(())
+    }
+}
+
+/// The part of a name after its namespace prefix.
+fn local(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn push_normalized(text: &str, output: &mut String) {
+    output.push_str(&text.replace("\r\n", "\n").replace('\r', "\n"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Node};
+
+    #[test]
+    fn elements_and_text() {
+        let source = "<?xml version=\"1.0\"?>\n\
+                      <!DOCTYPE a [<!ENTITY b \"c\">]>\n\
+                      <!-- A comment -->\n\
+                      <x:a xmlns:x=\"urn:a\" x:b='1 &amp; 2'>\r\n\
+                      Tom &amp; &quot;Jerry&quot;&#13;<b/><![CDATA[<i>]]><?pi?>&#x1F600;\
+                      </x:a>\n";
+        let root = parse(source).unwrap();
+        assert_eq!(root.name, "a");
+        assert_eq!(root.attribute("x"), Some("urn:a"));
+        assert_eq!(root.attribute("b"), Some("1 & 2"));
+        assert_eq!(root.elements().next().unwrap().offset, source.find("<b/>").unwrap());
+        assert_eq!(root.children[0], Node::Text("\nTom & \"Jerry\"\r".to_string()));
+        assert_eq!(root.text(), "\nTom & \"Jerry\"\r<i>😀");
+    }
+
+    #[test]
+    fn errors() {
+        let cases = [("", "Expected an element", 0),
+                     ("<a>", "Unclosed element", 3),
+                     ("<a></b>", "Mismatched end tag", 6),
+                     ("<a b=c/>", "Expected a quoted value", 5),
+                     ("<a>&nbsp;</a>", "Unknown entity", 3),
+                     ("<a/><b/>", "Unexpected trailing characters", 4)];
+        for &(source, message, offset) in &cases {
+            let error = parse(source).unwrap_err();
+            assert_eq!((error.message, error.offset), (message, offset), "{}", source);
+        }
+        assert_eq!(parse(&"<a>".repeat(300)).unwrap_err().message, "Too deeply nested");
+    }
+}
@@ -0,0 +1,794 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A small YAML reader for loading locale files.
+//!
+//! This reads the block mappings and sequences, flow collections, and
+//! plain, quoted and block scalars that locale files are written with.
+//! Every scalar is read as a string, other than `~` and `null`, and
+//! tags are kept for the reader to check. Anchors are skipped, and
+//! aliases are kept, without being resolved, for the reader to report.
+//! More than one document isn't supported.
+
+use std::error::Error;
+use std::fmt;
+
+use nesting::Nesting;
+
+/// A value in a YAML document. The entries of a mapping are kept in
+/// order.
+#[derive(Clone,Debug,PartialEq)]
+pub enum Value {
+    Null,
+    String(String),
+    Sequence(Vec<Node>),
+    Mapping(Vec<(String, Node)>),
+    /// An alias, like `*defaults`, of the node with the anchor. Has the
+    /// name of the anchor.
+    Alias(String),
+}
+
+/// A value with its tag, like `!ruby/object`, and the line it starts
+/// on, counting from 1.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Node {
+    pub value: Value,
+    pub tag: Option<String>,
+    pub line: usize,
+}
+
+/// An error in a YAML document, on a line counting from 1.
+#[derive(Clone,Debug,PartialEq)]
+pub struct YamlError {
+    pub line: usize,
+    pub message: &'static str,
+}
+
+impl Error for YamlError {}
+
+impl fmt::Display for YamlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{} on line {}.", self.message, self.line)
+    }
+}
+
+/// Parse a YAML document, returning its root node.
+pub fn parse(source: &str) -> Result<Node, YamlError> {
+    let source = source.strip_prefix('\u{feff}').unwrap_or(source);
+    let mut lines = vec![];
+    for (index, raw) in source.lines().enumerate() {
+        let text = raw.trim_start_matches(' ');
+        if text.starts_with('\t') {
+            return Err(YamlError { line: index + 1, message: "Tabs can't indent" });
+        }
+        lines.push(Line {
+            number: index + 1,
+            indent: raw.len() - text.len(),
+            text: text.trim_end(),
+            raw,
+        });
+    }
+    let mut flow_text = String::with_capacity(source.len());
+    let mut offsets = vec![];
+    for line in &lines {
+        if !offsets.is_empty() {
+            flow_text.push('\n');
+        }
+        offsets.push(flow_text.len());
+        flow_text.push_str(line.raw.trim());
+    }
+    let mut p = Parser { lines, pos: 0, flow_text, offsets, depth: 0 };
+    p.skip_blank();
+    if p.pos < p.lines.len() && p.lines[p.pos].text.starts_with("%") {
+        p.pos += 1;
+        p.skip_blank();
+    }
+    if p.pos < p.lines.len() && is_marker(p.lines[p.pos].text, "---") {
+        p.pos += 1;
+    }
+    let line = p.peek().map_or(1, |line| line.number);
+    let root = p.get_node(0)?.unwrap_or(Node { value: Value::Null, tag: None, line });
+    if p.peek().is_some_and(|line| is_marker(line.text, "...")) {
+        p.pos += 1;
+    }
+    match p.peek() {
+        Some(line) => Err(YamlError { line: line.number, message: "Unexpected content" }),
+        None => Ok(root),
+    }
+}
+
+/// Whether `text` is a document marker, like `---`.
+fn is_marker(text: &str, marker: &str) -> bool {
+    text.strip_prefix(marker).is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+}
+
+/// A line of the document, with the indentation of its text.
+#[derive(Clone,Copy,Debug)]
+struct Line<'a> {
+    number: usize,
+    indent: usize,
+    /// The text after the indentation, without trailing whitespace.
+    text: &'a str,
+    raw: &'a str,
+}
+
+impl<'a> Line<'a> {
+    fn is_blank(&self) -> bool {
+        self.text.is_empty() || self.text.starts_with('#')
+    }
+}
+
+struct Parser<'a> {
+    lines: Vec<Line<'a>>,
+    pos: usize,
+    /// The text of the lines without indentation, joined by line breaks,
+    /// to read flow values that span lines from.
+    flow_text: String,
+    /// The offset of each line in `flow_text`.
+    offsets: Vec<usize>,
+    /// The number of block collections that are being parsed.
+    depth: usize,
+}
+
+impl<'a> Nesting for Parser<'a> {
+    type Error = YamlError;
+
+    fn depth(&mut self) -> &mut usize {
+        &mut self.depth
+    }
+
+    /// The error is on the line of the node, which is the current one.
+    fn too_deep(&self) -> YamlError {
+        YamlError {
+            line: self.lines[self.pos].number,
+            message: "Too deeply nested",
+        }
+    }
+}
+
+impl<'a> Parser<'a> {
+    fn skip_blank(&mut self) {
+        while self.pos < self.lines.len() && self.lines[self.pos].is_blank() {
+            self.pos += 1;
+        }
+    }
+
+    /// The next line with content.
+    fn peek(&mut self) -> Option<Line<'a>> {
+        self.skip_blank();
+        self.lines.get(self.pos).copied()
+    }
+
+    /// Parse the block node on the next line with content, if it is
+    /// indented by at least `indent`.
+    fn get_node(&mut self, indent: usize) -> Result<Option<Node>, YamlError> {
+        let line = match self.peek() {
+            Some(line) if line.indent >= indent => line,
+            _ => return Ok(None),
+        };
+        self.nested(|p| p.get_node_contents(line))
+    }
+
+    /// Parse the block node that starts on `line`.
+    fn get_node_contents(&mut self, line: Line<'a>) -> Result<Option<Node>, YamlError> {
+        if is_item(line.text) {
+            self.get_sequence(line.indent).map(Some)
+        } else if split_key(line.text, line.number)?.is_some() {
+            self.get_mapping(line.indent).map(Some)
+        } else {
+            self.get_value(line, line.text, line.indent).map(Some)
+        }
+    }
+
+    fn get_mapping(&mut self, indent: usize) -> Result<Node, YamlError> {
+        let mut entries = vec![];
+        let first = self.lines[self.pos].number;
+        while let Some(line) = self.peek() {
+            if line.indent < indent || line.indent == indent && is_item(line.text) {
+                break;
+            }
+            if line.indent > indent {
+                return Err(YamlError { line: line.number, message: "Unexpected indentation" });
+            }
+            let (key, rest) = match split_key(line.text, line.number)? {
+                Some(entry) => entry,
+                None => return Err(YamlError { line: line.number, message: "Expected a key" }),
+            };
+            let mut value = self.get_value(line, rest, indent)?;
+            // A sequence may be indented as much as its key.
+            if value.value == Value::Null && value.tag.is_none() && rest.trim().is_empty() {
+                if let Some(next) = self.peek().filter(|next| next.indent == indent) {
+                    if is_item(next.text) {
+                        value = self.get_sequence(indent)?;
+                    }
+                }
+            }
+            entries.push((key, value));
+        }
+        Ok(Node { value: Value::Mapping(entries), tag: None, line: first })
+    }
+
+    fn get_sequence(&mut self, indent: usize) -> Result<Node, YamlError> {
+        let mut items = vec![];
+        let first = self.lines[self.pos].number;
+        while let Some(line) = self.peek() {
+            if line.indent != indent || !is_item(line.text) {
+                if line.indent > indent {
+                    return Err(YamlError { line: line.number, message: "Unexpected indentation" });
+                }
+                break;
+            }
+            let content = line.text[1..].trim_start_matches(' ');
+            let column = indent + line.text.len() - content.len();
+            if !content.is_empty() &&
+               (is_item(content) || split_key(content, line.number)?.is_some()) {
+                // Read the rest of the item as a block that starts on this
+                // line.
+                self.lines[self.pos].indent = column;
+                self.lines[self.pos].text = content;
+                items.push(self.get_node(column)?.expect("a node on the line"));
+            } else {
+                items.push(self.get_value(line, content, indent)?);
+            }
+        }
+        Ok(Node { value: Value::Sequence(items), tag: None, line: first })
+    }
+
+    /// Parse the value in the `rest` of `line`, after a key or `-`, and
+    /// the lines after it that are indented by more than `indent`.
+    fn get_value(&mut self, line: Line<'a>, rest: &'a str, indent: usize)
+                 -> Result<Node, YamlError> {
+        let mut rest = rest.trim();
+        let mut tag = None;
+        // The tag and the anchor may be in either order.
+        while rest.starts_with(['!', '&']) {
+            let end = rest.find(' ').unwrap_or(rest.len());
+            if rest.starts_with('!') {
+                tag = Some(rest[..end].to_string());
+            }
+            rest = rest[end..].trim_start();
+        }
+        let node = |value| Node { value, tag: tag.clone(), line: line.number };
+        if let Some(alias) = rest.strip_prefix('*') {
+            let (name, trailing) = alias.split_at(alias.find(' ').unwrap_or(alias.len()));
+            if !strip_comment(trailing.trim_start()).is_empty() {
+                return Err(YamlError {
+                    line: line.number,
+                    message: "Unexpected characters after a value",
+                });
+            }
+            self.pos += 1;
+            return Ok(node(Value::Alias(name.to_string())));
+        }
+        if rest.is_empty() || rest.starts_with('#') {
+            self.pos += 1;
+            return Ok(match self.get_node(indent + 1)? {
+                Some(child) => Node { tag: tag.clone(), ..child },
+                None => node(Value::Null),
+            });
+        }
+        if rest.starts_with('|') || rest.starts_with('>') {
+            let text = self.get_block_scalar(line, rest, indent)?;
+            return Ok(node(Value::String(text)));
+        }
+        if rest.starts_with(['"', '\'', '[', '{']) {
+            // A flow value may continue on the lines after this one, so it
+            // is read from the text of all of the lines.
+            let start = self.offsets[self.pos] + line.raw.trim().len() - rest.len();
+            let mut flow = Flow {
+                source: &self.flow_text,
+                pos: start,
+                line: line.number,
+                depth: 0,
+            };
+            let value = flow.get_value()?;
+            let last = self.pos + flow.line - line.number;
+            let end = self.offsets[last] + self.lines[last].raw.trim().len();
+            let trailing = self.flow_text[flow.pos..end].trim();
+            if !trailing.is_empty() && !trailing.starts_with('#') {
+                return Err(YamlError {
+                    line: flow.line,
+                    message: "Unexpected characters after a value",
+                });
+            }
+            self.pos = last + 1;
+            return Ok(node(value));
+        }
+        // A plain scalar may continue on more indented lines.
+        let mut text = strip_comment(rest).to_string();
+        self.pos += 1;
+        while let Some(next) = self.peek().filter(|next| next.indent > indent) {
+            text.push(' ');
+            text.push_str(strip_comment(next.text));
+            self.pos += 1;
+        }
+        Ok(node(plain(text)))
+    }
+
+    /// Parse a literal or folded block scalar with the `header`, like
+    /// `|-`, whose lines are indented by more than `indent`.
+    fn get_block_scalar(&mut self,
+                        line: Line<'a>,
+                        header: &str,
+                        indent: usize)
+                        -> Result<String, YamlError> {
+        let header = strip_comment(header);
+        let literal = header.starts_with('|');
+        let mut chomp = None;
+        let mut explicit = None;
+        for ch in header[1..].chars() {
+            match ch {
+                '-' | '+' if chomp.is_none() => chomp = Some(ch),
+                '1'..='9' if explicit.is_none() => explicit = ch.to_digit(10),
+                _ => {
+                    return Err(YamlError {
+                        line: line.number,
+                        message: "Invalid block scalar header",
+                    })
+                }
+            }
+        }
+        self.pos += 1;
+        let start = self.pos;
+        while self.pos < self.lines.len() {
+            let next = self.lines[self.pos];
+            if !next.text.is_empty() && next.indent <= indent {
+                break;
+            }
+            self.pos += 1;
+        }
+        let lines = &self.lines[start..self.pos];
+        let content_indent = match explicit {
+            Some(digits) => indent + digits as usize,
+            None => lines.iter().find(|l| !l.text.is_empty()).map_or(0, |l| l.indent),
+        };
+        let texts = lines.iter()
+            .map(|l| l.raw.get(content_indent..).unwrap_or("").trim_end_matches('\r'))
+            .collect::<Vec<_>>();
+        let trailing = texts.iter().rev().take_while(|text| text.trim().is_empty()).count();
+        let body = &texts[..texts.len() - trailing];
+        let mut text = String::new();
+        if literal {
+            text = body.join("\n");
+        } else {
+            // Lines are folded into spaces, and empty lines are kept as
+            // line breaks, except around more indented lines.
+            let mut previous: Option<&str> = None;
+            for &body_line in body {
+                match previous {
+                    Some(p) if !p.is_empty() && !body_line.is_empty() &&
+                               !p.starts_with(' ') && !body_line.starts_with(' ') => text.push(' '),
+                    Some(p) if !p.is_empty() || body_line.starts_with(' ') => text.push('\n'),
+                    Some(_) if body_line.is_empty() => text.push('\n'),
+                    _ => {}
+                }
+                text.push_str(body_line);
+                previous = Some(body_line);
+            }
+        }
+        let breaks = match chomp {
+            Some('-') => 0,
+            Some(_) => trailing + usize::from(!body.is_empty()),
+            None => usize::from(!body.is_empty()),
+        };
+        text.push_str(&"\n".repeat(breaks));
+        Ok(text)
+    }
+}
+
+/// Whether `text` is an item of a block sequence, like `- a`.
+fn is_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// Split a mapping entry, like `key: value`, into its key and the rest
+/// of the line, or `None` if the text isn't a mapping entry.
+fn split_key(text: &str, line: usize) -> Result<Option<(String, &str)>, YamlError> {
+    let (key, rest) = if text.starts_with(['"', '\'']) {
+        let mut flow = Flow { source: text, pos: 0, line, depth: 0 };
+        let key = match flow.get_value()? {
+            Value::String(key) => key,
+            _ => return Ok(None),
+        };
+        (key, text[flow.pos..].trim_start_matches(' '))
+    } else if text.starts_with(['[', '{', '|', '>', '#', '!', '&', '*', '%', '@', '`']) {
+        return Ok(None);
+    } else {
+        let end = match text.find(": ") {
+            Some(end) => end,
+            None if text.ends_with(':') => text.len() - 1,
+            None => return Ok(None),
+        };
+        (text[..end].trim_end().to_string(), &text[end..])
+    };
+    match rest.strip_prefix(':') {
+        Some(rest) if rest.is_empty() || rest.starts_with(' ') => Ok(Some((key, rest))),
+        _ => Ok(None),
+    }
+}
+
+/// Remove a comment from the end of a plain scalar.
+fn strip_comment(text: &str) -> &str {
+    match text.find(" #") {
+        Some(end) => text[..end].trim_end(),
+        None if text.starts_with('#') => "",
+        None => text,
+    }
+}
+
+/// The value of a plain scalar.
+fn plain(text: String) -> Value {
+    match &*text {
+        "" | "~" | "null" | "Null" | "NULL" => Value::Null,
+        _ => Value::String(text),
+    }
+}
+
+/// A reader of flow values, like `"text"` and `[a, b]`, which may span
+/// lines.
+struct Flow<'a> {
+    source: &'a str,
+    pos: usize,
+    /// The line of the current position.
+    line: usize,
+    /// The number of flow collections that are being parsed.
+    depth: usize,
+}
+
+impl<'a> Nesting for Flow<'a> {
+    type Error = YamlError;
+
+    fn depth(&mut self) -> &mut usize {
+        &mut self.depth
+    }
+
+    fn too_deep(&self) -> YamlError {
+        self.error("Too deeply nested")
+    }
+}
+
+impl<'a> Flow<'a> {
+    fn error(&self, message: &'static str) -> YamlError {
+        YamlError {
+            line: self.line,
+            message,
+        }
+    }
+
+    fn ch(&self) -> Option<char> {
+        self.source[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) {
+        if let Some(ch) = self.ch() {
+            self.pos += ch.len_utf8();
+            if ch == '\n' {
+                self.line += 1;
+            }
+        }
+    }
+
+    fn get_ws(&mut self) {
+        while let Some(' ') | Some('\n') = self.ch() {
+            self.bump();
+        }
+    }
+
+    fn get_value(&mut self) -> Result<Value, YamlError> {
+        self.get_ws();
+        if self.ch() == Some('&') {
+            self.get_anchor();
+            self.get_ws();
+        }
+        match self.ch() {
+            Some('"') => self.get_quoted('"').map(Value::String),
+            Some('\'') => self.get_quoted('\'').map(Value::String),
+            Some('[') => self.nested(|flow| flow.get_collection(']')).map(Value::Sequence),
+            Some('{') => {
+                let entries = self.nested(|flow| flow.get_collection('}'))?;
+                let entries = entries.into_iter().map(|node| match node.value {
+                    Value::Mapping(mut entries) => entries.pop().expect("one entry"),
+                    _ => unreachable!("flow mappings have entries"),
+                });
+                Ok(Value::Mapping(entries.collect()))
+            }
+            Some('*') => Ok(Value::Alias(self.get_anchor().to_string())),
+            Some('&') => Err(self.error("More than one anchor")),
+            Some(_) => {
+                let start = self.pos;
+                while let Some(ch) = self.ch() {
+                    if ch == ',' || ch == ']' || ch == '}' || ch == '\n' ||
+                       ch == ':' && self.source[self.pos + 1..].starts_with([' ', '\n']) {
+                        break;
+                    }
+                    self.bump();
+                }
+                Ok(plain(self.source[start..self.pos].trim().to_string()))
+            }
+            None => Err(self.error("Unexpected end")),
+        }
+    }
+
+    /// The name of an anchor, like `&defaults`, or of an alias, like
+    /// `*defaults`.
+    fn get_anchor(&mut self) -> &'a str {
+        self.bump();
+        let start = self.pos;
+        while self.ch().is_some_and(|ch| !" \n,[]{}".contains(ch)) {
+            self.bump();
+        }
+        &self.source[start..self.pos]
+    }
+
+    /// Parse a flow sequence or mapping up to its `close` character.
+    /// Each entry of a mapping is returned as a mapping of its own.
+    fn get_collection(&mut self, close: char) -> Result<Vec<Node>, YamlError> {
+        self.bump();
+        let mut nodes = vec![];
+        loop {
+            self.get_ws();
+            if self.ch() == Some(close) {
+                self.bump();
+                return Ok(nodes);
+            }
+            let line = self.line;
+            let mut value = self.get_value()?;
+            if close == '}' {
+                self.get_ws();
+                if self.ch() != Some(':') {
+                    return Err(self.error("Expected ':'"));
+                }
+                self.bump();
+                let key = match value {
+                    Value::String(key) => key,
+                    _ => return Err(self.error("Expected a key")),
+                };
+                let entry = Node { value: self.get_value()?, tag: None, line };
+                value = Value::Mapping(vec![(key, entry)]);
+            }
+            nodes.push(Node { value, tag: None, line });
+            self.get_ws();
+            match self.ch() {
+                Some(',') => self.bump(),
+                Some(ch) if ch == close => {}
+                _ => return Err(self.error("Expected ',' or the end of a collection")),
+            }
+        }
+    }
+
+    /// Parse a quoted scalar. Line breaks within it are folded into
+    /// spaces, and empty lines are kept as line breaks.
+    fn get_quoted(&mut self, quote: char) -> Result<String, YamlError> {
+        self.bump();
+        let mut value = String::new();
+        loop {
+            match self.ch() {
+                None => return Err(self.error("Unterminated string")),
+                Some('\'') if quote == '\'' => {
+                    self.bump();
+                    if self.ch() != Some('\'') {
+                        return Ok(value);
+                    }
+                    value.push('\'');
+                    self.bump();
+                }
+                Some('"') if quote == '"' => {
+                    self.bump();
+                    return Ok(value);
+                }
+                Some('\\') if quote == '"' => {
+                    self.bump();
+                    self.get_escape(&mut value)?;
+                }
+                Some('\n') => {
+                    let len = value.trim_end_matches(' ').len();
+                    value.truncate(len);
+                    let mut breaks = 0;
+                    while let Some(' ') | Some('\n') = self.ch() {
+                        breaks += (self.ch() == Some('\n')) as usize;
+                        self.bump();
+                    }
+                    if breaks == 1 {
+                        value.push(' ');
+                    } else {
+                        value.push_str(&"\n".repeat(breaks - 1));
+                    }
+                }
+                Some(ch) => {
+                    value.push(ch);
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    fn get_escape(&mut self, value: &mut String) -> Result<(), YamlError> {
+        let escaped = match self.ch() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('0') => '\0',
+            Some('a') => '\u{7}',
+            Some('b') => '\u{8}',
+            Some('e') => '\u{1b}',
+            Some('f') => '\u{c}',
+            Some('v') => '\u{b}',
+            Some(' ') => ' ',
+            Some('_') => '\u{a0}',
+            Some(ch @ '"') | Some(ch @ '\\') | Some(ch @ '/') => ch,
+            // An escaped line break joins the lines without a space.
+            Some('\n') => {
+                self.bump();
+                while self.ch() == Some(' ') {
+                    self.bump();
+                }
+                return Ok(());
+            }
+            Some(kind @ 'x') | Some(kind @ 'u') | Some(kind @ 'U') => {
+                let len = match kind {
+                    'x' => 2,
+                    'u' => 4,
+                    _ => 8,
+                };
+                let start = self.pos + 1;
+                let code = self.source.get(start..start + len)
+                    .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| self.error("Invalid escape"))?;
+                self.pos = start + len;
+                value.push(code);
+                return Ok(());
+            }
+            _ => return Err(self.error("Invalid escape")),
+        };
+        value.push(escaped);
+        self.bump();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Node, Value};
+
+    fn string(node: &Node) -> &str {
+        match node.value {
+            Value::String(ref text) => text,
+            ref value => panic!("{:?}", value),
+        }
+    }
+
+    fn get<'a>(node: &'a Node, key: &str) -> &'a Node {
+        match node.value {
+            Value::Mapping(ref entries) => &entries.iter().find(|(k, _)| k == key).unwrap().1,
+            ref value => panic!("{:?}", value),
+        }
+    }
+
+    #[test]
+    fn mappings_and_scalars() {
+        let source = "# Comment\n\
+                      ---\n\
+                      en:\n  \
+                        plain: Hello, world # comment\n  \
+                        \"quoted key\": 'It''s'\n  \
+                        double: \"Tab\\there \\u00e9\n    and more\"\n  \
+                        nested:\n    \
+                          deep: value\n      \
+                            continued\n  \
+                        empty:\n  \
+                        tilde: ~\n  \
+                        literal: |\n    \
+                          Line one\n\n    \
+                          Line two\n  \
+                        folded: >-\n    \
+                          Folded\n    \
+                          text\n\n    \
+                          Next\n  \
+                        tagged: !ruby/object x\n";
+        let root = parse(source).unwrap();
+        let en = get(&root, "en");
+        assert_eq!(en.line, 4);
+        assert_eq!(string(get(en, "plain")), "Hello, world");
+        assert_eq!(string(get(en, "quoted key")), "It's");
+        assert_eq!(string(get(en, "double")), "Tab\there é and more");
+        assert_eq!(string(get(get(en, "nested"), "deep")), "value continued");
+        assert_eq!(get(en, "empty").value, Value::Null);
+        assert_eq!(get(en, "tilde").value, Value::Null);
+        assert_eq!(string(get(en, "literal")), "Line one\n\nLine two\n");
+        assert_eq!(string(get(en, "folded")), "Folded text\nNext");
+        assert_eq!(get(en, "tagged").tag.as_ref().unwrap(), "!ruby/object");
+        assert_eq!(get(en, "tagged").line, 22);
+    }
+
+    #[test]
+    fn sequences() {
+        let source = "days: [Sun, 'Mon',\n  Tue]\n\
+                      list:\n\
+                      - a\n\
+                      - key: value\n  \
+                        other: 1\n\
+                      -\n  - nested\n\
+                      map: {a: 1, b: [2]}\n";
+        let root = parse(source).unwrap();
+        let items = |node: &Node| match node.value {
+            Value::Sequence(ref items) => items.clone(),
+            ref value => panic!("{:?}", value),
+        };
+        let days = items(get(&root, "days"));
+        assert_eq!(days.iter().map(string).collect::<Vec<_>>(), ["Sun", "Mon", "Tue"]);
+        let list = items(get(&root, "list"));
+        assert_eq!(string(&list[0]), "a");
+        assert_eq!(string(get(&list[1], "other")), "1");
+        assert_eq!(string(&items(&list[2])[0]), "nested");
+        assert_eq!(string(get(get(&root, "map"), "a")), "1");
+        assert_eq!(get(&root, "map").line, 9);
+    }
+
+    #[test]
+    fn errors() {
+        let cases = [("a:\n\tb: c", "Tabs can't indent", 2),
+                     ("a:\n    b: c\n  d: e", "Unexpected indentation", 3),
+                     ("a: [&x &y b]", "More than one anchor", 1),
+                     ("a: *x b", "Unexpected characters after a value", 1),
+                     ("a: \"b\n\nc", "Unterminated string", 3),
+                     ("a: [b, c\nd: e", "Expected ',' or the end of a collection", 2),
+                     ("a: \"\\q\"", "Invalid escape", 1),
+                     ("a: 'b' c", "Unexpected characters after a value", 1),
+                     ("a: b\n- c", "Unexpected content", 2)];
+        for &(source, message, line) in &cases {
+            let error = parse(source).unwrap_err();
+            assert_eq!((error.message, error.line), (message, line), "{}", source);
+        }
+        assert_eq!(parse("").unwrap().value, Value::Null);
+    }
+
+    #[test]
+    fn anchors_and_aliases() {
+        let source = "defaults: &defaults\n  a: b\n\
+                      c: !t &x d\n\
+                      e:\n  <<: *defaults # Merged\n\
+                      f: [&y g, *y]\n";
+        let root = parse(source).unwrap();
+        assert_eq!(string(get(get(&root, "defaults"), "a")), "b");
+        assert_eq!((string(get(&root, "c")), get(&root, "c").tag.as_deref()), ("d", Some("!t")));
+        assert_eq!(get(get(&root, "e"), "<<").value, Value::Alias("defaults".to_string()));
+        assert_eq!(get(&root, "f").value,
+                   Value::Sequence(vec![Node { value: Value::String("g".to_string()),
+                                               tag: None,
+                                               line: 6 },
+                                        Node { value: Value::Alias("y".to_string()),
+                                               tag: None,
+                                               line: 6 }]));
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let flow = |depth| format!("a: {}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(parse(&flow(256)).is_ok());
+        let error = parse(&flow(257)).unwrap_err();
+        assert_eq!((error.message, error.line), ("Too deeply nested", 1));
+        assert!(parse(&"a: {b: ".repeat(100_000)).is_err());
+
+        let block = |depth| format!("{}a", "- ".repeat(depth));
+        assert!(parse(&block(256)).is_ok());
+        let error = parse(&block(257)).unwrap_err();
+        assert_eq!((error.message, error.line), ("Too deeply nested", 1));
+    }
+
+    #[test]
+    fn long_flow_values() {
+        let source = format!("a: [{}]
+b: c", "x,\n".repeat(100_000));
+        let root = parse(&source).unwrap();
+        match get(&root, "a").value {
+            Value::Sequence(ref items) => assert_eq!(items.len(), 100_000),
+            ref value => panic!("{:?}", value),
+        }
+        assert_eq!(get(&root, "b").line, 100_002);
+    }
+}
@@ -0,0 +1,177 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Load message bundles from resource files and format their messages.
+
+#![cfg(feature = "std")]
+
+extern crate message_format;
+
+use std::sync::Arc;
+use std::thread;
+
+use message_format::{arg, Args, Context, MessageBundle, Placeholder, PropertiesEncoding};
+
+#[cfg(feature = "serde_json")]
+const MESSAGES_EN: &str = include_str!("fixtures/messages_en.json");
+const PROPERTIES_EN: &[u8] = include_bytes!("fixtures/messages_en.properties");
+const PROPERTIES_FR: &[u8] = include_bytes!("fixtures/messages_fr.properties");
+const APP_DE: &str = include_str!("fixtures/app_de.arb");
+
+fn format(bundle: &MessageBundle, key: &str, args: Option<&Args>) -> String {
+    bundle.format(key, args).unwrap_or_else(|e| panic!("{}", e))
+}
+
+#[test]
+#[cfg(feature = "serde_json")]
+fn json() {
+    let bundle = MessageBundle::from_json_str(Context::default(), MESSAGES_EN)
+        .unwrap_or_else(|errors| panic!("{:?}", errors));
+    assert_eq!(bundle.len(), 20);
+    assert_eq!(bundle.keys().take(4).collect::<Vec<_>>(),
+               ["app.tagline", "app.title", "errors.network", "errors.unknown"]);
+
+    assert_eq!(format(&bundle, "app.title", None), "Photo Library");
+    assert_eq!(format(&bundle, "settings.profile.name_label", None), "Display name");
+    assert_eq!(format(&bundle, "greeting", Some(&arg("name", "Ana"))), "Welcome back, Ana!");
+    assert_eq!(format(&bundle, "last_login", Some(&arg("days", 0))),
+               "You last signed in today.");
+    assert_eq!(format(&bundle, "last_login", Some(&arg("days", 1))),
+               "You last signed in yesterday.");
+    assert_eq!(format(&bundle, "last_login", Some(&arg("days", 4))),
+               "You last signed in 4 days ago.");
+    assert_eq!(format(&bundle, "library.photos", Some(&arg("count", 0))), "No photos");
+    assert_eq!(format(&bundle, "library.photos", Some(&arg("count", 1234))), "1234 photos");
+    assert_eq!(format(&bundle, "library.storage", Some(&arg("used", 1536).arg("total", 10240))),
+               "1,536 of 10,240 MB used");
+    assert_eq!(format(&bundle, "sharing.shared_by",
+                      Some(&arg("gender", "female").arg("name", "Ana"))),
+               "Ana shared her album with you.");
+    assert_eq!(format(&bundle, "sharing.shared_by", Some(&arg("gender", "x").arg("name", "Sam"))),
+               "Sam shared their album with you.");
+    assert_eq!(format(&bundle, "sharing.people", Some(&arg("count", 1).arg("other", "Ana"))),
+               "You and Ana");
+    assert_eq!(format(&bundle, "sharing.people", Some(&arg("count", 2).arg("other", "Ana"))),
+               "You, Ana and 1 more person");
+    assert_eq!(format(&bundle, "sharing.people", Some(&arg("count", 5).arg("other", "Ana"))),
+               "You, Ana and 4 more people");
+    assert_eq!(format(&bundle, "upload.progress", Some(&arg("done", 2).arg("total", 3))),
+               "Uploading 2 of 3 photos…");
+    assert_eq!(format(&bundle, "upload.failed", Some(&arg("count", 1))),
+               "One photo couldn't be uploaded.");
+    assert_eq!(format(&bundle, "upload.rank", Some(&arg("n", 22))),
+               "This is your 22nd upload today.");
+    assert_eq!(format(&bundle, "errors.network", Some(&arg("host", "example.com"))),
+               "Unable to reach example.com. Check your connection.");
+}
+
+#[test]
+fn properties() {
+    let bundle = MessageBundle::from_properties(Context::default(),
+                                                PROPERTIES_EN,
+                                                PropertiesEncoding::Latin1)
+        .unwrap_or_else(|errors| panic!("{:?}", errors));
+    assert_eq!(bundle.len(), 10);
+
+    assert_eq!(format(&bundle, "app.title", None), "Photo Library");
+    assert_eq!(format(&bundle, "app.tagline", None), "All of your photos, in one place.");
+    assert_eq!(format(&bundle, "greeting", Some(&arg("name", "Ana"))), "Welcome back, Ana!");
+    assert_eq!(format(&bundle, "library.empty", None),
+               "Your library is empty. Drag photos here to add them.");
+    // Continuation lines inside plural and select bodies.
+    assert_eq!(format(&bundle, "library.photos", Some(&arg("count", 0))), "No photos");
+    assert_eq!(format(&bundle, "library.photos", Some(&arg("count", 1))), "1 photo");
+    assert_eq!(format(&bundle, "library.photos", Some(&arg("count", 7))), "7 photos");
+    assert_eq!(format(&bundle, "sharing.shared_by",
+                      Some(&arg("gender", "male").arg("name", "Jo"))),
+               "Jo shared his album with you.");
+    // Escaped unicode in the branches of a plural.
+    assert_eq!(format(&bundle, "upload.progress", Some(&arg("done", 1).arg("total", 2))),
+               "Uploading 1 of 2 photos…");
+    assert_eq!(format(&bundle, "settings.currency", Some(&arg("n", 1))), "1 €");
+    assert_eq!(format(&bundle, "settings.currency", Some(&arg("n", 5))),
+               "5 € — or £ on request");
+}
+
+#[test]
+fn properties_encodings() {
+    let fr = MessageBundle::from_properties(Context::default(),
+                                            PROPERTIES_FR,
+                                            PropertiesEncoding::default())
+        .unwrap_or_else(|errors| panic!("{:?}", errors));
+    assert_eq!(format(&fr, "app.title", None), "Photothèque");
+    assert_eq!(format(&fr, "settings.tagline", None), "Vos photos, en un seul endroit.");
+
+    let utf8 = MessageBundle::from_properties(Context::default(),
+                                              "app.title = Photothèque".as_bytes(),
+                                              PropertiesEncoding::Utf8)
+        .unwrap_or_else(|errors| panic!("{:?}", errors));
+    assert_eq!(format(&utf8, "app.title", None), "Photothèque");
+}
+
+#[test]
+fn arb() {
+    let bundle = MessageBundle::from_arb_str(Context::default(), APP_DE)
+        .unwrap_or_else(|errors| panic!("{:?}", errors));
+    assert_eq!(bundle.context().locale().language(), "de");
+    assert_eq!(bundle.keys().collect::<Vec<_>>(),
+               ["appTitle", "photoCount", "sharedBy", "signOut"]);
+    assert_eq!(bundle.attributes().collect::<Vec<_>>(),
+               [("last_modified", "2024-03-01T12:00:00Z"),
+                ("locale", "de"),
+                ("x-generator", "translation-portal")]);
+
+    assert_eq!(format(&bundle, "appTitle", None), "Fotobibliothek");
+    assert_eq!(format(&bundle, "photoCount", Some(&arg("count", 0))), "Keine Fotos");
+    assert_eq!(format(&bundle, "photoCount", Some(&arg("count", 1))), "1 Foto");
+    assert_eq!(format(&bundle, "photoCount", Some(&arg("count", 3))), "3 Fotos");
+    assert_eq!(format(&bundle, "sharedBy", Some(&arg("name", "Ana").arg("gender", "female"))),
+               "Ana hat ihr Album geteilt.");
+
+    let metadata = bundle.metadata("sharedBy").unwrap();
+    assert_eq!(metadata.description.as_ref().unwrap(), "Shown when someone shares an album.");
+    assert_eq!(metadata.placeholders,
+               [Placeholder {
+                    name: "name".to_string(),
+                    type_name: Some("String".to_string()),
+                    description: Some("The name of the person who shared it.".to_string()),
+                    example: Some("Ana".to_string()),
+                },
+                Placeholder {
+                    name: "gender".to_string(),
+                    type_name: Some("String".to_string()),
+                    ..Placeholder::default()
+                }]);
+    assert_eq!(bundle.metadata("photoCount").unwrap().placeholders[0].example.as_ref().unwrap(),
+               "3");
+    assert_eq!(bundle.metadata("signOut"), None);
+}
+
+#[test]
+fn lazy_messages_are_shared_between_threads() {
+    let mut bundle = MessageBundle::new(Context::default());
+    for n in 0..100 {
+        let source = format!("{{count, plural, one {{# item {0}}} other {{# items {0}}}}}", n);
+        bundle.insert_raw(&format!("item{}", n), &source);
+    }
+    let bundle = Arc::new(bundle);
+    let threads = (0..4)
+        .map(|t| {
+            let bundle = bundle.clone();
+            thread::spawn(move || {
+                (0..100)
+                    .map(|n| format(&bundle, &format!("item{}", n), Some(&arg("count", t))))
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect::<Vec<_>>();
+    for (t, thread) in threads.into_iter().enumerate() {
+        let output = thread.join().unwrap();
+        let expected = if t == 1 { "1 item 42".to_string() } else { format!("{} items 42", t) };
+        assert_eq!(output[42], expected);
+    }
+    assert_eq!(bundle.parse_all(), Ok(()));
+}
@@ -0,0 +1,65 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Parse and format every message in a catalog of patterns, checking
+//! the output for each of its sample arguments.
+
+extern crate message_format;
+extern crate serde_json;
+
+use message_format::{icu, AsValue, Args, Context, Locale, Message, Value};
+use serde_json::Value as Json;
+
+const CATALOG: &str = include_str!("fixtures/catalog.json");
+
+/// Convert a JSON argument to a `Value`. Integers are numbers, other
+/// numbers are decimals.
+fn value(json: &Json) -> Value<'_> {
+    match *json {
+        Json::String(ref value) => Value::Str(value),
+        Json::Number(ref value) => match value.as_i64() {
+            Some(value) => Value::Number(value),
+            None => value.as_f64().unwrap().as_formattable(),
+        },
+        _ => panic!("unsupported argument {}", json),
+    }
+}
+
+/// Format a message, chaining `args` onto `prev`.
+fn format(ctx: &Context, message: &Message, args: &[(&str, Value)], prev: Option<&Args>) -> String {
+    match args.split_first() {
+        Some((&(name, value), rest)) => {
+            format(ctx, message, rest, Some(&Args { name, value, prev }))
+        }
+        None => ctx.format(message, prev),
+    }
+}
+
+#[test]
+fn catalog() {
+    let catalog: Json = serde_json::from_str(CATALOG).unwrap();
+    let locale = Locale::new(catalog["locale"].as_str().unwrap()).unwrap();
+    let ctx = Context::new(locale, None);
+    let messages = catalog["messages"].as_object().unwrap();
+    assert!(!messages.is_empty());
+
+    for (id, entry) in messages {
+        let pattern = entry["pattern"].as_str().unwrap();
+        let message = icu::parse(pattern).unwrap_or_else(|e| panic!("{}: {}", id, e));
+        for case in entry["cases"].as_array().unwrap() {
+            let args = case["args"]
+                .as_object()
+                .unwrap()
+                .iter()
+                .map(|(name, json)| (name.as_str(), value(json)))
+                .collect::<Vec<_>>();
+            assert_eq!(format(&ctx, &message, &args, None),
+                       case["expected"].as_str().unwrap(),
+                       "{}",
+                       id);
+        }
+    }
+}
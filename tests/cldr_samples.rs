@@ -0,0 +1,127 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Check the built-in plural rules against every `@integer` and
+//! `@decimal` sample in the CLDR snapshot that they're generated from.
+//!
+//! All of the failures for each locale are reported together, so
+//! that a locale whose rules are wrong can be fixed in one pass.
+
+extern crate message_format;
+extern crate serde_json;
+
+use message_format::{english_cardinal_classifier, english_ordinal_classifier, PluralCategory,
+                     PluralClassifier, PluralOperands};
+use message_format::plural_rules::{cardinal_classifier, ordinal_classifier};
+use message_format::plural_rules::syntax::parse_rule;
+use serde_json::Value as Json;
+
+const PLURALS: &str = include_str!("../cldr-gen/data/plurals.json");
+const ORDINALS: &str = include_str!("../cldr-gen/data/ordinals.json");
+
+/// The samples of each category for a locale, from the
+/// `pluralRule-count-` members of its rules.
+fn samples(locale: &str, rules: &Json) -> Vec<(PluralCategory, Vec<String>)> {
+    let rules = rules.as_object().unwrap_or_else(|| panic!("{}: invalid rules", locale));
+    rules.iter()
+        .map(|(key, rule)| {
+            let category = key.trim_start_matches("pluralRule-count-")
+                .parse()
+                .unwrap_or_else(|e| panic!("{}: {}", locale, e));
+            let rule = parse_rule(rule.as_str().unwrap())
+                .unwrap_or_else(|e| panic!("{}: {}", locale, e));
+            (category, rule.samples().map(str::to_string).collect())
+        })
+        .collect()
+}
+
+/// Classify every sample, describing each one that is wrong.
+fn failures<F>(samples: &[(PluralCategory, Vec<String>)], classify: F) -> Vec<String>
+    where F: Fn(&PluralOperands) -> PluralCategory
+{
+    let mut failures = vec![];
+    for (category, values) in samples {
+        for value in values {
+            let operands = match value.parse::<PluralOperands>() {
+                Ok(operands) => operands,
+                Err(e) => {
+                    failures.push(e.to_string());
+                    continue;
+                }
+            };
+            let actual = classify(&operands);
+            if actual != *category {
+                failures.push(format!("{} is {}, expected {}", value, actual, category));
+            }
+        }
+    }
+    failures
+}
+
+/// Check each locale of a CLDR rules file with `classifier`, panicking
+/// with every failure once all of the locales have been checked.
+fn check<F, C>(json: &str, key: &str, classifier: F)
+    where F: Fn(&str) -> Option<C>,
+          C: PluralClassifier
+{
+    let json: Json = serde_json::from_str(json).unwrap();
+    let locales = json["supplemental"][key].as_object().unwrap();
+    assert!(!locales.is_empty());
+    let mut report = vec![];
+    for (locale, rules) in locales {
+        let failures = match classifier(locale) {
+            Some(classifier) => failures(&samples(locale, rules), |o| classifier.classify(o)),
+            None => vec!["no built-in rules".to_string()],
+        };
+        if !failures.is_empty() {
+            report.push(format!("{} {}:\n    {}", key, locale, failures.join("\n    ")));
+        }
+    }
+    assert!(report.is_empty(), "\n{}\n", report.join("\n"));
+}
+
+#[test]
+fn cardinal_samples() {
+    check(PLURALS, "plurals-type-cardinal", cardinal_classifier);
+}
+
+#[test]
+fn ordinal_samples() {
+    check(ORDINALS, "plurals-type-ordinal", ordinal_classifier);
+}
+
+/// The integer samples of the English rules in a CLDR rules file.
+fn english_integer_samples(json: &str, key: &str) -> Vec<(PluralCategory, Vec<String>)> {
+    let json: Json = serde_json::from_str(json).unwrap();
+    samples("en", &json["supplemental"][key]["en"])
+        .into_iter()
+        .map(|(category, values)| {
+            (category, values.into_iter().filter(|value| !value.contains('.')).collect())
+        })
+        .collect()
+}
+
+#[test]
+fn english_cardinal_integer_samples() {
+    let samples = english_integer_samples(PLURALS, "plurals-type-cardinal");
+    let failures = failures(&samples, |o| english_cardinal_classifier(o.i));
+    assert!(failures.is_empty(), "{:?}", failures);
+}
+
+#[test]
+fn english_ordinal_integer_samples() {
+    let samples = english_integer_samples(ORDINALS, "plurals-type-ordinal");
+    let failures = failures(&samples, |o| english_ordinal_classifier(o.i));
+    assert!(failures.is_empty(), "{:?}", failures);
+}
+
+#[test]
+fn failures_are_all_reported() {
+    let samples = [(PluralCategory::One, vec!["1".to_string(), "2".to_string()]),
+                   (PluralCategory::Other, vec!["3".to_string(), "x".to_string()])];
+    let failures = failures(&samples, |_| PluralCategory::One);
+    assert_eq!(failures, ["3 is one, expected other", "Invalid number 'x'."]);
+}
@@ -0,0 +1,101 @@
+/*
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+/*
+ * Parse and format a plural message through the C API. See src/ffi.rs
+ * for how this is built and run.
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "message_format.h"
+
+static int failures = 0;
+
+static void check(const char *actual, const char *expected) {
+    if (actual == NULL || strcmp(actual, expected) != 0) {
+        fprintf(stderr, "Expected \"%s\", but got \"%s\".\n", expected,
+                actual ? actual : "(null)");
+        failures++;
+    }
+}
+
+static void format_count(const MfMessage *m, int64_t count, const char *expected) {
+    MfArg args[] = {
+        { "name", MF_ARG_STRING, { .string = "Ana" } },
+        { "count", MF_ARG_INT64, { .int64 = count } },
+        { "size", MF_ARG_DOUBLE, { .dbl = 2.5 } },
+    };
+    MfError error;
+    char *out = NULL;
+    if (mf_format(m, args, 3, &out, &error) != MF_OK) {
+        fprintf(stderr, "Formatting failed: %s\n", error.message);
+        mf_string_free(error.message);
+        failures++;
+        return;
+    }
+    check(out, expected);
+    mf_string_free(out);
+}
+
+int main(void) {
+    MfError error;
+    MfMessage *m = mf_parse(
+        "{name} has {count, plural, =0 {no files} one {# file} other {# files}} of {size} MB",
+        &error);
+    if (m == NULL) {
+        fprintf(stderr, "Parsing failed: %s\n", error.message);
+        mf_string_free(error.message);
+        return 1;
+    }
+    format_count(m, 0, "Ana has no files of 2.5 MB");
+    format_count(m, 1, "Ana has 1 file of 2.5 MB");
+    format_count(m, 42, "Ana has 42 files of 2.5 MB");
+
+    char *out = NULL;
+    if (mf_format(m, NULL, 0, &out, &error) != MF_FORMAT || out != NULL) {
+        fprintf(stderr, "A missing argument should fail.\n");
+        failures++;
+    }
+    check(error.message, "Missing argument 'name'.");
+    mf_string_free(error.message);
+    mf_message_free(m);
+
+    if (mf_parse("{count, plural, one {# file}}", &error) != NULL || error.code != MF_PARSE) {
+        fprintf(stderr, "A plural without 'other' should fail.\n");
+        failures++;
+    }
+    mf_string_free(error.message);
+
+    m = mf_parse_with_locale("{count, plural, one {# fichier} other {# fichiers}}", "fr",
+                             &error);
+    if (m == NULL) {
+        fprintf(stderr, "Parsing failed: %s\n", error.message);
+        mf_string_free(error.message);
+        return 1;
+    }
+    MfArg count = { "count", MF_ARG_INT64, { .int64 = 0 } };
+    mf_format(m, &count, 1, &out, &error);
+    check(out, "0 fichier");
+    mf_string_free(out);
+    mf_string_free(error.message);
+    count.kind = 7;
+    if (mf_format(m, &count, 1, &out, &error) != MF_INVALID_ARGUMENT) {
+        fprintf(stderr, "An unknown kind should fail.\n");
+        failures++;
+    }
+    mf_string_free(error.message);
+    mf_message_free(m);
+
+    if (failures == 0) {
+        puts("ok");
+    }
+    return failures == 0 ? EXIT_SUCCESS : EXIT_FAILURE;
+}
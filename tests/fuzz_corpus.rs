@@ -0,0 +1,41 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Run the seed corpus of the `parse` fuzz target, so that the inputs
+//! in it are checked without a nightly compiler.
+
+extern crate message_format;
+
+use std::fs;
+use std::path::Path;
+
+use message_format::{arg, icu, Context, DecimalStr, Value};
+
+#[test]
+fn parse_corpus() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus/parse");
+    let ctx = Context::default();
+    let values = [Value::Number(0),
+                  Value::Number(-7),
+                  Value::Number(i64::MIN),
+                  Value::BigInt(i128::MAX),
+                  Value::Decimal { value: 1.5, fraction_digits: 2 },
+                  Value::DecimalStr(DecimalStr::new("-0.001").unwrap()),
+                  Value::Str("other")];
+    let mut parsed = 0;
+    for entry in fs::read_dir(&dir).unwrap() {
+        let source = fs::read_to_string(entry.unwrap().path()).unwrap();
+        if let Ok(message) = icu::parse(&source) {
+            for &value in &values {
+                ctx.format(&message, Some(&arg("n", value)));
+            }
+            ctx.format(&message, None);
+            message.variables();
+            parsed += 1;
+        }
+    }
+    assert!(parsed >= 10, "only {} inputs parsed", parsed);
+}
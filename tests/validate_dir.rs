@@ -0,0 +1,86 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Validate directories of resource files, as a build script would.
+
+#![cfg(feature = "serde_json")]
+
+extern crate message_format;
+
+use std::path::{Path, PathBuf};
+
+use message_format::build::{validate_dir, IssueKind, ValidateOptions};
+use message_format::{ConsistencyIssue, ConsistencyIssueKind, Locale};
+
+fn fixtures(dir: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/locales").join(dir)
+}
+
+#[test]
+fn valid() {
+    let dir = fixtures("valid");
+    let report = validate_dir(&dir, &ValidateOptions::default())
+        .unwrap_or_else(|issues| panic!("{:?}", issues));
+    assert_eq!(report.files,
+               [dir.join("app_pl.arb"),
+                dir.join("de.json"),
+                dir.join("en.json"),
+                dir.join("messages_fr.properties")]);
+    let locales = report.locales.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(locales, ["pl", "de", "en", "fr"]);
+    assert_eq!(report.messages, 12);
+}
+
+#[test]
+fn source_locale() {
+    let dir = fixtures("valid");
+    let options = ValidateOptions { source_locale: "pl".to_string(), ..Default::default() };
+    assert!(validate_dir(&dir, &options).is_ok());
+    let options = ValidateOptions { source_locale: "ja".to_string(), ..Default::default() };
+    let issues = validate_dir(&dir, &options).unwrap_err();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].path, dir);
+    assert_eq!(issues[0].kind, IssueKind::MissingSource("ja".to_string()));
+}
+
+#[test]
+fn invalid() {
+    let dir = fixtures("invalid");
+    let issues = validate_dir(&dir, &ValidateOptions::default()).unwrap_err();
+    let lines = issues.iter()
+        .map(|issue| {
+            let path = issue.path.strip_prefix(&dir).unwrap();
+            format!("{}: {}", path.display(), issue.kind)
+        })
+        .collect::<Vec<_>>();
+    let pl = Path::new("extra").join("messages_pl.properties");
+    assert_eq!(lines,
+               ["de.json: de 'shared': Variable 'name' is missing.".to_string(),
+                "de.json: de 'shared': Variable 'gender' is missing.".to_string(),
+                "de.json: de 'shared': Variable 'person' isn't in the source.".to_string(),
+                format!("{}: The locale is also that of '{}'.",
+                        Path::new("extra").join("app_de.arb").display(),
+                        dir.join("de.json").display()),
+                format!("{}: Line 2: Invalid message 'photos': At offset 51: Unexpected end \
+                         of message.",
+                        pl.display()),
+                "strings.json: The file name has no locale.".to_string()]);
+    assert_eq!(issues[0].kind,
+               IssueKind::Consistency(ConsistencyIssue {
+                   key: "shared".to_string(),
+                   locale: Locale::new("de").unwrap(),
+                   kind: ConsistencyIssueKind::MissingVariable("name".to_string()),
+               }));
+    // The output is the same every time.
+    assert_eq!(validate_dir(&dir, &ValidateOptions::default()).unwrap_err(), issues);
+}
+
+#[test]
+fn missing_directory() {
+    let issues = validate_dir(fixtures("missing"), &ValidateOptions::default()).unwrap_err();
+    assert_eq!(issues.len(), 1);
+    assert!(matches!(issues[0].kind, IssueKind::Unreadable(_)));
+}
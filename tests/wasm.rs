@@ -0,0 +1,58 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Format messages with the WebAssembly bindings, in node.
+
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+extern crate js_sys;
+extern crate message_format;
+extern crate wasm_bindgen;
+extern crate wasm_bindgen_test;
+
+use js_sys::{Object, Reflect};
+use message_format::wasm::JsMessage;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+fn object(properties: &[(&str, JsValue)]) -> JsValue {
+    let object = Object::new();
+    for (name, value) in properties {
+        Reflect::set(&object, &(*name).into(), value).unwrap();
+    }
+    object.into()
+}
+
+#[wasm_bindgen_test]
+fn formats_plurals() {
+    let m = JsMessage::parse("{user.name} has {count, plural, one {# file} other {# files}}",
+                             None)
+        .unwrap();
+    let user = object(&[("name", "Ana".into())]);
+    assert_eq!(m.format(&object(&[("user", user.clone()), ("count", 1.into())])).unwrap(),
+               "Ana has 1 file");
+    assert_eq!(m.format(&object(&[("user", user), ("count", 1234.into())])).unwrap(),
+               "Ana has 1234 files");
+}
+
+#[wasm_bindgen_test]
+fn converts_arguments() {
+    let m = JsMessage::parse("{admin, select, true {Admin} other {User}} {ratio} {gone}",
+                             Some("de".to_string()))
+        .unwrap();
+    let args = object(&[("admin", true.into()), ("ratio", 0.5.into()), ("gone", JsValue::NULL)]);
+    // Formatting stops at the missing argument.
+    assert_eq!(m.format(&args).unwrap(), "Admin 0.5 ");
+    assert_eq!(m.format(&JsValue::UNDEFINED).unwrap(), "");
+    assert!(m.format(&object(&[("list", js_sys::Array::new().into())])).is_err());
+}
+
+#[wasm_bindgen_test]
+fn parse_errors_have_offsets() {
+    let error = JsMessage::parse("Größe: {size", None).err().unwrap();
+    assert_eq!(Reflect::get(&error, &"offset".into()).unwrap().as_f64(), Some(12.0));
+    assert_eq!(Reflect::get(&error, &"byteOffset".into()).unwrap().as_f64(), Some(14.0));
+}
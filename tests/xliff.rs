@@ -0,0 +1,111 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Export bundles to XLIFF and compare them with golden files, and
+//! import translated XLIFF documents.
+
+#![cfg(all(feature = "xliff", feature = "serde_json"))]
+
+extern crate message_format;
+
+use std::fs;
+use std::path::Path;
+
+use message_format::convert::xliff::{export, import, Diagnostic, DiagnosticKind, ImportOptions,
+                                     XliffOptions};
+use message_format::{arg, Context, Locale, MessageBundle};
+
+const EN: &str = include_str!("fixtures/xliff/en.arb");
+const DE: &str = include_str!("fixtures/xliff/de.json");
+const EN_DE: &str = include_str!("fixtures/xliff/en_de.xlf");
+const VENDOR: &str = include_str!("fixtures/xliff/vendor.xlf");
+
+fn bundles() -> (MessageBundle, MessageBundle) {
+    let en = MessageBundle::from_arb_str(Context::default(), EN)
+        .unwrap_or_else(|errors| panic!("{:?}", errors));
+    let de = MessageBundle::from_json_str(Context::new(Locale::new("de").unwrap(), None), DE)
+        .unwrap_or_else(|errors| panic!("{:?}", errors));
+    (en, de)
+}
+
+/// Compare `xliff` with the golden file `name`. Set `UPDATE_GOLDEN` to
+/// write the file instead.
+fn assert_golden(xliff: &str, name: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/xliff").join(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, xliff).unwrap();
+    }
+    let golden = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+    assert!(xliff == golden, "{} differs:\n{}", path.display(), xliff);
+}
+
+#[test]
+fn source_only() {
+    let (en, _) = bundles();
+    let xliff = export(&en, None, &XliffOptions::default()).unwrap();
+    assert_golden(&xliff, "en.xlf");
+}
+
+#[test]
+fn with_target_and_placeholders() {
+    let (en, de) = bundles();
+    let options = XliffOptions {
+        original: "app.arb".to_string(),
+        placeholders: true,
+    };
+    let xliff = export(&en, Some(&de), &options).unwrap();
+    assert_golden(&xliff, "en_de.xlf");
+}
+
+#[test]
+fn round_trip() {
+    let (en, de) = bundles();
+    let imported = import(Context::default(), EN_DE, &ImportOptions::default()).unwrap();
+    let diagnostics = imported.diagnostics;
+    let missing = |id: &str| Diagnostic {
+        id: id.to_string(),
+        line: Some(EN_DE.lines().position(|line| line.contains(id)).unwrap() + 1),
+        kind: DiagnosticKind::MissingTarget,
+    };
+    assert_eq!(diagnostics, [missing("invitation"), missing("total")]);
+    assert_eq!(imported.bundles.len(), 1);
+    let imported = &imported.bundles[0];
+    assert_eq!(imported.context().locale(), de.context().locale());
+    assert_eq!(imported.keys().collect::<Vec<_>>(), de.keys().collect::<Vec<_>>());
+    for key in de.keys() {
+        assert_eq!(imported.source(key), de.source(key), "{}", key);
+    }
+    assert_eq!(imported.metadata("cart.summary"), en.metadata("cart.summary"));
+}
+
+#[test]
+fn vendor_document() {
+    let imported = import(Context::default(), VENDOR, &ImportOptions::default()).unwrap();
+    let diagnostics = imported.diagnostics.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(diagnostics,
+               ["Line 21: 'farewell': Placeholder '2' isn't in the source.",
+                "Line 25: 'invitation': The target is missing [host] and adds [gast].",
+                "Line 29: 'total': Invalid target: At offset 22: Unexpected end of message.",
+                "Line 45: 'settings.reset': The translation unit has no target."]);
+
+    let locales = imported.bundles.iter().map(|b| b.context().locale().to_string());
+    assert_eq!(locales.collect::<Vec<_>>(), ["de", "fr"]);
+    let de = &imported.bundles[0];
+    assert_eq!(de.keys().collect::<Vec<_>>(),
+               ["cart.summary", "errors.network", "greeting", "invitation"]);
+    assert_eq!(de.format("cart.summary", Some(&arg("count", 3))).unwrap(), "3 Artikel");
+    assert_eq!(de.format("greeting", Some(&arg("name", "Jo"))).unwrap(), "Hallo, Jo!");
+    assert_eq!(de.metadata("cart.summary").unwrap().description.as_ref().unwrap(),
+               "The number of items in a cart.");
+    let fr = &imported.bundles[1];
+    assert_eq!(fr.format("settings.title", None).unwrap(), "Paramètres & confidentialité");
+    assert_eq!(fr.format("settings.save", None).unwrap(), "Save");
+
+    let options = ImportOptions { skip_untranslated: true };
+    let imported = import(Context::default(), VENDOR, &options).unwrap();
+    assert_eq!(imported.diagnostics.len(), 4);
+    assert!(!imported.bundles[1].contains_key("settings.save"));
+}